@@ -3,7 +3,7 @@
 pub(crate) mod test {
   use crate::api::WindowsApi;
   use crate::common::{
-    Monitor, MonitorHandle, MonitorInfo, Monitors, Point, Rect, Sizing, Window, WindowHandle, WindowPlacement,
+    CursorSpeed, Monitor, MonitorHandle, MonitorInfo, Monitors, Point, Rect, Sizing, Window, WindowHandle, WindowPlacement,
   };
   use std::cell::RefCell;
   use std::collections::{HashMap, HashSet};
@@ -16,6 +16,8 @@ pub(crate) mod test {
   #[derive(Default)]
   struct MockState {
     cursor_position: Point,
+    cursor_speed: Option<CursorSpeed>,
+    milliseconds_since_last_input: u32,
     windows: HashMap<WindowHandle, WindowState>,
     monitors: HashMap<MonitorHandle, MonitorState>,
     monitor_windows: HashMap<MonitorHandle, Vec<WindowHandle>>,
@@ -24,6 +26,15 @@ pub(crate) mod test {
     deferred_positioning_failures: HashSet<WindowHandle>,
     deferred_positioning_attempts: HashMap<WindowHandle, usize>,
     window_position_minimum_dimensions: HashMap<WindowHandle, (i32, i32)>,
+    elevated_windows: HashSet<WindowHandle>,
+    ignored_windows: HashSet<WindowHandle>,
+    always_on_top_windows: HashSet<WindowHandle>,
+    border_colours: HashMap<WindowHandle, u32>,
+    window_class_names: HashMap<WindowHandle, String>,
+    window_owners: HashMap<WindowHandle, WindowHandle>,
+    disabled_windows: HashSet<WindowHandle>,
+    window_process_names: HashMap<WindowHandle, String>,
+    is_not_running_as_admin: bool,
   }
 
   struct WindowState {
@@ -121,6 +132,27 @@ pub(crate) mod test {
       });
     }
 
+    /// Removes a monitor from the mock state, simulating it being disconnected.
+    pub fn remove_monitor(monitor_handle: MonitorHandle) {
+      trace!("Mock windows API removes monitor {monitor_handle}");
+      MOCK_STATE.with(|state| {
+        state.borrow_mut().monitors.remove(&monitor_handle);
+      });
+    }
+
+    /// Changes the work area of an existing monitor, simulating e.g. a moved taskbar.
+    pub fn set_monitor_work_area(monitor_handle: MonitorHandle, work_area: Rect) {
+      trace!("Mock windows API sets work area of monitor {monitor_handle} to {work_area}");
+      MOCK_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        let Some(monitor_state) = state.monitors.get_mut(&monitor_handle) else {
+          panic!("Monitor with handle {monitor_handle} not found - did you forget to add it?");
+        };
+        monitor_state.monitor.work_area = work_area;
+        monitor_state.monitor_info = (&monitor_state.monitor).into();
+      });
+    }
+
     /// Adds a link between a window and a monitor, simulating the placement of the window on that monitor.
     /// This does not mean that the window is on the active workspace of the monitor or that it is active.
     pub fn place_window(window_handle: WindowHandle, monitor_handle: MonitorHandle) {
@@ -159,6 +191,12 @@ pub(crate) mod test {
       });
     }
 
+    pub fn set_milliseconds_since_last_input(milliseconds: u32) {
+      MOCK_STATE.with(|state| {
+        state.borrow_mut().milliseconds_since_last_input = milliseconds;
+      });
+    }
+
     /// Configures the minimum dimensions enforced during window positioning.
     pub fn set_window_position_minimum_dimensions(handle: WindowHandle, width: i32, height: i32) {
       MOCK_STATE.with(|state| {
@@ -177,6 +215,55 @@ pub(crate) mod test {
       });
     }
 
+    pub fn set_window_class_name(handle: WindowHandle, class_name: &str) {
+      MOCK_STATE.with(|state| {
+        state.borrow_mut().window_class_names.insert(handle, class_name.to_string());
+      });
+    }
+
+    pub fn set_window_owner(handle: WindowHandle, owner: WindowHandle) {
+      MOCK_STATE.with(|state| {
+        state.borrow_mut().window_owners.insert(handle, owner);
+      });
+    }
+
+    pub fn mark_window_disabled(handle: WindowHandle) {
+      MOCK_STATE.with(|state| {
+        state.borrow_mut().disabled_windows.insert(handle);
+      });
+    }
+
+    pub fn set_window_process_name(handle: WindowHandle, process_name: &str) {
+      MOCK_STATE.with(|state| {
+        state
+          .borrow_mut()
+          .window_process_names
+          .insert(handle, process_name.to_string());
+      });
+    }
+
+    pub fn mark_window_elevated(handle: WindowHandle) {
+      MOCK_STATE.with(|state| {
+        state.borrow_mut().elevated_windows.insert(handle);
+      });
+    }
+
+    pub fn mark_window_always_on_top(handle: WindowHandle) {
+      MOCK_STATE.with(|state| {
+        state.borrow_mut().always_on_top_windows.insert(handle);
+      });
+    }
+
+    pub fn get_window_border_colour(handle: WindowHandle) -> Option<u32> {
+      MOCK_STATE.with(|state| state.borrow().border_colours.get(&handle).copied())
+    }
+
+    pub fn set_running_as_admin(is_running_as_admin: bool) {
+      MOCK_STATE.with(|state| {
+        state.borrow_mut().is_not_running_as_admin = !is_running_as_admin;
+      });
+    }
+
     pub fn clear_position_batches() {
       MOCK_STATE.with(|state| state.borrow_mut().position_batches.clear());
     }
@@ -202,6 +289,12 @@ pub(crate) mod test {
       })
     }
 
+    pub fn remove_window(handle: WindowHandle) {
+      MOCK_STATE.with(|state| {
+        state.borrow_mut().windows.remove(&handle);
+      });
+    }
+
     #[allow(dead_code)]
     pub fn reset() {
       trace!("Mock windows API resets state");
@@ -214,12 +307,17 @@ pub(crate) mod test {
   impl WindowsApi for MockWindowsApi {
     fn is_running_as_admin(&self) -> bool {
       trace!("Mock windows API checks if running as admin");
-      true
+      MOCK_STATE.with(|state| !state.borrow().is_not_running_as_admin)
     }
 
     fn get_foreground_window(&self) -> Option<WindowHandle> {
       trace!("Mock windows API gets foreground window");
-      MOCK_STATE.with(|state| state.borrow_mut().foreground_window)
+      MOCK_STATE.with(|state| {
+        let state = state.borrow();
+        state
+          .foreground_window
+          .filter(|handle| !state.ignored_windows.contains(handle))
+      })
     }
 
     fn set_foreground_window(&self, handle: WindowHandle) {
@@ -287,7 +385,7 @@ pub(crate) mod test {
 
     fn get_window_class_name(&self, handle: &WindowHandle) -> String {
       trace!("Mock windows API gets window class name for {handle}");
-      unimplemented!()
+      MOCK_STATE.with(|state| state.borrow().window_class_names.get(handle).cloned().unwrap_or_default())
     }
 
     fn get_window_rect(&self, handle: WindowHandle) -> Option<Rect> {
@@ -315,6 +413,66 @@ pub(crate) mod test {
       MOCK_STATE.with(|state| state.borrow().windows.get(handle).is_none_or(|window| !window.is_manageable))
     }
 
+    fn get_ignored_foreground_window(&self) -> Option<WindowHandle> {
+      trace!("Mock windows API gets ignored foreground window");
+      MOCK_STATE.with(|state| {
+        let state = state.borrow();
+        state
+          .foreground_window
+          .filter(|handle| state.ignored_windows.contains(handle))
+      })
+    }
+
+    fn is_window_ignored(&self, handle: WindowHandle) -> bool {
+      trace!("Mock windows API checks if window {handle} is ignored");
+      MOCK_STATE.with(|state| state.borrow().ignored_windows.contains(&handle))
+    }
+
+    /// Also marks the window as (un-)manageable, so that it is skipped when windows are enumerated like in the real API.
+    fn set_window_ignored(&self, handle: WindowHandle, is_ignored: bool) {
+      trace!("Mock windows API sets window {handle} to be ignored: {is_ignored}");
+      MOCK_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        if is_ignored {
+          state.ignored_windows.insert(handle);
+        } else {
+          state.ignored_windows.remove(&handle);
+        }
+        if let Some(window) = state.windows.get_mut(&handle) {
+          window.is_manageable = !is_ignored;
+        }
+      });
+    }
+
+    fn get_ignored_windows(&self) -> Vec<WindowHandle> {
+      trace!("Mock windows API gets ignored windows");
+      MOCK_STATE.with(|state| state.borrow().ignored_windows.iter().copied().collect())
+    }
+
+    fn is_window_elevated(&self, handle: WindowHandle) -> bool {
+      trace!("Mock windows API checks if window {handle} is elevated");
+      MOCK_STATE.with(|state| state.borrow().elevated_windows.contains(&handle))
+    }
+
+    fn get_window_process_name(&self, handle: WindowHandle) -> Option<String> {
+      trace!("Mock windows API gets process name of window {handle}");
+      MOCK_STATE.with(|state| state.borrow().window_process_names.get(&handle).cloned())
+    }
+
+    fn get_window_executable_path(&self, handle: WindowHandle) -> Option<String> {
+      trace!("Mock windows API gets executable path of window {handle}");
+      self
+        .get_window_process_name(handle)
+        .map(|process_name| format!("C:\\Program Files\\{process_name}"))
+    }
+
+    fn set_window_border_colour(&self, handle: WindowHandle, colour: u32) {
+      trace!("Mock windows API sets border colour of window {handle} to {colour:#08x}");
+      MOCK_STATE.with(|state| {
+        state.borrow_mut().border_colours.insert(handle, colour);
+      });
+    }
+
     fn is_window_hidden(&self, handle: &WindowHandle) -> bool {
       trace!("Mock windows API checks if window {handle} is hidden");
       MOCK_STATE.with(|state| {
@@ -325,6 +483,54 @@ pub(crate) mod test {
       })
     }
 
+    fn is_window_always_on_top(&self, handle: WindowHandle) -> bool {
+      trace!("Mock windows API checks if window {handle} is always on top");
+      MOCK_STATE.with(|state| state.borrow().always_on_top_windows.contains(&handle))
+    }
+
+    fn set_window_always_on_top(&self, handle: WindowHandle, is_always_on_top: bool) {
+      trace!("Mock windows API sets always on top to [{is_always_on_top}] for window {handle}");
+      MOCK_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        if is_always_on_top {
+          state.always_on_top_windows.insert(handle);
+        } else {
+          state.always_on_top_windows.remove(&handle);
+        }
+      });
+    }
+
+    fn is_window_alive(&self, handle: WindowHandle) -> bool {
+      trace!("Mock windows API checks if window {handle} is alive");
+      MOCK_STATE.with(|state| {
+        state
+          .borrow()
+          .windows
+          .get(&handle)
+          .is_some_and(|window_state| !window_state.is_closed)
+      })
+    }
+
+    fn get_owned_windows(&self, owner: WindowHandle) -> Vec<Window> {
+      trace!("Mock windows API gets windows owned by {owner}");
+      MOCK_STATE.with(|state| {
+        let state = state.borrow();
+        state
+          .window_owners
+          .iter()
+          .filter(|(_, window_owner)| **window_owner == owner)
+          .filter_map(|(handle, _)| state.windows.get(handle))
+          .filter(|ws| !ws.is_hidden && !ws.is_minimised && !ws.is_closed)
+          .map(|ws| ws.window.clone())
+          .collect()
+      })
+    }
+
+    fn is_window_enabled(&self, handle: WindowHandle) -> bool {
+      trace!("Mock windows API checks if window {handle} is enabled");
+      MOCK_STATE.with(|state| !state.borrow().disabled_windows.contains(&handle))
+    }
+
     fn set_window_position(&self, handle: WindowHandle, mut rect: Rect) {
       trace!("Mock windows API sets window position for {handle} to {rect}");
       MOCK_STATE.with(|state| {
@@ -381,7 +587,7 @@ pub(crate) mod test {
       trace!(
         "Mock windows API sets window position for {window_handle} to {rect} with DPI adjustment from {source_monitor_handle} to {target_monitor_handle}"
       );
-      unimplemented!()
+      self.set_window_position(window_handle, rect);
     }
 
     fn do_restore_window(&self, window: &Window, is_minimised: &bool) {
@@ -530,6 +736,11 @@ pub(crate) mod test {
       MOCK_STATE.with(|state| state.borrow().cursor_position)
     }
 
+    fn get_milliseconds_since_last_input(&self) -> u32 {
+      trace!("Mock windows API gets milliseconds since last input");
+      MOCK_STATE.with(|state| state.borrow().milliseconds_since_last_input)
+    }
+
     fn set_cursor_position(&self, target_point: &Point) {
       trace!("Mock windows API sets cursor position to {target_point}");
       MOCK_STATE.with(|state| {
@@ -537,6 +748,18 @@ pub(crate) mod test {
       });
     }
 
+    fn get_cursor_speed(&self) -> Option<CursorSpeed> {
+      trace!("Mock windows API gets cursor speed");
+      MOCK_STATE.with(|state| Some(state.borrow().cursor_speed.unwrap_or(CursorSpeed::new(10, true))))
+    }
+
+    fn set_cursor_speed(&self, cursor_speed: &CursorSpeed) {
+      trace!("Mock windows API sets cursor {cursor_speed}");
+      MOCK_STATE.with(|state| {
+        state.borrow_mut().cursor_speed = Some(*cursor_speed);
+      });
+    }
+
     fn get_all_monitors(&self) -> Monitors {
       trace!("Mock windows API gets all monitors");
       MOCK_STATE.with(|state| {
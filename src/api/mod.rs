@@ -1,9 +1,25 @@
 mod mock_windows_api;
 mod real_windows_api;
+pub mod real_windows_api_for_close_prompt;
+pub mod real_windows_api_for_display_changes;
 pub mod real_windows_api_for_dragging;
+pub mod real_windows_api_for_focus_border;
+pub mod real_windows_api_for_gestures;
+pub mod real_windows_api_for_inspector;
+pub mod real_windows_api_for_place_mode;
+pub mod real_windows_api_for_snap_zone_preview;
+pub mod real_windows_api_for_window_events;
+pub mod real_windows_api_for_window_switcher;
+pub mod real_windows_api_for_workspace_badges;
+pub mod real_windows_api_for_workspace_notes;
+mod window_filters;
 mod windows_api;
 
-pub use real_windows_api::{RealWindowsApi, do_process_windows_messages, get_all_monitors};
+pub use real_windows_api::{
+  RealWindowsApi, ask_question, do_process_windows_messages, generate_random_token, get_all_monitors,
+  get_high_contrast_colours, get_last_input_tick_count, get_small_icon_size_in_px, get_user_locale_name, is_shell_ready,
+  render_text_coverage, set_efficiency_mode, show_message,
+};
 pub use windows_api::WindowsApi;
 
 #[cfg(test)]
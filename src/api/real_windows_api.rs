@@ -1,48 +1,122 @@
 use crate::api::WindowsApi;
-use crate::common::{Monitor, MonitorHandle, MonitorInfo, Monitors, Point, Rect, Window, WindowHandle, WindowPlacement};
+use crate::api::real_windows_api_for_window_switcher::WindowsApiForWindowSwitcher;
+use crate::api::window_filters::{
+  CloakFilter, ExclusionFilter, IgnoreListFilter, SizeFilter, StyleFilter, VisibilityFilter, WindowCandidate, WindowFilter,
+  is_excluded_by_any,
+};
+use crate::common::{
+  CursorSpeed, Monitor, MonitorHandle, MonitorInfo, Monitors, Point, Rect, Window, WindowHandle, WindowPlacement, geometry,
+};
 use crate::configuration_provider::ExclusionSettings;
+use crate::utils::IGNORED_WINDOWS_LOCK;
+use std::collections::HashSet;
 use std::ffi::c_void;
-use std::mem::MaybeUninit;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
 use std::{mem, ptr};
-use windows::Win32::Foundation::{HWND, LPARAM, POINT, RECT, WPARAM};
+use windows::Win32::Foundation::{COLORREF, CloseHandle, HANDLE, HWND, LPARAM, POINT, RECT, STILL_ACTIVE, WPARAM};
+use windows::Win32::Globalization::GetUserDefaultLocaleName;
 use windows::Win32::Graphics::Gdi::{
-  EnumDisplayMonitors, GetMonitorInfoW, HDC, HMONITOR, MONITOR_DEFAULTTONEAREST, MONITORINFO, MONITORINFOEXW,
-  MonitorFromPoint, MonitorFromWindow,
+  ANTIALIASED_QUALITY, BI_RGB, BITMAPINFO, BITMAPINFOHEADER, CLIP_DEFAULT_PRECIS, COLOR_WINDOW, COLOR_WINDOWTEXT,
+  CreateCompatibleDC, CreateDIBSection, CreateFontW, DEFAULT_CHARSET, DIB_RGB_COLORS, DT_BOTTOM, DT_RIGHT, DT_SINGLELINE,
+  DeleteDC, DeleteObject, DrawTextW, EnumDisplayMonitors, FW_BOLD, GdiFlush, GetMonitorInfoW, GetSysColor, HDC, HMONITOR,
+  MONITOR_DEFAULTTONEAREST, MONITORINFO, MONITORINFOEXW, MonitorFromPoint, MonitorFromWindow, OUT_DEFAULT_PRECIS,
+  SelectObject, SetBkMode, SetTextColor, TRANSPARENT,
 };
+use windows::Win32::Security::{GetTokenInformation, TOKEN_ELEVATION, TOKEN_QUERY, TokenElevation};
 use windows::Win32::System::Com::{CLSCTX_ALL, COINIT_APARTMENTTHREADED, CoCreateInstance, CoInitializeEx};
-use windows::Win32::UI::HiDpi::{GetDpiForMonitor, PROCESS_PER_MONITOR_DPI_AWARE, SetProcessDpiAwareness};
+use windows::Win32::System::SystemInformation::GetTickCount;
+use windows::Win32::System::Threading::{
+  BELOW_NORMAL_PRIORITY_CLASS, GetCurrentProcess, GetExitCodeProcess, NORMAL_PRIORITY_CLASS, OpenProcess, OpenProcessToken,
+  PROCESS_NAME_WIN32, PROCESS_POWER_THROTTLING_CURRENT_VERSION, PROCESS_POWER_THROTTLING_EXECUTION_SPEED,
+  PROCESS_POWER_THROTTLING_STATE, PROCESS_QUERY_LIMITED_INFORMATION, ProcessPowerThrottling, QueryFullProcessImageNameW,
+  SetPriorityClass, SetProcessInformation,
+};
+use windows::Win32::UI::Accessibility::{HCF_HIGHCONTRASTON, HIGHCONTRASTW};
+use windows::Win32::UI::HiDpi::{
+  DPI_AWARENESS_PER_MONITOR_AWARE, GetAwarenessFromDpiAwarenessContext, GetDpiForMonitor, GetDpiForSystem,
+  GetSystemMetricsForDpi, GetWindowDpiAwarenessContext, MDT_EFFECTIVE_DPI,
+};
+use windows::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, IsWindowEnabled, LASTINPUTINFO};
 use windows::Win32::UI::Shell::{IVirtualDesktopManager, IsUserAnAdmin};
 use windows::Win32::UI::WindowsAndMessaging::{
-  BeginDeferWindowPos, DeferWindowPos, DispatchMessageA, EndDeferWindowPos, EnumWindows, GetClassNameW, GetCursorPos,
-  GetDesktopWindow, GetForegroundWindow, GetWindowInfo, GetWindowPlacement, GetWindowRect, GetWindowTextW,
-  GetWindowThreadProcessId, HWND_TOP, IsIconic, IsWindowVisible, MINMAXINFO, MSG, PM_REMOVE, PeekMessageA, PostMessageW,
-  SW_HIDE, SW_MAXIMIZE, SW_MINIMIZE, SW_RESTORE, SWP_FRAMECHANGED, SWP_NOACTIVATE, SWP_NOZORDER, SWP_SHOWWINDOW,
-  SendMessageW, SetCursorPos, SetForegroundWindow, SetWindowPlacement, SetWindowPos, ShowWindow, TranslateMessage,
-  WINDOWINFO, WINDOWPLACEMENT, WM_CLOSE, WM_GETMINMAXINFO, WM_PAINT,
+  BeginDeferWindowPos, DeferWindowPos, DispatchMessageA, EndDeferWindowPos, EnumWindows, FindWindowW, GW_OWNER, GWL_EXSTYLE,
+  GetClassNameW, GetCursorPos, GetDesktopWindow, GetForegroundWindow, GetWindow, GetWindowInfo, GetWindowLongW,
+  GetWindowPlacement, GetWindowRect, GetWindowTextW, GetWindowThreadProcessId, HWND_NOTOPMOST, HWND_TOP, HWND_TOPMOST,
+  IDYES, IsIconic, IsWindow, IsWindowVisible, MB_ICONINFORMATION, MB_ICONQUESTION, MB_OK, MB_SETFOREGROUND, MB_YESNO,
+  MINMAXINFO, MSG, MessageBoxW, PM_REMOVE, PeekMessageA, PostMessageW, SM_CXSMICON, SPI_GETHIGHCONTRAST, SPI_GETMOUSE,
+  SPI_GETMOUSESPEED, SPI_SETMOUSE, SPI_SETMOUSESPEED, SW_HIDE, SW_MAXIMIZE, SW_MINIMIZE, SW_RESTORE, SWP_FRAMECHANGED,
+  SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOSIZE, SWP_NOZORDER, SWP_SHOWWINDOW, SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS, SendMessageW,
+  SetCursorPos, SetForegroundWindow, SetWindowPlacement, SetWindowPos, ShowWindow, SystemParametersInfoW, TranslateMessage,
+  WINDOWINFO, WINDOWPLACEMENT, WM_CLOSE, WM_GETMINMAXINFO, WM_PAINT, WS_EX_TOOLWINDOW, WS_EX_TOPMOST,
 };
 use windows::core::BOOL;
 use windows::core::HRESULT;
-
-const TRANSIENT_WINDOW_CLASSES: &[&str] = &[
-  "#32768",
-  "tooltips_class32",
-  "NotifyIconOverflowWindow",
-  "Xaml_WindowedPopupClass",
-  "TopLevelWindowForOverflowXamlIsland",
-];
-
+use windows::core::HSTRING;
+use windows::core::PCWSTR;
+use windows::core::PWSTR;
+
+/// The minimum area in square pixels of a window to be listed, which excludes invisible helper windows.
+const MINIMUM_WINDOW_AREA: i32 = 5;
+
+/// The Windows API used at runtime. Clones share the exclusion settings, which are updated in place whenever the
+/// configuration is reloaded, so that changes to exclusions take effect without a restart.
+///
+/// Which windows are managed or listed is decided by chains of [`WindowFilter`]s that are configured here, so that new
+/// filters can be added and tested independently.
 #[derive(Clone)]
 pub struct RealWindowsApi {
-  ignored_window_titles: Vec<String>,
-  ignored_class_names: Vec<String>,
+  /// Decides which windows are managed at all.
+  managed_window_filters: Arc<[Box<dyn WindowFilter>]>,
+  /// Applied in addition to the managed window filters when enumerating windows.
+  listed_window_filters: Arc<[Box<dyn WindowFilter>]>,
+  /// Applied in addition to the other filters when only windows that can be seen are requested.
+  visible_window_filters: Arc<[Box<dyn WindowFilter>]>,
+  /// The windows that the user has chosen to stop managing at runtime, which the managed window filters exclude.
+  ignored_windows: Arc<RwLock<HashSet<WindowHandle>>>,
 }
 
 impl RealWindowsApi {
-  pub fn new(settings: &ExclusionSettings) -> Self {
+  pub fn new(exclusion_settings: Arc<RwLock<ExclusionSettings>>) -> Self {
+    let is_running_as_admin = unsafe { IsUserAnAdmin().as_bool() };
+    let ignored_windows = Arc::new(RwLock::new(HashSet::new()));
+    let managed_window_filters: Vec<Box<dyn WindowFilter>> = vec![
+      Box::new(IgnoreListFilter::new(ignored_windows.clone())),
+      Box::new(ExclusionFilter::new(
+        exclusion_settings.clone(),
+        std::process::id(),
+        is_running_as_admin,
+        is_process_of_window_elevated,
+      )),
+      Box::new(StyleFilter::new(exclusion_settings)),
+    ];
+    let listed_window_filters: Vec<Box<dyn WindowFilter>> = vec![Box::new(SizeFilter::new(MINIMUM_WINDOW_AREA))];
+    let visible_window_filters: Vec<Box<dyn WindowFilter>> =
+      vec![Box::new(VisibilityFilter), Box::new(CloakFilter::new(is_window_cloaked))];
     Self {
-      ignored_window_titles: settings.window_titles.clone(),
-      ignored_class_names: settings.window_class_names.clone(),
+      managed_window_filters: managed_window_filters.into(),
+      listed_window_filters: listed_window_filters.into(),
+      visible_window_filters: visible_window_filters.into(),
+      ignored_windows,
+    }
+  }
+
+  fn get_all_windows_passing(&self, additional_filters: &[Box<dyn WindowFilter>]) -> Vec<Window> {
+    let mut windows: Vec<Window> = Vec::new();
+    unsafe {
+      if let Err(err) = EnumWindows(Some(enum_windows_callback), LPARAM(&mut windows as *mut _ as isize)) {
+        warn!("Failed to enumerate windows because: {}", err.message());
+      }
     }
+    windows.retain(|window| {
+      let candidate = create_window_candidate(window.handle, window.title.clone(), window.rect);
+      // The managed window filters go last because they may have to open the window's process
+      !is_excluded_by_any(&self.listed_window_filters, &candidate)
+        && !is_excluded_by_any(additional_filters, &candidate)
+        && !is_excluded_by_any(&self.managed_window_filters, &candidate)
+    });
+
+    windows
   }
 }
 
@@ -81,68 +155,32 @@ impl WindowsApi for RealWindowsApi {
   }
 
   fn get_all_windows(&self) -> Vec<Window> {
-    let mut windows: Vec<Window> = Vec::new();
-    unsafe {
-      if let Err(err) = EnumWindows(Some(enum_windows_callback), LPARAM(&mut windows as *mut _ as isize)) {
-        warn!("Failed to enumerate windows because: {}", err.message());
-      }
-    }
-
-    windows.retain(|window| {
-      if self.is_not_a_managed_window(&window.handle) {
-        return false;
-      }
-      if window.rect.area() < 5 {
-        return false;
-      }
-
-      true
-    });
-
-    windows
+    self.get_all_windows_passing(&[])
   }
 
   fn get_all_visible_windows(&self) -> Vec<Window> {
-    let mut windows = self.get_all_windows();
+    let windows = self.get_all_windows_passing(&self.visible_window_filters);
 
     trace!("┌| Found the following windows:");
-    let mut i: usize = 1;
-    windows.retain(|window| {
-      if self.is_not_a_managed_window(&window.handle)
-        || self.is_window_minimised(window.handle)
-        || self.is_window_hidden(&window.handle)
-      {
-        false
-      } else {
-        let window_area = ((window.rect.right - window.rect.left) * (window.rect.bottom - window.rect.top)) / 1000;
-        trace!(
-          "├> {}. {} at ({}, {}) with a size of {window_area}k sq px and title \"{}\"",
-          i,
-          window.handle,
-          window.rect.left,
-          window.rect.top,
-          window.title_trunc()
-        );
-        i += 1;
-
-        true
-      }
-    });
+    for (i, window) in windows.iter().enumerate() {
+      let window_area = ((window.rect.right - window.rect.left) * (window.rect.bottom - window.rect.top)) / 1000;
+      trace!(
+        "├> {}. {} at ({}, {}) with a size of {window_area}k sq px and title \"{}\"",
+        i + 1,
+        window.handle,
+        window.rect.left,
+        window.rect.top,
+        window.title_trunc()
+      );
+    }
     trace!("└─| Identified [{:?}] windows", windows.len());
 
     windows
   }
 
   fn get_all_visible_windows_within_area(&self, rect: Rect) -> Vec<Window> {
-    let mut windows = self.get_all_windows();
-
-    windows.retain(|window| {
-      if self.is_not_a_managed_window(&window.handle) || self.is_window_hidden(&window.handle) {
-        false
-      } else {
-        window.rect.intersects(&rect)
-      }
-    });
+    let mut windows = self.get_all_windows_passing(&self.visible_window_filters);
+    windows.retain(|window| window.rect.intersects(&rect));
 
     windows
   }
@@ -187,43 +225,160 @@ impl WindowsApi for RealWindowsApi {
     }
   }
 
+  fn set_window_border_colour(&self, handle: WindowHandle, colour: u32) {
+    unsafe {
+      // 34 is DWMWA_BORDER_COLOR which is only supported on Windows 11 and expects a COLORREF i.e. 0x00BBGGRR
+      let hr = DwmSetWindowAttribute(
+        handle.as_hwnd(),
+        34u32,
+        &colour as *const u32 as *const c_void,
+        size_of::<u32>() as u32,
+      );
+      if hr.0 != 0 {
+        warn!("Failed to set border colour of {handle}: HRESULT={}", hr.0);
+      }
+    }
+  }
+
   fn is_window_minimised(&self, handle: WindowHandle) -> bool {
     unsafe { IsIconic(handle.as_hwnd()).as_bool() }
   }
 
   fn is_not_a_managed_window(&self, handle: &WindowHandle) -> bool {
+    // None of the managed window filters look at the size of the window
+    let candidate = create_window_candidate(*handle, self.get_window_title(handle), Rect::default());
+
+    is_excluded_by_any(&self.managed_window_filters, &candidate)
+  }
+
+  fn get_ignored_foreground_window(&self) -> Option<WindowHandle> {
+    let handle = WindowHandle::from(unsafe { GetForegroundWindow() });
+
+    self.is_window_ignored(handle).then_some(handle)
+  }
+
+  fn is_window_ignored(&self, handle: WindowHandle) -> bool {
+    self.ignored_windows.read().expect(IGNORED_WINDOWS_LOCK).contains(&handle)
+  }
+
+  fn set_window_ignored(&self, handle: WindowHandle, is_ignored: bool) {
+    let mut ignored_windows = self.ignored_windows.write().expect(IGNORED_WINDOWS_LOCK);
+    if is_ignored {
+      ignored_windows.insert(handle);
+    } else {
+      ignored_windows.remove(&handle);
+    }
+  }
+
+  fn get_ignored_windows(&self) -> Vec<WindowHandle> {
+    self
+      .ignored_windows
+      .read()
+      .expect(IGNORED_WINDOWS_LOCK)
+      .iter()
+      .copied()
+      .collect()
+  }
+
+  fn is_window_elevated(&self, handle: WindowHandle) -> bool {
+    is_process_of_window_elevated(handle)
+  }
+
+  fn get_window_process_name(&self, handle: WindowHandle) -> Option<String> {
+    let path = self.get_window_executable_path(handle)?;
+
+    Path::new(&path).file_name().map(|name| name.to_string_lossy().to_string())
+  }
+
+  fn get_window_executable_path(&self, handle: WindowHandle) -> Option<String> {
+    let mut process_id = 0;
+    unsafe {
+      GetWindowThreadProcessId(handle.as_hwnd(), Some(&mut process_id));
+      let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process_id).ok()?;
+      let mut buffer = [0u16; 260];
+      let mut size = buffer.len() as u32;
+      let result = QueryFullProcessImageNameW(process, PROCESS_NAME_WIN32, PWSTR(buffer.as_mut_ptr()), &mut size);
+      let _ = CloseHandle(process);
+      result.ok()?;
+
+      Some(String::from_utf16_lossy(&buffer[..size as usize]))
+    }
+  }
+
+  fn is_window_hidden(&self, handle: &WindowHandle) -> bool {
+    unsafe { !IsWindowVisible(handle.as_hwnd()).as_bool() }
+  }
+
+  fn is_window_always_on_top(&self, handle: WindowHandle) -> bool {
+    let extended_style = unsafe { GetWindowLongW(handle.as_hwnd(), GWL_EXSTYLE) } as u32;
+    extended_style & WS_EX_TOPMOST.0 != 0
+  }
+
+  fn set_window_always_on_top(&self, handle: WindowHandle, is_always_on_top: bool) {
+    let insert_after = if is_always_on_top { HWND_TOPMOST } else { HWND_NOTOPMOST };
+    unsafe {
+      if let Err(err) = SetWindowPos(
+        handle.as_hwnd(),
+        Some(insert_after),
+        0,
+        0,
+        0,
+        0,
+        SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE,
+      ) {
+        warn!(
+          "Failed to set always on top to [{is_always_on_top}] for window {handle}: {}",
+          err.message()
+        );
+      }
+    }
+  }
+
+  fn is_window_alive(&self, handle: WindowHandle) -> bool {
+    if unsafe { !IsWindow(Some(handle.as_hwnd())).as_bool() } {
+      return false;
+    }
     let mut process_id = 0;
     unsafe {
       GetWindowThreadProcessId(handle.as_hwnd(), Some(&mut process_id));
     }
-    if process_id == std::process::id() {
-      return true;
+    if process_id == 0 {
+      return false;
     }
 
-    let class_name = self.get_window_class_name(handle);
-    let mut result = TRANSIENT_WINDOW_CLASSES.contains(&class_name.as_str());
-    if self.ignored_class_names.contains(&class_name) {
-      result = true;
+    unsafe {
+      let Ok(process) = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process_id) else {
+        // Access to elevated processes may be denied, which means the process still exists
+        trace!("Failed to open process of window {handle}, assuming it is alive");
+        return true;
+      };
+      let mut exit_code = 0;
+      let is_alive = GetExitCodeProcess(process, &mut exit_code).is_err() || exit_code == STILL_ACTIVE.0 as u32;
+      let _ = CloseHandle(process);
+
+      is_alive
     }
+  }
 
-    let title = self.get_window_title(handle);
-    if self.ignored_window_titles.contains(&title) {
-      result = true;
+  fn get_owned_windows(&self, owner: WindowHandle) -> Vec<Window> {
+    let mut windows: Vec<Window> = Vec::new();
+    unsafe {
+      if let Err(err) = EnumWindows(Some(enum_windows_callback), LPARAM(&mut windows as *mut _ as isize)) {
+        warn!("Failed to enumerate windows because: {}", err.message());
+      }
     }
 
-    // debug!(
-    //   "{}  {} {} being managed (class name [{}] and title [\"{}\"])",
-    //   if result { "⛔" } else { "✅" },
-    //   handle,
-    //   if result { "is NOT" } else { "is" },
-    //   class_name,
-    //   title,
-    // );
-    result
+    windows.retain(|window| unsafe {
+      GetWindow(window.handle.as_hwnd(), GW_OWNER).is_ok_and(|hwnd| hwnd == owner.as_hwnd())
+        && IsWindowVisible(window.handle.as_hwnd()).as_bool()
+        && !IsIconic(window.handle.as_hwnd()).as_bool()
+    });
+
+    windows
   }
 
-  fn is_window_hidden(&self, handle: &WindowHandle) -> bool {
-    unsafe { !IsWindowVisible(handle.as_hwnd()).as_bool() }
+  fn is_window_enabled(&self, handle: WindowHandle) -> bool {
+    unsafe { IsWindowEnabled(handle.as_hwnd()).as_bool() }
   }
 
   /// Sets the window position on the same monitor as the given rectangle. WARNING: Does not adjust for DPI scaling.
@@ -290,14 +445,11 @@ impl WindowsApi for RealWindowsApi {
     Vec::new()
   }
 
-  // TODO: Try fixing the method below which aims to adjust the window position based on the DPI of the source and
-  //   target monitors
-  // This does not work yet and it turned out to be much easier to simply call SetWindowPos twice in a row which always
-  // works because the second call will use the context of the target monitor.
-  // Example of moving a near-maximised window from a 100% monitor to a 125% monitor:
-  // Should be: Rect[(-1420, -367)-(-20, 2153), width: 1400, height: 2520]
-  // But is:    Rect[(-1420, -367)-(-30, 2143), width: 1390, height: 2510] when using PROCESS_PER_MONITOR_DPI_AWARE and new code
-  // Or is:     Rect[(-1420, -367)-(-308, 1641), width: 1112, height: 2008] when using DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2 without new code
+  /// Per-monitor DPI aware windows resize themselves when they receive `WM_DPICHANGED` after crossing onto a monitor with
+  /// a different DPI, scaling the size they were given from the source to the target DPI. The size is therefore
+  /// translated in the opposite direction first, so that a single call results in the correct physical size. Windows
+  /// whose non-client area does not scale linearly, or which ignore `WM_DPICHANGED`, can end up a few pixels off, which
+  /// is corrected with a second call that no longer crosses monitors.
   fn set_window_position_with_dpi_adjustment(
     &self,
     window_handle: WindowHandle,
@@ -305,79 +457,27 @@ impl WindowsApi for RealWindowsApi {
     target_monitor_handle: MonitorHandle,
     rect: Rect,
   ) {
-    unsafe {
-      // let old_context = SetThreadDpiAwarenessContext(DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2);
-      let _ = SetProcessDpiAwareness(PROCESS_PER_MONITOR_DPI_AWARE);
-
-      let mut source_dpi_x = MaybeUninit::<u32>::uninit();
-      let mut source_dpi_y = MaybeUninit::<u32>::uninit();
-      if let Err(err) = GetDpiForMonitor(
-        source_monitor_handle.as_h_monitor(),
-        windows::Win32::UI::HiDpi::MDT_EFFECTIVE_DPI,
-        source_dpi_x.as_mut_ptr(),
-        source_dpi_y.as_mut_ptr(),
-      ) {
-        error!("Failed to get DPI for monitor {source_monitor_handle}: {}", err.message());
-        return;
-      }
-
-      let source_dpi_x = source_dpi_x.assume_init();
-      let source_dpi_y = source_dpi_y.assume_init();
-      let source_scale_factor = source_dpi_x as f32 / 96.0;
-      warn!(
-        "DPI for source monitor {source_monitor_handle}: {source_scale_factor} with x={:?}dpi & y={:?}dpi",
-        source_dpi_x, source_dpi_y
-      );
-
-      let mut target_dpi_x = MaybeUninit::<u32>::uninit();
-      let mut target_dpi_y = MaybeUninit::<u32>::uninit();
-      if let Err(err) = GetDpiForMonitor(
-        target_monitor_handle.as_h_monitor(),
-        windows::Win32::UI::HiDpi::MDT_EFFECTIVE_DPI,
-        target_dpi_x.as_mut_ptr(),
-        target_dpi_y.as_mut_ptr(),
-      ) {
-        error!("Failed to get DPI for monitor {target_monitor_handle}: {}", err.message());
-        return;
-      }
-
-      let target_dpi_x = target_dpi_x.assume_init();
-      let target_dpi_y = target_dpi_y.assume_init();
-      let target_scale_factor = target_dpi_x as f32 / 96.0;
-      warn!(
-        "DPI for target monitor {target_monitor_handle}: {target_scale_factor} with x={:?}dpi & y={:?}dpi",
-        target_dpi_x, target_dpi_y
-      );
-
-      let relative_scale = (target_scale_factor / source_scale_factor).clamp(0.1, 1.0);
-      warn!(
-        "Relative scale factor from source to target monitor: {relative_scale} (target: {target_scale_factor} / source: {source_scale_factor})"
-      );
-
-      let logical_left = rect.left;
-      let logical_top = rect.top;
-      let logical_width = ((rect.right - rect.left) as f32 / relative_scale).round() as i32 - 10;
-      let logical_height = ((rect.bottom - rect.top) as f32 / relative_scale).round() as i32 - 10;
-
-      warn!(
-        "Adjusted to logical coordinates: {}, width={logical_width}, height={logical_height}",
-        Point::new(logical_left, logical_top),
-      );
-
-      if let Err(err) = SetWindowPos(
-        window_handle.as_hwnd(),
-        Some(HWND(ptr::null_mut())),
-        logical_left,
-        logical_top,
-        logical_width,
-        logical_height,
-        SWP_NOZORDER | SWP_NOACTIVATE | SWP_FRAMECHANGED,
-      ) {
-        warn!("Failed to set window position for window {window_handle}: {}", err.message());
-      }
-
-      // self.set_window_position(window_handle, rect);
-      // SetThreadDpiAwarenessContext(old_context);
+    let (Some(source_dpi), Some(target_dpi)) = (
+      get_dpi_for_monitor(source_monitor_handle),
+      get_dpi_for_monitor(target_monitor_handle),
+    ) else {
+      self.set_window_position(window_handle, rect);
+      return;
+    };
+    if source_dpi == target_dpi || !is_per_monitor_dpi_aware(window_handle) {
+      self.set_window_position(window_handle, rect);
+      return;
+    }
+    let compensated_rect = geometry::scale_for_dpi(rect, target_dpi, source_dpi);
+    trace!(
+      "Positioning {window_handle} at {compensated_rect} so that it becomes {rect} after moving from {source_dpi}dpi to {target_dpi}dpi"
+    );
+    self.set_window_position(window_handle, compensated_rect);
+    if let Some(actual_rect) = self.get_window_rect(window_handle)
+      && actual_rect != rect
+    {
+      debug!("Correcting position of {window_handle} from {actual_rect} to {rect} after moving it to another DPI");
+      self.set_window_position(window_handle, rect);
     }
   }
 
@@ -421,6 +521,8 @@ impl WindowsApi for RealWindowsApi {
   }
 
   fn do_hide_window(&self, handle: WindowHandle) {
+    // The Desktop Window Manager does not render thumbnails of hidden windows, so the window switcher needs a picture
+    WindowsApiForWindowSwitcher::capture_thumbnail(handle);
     unsafe {
       if !ShowWindow(handle.as_hwnd(), SW_HIDE).as_bool() {
         warn!("Failed to hide window {handle}");
@@ -514,6 +616,15 @@ impl WindowsApi for RealWindowsApi {
     Point::new(point.x, point.y)
   }
 
+  fn get_milliseconds_since_last_input(&self) -> u32 {
+    let Some(last_input_at_in_ms) = get_last_input_tick_count() else {
+      warn!("Failed to get the time of the last input");
+      return 0;
+    };
+
+    unsafe { GetTickCount() }.wrapping_sub(last_input_at_in_ms)
+  }
+
   fn set_cursor_position(&self, target_point: &Point) {
     unsafe {
       if let Err(err) = SetCursorPos(target_point.x(), target_point.y()) {
@@ -522,6 +633,59 @@ impl WindowsApi for RealWindowsApi {
     }
   }
 
+  fn get_cursor_speed(&self) -> Option<CursorSpeed> {
+    let mut speed = 0u32;
+    let mut mouse_parameters = [0i32; 3];
+    unsafe {
+      if let Err(err) = SystemParametersInfoW(
+        SPI_GETMOUSESPEED,
+        0,
+        Some(&mut speed as *mut u32 as *mut c_void),
+        SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+      ) {
+        warn!("Failed to get cursor speed because: {}", err.message());
+        return None;
+      }
+      if let Err(err) = SystemParametersInfoW(
+        SPI_GETMOUSE,
+        0,
+        Some(mouse_parameters.as_mut_ptr() as *mut c_void),
+        SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+      ) {
+        warn!("Failed to get cursor acceleration because: {}", err.message());
+        return None;
+      }
+    }
+
+    Some(CursorSpeed::from_mouse_parameters(speed, mouse_parameters))
+  }
+
+  fn set_cursor_speed(&self, cursor_speed: &CursorSpeed) {
+    let mut mouse_parameters = cursor_speed.mouse_parameters;
+    unsafe {
+      if let Err(err) = SystemParametersInfoW(
+        SPI_SETMOUSESPEED,
+        0,
+        Some(cursor_speed.speed as usize as *mut c_void),
+        SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+      ) {
+        warn!(
+          "Failed to set cursor speed to [{}] because: {}",
+          cursor_speed.speed,
+          err.message()
+        );
+      }
+      if let Err(err) = SystemParametersInfoW(
+        SPI_SETMOUSE,
+        0,
+        Some(mouse_parameters.as_mut_ptr() as *mut c_void),
+        SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+      ) {
+        warn!("Failed to set cursor acceleration because: {}", err.message());
+      }
+    }
+  }
+
   fn get_all_monitors(&self) -> Monitors {
     get_all_monitors()
   }
@@ -637,6 +801,104 @@ extern "system" fn enum_windows_callback(hwnd: HWND, lparam: LPARAM) -> BOOL {
   }
 }
 
+/// Returns `true` if the window belongs to an elevated process. Querying the elevation of a process only requires
+/// limited access, which is granted for elevated processes too, so a process or token that cannot be queried (e.g. a
+/// protected system process) says nothing about elevation and the window is not reported as elevated.
+fn is_process_of_window_elevated(handle: WindowHandle) -> bool {
+  let mut process_id = 0;
+  unsafe {
+    GetWindowThreadProcessId(handle.as_hwnd(), Some(&mut process_id));
+  }
+  if process_id == 0 {
+    return false;
+  }
+
+  unsafe {
+    let Ok(process) = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process_id) else {
+      trace!("Failed to open process of window {handle}, unable to tell whether it is elevated");
+      return false;
+    };
+    let mut token = HANDLE::default();
+    let is_elevated = if OpenProcessToken(process, TOKEN_QUERY, &mut token).is_ok() {
+      let mut elevation = TOKEN_ELEVATION::default();
+      let mut return_length = 0;
+      let result = GetTokenInformation(
+        token,
+        TokenElevation,
+        Some(&mut elevation as *mut TOKEN_ELEVATION as *mut c_void),
+        mem::size_of::<TOKEN_ELEVATION>() as u32,
+        &mut return_length,
+      );
+      let _ = CloseHandle(token);
+      result.is_ok() && elevation.TokenIsElevated != 0
+    } else {
+      trace!("Failed to open process token of window {handle}, unable to tell whether it is elevated");
+      false
+    };
+    let _ = CloseHandle(process);
+
+    is_elevated
+  }
+}
+
+/// Returns `true` if the Desktop Window Manager cloaks the window, e.g. because it is on another Windows virtual desktop.
+fn is_window_cloaked(handle: WindowHandle) -> bool {
+  let mut cloaked: u32 = 0;
+  // 14 is DWMWA_CLOAKED which is non-zero if the window is cloaked for any reason
+  let hr = unsafe {
+    DwmGetWindowAttribute(
+      handle.as_hwnd(),
+      14u32,
+      &mut cloaked as *mut u32 as *mut c_void,
+      size_of::<u32>() as u32,
+    )
+  };
+
+  hr.0 == 0 && cloaked != 0
+}
+
+/// Returns the effective DPI of a monitor, e.g. `144` for a scale factor of 150%.
+fn get_dpi_for_monitor(handle: MonitorHandle) -> Option<u32> {
+  let (mut dpi_x, mut dpi_y) = (0, 0);
+  if let Err(err) = unsafe { GetDpiForMonitor(handle.as_h_monitor(), MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y) } {
+    warn!("Failed to get DPI of monitor {handle}: {}", err.message());
+    return None;
+  }
+
+  (dpi_x > 0).then_some(dpi_x)
+}
+
+/// Returns `true` if the window handles DPI changes itself rather than being bitmap-scaled by Windows.
+fn is_per_monitor_dpi_aware(handle: WindowHandle) -> bool {
+  let awareness = unsafe { GetAwarenessFromDpiAwarenessContext(GetWindowDpiAwarenessContext(handle.as_hwnd())) };
+
+  awareness == DPI_AWARENESS_PER_MONITOR_AWARE
+}
+
+/// Reads the properties of a window that window filters decide on.
+fn create_window_candidate(handle: WindowHandle, title: String, rect: Rect) -> WindowCandidate {
+  let hwnd = handle.as_hwnd();
+  let mut process_id = 0;
+  unsafe {
+    GetWindowThreadProcessId(hwnd, Some(&mut process_id));
+  }
+  let mut class_name: [u16; 256] = [0; 256];
+  let len = unsafe { GetClassNameW(hwnd, &mut class_name) };
+  let extended_style = unsafe { GetWindowLongW(hwnd, GWL_EXSTYLE) } as u32;
+
+  WindowCandidate {
+    handle,
+    title,
+    class_name: String::from_utf16_lossy(&class_name[..len as usize]),
+    rect,
+    process_id,
+    is_visible: unsafe { IsWindowVisible(hwnd).as_bool() },
+    is_minimised: unsafe { IsIconic(hwnd).as_bool() },
+    is_tool_window: extended_style & WS_EX_TOOLWINDOW.0 != 0,
+    has_owner: unsafe { GetWindow(hwnd, GW_OWNER) }.is_ok(),
+  }
+}
+
 fn get_window_info(hwnd: HWND) -> Result<WINDOWINFO, &'static str> {
   unsafe {
     let mut info = WINDOWINFO {
@@ -717,6 +979,216 @@ fn get_persistent_device_name(_handle: &MonitorHandle, info: &MONITORINFOEXW) ->
 #[link(name = "dwmapi")]
 unsafe extern "system" {
   fn DwmGetWindowAttribute(h_wnd: HWND, dw_attribute: u32, pv_attribute: *mut c_void, cb_attribute: u32) -> HRESULT;
+  fn DwmSetWindowAttribute(h_wnd: HWND, dw_attribute: u32, pv_attribute: *const c_void, cb_attribute: u32) -> HRESULT;
+}
+
+/// Returns the tick count (see `GetTickCount`) at which the user last provided any input, or `None` if it cannot be
+/// queried.
+pub fn get_last_input_tick_count() -> Option<u32> {
+  let mut last_input_info = LASTINPUTINFO {
+    cbSize: mem::size_of::<LASTINPUTINFO>() as u32,
+    dwTime: 0,
+  };
+  unsafe { GetLastInputInfo(&mut last_input_info).as_bool() }.then_some(last_input_info.dwTime)
+}
+
+/// Returns a random token of 64 hexadecimal characters, generated from two GUIDs whose random bits come from the
+/// cryptographically secure random number generator of Windows, or `None` if no GUID can be created.
+pub fn generate_random_token() -> Option<String> {
+  let first = windows::core::GUID::new().ok()?;
+  let second = windows::core::GUID::new().ok()?;
+
+  Some(format!("{:032x}{:032x}", first.to_u128(), second.to_u128()))
+}
+
+/// Shows an informational message box with the given title and message. Blocks the calling thread until the user
+/// dismisses the message box.
+pub fn show_message(title: &str, message: &str) {
+  unsafe {
+    MessageBoxW(
+      None,
+      &HSTRING::from(message),
+      &HSTRING::from(title),
+      MB_OK | MB_ICONINFORMATION | MB_SETFOREGROUND,
+    );
+  }
+}
+
+/// Shows a message box with "Yes" and "No" buttons and returns `true` if the user selected "Yes". Blocks until the
+/// user has made a choice.
+pub fn ask_question(title: &str, message: &str) -> bool {
+  unsafe {
+    MessageBoxW(
+      None,
+      &HSTRING::from(message),
+      &HSTRING::from(title),
+      MB_YESNO | MB_ICONQUESTION | MB_SETFOREGROUND,
+    ) == IDYES
+  }
+}
+
+/// Lowers the priority of Randolf's process and opts it into efficiency mode (EcoQoS), which allows Windows to run all
+/// of its threads on efficient cores and at lower clock speeds, if `is_enabled` is `true`. Otherwise, restores the
+/// normal priority and lets Windows decide how to schedule the process.
+pub fn set_efficiency_mode(is_enabled: bool) {
+  unsafe {
+    let process = GetCurrentProcess();
+    let priority_class = if is_enabled {
+      BELOW_NORMAL_PRIORITY_CLASS
+    } else {
+      NORMAL_PRIORITY_CLASS
+    };
+    if let Err(err) = SetPriorityClass(process, priority_class) {
+      warn!("Failed to set process priority class: {}", err.message());
+    }
+    let power_throttling_state = PROCESS_POWER_THROTTLING_STATE {
+      Version: PROCESS_POWER_THROTTLING_CURRENT_VERSION,
+      ControlMask: PROCESS_POWER_THROTTLING_EXECUTION_SPEED,
+      StateMask: if is_enabled {
+        PROCESS_POWER_THROTTLING_EXECUTION_SPEED
+      } else {
+        0
+      },
+    };
+    if let Err(err) = SetProcessInformation(
+      process,
+      ProcessPowerThrottling,
+      &power_throttling_state as *const _ as *const c_void,
+      size_of::<PROCESS_POWER_THROTTLING_STATE>() as u32,
+    ) {
+      warn!("Failed to set process power throttling state: {}", err.message());
+    }
+    debug!("Efficiency mode {}", if is_enabled { "enabled" } else { "disabled" });
+  }
+}
+
+/// Returns `true` if the Windows shell has created the taskbar, which hosts the notification area. Until then, creating
+/// a tray icon may silently fail and the monitor work areas may not account for the taskbar yet, e.g. right after
+/// logging in.
+pub fn is_shell_ready() -> bool {
+  unsafe { FindWindowW(&HSTRING::from("Shell_TrayWnd"), PCWSTR::null()).is_ok_and(|hwnd| !hwnd.is_invalid()) }
+}
+
+/// Returns the size in pixels of small icons at the system DPI, which is the size that the notification area displays
+/// tray icons at, e.g. 16 pixels at 100% and 24 pixels at 150% scaling.
+pub fn get_small_icon_size_in_px() -> i32 {
+  unsafe { GetSystemMetricsForDpi(SM_CXSMICON, GetDpiForSystem()) }
+}
+
+/// Returns the name of the user's locale, e.g. `de-DE`, or `None` if it cannot be determined.
+pub fn get_user_locale_name() -> Option<String> {
+  // A locale name has at most 85 characters, including the terminating null character
+  let mut buffer = [0u16; 85];
+  let length = unsafe { GetUserDefaultLocaleName(&mut buffer) };
+  if length <= 1 {
+    warn!("Failed to get name of user's locale");
+    return None;
+  }
+
+  Some(String::from_utf16_lossy(&buffer[..length as usize - 1]))
+}
+
+/// Returns the text and the background colour of the active high contrast theme in the byte order of icon pixels, i.e.
+/// blue, green, red, or `None` if high contrast mode is off.
+pub fn get_high_contrast_colours() -> Option<([u8; 3], [u8; 3])> {
+  unsafe {
+    let mut high_contrast = HIGHCONTRASTW {
+      cbSize: size_of::<HIGHCONTRASTW>() as u32,
+      ..Default::default()
+    };
+    if let Err(err) = SystemParametersInfoW(
+      SPI_GETHIGHCONTRAST,
+      high_contrast.cbSize,
+      Some(&mut high_contrast as *mut HIGHCONTRASTW as *mut c_void),
+      SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+    ) {
+      warn!("Failed to check whether high contrast mode is on: {err}");
+      return None;
+    }
+    if high_contrast.dwFlags.0 & HCF_HIGHCONTRASTON.0 == 0 {
+      return None;
+    }
+    let to_pixel = |colour: u32| [(colour >> 16) as u8, (colour >> 8) as u8, colour as u8];
+
+    Some((to_pixel(GetSysColor(COLOR_WINDOWTEXT)), to_pixel(GetSysColor(COLOR_WINDOW))))
+  }
+}
+
+/// Renders the text in white, bold, and aligned to the bottom-right corner onto a black bitmap of `size` by `size`
+/// pixels and returns the coverage of every pixel, row by row from the top, from `0` (background) to `255` (text).
+/// Rendering the text separately, instead of directly onto an icon, is required because GDI does not write the alpha
+/// channel, which would leave the text transparent.
+pub fn render_text_coverage(text: &str, font_height: i32, size: i32) -> Option<Vec<u8>> {
+  unsafe {
+    let hdc = CreateCompatibleDC(None);
+    if hdc.is_invalid() {
+      warn!("Failed to create device context to render [{text}]");
+      return None;
+    }
+    let bitmap_info = BITMAPINFO {
+      bmiHeader: BITMAPINFOHEADER {
+        biSize: size_of::<BITMAPINFOHEADER>() as u32,
+        biWidth: size,
+        biHeight: -size,
+        biPlanes: 1,
+        biBitCount: 32,
+        biCompression: BI_RGB.0,
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+    let mut bits: *mut c_void = ptr::null_mut();
+    let bitmap = match CreateDIBSection(Some(hdc), &bitmap_info, DIB_RGB_COLORS, &mut bits, None, 0) {
+      Ok(bitmap) if !bits.is_null() => bitmap,
+      result => {
+        warn!("Failed to create bitmap to render [{text}]: {:?}", result.err());
+        let _ = DeleteDC(hdc);
+        return None;
+      }
+    };
+    let font = CreateFontW(
+      font_height,
+      0,
+      0,
+      0,
+      FW_BOLD.0 as i32,
+      0,
+      0,
+      0,
+      DEFAULT_CHARSET,
+      OUT_DEFAULT_PRECIS,
+      CLIP_DEFAULT_PRECIS,
+      ANTIALIASED_QUALITY,
+      0,
+      &HSTRING::from("Segoe UI"),
+    );
+    let previous_bitmap = SelectObject(hdc, bitmap.into());
+    let previous_font = SelectObject(hdc, font.into());
+    ptr::write_bytes(bits as *mut u8, 0, (size * size * 4) as usize);
+    SetTextColor(hdc, COLORREF(0x00FF_FFFF));
+    SetBkMode(hdc, TRANSPARENT);
+    let mut rect = RECT {
+      left: 0,
+      top: 0,
+      right: size,
+      bottom: size,
+    };
+    let mut text_utf16 = text.encode_utf16().collect::<Vec<u16>>();
+    DrawTextW(hdc, &mut text_utf16, &mut rect, DT_RIGHT | DT_BOTTOM | DT_SINGLELINE);
+    let _ = GdiFlush();
+    let pixels = std::slice::from_raw_parts(bits as *const u8, (size * size * 4) as usize);
+    let coverage = pixels
+      .chunks_exact(4)
+      .map(|pixel| pixel[0].max(pixel[1]).max(pixel[2]))
+      .collect();
+    SelectObject(hdc, previous_font);
+    SelectObject(hdc, previous_bitmap);
+    let _ = DeleteObject(font.into());
+    let _ = DeleteObject(bitmap.into());
+    let _ = DeleteDC(hdc);
+
+    Some(coverage)
+  }
 }
 
 pub fn do_process_windows_messages() {
@@ -0,0 +1,147 @@
+use crate::common::WindowHandle;
+use crate::overlay::{OverlayHost, OverlayId, OverlayOptions, OverlayWindow};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use windows::Win32::Foundation::{HWND, RECT};
+use windows::Win32::Graphics::Gdi::{
+  COLOR_INFOBK, DT_CALCRECT, DT_CENTER, DT_WORDBREAK, DrawTextW, FillRect, GetDC, GetSysColorBrush, HDC, InvalidateRect,
+  ReleaseDC, SetBkMode, TRANSPARENT,
+};
+use windows::Win32::UI::WindowsAndMessaging::*;
+
+/// How often the overlay checks whether a prompt that is shown has expired or its window has been closed.
+const EXPIRY_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+const MAX_TEXT_WIDTH_IN_PX: i32 = 320;
+const PADDING_IN_PX: i32 = 12;
+
+static OVERLAY: OnceLock<OverlayId> = OnceLock::new();
+static PROMPT: OnceLock<Mutex<Option<Prompt>>> = OnceLock::new();
+
+/// The text of a prompt, the window over whose centre it is shown, and when it is hidden again.
+struct Prompt {
+  text: Vec<u16>,
+  window: WindowHandle,
+  hide_at: Instant,
+}
+
+/// This struct shows a short prompt in a small, click-through overlay over the centre of another window, e.g. to ask
+/// the user to press the close hotkey again before a protected window is closed. While the prompt is shown, the
+/// overlay checks regularly whether it has expired or its window has been closed and hides it if so.
+pub struct WindowsApiForClosePrompt;
+
+impl WindowsApiForClosePrompt {
+  pub fn initialise() -> Result<(), Box<dyn std::error::Error>> {
+    let options = OverlayOptions::click_through("Randolf Close Prompt", 235);
+    let id = OverlayHost::add(options, || Box::new(ClosePromptWindow))?;
+    let _ = OVERLAY.set(id);
+
+    Ok(())
+  }
+
+  pub fn show(window: WindowHandle, text: &str, duration: Duration) {
+    *Self::prompt() = Some(Prompt {
+      text: text.encode_utf16().collect(),
+      window,
+      hide_at: Instant::now() + duration,
+    });
+    Self::wake();
+  }
+
+  pub fn hide() {
+    *Self::prompt() = None;
+    Self::wake();
+  }
+
+  fn wake() {
+    if let Some(id) = OVERLAY.get() {
+      OverlayHost::wake(*id);
+    }
+  }
+
+  fn prompt() -> std::sync::MutexGuard<'static, Option<Prompt>> {
+    PROMPT
+      .get_or_init(|| Mutex::new(None))
+      .lock()
+      .expect("Failed to lock close prompt")
+  }
+
+  /// Returns `true` if a prompt is shown that should no longer be, because its time is up or its window is gone.
+  fn has_expired() -> bool {
+    Self::prompt().as_ref().is_some_and(|prompt| {
+      Instant::now() >= prompt.hide_at || unsafe { !IsWindow(Some(prompt.window.as_hwnd())).as_bool() }
+    })
+  }
+}
+
+/// The prompt overlay, whose state is shared with [`WindowsApiForClosePrompt`].
+struct ClosePromptWindow;
+
+impl OverlayWindow for ClosePromptWindow {
+  /// Shows the prompt over the centre of its window and keeps checking whether it has expired while it is shown.
+  fn update(&mut self, hwnd: HWND) -> Option<Duration> {
+    let prompt = WindowsApiForClosePrompt::prompt();
+    let mut window_rect = RECT::default();
+    let Some(prompt) = prompt
+      .as_ref()
+      .filter(|prompt| unsafe { GetWindowRect(prompt.window.as_hwnd(), &mut window_rect).is_ok() })
+    else {
+      unsafe {
+        let _ = ShowWindow(hwnd, SW_HIDE);
+      }
+      return None;
+    };
+
+    unsafe {
+      let mut text = prompt.text.clone();
+      let mut rect = RECT {
+        right: MAX_TEXT_WIDTH_IN_PX,
+        ..Default::default()
+      };
+      let hdc = GetDC(Some(hwnd));
+      DrawTextW(hdc, &mut text, &mut rect, DT_CALCRECT | DT_CENTER | DT_WORDBREAK);
+      ReleaseDC(Some(hwnd), hdc);
+      let width = rect.right - rect.left + 2 * PADDING_IN_PX;
+      let height = rect.bottom - rect.top + 2 * PADDING_IN_PX;
+      if let Err(err) = SetWindowPos(
+        hwnd,
+        Some(HWND_TOPMOST),
+        window_rect.left + (window_rect.right - window_rect.left - width) / 2,
+        window_rect.top + (window_rect.bottom - window_rect.top - height) / 2,
+        width,
+        height,
+        SWP_NOACTIVATE | SWP_SHOWWINDOW,
+      ) {
+        warn!("Failed to position close prompt: {err}");
+      }
+      let _ = InvalidateRect(Some(hwnd), None, true);
+    }
+
+    Some(EXPIRY_CHECK_INTERVAL)
+  }
+
+  fn tick(&mut self, hwnd: HWND) -> Option<Duration> {
+    if !WindowsApiForClosePrompt::has_expired() {
+      return Some(EXPIRY_CHECK_INTERVAL);
+    }
+    *WindowsApiForClosePrompt::prompt() = None;
+    self.update(hwnd)
+  }
+
+  fn paint(&mut self, _hwnd: HWND, hdc: HDC, rect: RECT) {
+    unsafe {
+      FillRect(hdc, &rect, GetSysColorBrush(COLOR_INFOBK));
+      SetBkMode(hdc, TRANSPARENT);
+      let mut text_rect = RECT {
+        left: rect.left + PADDING_IN_PX,
+        top: rect.top + PADDING_IN_PX,
+        right: rect.right - PADDING_IN_PX,
+        bottom: rect.bottom - PADDING_IN_PX,
+      };
+      let mut text = WindowsApiForClosePrompt::prompt()
+        .as_ref()
+        .map(|prompt| prompt.text.clone())
+        .unwrap_or_default();
+      DrawTextW(hdc, &mut text, &mut text_rect, DT_CENTER | DT_WORDBREAK);
+    }
+  }
+}
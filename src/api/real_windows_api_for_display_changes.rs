@@ -0,0 +1,143 @@
+use crate::common::{Command, HookHealth};
+use crossbeam_channel::Sender;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use windows::Win32::Foundation::*;
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::WindowsAndMessaging::*;
+use windows::core::w;
+
+/// The `wParam` of `WM_DEVICECHANGE` when a device was added to or removed from the system, e.g. a monitor.
+const DBT_DEVNODES_CHANGED: usize = 0x0007;
+const SETTLE_TIMER_ID: usize = 1;
+const WORK_AREA_SETTLE_TIMER_ID: usize = 2;
+/// How long to wait after the last display, device, or work area change before reporting it, because docking or
+/// undocking a laptop raises many changes while Windows is still rearranging the monitors.
+const SETTLE_DELAY_IN_MS: u32 = 500;
+
+static SENDER: OnceLock<Mutex<Sender<Command>>> = OnceLock::new();
+static IS_REGISTERED: AtomicBool = AtomicBool::new(false);
+
+/// This struct creates a hidden window that receives `WM_DISPLAYCHANGE` and `WM_DEVICECHANGE`, which are broadcast when
+/// monitors are connected, disconnected, or rearranged, or when their resolution changes, and reports them as
+/// [`Command::DisplayChanged`] once they have settled. It also receives `WM_SETTINGCHANGE` with `SPI_SETWORKAREA`,
+/// which is broadcast when a work area changes, e.g. because the taskbar was moved, and reports it as
+/// [`Command::WorkAreaChanged`]. Unlike the windows of
+/// [`crate::api::real_windows_api_for_gestures::WindowsApiForGestures`], this window cannot be a message-only window
+/// because broadcast messages are only sent to top-level windows. It runs its own message loop on a separate thread.
+pub struct WindowsApiForDisplayChanges;
+
+impl WindowsApiForDisplayChanges {
+  pub fn new(sender: Sender<Command>) -> Self {
+    SENDER.set(Mutex::new(sender)).expect("Failed to set command sender");
+    Self
+  }
+
+  pub fn initialise(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+    thread::Builder::new().name("display-changes".to_string()).spawn(|| {
+      if let Err(err) = Self::create_window_and_run_message_loop() {
+        error!("Detecting display changes is disabled because: {err}");
+      }
+    })?;
+
+    Ok(())
+  }
+
+  /// Returns the health of the hidden window that receives display changes, which is created on a separate thread.
+  pub fn hook_health() -> HookHealth {
+    HookHealth::new(SENDER.get().is_some(), IS_REGISTERED.load(Ordering::Relaxed))
+  }
+
+  fn create_window_and_run_message_loop() -> windows::core::Result<()> {
+    unsafe {
+      let h_module = GetModuleHandleW(None)?;
+      let h_instance = HINSTANCE(h_module.0);
+      let class_name = w!("RandolfDisplayChanges");
+      let window_class = WNDCLASSW {
+        lpfnWndProc: Some(Self::window_procedure),
+        hInstance: h_instance,
+        lpszClassName: class_name,
+        ..Default::default()
+      };
+      if RegisterClassW(&window_class) == 0 {
+        return Err(windows::core::Error::from_win32());
+      }
+      // The window is never shown, and the tool window style keeps it out of the taskbar and Alt+Tab
+      CreateWindowExW(
+        WS_EX_TOOLWINDOW,
+        class_name,
+        w!("Randolf Display Changes"),
+        WS_POPUP,
+        0,
+        0,
+        0,
+        0,
+        None,
+        None,
+        Some(h_instance),
+        None,
+      )?;
+      IS_REGISTERED.store(true, Ordering::Relaxed);
+      debug!("Registered for display and device changes");
+
+      let mut message = MSG::default();
+      while GetMessageW(&mut message, None, 0, 0).as_bool() {
+        let _ = TranslateMessage(&message);
+        DispatchMessageW(&message);
+      }
+      IS_REGISTERED.store(false, Ordering::Relaxed);
+    }
+
+    Ok(())
+  }
+
+  extern "system" fn window_procedure(hwnd: HWND, message: u32, w_param: WPARAM, l_param: LPARAM) -> LRESULT {
+    match message {
+      WM_DISPLAYCHANGE => Self::restart_settle_timer(hwnd, SETTLE_TIMER_ID, "Display settings changed"),
+      WM_DEVICECHANGE if w_param.0 == DBT_DEVNODES_CHANGED => {
+        Self::restart_settle_timer(hwnd, SETTLE_TIMER_ID, "Devices changed")
+      }
+      WM_SETTINGCHANGE if w_param.0 == SPI_SETWORKAREA.0 as usize => {
+        Self::restart_settle_timer(hwnd, WORK_AREA_SETTLE_TIMER_ID, "Work area changed")
+      }
+      WM_TIMER if w_param.0 == SETTLE_TIMER_ID || w_param.0 == WORK_AREA_SETTLE_TIMER_ID => {
+        unsafe {
+          let _ = KillTimer(Some(hwnd), w_param.0);
+        }
+        Self::send_command(w_param.0);
+      }
+      _ => {}
+    }
+
+    unsafe { DefWindowProcW(hwnd, message, w_param, l_param) }
+  }
+
+  /// Starts the timer after which the change is reported or, if it is already running, starts it again, so that a burst
+  /// of changes is only reported once.
+  fn restart_settle_timer(hwnd: HWND, timer_id: usize, reason: &str) {
+    trace!("{reason}, waiting [{SETTLE_DELAY_IN_MS}] ms for further changes");
+    unsafe {
+      if SetTimer(Some(hwnd), timer_id, SETTLE_DELAY_IN_MS, None) == 0 {
+        warn!("Failed to set timer, reporting change straight away");
+        Self::send_command(timer_id);
+      }
+    }
+  }
+
+  /// Sends the command that reports the change for which the timer with the given ID was started.
+  fn send_command(timer_id: usize) {
+    let command = if timer_id == WORK_AREA_SETTLE_TIMER_ID {
+      Command::WorkAreaChanged
+    } else {
+      Command::DisplayChanged
+    };
+    let Some(sender) = SENDER.get() else {
+      error!("Failed to send command [{command}] because command sender is not set");
+      return;
+    };
+    if let Err(err) = sender.lock().expect("Failed to lock command sender").send(command) {
+      error!("Failed to send display or work area change command: {err}");
+    }
+  }
+}
@@ -1,21 +1,45 @@
-use crate::common::{Command, DragState, Point, Rect, ResizeMode, ResizeState, WindowHandle};
+use crate::api::real_windows_api_for_focus_border::WindowsApiForFocusBorder;
+use crate::api::real_windows_api_for_snap_zone_preview::WindowsApiForSnapZonePreview;
+use crate::api::{get_all_monitors, get_last_input_tick_count};
+use crate::common::{
+  Command, DragState, HookActivity, HookHealth, Point, Rect, ResizeMode, ResizeState, Sizing, WinKeyRelease, WinKeyState,
+  WindowHandle, geometry,
+};
+use crate::configuration_provider::{ModifierKey, MouseBindingsConfiguration, MouseButton};
+use crate::utils::{FREE_FLOATING_MONITORS_LOCK, MOUSE_BINDINGS_LOCK};
 use crossbeam_channel::Sender;
+use std::collections::HashSet;
 use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering};
-use std::sync::{Arc, Mutex, OnceLock};
+use std::sync::{Arc, Mutex, OnceLock, RwLock, RwLockReadGuard};
 use windows::Win32::Foundation::*;
 use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::System::SystemInformation::GetTickCount;
 use windows::Win32::UI::Input::KeyboardAndMouse::*;
 use windows::Win32::UI::WindowsAndMessaging::*;
 
-static IS_WIN_KEY_PRESSED: AtomicBool = AtomicBool::new(false);
+static WIN_KEY_STATE: OnceLock<Arc<Mutex<WinKeyState>>> = OnceLock::new();
+static IS_KEYBOARD_HOOKED: AtomicBool = AtomicBool::new(false);
+static KEYBOARD_HOOK_ACTIVITY: HookActivity = HookActivity::new();
 static IS_DRAGGING: AtomicBool = AtomicBool::new(false);
 static IS_RESIZING: AtomicBool = AtomicBool::new(false);
 static DRAG_STATE: OnceLock<Arc<Mutex<DragState>>> = OnceLock::new();
 static RESIZE_STATE: OnceLock<Arc<Mutex<ResizeState>>> = OnceLock::new();
+static KEYBOARD_HOOK_HANDLE: AtomicPtr<std::ffi::c_void> = AtomicPtr::new(std::ptr::null_mut());
 static MOUSE_HOOK_HANDLE: AtomicPtr<std::ffi::c_void> = AtomicPtr::new(std::ptr::null_mut());
 static HOOK_TIMER_ID: AtomicUsize = AtomicUsize::new(0);
 static SENDER: OnceLock<Arc<Mutex<Sender<Command>>>> = OnceLock::new();
 static KEY_PRESS_DELAY_IN_MS: OnceLock<u32> = OnceLock::new();
+static GRID_CELL_SIZE_IN_PX: OnceLock<i32> = OnceLock::new();
+static MAGNETIC_EDGE_DISTANCE_IN_PX: OnceLock<i32> = OnceLock::new();
+static SNAP_ZONE_DISTANCE_IN_PX: OnceLock<i32> = OnceLock::new();
+static WINDOW_MARGIN: OnceLock<i32> = OnceLock::new();
+static MOUSE_BINDINGS: OnceLock<Arc<RwLock<MouseBindingsConfiguration>>> = OnceLock::new();
+static FREE_FLOATING_MONITORS: OnceLock<Arc<RwLock<HashSet<[u16; 32]>>>> = OnceLock::new();
+
+/// An unassigned virtual-key code that is sent while the modifier is held down, so that Windows does not open the Start
+/// menu (or, for `Alt`, activate the menu bar of the foreground window) when the modifier is released after it was used
+/// together with the mouse.
+const START_MENU_MASK_KEY: VIRTUAL_KEY = VIRTUAL_KEY(0xE8);
 
 const IGNORED_CLASS_NAMES: [&str; 6] = [
   "Progman",
@@ -38,67 +62,154 @@ const IGNORED_WINDOW_TITLES: [&str; 9] = [
   "Annotation - Zoom",
 ];
 
-/// This struct registers a keyboard hook that, if active for [`KEY_PRESS_DELAY_IN_MS`], will install a mouse
-/// hook that allows the user to drag and resize windows by holding down the Windows key and clicking the left or right
-/// mouse button. Since this functionality is very specific and isolated from other interactions with the Windows API
-/// and the code is incredibly verbose, it is implemented in a separate struct to avoid cluttering the main API
-/// interface which is [`crate::RealWindowsApi`]. Also, I'm not sure if this feature should remain part of Randolf.
-pub struct WindowsApiForDragging {
-  keyboard_hook_handle: Option<HHOOK>,
-}
+/// This struct registers a keyboard hook that, if active for [`KEY_PRESS_DELAY_IN_MS`], will install a mouse hook that
+/// allows the user to drag and resize windows by holding down the modifier (the Windows key by default) and clicking
+/// the left or right mouse button, as configured in [`MouseBindingsConfiguration`]. The mouse bindings are shared with
+/// the configuration provider, so that changes take effect the next time the modifier is pressed after the
+/// configuration was reloaded. Likewise, the monitors whose active workspace is free-floating are shared with the
+/// window manager, so that windows dragged or resized on them are not snapped. Since this functionality is very
+/// specific and isolated from other interactions with the Windows API and the code is incredibly verbose, it is
+/// implemented in a separate struct to avoid cluttering the main API interface which is [`crate::RealWindowsApi`].
+/// Also, I'm not sure if this feature should remain part of Randolf.
+pub struct WindowsApiForDragging;
 
 impl WindowsApiForDragging {
-  pub fn new(sender: Sender<Command>, key_press_delay_in_ms: u32) -> Self {
+  pub fn new(
+    sender: Sender<Command>,
+    key_press_delay_in_ms: u32,
+    grid_cell_size_in_px: i32,
+    magnetic_edge_distance_in_px: i32,
+    snap_zone_distance_in_px: i32,
+    window_margin: i32,
+    mouse_bindings: Arc<RwLock<MouseBindingsConfiguration>>,
+    free_floating_monitors: Arc<RwLock<HashSet<[u16; 32]>>>,
+  ) -> Self {
     SENDER
       .set(Arc::new(Mutex::new(sender)))
       .expect("Failed to set command sender");
     KEY_PRESS_DELAY_IN_MS
       .set(key_press_delay_in_ms)
       .expect("Failed to set key press delay in");
-    Self {
-      keyboard_hook_handle: None,
-    }
+    GRID_CELL_SIZE_IN_PX
+      .set(grid_cell_size_in_px)
+      .expect("Failed to set grid cell size");
+    MAGNETIC_EDGE_DISTANCE_IN_PX
+      .set(magnetic_edge_distance_in_px)
+      .expect("Failed to set magnetic edge distance");
+    SNAP_ZONE_DISTANCE_IN_PX
+      .set(snap_zone_distance_in_px)
+      .expect("Failed to set snap zone distance");
+    WINDOW_MARGIN.set(window_margin).expect("Failed to set window margin");
+    MOUSE_BINDINGS.set(mouse_bindings).expect("Failed to set mouse bindings");
+    FREE_FLOATING_MONITORS
+      .set(free_floating_monitors)
+      .expect("Failed to set free-floating monitors");
+    Self
   }
 
   pub fn initialise(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+    Self::install_keyboard_hook()?;
+    if Self::get_snap_zone_distance() > 0 {
+      WindowsApiForSnapZonePreview::initialise()?;
+    }
+
+    Ok(())
+  }
+
+  /// Installs the keyboard hook again, e.g. because Windows removed it silently. Must be called on the thread that
+  /// installed it initially, which processes its messages. Does nothing if the mouse-based features are disabled.
+  pub fn reinstall_keyboard_hook() {
+    if SENDER.get().is_none() {
+      return;
+    }
+    Self::uninstall_keyboard_hook();
+    match Self::install_keyboard_hook() {
+      Ok(()) => info!("Re-installed keyboard hook"),
+      Err(err) => error!("Failed to re-install keyboard hook: {err}"),
+    }
+  }
+
+  fn install_keyboard_hook() -> windows::core::Result<()> {
     unsafe {
       let h_module = GetModuleHandleW(None)?;
       let h_instance = HINSTANCE(h_module.0);
       let keyboard_hook = SetWindowsHookExW(WH_KEYBOARD_LL, Some(Self::keyboard_callback), Option::from(h_instance), 0)?;
-
-      self.keyboard_hook_handle = Some(keyboard_hook);
+      KEYBOARD_HOOK_HANDLE.store(keyboard_hook.0, Ordering::Relaxed);
+      IS_KEYBOARD_HOOKED.store(true, Ordering::Relaxed);
+      KEYBOARD_HOOK_ACTIVITY.record(GetTickCount());
     }
 
     Ok(())
   }
 
-  // TODO: Fix bug where start menu opens after operation
+  /// Uninstalls the keyboard hook if it is currently installed. Does nothing if the hook is not installed.
+  fn uninstall_keyboard_hook() {
+    let hook_pointer = KEYBOARD_HOOK_HANDLE.swap(std::ptr::null_mut(), Ordering::Relaxed);
+    if hook_pointer.is_null() {
+      return;
+    }
+    unsafe {
+      if let Err(err) = UnhookWindowsHookEx(HHOOK(hook_pointer)) {
+        // Expected if Windows already removed the hook
+        warn!("Failed to unhook keyboard hook: {}", err);
+      }
+    }
+    IS_KEYBOARD_HOOKED.store(false, Ordering::Relaxed);
+  }
+
+  /// Returns the health of the keyboard hook, which is only installed if the mouse-based features are enabled.
+  pub fn hook_health() -> HookHealth {
+    HookHealth::new(SENDER.get().is_some(), IS_KEYBOARD_HOOKED.load(Ordering::Relaxed))
+      .stale_if(Self::is_keyboard_hook_stale())
+  }
+
+  /// Returns `true` if the keyboard hook is installed but has not been called for a while even though the user
+  /// provided input since (see [`HookActivity::is_stale`]).
+  pub fn is_keyboard_hook_stale() -> bool {
+    IS_KEYBOARD_HOOKED.load(Ordering::Relaxed)
+      && get_last_input_tick_count().is_some_and(|last_input_at| KEYBOARD_HOOK_ACTIVITY.is_stale(last_input_at))
+  }
+
+  /// Tracks the modifier using [`WinKeyState`], which counts as pressed once all of its keys are held down. The keys
+  /// themselves are never swallowed, so that tapping the Win key on its own still opens the Start menu, while holding
+  /// the modifier starts the timer that enables the mouse-based features.
   extern "system" fn keyboard_callback(n_code: i32, w_param: WPARAM, l_param: LPARAM) -> LRESULT {
     unsafe {
+      KEYBOARD_HOOK_ACTIVITY.record(GetTickCount());
       if n_code == HC_ACTION as i32 {
         let keyboard_data = *(l_param.0 as *const KBDLLHOOKSTRUCT);
         let vk_code = keyboard_data.vkCode;
-        let is_window_key = vk_code == VK_LWIN.0 as u32 || vk_code == VK_RWIN.0 as u32;
-        if is_window_key {
-          let is_pressed = (w_param.0 as u32) == WM_KEYDOWN || (w_param.0 as u32) == WM_SYSKEYDOWN;
+        let modifier_key = Self::to_modifier_key(vk_code).filter(|key| get_mouse_bindings().is_modifier(*key));
+        let is_pressed = (w_param.0 as u32) == WM_KEYDOWN || (w_param.0 as u32) == WM_SYSKEYDOWN;
+        if let Some(modifier_key) = modifier_key {
           if Self::is_state_inconsistent() {
             warn!("Detected inconsistent state, resetting...");
             Self::reset_all_state();
           }
-          if is_pressed == IS_WIN_KEY_PRESSED.load(Ordering::Relaxed) {
-            return CallNextHookEx(None, n_code, w_param, l_param);
-          }
-          trace!("Win key [{}] {}", vk_code, if is_pressed { "pressed" } else { "released" });
-          IS_WIN_KEY_PRESSED.store(is_pressed, Ordering::Relaxed);
           if is_pressed {
+            if !Self::are_other_modifier_keys_pressed(modifier_key)
+              || !get_win_key_state().lock().expect("Failed to lock Win key state").press()
+            {
+              return CallNextHookEx(None, n_code, w_param, l_param);
+            }
+            trace!("Modifier key [{}] pressed", vk_code);
             Self::start_mouse_hook_install_timer();
           } else {
+            let release = get_win_key_state().lock().expect("Failed to lock Win key state").release();
+            if release == WinKeyRelease::Ignored {
+              return CallNextHookEx(None, n_code, w_param, l_param);
+            }
+            trace!("Modifier key [{}] released ({:?})", vk_code, release);
             Self::handle_win_key_release();
           }
-        } else if IS_WIN_KEY_PRESSED.load(Ordering::Relaxed) {
+        } else if is_pressed && Self::is_modifier_pressed() {
+          get_win_key_state()
+            .lock()
+            .expect("Failed to lock Win key state")
+            .record_key_combination();
           // If VK_L i.e. the 'L' key is pressed while the Win key is down, reset all state because
           // once the screen is locked, the Win key state will be inconsistent
-          if vk_code == 0x4C && ((w_param.0 as u32) == WM_KEYDOWN || (w_param.0 as u32) == WM_SYSKEYDOWN) {
+          if vk_code == 0x4C && get_mouse_bindings().is_modifier(ModifierKey::Win) {
             warn!("Win + L detected, preemptively resetting state");
             Self::reset_all_state();
           }
@@ -109,19 +220,81 @@ impl WindowsApiForDragging {
     }
   }
 
-  fn is_state_inconsistent() -> bool {
-    unsafe {
-      let left_win_state = GetAsyncKeyState(VK_LWIN.0 as i32);
-      let right_win_state = GetAsyncKeyState(VK_RWIN.0 as i32);
-      let is_actually_pressed = (left_win_state & 0x8000u16 as i16) != 0 || (right_win_state & 0x8000u16 as i16) != 0;
-      let is_expected_to_be_pressed = IS_WIN_KEY_PRESSED.load(Ordering::Relaxed);
+  fn is_modifier_pressed() -> bool {
+    get_win_key_state().lock().expect("Failed to lock Win key state").is_pressed()
+  }
+
+  /// Returns the modifier key that a virtual-key code reported by the keyboard hook belongs to, if any.
+  fn to_modifier_key(vk_code: u32) -> Option<ModifierKey> {
+    match VIRTUAL_KEY(vk_code as u16) {
+      VK_LWIN | VK_RWIN => Some(ModifierKey::Win),
+      VK_MENU | VK_LMENU | VK_RMENU => Some(ModifierKey::Alt),
+      VK_CONTROL | VK_LCONTROL | VK_RCONTROL => Some(ModifierKey::Ctrl),
+      _ => None,
+    }
+  }
+
+  /// Returns `true` if either variant of the key is actually held down, regardless of what the hook has seen.
+  fn is_modifier_key_down(key: ModifierKey) -> bool {
+    let virtual_keys: &[VIRTUAL_KEY] = match key {
+      ModifierKey::Win => &[VK_LWIN, VK_RWIN],
+      ModifierKey::Alt => &[VK_MENU],
+      ModifierKey::Ctrl => &[VK_CONTROL],
+    };
+
+    virtual_keys
+      .iter()
+      .any(|virtual_key| unsafe { (GetAsyncKeyState(virtual_key.0 as i32) & 0x8000u16 as i16) != 0 })
+  }
+
+  /// Returns `true` if all keys of the modifier other than `key`, which is being pressed and therefore not yet reported
+  /// as down, are held down.
+  fn are_other_modifier_keys_pressed(key: ModifierKey) -> bool {
+    get_mouse_bindings()
+      .modifier
+      .iter()
+      .filter(|other_key| **other_key != key)
+      .all(|other_key| Self::is_modifier_key_down(*other_key))
+  }
 
-      is_expected_to_be_pressed != is_actually_pressed
+  /// Sends [`START_MENU_MASK_KEY`] the first time the Win key is used together with the mouse since it was pressed.
+  /// Windows does not consider mouse input when deciding whether releasing the Win key should open the Start menu.
+  fn mask_start_menu_if_required() {
+    if !get_win_key_state()
+      .lock()
+      .expect("Failed to lock Win key state")
+      .record_mouse_combination()
+    {
+      return;
+    }
+    let key = |flags: KEYBD_EVENT_FLAGS| INPUT {
+      r#type: INPUT_KEYBOARD,
+      Anonymous: INPUT_0 {
+        ki: KEYBDINPUT {
+          wVk: START_MENU_MASK_KEY,
+          dwFlags: flags,
+          ..Default::default()
+        },
+      },
+    };
+    let inputs = [key(KEYBD_EVENT_FLAGS(0)), key(KEYEVENTF_KEYUP)];
+    if unsafe { SendInput(&inputs, size_of::<INPUT>() as i32) } != inputs.len() as u32 {
+      warn!("Failed to send key to prevent the Start menu from opening");
     }
   }
 
+  fn is_state_inconsistent() -> bool {
+    let is_actually_pressed = get_mouse_bindings()
+      .modifier
+      .iter()
+      .all(|key| Self::is_modifier_key_down(*key));
+    let is_expected_to_be_pressed = Self::is_modifier_pressed();
+
+    is_expected_to_be_pressed != is_actually_pressed
+  }
+
   fn reset_all_state() {
-    IS_WIN_KEY_PRESSED.store(false, Ordering::Relaxed);
+    get_win_key_state().lock().expect("Failed to lock Win key state").reset();
     Self::cancel_mouse_hook_install_timer();
     if IS_DRAGGING.load(Ordering::Relaxed) {
       Self::finish_dragging();
@@ -177,7 +350,7 @@ impl WindowsApiForDragging {
   extern "system" fn timer_callback(_hwnd: HWND, _msg: u32, timer_id: usize, _time: u32) {
     if HOOK_TIMER_ID.load(Ordering::Relaxed) == timer_id {
       Self::cancel_mouse_hook_install_timer();
-      if IS_WIN_KEY_PRESSED.load(Ordering::Relaxed) && !Self::is_state_inconsistent() {
+      if Self::is_modifier_pressed() && !Self::is_state_inconsistent() {
         Self::install_mouse_hook();
         SENDER
           .get()
@@ -189,7 +362,7 @@ impl WindowsApiForDragging {
         let key_press_delay_in_ms = KEY_PRESS_DELAY_IN_MS.get().expect("Key press delay not initialised");
         trace!("Installed mouse hook after {}ms delay", key_press_delay_in_ms);
       } else {
-        trace!("Win key no longer pressed or state was inconsistent when timer expired");
+        trace!("Modifier no longer pressed or state was inconsistent when timer expired");
       }
     }
   }
@@ -241,7 +414,7 @@ impl WindowsApiForDragging {
   }
 
   /// Callback function for the low-level mouse hook. This function is called whenever a mouse event occurs, and it is
-  /// the main function that handles the dragging and resizing of windows when the Win key is pressed.
+  /// the main function that handles the dragging and resizing of windows when the modifier is pressed.
   extern "system" fn low_level_mouse_callback(n_code: i32, w_param: WPARAM, l_param: LPARAM) -> LRESULT {
     unsafe {
       if n_code != HC_ACTION as i32 {
@@ -254,60 +427,82 @@ impl WindowsApiForDragging {
         return CallNextHookEx(None, n_code, w_param, l_param);
       }
 
-      if !IS_WIN_KEY_PRESSED.load(Ordering::Relaxed) {
+      if !Self::is_modifier_pressed() {
         return CallNextHookEx(None, n_code, w_param, l_param);
       }
 
-      match w_param.0 as u32 {
-        WM_LBUTTONDOWN => {
+      let message = w_param.0 as u32;
+      if message == WM_MOUSEMOVE {
+        if IS_DRAGGING.load(Ordering::Relaxed) {
+          let mouse_low_level_hook_struct = *(l_param.0 as *const MSLLHOOKSTRUCT);
+          Self::do_drag(mouse_low_level_hook_struct.pt);
+        } else if IS_RESIZING.load(Ordering::Relaxed) {
+          let mouse_low_level_hook_struct = *(l_param.0 as *const MSLLHOOKSTRUCT);
+          Self::do_resize(mouse_low_level_hook_struct.pt);
+        }
+        return CallNextHookEx(None, n_code, w_param, l_param);
+      }
+      let Some((button, is_button_down)) = Self::to_mouse_button_event(message) else {
+        return CallNextHookEx(None, n_code, w_param, l_param);
+      };
+      let (move_button, resize_button) = {
+        let mouse_bindings = get_mouse_bindings();
+        (mouse_bindings.move_button, mouse_bindings.resize_button)
+      };
+      match is_button_down {
+        true if button == move_button => {
           let mouse_low_level_hook_struct = *(l_param.0 as *const MSLLHOOKSTRUCT);
           let cursor_position = Point::from(mouse_low_level_hook_struct.pt);
-          debug!("Win key + left mouse button pressed at {}, starting drag...", cursor_position);
+          debug!(
+            "Modifier + {:?} mouse button pressed at {}, starting drag...",
+            button, cursor_position
+          );
+          Self::mask_start_menu_if_required();
           Self::start_dragging(cursor_position);
           return LRESULT(1);
         }
-        WM_LBUTTONUP => {
-          if IS_DRAGGING.load(Ordering::Relaxed) {
-            debug!("Win key + left mouse button released, ending drag...",);
-            Self::finish_dragging();
-            return LRESULT(1);
-          }
+        false if button == move_button && IS_DRAGGING.load(Ordering::Relaxed) => {
+          debug!("Modifier + {:?} mouse button released, ending drag...", button);
+          Self::finish_dragging();
+          return LRESULT(1);
         }
-        WM_RBUTTONDOWN => {
+        true if button == resize_button => {
           let mouse_low_level_hook_struct = *(l_param.0 as *const MSLLHOOKSTRUCT);
           let cursor_position = Point::from(mouse_low_level_hook_struct.pt);
           debug!(
-            "Win key + right mouse button pressed at {}, starting resize...",
-            cursor_position
+            "Modifier + {:?} mouse button pressed at {}, starting resize...",
+            button, cursor_position
           );
+          Self::mask_start_menu_if_required();
           Self::start_resizing(cursor_position);
           return LRESULT(1);
         }
-        WM_RBUTTONUP => {
-          if IS_RESIZING.load(Ordering::Relaxed) {
-            debug!("Win key + right mouse button released, ending window resizing...");
-            Self::finish_resizing();
-            return LRESULT(1);
-          }
-        }
-        WM_MOUSEMOVE => {
-          if IS_DRAGGING.load(Ordering::Relaxed) {
-            let mouse_low_level_hook_struct = *(l_param.0 as *const MSLLHOOKSTRUCT);
-            Self::do_drag(mouse_low_level_hook_struct.pt);
-            return CallNextHookEx(None, n_code, w_param, l_param);
-          } else if IS_RESIZING.load(Ordering::Relaxed) {
-            let mouse_low_level_hook_struct = *(l_param.0 as *const MSLLHOOKSTRUCT);
-            Self::do_resize(mouse_low_level_hook_struct.pt);
-            return CallNextHookEx(None, n_code, w_param, l_param);
-          }
+        false if button == resize_button && IS_RESIZING.load(Ordering::Relaxed) => {
+          debug!("Modifier + {:?} mouse button released, ending window resizing...", button);
+          Self::finish_resizing();
+          return LRESULT(1);
         }
-        _ => return CallNextHookEx(None, n_code, w_param, l_param),
+        _ => {}
       }
 
       CallNextHookEx(None, n_code, w_param, l_param)
     }
   }
 
+  /// Returns the mouse button that a message reported by the mouse hook belongs to and whether it was pressed (`true`)
+  /// or released (`false`), if the message is about a mouse button at all.
+  fn to_mouse_button_event(message: u32) -> Option<(MouseButton, bool)> {
+    match message {
+      WM_LBUTTONDOWN => Some((MouseButton::Left, true)),
+      WM_LBUTTONUP => Some((MouseButton::Left, false)),
+      WM_RBUTTONDOWN => Some((MouseButton::Right, true)),
+      WM_RBUTTONUP => Some((MouseButton::Right, false)),
+      WM_MBUTTONDOWN => Some((MouseButton::Middle, true)),
+      WM_MBUTTONUP => Some((MouseButton::Middle, false)),
+      _ => None,
+    }
+  }
+
   fn start_dragging(cursor_position: Point) {
     unsafe {
       let mut hwnd = WindowFromPoint(cursor_position.as_point());
@@ -336,17 +531,27 @@ impl WindowsApiForDragging {
         warn!("Failed to set foreground window to w#{:?}", hwnd.0);
       }
       if let Ok(mut drag_state) = get_drag_state().lock() {
-        let window_position = Point::new(window_rect.left, window_rect.top);
         let window_handle = WindowHandle::from(hwnd);
-        drag_state.set(cursor_position, window_handle, window_position);
+        drag_state.set(cursor_position, window_handle, Rect::from(window_rect));
+        let (work_areas, free_floating_work_areas) = Self::get_work_areas();
+        let is_attracting_edges = *MAGNETIC_EDGE_DISTANCE_IN_PX.get().unwrap_or(&0) > 0;
+        if is_attracting_edges || Self::get_snap_zone_distance() > 0 {
+          let other_windows = match is_attracting_edges {
+            true => Self::get_other_window_rects(hwnd),
+            false => Vec::new(),
+          };
+          drag_state.set_snap_targets(work_areas, other_windows);
+        }
+        drag_state.set_free_floating_work_areas(free_floating_work_areas);
         IS_DRAGGING.store(true, Ordering::Relaxed);
+        Self::send_command(Command::MouseDragStarted(window_handle));
       }
     }
   }
 
   fn do_drag(cursor_point: POINT) {
     let drag_state = get_drag_state();
-    let drag_guard = match drag_state.lock() {
+    let mut drag_guard = match drag_state.lock() {
       Ok(guard) => guard,
       Err(_) => return,
     };
@@ -358,11 +563,30 @@ impl WindowsApiForDragging {
       return;
     }
     let drag_start_position = drag_guard.get_drag_start_position();
-    let window_start_position = drag_guard.get_window_start_position();
-    let delta_x = cursor_point.x - drag_start_position.x();
-    let delta_y = cursor_point.y - drag_start_position.y();
-    let new_x = window_start_position.x() + delta_x;
-    let new_y = window_start_position.y() + delta_y;
+    let window_start_rect = drag_guard.get_window_start_rect();
+    let (delta_x, delta_y) = Self::lock_to_dominant_axis_if_required(
+      cursor_point.x - drag_start_position.x(),
+      cursor_point.y - drag_start_position.y(),
+    );
+    let is_free_floating = drag_guard.is_free_floating_at(Point::from(cursor_point));
+    let cell_size = match is_free_floating {
+      true => 0,
+      false => Self::get_grid_cell_size(),
+    };
+    let new_x = geometry::snap_to_grid(window_start_rect.left + delta_x, cell_size);
+    let new_y = geometry::snap_to_grid(window_start_rect.top + delta_y, cell_size);
+    let new_rect = Rect::new(
+      new_x,
+      new_y,
+      new_x + window_start_rect.width(),
+      new_y + window_start_rect.height(),
+    );
+    let Rect {
+      left: new_x, top: new_y, ..
+    } = match is_free_floating {
+      true => new_rect,
+      false => Self::attract_to_edges(new_rect, drag_guard.get_work_areas(), drag_guard.get_other_windows()),
+    };
     let window_hwnd = match drag_guard.get_window_handle() {
       Some(handle) => handle.as_hwnd(),
       None => {
@@ -370,6 +594,12 @@ impl WindowsApiForDragging {
         return;
       }
     };
+    let snap_zone = Self::find_snap_zone(Point::from(cursor_point), drag_guard.get_work_areas());
+    if drag_guard.get_snap_zone() != snap_zone.as_ref() {
+      trace!("Cursor hovers over snap zone {:?}", snap_zone);
+      WindowsApiForSnapZonePreview::show(snap_zone.clone().map(Rect::from));
+      drag_guard.set_snap_zone(snap_zone);
+    }
     drop(drag_guard);
 
     trace!("Dragging window to ({}, {})", new_x, new_y);
@@ -388,10 +618,105 @@ impl WindowsApiForDragging {
     }
   }
 
+  /// Returns `true` if the Shift key is held down, which locks dragging to a single axis. Grid snapping and magnetic
+  /// edges remain active, so that both can be combined with it.
+  fn is_axis_lock_pressed() -> bool {
+    unsafe { (GetAsyncKeyState(VK_SHIFT.0 as i32) & 0x8000u16 as i16) != 0 }
+  }
+
+  /// Returns `true` if the Ctrl key is held down and is not part of the modifier, which allows the user to bypass grid
+  /// snapping, magnetic edges and snap zones.
+  fn is_bypass_pressed() -> bool {
+    !get_mouse_bindings().is_modifier(ModifierKey::Ctrl) && Self::is_modifier_key_down(ModifierKey::Ctrl)
+  }
+
+  /// Returns the offset locked to the axis in which the cursor has moved furthest while the Shift key is held down,
+  /// e.g. to slide a window along a row of windows without losing its alignment.
+  fn lock_to_dominant_axis_if_required(delta_x: i32, delta_y: i32) -> (i32, i32) {
+    if Self::is_axis_lock_pressed() {
+      return geometry::lock_to_dominant_axis(delta_x, delta_y);
+    }
+
+    (delta_x, delta_y)
+  }
+
+  /// Returns `rect` with its edges attracted to the given work areas and windows, unless the Ctrl key is held down.
+  fn attract_to_edges(rect: Rect, work_areas: &[Rect], other_windows: &[Rect]) -> Rect {
+    if Self::is_bypass_pressed() {
+      return rect;
+    }
+    let distance = *MAGNETIC_EDGE_DISTANCE_IN_PX.get().unwrap_or(&0);
+    let margin = *WINDOW_MARGIN.get().unwrap_or(&0);
+
+    geometry::attract_to_edges(rect, work_areas, other_windows, margin, distance)
+  }
+
+  /// Returns the snap zone of the work area under the cursor that the cursor hovers over (see [`Sizing::for_snap_zone`]),
+  /// unless the Ctrl key is held down.
+  fn find_snap_zone(cursor: Point, work_areas: &[Rect]) -> Option<Sizing> {
+    let distance = Self::get_snap_zone_distance();
+    if distance == 0 || Self::is_bypass_pressed() {
+      return None;
+    }
+    let margin = *WINDOW_MARGIN.get().unwrap_or(&0);
+
+    work_areas
+      .iter()
+      .find(|work_area| work_area.contains(&cursor))
+      .and_then(|work_area| Sizing::for_snap_zone(*work_area, cursor, distance, margin))
+  }
+
+  /// Returns the work areas of all monitors, split into those that windows are snapped to and those of monitors whose
+  /// active workspace is free-floating, on which windows are moved and resized freely.
+  fn get_work_areas() -> (Vec<Rect>, Vec<Rect>) {
+    let free_floating_monitors = get_free_floating_monitors();
+    let monitors = get_all_monitors();
+    let (free_floating, other): (Vec<_>, Vec<_>) = monitors
+      .get_all()
+      .into_iter()
+      .partition(|monitor| free_floating_monitors.contains(&monitor.id));
+
+    (
+      other.iter().map(|monitor| monitor.work_area).collect(),
+      free_floating.iter().map(|monitor| monitor.work_area).collect(),
+    )
+  }
+
+  fn get_snap_zone_distance() -> i32 {
+    *SNAP_ZONE_DISTANCE_IN_PX.get().unwrap_or(&0)
+  }
+
+  /// Returns the visible frame bounds (i.e. without invisible resize borders) of all visible, non-minimised, non-cloaked
+  /// top-level windows other than `dragged_window`, which are used as targets for magnetic window edges.
+  fn get_other_window_rects(dragged_window: HWND) -> Vec<Rect> {
+    let mut windows: Vec<(HWND, Rect)> = Vec::new();
+    unsafe {
+      if let Err(err) = EnumWindows(Some(enum_windows_callback), LPARAM(&mut windows as *mut _ as isize)) {
+        warn!("Failed to enumerate windows for magnetic edges because: {}", err.message());
+      }
+    }
+
+    windows
+      .into_iter()
+      .filter(|(hwnd, _)| *hwnd != dragged_window)
+      .filter(|(hwnd, _)| !IGNORED_CLASS_NAMES.contains(&Self::get_window_class_name(hwnd).as_str()))
+      .filter(|(hwnd, _)| !IGNORED_WINDOW_TITLES.contains(&Self::get_window_title(hwnd).as_str()))
+      .map(|(_, rect)| rect)
+      .collect()
+  }
+
   fn finish_dragging() {
     if let Ok(mut drag_state) = get_drag_state().lock() {
+      let dragged_window = drag_state.get_window_handle().copied();
+      let snap_zone = drag_state.get_snap_zone().cloned();
       drag_state.reset();
       IS_DRAGGING.store(false, Ordering::Relaxed);
+      if snap_zone.is_some() {
+        WindowsApiForSnapZonePreview::hide();
+      }
+      if let Some(window) = dragged_window {
+        Self::send_command(Command::MouseDragCompleted(window, snap_zone));
+      }
     }
   }
 
@@ -440,10 +765,16 @@ impl WindowsApiForDragging {
       }
       let resize_mode = Self::determine_resize_mode(cursor_position, &window_rect);
       let window_handle = WindowHandle::from(hwnd);
+      let (_, free_floating_work_areas) = Self::get_work_areas();
+      let is_free_floating = free_floating_work_areas
+        .iter()
+        .any(|work_area| work_area.contains(&cursor_position));
       if let Ok(mut resize_state) = get_resize_state().lock() {
         resize_state.set(cursor_position, window_handle, window_rect, resize_mode);
+        resize_state.set_free_floating(is_free_floating);
         IS_RESIZING.store(true, Ordering::Relaxed);
         debug!("Started resizing in [{:?}] mode", resize_mode);
+        Self::send_command(Command::MouseResizeStarted(window_handle));
       }
     }
   }
@@ -474,6 +805,10 @@ impl WindowsApiForDragging {
     };
     let resize_mode = resize_guard.get_resize_mode();
     let rect = resize_guard.get_window_start_rect();
+    let cell_size = match resize_guard.is_free_floating() {
+      true => 0,
+      false => Self::get_grid_cell_size(),
+    };
     let (new_left, new_top, new_width, new_height) = match resize_mode {
       ResizeMode::BottomRight => {
         let new_width = (rect.right - rect.left) + delta_x;
@@ -501,6 +836,14 @@ impl WindowsApiForDragging {
       }
     };
     drop(resize_guard);
+    let new_rect = Rect::new(new_left, new_top, new_left + new_width, new_top + new_height);
+    let snapped_rect = geometry::snap_resized_edges_to_grid(new_rect, resize_mode, cell_size);
+    let (new_left, new_top, new_width, new_height) = (
+      snapped_rect.left,
+      snapped_rect.top,
+      snapped_rect.width(),
+      snapped_rect.height(),
+    );
     let min_width = 200;
     let min_height = 50;
     let final_width = new_width.max(min_width);
@@ -525,6 +868,16 @@ impl WindowsApiForDragging {
     }
   }
 
+  /// Returns the configured grid cell size, or `0` (i.e. no snapping) while the Ctrl key is held down so that the
+  /// user can bypass the grid for individual operations.
+  fn get_grid_cell_size() -> i32 {
+    if Self::is_bypass_pressed() {
+      return 0;
+    }
+
+    *GRID_CELL_SIZE_IN_PX.get().unwrap_or(&0)
+  }
+
   fn finish_resizing() {
     if let Ok(mut resize_state) = get_resize_state().lock() {
       let sender = SENDER
@@ -547,6 +900,16 @@ impl WindowsApiForDragging {
     }
   }
 
+  fn send_command(command: Command) {
+    SENDER
+      .get()
+      .expect("Command sender not initialised")
+      .lock()
+      .expect("Failed to acquire command sender lock")
+      .send(command)
+      .expect("Failed to send mouse drag or resize command");
+  }
+
   fn determine_resize_mode(cursor_position: Point, window_rect: &Rect) -> ResizeMode {
     let distance_to_left = (cursor_position.x() - window_rect.left).abs();
     let distance_to_right = (cursor_position.x() - window_rect.right).abs();
@@ -633,16 +996,44 @@ impl WindowsApiForDragging {
 impl Drop for WindowsApiForDragging {
   fn drop(&mut self) {
     Self::uninstall_mouse_hook();
-    if let Some(keyboard_hook) = self.keyboard_hook_handle {
-      unsafe {
-        if let Err(err) = UnhookWindowsHookEx(keyboard_hook) {
-          error!("Failed to unhook keyboard hook: {}", err);
-        }
-      }
+    Self::uninstall_keyboard_hook();
+  }
+}
+
+extern "system" fn enum_windows_callback(hwnd: HWND, lparam: LPARAM) -> BOOL {
+  unsafe {
+    let windows = &mut *(lparam.0 as *mut Vec<(HWND, Rect)>);
+    if GetWindowTextLengthW(hwnd) == 0 {
+      return true.into();
+    }
+    if let Some(frame_bounds) = WindowsApiForFocusBorder::get_visible_frame_bounds(WindowHandle::from(hwnd)) {
+      windows.push((hwnd, frame_bounds));
     }
+
+    true.into()
   }
 }
 
+fn get_win_key_state() -> &'static Arc<Mutex<WinKeyState>> {
+  WIN_KEY_STATE.get_or_init(|| Arc::new(Mutex::new(WinKeyState::default())))
+}
+
+fn get_mouse_bindings() -> RwLockReadGuard<'static, MouseBindingsConfiguration> {
+  MOUSE_BINDINGS
+    .get()
+    .expect("Mouse bindings not initialised")
+    .read()
+    .expect(MOUSE_BINDINGS_LOCK)
+}
+
+fn get_free_floating_monitors() -> RwLockReadGuard<'static, HashSet<[u16; 32]>> {
+  FREE_FLOATING_MONITORS
+    .get()
+    .expect("Free-floating monitors not initialised")
+    .read()
+    .expect(FREE_FLOATING_MONITORS_LOCK)
+}
+
 fn get_drag_state() -> &'static Arc<Mutex<DragState>> {
   DRAG_STATE.get_or_init(|| Arc::new(Mutex::new(DragState::default())))
 }
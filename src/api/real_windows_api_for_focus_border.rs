@@ -0,0 +1,255 @@
+use crate::common::{Rect, WindowHandle};
+use crate::overlay::{OverlayHost, OverlayId, OverlayOptions, OverlayWindow};
+use std::ffi::c_void;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use windows::Win32::Foundation::{COLORREF, HWND, RECT};
+use windows::Win32::Graphics::Dwm::{DWMWA_CLOAKED, DWMWA_EXTENDED_FRAME_BOUNDS, DwmGetWindowAttribute};
+use windows::Win32::Graphics::Gdi::{
+  CombineRgn, CreateRectRgn, CreateSolidBrush, DeleteObject, FillRect, HDC, InvalidateRect, RGN_DIFF, SetWindowRgn,
+};
+use windows::Win32::UI::Accessibility::{HWINEVENTHOOK, SetWinEventHook, UnhookWinEvent};
+use windows::Win32::UI::WindowsAndMessaging::*;
+
+static OVERLAY: OnceLock<OverlayId> = OnceLock::new();
+static BORDER: OnceLock<Mutex<Option<Border>>> = OnceLock::new();
+
+/// The window around which the border is drawn, the colour of the border as a `COLORREF` and its width.
+#[derive(Copy, Clone, PartialEq, Eq)]
+struct Border {
+  window: WindowHandle,
+  colour: u32,
+  width_in_px: i32,
+}
+
+/// A border as drawn on screen, i.e. including the rect that the border window covers.
+#[derive(Copy, Clone, PartialEq, Eq)]
+struct DrawnBorder {
+  rect: Rect,
+  colour: u32,
+  width_in_px: i32,
+}
+
+/// This struct draws a coloured border around a window, using a click-through overlay whose region only consists of
+/// the border itself. Instead of polling, the overlay hooks the events of the window set via
+/// [`WindowsApiForFocusBorder::show`], so that it follows the window as it is moved or resized and hides the border
+/// while the window is minimised, hidden (e.g. on another workspace), closed, or cloaked.
+pub struct WindowsApiForFocusBorder;
+
+impl WindowsApiForFocusBorder {
+  pub fn initialise() -> Result<(), Box<dyn std::error::Error>> {
+    let options = OverlayOptions::click_through("Randolf Focus Border", 255);
+    let id = OverlayHost::add(options, || Box::new(FocusBorderWindow::default()))?;
+    let _ = OVERLAY.set(id);
+
+    Ok(())
+  }
+
+  /// Draws a border around the given window, where `colour` is a `COLORREF` (i.e. `0x00BBGGRR`).
+  pub fn show(window: WindowHandle, colour: u32, width_in_px: i32) {
+    *Self::border() = Some(Border {
+      window,
+      colour,
+      width_in_px,
+    });
+    Self::wake();
+  }
+
+  pub fn hide() {
+    *Self::border() = None;
+    Self::wake();
+  }
+
+  fn wake() {
+    if let Some(id) = OVERLAY.get() {
+      OverlayHost::wake(*id);
+    }
+  }
+
+  fn border() -> std::sync::MutexGuard<'static, Option<Border>> {
+    BORDER
+      .get_or_init(|| Mutex::new(None))
+      .lock()
+      .expect("Failed to lock focus border")
+  }
+
+  /// Wakes the overlay whenever the window with the border is closed, shown, hidden, moved, or (un)cloaked.
+  unsafe extern "system" fn handle_event(
+    _hook: HWINEVENTHOOK,
+    _event: u32,
+    hwnd: HWND,
+    id_object: i32,
+    id_child: i32,
+    _event_thread: u32,
+    _event_time: u32,
+  ) {
+    if id_object != OBJID_WINDOW.0 || id_child != CHILDID_SELF as i32 {
+      return;
+    }
+    if Self::border().is_some_and(|border| border.window.as_hwnd() == hwnd) {
+      Self::wake();
+    }
+  }
+
+  /// Returns the bounds of the window without its invisible resize borders, unless the window is closed, minimised,
+  /// hidden, or cloaked (e.g. because it is on another virtual desktop).
+  pub(crate) fn get_visible_frame_bounds(window: WindowHandle) -> Option<Rect> {
+    let hwnd = window.as_hwnd();
+    unsafe {
+      if !IsWindow(Some(hwnd)).as_bool() || !IsWindowVisible(hwnd).as_bool() || IsIconic(hwnd).as_bool() {
+        return None;
+      }
+      let mut cloaked = 0u32;
+      if DwmGetWindowAttribute(
+        hwnd,
+        DWMWA_CLOAKED,
+        &mut cloaked as *mut u32 as *mut c_void,
+        size_of::<u32>() as u32,
+      )
+      .is_ok()
+        && cloaked != 0
+      {
+        return None;
+      }
+      let mut rect = RECT::default();
+      DwmGetWindowAttribute(
+        hwnd,
+        DWMWA_EXTENDED_FRAME_BOUNDS,
+        &mut rect as *mut RECT as *mut c_void,
+        size_of::<RECT>() as u32,
+      )
+      .ok()?;
+
+      Some(Rect::from(rect))
+    }
+  }
+}
+
+/// The state of the border overlay, which only the thread of the overlay host uses.
+#[derive(Default)]
+struct FocusBorderWindow {
+  /// The border that is currently drawn, if any.
+  drawn_border: Option<DrawnBorder>,
+  /// The window with the border and the hooks that report its events, which are limited to the process of the window.
+  watched_window: Option<(WindowHandle, Vec<HWINEVENTHOOK>)>,
+}
+
+impl FocusBorderWindow {
+  /// Hooks the events of the given window instead of those of the previous one, unless it is watched already.
+  fn watch(&mut self, window: Option<WindowHandle>) {
+    if self.watched_window.as_ref().map(|(watched_window, _)| *watched_window) == window {
+      return;
+    }
+    if let Some((_, hooks)) = self.watched_window.take() {
+      for hook in hooks {
+        unsafe {
+          let _ = UnhookWinEvent(hook);
+        }
+      }
+    }
+    let Some(window) = window else {
+      return;
+    };
+    let mut process_id = 0;
+    unsafe { GetWindowThreadProcessId(window.as_hwnd(), Some(&mut process_id)) };
+    if process_id == 0 {
+      return;
+    }
+    // Destroying, showing, hiding, and moving a window (which includes minimising it) are adjacent events
+    let hooks = [
+      (EVENT_OBJECT_DESTROY, EVENT_OBJECT_LOCATIONCHANGE),
+      (EVENT_OBJECT_CLOAKED, EVENT_OBJECT_UNCLOAKED),
+    ]
+    .into_iter()
+    .filter_map(|(first_event, last_event)| {
+      let hook = unsafe {
+        SetWinEventHook(
+          first_event,
+          last_event,
+          None,
+          Some(WindowsApiForFocusBorder::handle_event),
+          process_id,
+          0,
+          WINEVENT_OUTOFCONTEXT,
+        )
+      };
+      if hook.is_invalid() {
+        warn!("Failed to hook events of {window}, the focus border will not follow it");
+        return None;
+      }
+      Some(hook)
+    })
+    .collect();
+    self.watched_window = Some((window, hooks));
+  }
+}
+
+impl OverlayWindow for FocusBorderWindow {
+  /// Watches the window with the border and moves the border to where the window currently is, if it has changed since
+  /// the last update.
+  fn update(&mut self, hwnd: HWND) -> Option<Duration> {
+    let border = *WindowsApiForFocusBorder::border();
+    self.watch(border.map(|border| border.window));
+    let target = border.and_then(|border| {
+      let rect = WindowsApiForFocusBorder::get_visible_frame_bounds(border.window)?;
+      let width = border.width_in_px;
+      Some(DrawnBorder {
+        rect: Rect::new(rect.left - width, rect.top - width, rect.right + width, rect.bottom + width),
+        colour: border.colour,
+        width_in_px: width,
+      })
+    });
+    if std::mem::replace(&mut self.drawn_border, target) == target {
+      return None;
+    }
+    let Some(target) = target else {
+      unsafe {
+        let _ = ShowWindow(hwnd, SW_HIDE);
+      }
+      return None;
+    };
+
+    unsafe {
+      let (width, height) = (target.rect.width(), target.rect.height());
+      if let Err(err) = SetWindowPos(
+        hwnd,
+        Some(HWND_TOPMOST),
+        target.rect.left,
+        target.rect.top,
+        width,
+        height,
+        SWP_NOACTIVATE | SWP_SHOWWINDOW,
+      ) {
+        warn!("Failed to position focus border: {err}");
+      }
+      // The region of the window is the area between the outer and the inner rect, which leaves the window itself
+      // uncovered. Windows takes ownership of the region once it has been set.
+      let region = CreateRectRgn(0, 0, width, height);
+      let inner_region = CreateRectRgn(
+        target.width_in_px,
+        target.width_in_px,
+        width - target.width_in_px,
+        height - target.width_in_px,
+      );
+      CombineRgn(Some(region), Some(region), Some(inner_region), RGN_DIFF);
+      let _ = DeleteObject(inner_region.into());
+      if SetWindowRgn(hwnd, Some(region), true) == 0 {
+        warn!("Failed to set region of focus border");
+        let _ = DeleteObject(region.into());
+      }
+      let _ = InvalidateRect(Some(hwnd), None, true);
+    }
+
+    None
+  }
+
+  fn paint(&mut self, _hwnd: HWND, hdc: HDC, rect: RECT) {
+    if let Some(border) = self.drawn_border {
+      unsafe {
+        let brush = CreateSolidBrush(COLORREF(border.colour));
+        FillRect(hdc, &rect, brush);
+        let _ = DeleteObject(brush.into());
+      }
+    }
+  }
+}
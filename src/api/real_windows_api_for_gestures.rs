@@ -0,0 +1,280 @@
+use crate::common::{Command, Direction, HookHealth, SwipeState};
+use crossbeam_channel::Sender;
+use std::collections::HashMap;
+use std::ffi::c_void;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::{mem, thread};
+use windows::Win32::Devices::HumanInterfaceDevice::{
+  HIDP_CAPS, HIDP_STATUS_SUCCESS, HIDP_VALUE_CAPS, HidP_GetCaps, HidP_GetUsageValue, HidP_GetValueCaps, HidP_Input,
+  PHIDP_PREPARSED_DATA,
+};
+use windows::Win32::Foundation::*;
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::Input::*;
+use windows::Win32::UI::WindowsAndMessaging::*;
+use windows::core::w;
+
+const HID_USAGE_PAGE_GENERIC: u16 = 0x01;
+const HID_USAGE_PAGE_DIGITIZER: u16 = 0x0D;
+const HID_USAGE_GENERIC_X: u16 = 0x30;
+const HID_USAGE_DIGITIZER_TOUCH_PAD: u16 = 0x05;
+const HID_USAGE_DIGITIZER_CONTACT_COUNT: u16 = 0x54;
+
+static SENDER: OnceLock<Mutex<Sender<Command>>> = OnceLock::new();
+static SWIPE_STATE: OnceLock<Mutex<SwipeState>> = OnceLock::new();
+static TOUCHPADS: OnceLock<Mutex<HashMap<isize, Touchpad>>> = OnceLock::new();
+static IS_REGISTERED: AtomicBool = AtomicBool::new(false);
+
+/// The parts of a touchpad's HID report descriptor that are needed to read the contact count and the horizontal
+/// position of the first contact from its input reports.
+struct Touchpad {
+  preparsed_data: Vec<u8>,
+  x_link_collection: u16,
+  width: i32,
+}
+
+/// This struct registers a hidden, message-only window for raw input from precision touchpads and turns horizontal
+/// swipes with three or more fingers into [`Command::NextWorkspace`] and [`Command::PreviousWorkspace`]. Like
+/// [`crate::api::real_windows_api_for_dragging::WindowsApiForDragging`], it is kept separate from
+/// [`crate::RealWindowsApi`] because it is isolated from everything else and very verbose. The window runs its own
+/// message loop on a separate thread, so that the many raw input messages do not slow down the main loop.
+pub struct WindowsApiForGestures;
+
+impl WindowsApiForGestures {
+  pub fn new(sender: Sender<Command>) -> Self {
+    SENDER.set(Mutex::new(sender)).expect("Failed to set command sender");
+    Self
+  }
+
+  pub fn initialise(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+    thread::Builder::new().name("touchpad-gestures".to_string()).spawn(|| {
+      if let Err(err) = Self::create_window_and_run_message_loop() {
+        error!("Touchpad gestures are disabled because: {err}");
+      }
+    })?;
+
+    Ok(())
+  }
+
+  /// Returns the health of the raw input registration for precision touchpads, which is only made if touchpad gestures
+  /// are enabled.
+  pub fn hook_health() -> HookHealth {
+    HookHealth::new(SENDER.get().is_some(), IS_REGISTERED.load(Ordering::Relaxed))
+  }
+
+  fn create_window_and_run_message_loop() -> windows::core::Result<()> {
+    unsafe {
+      let h_module = GetModuleHandleW(None)?;
+      let h_instance = HINSTANCE(h_module.0);
+      let class_name = w!("RandolfTouchpadGestures");
+      let window_class = WNDCLASSW {
+        lpfnWndProc: Some(Self::window_procedure),
+        hInstance: h_instance,
+        lpszClassName: class_name,
+        ..Default::default()
+      };
+      if RegisterClassW(&window_class) == 0 {
+        return Err(windows::core::Error::from_win32());
+      }
+      let hwnd = CreateWindowExW(
+        WINDOW_EX_STYLE::default(),
+        class_name,
+        w!("Randolf Touchpad Gestures"),
+        WINDOW_STYLE::default(),
+        0,
+        0,
+        0,
+        0,
+        Some(HWND_MESSAGE),
+        None,
+        Some(h_instance),
+        None,
+      )?;
+      let device = RAWINPUTDEVICE {
+        usUsagePage: HID_USAGE_PAGE_DIGITIZER,
+        usUsage: HID_USAGE_DIGITIZER_TOUCH_PAD,
+        dwFlags: RIDEV_INPUTSINK,
+        hwndTarget: hwnd,
+      };
+      RegisterRawInputDevices(&[device], mem::size_of::<RAWINPUTDEVICE>() as u32)?;
+      IS_REGISTERED.store(true, Ordering::Relaxed);
+      debug!("Registered for raw input from precision touchpads");
+
+      let mut message = MSG::default();
+      while GetMessageW(&mut message, None, 0, 0).as_bool() {
+        let _ = TranslateMessage(&message);
+        DispatchMessageW(&message);
+      }
+      IS_REGISTERED.store(false, Ordering::Relaxed);
+    }
+
+    Ok(())
+  }
+
+  extern "system" fn window_procedure(hwnd: HWND, message: u32, w_param: WPARAM, l_param: LPARAM) -> LRESULT {
+    if message == WM_INPUT {
+      Self::handle_raw_input(HRAWINPUT(l_param.0 as *mut c_void));
+    }
+
+    unsafe { DefWindowProcW(hwnd, message, w_param, l_param) }
+  }
+
+  fn handle_raw_input(raw_input_handle: HRAWINPUT) {
+    let Some(buffer) = Self::get_raw_input_data(raw_input_handle) else {
+      return;
+    };
+    let raw_input = unsafe { &*(buffer.as_ptr() as *const RAWINPUT) };
+    if raw_input.header.dwType != RIM_TYPEHID.0 {
+      return;
+    }
+    let device = raw_input.header.hDevice;
+    let mut touchpads = TOUCHPADS
+      .get_or_init(|| Mutex::new(HashMap::new()))
+      .lock()
+      .expect("Failed to lock touchpads");
+    if !touchpads.contains_key(&(device.0 as isize)) {
+      let Some(touchpad) = Self::get_touchpad(device) else {
+        return;
+      };
+      touchpads.insert(device.0 as isize, touchpad);
+    }
+    let touchpad = &touchpads[&(device.0 as isize)];
+
+    let hid = unsafe { &raw_input.data.hid };
+    let report_size = hid.dwSizeHid as usize;
+    let reports = unsafe { std::slice::from_raw_parts(hid.bRawData.as_ptr(), report_size * hid.dwCount as usize) };
+    for report in reports.chunks_exact(report_size.max(1)) {
+      let contact_count = Self::get_usage_value(
+        touchpad,
+        report,
+        HID_USAGE_PAGE_DIGITIZER,
+        0,
+        HID_USAGE_DIGITIZER_CONTACT_COUNT,
+      );
+      let x = Self::get_usage_value(
+        touchpad,
+        report,
+        HID_USAGE_PAGE_GENERIC,
+        touchpad.x_link_collection,
+        HID_USAGE_GENERIC_X,
+      );
+      // Reports without a contact count are continuation reports for the remaining contacts of the previous report
+      let (Some(contact_count), Some(x)) = (contact_count, x) else {
+        continue;
+      };
+      let direction = SWIPE_STATE
+        .get_or_init(|| Mutex::new(SwipeState::default()))
+        .lock()
+        .expect("Failed to lock swipe state")
+        .update(contact_count, x as i32, touchpad.width);
+      if let Some(direction) = direction {
+        Self::send_swipe_command(direction);
+      }
+    }
+  }
+
+  /// Returns the raw input data in a buffer of `u64`s, so that it is suitably aligned to be read as [`RAWINPUT`].
+  fn get_raw_input_data(raw_input_handle: HRAWINPUT) -> Option<Vec<u64>> {
+    let header_size = mem::size_of::<RAWINPUTHEADER>() as u32;
+    let mut size = 0;
+    unsafe {
+      GetRawInputData(raw_input_handle, RID_INPUT, None, &mut size, header_size);
+      let mut buffer = vec![0u64; (size as usize).div_ceil(mem::size_of::<u64>())];
+      let read = GetRawInputData(
+        raw_input_handle,
+        RID_INPUT,
+        Some(buffer.as_mut_ptr() as *mut c_void),
+        &mut size,
+        header_size,
+      );
+      if read == u32::MAX || read as usize > buffer.len() * mem::size_of::<u64>() {
+        trace!("Failed to read raw input data");
+        return None;
+      }
+
+      Some(buffer)
+    }
+  }
+
+  fn get_touchpad(device: HANDLE) -> Option<Touchpad> {
+    let mut size = 0;
+    unsafe {
+      GetRawInputDeviceInfoW(Some(device), RIDI_PREPARSEDDATA, None, &mut size);
+      let mut preparsed_data = vec![0u8; size as usize];
+      let read = GetRawInputDeviceInfoW(
+        Some(device),
+        RIDI_PREPARSEDDATA,
+        Some(preparsed_data.as_mut_ptr() as *mut c_void),
+        &mut size,
+      );
+      if read == u32::MAX || size == 0 {
+        warn!("Failed to get preparsed data for touchpad {:?}", device);
+        return None;
+      }
+      let preparsed_data_handle = PHIDP_PREPARSED_DATA(preparsed_data.as_ptr() as isize);
+      let mut capabilities = HIDP_CAPS::default();
+      if HidP_GetCaps(preparsed_data_handle, &mut capabilities) != HIDP_STATUS_SUCCESS {
+        warn!("Failed to get capabilities of touchpad {:?}", device);
+        return None;
+      }
+      let mut value_capabilities_length = capabilities.NumberInputValueCaps;
+      let mut value_capabilities = vec![HIDP_VALUE_CAPS::default(); value_capabilities_length as usize];
+      if HidP_GetValueCaps(
+        HidP_Input,
+        value_capabilities.as_mut_ptr(),
+        &mut value_capabilities_length,
+        preparsed_data_handle,
+      ) != HIDP_STATUS_SUCCESS
+      {
+        warn!("Failed to get value capabilities of touchpad {:?}", device);
+        return None;
+      }
+      let x_capability = value_capabilities.iter().find(|capability| {
+        capability.UsagePage == HID_USAGE_PAGE_GENERIC
+          && !capability.IsRange
+          && capability.Anonymous.NotRange.Usage == HID_USAGE_GENERIC_X
+      })?;
+      debug!("Found touchpad {:?} with logical width [{}]", device, x_capability.LogicalMax);
+
+      Some(Touchpad {
+        x_link_collection: x_capability.LinkCollection,
+        width: x_capability.LogicalMax - x_capability.LogicalMin,
+        preparsed_data,
+      })
+    }
+  }
+
+  fn get_usage_value(touchpad: &Touchpad, report: &[u8], usage_page: u16, link_collection: u16, usage: u16) -> Option<u32> {
+    let mut value = 0;
+    let status = unsafe {
+      HidP_GetUsageValue(
+        HidP_Input,
+        usage_page,
+        link_collection,
+        usage,
+        &mut value,
+        PHIDP_PREPARSED_DATA(touchpad.preparsed_data.as_ptr() as isize),
+        report,
+      )
+    };
+
+    (status == HIDP_STATUS_SUCCESS).then_some(value)
+  }
+
+  /// Swiping to the left reveals the workspace to the right and vice versa, just like native virtual desktops.
+  fn send_swipe_command(direction: Direction) {
+    let command = match direction {
+      Direction::Left => Command::NextWorkspace,
+      Direction::Right => Command::PreviousWorkspace,
+      Direction::Up | Direction::Down => return,
+    };
+    let Some(sender) = SENDER.get() else {
+      error!("Failed to send touchpad gesture command because command sender is not set");
+      return;
+    };
+    if let Err(err) = sender.lock().expect("Failed to lock command sender").send(command) {
+      error!("Failed to send touchpad gesture command: {err}");
+    }
+  }
+}
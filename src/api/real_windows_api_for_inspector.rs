@@ -0,0 +1,142 @@
+use crate::api::{RealWindowsApi, WindowsApi};
+use crate::common::{WindowHandle, WindowInspection};
+use crate::overlay::{OverlayHost, OverlayId, OverlayOptions, OverlayWindow};
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use windows::Win32::Foundation::{HWND, POINT, RECT};
+use windows::Win32::Graphics::Gdi::{
+  COLOR_INFOBK, DT_CALCRECT, DT_LEFT, DrawTextW, FillRect, GetDC, GetSysColorBrush, HDC, InvalidateRect, ReleaseDC,
+  SetBkMode, TRANSPARENT,
+};
+use windows::Win32::UI::Input::KeyboardAndMouse::{GetAsyncKeyState, VK_CONTROL, VK_MENU};
+use windows::Win32::UI::WindowsAndMessaging::*;
+
+const UPDATE_INTERVAL: Duration = Duration::from_millis(100);
+const CURSOR_OFFSET_IN_PX: i32 = 16;
+const PADDING_IN_PX: i32 = 6;
+
+static IS_ENABLED: AtomicBool = AtomicBool::new(false);
+static OVERLAY: OnceLock<OverlayId> = OnceLock::new();
+static WINDOWS_API: OnceLock<RealWindowsApi> = OnceLock::new();
+
+/// This struct shows a small tooltip next to the cursor while `Ctrl` + `Alt` is held and the inspector is enabled. The
+/// tooltip describes the window under the cursor, including whether this application considers it managed, to help
+/// with debugging exclusion rules. The overlay only checks the cursor regularly while the inspector is enabled.
+pub struct WindowsApiForInspector;
+
+impl WindowsApiForInspector {
+  pub fn new(windows_api: RealWindowsApi) -> Self {
+    WINDOWS_API.set(windows_api).expect("Failed to set windows API");
+    Self
+  }
+
+  pub fn initialise(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+    let options = OverlayOptions::click_through("Randolf Window Inspector", 230);
+    let id = OverlayHost::add(options, || Box::new(InspectorWindow::default()))?;
+    let _ = OVERLAY.set(id);
+
+    Ok(())
+  }
+
+  pub fn is_enabled() -> bool {
+    IS_ENABLED.load(Ordering::Relaxed)
+  }
+
+  pub fn set_enabled(is_enabled: bool) {
+    IS_ENABLED.store(is_enabled, Ordering::Relaxed);
+    if let Some(id) = OVERLAY.get() {
+      OverlayHost::wake(*id);
+    }
+  }
+
+  fn inspect(handle: WindowHandle) -> Option<WindowInspection> {
+    let api = WINDOWS_API.get()?;
+
+    Some(WindowInspection {
+      title: api.get_window_title(&handle),
+      class_name: api.get_window_class_name(&handle),
+      process_name: api.get_window_process_name(handle),
+      is_managed: !api.is_not_a_managed_window(&handle),
+    })
+  }
+}
+
+/// The tooltip overlay, which is enabled via [`WindowsApiForInspector::set_enabled`].
+#[derive(Default)]
+struct InspectorWindow {
+  /// The description of the window under the cursor, which is measured when the tooltip is positioned.
+  text: Vec<u16>,
+}
+
+impl OverlayWindow for InspectorWindow {
+  /// Describes the window under the cursor while `Ctrl` + `Alt` is held, checking the cursor regularly while the
+  /// inspector is enabled.
+  fn update(&mut self, hwnd: HWND) -> Option<Duration> {
+    if !WindowsApiForInspector::is_enabled() {
+      unsafe {
+        let _ = ShowWindow(hwnd, SW_HIDE);
+      }
+      return None;
+    }
+    let is_modifier_held = unsafe { GetAsyncKeyState(VK_CONTROL.0 as i32) < 0 && GetAsyncKeyState(VK_MENU.0 as i32) < 0 };
+    if !is_modifier_held {
+      unsafe {
+        let _ = ShowWindow(hwnd, SW_HIDE);
+      }
+      return Some(UPDATE_INTERVAL);
+    }
+
+    let mut cursor = POINT::default();
+    let target = unsafe {
+      if GetCursorPos(&mut cursor).is_err() {
+        return Some(UPDATE_INTERVAL);
+      }
+      GetAncestor(WindowFromPoint(cursor), GA_ROOT)
+    };
+    if target.0.is_null() || target == hwnd {
+      return Some(UPDATE_INTERVAL);
+    }
+    let Some(inspection) = WindowsApiForInspector::inspect(WindowHandle::from(target)) else {
+      return Some(UPDATE_INTERVAL);
+    };
+    let mut text = inspection.to_string().encode_utf16().collect::<Vec<u16>>();
+
+    unsafe {
+      let mut rect = RECT::default();
+      let hdc = GetDC(Some(hwnd));
+      DrawTextW(hdc, &mut text, &mut rect, DT_CALCRECT | DT_LEFT);
+      ReleaseDC(Some(hwnd), hdc);
+      self.text = text;
+      if let Err(err) = SetWindowPos(
+        hwnd,
+        Some(HWND_TOPMOST),
+        cursor.x + CURSOR_OFFSET_IN_PX,
+        cursor.y + CURSOR_OFFSET_IN_PX,
+        rect.right - rect.left + 2 * PADDING_IN_PX,
+        rect.bottom - rect.top + 2 * PADDING_IN_PX,
+        SWP_NOACTIVATE | SWP_SHOWWINDOW,
+      ) {
+        warn!("Failed to position window inspector: {err}");
+      }
+      let _ = InvalidateRect(Some(hwnd), None, true);
+    }
+
+    Some(UPDATE_INTERVAL)
+  }
+
+  fn paint(&mut self, _hwnd: HWND, hdc: HDC, rect: RECT) {
+    unsafe {
+      FillRect(hdc, &rect, GetSysColorBrush(COLOR_INFOBK));
+      SetBkMode(hdc, TRANSPARENT);
+      let mut text_rect = RECT {
+        left: rect.left + PADDING_IN_PX,
+        top: rect.top + PADDING_IN_PX,
+        right: rect.right - PADDING_IN_PX,
+        bottom: rect.bottom - PADDING_IN_PX,
+      };
+      let mut text = self.text.clone();
+      DrawTextW(hdc, &mut text, &mut text_rect, DT_LEFT);
+    }
+  }
+}
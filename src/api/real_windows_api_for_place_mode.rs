@@ -0,0 +1,266 @@
+use crate::common::{Command, Rect, Sizing, WindowHandle};
+use crate::overlay::{OverlayHost, OverlayId, OverlayOptions, OverlayWindow};
+use crossbeam_channel::Sender;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use windows::Win32::Foundation::{COLORREF, HWND, LPARAM, LRESULT, RECT, WPARAM};
+use windows::Win32::Graphics::Gdi::{
+  COLOR_BTNFACE, COLOR_HIGHLIGHT, COLOR_HIGHLIGHTTEXT, COLOR_WINDOW, COLOR_WINDOWTEXT, DT_CENTER, DT_NOPREFIX,
+  DT_SINGLELINE, DT_VCENTER, DrawTextW, FillRect, GetSysColor, GetSysColorBrush, HDC, InvalidateRect, SetBkMode,
+  SetTextColor, TRANSPARENT,
+};
+use windows::Win32::UI::Input::KeyboardAndMouse::{SetFocus, VIRTUAL_KEY, VK_ESCAPE, VK_RETURN};
+use windows::Win32::UI::WindowsAndMessaging::*;
+
+/// The opacity of the overlay, where `0` is fully transparent and `255` is opaque.
+const ALPHA: u8 = 176;
+const LABEL_SIZE_IN_PX: i32 = 32;
+const HINT_HEIGHT_IN_PX: i32 = 28;
+const PADDING_IN_PX: i32 = 8;
+
+static SENDER: OnceLock<Mutex<Sender<Command>>> = OnceLock::new();
+static OVERLAY: OnceLock<OverlayId> = OnceLock::new();
+static IS_OPEN: AtomicBool = AtomicBool::new(false);
+static REQUEST: OnceLock<Mutex<Option<Request>>> = OnceLock::new();
+
+/// The window to place, the work area of its monitor, which the overlay covers, and the zones it can be placed in.
+struct Request {
+  window: WindowHandle,
+  work_area: Rect,
+  zones: Vec<Sizing>,
+  hint: &'static str,
+}
+
+/// This struct shows the zones that the foreground window can be placed in over the work area of its monitor, each
+/// labelled with the number that selects it, from `1` to `9` followed by `0`. The selected zone is filled with the
+/// highlight colour of the current Windows theme and `Enter` sends the window into it as a [`Command`], while `Esc`
+/// cancels. Like the window switcher, the overlay takes the focus while it is open, so that it receives the pressed
+/// keys, and closes as soon as it loses the focus. `Enter` and `Esc` return the focus to the window it was opened for.
+pub struct WindowsApiForPlaceMode;
+
+impl WindowsApiForPlaceMode {
+  pub fn initialise(sender: Sender<Command>) -> Result<(), Box<dyn std::error::Error>> {
+    SENDER.set(Mutex::new(sender)).expect("Failed to set command sender");
+    let options = OverlayOptions {
+      title: "Randolf Place Mode",
+      ex_style: WS_EX_TOPMOST | WS_EX_TOOLWINDOW | WS_EX_LAYERED,
+      style: WS_POPUP,
+      alpha: Some(ALPHA),
+    };
+    let id = OverlayHost::add(options, || Box::new(PlaceModeWindow::default()))?;
+    let _ = OVERLAY.set(id);
+
+    Ok(())
+  }
+
+  pub fn is_open() -> bool {
+    IS_OPEN.load(Ordering::Relaxed)
+  }
+
+  /// Opens place mode for the given window, showing its zones over the given work area.
+  pub fn open(window: WindowHandle, work_area: Rect, zones: Vec<Sizing>, hint: &'static str) {
+    *Self::request() = Some(Request {
+      window,
+      work_area,
+      zones,
+      hint,
+    });
+    IS_OPEN.store(true, Ordering::Relaxed);
+    if let Some(id) = OVERLAY.get() {
+      OverlayHost::wake(*id);
+    }
+  }
+
+  fn request() -> std::sync::MutexGuard<'static, Option<Request>> {
+    REQUEST
+      .get_or_init(|| Mutex::new(None))
+      .lock()
+      .expect("Failed to lock place mode request")
+  }
+}
+
+/// The place mode overlay, whose state is only used by the thread of the overlay host while place mode is open.
+#[derive(Default)]
+struct PlaceModeWindow {
+  window: Option<WindowHandle>,
+  work_area: Rect,
+  zones: Vec<Sizing>,
+  hint: Vec<u16>,
+  selected: Option<usize>,
+}
+
+impl PlaceModeWindow {
+  fn show(&mut self, hwnd: HWND, request: Request) {
+    let work_area = request.work_area;
+    *self = PlaceModeWindow {
+      window: Some(request.window),
+      work_area,
+      zones: request.zones,
+      hint: request.hint.encode_utf16().collect(),
+      selected: None,
+    };
+    unsafe {
+      if let Err(err) = SetWindowPos(
+        hwnd,
+        Some(HWND_TOPMOST),
+        work_area.left,
+        work_area.top,
+        work_area.width(),
+        work_area.height(),
+        SWP_SHOWWINDOW,
+      ) {
+        warn!("Failed to position place mode overlay: {err}");
+      }
+      // Without the focus, the overlay would neither receive the pressed keys nor close when the user clicks elsewhere
+      if !SetForegroundWindow(hwnd).as_bool() {
+        warn!("Failed to focus place mode overlay, closing it again");
+        self.close(hwnd, false);
+        return;
+      }
+      let _ = SetFocus(Some(hwnd));
+      let _ = InvalidateRect(Some(hwnd), None, true);
+    }
+  }
+
+  fn handle_key(&mut self, hwnd: HWND, key: VIRTUAL_KEY) {
+    match key {
+      VK_ESCAPE => self.close(hwnd, true),
+      VK_RETURN => self.place(hwnd),
+      _ => {}
+    }
+  }
+
+  /// Selects the zone whose number was typed, where `1` selects the first zone and `0` the tenth.
+  fn handle_character(&mut self, hwnd: HWND, character: u32) {
+    let Some(digit) = char::from_u32(character).and_then(|character| character.to_digit(10)) else {
+      return;
+    };
+    let index = (digit as usize + 9) % 10;
+    if index >= self.zones.len() {
+      return;
+    }
+    self.selected = Some(index);
+    unsafe {
+      let _ = InvalidateRect(Some(hwnd), None, true);
+    }
+  }
+
+  /// Closes place mode and asks the main loop to move the window into the selected zone. Does nothing if no zone is
+  /// selected yet.
+  fn place(&mut self, hwnd: HWND) {
+    let (Some(window), Some(zone)) = (self.window, self.selected.and_then(|index| self.zones.get(index).cloned())) else {
+      return;
+    };
+    self.close(hwnd, true);
+    if let Some(sender) = SENDER.get() {
+      sender
+        .lock()
+        .expect("Failed to lock command sender")
+        .send(Command::PlaceWindowInZone(window, zone))
+        .expect("Failed to send command");
+    }
+  }
+
+  /// Hides the overlay and, if `is_returning_focus` is `true`, focuses the window that place mode was opened for again.
+  /// The focus is not returned if the overlay closes because the user focused another window.
+  fn close(&mut self, hwnd: HWND, is_returning_focus: bool) {
+    if !IS_OPEN.swap(false, Ordering::Relaxed) {
+      return;
+    }
+    let window = self.window.take();
+    *self = PlaceModeWindow::default();
+    unsafe {
+      let _ = ShowWindow(hwnd, SW_HIDE);
+      if is_returning_focus && let Some(window) = window {
+        let _ = SetForegroundWindow(window.as_hwnd());
+      }
+    }
+  }
+
+  /// Returns the rect of a zone relative to the overlay, which covers the work area.
+  fn to_client_rect(&self, zone: &Sizing) -> RECT {
+    RECT {
+      left: zone.x - self.work_area.left,
+      top: zone.y - self.work_area.top,
+      right: zone.x + zone.width - self.work_area.left,
+      bottom: zone.y + zone.height - self.work_area.top,
+    }
+  }
+
+  fn draw_text(hdc: HDC, text: &[u16], mut rect: RECT) {
+    let mut text = text.to_vec();
+    unsafe {
+      DrawTextW(
+        hdc,
+        &mut text,
+        &mut rect,
+        DT_CENTER | DT_VCENTER | DT_SINGLELINE | DT_NOPREFIX,
+      );
+    }
+  }
+}
+
+impl OverlayWindow for PlaceModeWindow {
+  fn update(&mut self, hwnd: HWND) -> Option<Duration> {
+    let request = WindowsApiForPlaceMode::request().take();
+    if let Some(request) = request {
+      self.show(hwnd, request);
+    }
+
+    None
+  }
+
+  fn handle_message(&mut self, hwnd: HWND, message: u32, w_param: WPARAM, _l_param: LPARAM) -> Option<LRESULT> {
+    match message {
+      WM_KEYDOWN => self.handle_key(hwnd, VIRTUAL_KEY(w_param.0 as u16)),
+      WM_CHAR => self.handle_character(hwnd, w_param.0 as u32),
+      WM_ACTIVATE => {
+        if (w_param.0 & 0xFFFF) as u32 == WA_INACTIVE {
+          self.close(hwnd, false);
+        }
+      }
+      _ => return None,
+    }
+
+    Some(LRESULT(0))
+  }
+
+  /// Draws the hint along the top edge, fills the selected zone, and draws the number of every zone in its centre.
+  fn paint(&mut self, _hwnd: HWND, hdc: HDC, rect: RECT) {
+    unsafe {
+      FillRect(hdc, &rect, GetSysColorBrush(COLOR_WINDOW));
+      SetBkMode(hdc, TRANSPARENT);
+      if let Some(zone) = self.selected.and_then(|index| self.zones.get(index)) {
+        FillRect(hdc, &self.to_client_rect(zone), GetSysColorBrush(COLOR_HIGHLIGHT));
+      }
+      SetTextColor(hdc, COLORREF(GetSysColor(COLOR_WINDOWTEXT)));
+      let hint_rect = RECT {
+        left: rect.left + PADDING_IN_PX,
+        top: rect.top + PADDING_IN_PX,
+        right: rect.right - PADDING_IN_PX,
+        bottom: rect.top + PADDING_IN_PX + HINT_HEIGHT_IN_PX,
+      };
+      Self::draw_text(hdc, &self.hint, hint_rect);
+      for (index, zone) in self.zones.iter().enumerate() {
+        let zone_rect = self.to_client_rect(zone);
+        let (centre_x, centre_y) = ((zone_rect.left + zone_rect.right) / 2, (zone_rect.top + zone_rect.bottom) / 2);
+        let label_rect = RECT {
+          left: centre_x - LABEL_SIZE_IN_PX / 2,
+          top: centre_y - LABEL_SIZE_IN_PX / 2,
+          right: centre_x + LABEL_SIZE_IN_PX / 2,
+          bottom: centre_y + LABEL_SIZE_IN_PX / 2,
+        };
+        if self.selected == Some(index) {
+          FillRect(hdc, &label_rect, GetSysColorBrush(COLOR_HIGHLIGHT));
+          SetTextColor(hdc, COLORREF(GetSysColor(COLOR_HIGHLIGHTTEXT)));
+        } else {
+          FillRect(hdc, &label_rect, GetSysColorBrush(COLOR_BTNFACE));
+          SetTextColor(hdc, COLORREF(GetSysColor(COLOR_WINDOWTEXT)));
+        }
+        let label = ((index + 1) % 10).to_string().encode_utf16().collect::<Vec<_>>();
+        Self::draw_text(hdc, &label, label_rect);
+      }
+    }
+  }
+}
@@ -0,0 +1,95 @@
+use crate::common::Rect;
+use crate::overlay::{OverlayHost, OverlayId, OverlayOptions, OverlayWindow};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use windows::Win32::Foundation::{HWND, RECT};
+use windows::Win32::Graphics::Gdi::{COLOR_HIGHLIGHT, FillRect, GetSysColorBrush, HDC};
+use windows::Win32::UI::WindowsAndMessaging::*;
+
+/// The opacity of the preview, where `0` is fully transparent and `255` is opaque.
+const ALPHA: u8 = 96;
+
+static OVERLAY: OnceLock<OverlayId> = OnceLock::new();
+static ZONE: OnceLock<Mutex<Option<Rect>>> = OnceLock::new();
+
+/// This struct shows a translucent, click-through overlay over the snap zone that a window dragged with the mouse will
+/// be snapped into once the mouse button is released. Showing it only wakes the overlay, so that it never delays the
+/// mouse hook. It is filled with the highlight colour of the current Windows theme. Unlike the other overlays, it is
+/// not topmost, because it is placed right below the dragged window.
+pub struct WindowsApiForSnapZonePreview;
+
+impl WindowsApiForSnapZonePreview {
+  pub fn initialise() -> Result<(), Box<dyn std::error::Error>> {
+    let options = OverlayOptions {
+      ex_style: WS_EX_TOOLWINDOW | WS_EX_NOACTIVATE | WS_EX_LAYERED | WS_EX_TRANSPARENT,
+      ..OverlayOptions::click_through("Randolf Snap Zone Preview", ALPHA)
+    };
+    let id = OverlayHost::add(options, || Box::new(SnapZonePreviewWindow))?;
+    let _ = OVERLAY.set(id);
+
+    Ok(())
+  }
+
+  /// Shows the preview over the given zone or, if it is `None`, hides it. Does nothing if the zone has not changed.
+  pub fn show(zone: Option<Rect>) {
+    let mut current_zone = Self::zone();
+    if *current_zone != zone {
+      *current_zone = zone;
+      if let Some(id) = OVERLAY.get() {
+        OverlayHost::wake(*id);
+      }
+    }
+  }
+
+  pub fn hide() {
+    Self::show(None);
+  }
+
+  fn zone() -> std::sync::MutexGuard<'static, Option<Rect>> {
+    ZONE
+      .get_or_init(|| Mutex::new(None))
+      .lock()
+      .expect("Failed to lock snap zone preview")
+  }
+}
+
+/// The preview overlay, whose state is shared with [`WindowsApiForSnapZonePreview`].
+struct SnapZonePreviewWindow;
+
+impl OverlayWindow for SnapZonePreviewWindow {
+  fn update(&mut self, hwnd: HWND) -> Option<Duration> {
+    let zone = *WindowsApiForSnapZonePreview::zone();
+    unsafe {
+      let Some(zone) = zone else {
+        let _ = ShowWindow(hwnd, SW_HIDE);
+        return None;
+      };
+      // The preview is placed right below the dragged window, which is the foreground window, and above all others
+      let foreground_window = GetForegroundWindow();
+      let insert_after = if foreground_window.0.is_null() {
+        HWND_TOP
+      } else {
+        foreground_window
+      };
+      if let Err(err) = SetWindowPos(
+        hwnd,
+        Some(insert_after),
+        zone.left,
+        zone.top,
+        zone.width(),
+        zone.height(),
+        SWP_NOACTIVATE | SWP_SHOWWINDOW,
+      ) {
+        warn!("Failed to position snap zone preview: {err}");
+      }
+    }
+
+    None
+  }
+
+  fn paint(&mut self, _hwnd: HWND, hdc: HDC, rect: RECT) {
+    unsafe {
+      FillRect(hdc, &rect, GetSysColorBrush(COLOR_HIGHLIGHT));
+    }
+  }
+}
@@ -0,0 +1,177 @@
+use crate::common::{Command, HookHealth, LocationChangeDebouncer, WindowHandle};
+use crossbeam_channel::Sender;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::Instant;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::Accessibility::{HWINEVENTHOOK, SetWinEventHook, UnhookWinEvent};
+use windows::Win32::UI::WindowsAndMessaging::{
+  CHILDID_SELF, DispatchMessageW, EVENT_OBJECT_LOCATIONCHANGE, EVENT_OBJECT_NAMECHANGE, EVENT_SYSTEM_FOREGROUND, GA_ROOT,
+  GetAncestor, GetMessageW, IsWindowVisible, MSG, OBJID_WINDOW, SetTimer, TranslateMessage, WINEVENT_OUTOFCONTEXT,
+  WINEVENT_SKIPOWNPROCESS,
+};
+
+const FLUSH_INTERVAL_IN_MS: u32 = 100;
+
+static SENDER: OnceLock<Mutex<Sender<Command>>> = OnceLock::new();
+static DEBOUNCER: OnceLock<Mutex<LocationChangeDebouncer>> = OnceLock::new();
+static TITLE_DEBOUNCER: OnceLock<Mutex<LocationChangeDebouncer>> = OnceLock::new();
+static IS_HOOKED: AtomicBool = AtomicBool::new(false);
+
+/// This struct listens to location changes of top-level windows made by any other process, such as Windows Snap or
+/// FancyZones, and reports them as [`Command::WindowLocationChanged`] once a window has stopped moving. Title changes
+/// are debounced the same way and reported as [`Command::WindowTitleChanged`], so that windows which only set their
+/// real title after being shown are re-evaluated against the exclusion settings. Changes of the foreground window are
+/// reported straight away as [`Command::ForegroundWindowChanged`], e.g. to detect applications stealing focus. Like
+/// [`crate::api::real_windows_api_for_gestures::WindowsApiForGestures`], the event hook runs its own message loop on
+/// a separate thread because location changes are far too frequent to be processed by the main loop.
+pub struct WindowsApiForWindowEvents;
+
+impl WindowsApiForWindowEvents {
+  pub fn new(sender: Sender<Command>) -> Self {
+    SENDER.set(Mutex::new(sender)).expect("Failed to set command sender");
+    Self
+  }
+
+  pub fn initialise(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+    thread::Builder::new().name("window-events".to_string()).spawn(|| {
+      if let Err(err) = Self::hook_events_and_run_message_loop() {
+        error!("Detecting external window location and title changes is disabled because: {err}");
+      }
+    })?;
+
+    Ok(())
+  }
+
+  /// Returns the health of the window event hook, which is installed on a separate thread.
+  pub fn hook_health() -> HookHealth {
+    HookHealth::new(SENDER.get().is_some(), IS_HOOKED.load(Ordering::Relaxed))
+  }
+
+  fn hook_events_and_run_message_loop() -> Result<(), String> {
+    unsafe {
+      // The range covers both location and name changes because the two events are adjacent
+      let hook = SetWinEventHook(
+        EVENT_OBJECT_LOCATIONCHANGE,
+        EVENT_OBJECT_NAMECHANGE,
+        None,
+        Some(Self::handle_event),
+        0,
+        0,
+        WINEVENT_OUTOFCONTEXT | WINEVENT_SKIPOWNPROCESS,
+      );
+      if hook.is_invalid() {
+        return Err("Failed to set window event hook".to_string());
+      }
+      let foreground_hook = SetWinEventHook(
+        EVENT_SYSTEM_FOREGROUND,
+        EVENT_SYSTEM_FOREGROUND,
+        None,
+        Some(Self::handle_foreground_event),
+        0,
+        0,
+        WINEVENT_OUTOFCONTEXT | WINEVENT_SKIPOWNPROCESS,
+      );
+      if foreground_hook.is_invalid() {
+        warn!("Failed to set foreground event hook, do-not-disturb workspaces will not prevent focus stealing");
+      }
+      if SetTimer(None, 0, FLUSH_INTERVAL_IN_MS, Some(Self::send_settled_changes)) == 0 {
+        let _ = UnhookWinEvent(hook);
+        let _ = UnhookWinEvent(foreground_hook);
+        return Err("Failed to set timer".to_string());
+      }
+      IS_HOOKED.store(true, Ordering::Relaxed);
+      debug!("Registered for location and title changes of windows");
+
+      let mut message = MSG::default();
+      while GetMessageW(&mut message, None, 0, 0).as_bool() {
+        let _ = TranslateMessage(&message);
+        DispatchMessageW(&message);
+      }
+      IS_HOOKED.store(false, Ordering::Relaxed);
+      let _ = UnhookWinEvent(hook);
+      let _ = UnhookWinEvent(foreground_hook);
+    }
+
+    Ok(())
+  }
+
+  unsafe extern "system" fn handle_event(
+    _hook: HWINEVENTHOOK,
+    event: u32,
+    hwnd: HWND,
+    id_object: i32,
+    id_child: i32,
+    _event_thread: u32,
+    _event_time: u32,
+  ) {
+    if hwnd.0.is_null() || id_object != OBJID_WINDOW.0 || id_child != CHILDID_SELF as i32 {
+      return;
+    }
+    unsafe {
+      if GetAncestor(hwnd, GA_ROOT) != hwnd || !IsWindowVisible(hwnd).as_bool() {
+        return;
+      }
+    }
+    let debouncer = match event {
+      EVENT_OBJECT_LOCATIONCHANGE => &DEBOUNCER,
+      EVENT_OBJECT_NAMECHANGE => &TITLE_DEBOUNCER,
+      _ => return,
+    };
+    debouncer
+      .get_or_init(|| Mutex::new(LocationChangeDebouncer::default()))
+      .lock()
+      .expect("Failed to lock window event debouncer")
+      .record(WindowHandle::from(hwnd), Instant::now());
+  }
+
+  unsafe extern "system" fn handle_foreground_event(
+    _hook: HWINEVENTHOOK,
+    _event: u32,
+    hwnd: HWND,
+    id_object: i32,
+    _id_child: i32,
+    _event_thread: u32,
+    _event_time: u32,
+  ) {
+    if hwnd.0.is_null() || id_object != OBJID_WINDOW.0 {
+      return;
+    }
+    let Some(sender) = SENDER.get() else {
+      error!("Failed to send foreground change because command sender is not set");
+      return;
+    };
+    let command = Command::ForegroundWindowChanged(WindowHandle::from(hwnd));
+    if let Err(err) = sender.lock().expect("Failed to lock command sender").send(command) {
+      error!("Failed to send foreground change: {err}");
+    }
+  }
+
+  unsafe extern "system" fn send_settled_changes(_hwnd: HWND, _message: u32, _id: usize, _time: u32) {
+    Self::send_settled(&DEBOUNCER, Command::WindowLocationChanged);
+    Self::send_settled(&TITLE_DEBOUNCER, Command::WindowTitleChanged);
+  }
+
+  fn send_settled(debouncer: &OnceLock<Mutex<LocationChangeDebouncer>>, to_command: fn(WindowHandle) -> Command) {
+    let settled = debouncer
+      .get_or_init(|| Mutex::new(LocationChangeDebouncer::default()))
+      .lock()
+      .expect("Failed to lock window event debouncer")
+      .take_settled(Instant::now());
+    if settled.is_empty() {
+      return;
+    }
+    let Some(sender) = SENDER.get() else {
+      error!("Failed to send window event command because command sender is not set");
+      return;
+    };
+    let sender = sender.lock().expect("Failed to lock command sender");
+    for handle in settled {
+      let command = to_command(handle);
+      if let Err(err) = sender.send(command) {
+        error!("Failed to send window event command: {err}");
+      }
+    }
+  }
+}
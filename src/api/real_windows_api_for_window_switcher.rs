@@ -0,0 +1,595 @@
+use crate::common::{Command, Rect, WindowHandle, WindowSummary, WindowVisibility, geometry};
+use crate::overlay::{OverlayHost, OverlayId, OverlayOptions, OverlayWindow};
+use crossbeam_channel::Sender;
+use std::collections::HashMap;
+use std::ffi::c_void;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use windows::Win32::Foundation::{COLORREF, HWND, LPARAM, LRESULT, RECT, WPARAM};
+use windows::Win32::Graphics::Dwm::{
+  DWM_THUMBNAIL_PROPERTIES, DWM_TNP_RECTDESTINATION, DWM_TNP_SOURCECLIENTAREAONLY, DWM_TNP_VISIBLE,
+  DwmQueryThumbnailSourceSize, DwmRegisterThumbnail, DwmUnregisterThumbnail, DwmUpdateThumbnailProperties,
+};
+use windows::Win32::Graphics::Gdi::{
+  BI_RGB, BITMAPINFO, BITMAPINFOHEADER, COLOR_GRAYTEXT, COLOR_HIGHLIGHT, COLOR_HIGHLIGHTTEXT, COLOR_WINDOW,
+  COLOR_WINDOWTEXT, CreateCompatibleBitmap, CreateCompatibleDC, DIB_RGB_COLORS, DRAW_TEXT_FORMAT, DT_END_ELLIPSIS, DT_LEFT,
+  DT_NOPREFIX, DT_RIGHT, DT_SINGLELINE, DT_VCENTER, DeleteDC, DeleteObject, DrawTextW, FillRect, GetDC, GetDIBits,
+  GetSysColor, GetSysColorBrush, HALFTONE, HDC, InvalidateRect, ReleaseDC, SRCCOPY, SYS_COLOR_INDEX, SelectObject,
+  SetBkMode, SetStretchBltMode, SetTextColor, StretchBlt, StretchDIBits, TRANSPARENT,
+};
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+  GetAsyncKeyState, SetFocus, VIRTUAL_KEY, VK_BACK, VK_CONTROL, VK_DOWN, VK_ESCAPE, VK_LWIN, VK_MENU, VK_RETURN, VK_RWIN,
+  VK_SHIFT, VK_TAB, VK_UP,
+};
+use windows::Win32::UI::WindowsAndMessaging::*;
+use windows::core::BOOL;
+
+/// How often the switcher checks whether the modifier keys of the hotkey have been released after cycling.
+const MODIFIER_CHECK_INTERVAL: Duration = Duration::from_millis(50);
+const WIDTH_IN_PX: i32 = 640;
+const ROW_HEIGHT_IN_PX: i32 = 28;
+const PADDING_IN_PX: i32 = 8;
+const PROCESS_NAME_WIDTH_IN_PX: i32 = 180;
+const MAX_VISIBLE_WINDOWS: usize = 12;
+/// The height of the area below the list that shows a thumbnail of the highlighted window.
+const THUMBNAIL_HEIGHT_IN_PX: i32 = 240;
+/// The flag of `PrintWindow` that also captures content rendered with DirectX, e.g. by browsers.
+const PW_RENDERFULLCONTENT: u32 = 0x2;
+
+static SENDER: OnceLock<Mutex<Sender<Command>>> = OnceLock::new();
+static OVERLAY: OnceLock<OverlayId> = OnceLock::new();
+static IS_OPEN: AtomicBool = AtomicBool::new(false);
+static IS_NEXT_WINDOW_REQUESTED: AtomicBool = AtomicBool::new(false);
+static REQUEST: OnceLock<Mutex<Option<Request>>> = OnceLock::new();
+static THUMBNAILS: OnceLock<Mutex<HashMap<WindowHandle, Thumbnail>>> = OnceLock::new();
+
+/// The windows to list and the work area of the monitor in whose centre the switcher is opened.
+struct Request {
+  windows: Vec<WindowSummary>,
+  work_area: Rect,
+  placeholder: &'static str,
+}
+
+/// A picture of a window that was captured just before the window was hidden, because the Desktop Window Manager only
+/// renders live thumbnails of windows that are shown.
+struct Thumbnail {
+  width: i32,
+  height: i32,
+  /// The colour of every pixel, row by row from the top, as expected by a top-down bitmap with 32 bits per pixel.
+  pixels: Vec<u32>,
+}
+
+#[derive(Default)]
+struct SwitcherState {
+  windows: Vec<WindowSummary>,
+  placeholder: Vec<u16>,
+  typed_text: String,
+  selected: usize,
+  /// Whether the hotkey was pressed again while the switcher was open, in which case releasing its modifier keys selects
+  /// the highlighted window, like releasing the Alt key does for Alt+Tab.
+  has_cycled: bool,
+  /// The window whose live thumbnail is shown and the ID under which the thumbnail is registered with the Desktop
+  /// Window Manager.
+  live_thumbnail: Option<(WindowHandle, isize)>,
+}
+
+impl SwitcherState {
+  fn matching_windows(&self) -> Vec<&WindowSummary> {
+    self
+      .windows
+      .iter()
+      .filter(|window| window.matches_typed_text(&self.typed_text))
+      .collect()
+  }
+
+  fn selected_window(&self) -> Option<&WindowSummary> {
+    self.matching_windows().get(self.selected).copied()
+  }
+
+  fn move_selection(&mut self, is_next: bool) {
+    let count = self.matching_windows().len();
+    if count == 0 {
+      return;
+    }
+    self.selected = match is_next {
+      true => (self.selected + 1) % count,
+      false => (self.selected + count - 1) % count,
+    };
+  }
+}
+
+/// This struct shows a list of managed windows in the centre of a monitor that can be filtered by typing and from which
+/// a window can be selected with the arrow keys, `Tab`, or by pressing the hotkey again, to switch to it with `Enter`
+/// or bring it to the current workspace with `Shift` + `Enter`. Below the list, it shows a thumbnail of the highlighted
+/// window, which is rendered live by the Desktop Window Manager for windows that are shown and captured just before the
+/// window was hidden for windows in inactive workspaces. Unlike the other overlays, the switcher window takes the focus
+/// while it is open, so that it receives typed input, and it closes as soon as it loses the focus. It sends the
+/// selected window to the main loop as a [`Command`].
+pub struct WindowsApiForWindowSwitcher;
+
+impl WindowsApiForWindowSwitcher {
+  pub fn initialise(sender: Sender<Command>) -> Result<(), Box<dyn std::error::Error>> {
+    SENDER.set(Mutex::new(sender)).expect("Failed to set command sender");
+    let options = OverlayOptions {
+      title: "Randolf Window Switcher",
+      ex_style: WS_EX_TOPMOST | WS_EX_TOOLWINDOW,
+      style: WS_POPUP | WS_BORDER,
+      alpha: None,
+    };
+    let id = OverlayHost::add(options, || Box::new(SwitcherWindow::default()))?;
+    let _ = OVERLAY.set(id);
+
+    Ok(())
+  }
+
+  pub fn is_open() -> bool {
+    IS_OPEN.load(Ordering::Relaxed)
+  }
+
+  /// Opens the switcher with the given windows, replacing any windows that are listed already. Forgets the captured
+  /// thumbnails of all other windows, e.g. because they were closed.
+  pub fn open(windows: Vec<WindowSummary>, work_area: Rect, placeholder: &'static str) {
+    Self::thumbnails().retain(|handle, _| windows.iter().any(|window| window.handle == *handle));
+    *Self::request() = Some(Request {
+      windows,
+      work_area,
+      placeholder,
+    });
+    IS_OPEN.store(true, Ordering::Relaxed);
+    Self::wake();
+  }
+
+  /// Highlights the next window in the list, e.g. when the hotkey is pressed again while the switcher is open.
+  pub fn select_next_window() {
+    IS_NEXT_WINDOW_REQUESTED.store(true, Ordering::Relaxed);
+    Self::wake();
+  }
+
+  /// Captures a thumbnail of the window, which is about to be hidden, so that the switcher can show it while the window
+  /// is hidden in an inactive workspace. Does nothing until the switcher has been opened for the first time, so that
+  /// users who do not use the switcher do not pay for capturing, and for minimised windows and windows that do not
+  /// respond, because capturing them would return nothing useful or block until they respond.
+  pub fn capture_thumbnail(handle: WindowHandle) {
+    if SENDER.get().is_none() {
+      return;
+    }
+    unsafe {
+      if IsIconic(handle.as_hwnd()).as_bool() || IsHungAppWindow(handle.as_hwnd()).as_bool() {
+        return;
+      }
+    }
+    match Self::capture(handle.as_hwnd()) {
+      Some(thumbnail) => {
+        Self::thumbnails().insert(handle, thumbnail);
+      }
+      None => debug!("Failed to capture thumbnail of {handle}"),
+    }
+  }
+
+  fn request() -> std::sync::MutexGuard<'static, Option<Request>> {
+    REQUEST
+      .get_or_init(|| Mutex::new(None))
+      .lock()
+      .expect("Failed to lock window switcher request")
+  }
+
+  fn thumbnails() -> std::sync::MutexGuard<'static, HashMap<WindowHandle, Thumbnail>> {
+    THUMBNAILS
+      .get_or_init(|| Mutex::new(HashMap::new()))
+      .lock()
+      .expect("Failed to lock window switcher thumbnails")
+  }
+
+  /// Draws the window into a bitmap of its full size and scales it down to the size of the thumbnail area.
+  fn capture(hwnd: HWND) -> Option<Thumbnail> {
+    let mut window_rect = RECT::default();
+    unsafe { GetWindowRect(hwnd, &mut window_rect).ok()? };
+    let window_rect = Rect::from(window_rect);
+    let (window_width, window_height) = (window_rect.width(), window_rect.height());
+    let size = geometry::scale_to_fit(
+      window_width,
+      window_height,
+      &Rect::new(0, 0, WIDTH_IN_PX, THUMBNAIL_HEIGHT_IN_PX),
+    );
+    let (width, height) = (size.width(), size.height());
+    if width == 0 || height == 0 {
+      return None;
+    }
+    let mut pixels = vec![0u32; (width * height) as usize];
+    unsafe {
+      let screen_dc = GetDC(None);
+      let window_dc = CreateCompatibleDC(Some(screen_dc));
+      let window_bitmap = CreateCompatibleBitmap(screen_dc, window_width, window_height);
+      let thumbnail_dc = CreateCompatibleDC(Some(screen_dc));
+      let thumbnail_bitmap = CreateCompatibleBitmap(screen_dc, width, height);
+      let previous_window_object = SelectObject(window_dc, window_bitmap.into());
+      let previous_thumbnail_object = SelectObject(thumbnail_dc, thumbnail_bitmap.into());
+      let is_printed = PrintWindow(hwnd, window_dc, PW_RENDERFULLCONTENT).as_bool();
+      SetStretchBltMode(thumbnail_dc, HALFTONE);
+      let is_scaled = is_printed
+        && StretchBlt(
+          thumbnail_dc,
+          0,
+          0,
+          width,
+          height,
+          Some(window_dc),
+          0,
+          0,
+          window_width,
+          window_height,
+          SRCCOPY,
+        )
+        .as_bool();
+      SelectObject(window_dc, previous_window_object);
+      SelectObject(thumbnail_dc, previous_thumbnail_object);
+      let mut bitmap_info = Self::bitmap_info(width, height);
+      // The bitmap must not be selected into a device context while its pixels are copied
+      let is_copied = is_scaled
+        && GetDIBits(
+          thumbnail_dc,
+          thumbnail_bitmap,
+          0,
+          height as u32,
+          Some(pixels.as_mut_ptr() as *mut c_void),
+          &mut bitmap_info,
+          DIB_RGB_COLORS,
+        ) == height;
+      let _ = DeleteObject(window_bitmap.into());
+      let _ = DeleteObject(thumbnail_bitmap.into());
+      let _ = DeleteDC(window_dc);
+      let _ = DeleteDC(thumbnail_dc);
+      ReleaseDC(None, screen_dc);
+
+      is_copied.then_some(Thumbnail { width, height, pixels })
+    }
+  }
+
+  /// Describes a top-down bitmap with 32 bits per pixel, which is the layout of [`Thumbnail::pixels`].
+  fn bitmap_info(width: i32, height: i32) -> BITMAPINFO {
+    BITMAPINFO {
+      bmiHeader: BITMAPINFOHEADER {
+        biSize: size_of::<BITMAPINFOHEADER>() as u32,
+        biWidth: width,
+        biHeight: -height,
+        biPlanes: 1,
+        biBitCount: 32,
+        biCompression: BI_RGB.0,
+        ..Default::default()
+      },
+      ..Default::default()
+    }
+  }
+
+  fn wake() {
+    if let Some(id) = OVERLAY.get() {
+      OverlayHost::wake(*id);
+    }
+  }
+}
+
+/// The switcher overlay, whose state is only used by the thread of the overlay host while the switcher is open.
+#[derive(Default)]
+struct SwitcherWindow {
+  state: SwitcherState,
+}
+
+impl SwitcherWindow {
+  fn show(&mut self, hwnd: HWND, request: Request) {
+    let row_count = request.windows.len().clamp(1, MAX_VISIBLE_WINDOWS) as i32 + 1;
+    let width = WIDTH_IN_PX.min(request.work_area.width());
+    let height = row_count * ROW_HEIGHT_IN_PX + THUMBNAIL_HEIGHT_IN_PX + 3 * PADDING_IN_PX;
+    self.state = SwitcherState {
+      windows: request.windows,
+      placeholder: request.placeholder.encode_utf16().collect(),
+      ..SwitcherState::default()
+    };
+    unsafe {
+      if let Err(err) = SetWindowPos(
+        hwnd,
+        Some(HWND_TOPMOST),
+        request.work_area.left + (request.work_area.width() - width) / 2,
+        request.work_area.top + (request.work_area.height() - height) / 2,
+        width,
+        height,
+        SWP_SHOWWINDOW,
+      ) {
+        warn!("Failed to position window switcher: {err}");
+      }
+      // Without the focus, the switcher would neither receive typed input nor close when the user clicks elsewhere
+      if !SetForegroundWindow(hwnd).as_bool() {
+        warn!("Failed to focus window switcher, closing it again");
+        self.close(hwnd);
+        return;
+      }
+      let _ = SetFocus(Some(hwnd));
+    }
+    self.refresh(hwnd);
+  }
+
+  fn handle_key(&mut self, hwnd: HWND, key: VIRTUAL_KEY) {
+    let is_shift_held = unsafe { GetAsyncKeyState(VK_SHIFT.0 as i32) < 0 };
+    match key {
+      VK_ESCAPE => self.close(hwnd),
+      VK_RETURN => self.select(hwnd, is_shift_held),
+      VK_DOWN => self.state.move_selection(true),
+      VK_UP => self.state.move_selection(false),
+      VK_TAB => self.state.move_selection(!is_shift_held),
+      VK_BACK => {
+        self.state.typed_text.pop();
+        self.state.selected = 0;
+      }
+      _ => return,
+    }
+    self.refresh(hwnd);
+  }
+
+  /// Adds a typed character to the filter. Control characters, such as those produced by `Enter` and `Backspace`, are
+  /// handled as keys instead.
+  fn handle_character(&mut self, hwnd: HWND, character: u32) {
+    let Some(character) = char::from_u32(character).filter(|character| !character.is_control()) else {
+      return;
+    };
+    self.state.typed_text.push(character);
+    self.state.selected = 0;
+    self.refresh(hwnd);
+  }
+
+  /// Closes the switcher and asks the main loop to switch to the highlighted window or, if `is_bringing_here` is
+  /// `true`, to bring it to the current workspace.
+  fn select(&mut self, hwnd: HWND, is_bringing_here: bool) {
+    let handle = self.state.selected_window().map(|window| window.handle);
+    self.close(hwnd);
+    let Some(handle) = handle else {
+      return;
+    };
+    let command = match is_bringing_here {
+      true => Command::BringWindowHere(handle),
+      false => Command::SwitchToWindow(handle),
+    };
+    if let Some(sender) = SENDER.get() {
+      sender
+        .lock()
+        .expect("Failed to lock command sender")
+        .send(command)
+        .expect("Failed to send command");
+    }
+  }
+
+  fn close(&mut self, hwnd: HWND) {
+    if !IS_OPEN.swap(false, Ordering::Relaxed) {
+      return;
+    }
+    if let Some((_, thumbnail_id)) = self.state.live_thumbnail {
+      unsafe {
+        let _ = DwmUnregisterThumbnail(thumbnail_id);
+      }
+    }
+    self.state = SwitcherState::default();
+    unsafe {
+      let _ = ShowWindow(hwnd, SW_HIDE);
+    }
+  }
+
+  /// Updates the thumbnail of the highlighted window and redraws the switcher.
+  fn refresh(&mut self, hwnd: HWND) {
+    self.update_live_thumbnail(hwnd);
+    unsafe {
+      let _ = InvalidateRect(Some(hwnd), None, true);
+    }
+  }
+
+  /// Shows a live thumbnail of the highlighted window, which the Desktop Window Manager draws over the thumbnail area,
+  /// unless the window is hidden in an inactive workspace, in which case [`SwitcherWindow::paint`] draws its captured
+  /// thumbnail.
+  fn update_live_thumbnail(&mut self, hwnd: HWND) {
+    let selected = self
+      .state
+      .selected_window()
+      .filter(|window| window.visibility == WindowVisibility::Visible)
+      .map(|window| window.handle);
+    if self.state.live_thumbnail.map(|(handle, _)| handle) == selected {
+      return;
+    }
+    if let Some((_, thumbnail_id)) = self.state.live_thumbnail {
+      unsafe {
+        let _ = DwmUnregisterThumbnail(thumbnail_id);
+      }
+    }
+    self.state.live_thumbnail =
+      selected.and_then(|handle| Self::register_live_thumbnail(hwnd, handle).map(|thumbnail_id| (handle, thumbnail_id)));
+  }
+
+  fn register_live_thumbnail(hwnd: HWND, handle: WindowHandle) -> Option<isize> {
+    unsafe {
+      let thumbnail_id = DwmRegisterThumbnail(hwnd, handle.as_hwnd())
+        .inspect_err(|err| debug!("Failed to register thumbnail of {handle}: {err}"))
+        .ok()?;
+      let source_size = DwmQueryThumbnailSourceSize(thumbnail_id).unwrap_or_default();
+      let mut client_rect = RECT::default();
+      let _ = GetClientRect(hwnd, &mut client_rect);
+      let destination = geometry::scale_to_fit(source_size.cx, source_size.cy, &Self::thumbnail_area(client_rect));
+      let properties = DWM_THUMBNAIL_PROPERTIES {
+        dwFlags: DWM_TNP_RECTDESTINATION | DWM_TNP_VISIBLE | DWM_TNP_SOURCECLIENTAREAONLY,
+        rcDestination: destination.into(),
+        fVisible: true.into(),
+        fSourceClientAreaOnly: false.into(),
+        ..Default::default()
+      };
+      if let Err(err) = DwmUpdateThumbnailProperties(thumbnail_id, &properties) {
+        debug!("Failed to show thumbnail of {handle}: {err}");
+        let _ = DwmUnregisterThumbnail(thumbnail_id);
+        return None;
+      }
+
+      Some(thumbnail_id)
+    }
+  }
+
+  /// Returns the area below the list in which the thumbnail of the highlighted window is shown.
+  fn thumbnail_area(client_rect: RECT) -> Rect {
+    Rect::new(
+      client_rect.left + PADDING_IN_PX,
+      client_rect.bottom - PADDING_IN_PX - THUMBNAIL_HEIGHT_IN_PX,
+      client_rect.right - PADDING_IN_PX,
+      client_rect.bottom - PADDING_IN_PX,
+    )
+  }
+
+  /// Draws the thumbnail that was captured when the window was hidden, if there is one.
+  fn draw_captured_thumbnail(hdc: HDC, handle: WindowHandle, area: Rect) {
+    let thumbnails = WindowsApiForWindowSwitcher::thumbnails();
+    let Some(thumbnail) = thumbnails.get(&handle) else {
+      return;
+    };
+    let destination = geometry::scale_to_fit(thumbnail.width, thumbnail.height, &area);
+    let bitmap_info = WindowsApiForWindowSwitcher::bitmap_info(thumbnail.width, thumbnail.height);
+    unsafe {
+      StretchDIBits(
+        hdc,
+        destination.left,
+        destination.top,
+        destination.width(),
+        destination.height(),
+        0,
+        0,
+        thumbnail.width,
+        thumbnail.height,
+        Some(thumbnail.pixels.as_ptr() as *const c_void),
+        &bitmap_info,
+        DIB_RGB_COLORS,
+        SRCCOPY,
+      );
+    }
+  }
+
+  fn draw_text(hdc: HDC, text: &[u16], mut rect: RECT, colour: SYS_COLOR_INDEX, alignment: DRAW_TEXT_FORMAT) {
+    let mut text = text.to_vec();
+    unsafe {
+      SetTextColor(hdc, COLORREF(GetSysColor(colour)));
+      DrawTextW(
+        hdc,
+        &mut text,
+        &mut rect,
+        alignment | DT_VCENTER | DT_SINGLELINE | DT_END_ELLIPSIS | DT_NOPREFIX,
+      );
+    }
+  }
+}
+
+impl OverlayWindow for SwitcherWindow {
+  /// Opens the switcher if it was requested and highlights the next window if the hotkey was pressed again, after
+  /// which it keeps checking whether the modifier keys of the hotkey have been released.
+  fn update(&mut self, hwnd: HWND) -> Option<Duration> {
+    let request = WindowsApiForWindowSwitcher::request().take();
+    if let Some(request) = request {
+      self.show(hwnd, request);
+    }
+    if IS_OPEN.load(Ordering::Relaxed) && IS_NEXT_WINDOW_REQUESTED.swap(false, Ordering::Relaxed) {
+      self.state.has_cycled = true;
+      self.state.move_selection(true);
+      self.refresh(hwnd);
+    }
+
+    self.tick(hwnd)
+  }
+
+  /// Selects the highlighted window once all modifier keys are released after cycling through the windows with the
+  /// hotkey.
+  fn tick(&mut self, hwnd: HWND) -> Option<Duration> {
+    if !IS_OPEN.load(Ordering::Relaxed) || !self.state.has_cycled {
+      return None;
+    }
+    let is_modifier_held = [VK_LWIN, VK_RWIN, VK_SHIFT, VK_CONTROL, VK_MENU]
+      .iter()
+      .any(|key| unsafe { GetAsyncKeyState(key.0 as i32) < 0 });
+    if is_modifier_held {
+      return Some(MODIFIER_CHECK_INTERVAL);
+    }
+    self.select(hwnd, false);
+
+    None
+  }
+
+  fn handle_message(&mut self, hwnd: HWND, message: u32, w_param: WPARAM, _l_param: LPARAM) -> Option<LRESULT> {
+    match message {
+      WM_KEYDOWN => self.handle_key(hwnd, VIRTUAL_KEY(w_param.0 as u16)),
+      WM_CHAR => self.handle_character(hwnd, w_param.0 as u32),
+      WM_ACTIVATE => {
+        if (w_param.0 & 0xFFFF) as u32 == WA_INACTIVE {
+          self.close(hwnd);
+        }
+      }
+      _ => return None,
+    }
+
+    Some(LRESULT(0))
+  }
+
+  /// Draws the typed text (or a hint if nothing was typed yet) followed by as many matching windows as fit, scrolling
+  /// the list so that the highlighted window is always visible, and the thumbnail of the highlighted window if it is
+  /// hidden in an inactive workspace. Windows hidden in inactive workspaces are greyed out.
+  fn paint(&mut self, _hwnd: HWND, hdc: HDC, rect: RECT) {
+    let state = &self.state;
+    unsafe {
+      FillRect(hdc, &rect, GetSysColorBrush(COLOR_WINDOW));
+      SetBkMode(hdc, TRANSPARENT);
+      let row = |index: i32| RECT {
+        left: rect.left + PADDING_IN_PX,
+        top: rect.top + PADDING_IN_PX + index * ROW_HEIGHT_IN_PX,
+        right: rect.right - PADDING_IN_PX,
+        bottom: rect.top + PADDING_IN_PX + (index + 1) * ROW_HEIGHT_IN_PX,
+      };
+      match state.typed_text.is_empty() {
+        true => Self::draw_text(hdc, &state.placeholder, row(0), COLOR_GRAYTEXT, DT_LEFT),
+        false => {
+          let typed_text = state.typed_text.encode_utf16().collect::<Vec<_>>();
+          Self::draw_text(hdc, &typed_text, row(0), COLOR_WINDOWTEXT, DT_LEFT);
+        }
+      }
+      let first_visible = state.selected.saturating_sub(MAX_VISIBLE_WINDOWS - 1);
+      let windows = state.matching_windows();
+      for (index, window) in windows.iter().enumerate().skip(first_visible).take(MAX_VISIBLE_WINDOWS) {
+        let row_rect = row((index - first_visible) as i32 + 1);
+        let colour = if index == state.selected {
+          FillRect(hdc, &row_rect, GetSysColorBrush(COLOR_HIGHLIGHT));
+          COLOR_HIGHLIGHTTEXT
+        } else if window.visibility == WindowVisibility::Hidden {
+          COLOR_GRAYTEXT
+        } else {
+          COLOR_WINDOWTEXT
+        };
+        let title_rect = RECT {
+          left: row_rect.left + PADDING_IN_PX,
+          right: row_rect.right - PROCESS_NAME_WIDTH_IN_PX - PADDING_IN_PX,
+          ..row_rect
+        };
+        let title = window.title.encode_utf16().collect::<Vec<_>>();
+        Self::draw_text(hdc, &title, title_rect, colour, DT_LEFT);
+        let process_name_rect = RECT {
+          left: row_rect.right - PROCESS_NAME_WIDTH_IN_PX,
+          right: row_rect.right - PADDING_IN_PX,
+          ..row_rect
+        };
+        let process_name = window
+          .process_name
+          .as_deref()
+          .unwrap_or_default()
+          .encode_utf16()
+          .collect::<Vec<_>>();
+        Self::draw_text(hdc, &process_name, process_name_rect, colour, DT_RIGHT);
+      }
+      if let Some(window) = state.selected_window()
+        && window.visibility == WindowVisibility::Hidden
+      {
+        Self::draw_captured_thumbnail(hdc, window.handle, Self::thumbnail_area(rect));
+      }
+    }
+  }
+}
+
+#[link(name = "user32")]
+unsafe extern "system" {
+  fn PrintWindow(hwnd: HWND, hdc_blt: HDC, flags: u32) -> BOOL;
+}
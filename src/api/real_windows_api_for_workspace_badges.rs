@@ -0,0 +1,135 @@
+use crate::common::WorkspaceBadge;
+use crate::overlay::{OverlayHost, OverlayId, OverlayOptions, OverlayWindow};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use windows::Win32::Foundation::{COLORREF, HWND, RECT};
+use windows::Win32::Graphics::Gdi::{
+  COLOR_HIGHLIGHT, COLOR_HIGHLIGHTTEXT, COLOR_INFOBK, COLOR_INFOTEXT, DT_CENTER, DT_SINGLELINE, DT_VCENTER, DrawTextW,
+  FillRect, GetSysColor, GetSysColorBrush, HDC, InvalidateRect, SetBkMode, SetTextColor, TRANSPARENT,
+};
+use windows::Win32::UI::WindowsAndMessaging::*;
+
+const CELL_SIZE_IN_PX: i32 = 40;
+const PADDING_IN_PX: i32 = 6;
+/// The label of a workspace that cannot be switched to with a `switch_to_workspace_<number>` hotkey.
+const UNNUMBERED_WORKSPACE_LABEL: &str = "•";
+
+static OVERLAY: OnceLock<OverlayId> = OnceLock::new();
+static BADGES: OnceLock<Mutex<Vec<WorkspaceBadge>>> = OnceLock::new();
+
+/// This struct shows a small, click-through badge in the centre of each monitor that lists the numbers of the
+/// monitor's workspaces and highlights the active one, e.g. while the Win key is held. Since the number of monitors can
+/// change, the first window of the overlay is never shown and the overlay creates one additional window per monitor as
+/// needed.
+pub struct WindowsApiForWorkspaceBadges;
+
+impl WindowsApiForWorkspaceBadges {
+  pub fn initialise() -> Result<(), Box<dyn std::error::Error>> {
+    let options = OverlayOptions::click_through("Randolf Workspace Badge", 235);
+    let id = OverlayHost::add(options, || Box::new(WorkspaceBadgesWindow::default()))?;
+    let _ = OVERLAY.set(id);
+
+    Ok(())
+  }
+
+  pub fn show(badges: Vec<WorkspaceBadge>) {
+    *Self::badges() = badges;
+    if let Some(id) = OVERLAY.get() {
+      OverlayHost::wake(*id);
+    }
+  }
+
+  pub fn hide() {
+    Self::show(vec![]);
+  }
+
+  fn badges() -> std::sync::MutexGuard<'static, Vec<WorkspaceBadge>> {
+    BADGES
+      .get_or_init(|| Mutex::new(vec![]))
+      .lock()
+      .expect("Failed to lock workspace badges")
+  }
+}
+
+/// The badges overlay, whose state is shared with [`WindowsApiForWorkspaceBadges`].
+#[derive(Default)]
+struct WorkspaceBadgesWindow {
+  /// The windows that show the badges, one per badge and in the same order, which are created on demand.
+  badge_windows: Vec<HWND>,
+}
+
+impl OverlayWindow for WorkspaceBadgesWindow {
+  /// Shows a window over the centre of the work area of each badge, creating windows if there are more badges than
+  /// windows, and hides the remaining windows.
+  fn update(&mut self, hwnd: HWND) -> Option<Duration> {
+    let badges = WindowsApiForWorkspaceBadges::badges().clone();
+    let windows = &mut self.badge_windows;
+    while windows.len() < badges.len() {
+      match OverlayHost::create_additional_window(hwnd) {
+        Ok(badge_hwnd) => windows.push(badge_hwnd),
+        Err(err) => {
+          warn!("Failed to create workspace badge window: {err}");
+          break;
+        }
+      }
+    }
+    for (index, badge_hwnd) in windows.iter().enumerate() {
+      let Some(badge) = badges.get(index) else {
+        unsafe {
+          let _ = ShowWindow(*badge_hwnd, SW_HIDE);
+        }
+        continue;
+      };
+      let width = badge.workspaces.len() as i32 * CELL_SIZE_IN_PX + 2 * PADDING_IN_PX;
+      let height = CELL_SIZE_IN_PX + 2 * PADDING_IN_PX;
+      unsafe {
+        if let Err(err) = SetWindowPos(
+          *badge_hwnd,
+          Some(HWND_TOPMOST),
+          badge.work_area.left + (badge.work_area.width() - width) / 2,
+          badge.work_area.top + (badge.work_area.height() - height) / 2,
+          width,
+          height,
+          SWP_NOACTIVATE | SWP_SHOWWINDOW,
+        ) {
+          warn!("Failed to position workspace badge: {err}");
+        }
+        let _ = InvalidateRect(Some(*badge_hwnd), None, true);
+      }
+    }
+
+    None
+  }
+
+  fn paint(&mut self, hwnd: HWND, hdc: HDC, rect: RECT) {
+    let Some(index) = self.badge_windows.iter().position(|badge_hwnd| *badge_hwnd == hwnd) else {
+      return;
+    };
+    unsafe {
+      FillRect(hdc, &rect, GetSysColorBrush(COLOR_INFOBK));
+      SetBkMode(hdc, TRANSPARENT);
+      let workspaces = WindowsApiForWorkspaceBadges::badges()
+        .get(index)
+        .map(|badge| badge.workspaces.clone())
+        .unwrap_or_default();
+      for (position, (number, is_active)) in workspaces.into_iter().enumerate() {
+        let left = rect.left + PADDING_IN_PX + position as i32 * CELL_SIZE_IN_PX;
+        let mut cell = RECT {
+          left,
+          top: rect.top + PADDING_IN_PX,
+          right: left + CELL_SIZE_IN_PX,
+          bottom: rect.top + PADDING_IN_PX + CELL_SIZE_IN_PX,
+        };
+        if is_active {
+          FillRect(hdc, &cell, GetSysColorBrush(COLOR_HIGHLIGHT));
+          SetTextColor(hdc, COLORREF(GetSysColor(COLOR_HIGHLIGHTTEXT)));
+        } else {
+          SetTextColor(hdc, COLORREF(GetSysColor(COLOR_INFOTEXT)));
+        }
+        let label = number.map_or_else(|| UNNUMBERED_WORKSPACE_LABEL.to_string(), |number| number.to_string());
+        let mut text = label.encode_utf16().collect::<Vec<_>>();
+        DrawTextW(hdc, &mut text, &mut cell, DT_CENTER | DT_VCENTER | DT_SINGLELINE);
+      }
+    }
+  }
+}
@@ -0,0 +1,124 @@
+use crate::common::Rect;
+use crate::overlay::{OverlayHost, OverlayId, OverlayOptions, OverlayWindow};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use windows::Win32::Foundation::{HWND, RECT};
+use windows::Win32::Graphics::Gdi::{
+  COLOR_INFOBK, DT_CALCRECT, DT_LEFT, DT_WORDBREAK, DrawTextW, FillRect, GetDC, GetSysColorBrush, HDC, InvalidateRect,
+  ReleaseDC, SetBkMode, TRANSPARENT,
+};
+use windows::Win32::UI::WindowsAndMessaging::*;
+
+const MAX_TEXT_WIDTH_IN_PX: i32 = 320;
+const OFFSET_IN_PX: i32 = 12;
+const PADDING_IN_PX: i32 = 8;
+
+static OVERLAY: OnceLock<OverlayId> = OnceLock::new();
+static NOTE: OnceLock<Mutex<Option<Note>>> = OnceLock::new();
+
+/// The text of a note and the work area of the monitor in whose top-right corner it is shown.
+struct Note {
+  text: Vec<u16>,
+  work_area: Rect,
+}
+
+/// This struct shows the note of a workspace in a small, click-through overlay in the top-right corner of its monitor.
+/// The overlay is woken by [`WindowsApiForWorkspaceNotes::show`] and [`WindowsApiForWorkspaceNotes::hide`] and sleeps
+/// otherwise.
+pub struct WindowsApiForWorkspaceNotes;
+
+impl WindowsApiForWorkspaceNotes {
+  pub fn initialise() -> Result<(), Box<dyn std::error::Error>> {
+    let options = OverlayOptions::click_through("Randolf Workspace Note", 220);
+    let id = OverlayHost::add(options, || Box::new(WorkspaceNoteWindow))?;
+    let _ = OVERLAY.set(id);
+
+    Ok(())
+  }
+
+  pub fn show(text: &str, work_area: Rect) {
+    *Self::note() = Some(Note {
+      text: text.encode_utf16().collect(),
+      work_area,
+    });
+    Self::wake();
+  }
+
+  pub fn hide() {
+    *Self::note() = None;
+    Self::wake();
+  }
+
+  fn wake() {
+    if let Some(id) = OVERLAY.get() {
+      OverlayHost::wake(*id);
+    }
+  }
+
+  fn note() -> std::sync::MutexGuard<'static, Option<Note>> {
+    NOTE
+      .get_or_init(|| Mutex::new(None))
+      .lock()
+      .expect("Failed to lock workspace note")
+  }
+}
+
+/// The note overlay, whose state is shared with [`WindowsApiForWorkspaceNotes`].
+struct WorkspaceNoteWindow;
+
+impl OverlayWindow for WorkspaceNoteWindow {
+  fn update(&mut self, hwnd: HWND) -> Option<Duration> {
+    let note = WindowsApiForWorkspaceNotes::note();
+    let Some(note) = note.as_ref() else {
+      unsafe {
+        let _ = ShowWindow(hwnd, SW_HIDE);
+      }
+      return None;
+    };
+
+    unsafe {
+      let mut text = note.text.clone();
+      let mut rect = RECT {
+        right: MAX_TEXT_WIDTH_IN_PX,
+        ..Default::default()
+      };
+      let hdc = GetDC(Some(hwnd));
+      DrawTextW(hdc, &mut text, &mut rect, DT_CALCRECT | DT_LEFT | DT_WORDBREAK);
+      ReleaseDC(Some(hwnd), hdc);
+      let width = rect.right - rect.left + 2 * PADDING_IN_PX;
+      let height = rect.bottom - rect.top + 2 * PADDING_IN_PX;
+      if let Err(err) = SetWindowPos(
+        hwnd,
+        Some(HWND_TOPMOST),
+        note.work_area.right - width - OFFSET_IN_PX,
+        note.work_area.top + OFFSET_IN_PX,
+        width,
+        height,
+        SWP_NOACTIVATE | SWP_SHOWWINDOW,
+      ) {
+        warn!("Failed to position workspace note: {err}");
+      }
+      let _ = InvalidateRect(Some(hwnd), None, true);
+    }
+
+    None
+  }
+
+  fn paint(&mut self, _hwnd: HWND, hdc: HDC, rect: RECT) {
+    unsafe {
+      FillRect(hdc, &rect, GetSysColorBrush(COLOR_INFOBK));
+      SetBkMode(hdc, TRANSPARENT);
+      let mut text_rect = RECT {
+        left: rect.left + PADDING_IN_PX,
+        top: rect.top + PADDING_IN_PX,
+        right: rect.right - PADDING_IN_PX,
+        bottom: rect.bottom - PADDING_IN_PX,
+      };
+      let mut text = WindowsApiForWorkspaceNotes::note()
+        .as_ref()
+        .map(|note| note.text.clone())
+        .unwrap_or_default();
+      DrawTextW(hdc, &mut text, &mut text_rect, DT_LEFT | DT_WORDBREAK);
+    }
+  }
+}
@@ -0,0 +1,368 @@
+use crate::common::{Rect, WindowHandle};
+use crate::configuration_provider::ExclusionSettings;
+use crate::utils::{EXCLUSION_SETTINGS_LOCK, IGNORED_WINDOWS_LOCK};
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+
+/// Window classes of short-lived windows such as context menus, tooltips, and the notification area overflow, which
+/// are never managed.
+const TRANSIENT_WINDOW_CLASSES: &[&str] = &[
+  "#32768",
+  "tooltips_class32",
+  "NotifyIconOverflowWindow",
+  "Xaml_WindowedPopupClass",
+  "TopLevelWindowForOverflowXamlIsland",
+];
+
+/// Window classes of input method windows such as IME composition and candidate windows and the text services
+/// framework's floating UI, which must never receive focus because doing so interrupts typing.
+const INPUT_METHOD_WINDOW_CLASSES: &[&str] = &[
+  "IME",
+  "MSCTFIME UI",
+  "CiceroUIWndFrame",
+  "Microsoft.IME.UIManager.CandidateWindow.Host",
+  "Microsoft.IME.CandidateWindow.View",
+];
+
+/// The class and title of the window hosting the emoji picker and the clipboard history. The class is shared with other
+/// shell UI, so the title is required to identify it.
+const INPUT_EXPERIENCE_WINDOW: (&str, &str) = ("Windows.UI.Core.CoreWindow", "Windows Input Experience");
+
+/// The properties of a window that [`WindowFilter`]s decide on. Only contains properties that are cheap to read, so
+/// that a candidate can be created for every window whenever windows are enumerated. Filters that require expensive
+/// queries, e.g. whether the window's process is elevated, perform them on demand.
+#[derive(Debug, Clone)]
+pub struct WindowCandidate {
+  pub handle: WindowHandle,
+  pub title: String,
+  pub class_name: String,
+  pub rect: Rect,
+  pub process_id: u32,
+  pub is_visible: bool,
+  pub is_minimised: bool,
+  pub is_tool_window: bool,
+  pub has_owner: bool,
+}
+
+/// A single check that decides whether a window is excluded from being managed or listed. The [`RealWindowsApi`][rwa]
+/// configures chains of filters at startup and excludes a window as soon as any filter of a chain excludes it, so
+/// filters should be ordered from cheapest to most expensive.
+///
+/// [rwa]: crate::api::RealWindowsApi
+pub trait WindowFilter: Send + Sync {
+  /// Returns `true` if the window must be excluded.
+  fn excludes(&self, candidate: &WindowCandidate) -> bool;
+}
+
+/// Returns `true` if any of the filters excludes the window.
+pub fn is_excluded_by_any(filters: &[Box<dyn WindowFilter>], candidate: &WindowCandidate) -> bool {
+  filters.iter().any(|filter| filter.excludes(candidate))
+}
+
+/// Excludes Randolf's own windows, transient windows, input method windows, windows excluded in the configuration and,
+/// if configured and not running as admin, windows of elevated processes.
+pub struct ExclusionFilter {
+  exclusion_settings: Arc<RwLock<ExclusionSettings>>,
+  own_process_id: u32,
+  is_running_as_admin: bool,
+  is_window_elevated: fn(WindowHandle) -> bool,
+}
+
+impl ExclusionFilter {
+  pub fn new(
+    exclusion_settings: Arc<RwLock<ExclusionSettings>>,
+    own_process_id: u32,
+    is_running_as_admin: bool,
+    is_window_elevated: fn(WindowHandle) -> bool,
+  ) -> Self {
+    Self {
+      exclusion_settings,
+      own_process_id,
+      is_running_as_admin,
+      is_window_elevated,
+    }
+  }
+}
+
+impl WindowFilter for ExclusionFilter {
+  fn excludes(&self, candidate: &WindowCandidate) -> bool {
+    if candidate.process_id == self.own_process_id
+      || TRANSIENT_WINDOW_CLASSES.contains(&candidate.class_name.as_str())
+      || is_input_method_window(candidate)
+    {
+      return true;
+    }
+    let (is_excluded, ignore_elevated_windows) = {
+      let settings = self.exclusion_settings.read().expect(EXCLUSION_SETTINGS_LOCK);
+      (
+        settings.window_class_names.contains(&candidate.class_name) || settings.window_titles.contains(&candidate.title),
+        settings.ignore_elevated_windows,
+      )
+    };
+
+    // Elevated windows can be managed when running as admin, so there is no need to ignore them in that case
+    is_excluded || (ignore_elevated_windows && !self.is_running_as_admin && (self.is_window_elevated)(candidate.handle))
+  }
+}
+
+/// Returns `true` if the window belongs to an input method, e.g. an IME candidate window, the emoji picker, or the
+/// clipboard history. These are excluded regardless of the configuration because they are shown on top of the window
+/// the user is typing in.
+fn is_input_method_window(candidate: &WindowCandidate) -> bool {
+  let (input_experience_class_name, input_experience_title) = INPUT_EXPERIENCE_WINDOW;
+
+  INPUT_METHOD_WINDOW_CLASSES.contains(&candidate.class_name.as_str())
+    || (candidate.class_name == input_experience_class_name && candidate.title == input_experience_title)
+}
+
+/// Excludes windows that the user has chosen to stop managing at runtime, e.g. to temporarily exempt a misbehaving
+/// application without editing the exclusion settings. The set of windows is shared with, and updated by, the
+/// [`RealWindowsApi`][rwa].
+///
+/// [rwa]: crate::api::RealWindowsApi
+pub struct IgnoreListFilter {
+  ignored_windows: Arc<RwLock<HashSet<WindowHandle>>>,
+}
+
+impl IgnoreListFilter {
+  pub fn new(ignored_windows: Arc<RwLock<HashSet<WindowHandle>>>) -> Self {
+    Self { ignored_windows }
+  }
+}
+
+impl WindowFilter for IgnoreListFilter {
+  fn excludes(&self, candidate: &WindowCandidate) -> bool {
+    self
+      .ignored_windows
+      .read()
+      .expect(IGNORED_WINDOWS_LOCK)
+      .contains(&candidate.handle)
+  }
+}
+
+/// Excludes tool windows (e.g. floating palettes or docks of an IDE) that are owned by another window, because they
+/// follow their owner when it is hidden or restored, unless their class has been opted in explicitly.
+pub struct StyleFilter {
+  exclusion_settings: Arc<RwLock<ExclusionSettings>>,
+}
+
+impl StyleFilter {
+  pub fn new(exclusion_settings: Arc<RwLock<ExclusionSettings>>) -> Self {
+    Self { exclusion_settings }
+  }
+}
+
+impl WindowFilter for StyleFilter {
+  fn excludes(&self, candidate: &WindowCandidate) -> bool {
+    candidate.is_tool_window
+      && candidate.has_owner
+      && !self
+        .exclusion_settings
+        .read()
+        .expect(EXCLUSION_SETTINGS_LOCK)
+        .managed_tool_window_classes
+        .contains(&candidate.class_name)
+  }
+}
+
+/// Excludes windows whose area is smaller than the minimum, e.g. invisible helper windows of other applications.
+pub struct SizeFilter {
+  minimum_area: i32,
+}
+
+impl SizeFilter {
+  pub fn new(minimum_area: i32) -> Self {
+    Self { minimum_area }
+  }
+}
+
+impl WindowFilter for SizeFilter {
+  fn excludes(&self, candidate: &WindowCandidate) -> bool {
+    candidate.rect.area() < self.minimum_area
+  }
+}
+
+/// Excludes hidden and minimised windows.
+pub struct VisibilityFilter;
+
+impl WindowFilter for VisibilityFilter {
+  fn excludes(&self, candidate: &WindowCandidate) -> bool {
+    !candidate.is_visible || candidate.is_minimised
+  }
+}
+
+/// Excludes windows that are cloaked by the Desktop Window Manager, e.g. windows on another Windows virtual desktop or
+/// suspended Store apps. Such windows count as visible for Windows but cannot be seen.
+pub struct CloakFilter {
+  is_window_cloaked: fn(WindowHandle) -> bool,
+}
+
+impl CloakFilter {
+  pub fn new(is_window_cloaked: fn(WindowHandle) -> bool) -> Self {
+    Self { is_window_cloaked }
+  }
+}
+
+impl WindowFilter for CloakFilter {
+  fn excludes(&self, candidate: &WindowCandidate) -> bool {
+    (self.is_window_cloaked)(candidate.handle)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const OWN_PROCESS_ID: u32 = 1;
+
+  fn candidate() -> WindowCandidate {
+    WindowCandidate {
+      handle: WindowHandle::new(1),
+      title: "Title".to_string(),
+      class_name: "Class".to_string(),
+      rect: Rect::new(0, 0, 100, 100),
+      process_id: 2,
+      is_visible: true,
+      is_minimised: false,
+      is_tool_window: false,
+      has_owner: false,
+    }
+  }
+
+  fn exclusion_settings(ignore_elevated_windows: bool) -> Arc<RwLock<ExclusionSettings>> {
+    Arc::new(RwLock::new(ExclusionSettings {
+      window_titles: vec!["Excluded title".to_string()],
+      window_class_names: vec!["ExcludedClass".to_string()],
+      ignore_elevated_windows,
+      managed_tool_window_classes: vec!["ManagedToolWindow".to_string()],
+      persist_ignored_windows: false,
+    }))
+  }
+
+  #[test]
+  fn exclusion_filter_excludes_own_transient_and_configured_windows() {
+    let filter = ExclusionFilter::new(exclusion_settings(false), OWN_PROCESS_ID, false, |_| true);
+
+    assert!(!filter.excludes(&candidate()));
+    assert!(filter.excludes(&WindowCandidate {
+      process_id: OWN_PROCESS_ID,
+      ..candidate()
+    }));
+    assert!(filter.excludes(&WindowCandidate {
+      class_name: "#32768".to_string(),
+      ..candidate()
+    }));
+    assert!(filter.excludes(&WindowCandidate {
+      class_name: "ExcludedClass".to_string(),
+      ..candidate()
+    }));
+    assert!(filter.excludes(&WindowCandidate {
+      title: "Excluded title".to_string(),
+      ..candidate()
+    }));
+  }
+
+  #[test]
+  fn exclusion_filter_excludes_input_method_windows() {
+    let filter = ExclusionFilter::new(exclusion_settings(false), OWN_PROCESS_ID, false, |_| false);
+
+    assert!(filter.excludes(&WindowCandidate {
+      class_name: "Microsoft.IME.UIManager.CandidateWindow.Host".to_string(),
+      ..candidate()
+    }));
+    assert!(filter.excludes(&WindowCandidate {
+      class_name: "Windows.UI.Core.CoreWindow".to_string(),
+      title: "Windows Input Experience".to_string(),
+      ..candidate()
+    }));
+    assert!(!filter.excludes(&WindowCandidate {
+      class_name: "Windows.UI.Core.CoreWindow".to_string(),
+      title: "Search".to_string(),
+      ..candidate()
+    }));
+  }
+
+  #[test]
+  fn exclusion_filter_excludes_elevated_windows_only_if_configured_and_not_running_as_admin() {
+    assert!(ExclusionFilter::new(exclusion_settings(true), OWN_PROCESS_ID, false, |_| true).excludes(&candidate()));
+    assert!(!ExclusionFilter::new(exclusion_settings(true), OWN_PROCESS_ID, true, |_| true).excludes(&candidate()));
+    assert!(!ExclusionFilter::new(exclusion_settings(false), OWN_PROCESS_ID, false, |_| true).excludes(&candidate()));
+    assert!(!ExclusionFilter::new(exclusion_settings(true), OWN_PROCESS_ID, false, |_| false).excludes(&candidate()));
+  }
+
+  #[test]
+  fn style_filter_excludes_owned_tool_windows_unless_opted_in() {
+    let filter = StyleFilter::new(exclusion_settings(false));
+    let owned_tool_window = WindowCandidate {
+      is_tool_window: true,
+      has_owner: true,
+      ..candidate()
+    };
+
+    assert!(filter.excludes(&owned_tool_window));
+    assert!(!filter.excludes(&WindowCandidate {
+      has_owner: false,
+      ..owned_tool_window.clone()
+    }));
+    assert!(!filter.excludes(&WindowCandidate {
+      class_name: "ManagedToolWindow".to_string(),
+      ..owned_tool_window
+    }));
+  }
+
+  #[test]
+  fn ignore_list_filter_excludes_ignored_windows_until_they_are_removed_from_the_list() {
+    let ignored_windows = Arc::new(RwLock::new(HashSet::from([WindowHandle::new(1)])));
+    let filter = IgnoreListFilter::new(ignored_windows.clone());
+
+    assert!(filter.excludes(&candidate()));
+    assert!(!filter.excludes(&WindowCandidate {
+      handle: WindowHandle::new(2),
+      ..candidate()
+    }));
+
+    ignored_windows.write().unwrap().clear();
+
+    assert!(!filter.excludes(&candidate()));
+  }
+
+  #[test]
+  fn size_filter_excludes_windows_below_minimum_area() {
+    let filter = SizeFilter::new(5);
+
+    assert!(!filter.excludes(&candidate()));
+    assert!(filter.excludes(&WindowCandidate {
+      rect: Rect::new(0, 0, 2, 2),
+      ..candidate()
+    }));
+  }
+
+  #[test]
+  fn visibility_and_cloak_filters_exclude_windows_that_cannot_be_seen() {
+    assert!(!VisibilityFilter.excludes(&candidate()));
+    assert!(VisibilityFilter.excludes(&WindowCandidate {
+      is_visible: false,
+      ..candidate()
+    }));
+    assert!(VisibilityFilter.excludes(&WindowCandidate {
+      is_minimised: true,
+      ..candidate()
+    }));
+    assert!(CloakFilter::new(|_| true).excludes(&candidate()));
+    assert!(!CloakFilter::new(|_| false).excludes(&candidate()));
+  }
+
+  #[test]
+  fn is_excluded_by_any_excludes_window_if_any_filter_excludes_it() {
+    let filters: Vec<Box<dyn WindowFilter>> = vec![Box::new(SizeFilter::new(5)), Box::new(VisibilityFilter)];
+
+    assert!(!is_excluded_by_any(&filters, &candidate()));
+    assert!(is_excluded_by_any(
+      &filters,
+      &WindowCandidate {
+        is_minimised: true,
+        ..candidate()
+      }
+    ));
+  }
+}
@@ -1,4 +1,4 @@
-use crate::common::{MonitorHandle, MonitorInfo, Monitors, Point, Rect, Window, WindowHandle, WindowPlacement};
+use crate::common::{CursorSpeed, MonitorHandle, MonitorInfo, Monitors, Point, Rect, Window, WindowHandle, WindowPlacement};
 use windows::Win32::UI::Shell::IVirtualDesktopManager;
 
 pub trait WindowsApi {
@@ -14,15 +14,41 @@ pub trait WindowsApi {
   fn get_window_rect(&self, handle: WindowHandle) -> Option<Rect>;
   /// Returns the DWM extended frame bounds (includes drop shadows) when available.
   fn get_extended_frame_bounds(&self, handle: WindowHandle) -> Option<Rect>;
+  /// Sets the colour of the window's border, given as a `COLORREF` (i.e. `0x00BBGGRR`).
+  fn set_window_border_colour(&self, handle: WindowHandle, colour: u32);
   fn is_window_minimised(&self, handle: WindowHandle) -> bool;
   fn is_not_a_managed_window(&self, handle: &WindowHandle) -> bool;
+  /// Returns the foreground window if it is ignored (see [`Self::set_window_ignored`]), which
+  /// [`Self::get_foreground_window`] does not return because ignored windows are not managed.
+  fn get_ignored_foreground_window(&self) -> Option<WindowHandle>;
+  fn is_window_ignored(&self, handle: WindowHandle) -> bool;
+  /// Stops or resumes managing the window until Randolf exits, regardless of the exclusion settings.
+  fn set_window_ignored(&self, handle: WindowHandle, is_ignored: bool);
+  fn get_ignored_windows(&self) -> Vec<WindowHandle>;
+  /// Returns `true` if the window belongs to a process running with elevated (admin) privileges.
+  fn is_window_elevated(&self, handle: WindowHandle) -> bool;
+  /// Returns the file name of the executable of the window's process, e.g. `notepad.exe`, if it can be queried.
+  fn get_window_process_name(&self, handle: WindowHandle) -> Option<String>;
+  /// Returns the full path to the executable of the window's process, e.g. `C:\Windows\notepad.exe`, if it can be
+  /// queried.
+  fn get_window_executable_path(&self, handle: WindowHandle) -> Option<String>;
   fn is_window_hidden(&self, handle: &WindowHandle) -> bool;
+  /// Returns `true` if the window is set to be "always on top" (i.e. has the `WS_EX_TOPMOST` extended style).
+  fn is_window_always_on_top(&self, handle: WindowHandle) -> bool;
+  /// Sets or removes the "always on top" state of the window without moving, resizing, or activating it.
+  fn set_window_always_on_top(&self, handle: WindowHandle, is_always_on_top: bool);
+  /// Returns `true` if the window still exists and the process it belongs to has not exited.
+  fn is_window_alive(&self, handle: WindowHandle) -> bool;
+  /// Returns all visible, non-minimised windows owned by the given window, such as its modal dialogs.
+  fn get_owned_windows(&self, owner: WindowHandle) -> Vec<Window>;
+  /// Returns `false` if the window does not accept input, which is the case while one of its modal dialogs is open.
+  fn is_window_enabled(&self, handle: WindowHandle) -> bool;
   fn set_window_position(&self, handle: WindowHandle, rect: Rect);
   /// Moves windows atomically and orders them below the active/foreground window. Returns window handles of windows
   /// that could not be positioned (i.e. failures).
   fn set_window_positions(&self, positions: &[(WindowHandle, Rect)], active_handle: WindowHandle) -> Vec<WindowHandle>;
-  /// Sets the window position on the same monitor as the given rectangle. WARNING: Does not adjust for DPI scaling.
-  #[allow(dead_code)]
+  /// Sets the position of a window that is moved from the source monitor to the target monitor, so that it ends up
+  /// with the physical size of the given rectangle even if the monitors use different DPI scaling.
   fn set_window_position_with_dpi_adjustment(
     &self,
     window_handle: WindowHandle,
@@ -42,7 +68,14 @@ pub trait WindowsApi {
   fn set_window_placement_and_force_repaint(&self, handle: WindowHandle, placement: WindowPlacement);
   fn do_restore_window_placement(&self, handle: WindowHandle, previous_placement: WindowPlacement);
   fn get_cursor_position(&self) -> Point;
+  /// Returns the milliseconds since the user last pressed a key, clicked, or moved the mouse, or `0` if unknown.
+  fn get_milliseconds_since_last_input(&self) -> u32;
   fn set_cursor_position(&self, target_point: &Point);
+  /// Returns the current speed and acceleration of the cursor, or `None` if they cannot be read.
+  fn get_cursor_speed(&self) -> Option<CursorSpeed>;
+  /// Changes the speed and acceleration of the cursor for the current session only, i.e. without persisting them in the
+  /// user's profile, so that Windows falls back to the user's settings after signing out.
+  fn set_cursor_speed(&self, cursor_speed: &CursorSpeed);
   fn get_all_monitors(&self) -> Monitors;
   fn get_monitor_info_for_window(&self, handle: WindowHandle) -> Option<MonitorInfo>;
   fn get_monitor_info_for_monitor(&self, handle: MonitorHandle) -> Option<MonitorInfo>;
@@ -1,21 +1,29 @@
 use crate::api::WindowsApi;
 use crate::configuration_provider::{ALLOW_MOVING_CURSOR_AFTER_OPEN_CLOSE_OR_MINIMISE, ConfigurationProvider};
 use crate::files::{FileManager, FileType};
+use crate::task_runner::TaskRunner;
 use crate::utils::CONFIGURATION_PROVIDER_LOCK;
 use std::process::Command;
 use std::sync::{Arc, Mutex};
 
 // TODO: Instead of a fixed delay, consider listening for the relevant application to be ready before moving the cursor
 const FIXED_DELAY: u64 = 750;
+/// How long relaunched applications get to open their windows before the session is restored again to place them.
+const SESSION_RELAUNCH_DELAY: u64 = 5_000;
 
 pub struct ApplicationLauncher<T: WindowsApi> {
   _configuration_provider: Arc<Mutex<ConfigurationProvider>>,
   allow_moving_cursor_after_open: bool,
   windows_api: T,
+  task_runner: TaskRunner,
 }
 
 impl<T: WindowsApi> ApplicationLauncher<T> {
-  pub fn new_initialised(configuration_provider: Arc<Mutex<ConfigurationProvider>>, windows_api: T) -> Self {
+  pub fn new_initialised(
+    configuration_provider: Arc<Mutex<ConfigurationProvider>>,
+    windows_api: T,
+    task_runner: TaskRunner,
+  ) -> Self {
     let allow_moving_cursor_after_open = match configuration_provider.try_lock() {
       Ok(guard) => guard.get_bool(ALLOW_MOVING_CURSOR_AFTER_OPEN_CLOSE_OR_MINIMISE),
       Err(err) => {
@@ -29,6 +37,7 @@ impl<T: WindowsApi> ApplicationLauncher<T> {
       _configuration_provider: configuration_provider.clone(),
       allow_moving_cursor_after_open,
       windows_api,
+      task_runner,
     }
   }
 
@@ -42,11 +51,35 @@ impl<T: WindowsApi> ApplicationLauncher<T> {
       return;
     }
     if self.execute_command(&path_to_executable, args, as_admin) && self.allow_moving_cursor_after_open {
-      std::thread::sleep(std::time::Duration::from_millis(FIXED_DELAY));
-      self.set_cursor_position();
+      self.task_runner.spawn("wait-for-launched-application", || {
+        std::thread::sleep(std::time::Duration::from_millis(FIXED_DELAY));
+        Some(crate::common::Command::LaunchedApplicationReady)
+      });
     }
   }
 
+  /// Launches each of the executables once, e.g. the applications of windows that were missing when restoring a
+  /// session, and restores the session again (without relaunching) once they had time to open their windows. Returns
+  /// the number of launched applications.
+  pub fn relaunch_for_session(&self, mut executable_paths: Vec<String>) -> usize {
+    executable_paths.sort();
+    executable_paths.dedup();
+    let launched_count = executable_paths
+      .iter()
+      .filter(|path| path.ends_with(".exe"))
+      .filter(|path| self.execute_command(path, None, false))
+      .count();
+    if launched_count > 0 {
+      info!("Relaunched [{launched_count}] application(s) to restore the session");
+      self.task_runner.spawn("wait-for-relaunched-applications", || {
+        std::thread::sleep(std::time::Duration::from_millis(SESSION_RELAUNCH_DELAY));
+        Some(crate::common::Command::RestoreSession(false))
+      });
+    }
+
+    launched_count
+  }
+
   pub fn get_executable_path(&self) -> String {
     if let Ok(executable_path) = std::env::current_exe() {
       executable_path
@@ -124,7 +157,9 @@ impl<T: WindowsApi> ApplicationLauncher<T> {
     }
   }
 
-  fn set_cursor_position(&self) {
+  /// Moves the cursor to the center of the foreground window which, after launching an application, is expected to be
+  /// the newly opened window.
+  pub fn set_cursor_position(&self) {
     let Some(foreground_window) = self.windows_api.get_foreground_window() else {
       debug!("Failed to get foreground window, no window to set cursor position");
       return;
@@ -144,6 +179,7 @@ mod tests {
   use crate::api::MockWindowsApi;
   use crate::common::{Point, Sizing, WindowHandle};
   use crate::configuration_provider::ConfigurationProvider;
+  use crossbeam_channel::unbounded;
   use log::Level::Warn;
 
   #[test]
@@ -153,7 +189,8 @@ mod tests {
     MockWindowsApi::set_cursor_position(cursor_position);
     let mock_api = MockWindowsApi;
     let configuration_provider = Arc::new(Mutex::new(ConfigurationProvider::default()));
-    let launcher = ApplicationLauncher::new_initialised(configuration_provider.clone(), mock_api);
+    let launcher =
+      ApplicationLauncher::new_initialised(configuration_provider.clone(), mock_api, TaskRunner::new(unbounded().0));
 
     launcher.launch("C:\\does\\not\\exist.exe".to_string(), Some("C:\\does\\not\\exist"), false);
     launcher.launch("not an executable".to_string(), None, false);
@@ -177,13 +214,29 @@ mod tests {
     assert_eq!(mock_api.get_cursor_position(), cursor_position);
   }
 
+  #[test]
+  fn relaunch_for_session_does_not_restore_session_again_if_nothing_was_launched() {
+    let (sender, receiver) = unbounded();
+    let configuration_provider = Arc::new(Mutex::new(ConfigurationProvider::default()));
+    let launcher = ApplicationLauncher::new_initialised(configuration_provider, MockWindowsApi, TaskRunner::new(sender));
+
+    let launched_count = launcher.relaunch_for_session(vec![
+      "C:\\does\\not\\exist.exe".to_string(),
+      "C:\\does\\not\\exist.exe".to_string(),
+      "not an executable".to_string(),
+    ]);
+
+    assert_eq!(launched_count, 0);
+    assert!(receiver.try_recv().is_err());
+  }
+
   #[test]
   fn set_cursor_position_does_nothing_when_foreground_window_is_none() {
     let cursor_position = Point::new(-1, -1);
     MockWindowsApi::set_cursor_position(cursor_position);
     let mock_api = MockWindowsApi;
     let config_provider = Arc::new(Mutex::new(ConfigurationProvider::default()));
-    let launcher = ApplicationLauncher::new_initialised(config_provider, mock_api);
+    let launcher = ApplicationLauncher::new_initialised(config_provider, mock_api, TaskRunner::new(unbounded().0));
 
     launcher.set_cursor_position();
 
@@ -198,7 +251,7 @@ mod tests {
     MockWindowsApi::set_foreground_window(foreground_window_handle);
     let mock_api = MockWindowsApi;
     let config_provider = Arc::new(Mutex::new(ConfigurationProvider::default()));
-    let launcher = ApplicationLauncher::new_initialised(config_provider, mock_api);
+    let launcher = ApplicationLauncher::new_initialised(config_provider, mock_api, TaskRunner::new(unbounded().0));
 
     launcher.set_cursor_position();
 
@@ -212,7 +265,7 @@ mod tests {
     MockWindowsApi::add_or_update_window(handle, "Test Window".to_string(), sizing, false, false, true);
     let mock_api = MockWindowsApi;
     let config_provider = Arc::new(Mutex::new(ConfigurationProvider::default()));
-    let launcher = ApplicationLauncher::new_initialised(config_provider, mock_api);
+    let launcher = ApplicationLauncher::new_initialised(config_provider, mock_api, TaskRunner::new(unbounded().0));
 
     launcher.set_cursor_position();
 
@@ -222,7 +275,7 @@ mod tests {
   #[test]
   fn get_executable_folder_returns_correct_path() {
     let config_provider = Arc::new(Mutex::new(ConfigurationProvider::default()));
-    let launcher = ApplicationLauncher::new_initialised(config_provider, MockWindowsApi);
+    let launcher = ApplicationLauncher::new_initialised(config_provider, MockWindowsApi, TaskRunner::new(unbounded().0));
 
     let folder = launcher.get_executable_folder();
 
@@ -232,7 +285,7 @@ mod tests {
   #[test]
   fn get_executable_path_returns_path_to_an_executable() {
     let config_provider = Arc::new(Mutex::new(ConfigurationProvider::default()));
-    let launcher = ApplicationLauncher::new_initialised(config_provider, MockWindowsApi);
+    let launcher = ApplicationLauncher::new_initialised(config_provider, MockWindowsApi, TaskRunner::new(unbounded().0));
 
     let path = launcher.get_executable_path();
 
@@ -242,7 +295,7 @@ mod tests {
   #[test]
   fn get_project_folder_returns_a_path() {
     let config_provider = Arc::new(Mutex::new(ConfigurationProvider::default()));
-    let launcher = ApplicationLauncher::new_initialised(config_provider, MockWindowsApi);
+    let launcher = ApplicationLauncher::new_initialised(config_provider, MockWindowsApi, TaskRunner::new(unbounded().0));
 
     let folder = launcher.get_project_folder(FileType::Data);
 
@@ -0,0 +1,408 @@
+use directories::ProjectDirs;
+use randolf_ipc::{IPC_TOKEN_FILE_NAME, IpcRequest, IpcResponse};
+use std::env;
+use std::fs;
+use std::io::{self, BufReader};
+use std::net::{Ipv4Addr, TcpStream};
+use std::process::ExitCode;
+
+// Must match the project directories, configuration file, and data files used by Randolf itself
+const PROJECT_DIR_QUALIFIER: &str = "io";
+const PROJECT_DIR_ORGANISATION_NAME: &str = "kimgoetzke";
+const PROJECT_DIR_APPLICATION_NAME: &str = "randolf";
+const CONFIGURATION_FILE_NAME: &str = "randolf.toml";
+const IPC_SERVER_PORT: &str = "ipc_server_port";
+
+const USAGE: &str = "Controls a running instance of Randolf via its IPC server (see ipc_server_port in randolf.toml).
+
+Usage: randolf-cli [--port <port>] <command>
+
+Commands:
+  workspace switch <number>             Switch to the workspace with the given number
+  workspace next|previous               Switch to the next or previous workspace of the monitor under the cursor
+  workspace create|delete               Create or delete a workspace on the monitor under the cursor
+  workspace note                        Toggle the note of the workspace under the cursor
+  window move <direction>               Move the foreground window
+  window resize <direction>             Resize the foreground window in the spatial layout
+  window resize-scrolling <direction>   Narrow or widen the foreground window in the scrolling layout
+  window move-edge <direction>          Move the edge the foreground window shares with its neighbours
+  window move-to-workspace <number>     Move the foreground window to the workspace with the given number
+  window move-to-monitor <target>       Move the foreground window to the active workspace of another monitor
+  window close|minimise                 Close or minimise the foreground window
+  window near-maximise                  Near-maximise the foreground window or restore it
+  window restore-older-placement        Restore an older placement of the foreground window
+  window picture-in-picture             Toggle picture-in-picture for the foreground window
+  window monocle                        Toggle monocle mode for the workspace of the foreground window
+  window ignore                         Stop managing the foreground window or, if it is ignored, manage it again
+  window focus next|previous            Focus the next or previous window of the current workspace
+  window bring-here <hwnd>              Move a window hidden on another workspace here, see query windows
+  window reconcile-rules                Move windows to the workspaces required by the current window rules
+  window rescue-offscreen               Move windows that cannot be reached with the mouse onto the nearest monitor
+  cursor move|throw <direction>         Move the cursor to the closest window or throw it to the next monitor
+  session save                          Save the placement and workspace of every managed window
+  session restore [--relaunch]          Restore the saved session and optionally relaunch missing applications
+  query windows [--json] [<filters>]    Print the managed windows as TOML or, with --json, as JSON
+  query workspaces [--json]             Print every workspace, e.g. whether it is active and its window count
+  health [--json]                       Print the version, uptime, health of each hook, and every workspace
+  events                                Print events, e.g. when a window is dragged, until Randolf exits
+  send <line>                           Send a line using the syntax of Randolf's command file
+  help                                  Print this message
+
+<direction> is left, right, up, or down, and <target> is a <direction> or a monitor number. The filters of
+query windows are --workspace <number>, --monitor <device name>, --process <executable name>, and
+--visibility visible|hidden.";
+
+/// The format in which the data of a query is printed.
+#[derive(Debug, PartialEq, Eq)]
+enum OutputFormat {
+  Toml,
+  Json,
+}
+
+/// A request for the IPC server, translated from the arguments of this binary.
+#[derive(Debug, PartialEq, Eq)]
+struct Request {
+  request: IpcRequest,
+  output_format: OutputFormat,
+}
+
+fn main() -> ExitCode {
+  let args = env::args().skip(1).collect::<Vec<_>>();
+  let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+  match run(&args) {
+    Ok(()) => ExitCode::SUCCESS,
+    Err(err) => {
+      eprintln!("error: {err}");
+      ExitCode::FAILURE
+    }
+  }
+}
+
+fn run(args: &[&str]) -> Result<(), String> {
+  let (port, args) = match args {
+    ["--port", port, args @ ..] => (
+      Some(port.parse::<u16>().map_err(|_| format!("[{port}] is not a valid port"))?),
+      args,
+    ),
+    args => (None, args),
+  };
+  let Some(request) = parse_request(args)? else {
+    println!("{USAGE}");
+    return Ok(());
+  };
+  let port = match port {
+    Some(port) => port,
+    None => read_configured_port()?,
+  };
+  let token = read_token()?;
+  let mut reader = send_request(port, &token, &request.request)?;
+  match read_response(&mut reader)? {
+    IpcResponse::Ok if request.request == IpcRequest::SubscribeEvents => print_events(&mut reader),
+    IpcResponse::Ok => Ok(()),
+    IpcResponse::NoOp { reason } => {
+      println!("no-op: {reason}");
+      Ok(())
+    }
+    IpcResponse::Error { reason } => Err(reason),
+    IpcResponse::Data { data } => print_data(data, &request.output_format),
+    IpcResponse::Event { event } => Err(format!("Received event {event:?} without subscribing to events")),
+  }
+}
+
+/// Translates the arguments into a request for the IPC server which, unless it subscribes to events, contains a line
+/// using the syntax of the command file. Returns `None` if the usage should be printed instead. Arguments of commands,
+/// e.g. directions, are validated by Randolf, so that this binary does not need to be updated whenever Randolf accepts
+/// new values.
+fn parse_request(args: &[&str]) -> Result<Option<Request>, String> {
+  if let ["query", "windows", options @ ..] = args {
+    return parse_window_query(options).map(Some);
+  }
+  if let ["query", "workspaces", options @ ..] = args {
+    return Ok(Some(Request {
+      request: command("list-workspaces"),
+      output_format: parse_output_format(options, "query workspaces")?,
+    }));
+  }
+  if let ["health", options @ ..] = args {
+    return Ok(Some(Request {
+      request: IpcRequest::Health,
+      output_format: parse_output_format(options, "health")?,
+    }));
+  }
+  let line = match args {
+    [] | ["help" | "--help" | "-h"] => return Ok(None),
+    ["events"] => {
+      return Ok(Some(Request {
+        request: IpcRequest::SubscribeEvents,
+        output_format: OutputFormat::Json,
+      }));
+    }
+    ["workspace", "switch", number] => format!("switch-workspace {number}"),
+    ["workspace", action @ ("next" | "previous" | "create" | "delete")] => format!("{action}-workspace"),
+    ["workspace", "note"] => "toggle-workspace-note".to_string(),
+    ["window", "move", direction] => format!("move-window {direction}"),
+    ["window", "resize", direction] => format!("resize-spatial-window {direction}"),
+    ["window", "resize-scrolling", direction] => format!("resize-scrolling-window {direction}"),
+    ["window", "move-edge", direction] => format!("move-shared-edge {direction}"),
+    ["window", "move-to-workspace", number] => format!("move-window-to-workspace {number}"),
+    ["window", "move-to-monitor", target] => format!("move-window-to-monitor {target}"),
+    ["window", action @ ("close" | "minimise" | "near-maximise")] => format!("{action}-window"),
+    ["window", "restore-older-placement"] => "restore-older-placement".to_string(),
+    ["window", "picture-in-picture"] => "toggle-picture-in-picture".to_string(),
+    ["window", "monocle"] => "toggle-monocle".to_string(),
+    ["window", "ignore"] => "toggle-manage-window".to_string(),
+    ["window", "focus", action @ ("next" | "previous")] => format!("focus-{action}-window"),
+    ["window", "bring-here", hwnd] => format!("bring-window-here {hwnd}"),
+    ["window", "reconcile-rules"] => "reconcile-window-rules".to_string(),
+    ["window", "rescue-offscreen"] => "rescue-offscreen-windows".to_string(),
+    ["cursor", action @ ("move" | "throw"), direction] => format!("{action}-cursor {direction}"),
+    ["session", "save"] => "save-session".to_string(),
+    ["session", "restore"] => "restore-session".to_string(),
+    ["session", "restore", "--relaunch"] => "restore-session relaunch".to_string(),
+    ["send", line @ ..] if !line.is_empty() => line.join(" "),
+    _ => {
+      return Err(format!(
+        "[{}] is not a known command, run randolf-cli help to see all commands",
+        args.join(" ")
+      ));
+    }
+  };
+
+  Ok(Some(Request {
+    request: command(&line),
+    output_format: OutputFormat::Toml,
+  }))
+}
+
+fn command(line: &str) -> IpcRequest {
+  IpcRequest::Command { line: line.to_string() }
+}
+
+/// Parses the options of a query that only supports `--json`.
+fn parse_output_format(options: &[&str], query: &str) -> Result<OutputFormat, String> {
+  match options {
+    [] => Ok(OutputFormat::Toml),
+    ["--json"] => Ok(OutputFormat::Json),
+    _ => Err(format!("[{}] is not an option of {query}", options.join(" "))),
+  }
+}
+
+/// Translates the options of `query windows` into a `list-windows` request.
+fn parse_window_query(options: &[&str]) -> Result<Request, String> {
+  let mut line = "list-windows".to_string();
+  let mut output_format = OutputFormat::Toml;
+  let mut options = options.iter();
+  while let Some(option) = options.next() {
+    let key = match *option {
+      "--json" => {
+        output_format = OutputFormat::Json;
+        continue;
+      }
+      "--workspace" => "workspace",
+      "--monitor" => "monitor",
+      "--process" => "process",
+      "--visibility" => "visibility",
+      _ => return Err(format!("[{option}] is not an option of query windows")),
+    };
+    let value = options.next().ok_or_else(|| format!("[{option}] requires a value"))?;
+    line.push_str(&format!(" {key}={value}"));
+  }
+
+  Ok(Request {
+    request: command(&line),
+    output_format,
+  })
+}
+
+/// Reads the port of the IPC server from Randolf's configuration file.
+fn read_configured_port() -> Result<u16, String> {
+  let path = get_project_directories()
+    .ok_or("Could not determine Randolf's configuration folder, use --port instead")?
+    .config_dir()
+    .join(CONFIGURATION_FILE_NAME);
+  let configuration = fs::read_to_string(&path)
+    .map_err(|err| format!("Failed to read [{}], use --port instead: {err}", path.display()))?
+    .parse::<toml::Table>()
+    .map_err(|err| format!("Failed to parse [{}], use --port instead: {err}", path.display()))?;
+  let port = configuration
+    .get("general")
+    .and_then(|general| general.get(IPC_SERVER_PORT))
+    .and_then(toml::Value::as_integer)
+    .unwrap_or_default();
+  match u16::try_from(port) {
+    Ok(port) if port > 0 => Ok(port),
+    _ => Err(format!(
+      "The IPC server is disabled, set [{IPC_SERVER_PORT}] in [{}] and restart Randolf",
+      path.display()
+    )),
+  }
+}
+
+/// Reads the token that Randolf wrote to its data folder when it started, which the IPC server requires before any
+/// other request.
+fn read_token() -> Result<String, String> {
+  let path = get_project_directories()
+    .ok_or("Could not determine Randolf's data folder")?
+    .data_local_dir()
+    .join(IPC_TOKEN_FILE_NAME);
+  let token_file = fs::read_to_string(&path)
+    .map_err(|err| format!("Failed to read [{}], is Randolf running? {err}", path.display()))?
+    .parse::<toml::Table>()
+    .map_err(|err| format!("Failed to parse [{}]: {err}", path.display()))?;
+
+  token_file
+    .get("token")
+    .and_then(toml::Value::as_str)
+    .map(str::to_string)
+    .ok_or_else(|| format!("[{}] does not contain a token", path.display()))
+}
+
+fn get_project_directories() -> Option<ProjectDirs> {
+  ProjectDirs::from(
+    PROJECT_DIR_QUALIFIER,
+    PROJECT_DIR_ORGANISATION_NAME,
+    PROJECT_DIR_APPLICATION_NAME,
+  )
+}
+
+/// Connects to the IPC server, authenticates with the token, and sends the request.
+fn send_request(port: u16, token: &str, request: &IpcRequest) -> Result<BufReader<TcpStream>, String> {
+  let mut stream = TcpStream::connect((Ipv4Addr::LOCALHOST, port))
+    .map_err(|err| format!("Failed to connect to Randolf on port [{port}], is it running? {err}"))?;
+  IpcRequest::Authenticate {
+    token: token.to_string(),
+  }
+  .write_to(&mut stream)
+  .map_err(|err| format!("Failed to authenticate: {err}"))?;
+  let mut reader = BufReader::new(stream);
+  match read_response(&mut reader)? {
+    IpcResponse::Ok => {}
+    IpcResponse::Error { reason } => return Err(format!("Randolf rejected the token, try again: {reason}")),
+    response => return Err(format!("Received {response:?} instead of an answer to authenticating")),
+  }
+  request
+    .write_to(reader.get_mut())
+    .map_err(|err| format!("Failed to send request: {err}"))?;
+
+  Ok(reader)
+}
+
+fn read_response(reader: &mut BufReader<TcpStream>) -> Result<IpcResponse, String> {
+  IpcResponse::read_from(reader).map_err(|err| format!("Failed to read response: {err}"))
+}
+
+/// Prints each event as a line of JSON until Randolf closes the connection.
+fn print_events(reader: &mut BufReader<TcpStream>) -> Result<(), String> {
+  loop {
+    match IpcResponse::read_from(reader) {
+      Ok(IpcResponse::Event { event }) => match serde_json::to_string(&event) {
+        Ok(event) => println!("{event}"),
+        Err(err) => return Err(format!("Failed to print event: {err}")),
+      },
+      Ok(response) => return Err(format!("Received {response:?} instead of an event")),
+      Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+      Err(err) => return Err(format!("Failed to read event: {err}")),
+    }
+  }
+}
+
+/// Prints the result of a query either as TOML, which for `list-windows` and `list-workspaces` is the same format as
+/// the corresponding file in the data folder, or as JSON. Lists, i.e. objects with a single array such as
+/// `{"windows":[...]}`, are printed as the JSON array itself.
+fn print_data(data: serde_json::Value, output_format: &OutputFormat) -> Result<(), String> {
+  match output_format {
+    OutputFormat::Toml => print!(
+      "{}",
+      toml::to_string(&data).map_err(|err| format!("Failed to print response: {err}"))?
+    ),
+    OutputFormat::Json => {
+      let list = data
+        .as_object()
+        .filter(|object| object.len() == 1)
+        .and_then(|object| object.values().next())
+        .filter(|value| value.is_array());
+      println!("{}", list.unwrap_or(&data));
+    }
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn line(args: &[&str]) -> String {
+    let request = parse_request(args)
+      .expect("Failed to parse request")
+      .expect("Expected a request")
+      .request;
+    match request {
+      IpcRequest::Command { line } => line,
+      request => panic!("Expected a command but received {request:?}"),
+    }
+  }
+
+  #[test]
+  fn parse_request_translates_subcommands_into_command_file_syntax() {
+    assert_eq!(line(&["workspace", "switch", "3"]), "switch-workspace 3");
+    assert_eq!(line(&["workspace", "next"]), "next-workspace");
+    assert_eq!(line(&["window", "move", "left"]), "move-window left");
+    assert_eq!(line(&["window", "near-maximise"]), "near-maximise-window");
+    assert_eq!(line(&["window", "move-to-workspace", "2"]), "move-window-to-workspace 2");
+    assert_eq!(line(&["window", "move-to-monitor", "left"]), "move-window-to-monitor left");
+    assert_eq!(line(&["cursor", "throw", "up"]), "throw-cursor up");
+    assert_eq!(line(&["window", "focus", "previous"]), "focus-previous-window");
+    assert_eq!(line(&["window", "bring-here", "132456"]), "bring-window-here 132456");
+    assert_eq!(line(&["window", "ignore"]), "toggle-manage-window");
+    assert_eq!(line(&["window", "reconcile-rules"]), "reconcile-window-rules");
+    assert_eq!(line(&["window", "rescue-offscreen"]), "rescue-offscreen-windows");
+    assert_eq!(line(&["session", "restore", "--relaunch"]), "restore-session relaunch");
+    assert_eq!(line(&["send", "restore-all-hidden-windows"]), "restore-all-hidden-windows");
+  }
+
+  #[test]
+  fn parse_request_translates_events_and_health_into_their_own_requests() {
+    assert_eq!(
+      parse_request(&["events"]).map(|request| request.map(|request| request.request)),
+      Ok(Some(IpcRequest::SubscribeEvents))
+    );
+    assert_eq!(
+      parse_request(&["health", "--json"]),
+      Ok(Some(Request {
+        request: IpcRequest::Health,
+        output_format: OutputFormat::Json,
+      }))
+    );
+    assert!(parse_request(&["health", "--verbose"]).is_err());
+  }
+
+  #[test]
+  fn parse_request_returns_none_for_help_and_rejects_unknown_commands() {
+    assert_eq!(parse_request(&[]), Ok(None));
+    assert_eq!(parse_request(&["help"]), Ok(None));
+    assert!(parse_request(&["window", "fly"]).is_err());
+    assert!(parse_request(&["send"]).is_err());
+  }
+
+  #[test]
+  fn parse_request_translates_query_options_into_filters() {
+    assert_eq!(
+      parse_request(&["query", "windows", "--json", "--workspace", "2", "--process", "notepad.exe"]),
+      Ok(Some(Request {
+        request: command("list-windows workspace=2 process=notepad.exe"),
+        output_format: OutputFormat::Json,
+      }))
+    );
+    assert!(parse_request(&["query", "windows", "--workspace"]).is_err());
+    assert!(parse_request(&["query", "windows", "--title", "Notepad"]).is_err());
+    assert_eq!(
+      parse_request(&["query", "workspaces", "--json"]),
+      Ok(Some(Request {
+        request: command("list-workspaces"),
+        output_format: OutputFormat::Json,
+      }))
+    );
+    assert!(parse_request(&["query", "workspaces", "--active"]).is_err());
+  }
+}
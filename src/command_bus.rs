@@ -0,0 +1,144 @@
+use crate::common::Command;
+use crossbeam_channel::Receiver;
+use std::collections::VecDeque;
+
+/// Queues commands received from the tray menu, hotkeys, and drag manager, and coalesces redundant consecutive
+/// commands before they are executed. For example, rapidly pressing a workspace hotkey only results in a single
+/// workspace switch to the last target, rather than one full switch per key press.
+pub struct CommandBus {
+  receiver: Receiver<Command>,
+  queue: VecDeque<Command>,
+}
+
+impl CommandBus {
+  pub fn new(receiver: Receiver<Command>) -> Self {
+    Self {
+      receiver,
+      queue: VecDeque::new(),
+    }
+  }
+
+  /// Moves all commands that have been received since the last call into the queue and returns the oldest command
+  /// that has not been superseded by a later one.
+  pub fn next_command(&mut self) -> Option<Command> {
+    while let Ok(command) = self.receiver.try_recv() {
+      self.enqueue(command);
+    }
+    self.queue.pop_front()
+  }
+
+  /// Returns the number of commands that are waiting to be executed, including those not yet moved into the queue.
+  pub fn queue_depth(&self) -> usize {
+    self.queue.len() + self.receiver.len()
+  }
+
+  fn enqueue(&mut self, command: Command) {
+    if let Some(previous) = self.queue.back()
+      && command.supersedes(previous)
+    {
+      debug!("Dropping command [{}] because it is superseded by [{}]", previous, command);
+      self.queue.pop_back();
+    }
+    self.queue.push_back(command);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::common::{Direction, PersistentWorkspaceId, WindowHandle};
+  use crossbeam_channel::unbounded;
+
+  fn workspace_id(monitor: u16, workspace: usize) -> PersistentWorkspaceId {
+    let mut monitor_id = [0; 32];
+    monitor_id[0] = monitor;
+    PersistentWorkspaceId::new(monitor_id, workspace, monitor == 1)
+  }
+
+  #[test]
+  fn next_command_coalesces_consecutive_workspace_switches_on_same_monitor() {
+    let (sender, receiver) = unbounded();
+    let mut command_bus = CommandBus::new(receiver);
+    sender.send(Command::SwitchWorkspace(workspace_id(1, 1))).unwrap();
+    sender.send(Command::SwitchWorkspace(workspace_id(1, 2))).unwrap();
+    sender.send(Command::SwitchWorkspace(workspace_id(1, 3))).unwrap();
+
+    let first = command_bus.next_command();
+    let second = command_bus.next_command();
+
+    assert!(matches!(first, Some(Command::SwitchWorkspace(id)) if id == workspace_id(1, 3)));
+    assert!(second.is_none());
+  }
+
+  #[test]
+  fn next_command_keeps_workspace_switches_on_different_monitors() {
+    let (sender, receiver) = unbounded();
+    let mut command_bus = CommandBus::new(receiver);
+    sender.send(Command::SwitchWorkspace(workspace_id(1, 2))).unwrap();
+    sender.send(Command::SwitchWorkspace(workspace_id(2, 2))).unwrap();
+
+    let first = command_bus.next_command();
+    let second = command_bus.next_command();
+
+    assert!(matches!(first, Some(Command::SwitchWorkspace(id)) if id == workspace_id(1, 2)));
+    assert!(matches!(second, Some(Command::SwitchWorkspace(id)) if id == workspace_id(2, 2)));
+  }
+
+  #[test]
+  fn next_command_does_not_coalesce_commands_that_are_not_consecutive() {
+    let (sender, receiver) = unbounded();
+    let mut command_bus = CommandBus::new(receiver);
+    sender.send(Command::SwitchWorkspace(workspace_id(1, 2))).unwrap();
+    sender.send(Command::MoveWindow(Direction::Left)).unwrap();
+    sender.send(Command::SwitchWorkspace(workspace_id(1, 3))).unwrap();
+
+    let commands = std::iter::from_fn(|| command_bus.next_command()).collect::<Vec<_>>();
+
+    assert_eq!(commands.len(), 3);
+  }
+
+  #[test]
+  fn next_command_coalesces_consecutive_title_changes_of_the_same_window_only() {
+    let (sender, receiver) = unbounded();
+    let mut command_bus = CommandBus::new(receiver);
+    sender.send(Command::WindowTitleChanged(WindowHandle::new(1))).unwrap();
+    sender.send(Command::WindowTitleChanged(WindowHandle::new(2))).unwrap();
+    sender.send(Command::WindowTitleChanged(WindowHandle::new(2))).unwrap();
+
+    let first = command_bus.next_command();
+    let second = command_bus.next_command();
+    let third = command_bus.next_command();
+
+    assert!(matches!(first, Some(Command::WindowTitleChanged(handle)) if handle == WindowHandle::new(1)));
+    assert!(matches!(second, Some(Command::WindowTitleChanged(handle)) if handle == WindowHandle::new(2)));
+    assert!(third.is_none());
+  }
+
+  #[test]
+  fn next_command_coalesces_consecutive_display_changes() {
+    let (sender, receiver) = unbounded();
+    let mut command_bus = CommandBus::new(receiver);
+    sender.send(Command::DisplayChanged).unwrap();
+    sender.send(Command::DisplayChanged).unwrap();
+
+    let first = command_bus.next_command();
+    let second = command_bus.next_command();
+
+    assert!(matches!(first, Some(Command::DisplayChanged)));
+    assert!(second.is_none());
+  }
+
+  #[test]
+  fn queue_depth_counts_received_and_queued_commands() {
+    let (sender, receiver) = unbounded();
+    let mut command_bus = CommandBus::new(receiver);
+    sender.send(Command::MoveWindow(Direction::Left)).unwrap();
+    sender.send(Command::MoveWindow(Direction::Right)).unwrap();
+    sender.send(Command::CloseWindow).unwrap();
+
+    command_bus.next_command();
+    sender.send(Command::MinimiseWindow).unwrap();
+
+    assert_eq!(command_bus.queue_depth(), 3);
+  }
+}
@@ -0,0 +1,565 @@
+use crate::common::{Command, Direction, WindowHandle, WindowQuery, WindowVisibility};
+use crate::configuration_provider::{ConfigurationProvider, ENABLE_COMMAND_FILE};
+use crate::files::{FileManager, FileType};
+use crate::utils::CONFIGURATION_PROVIDER_LOCK;
+use crossbeam_channel::Sender;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+pub const COMMAND_FILE_NAME: &str = "commands.txt";
+/// The number of most recent request IDs that are remembered to skip commands that were appended more than once.
+const REMEMBERED_REQUEST_ID_COUNT: usize = 100;
+
+/// Executes commands that other tools append to `commands.txt` in the data folder, one command per line, if enabled via
+/// `enable_command_file`. This is a simple integration path for tools that cannot use hotkeys. Only lines appended
+/// while Randolf is running are executed, and a line is only read once it ends with a newline.
+pub struct CommandFileManager {
+  path: Option<PathBuf>,
+  sender: Sender<Command>,
+  offset: u64,
+  executed_request_ids: VecDeque<String>,
+}
+
+impl CommandFileManager {
+  pub fn new(configuration_provider: Arc<Mutex<ConfigurationProvider>>, sender: Sender<Command>) -> Self {
+    let is_enabled = match configuration_provider.try_lock() {
+      Ok(guard) => guard.get_bool(ENABLE_COMMAND_FILE),
+      Err(err) => {
+        error!(
+          "Command file is disabled because: {} with error: {}",
+          CONFIGURATION_PROVIDER_LOCK, err
+        );
+        false
+      }
+    };
+    if !is_enabled {
+      return Self::new_with(None, sender);
+    }
+    match FileManager::<String>::get_path_to_file(COMMAND_FILE_NAME, FileType::Data) {
+      Ok(path) => Self::new_with(Some(path), sender),
+      Err(err) => {
+        error!("Command file is disabled because its path could not be determined: {err}");
+        Self::new_with(None, sender)
+      }
+    }
+  }
+
+  /// Creates a manager for the given file which skips any commands that are already in the file, so that stale
+  /// commands from a previous session are never executed.
+  fn new_with(path: Option<PathBuf>, sender: Sender<Command>) -> Self {
+    let offset = path
+      .as_ref()
+      .and_then(|path| path.metadata().ok())
+      .map(|metadata| metadata.len())
+      .unwrap_or_default();
+    if let Some(path) = &path {
+      info!("Watching command file at [{}]", path.display());
+    }
+
+    Self {
+      path,
+      sender,
+      offset,
+      executed_request_ids: VecDeque::new(),
+    }
+  }
+
+  /// Sends all commands that were appended to the command file since the last call, including repeated ones, e.g. to
+  /// move a window two steps at once. A line may start with a request ID, e.g. `@42 next-workspace`, in which case it
+  /// is skipped if a command with the same ID was executed recently, e.g. when a tool retries appending a command.
+  /// Lines that cannot be parsed are logged and skipped without remembering their request ID.
+  pub fn process_appended_commands(&mut self) {
+    let Some(appended) = self.read_appended_lines() else {
+      return;
+    };
+    for line in appended.lines().map(str::trim) {
+      if line.is_empty() || line.starts_with('#') {
+        continue;
+      }
+      let (request_id, line) = split_request_id(line);
+      if let Some(request_id) = request_id
+        && self.executed_request_ids.iter().any(|id| id == request_id)
+      {
+        debug!("Ignored command [{line}] in command file because request [{request_id}] was executed already");
+        continue;
+      }
+      match parse_command(line) {
+        Ok(command) => {
+          info!("Command file requested [{line}]");
+          self.sender.send(command).expect("Failed to send command from command file");
+          if let Some(request_id) = request_id {
+            self.remember_executed_request_id(request_id);
+          }
+        }
+        Err(err) => warn!("Ignored invalid line [{line}] in command file: {err}"),
+      }
+    }
+  }
+
+  /// Remembers the ID of a request whose command was sent, forgetting the oldest ID once
+  /// [`REMEMBERED_REQUEST_ID_COUNT`] IDs are remembered. IDs of lines that could not be parsed are not remembered, so
+  /// that a tool can retry a corrected command with the same request ID.
+  fn remember_executed_request_id(&mut self, request_id: &str) {
+    if self.executed_request_ids.len() == REMEMBERED_REQUEST_ID_COUNT {
+      self.executed_request_ids.pop_front();
+    }
+    self.executed_request_ids.push_back(request_id.to_string());
+  }
+
+  /// Reads all complete lines appended since the last call. Starts from the beginning again if the file was truncated
+  /// or replaced, e.g. by a tool that clears it after its commands were executed.
+  fn read_appended_lines(&mut self) -> Option<String> {
+    let path = self.path.as_ref()?;
+    let length = path.metadata().ok()?.len();
+    if length < self.offset {
+      debug!("Command file was truncated, reading it from the start");
+      self.offset = 0;
+    }
+    if length == self.offset {
+      return None;
+    }
+    let mut buffer = Vec::new();
+    let result = File::open(path).and_then(|mut file| {
+      file.seek(SeekFrom::Start(self.offset))?;
+      file.read_to_end(&mut buffer)
+    });
+    if let Err(err) = result {
+      error!("Failed to read command file [{}]: {err}", path.display());
+      return None;
+    }
+    let complete_length = buffer.iter().rposition(|byte| *byte == b'\n').map(|index| index + 1)?;
+    buffer.truncate(complete_length);
+    self.offset += complete_length as u64;
+
+    Some(String::from_utf8_lossy(&buffer).into_owned())
+  }
+}
+
+/// Splits the optional request ID, which starts with `@`, from the command of a line of the command file.
+fn split_request_id(line: &str) -> (Option<&str>, &str) {
+  match line.strip_prefix('@').and_then(|rest| rest.split_once(char::is_whitespace)) {
+    Some((request_id, command)) => (Some(request_id), command.trim_start()),
+    None => (None, line),
+  }
+}
+
+/// Parses a line of the command file or a request to the IPC server, e.g. `move-window left`, into a command.
+pub fn parse_command(line: &str) -> Result<Command, String> {
+  let mut parts = line.split_whitespace();
+  let name = parts.next().ok_or("line is empty")?;
+  if name == "list-windows" {
+    return parse_window_query(parts).map(Command::ListWindows);
+  }
+  let argument = parts.next();
+  if parts.next().is_some() {
+    return Err("too many arguments".to_string());
+  }
+  let command = match (name, argument) {
+    ("close-window", None) => Command::CloseWindow,
+    ("near-maximise-window", None) => Command::NearMaximiseWindow,
+    ("restore-older-placement", None) => Command::RestoreOlderPlacement,
+    ("toggle-picture-in-picture", None) => Command::TogglePictureInPicture,
+    ("toggle-monocle", None) => Command::ToggleMonocle,
+    ("toggle-workspace-note", None) => Command::ToggleWorkspaceNote,
+    ("toggle-manage-window", None) => Command::ToggleManageWindow,
+    ("minimise-window", None) => Command::MinimiseWindow,
+    ("move-window", Some(direction)) => Command::MoveWindow(parse_direction(direction)?),
+    ("resize-spatial-window", Some(direction)) => Command::ResizeSpatialWindow(parse_direction(direction)?),
+    ("resize-scrolling-window", Some(direction)) => Command::ResizeScrollingWindow(parse_direction(direction)?),
+    ("move-shared-edge", Some(direction)) => Command::MoveSharedEdge(parse_direction(direction)?),
+    ("rotate-tiled-split", None) => Command::RotateTiledSplit,
+    ("swap-tiled-windows", None) => Command::SwapTiledWindows,
+    ("move-cursor", Some(direction)) => Command::MoveCursor(parse_direction(direction)?),
+    ("throw-cursor", Some(direction)) => Command::ThrowCursor(parse_direction(direction)?),
+    ("focus-next-window", None) => Command::FocusNextWindow,
+    ("focus-previous-window", None) => Command::FocusPreviousWindow,
+    ("switch-workspace", Some(number)) => Command::SwitchToWorkspaceNumber(parse_workspace_number(number)?),
+    ("move-window-to-workspace", Some(number)) => Command::MoveWindowToWorkspaceNumber(parse_workspace_number(number)?),
+    ("move-window-to-monitor", Some(target)) => parse_monitor_target(target)?,
+    ("bring-window-here", Some(hwnd)) => Command::BringWindowHere(parse_window_handle(hwnd)?),
+    ("next-workspace", None) => Command::NextWorkspace,
+    ("previous-workspace", None) => Command::PreviousWorkspace,
+    ("create-workspace", None) => Command::CreateWorkspace,
+    ("delete-workspace", None) => Command::DeleteWorkspace,
+    ("restore-all-hidden-windows", None) => Command::RestoreAllHiddenWindows,
+    ("reinitialise", None) => Command::Reinitialise,
+    ("enable-trace-logging", None) => Command::EnableTraceLogging,
+    ("save-session", None) => Command::SaveSession,
+    ("list-workspaces", None) => Command::ListWorkspaces,
+    ("reconcile-window-rules", None) => Command::ReconcileWindowRules,
+    ("rescue-offscreen-windows", None) => Command::RescueOffscreenWindows,
+    ("restore-session", None) => Command::RestoreSession(false),
+    ("restore-session", Some("relaunch")) => Command::RestoreSession(true),
+    ("restore-session", Some(argument)) => return Err(format!("[{argument}] is not an option, use relaunch")),
+    (
+      "move-window"
+      | "resize-spatial-window"
+      | "resize-scrolling-window"
+      | "move-shared-edge"
+      | "move-cursor"
+      | "throw-cursor",
+      None,
+    ) => return Err(format!("[{name}] requires a direction")),
+    ("switch-workspace" | "move-window-to-workspace", None) => return Err(format!("[{name}] requires a workspace number")),
+    ("bring-window-here", None) => return Err(format!("[{name}] requires a window handle")),
+    ("move-window-to-monitor", None) => return Err(format!("[{name}] requires a direction or a monitor number")),
+    (_, Some(_)) if is_known_command(name) => return Err(format!("[{name}] does not take an argument")),
+    _ => return Err(format!("[{name}] is not a known command")),
+  };
+
+  Ok(command)
+}
+
+fn is_known_command(name: &str) -> bool {
+  matches!(
+    name,
+    "close-window"
+      | "near-maximise-window"
+      | "restore-older-placement"
+      | "toggle-picture-in-picture"
+      | "toggle-monocle"
+      | "toggle-workspace-note"
+      | "toggle-manage-window"
+      | "minimise-window"
+      | "rotate-tiled-split"
+      | "swap-tiled-windows"
+      | "focus-next-window"
+      | "focus-previous-window"
+      | "next-workspace"
+      | "previous-workspace"
+      | "create-workspace"
+      | "delete-workspace"
+      | "restore-all-hidden-windows"
+      | "reinitialise"
+      | "enable-trace-logging"
+      | "save-session"
+      | "list-workspaces"
+      | "reconcile-window-rules"
+      | "rescue-offscreen-windows"
+  )
+}
+
+/// Parses the optional `key=value` filters of the `list-windows` command, e.g. `workspace=2 process=notepad.exe`.
+fn parse_window_query<'a>(filters: impl Iterator<Item = &'a str>) -> Result<WindowQuery, String> {
+  let mut query = WindowQuery::default();
+  for filter in filters {
+    let (key, value) = filter
+      .split_once('=')
+      .ok_or_else(|| format!("[{filter}] is not a filter, use key=value"))?;
+    match key {
+      "workspace" => query.workspace = Some(value.parse().map_err(|_| format!("[{value}] is not a workspace number"))?),
+      "monitor" => query.monitor_id = Some(value.to_string()),
+      "process" => query.process_name = Some(value.to_string()),
+      "visibility" => {
+        query.visibility = Some(match value {
+          "visible" => WindowVisibility::Visible,
+          "hidden" => WindowVisibility::Hidden,
+          _ => return Err(format!("[{value}] is not a visibility, use visible or hidden")),
+        })
+      }
+      _ => {
+        return Err(format!(
+          "[{key}] is not a filter, use workspace, monitor, process, or visibility"
+        ));
+      }
+    }
+  }
+
+  Ok(query)
+}
+
+/// Parses a workspace number, counting from 1 in the same order as the workspace hotkeys.
+fn parse_workspace_number(number: &str) -> Result<usize, String> {
+  match number.parse::<usize>() {
+    Ok(number) if number > 0 => Ok(number),
+    _ => Err(format!("[{number}] is not a workspace number, use 1 or higher")),
+  }
+}
+
+/// Parses the target of `move-window-to-monitor`, which is either a direction or the number of a monitor, counting
+/// from `1` in the configured monitor order.
+fn parse_monitor_target(target: &str) -> Result<Command, String> {
+  if let Ok(direction) = parse_direction(target) {
+    return Ok(Command::MoveWindowToMonitor(direction));
+  }
+  match target.parse::<usize>() {
+    Ok(number) if number > 0 => Ok(Command::MoveWindowToMonitorNumber(number)),
+    _ => Err(format!(
+      "[{target}] is neither a direction nor a monitor number, use 1 or higher"
+    )),
+  }
+}
+
+/// Parses a window handle as reported by `list-windows`, e.g. `132456`.
+fn parse_window_handle(hwnd: &str) -> Result<WindowHandle, String> {
+  match hwnd.parse::<isize>() {
+    Ok(hwnd) if hwnd != 0 => Ok(WindowHandle { hwnd }),
+    _ => Err(format!("[{hwnd}] is not a window handle")),
+  }
+}
+
+fn parse_direction(direction: &str) -> Result<Direction, String> {
+  match direction {
+    "left" => Ok(Direction::Left),
+    "right" => Ok(Direction::Right),
+    "up" => Ok(Direction::Up),
+    "down" => Ok(Direction::Down),
+    _ => Err(format!("[{direction}] is not a direction, use left, right, up, or down")),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::utils::create_temp_directory;
+  use crossbeam_channel::unbounded;
+  use std::fs;
+  use std::fs::OpenOptions;
+  use std::io::Write;
+  use std::path::Path;
+
+  fn append(path: &Path, content: &str) {
+    let mut file = OpenOptions::new()
+      .create(true)
+      .append(true)
+      .open(path)
+      .expect("Failed to open command file");
+    file.write_all(content.as_bytes()).expect("Failed to append to command file");
+  }
+
+  #[test]
+  fn command_file_manager_is_disabled_by_default() {
+    let (sender, receiver) = unbounded();
+    let configuration_provider = Arc::new(Mutex::new(ConfigurationProvider::default()));
+    let mut manager = CommandFileManager::new(configuration_provider, sender);
+
+    manager.process_appended_commands();
+
+    assert!(manager.path.is_none());
+    assert!(receiver.is_empty());
+  }
+
+  #[test]
+  fn process_appended_commands_sends_only_commands_appended_after_start() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(COMMAND_FILE_NAME);
+    fs::write(&path, "close-window\n").expect("Failed to write command file");
+    let (sender, receiver) = unbounded();
+    let mut manager = CommandFileManager::new_with(Some(path.clone()), sender);
+
+    append(&path, "move-window left\nnext-workspace\n");
+    manager.process_appended_commands();
+    manager.process_appended_commands();
+
+    let commands: Vec<Command> = receiver.try_iter().collect();
+    assert_eq!(commands.len(), 2);
+    assert!(matches!(commands[0], Command::MoveWindow(Direction::Left)));
+    assert!(matches!(commands[1], Command::NextWorkspace));
+  }
+
+  #[test]
+  fn process_appended_commands_waits_for_incomplete_line() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(COMMAND_FILE_NAME);
+    let (sender, receiver) = unbounded();
+    let mut manager = CommandFileManager::new_with(Some(path.clone()), sender);
+
+    append(&path, "minimise-");
+    manager.process_appended_commands();
+    assert!(receiver.is_empty());
+
+    append(&path, "window\n");
+    manager.process_appended_commands();
+    assert!(matches!(receiver.try_recv(), Ok(Command::MinimiseWindow)));
+  }
+
+  #[test]
+  fn process_appended_commands_executes_repeated_commands_and_skips_comments_and_invalid_lines() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(COMMAND_FILE_NAME);
+    let (sender, receiver) = unbounded();
+    let mut manager = CommandFileManager::new_with(Some(path.clone()), sender);
+
+    append(
+      &path,
+      "# A comment\nmove-cursor up\nmove-cursor up\n\nmove-cursor sideways\nunknown\nmove-cursor up\n",
+    );
+    manager.process_appended_commands();
+
+    let commands: Vec<Command> = receiver.try_iter().collect();
+    assert_eq!(commands.len(), 3);
+    assert!(
+      commands
+        .iter()
+        .all(|command| matches!(command, Command::MoveCursor(Direction::Up)))
+    );
+  }
+
+  #[test]
+  fn process_appended_commands_skips_commands_with_request_id_that_was_executed_already() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(COMMAND_FILE_NAME);
+    let (sender, receiver) = unbounded();
+    let mut manager = CommandFileManager::new_with(Some(path.clone()), sender);
+
+    append(&path, "@1 next-workspace\n@1 next-workspace\n@2 next-workspace\n");
+    manager.process_appended_commands();
+    append(&path, "@2 next-workspace\n");
+    manager.process_appended_commands();
+
+    let commands: Vec<Command> = receiver.try_iter().collect();
+    assert_eq!(commands.len(), 2);
+    assert!(commands.iter().all(|command| matches!(command, Command::NextWorkspace)));
+  }
+
+  #[test]
+  fn process_appended_commands_does_not_remember_request_id_of_invalid_line() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(COMMAND_FILE_NAME);
+    let (sender, receiver) = unbounded();
+    let mut manager = CommandFileManager::new_with(Some(path.clone()), sender);
+
+    append(&path, "@1 move-window sideways\n@1 move-window left\n");
+    manager.process_appended_commands();
+
+    let commands: Vec<Command> = receiver.try_iter().collect();
+    assert_eq!(commands.len(), 1);
+    assert!(matches!(commands[0], Command::MoveWindow(Direction::Left)));
+    assert_eq!(manager.executed_request_ids, VecDeque::from(["1".to_string()]));
+  }
+
+  #[test]
+  fn process_appended_commands_reads_from_start_after_file_was_truncated() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(COMMAND_FILE_NAME);
+    fs::write(&path, "restore-all-hidden-windows\nclose-window\n").expect("Failed to write command file");
+    let (sender, receiver) = unbounded();
+    let mut manager = CommandFileManager::new_with(Some(path.clone()), sender);
+
+    fs::write(&path, "reinitialise\n").expect("Failed to write command file");
+    manager.process_appended_commands();
+
+    assert!(matches!(receiver.try_recv(), Ok(Command::Reinitialise)));
+    assert!(receiver.is_empty());
+  }
+
+  #[test]
+  fn parse_command_reports_missing_and_unexpected_arguments() {
+    assert!(parse_command("throw-cursor").is_err());
+    assert!(parse_command("next-workspace now").is_err());
+    assert!(parse_command("enable-trace-logging now").is_err());
+    assert!(parse_command("focus-next-window now").is_err());
+    assert!(parse_command("move-window left now").is_err());
+    assert!(matches!(
+      parse_command("resize-spatial-window down"),
+      Ok(Command::ResizeSpatialWindow(Direction::Down))
+    ));
+  }
+
+  #[test]
+  fn parse_command_parses_workspace_numbers() {
+    assert!(matches!(
+      parse_command("switch-workspace 3"),
+      Ok(Command::SwitchToWorkspaceNumber(3))
+    ));
+    assert!(matches!(
+      parse_command("move-window-to-workspace 1"),
+      Ok(Command::MoveWindowToWorkspaceNumber(1))
+    ));
+    assert!(parse_command("switch-workspace").is_err());
+    assert!(parse_command("switch-workspace 0").is_err());
+    assert!(parse_command("move-window-to-workspace next").is_err());
+  }
+
+  #[test]
+  fn parse_command_parses_monitor_directions_and_numbers() {
+    assert!(matches!(
+      parse_command("move-window-to-monitor left"),
+      Ok(Command::MoveWindowToMonitor(Direction::Left))
+    ));
+    assert!(matches!(
+      parse_command("move-window-to-monitor 2"),
+      Ok(Command::MoveWindowToMonitorNumber(2))
+    ));
+    assert!(parse_command("move-window-to-monitor").is_err());
+    assert!(parse_command("move-window-to-monitor 0").is_err());
+    assert!(parse_command("move-window-to-monitor next").is_err());
+  }
+
+  #[test]
+  fn parse_command_parses_window_handles() {
+    assert!(matches!(
+      parse_command("bring-window-here 132456"),
+      Ok(Command::BringWindowHere(WindowHandle { hwnd: 132456 }))
+    ));
+    assert!(parse_command("bring-window-here").is_err());
+    assert!(parse_command("bring-window-here 0").is_err());
+    assert!(parse_command("bring-window-here notepad").is_err());
+  }
+
+  #[test]
+  fn parse_command_parses_session_commands() {
+    assert!(matches!(parse_command("save-session"), Ok(Command::SaveSession)));
+    assert!(matches!(parse_command("restore-session"), Ok(Command::RestoreSession(false))));
+    assert!(matches!(
+      parse_command("restore-session relaunch"),
+      Ok(Command::RestoreSession(true))
+    ));
+    assert!(parse_command("save-session now").is_err());
+    assert!(parse_command("restore-session all").is_err());
+  }
+
+  #[test]
+  fn parse_command_parses_list_windows_filters() {
+    assert!(matches!(parse_command("list-windows"), Ok(Command::ListWindows(query)) if query == WindowQuery::default()));
+    assert!(matches!(
+      parse_command("list-windows workspace=2 process=notepad.exe visibility=hidden"),
+      Ok(Command::ListWindows(query)) if query == WindowQuery {
+        workspace: Some(2),
+        process_name: Some("notepad.exe".to_string()),
+        visibility: Some(WindowVisibility::Hidden),
+        ..WindowQuery::default()
+      }
+    ));
+    assert!(parse_command("list-windows workspace=two").is_err());
+    assert!(parse_command("list-windows visibility=minimised").is_err());
+    assert!(parse_command("list-windows title=Notepad").is_err());
+    assert!(parse_command("list-windows hidden").is_err());
+  }
+
+  #[test]
+  fn parse_command_parses_list_workspaces() {
+    assert!(matches!(parse_command("list-workspaces"), Ok(Command::ListWorkspaces)));
+    assert_eq!(
+      parse_command("list-workspaces active").err(),
+      Some("[list-workspaces] does not take an argument".to_string())
+    );
+  }
+
+  #[test]
+  fn parse_command_parses_reconcile_window_rules() {
+    assert!(matches!(
+      parse_command("reconcile-window-rules"),
+      Ok(Command::ReconcileWindowRules)
+    ));
+    assert_eq!(
+      parse_command("reconcile-window-rules now").err(),
+      Some("[reconcile-window-rules] does not take an argument".to_string())
+    );
+  }
+
+  #[test]
+  fn parse_command_parses_rescue_offscreen_windows() {
+    assert!(matches!(
+      parse_command("rescue-offscreen-windows"),
+      Ok(Command::RescueOffscreenWindows)
+    ));
+    assert_eq!(
+      parse_command("rescue-offscreen-windows all").err(),
+      Some("[rescue-offscreen-windows] does not take an argument".to_string())
+    );
+  }
+}
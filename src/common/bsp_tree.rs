@@ -0,0 +1,227 @@
+use crate::common::{Rect, Sizing, WindowHandle, geometry};
+
+/// How the two children of a split in a [`BspTree`] are arranged.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Orientation {
+  /// The children are placed next to each other, the first on the left.
+  Horizontal,
+  /// The children are placed on top of each other, the first at the top.
+  Vertical,
+}
+
+impl Orientation {
+  fn rotated(self) -> Self {
+    match self {
+      Orientation::Horizontal => Orientation::Vertical,
+      Orientation::Vertical => Orientation::Horizontal,
+    }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Node {
+  Leaf(WindowHandle),
+  Split {
+    orientation: Orientation,
+    first: Box<Node>,
+    second: Box<Node>,
+  },
+}
+
+/// A binary space partition of the work area of one workspace. Every leaf is a window and every other node splits its
+/// region in half between its two children. Must only be used by the tiling layout.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct BspTree {
+  root: Option<Node>,
+}
+
+impl BspTree {
+  /// Returns the windows in the tree, ordered from the first to the last leaf.
+  pub(crate) fn windows(&self) -> Vec<WindowHandle> {
+    let mut windows = vec![];
+    if let Some(root) = &self.root {
+      root.collect_windows(&mut windows);
+    }
+
+    windows
+  }
+
+  pub(crate) fn contains(&self, window: WindowHandle) -> bool {
+    self.root.as_ref().is_some_and(|root| root.contains(window))
+  }
+
+  /// Adds a window by splitting the region of `target` in half, or the region of the last window if `target` is not in
+  /// the tree. The region is split along its longer side and the new window takes the second half.
+  pub(crate) fn insert(&mut self, window: WindowHandle, target: Option<WindowHandle>, work_area: Rect, margin: i32) {
+    let regions = self.layout(work_area, margin);
+    let Some((target, region)) = regions
+      .iter()
+      .find(|(handle, _)| Some(*handle) == target)
+      .or_else(|| regions.last())
+      .cloned()
+    else {
+      self.root = Some(Node::Leaf(window));
+      return;
+    };
+    let orientation = if region.width >= region.height {
+      Orientation::Horizontal
+    } else {
+      Orientation::Vertical
+    };
+    if let Some(root) = &mut self.root {
+      root.replace_leaf(
+        target,
+        Node::Split {
+          orientation,
+          first: Box::new(Node::Leaf(target)),
+          second: Box::new(Node::Leaf(window)),
+        },
+      );
+    }
+  }
+
+  /// Removes a window, giving its region to its sibling. Returns `true` if the window was in the tree.
+  pub(crate) fn remove(&mut self, window: WindowHandle) -> bool {
+    match self.root.take() {
+      Some(Node::Leaf(handle)) if handle == window => true,
+      Some(mut root) => {
+        let is_removed = root.remove(window);
+        self.root = Some(root);
+        is_removed
+      }
+      None => false,
+    }
+  }
+
+  /// Turns the split that contains `window` by 90 degrees, e.g. so that two windows next to each other end up on top of
+  /// each other. Returns `true` if there was such a split.
+  pub(crate) fn rotate(&mut self, window: WindowHandle) -> bool {
+    let Some(Node::Split { orientation, .. }) = self.root.as_mut().and_then(|root| root.find_parent_of(window)) else {
+      return false;
+    };
+    *orientation = orientation.rotated();
+
+    true
+  }
+
+  /// Swaps the two children of the split that contains `window`, so that `window` trades places with its sibling.
+  /// Returns `true` if there was such a split.
+  pub(crate) fn swap(&mut self, window: WindowHandle) -> bool {
+    let Some(Node::Split { first, second, .. }) = self.root.as_mut().and_then(|root| root.find_parent_of(window)) else {
+      return false;
+    };
+    std::mem::swap(first, second);
+
+    true
+  }
+
+  /// Returns the region of every window in the tree within the near-maximised work area, leaving `margin` between
+  /// neighbouring windows.
+  pub(crate) fn layout(&self, work_area: Rect, margin: i32) -> Vec<(WindowHandle, Sizing)> {
+    let mut regions = vec![];
+    if let Some(root) = &self.root {
+      root.collect_regions(geometry::near_maximised(work_area, margin), margin, &mut regions);
+    }
+
+    regions
+  }
+}
+
+impl Node {
+  fn is_leaf_of(&self, window: WindowHandle) -> bool {
+    matches!(self, Node::Leaf(handle) if *handle == window)
+  }
+
+  fn contains(&self, window: WindowHandle) -> bool {
+    match self {
+      Node::Leaf(handle) => *handle == window,
+      Node::Split { first, second, .. } => first.contains(window) || second.contains(window),
+    }
+  }
+
+  fn collect_windows(&self, windows: &mut Vec<WindowHandle>) {
+    match self {
+      Node::Leaf(handle) => windows.push(*handle),
+      Node::Split { first, second, .. } => {
+        first.collect_windows(windows);
+        second.collect_windows(windows);
+      }
+    }
+  }
+
+  fn collect_regions(&self, region: Sizing, margin: i32, regions: &mut Vec<(WindowHandle, Sizing)>) {
+    match self {
+      Node::Leaf(handle) => regions.push((*handle, region)),
+      Node::Split {
+        orientation,
+        first,
+        second,
+      } => {
+        let (first_region, second_region) = match orientation {
+          Orientation::Horizontal => {
+            let width = (region.width - margin) / 2;
+            (
+              Sizing { width, ..region },
+              Sizing {
+                x: region.x + width + margin,
+                width: region.width - width - margin,
+                ..region
+              },
+            )
+          }
+          Orientation::Vertical => {
+            let height = (region.height - margin) / 2;
+            (
+              Sizing { height, ..region },
+              Sizing {
+                y: region.y + height + margin,
+                height: region.height - height - margin,
+                ..region
+              },
+            )
+          }
+        };
+        first.collect_regions(first_region, margin, regions);
+        second.collect_regions(second_region, margin, regions);
+      }
+    }
+  }
+
+  fn replace_leaf(&mut self, window: WindowHandle, replacement: Node) {
+    match self {
+      Node::Leaf(handle) if *handle == window => *self = replacement,
+      Node::Leaf(_) => {}
+      Node::Split { first, .. } if first.contains(window) => first.replace_leaf(window, replacement),
+      Node::Split { second, .. } => second.replace_leaf(window, replacement),
+    }
+  }
+
+  /// Removes the leaf of `window` from the subtree by replacing its parent with its sibling. Cannot remove the leaf if
+  /// it is the subtree itself, which must be handled by the caller.
+  fn remove(&mut self, window: WindowHandle) -> bool {
+    let Node::Split { first, second, .. } = self else {
+      return false;
+    };
+    let sibling = if first.is_leaf_of(window) {
+      second.as_ref().clone()
+    } else if second.is_leaf_of(window) {
+      first.as_ref().clone()
+    } else {
+      return first.remove(window) || second.remove(window);
+    };
+    *self = sibling;
+
+    true
+  }
+
+  fn find_parent_of(&mut self, window: WindowHandle) -> Option<&mut Node> {
+    if matches!(self, Node::Split { first, second, .. } if first.is_leaf_of(window) || second.is_leaf_of(window)) {
+      return Some(self);
+    }
+    match self {
+      Node::Leaf(_) => None,
+      Node::Split { first, .. } if first.contains(window) => first.find_parent_of(window),
+      Node::Split { second, .. } => second.find_parent_of(window),
+    }
+  }
+}
@@ -1,4 +1,8 @@
-use crate::common::{Direction, PersistentWorkspaceId, WindowHandle};
+use crate::common::{
+  CommandOutcome, Direction, HealthSummary, PersistentWorkspaceId, Sizing, WindowHandle, WindowQuery, WindowSummary,
+  WorkspaceSummary,
+};
+use crossbeam_channel::Sender;
 use std::fmt::Display;
 
 /// Represents commands that can be executed in the main loop of this application. Basically, these are the actions
@@ -7,41 +11,301 @@ use std::fmt::Display;
 pub enum Command {
   CloseWindow,
   NearMaximiseWindow,
+  RestoreOlderPlacement,
+  TogglePictureInPicture,
+  ToggleMonocle,
+  ToggleWorkspaceNote,
+  /// Stops managing the foreground window, or resumes managing it if it is ignored already.
+  ToggleManageWindow,
   MinimiseWindow,
   MoveWindow(Direction),
   ResizeSpatialWindow(Direction),
   ResizeScrollingWindow(Direction),
+  MoveSharedEdge(Direction),
+  RotateTiledSplit,
+  SwapTiledWindows,
+  MouseDragStarted(WindowHandle),
+  /// A window was dragged with the mouse and, if the cursor was over a snap zone when it was released, must be snapped
+  /// into that zone.
+  MouseDragCompleted(WindowHandle, Option<Sizing>),
+  MouseResizeStarted(WindowHandle),
   MouseResizeCompleted(WindowHandle),
+  WindowLocationChanged(WindowHandle),
+  WindowTitleChanged(WindowHandle),
+  ForegroundWindowChanged(WindowHandle),
   MoveCursor(Direction),
+  ThrowCursor(Direction),
+  FocusNextWindow,
+  FocusPreviousWindow,
   SwitchWorkspace(PersistentWorkspaceId),
+  SwitchToWorkspaceNumber(usize),
+  NextWorkspace,
+  PreviousWorkspace,
   MoveWindowToWorkspace(PersistentWorkspaceId),
+  MoveWindowToWorkspaceNumber(usize),
+  MoveWindowToMonitor(Direction),
+  MoveWindowToMonitorNumber(usize),
+  BringWindowHere(WindowHandle),
+  OpenWindowSwitcher,
+  /// Focuses a window that was selected in the window switcher, switching to its workspace first if necessary.
+  SwitchToWindow(WindowHandle),
+  EnterPlaceMode,
+  /// Moves a window into the snap zone that was selected in place mode.
+  PlaceWindowInZone(WindowHandle, Sizing),
+  CreateWorkspace,
+  DeleteWorkspace,
+  RestoreAllHiddenWindows,
+  Reinitialise,
+  /// Monitors were connected, disconnected, or rearranged, or their resolution changed.
+  DisplayChanged,
+  /// The work area of a monitor changed, e.g. because the taskbar was moved or an app bar was registered.
+  WorkAreaChanged,
+  MoveParkedWindowsToPrimaryMonitor([u16; 32]),
   DragWindows(bool),
   OpenApplication(String, bool),
+  LaunchedApplicationReady,
   OpenRandolfExecutableFolder,
   OpenRandolfConfigFolder,
   OpenRandolfDataFolder,
+  /// Creates a support bundle and, if `true`, includes the titles of the visible windows, which are redacted otherwise.
+  CreateSupportBundle(bool),
+  EnableTraceLogging,
+  ExportWorkspaceProfile,
+  ImportWorkspaceProfile,
+  SaveSession,
+  /// Restores the saved session and, if `true`, relaunches the applications of windows that are missing.
+  RestoreSession(bool),
+  /// Moves windows to the workspaces the current window rules require and windows that a removed rule had moved back.
+  ReconcileWindowRules,
+  /// Moves visible windows that cannot be reached with the mouse onto the nearest monitor.
+  RescueOffscreenWindows,
+  ShowUsageMetrics,
+  /// Recording usage metrics was enabled (`true`) or disabled (`false`) via the tray menu.
+  SetUsageMetricsRecording(bool),
+  ListWindows(WindowQuery),
+  QueryWindows(WindowQuery, Sender<Vec<WindowSummary>>),
+  ListWorkspaces,
+  QueryWorkspaces(Sender<Vec<WorkspaceSummary>>),
+  QueryHealth(Sender<HealthSummary>),
+  /// Executes the command and sends its [`CommandOutcome`] to the sender, e.g. to answer an IPC client.
+  ReportingOutcome(Box<Command>, Sender<CommandOutcome>),
   RestartRandolf(bool),
   Exit,
 }
 
+impl Command {
+  /// Separates the command from the sender that its outcome must be reported to, if any.
+  pub fn into_parts(self) -> (Command, Option<Sender<CommandOutcome>>) {
+    match self {
+      Command::ReportingOutcome(command, outcome_sender) => (*command, Some(outcome_sender)),
+      command => (command, None),
+    }
+  }
+
+  /// Returns `true` if the command acts on the foreground window, e.g. to move, resize, or close it.
+  pub fn targets_foreground_window(&self) -> bool {
+    matches!(
+      self,
+      Command::CloseWindow
+        | Command::NearMaximiseWindow
+        | Command::RestoreOlderPlacement
+        | Command::TogglePictureInPicture
+        | Command::ToggleMonocle
+        | Command::MinimiseWindow
+        | Command::MoveWindow(_)
+        | Command::ResizeSpatialWindow(_)
+        | Command::ResizeScrollingWindow(_)
+        | Command::MoveSharedEdge(_)
+        | Command::RotateTiledSplit
+        | Command::SwapTiledWindows
+        | Command::MoveWindowToWorkspace(_)
+        | Command::MoveWindowToWorkspaceNumber(_)
+        | Command::MoveWindowToMonitor(_)
+        | Command::MoveWindowToMonitorNumber(_)
+        | Command::EnterPlaceMode
+    )
+  }
+
+  /// Returns `true` if the command moves windows or the cursor, which is paused while the foreground window matches a
+  /// window rule with `pause_while_foreground` or a screen sharing window is full-screen, e.g. during a screen share.
+  pub fn moves_windows_or_cursor(&self) -> bool {
+    matches!(
+      self,
+      Command::NearMaximiseWindow
+        | Command::RestoreOlderPlacement
+        | Command::TogglePictureInPicture
+        | Command::ToggleMonocle
+        | Command::MoveWindow(_)
+        | Command::ResizeSpatialWindow(_)
+        | Command::ResizeScrollingWindow(_)
+        | Command::MoveSharedEdge(_)
+        | Command::RotateTiledSplit
+        | Command::SwapTiledWindows
+        | Command::MoveCursor(_)
+        | Command::ThrowCursor(_)
+        | Command::FocusNextWindow
+        | Command::FocusPreviousWindow
+        | Command::SwitchWorkspace(_)
+        | Command::SwitchToWorkspaceNumber(_)
+        | Command::NextWorkspace
+        | Command::PreviousWorkspace
+        | Command::MoveWindowToWorkspace(_)
+        | Command::MoveWindowToWorkspaceNumber(_)
+        | Command::MoveWindowToMonitor(_)
+        | Command::MoveWindowToMonitorNumber(_)
+        | Command::BringWindowHere(_)
+        | Command::SwitchToWindow(_)
+        | Command::PlaceWindowInZone(_, _)
+        | Command::RestoreSession(_)
+        | Command::ReconcileWindowRules
+        | Command::RescueOffscreenWindows
+    )
+  }
+
+  /// Returns the name under which usage of this command is counted, if it is a command triggered by the user. Groups
+  /// variants of the same feature, e.g. moving a window in any direction, under one name.
+  pub fn usage_metric_name(&self) -> Option<&'static str> {
+    match self {
+      Command::CloseWindow => Some("Close window"),
+      Command::NearMaximiseWindow => Some("Near-maximise or restore window"),
+      Command::RestoreOlderPlacement => Some("Restore older placement"),
+      Command::TogglePictureInPicture => Some("Toggle picture-in-picture"),
+      Command::ToggleMonocle => Some("Toggle monocle mode"),
+      Command::ToggleWorkspaceNote => Some("Toggle workspace note"),
+      Command::ToggleManageWindow => Some("Toggle managing window"),
+      Command::MinimiseWindow => Some("Minimise window"),
+      Command::MoveWindow(_) => Some("Move window"),
+      Command::ResizeSpatialWindow(_) | Command::ResizeScrollingWindow(_) => Some("Resize window"),
+      Command::MoveSharedEdge(_) => Some("Move shared edge"),
+      Command::RotateTiledSplit => Some("Rotate tiled split"),
+      Command::SwapTiledWindows => Some("Swap tiled windows"),
+      Command::MouseDragCompleted(_, _) => Some("Move window with mouse"),
+      Command::MouseResizeCompleted(_) => Some("Resize window with mouse"),
+      Command::MoveCursor(_) => Some("Move cursor"),
+      Command::ThrowCursor(_) => Some("Throw cursor"),
+      Command::FocusNextWindow | Command::FocusPreviousWindow => Some("Cycle focus"),
+      Command::SwitchWorkspace(_)
+      | Command::SwitchToWorkspaceNumber(_)
+      | Command::NextWorkspace
+      | Command::PreviousWorkspace => Some("Switch workspace"),
+      Command::MoveWindowToWorkspace(_) | Command::MoveWindowToWorkspaceNumber(_) => Some("Move window to workspace"),
+      Command::MoveWindowToMonitor(_) | Command::MoveWindowToMonitorNumber(_) => Some("Move window to monitor"),
+      Command::BringWindowHere(_) => Some("Bring window here"),
+      Command::OpenWindowSwitcher => Some("Open window switcher"),
+      Command::SwitchToWindow(_) => Some("Switch to window"),
+      Command::EnterPlaceMode => Some("Enter place mode"),
+      Command::PlaceWindowInZone(_, _) => Some("Place window in zone"),
+      Command::CreateWorkspace => Some("Create workspace"),
+      Command::DeleteWorkspace => Some("Delete workspace"),
+      Command::OpenApplication(_, _) => Some("Open application"),
+      Command::SaveSession => Some("Save session"),
+      Command::RestoreSession(_) => Some("Restore session"),
+      Command::ReconcileWindowRules => Some("Reconcile window rules"),
+      Command::RescueOffscreenWindows => Some("Rescue off-screen windows"),
+      _ => None,
+    }
+  }
+
+  /// Returns `true` if executing this command straight after `previous` makes executing `previous` pointless, e.g.
+  /// when switching workspaces on the same monitor twice in a row. Used to drop stale commands from the queue.
+  pub fn supersedes(&self, previous: &Command) -> bool {
+    match (self, previous) {
+      (Command::SwitchWorkspace(id), Command::SwitchWorkspace(previous_id)) => id.monitor_id == previous_id.monitor_id,
+      (Command::MouseResizeCompleted(window), Command::MouseResizeCompleted(previous_window)) => window == previous_window,
+      (Command::WindowLocationChanged(window), Command::WindowLocationChanged(previous_window)) => window == previous_window,
+      (Command::WindowTitleChanged(window), Command::WindowTitleChanged(previous_window)) => window == previous_window,
+      (Command::ForegroundWindowChanged(_), Command::ForegroundWindowChanged(_)) => true,
+      (Command::Reinitialise, Command::Reinitialise) => true,
+      (Command::DisplayChanged, Command::DisplayChanged) => true,
+      (Command::WorkAreaChanged, Command::WorkAreaChanged) => true,
+      (Command::DragWindows(_), Command::DragWindows(_)) => true,
+      _ => false,
+    }
+  }
+}
+
 impl Display for Command {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     match self {
       Command::CloseWindow => write!(f, "Close window"),
       Command::NearMaximiseWindow => write!(f, "Near maximise window"),
+      Command::RestoreOlderPlacement => write!(f, "Restore older placement of window"),
+      Command::TogglePictureInPicture => write!(f, "Toggle picture-in-picture for window"),
+      Command::ToggleMonocle => write!(f, "Toggle monocle mode for window"),
+      Command::ToggleWorkspaceNote => write!(f, "Toggle note of workspace under cursor"),
+      Command::ToggleManageWindow => write!(f, "Toggle managing window"),
       Command::MinimiseWindow => write!(f, "Minimise window"),
       Command::MoveWindow(direction) => write!(f, "Move window [{:?}]", direction),
       Command::ResizeSpatialWindow(direction) => write!(f, "Resize spatial window [{:?}]", direction),
       Command::ResizeScrollingWindow(direction) => write!(f, "Resize scrolling window [{:?}]", direction),
+      Command::MoveSharedEdge(direction) => write!(f, "Move shared edge [{:?}]", direction),
+      Command::RotateTiledSplit => write!(f, "Rotate tiled split of window"),
+      Command::SwapTiledWindows => write!(f, "Swap tiled window with its sibling"),
+      Command::MouseDragStarted(window) => write!(f, "Mouse drag started [{window}]"),
+      Command::MouseDragCompleted(window, snap_zone) => {
+        write!(f, "Mouse drag completed [{window}] in snap zone {snap_zone:?}")
+      }
+      Command::MouseResizeStarted(window) => write!(f, "Mouse resize started [{window}]"),
       Command::MouseResizeCompleted(window) => write!(f, "Mouse resize completed [{window}]"),
+      Command::WindowLocationChanged(window) => write!(f, "Window location changed [{window}]"),
+      Command::WindowTitleChanged(window) => write!(f, "Window title changed [{window}]"),
+      Command::ForegroundWindowChanged(window) => write!(f, "Foreground window changed [{window}]"),
       Command::MoveCursor(direction) => write!(f, "Move cursor [{:?}]", direction),
+      Command::ThrowCursor(direction) => write!(f, "Throw cursor to monitor [{:?}]", direction),
+      Command::FocusNextWindow => write!(f, "Focus next window of workspace"),
+      Command::FocusPreviousWindow => write!(f, "Focus previous window of workspace"),
       Command::SwitchWorkspace(id) => write!(f, "Switch to workspace [{id}]"),
+      Command::SwitchToWorkspaceNumber(number) => write!(f, "Switch to workspace number [{number}]"),
+      Command::NextWorkspace => write!(f, "Switch to next workspace"),
+      Command::PreviousWorkspace => write!(f, "Switch to previous workspace"),
       Command::MoveWindowToWorkspace(id) => write!(f, "Move window to workspace [{id}]"),
+      Command::MoveWindowToWorkspaceNumber(number) => write!(f, "Move window to workspace number [{number}]"),
+      Command::MoveWindowToMonitor(direction) => write!(f, "Move window to monitor [{:?}]", direction),
+      Command::MoveWindowToMonitorNumber(number) => write!(f, "Move window to monitor number [{number}]"),
+      Command::BringWindowHere(window) => write!(f, "Bring window [{window}] to current workspace"),
+      Command::OpenWindowSwitcher => write!(f, "Open window switcher"),
+      Command::SwitchToWindow(window) => write!(f, "Switch to window [{window}]"),
+      Command::EnterPlaceMode => write!(f, "Enter place mode"),
+      Command::PlaceWindowInZone(window, zone) => write!(f, "Place window [{window}] in zone {zone:?}"),
+      Command::CreateWorkspace => write!(f, "Create workspace on current monitor"),
+      Command::DeleteWorkspace => write!(f, "Delete last workspace on current monitor"),
+      Command::RestoreAllHiddenWindows => write!(f, "Restore all hidden windows"),
+      Command::Reinitialise => write!(f, "Reinitialise monitor and workspace state"),
+      Command::DisplayChanged => write!(f, "Handle display change"),
+      Command::WorkAreaChanged => write!(f, "Re-fit windows after work area change"),
+      Command::MoveParkedWindowsToPrimaryMonitor(monitor_id) => write!(
+        f,
+        "Move windows parked on disconnected monitor [{}] to primary monitor",
+        String::from_utf16_lossy(monitor_id).trim_end_matches('\0')
+      ),
       Command::DragWindows(is_allowed) => write!(f, "Allow window dragging [{}]", is_allowed),
       Command::OpenApplication(path, as_admin) => write!(f, "Open [{path}] as admin [{as_admin}]"),
+      Command::LaunchedApplicationReady => write!(f, "Launched application is ready"),
       Command::OpenRandolfExecutableFolder => write!(f, "Open Randolf's executable folder in Explorer"),
       Command::OpenRandolfConfigFolder => write!(f, "Open Randolf's config folder in Explorer"),
       Command::OpenRandolfDataFolder => write!(f, "Open Randolf's data folder in Explorer"),
+      Command::CreateSupportBundle(include_window_titles) => write!(
+        f,
+        "Create support bundle in Randolf's data folder, including window titles [{include_window_titles}]"
+      ),
+      Command::EnableTraceLogging => write!(f, "Temporarily enable trace logging"),
+      Command::ExportWorkspaceProfile => write!(f, "Export workspace profile to Randolf's data folder"),
+      Command::ImportWorkspaceProfile => write!(f, "Import workspace profile from Randolf's data folder"),
+      Command::SaveSession => write!(f, "Save session to Randolf's data folder"),
+      Command::RestoreSession(is_relaunching_missing_applications) => write!(
+        f,
+        "Restore session from Randolf's data folder, relaunching missing applications [{is_relaunching_missing_applications}]"
+      ),
+      Command::ReconcileWindowRules => write!(f, "Move windows to the workspaces required by the window rules"),
+      Command::RescueOffscreenWindows => write!(f, "Move off-screen windows onto the nearest monitor"),
+      Command::ShowUsageMetrics => write!(f, "Show usage metrics"),
+      Command::SetUsageMetricsRecording(is_enabled) => write!(f, "Record usage metrics [{is_enabled}]"),
+      Command::ListWindows(_) => write!(f, "List managed windows in Randolf's data folder"),
+      Command::QueryWindows(_, _) => write!(f, "Query managed windows"),
+      Command::ListWorkspaces => write!(f, "List workspaces in Randolf's data folder"),
+      Command::QueryWorkspaces(_) => write!(f, "Query workspaces"),
+      Command::QueryHealth(_) => write!(f, "Query health"),
+      Command::ReportingOutcome(command, _) => write!(f, "{command} (reporting outcome)"),
       Command::RestartRandolf(as_admin) => write!(f, "Restart Randolf as admin [{as_admin}]"),
       Command::Exit => write!(f, "Exit application"),
     }
@@ -0,0 +1,38 @@
+use std::fmt::Display;
+
+/// The result of executing a [`crate::common::Command`], which is logged, reported to the IPC client that requested the
+/// command, and, if enabled, shown in the tray. Reasons are written in lower case so that they read well after
+/// "because", e.g. "there is no foreground window".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandOutcome {
+  /// The command was executed.
+  Success,
+  /// The command was valid but there was nothing to do, e.g. because the target workspace is already active.
+  NoOp(String),
+  /// The command could not be executed, e.g. because the target workspace does not exist.
+  Error(String),
+}
+
+impl CommandOutcome {
+  pub fn no_op(reason: impl Into<String>) -> Self {
+    CommandOutcome::NoOp(reason.into())
+  }
+
+  pub fn error(reason: impl Into<String>) -> Self {
+    CommandOutcome::Error(reason.into())
+  }
+
+  pub fn is_success(&self) -> bool {
+    matches!(self, CommandOutcome::Success)
+  }
+}
+
+impl Display for CommandOutcome {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      CommandOutcome::Success => write!(f, "success"),
+      CommandOutcome::NoOp(reason) => write!(f, "no-op: {reason}"),
+      CommandOutcome::Error(reason) => write!(f, "error: {reason}"),
+    }
+  }
+}
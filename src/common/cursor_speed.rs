@@ -0,0 +1,61 @@
+use std::fmt::Display;
+
+/// The speed at which the cursor moves, as set in the mouse settings of Windows, and how Windows accelerates it (i.e.
+/// whether "Enhance pointer precision" is on).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CursorSpeed {
+  /// The speed of the cursor from `1` (slowest) to `20` (fastest). Windows uses `10` by default.
+  pub speed: u32,
+  /// The two mouse thresholds and the acceleration level, exactly as read via `SPI_GETMOUSE`, so that they can be
+  /// restored as they were. The cursor is not accelerated if the acceleration level is `0`.
+  pub mouse_parameters: [i32; 3],
+}
+
+impl CursorSpeed {
+  pub const MINIMUM_SPEED: u32 = 1;
+  pub const MAXIMUM_SPEED: u32 = 20;
+  /// The mouse parameters Windows uses when "Enhance pointer precision" is turned on in the mouse settings.
+  const ACCELERATED_MOUSE_PARAMETERS: [i32; 3] = [6, 10, 1];
+  /// The mouse parameters Windows uses when "Enhance pointer precision" is turned off in the mouse settings.
+  const UNACCELERATED_MOUSE_PARAMETERS: [i32; 3] = [0, 0, 0];
+
+  pub fn new(speed: u32, is_accelerated: bool) -> Self {
+    let mouse_parameters = if is_accelerated {
+      Self::ACCELERATED_MOUSE_PARAMETERS
+    } else {
+      Self::UNACCELERATED_MOUSE_PARAMETERS
+    };
+
+    Self::from_mouse_parameters(speed, mouse_parameters)
+  }
+
+  pub fn from_mouse_parameters(speed: u32, mouse_parameters: [i32; 3]) -> Self {
+    Self { speed, mouse_parameters }
+  }
+
+  pub fn is_accelerated(&self) -> bool {
+    self.mouse_parameters[2] != 0
+  }
+
+  /// Returns this cursor speed with the given speed and acceleration, keeping the mouse parameters if the acceleration
+  /// does not change, so that e.g. a custom acceleration level is not replaced with the one Windows uses by default.
+  pub fn with(&self, speed: u32, is_accelerated: bool) -> Self {
+    if is_accelerated == self.is_accelerated() {
+      return Self::from_mouse_parameters(speed, self.mouse_parameters);
+    }
+
+    Self::new(speed, is_accelerated)
+  }
+}
+
+impl Display for CursorSpeed {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "speed [{}] with acceleration [{}] (mouse parameters {:?})",
+      self.speed,
+      if self.is_accelerated() { "on" } else { "off" },
+      self.mouse_parameters
+    )
+  }
+}
@@ -1,6 +1,6 @@
 /// An enum representing the four cardinal directions. Used for movement and positioning logic in the application
 /// e.g. when moving the cursor using keyboard shortcuts, or to locate monitor work areas relative to each other.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Direction {
   Left,
   Right,
@@ -17,4 +17,8 @@ impl Direction {
       Direction::Down => Direction::Up,
     }
   }
+
+  pub fn is_horizontal(self) -> bool {
+    matches!(self, Direction::Left | Direction::Right)
+  }
 }
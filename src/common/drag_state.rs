@@ -1,21 +1,44 @@
-use crate::common::{Point, WindowHandle};
+use crate::common::{Point, Rect, Sizing, WindowHandle};
 
 /// Represents the state of a mouse-based window move operation. Not used for any keyboard operations.
 #[derive(Default)]
 pub struct DragState {
   drag_start_position: Point,
-  window_start_position: Point,
+  window_start_rect: Rect,
   window_handle: Option<WindowHandle>,
+  work_areas: Vec<Rect>,
+  other_windows: Vec<Rect>,
+  free_floating_work_areas: Vec<Rect>,
+  snap_zone: Option<Sizing>,
 }
 
 impl DragState {
   /// Sets the drag state when starting the drag operation. Only called after a window is selected for dragging.
-  pub(crate) fn set(&mut self, cursor_position: Point, window_handle: WindowHandle, window_position: Point) {
+  pub(crate) fn set(&mut self, cursor_position: Point, window_handle: WindowHandle, window_rect: Rect) {
     self.drag_start_position = cursor_position;
-    self.window_start_position = window_position;
+    self.window_start_rect = window_rect;
     self.window_handle = Some(window_handle);
   }
 
+  /// Sets the work areas and the rects of other windows that the edges of the dragged window are attracted to.
+  pub(crate) fn set_snap_targets(&mut self, work_areas: Vec<Rect>, other_windows: Vec<Rect>) {
+    self.work_areas = work_areas;
+    self.other_windows = other_windows;
+  }
+
+  /// Sets the work areas of monitors whose active workspace is free-floating, on which the dragged window is not snapped.
+  pub(crate) fn set_free_floating_work_areas(&mut self, work_areas: Vec<Rect>) {
+    self.free_floating_work_areas = work_areas;
+  }
+
+  /// Returns `true` if the point is on a monitor whose active workspace is free-floating.
+  pub(crate) fn is_free_floating_at(&self, point: Point) -> bool {
+    self
+      .free_floating_work_areas
+      .iter()
+      .any(|work_area| work_area.contains(&point))
+  }
+
   /// Returns the starting position of the cursor at the beginning of the drag operation.
   pub(crate) fn get_drag_start_position(&self) -> Point {
     self.drag_start_position
@@ -23,7 +46,32 @@ impl DragState {
 
   /// Returns the starting position of the window at the beginning of the drag operation.
   pub(crate) fn get_window_start_position(&self) -> Point {
-    self.window_start_position
+    Point::new(self.window_start_rect.left, self.window_start_rect.top)
+  }
+
+  /// Returns the rect of the window at the beginning of the drag operation.
+  pub(crate) fn get_window_start_rect(&self) -> Rect {
+    self.window_start_rect
+  }
+
+  /// Returns the work areas that the edges of the dragged window are attracted to.
+  pub(crate) fn get_work_areas(&self) -> &[Rect] {
+    &self.work_areas
+  }
+
+  /// Returns the rects of the other windows that the edges of the dragged window are attracted to.
+  pub(crate) fn get_other_windows(&self) -> &[Rect] {
+    &self.other_windows
+  }
+
+  /// Sets the snap zone that the cursor currently hovers over, if any.
+  pub(crate) fn set_snap_zone(&mut self, snap_zone: Option<Sizing>) {
+    self.snap_zone = snap_zone;
+  }
+
+  /// Returns the snap zone that the cursor currently hovers over, if any.
+  pub(crate) fn get_snap_zone(&self) -> Option<&Sizing> {
+    self.snap_zone.as_ref()
   }
 
   /// Returns the window handle if available, otherwise returns `None`.
@@ -40,14 +88,18 @@ impl DragState {
   /// Resets the drag state. Should be called after the drag operation ends.
   pub(crate) fn reset(&mut self) {
     self.drag_start_position = Point::default();
-    self.window_start_position = Point::default();
+    self.window_start_rect = Rect::default();
     self.window_handle = None;
+    self.work_areas.clear();
+    self.other_windows.clear();
+    self.free_floating_work_areas.clear();
+    self.snap_zone = None;
   }
 }
 
 #[cfg(test)]
 mod tests {
-  use crate::common::{DragState, Point, WindowHandle};
+  use crate::common::{DragState, Point, Rect, Sizing, WindowHandle};
 
   #[test]
   fn drag_state_has_default_values() {
@@ -62,12 +114,13 @@ mod tests {
     let mut drag_state = DragState::default();
     let cursor_position = Point::new(100, 100);
     let window_handle = WindowHandle::new(12345);
-    let window_position = Point::new(200, 200);
+    let window_rect = Rect::new(200, 200, 600, 500);
 
-    drag_state.set(cursor_position, window_handle, window_position);
+    drag_state.set(cursor_position, window_handle, window_rect);
 
     assert_eq!(drag_state.get_drag_start_position(), cursor_position);
-    assert_eq!(drag_state.get_window_start_position(), window_position);
+    assert_eq!(drag_state.get_window_start_position(), Point::new(200, 200));
+    assert_eq!(drag_state.get_window_start_rect(), window_rect);
     assert_eq!(drag_state.get_window_handle().unwrap(), &window_handle);
   }
 
@@ -76,14 +129,31 @@ mod tests {
     let mut drag_state = DragState::default();
     let cursor_position = Point::new(100, 100);
     let window_handle = WindowHandle::new(12345);
-    let window_position = Point::new(200, 200);
+    let window_rect = Rect::new(200, 200, 600, 500);
 
-    drag_state.set(cursor_position, window_handle, window_position);
+    drag_state.set(cursor_position, window_handle, window_rect);
+    drag_state.set_snap_targets(vec![Rect::new(0, 0, 1920, 1080)], vec![window_rect]);
+    drag_state.set_free_floating_work_areas(vec![Rect::new(1920, 0, 3840, 1080)]);
+    drag_state.set_snap_zone(Some(Sizing::new(0, 0, 960, 1080)));
     drag_state.reset();
 
     assert_eq!(drag_state.get_drag_start_position(), Point::default());
     assert_eq!(drag_state.get_window_start_position(), Point::default());
     assert!(drag_state.get_window_handle().is_none());
+    assert!(drag_state.get_work_areas().is_empty());
+    assert!(drag_state.get_other_windows().is_empty());
+    assert!(!drag_state.is_free_floating_at(Point::new(2000, 100)));
+    assert!(drag_state.get_snap_zone().is_none());
+  }
+
+  #[test]
+  fn is_free_floating_at_returns_true_only_on_free_floating_work_areas() {
+    let mut drag_state = DragState::default();
+    drag_state.set_snap_targets(vec![Rect::new(0, 0, 1920, 1080)], vec![]);
+    drag_state.set_free_floating_work_areas(vec![Rect::new(1920, 0, 3840, 1080)]);
+
+    assert!(drag_state.is_free_floating_at(Point::new(2000, 100)));
+    assert!(!drag_state.is_free_floating_at(Point::new(100, 100)));
   }
 
   #[test]
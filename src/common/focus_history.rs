@@ -0,0 +1,98 @@
+use crate::common::WindowHandle;
+use std::collections::VecDeque;
+
+const MAXIMUM_FOCUS_HISTORY_LENGTH: usize = 32;
+
+/// Remembers recently focused windows, most recent first. Used to choose which window to focus when the foreground
+/// window disappears.
+#[derive(Debug, Default)]
+pub struct FocusHistory {
+  handles: VecDeque<WindowHandle>,
+}
+
+impl FocusHistory {
+  /// Moves the window to the front of the history, dropping the oldest entry if the history is full.
+  pub fn record(&mut self, handle: WindowHandle) {
+    if self.handles.front() == Some(&handle) {
+      return;
+    }
+    self.handles.retain(|existing| *existing != handle);
+    self.handles.push_front(handle);
+    self.handles.truncate(MAXIMUM_FOCUS_HISTORY_LENGTH);
+  }
+
+  /// Removes a window from the history, e.g. after it was closed.
+  pub fn forget(&mut self, handle: WindowHandle) {
+    self.handles.retain(|existing| *existing != handle);
+  }
+
+  /// Returns the most recently focused window, if any.
+  pub fn most_recent(&self) -> Option<WindowHandle> {
+    self.handles.front().copied()
+  }
+
+  /// Returns the most recently focused window among the candidates, if any of them is known.
+  pub fn most_recent_of(&self, candidates: &[WindowHandle]) -> Option<WindowHandle> {
+    self.handles.iter().copied().find(|handle| candidates.contains(handle))
+  }
+
+  /// Returns how many windows were focused more recently than the window, or `None` if it is not in the history.
+  pub fn recency_of(&self, handle: WindowHandle) -> Option<usize> {
+    self.handles.iter().position(|existing| *existing == handle)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn record_moves_existing_window_to_front() {
+    let mut history = FocusHistory::default();
+    history.record(WindowHandle::new(1));
+    history.record(WindowHandle::new(2));
+
+    history.record(WindowHandle::new(1));
+
+    assert_eq!(
+      history.most_recent_of(&[WindowHandle::new(1), WindowHandle::new(2)]),
+      Some(WindowHandle::new(1))
+    );
+    assert_eq!(history.handles.len(), 2);
+    assert_eq!(history.most_recent(), Some(WindowHandle::new(1)));
+  }
+
+  #[test]
+  fn record_drops_oldest_window_when_full() {
+    let mut history = FocusHistory::default();
+    for i in 0..=MAXIMUM_FOCUS_HISTORY_LENGTH {
+      history.record(WindowHandle::new(i as isize));
+    }
+
+    assert_eq!(history.handles.len(), MAXIMUM_FOCUS_HISTORY_LENGTH);
+    assert_eq!(history.most_recent_of(&[WindowHandle::new(0)]), None);
+  }
+
+  #[test]
+  fn recency_of_returns_position_in_history() {
+    let mut history = FocusHistory::default();
+    history.record(WindowHandle::new(1));
+    history.record(WindowHandle::new(2));
+
+    assert_eq!(history.recency_of(WindowHandle::new(2)), Some(0));
+    assert_eq!(history.recency_of(WindowHandle::new(1)), Some(1));
+    assert_eq!(history.recency_of(WindowHandle::new(3)), None);
+  }
+
+  #[test]
+  fn most_recent_of_ignores_forgotten_and_unknown_windows() {
+    let mut history = FocusHistory::default();
+    history.record(WindowHandle::new(1));
+    history.record(WindowHandle::new(2));
+    history.forget(WindowHandle::new(2));
+
+    let result = history.most_recent_of(&[WindowHandle::new(2), WindowHandle::new(3)]);
+
+    assert_eq!(result, None);
+  }
+}
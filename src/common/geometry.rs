@@ -0,0 +1,432 @@
+//! The window geometry used throughout Randolf, e.g. halves, thirds, quarters, clamping, margin handling, and DPI
+//! translation. All functions in this module are pure so that layout features can build on primitives that are covered
+//! by property tests (see `common/tests/geometry_tests.rs`) rather than re-implementing the arithmetic.
+
+use crate::common::{Direction, Rect, ResizeMode, Sizing};
+
+/// The DPI that Windows treats as a scale factor of 100%.
+pub const DEFAULT_DPI: u32 = 96;
+
+/// Returns the [`Sizing`] that fills the work area minus `margin` on every side.
+pub fn near_maximised(work_area: Rect, margin: i32) -> Sizing {
+  Sizing {
+    x: work_area.left + margin,
+    y: work_area.top + margin,
+    width: work_area.width() - margin * 2,
+    height: work_area.height() - margin * 2,
+  }
+}
+
+/// Returns the [`Sizing`] of the half of the work area on the side of `direction`, leaving `margin` at the edges of the
+/// work area and `margin / 2` at the split edge.
+pub fn half(work_area: Rect, direction: Direction, margin: i32) -> Sizing {
+  let half_margin = margin / 2;
+  let half_width = work_area.width() / 2 - margin - half_margin;
+  let half_height = work_area.height() / 2 - margin - half_margin;
+  match direction {
+    Direction::Left => Sizing {
+      x: work_area.left + margin,
+      y: work_area.top + margin,
+      width: half_width,
+      height: work_area.height() - margin * 2,
+    },
+    Direction::Right => Sizing {
+      x: work_area.left + work_area.width() / 2 + half_margin,
+      y: work_area.top + margin,
+      width: half_width,
+      height: work_area.height() - margin * 2,
+    },
+    Direction::Up => Sizing {
+      x: work_area.left + margin,
+      y: work_area.top + margin,
+      width: work_area.width() - margin * 2,
+      height: half_height,
+    },
+    Direction::Down => Sizing {
+      x: work_area.left + margin,
+      y: work_area.top + work_area.height() / 2 + half_margin,
+      width: work_area.width() - margin * 2,
+      height: half_height,
+    },
+  }
+}
+
+/// Returns the [`Sizing`] that is 75% of the near-maximised size in the dimension of `direction`, anchored to the
+/// near-maximised edge on the side of `direction`.
+pub fn three_quarters(work_area: Rect, direction: Direction, margin: i32) -> Sizing {
+  let near_max = near_maximised(work_area, margin);
+  let half_margin = margin / 2;
+  match direction {
+    Direction::Left => Sizing {
+      width: near_max.width * 3 / 4 - half_margin,
+      ..near_max
+    },
+    Direction::Right => Sizing {
+      x: near_max.x + near_max.width / 4 + half_margin,
+      width: near_max.width * 3 / 4 - half_margin,
+      ..near_max
+    },
+    Direction::Up => Sizing {
+      height: near_max.height * 3 / 4 - half_margin,
+      ..near_max
+    },
+    Direction::Down => Sizing {
+      y: near_max.y + near_max.height / 4 + half_margin,
+      height: near_max.height * 3 / 4 - half_margin,
+      ..near_max
+    },
+  }
+}
+
+/// Returns the [`Sizing`] that occupies the centre half of the near-maximised area in the axis of `direction`.
+pub fn centre_half(work_area: Rect, direction: Direction, margin: i32) -> Sizing {
+  let near_max = near_maximised(work_area, margin);
+  let half_margin = margin / 2;
+  match direction {
+    Direction::Left | Direction::Right => Sizing {
+      x: near_max.x + near_max.width / 4 + half_margin,
+      width: near_max.width / 2 - margin,
+      ..near_max
+    },
+    Direction::Up | Direction::Down => Sizing {
+      y: near_max.y + near_max.height / 4 + half_margin,
+      height: near_max.height / 2 - margin,
+      ..near_max
+    },
+  }
+}
+
+/// Returns the half of `sizing` on the side of `direction`, subtracting `margin / 2` at the split edge.
+pub fn halve(sizing: &Sizing, direction: Direction, margin: i32) -> Sizing {
+  let half_margin = margin / 2;
+  match direction {
+    Direction::Left => Sizing {
+      width: sizing.width / 2 - half_margin,
+      ..*sizing
+    },
+    Direction::Right => Sizing {
+      x: sizing.x + sizing.width / 2 + half_margin,
+      width: sizing.width / 2 - half_margin,
+      ..*sizing
+    },
+    Direction::Up => Sizing {
+      height: sizing.height / 2 - half_margin,
+      ..*sizing
+    },
+    Direction::Down => Sizing {
+      y: sizing.y + sizing.height / 2 + half_margin,
+      height: sizing.height / 2 - half_margin,
+      ..*sizing
+    },
+  }
+}
+
+/// Returns the [`Sizing`] of the quarter of the work area in the corner of `horizontal` and `vertical`, i.e. the
+/// intersection of the two halves, so that its edges line up with windows snapped to a half.
+pub fn quarter(work_area: Rect, horizontal: Direction, vertical: Direction, margin: i32) -> Sizing {
+  let horizontal_half = half(work_area, horizontal, margin);
+  let vertical_half = half(work_area, vertical, margin);
+  Sizing {
+    x: horizontal_half.x,
+    y: vertical_half.y,
+    width: horizontal_half.width,
+    height: vertical_half.height,
+  }
+}
+
+/// Returns the [`Sizing`] spanning `count` thirds of the width of the work area, starting at the third with index
+/// `first` (`0` being the left third), leaving `margin` at the edges of the work area and `margin / 2` at the split
+/// edges.
+pub fn thirds(work_area: Rect, first: i32, count: i32, margin: i32) -> Sizing {
+  let half_margin = margin / 2;
+  let split_at = |index: i32| work_area.left + work_area.width() * index / 3;
+  let last = first + count;
+  let left = if first == 0 {
+    work_area.left + margin
+  } else {
+    split_at(first) + half_margin
+  };
+  let right = if last == 3 {
+    work_area.right - margin
+  } else {
+    split_at(last) - half_margin
+  };
+  Sizing {
+    x: left,
+    y: work_area.top + margin,
+    width: right - left,
+    height: work_area.height() - margin * 2,
+  }
+}
+
+/// Returns the [`Sizing`]s of `count` windows arranged in a master-stack layout: the first window is the master, which
+/// takes up `master_width_in_percent` of the width on the left, and all other windows are stacked on top of each other
+/// on the right. A single window is near-maximised. Windows are `margin` apart from each other and from the edges of
+/// the work area.
+pub fn master_stack(work_area: Rect, count: usize, master_width_in_percent: i32, margin: i32) -> Vec<Sizing> {
+  let near_max = near_maximised(work_area, margin);
+  if count <= 1 {
+    return vec![near_max; count];
+  }
+  let master_width = (near_max.width - margin) * master_width_in_percent / 100;
+  let stack_count = count as i32 - 1;
+  let stack_height = (near_max.height - margin * (stack_count - 1)) / stack_count;
+  let master = Sizing {
+    width: master_width,
+    ..near_max
+  };
+  let stack = (0..stack_count).map(|index| Sizing {
+    x: near_max.x + master_width + margin,
+    y: near_max.y + index * (stack_height + margin),
+    width: near_max.width - master_width - margin,
+    height: stack_height,
+  });
+
+  std::iter::once(master).chain(stack).collect()
+}
+
+/// Returns the width and height of a quarter of the work area, i.e. a half that has been halved again.
+pub fn quarter_dimensions(work_area: Rect, margin: i32) -> (i32, i32) {
+  let width = halve(&half(work_area, Direction::Left, margin), Direction::Left, margin).width;
+  let height = halve(&half(work_area, Direction::Up, margin), Direction::Up, margin).height;
+  (width, height)
+}
+
+/// Returns the rect of the given size in the corner of the work area that `horizontal` and `vertical` point to, leaving
+/// `margin` to the edges of the work area. Shrinks the size if it does not fit into the work area.
+pub fn in_corner(work_area: Rect, width: i32, height: i32, horizontal: Direction, vertical: Direction, margin: i32) -> Rect {
+  let width = width.min(work_area.width() - margin * 2);
+  let height = height.min(work_area.height() - margin * 2);
+  let left = match horizontal {
+    Direction::Left => work_area.left + margin,
+    _ => work_area.right - margin - width,
+  };
+  let top = match vertical {
+    Direction::Up => work_area.top + margin,
+    _ => work_area.bottom - margin - height,
+  };
+
+  Rect::new(left, top, left + width, top + height)
+}
+
+/// Returns `rect` with each edge moved inside `bounds` minus `margin`, if it is not already.
+pub fn clamp(rect: &Rect, bounds: &Rect, margin: i32) -> Rect {
+  Rect {
+    left: rect.left.max(bounds.left + margin),
+    top: rect.top.max(bounds.top + margin),
+    right: rect.right.min(bounds.right - margin),
+    bottom: rect.bottom.min(bounds.bottom - margin),
+  }
+}
+
+/// Returns `rect`, shrunk to the size of `bounds` if it is larger, moved the shortest distance needed to lie entirely
+/// within `bounds`.
+pub fn fit_into(rect: &Rect, bounds: &Rect) -> Rect {
+  let width = rect.width().min(bounds.width());
+  let height = rect.height().min(bounds.height());
+  let left = rect.left.clamp(bounds.left, bounds.right - width);
+  let top = rect.top.clamp(bounds.top, bounds.bottom - height);
+
+  Rect::new(left, top, left + width, top + height)
+}
+
+/// Returns `true` if at least `minimum_in_px` of the width and of the height of `rect`, or all of it if it is smaller,
+/// lie within one of `bounds`, e.g. to tell whether enough of a window is on a monitor for the user to grab it.
+pub fn is_visible_within(rect: &Rect, bounds: &[Rect], minimum_in_px: i32) -> bool {
+  let minimum_width = minimum_in_px.min(rect.width());
+  let minimum_height = minimum_in_px.min(rect.height());
+  bounds.iter().any(|bounds| {
+    let visible_width = rect.right.min(bounds.right) - rect.left.max(bounds.left);
+    let visible_height = rect.bottom.min(bounds.bottom) - rect.top.max(bounds.top);
+    visible_width > 0 && visible_height > 0 && visible_width >= minimum_width && visible_height >= minimum_height
+  })
+}
+
+/// Returns the bounds closest to the centre of `rect`, which are the bounds containing it if there are any.
+pub fn nearest<'a>(rect: &Rect, bounds: &'a [Rect]) -> Option<&'a Rect> {
+  let centre = rect.center();
+  bounds.iter().min_by_key(|bounds| {
+    let distance_x = i64::from((bounds.left - centre.x()).max(centre.x() - bounds.right).max(0));
+    let distance_y = i64::from((bounds.top - centre.y()).max(centre.y() - bounds.bottom).max(0));
+    distance_x * distance_x + distance_y * distance_y
+  })
+}
+
+/// Returns `rect`, shrunk to the size of `bounds` if it is larger, centred within `bounds`.
+pub fn centre_within(rect: &Rect, bounds: &Rect) -> Rect {
+  let width = rect.width().min(bounds.width());
+  let height = rect.height().min(bounds.height());
+  let left = bounds.left + (bounds.width() - width) / 2;
+  let top = bounds.top + (bounds.height() - height) / 2;
+
+  Rect::new(left, top, left + width, top + height)
+}
+
+/// Returns the largest rect with the aspect ratio of `width` and `height` that fits into `bounds` without exceeding the
+/// original size, centred within `bounds`, e.g. to show a thumbnail of a window without distorting it.
+pub fn scale_to_fit(width: i32, height: i32, bounds: &Rect) -> Rect {
+  let (width, height) = (width.max(0), height.max(0));
+  let scale = (f64::from(bounds.width()) / f64::from(width))
+    .min(f64::from(bounds.height()) / f64::from(height))
+    .min(1.0);
+  let scaled = Rect::new(
+    0,
+    0,
+    (f64::from(width) * scale).round() as i32,
+    (f64::from(height) * scale).round() as i32,
+  );
+
+  centre_within(&scaled, bounds)
+}
+
+/// Returns how much `rect` and `zone` overlap as a percentage of the area they cover together, i.e. `100` if they are
+/// identical and `0` if they do not overlap at all. Unlike the share of `rect` that lies inside `zone`, this is only
+/// high if both have a similar size and position.
+pub fn overlap_in_percent(rect: &Rect, zone: &Rect) -> i32 {
+  let width = rect.right.min(zone.right) - rect.left.max(zone.left);
+  let height = rect.bottom.min(zone.bottom) - rect.top.max(zone.top);
+  if width <= 0 || height <= 0 {
+    return 0;
+  }
+  let intersection = i64::from(width) * i64::from(height);
+  let union =
+    i64::from(rect.width()) * i64::from(rect.height()) + i64::from(zone.width()) * i64::from(zone.height()) - intersection;
+
+  (intersection * 100 / union) as i32
+}
+
+/// Returns `true` if every edge of `rect` is within `tolerance` pixels of `expected`.
+pub fn is_within_tolerance(rect: &Rect, expected: &Sizing, tolerance: i32) -> bool {
+  (rect.left - expected.x).abs() <= tolerance
+    && (rect.top - expected.y).abs() <= tolerance
+    && (rect.width() - expected.width).abs() <= tolerance
+    && (rect.height() - expected.height).abs() <= tolerance
+}
+
+/// Returns the rect between `start` and `target` at `progress`, which is clamped to `0.0..=1.0`.
+pub fn interpolate(start: Rect, target: Rect, progress: f64) -> Rect {
+  fn coordinate(start: i32, target: i32, progress: f64) -> i32 {
+    (f64::from(start) + f64::from(target - start) * progress.clamp(0.0, 1.0)).round() as i32
+  }
+  Rect::new(
+    coordinate(start.left, target.left, progress),
+    coordinate(start.top, target.top, progress),
+    coordinate(start.right, target.right, progress),
+    coordinate(start.bottom, target.bottom, progress),
+  )
+}
+
+/// Returns `rect` with its width and height translated from `source_dpi` to `target_dpi`, keeping its top-left corner
+/// in place. Returns `rect` unchanged if either DPI is zero.
+pub fn scale_for_dpi(rect: Rect, source_dpi: u32, target_dpi: u32) -> Rect {
+  if source_dpi == 0 || target_dpi == 0 || source_dpi == target_dpi {
+    return rect;
+  }
+  let scale = f64::from(target_dpi) / f64::from(source_dpi);
+  let width = (f64::from(rect.width()) * scale).round() as i32;
+  let height = (f64::from(rect.height()) * scale).round() as i32;
+
+  Rect::new(rect.left, rect.top, rect.left + width, rect.top + height)
+}
+
+/// Returns `coordinate` rounded to the nearest multiple of `cell_size`. Returns `coordinate` unchanged if `cell_size`
+/// is not positive, which means grid snapping is disabled.
+pub fn snap_to_grid(coordinate: i32, cell_size: i32) -> i32 {
+  if cell_size <= 0 {
+    return coordinate;
+  }
+  (f64::from(coordinate) / f64::from(cell_size)).round() as i32 * cell_size
+}
+
+/// Returns the offset with its smaller component set to `0`, so that a window being moved only follows the cursor along
+/// the axis in which the cursor has moved furthest. Prefers the horizontal axis if both components are equal.
+pub fn lock_to_dominant_axis(offset_x: i32, offset_y: i32) -> (i32, i32) {
+  if offset_x.abs() >= offset_y.abs() {
+    (offset_x, 0)
+  } else {
+    (0, offset_y)
+  }
+}
+
+/// Returns `rect` with only the edges that are being moved by `resize_mode` snapped to the grid, so that the fixed
+/// edges of a window stay exactly where they are while it is being resized.
+pub fn snap_resized_edges_to_grid(rect: Rect, resize_mode: ResizeMode, cell_size: i32) -> Rect {
+  let (mut left, mut top, mut right, mut bottom) = (rect.left, rect.top, rect.right, rect.bottom);
+  match resize_mode {
+    ResizeMode::TopLeft | ResizeMode::BottomLeft => left = snap_to_grid(left, cell_size),
+    ResizeMode::TopRight | ResizeMode::BottomRight => right = snap_to_grid(right, cell_size),
+  }
+  match resize_mode {
+    ResizeMode::TopLeft | ResizeMode::TopRight => top = snap_to_grid(top, cell_size),
+    ResizeMode::BottomLeft | ResizeMode::BottomRight => bottom = snap_to_grid(bottom, cell_size),
+  }
+
+  Rect::new(left, top, right, bottom)
+}
+
+/// Returns `rect` moved so that its closest edge within `distance` lines up with the edge of a work area minus `margin`
+/// or with the opposite edge of a neighbouring window plus `margin`. Windows only attract an edge if they overlap with
+/// `rect` along that edge. Returns `rect` unchanged if no edge is within `distance` or if `distance` is not positive.
+pub fn attract_to_edges(rect: Rect, work_areas: &[Rect], windows: &[Rect], margin: i32, distance: i32) -> Rect {
+  if distance <= 0 {
+    return rect;
+  }
+  let horizontal_offsets = work_areas
+    .iter()
+    .flat_map(|area| [area.left + margin - rect.left, area.right - margin - rect.right])
+    .chain(
+      windows
+        .iter()
+        .filter(|window| window.top < rect.bottom && window.bottom > rect.top)
+        .flat_map(|window| [window.right + margin - rect.left, window.left - margin - rect.right]),
+    );
+  let vertical_offsets = work_areas
+    .iter()
+    .flat_map(|area| [area.top + margin - rect.top, area.bottom - margin - rect.bottom])
+    .chain(
+      windows
+        .iter()
+        .filter(|window| window.left < rect.right && window.right > rect.left)
+        .flat_map(|window| [window.bottom + margin - rect.top, window.top - margin - rect.bottom]),
+    );
+  let offset_x = nearest_offset(horizontal_offsets, distance);
+  let offset_y = nearest_offset(vertical_offsets, distance);
+
+  Rect::new(
+    rect.left + offset_x,
+    rect.top + offset_y,
+    rect.right + offset_x,
+    rect.bottom + offset_y,
+  )
+}
+
+fn nearest_offset(offsets: impl Iterator<Item = i32>, distance: i32) -> i32 {
+  offsets
+    .filter(|offset| offset.abs() <= distance)
+    .min_by_key(|offset| offset.abs())
+    .unwrap_or(0)
+}
+
+/// Returns `true` if `other` lies on the `side` of `rect`, separated from it by `margin` (plus or minus `tolerance`),
+/// and overlaps with `rect` along that edge i.e. if both share an edge.
+pub fn is_adjacent(rect: &Rect, other: &Rect, side: Direction, margin: i32, tolerance: i32) -> bool {
+  let (gap, overlaps) = match side {
+    Direction::Left => (rect.left - other.right, other.top < rect.bottom && other.bottom > rect.top),
+    Direction::Right => (other.left - rect.right, other.top < rect.bottom && other.bottom > rect.top),
+    Direction::Up => (rect.top - other.bottom, other.left < rect.right && other.right > rect.left),
+    Direction::Down => (other.top - rect.bottom, other.left < rect.right && other.right > rect.left),
+  };
+
+  overlaps && (gap - margin).abs() <= tolerance
+}
+
+/// Returns `rect` with only its edge on `side` moved by `offset`, where positive offsets move the edge to the right or
+/// down.
+pub fn move_edge(rect: Rect, side: Direction, offset: i32) -> Rect {
+  match side {
+    Direction::Left => Rect::new(rect.left + offset, rect.top, rect.right, rect.bottom),
+    Direction::Right => Rect::new(rect.left, rect.top, rect.right + offset, rect.bottom),
+    Direction::Up => Rect::new(rect.left, rect.top + offset, rect.right, rect.bottom),
+    Direction::Down => Rect::new(rect.left, rect.top, rect.right, rect.bottom + offset),
+  }
+}
@@ -0,0 +1,23 @@
+use crate::common::{HookHealth, WorkspaceSummary};
+use serde::{Deserialize, Serialize};
+
+/// Describes whether Randolf is working as expected, so that scripts (e.g. ones that run after logging in) can check
+/// more than whether the process is running.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthSummary {
+  pub version: String,
+  /// How long the main loop has been running, in seconds.
+  pub uptime_in_s: u64,
+  pub hooks: HookHealthSummary,
+  pub workspaces: Vec<WorkspaceSummary>,
+}
+
+/// The health of each hook through which Randolf receives input or window events.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HookHealthSummary {
+  pub hotkeys: HookHealth,
+  pub keyboard: HookHealth,
+  pub window_events: HookHealth,
+  pub touchpad_gestures: HookHealth,
+  pub display_changes: HookHealth,
+}
@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// How much later than the last callback of a hook the user must have provided input for the hook to be considered
+/// stale. Input is not specific to a hook (e.g. using only the mouse does not call a keyboard hook), so this is long
+/// enough to rule out that the user simply did not use the kind of input the hook is for.
+const STALE_HOOK_THRESHOLD_IN_MS: u32 = 5 * 60 * 1000;
+
+/// The health of a hook through which Randolf receives input or window events, as written to the log with every
+/// heartbeat. Windows silently removes hooks in some situations, which otherwise only shows as features that stop
+/// working.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HookHealth {
+  /// The feature that relies on the hook is disabled in the configuration.
+  Disabled,
+  /// The hook was registered successfully. Unless its activity is tracked (see [`HookActivity`]), this does not mean
+  /// that Windows still calls it.
+  Registered,
+  /// The hook is registered, but it has not been called for a while even though the user provided input since.
+  Stale,
+  /// The feature is enabled but the hook is not (or no longer) registered.
+  NotRegistered,
+}
+
+impl HookHealth {
+  pub fn new(is_enabled: bool, is_registered: bool) -> Self {
+    match (is_enabled, is_registered) {
+      (false, _) => HookHealth::Disabled,
+      (true, true) => HookHealth::Registered,
+      (true, false) => HookHealth::NotRegistered,
+    }
+  }
+
+  /// Returns [`HookHealth::Stale`] instead of [`HookHealth::Registered`] if `is_stale` is `true`.
+  pub fn stale_if(self, is_stale: bool) -> Self {
+    match self {
+      HookHealth::Registered if is_stale => HookHealth::Stale,
+      health => health,
+    }
+  }
+}
+
+impl Display for HookHealth {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      HookHealth::Disabled => write!(f, "disabled"),
+      HookHealth::Registered => write!(f, "registered"),
+      HookHealth::Stale => write!(f, "stale"),
+      HookHealth::NotRegistered => write!(f, "not registered"),
+    }
+  }
+}
+
+/// Records when a hook was last called, as a tick count in milliseconds like the time of the last input reported by
+/// Windows, so that a hook that Windows removed silently can be told apart from one that simply had nothing to report.
+#[derive(Debug)]
+pub struct HookActivity {
+  last_callback_at_in_ms: AtomicU32,
+}
+
+impl HookActivity {
+  pub const fn new() -> Self {
+    Self {
+      last_callback_at_in_ms: AtomicU32::new(0),
+    }
+  }
+
+  /// Records that the hook was called (or registered) at the given tick count.
+  pub fn record(&self, tick_count_in_ms: u32) {
+    self.last_callback_at_in_ms.store(tick_count_in_ms, Ordering::Relaxed);
+  }
+
+  /// Returns `true` if the user provided input more than [`STALE_HOOK_THRESHOLD_IN_MS`] after the hook was last called.
+  /// Always returns `false` if no call was recorded yet.
+  pub fn is_stale(&self, last_input_at_in_ms: u32) -> bool {
+    let last_callback_at_in_ms = self.last_callback_at_in_ms.load(Ordering::Relaxed);
+    if last_callback_at_in_ms == 0 {
+      return false;
+    }
+
+    // Tick counts wrap around after ~49 days, so input before the last callback results in a negative difference
+    last_input_at_in_ms.wrapping_sub(last_callback_at_in_ms) as i32 > STALE_HOOK_THRESHOLD_IN_MS as i32
+  }
+}
+
+impl Default for HookActivity {
+  fn default() -> Self {
+    Self::new()
+  }
+}
@@ -0,0 +1,189 @@
+/// The size in bytes of the header and of the single directory entry of an ICO file, after which the image starts.
+const ICO_IMAGE_OFFSET: usize = 6 + 16;
+const BITMAP_INFO_HEADER_SIZE: usize = 40;
+/// The luminance from which a pixel is considered light when converting an icon to high contrast.
+const LIGHT_LUMINANCE_THRESHOLD: u32 = 128;
+
+/// A square icon with 24-bit colours and a transparency mask, as stored in the bundled ICO files. Used to generate tray
+/// icons in the size and colours that Windows expects, instead of leaving it to Windows to scale the bundled icons.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct IconBitmap {
+  size: usize,
+  /// The colour of every pixel, row by row from the top, in the byte order of ICO files, i.e. blue, green, red.
+  pixels: Vec<[u8; 3]>,
+  /// Whether every pixel is transparent, row by row from the top.
+  is_transparent: Vec<bool>,
+}
+
+impl IconBitmap {
+  /// Reads the first image of an ICO file. Returns `None` unless the image is an uncompressed, square bitmap with 24-bit
+  /// colours, which is the format of all bundled icons.
+  pub(crate) fn from_ico(ico: &[u8]) -> Option<Self> {
+    let read_u16 =
+      |offset: usize| -> Option<u16> { Some(u16::from_le_bytes(ico.get(offset..offset + 2)?.try_into().ok()?)) };
+    let read_u32 =
+      |offset: usize| -> Option<u32> { Some(u32::from_le_bytes(ico.get(offset..offset + 4)?.try_into().ok()?)) };
+    let image_offset = read_u32(18)? as usize;
+    let header_size = read_u32(image_offset)? as usize;
+    let size = read_u32(image_offset + 4)? as usize;
+    let height = read_u32(image_offset + 8)? as usize;
+    let bit_count = read_u16(image_offset + 14)?;
+    let compression = read_u32(image_offset + 16)?;
+    if size == 0 || height != 2 * size || bit_count != 24 || compression != 0 {
+      return None;
+    }
+    let pixels_offset = image_offset + header_size;
+    let mask_offset = pixels_offset + pixel_row_length(size) * size;
+    if ico.len() < mask_offset + mask_row_length(size) * size {
+      return None;
+    }
+
+    let mut pixels = Vec::with_capacity(size * size);
+    let mut is_transparent = Vec::with_capacity(size * size);
+    for y in 0..size {
+      // Rows are stored from the bottom up
+      let row = size - 1 - y;
+      for x in 0..size {
+        let pixel = pixels_offset + row * pixel_row_length(size) + x * 3;
+        pixels.push([ico[pixel], ico[pixel + 1], ico[pixel + 2]]);
+        is_transparent.push(ico[mask_offset + row * mask_row_length(size) + x / 8] & (0x80 >> (x % 8)) != 0);
+      }
+    }
+
+    Some(Self {
+      size,
+      pixels,
+      is_transparent,
+    })
+  }
+
+  /// Writes the icon as an ICO file that contains only this image.
+  pub(crate) fn to_ico(&self) -> Vec<u8> {
+    let pixels_length = pixel_row_length(self.size) * self.size;
+    let image_length = BITMAP_INFO_HEADER_SIZE + pixels_length + mask_row_length(self.size) * self.size;
+    // Sizes of 256 pixels and more are stored as 0 in the directory entry
+    let entry_size = u8::try_from(self.size).unwrap_or(0);
+    let mut ico = Vec::with_capacity(ICO_IMAGE_OFFSET + image_length);
+    ico.extend_from_slice(&[0, 0, 1, 0, 1, 0]);
+    ico.extend_from_slice(&[entry_size, entry_size, 0, 0]);
+    ico.extend_from_slice(&1u16.to_le_bytes());
+    ico.extend_from_slice(&24u16.to_le_bytes());
+    ico.extend_from_slice(&(image_length as u32).to_le_bytes());
+    ico.extend_from_slice(&(ICO_IMAGE_OFFSET as u32).to_le_bytes());
+    ico.extend_from_slice(&(BITMAP_INFO_HEADER_SIZE as u32).to_le_bytes());
+    ico.extend_from_slice(&(self.size as u32).to_le_bytes());
+    ico.extend_from_slice(&(2 * self.size as u32).to_le_bytes());
+    ico.extend_from_slice(&1u16.to_le_bytes());
+    ico.extend_from_slice(&24u16.to_le_bytes());
+    ico.extend_from_slice(&0u32.to_le_bytes());
+    ico.extend_from_slice(&(pixels_length as u32).to_le_bytes());
+    ico.extend_from_slice(&[0; 16]);
+    for row in self.rows_from_bottom() {
+      let mut bytes = row.flat_map(|(pixel, _)| *pixel).collect::<Vec<_>>();
+      bytes.resize(pixel_row_length(self.size), 0);
+      ico.extend_from_slice(&bytes);
+    }
+    for row in self.rows_from_bottom() {
+      let mut bytes = vec![0; mask_row_length(self.size)];
+      for (x, (_, is_transparent)) in row.enumerate() {
+        if *is_transparent {
+          bytes[x / 8] |= 0x80 >> (x % 8);
+        }
+      }
+      ico.extend_from_slice(&bytes);
+    }
+
+    ico
+  }
+
+  pub(crate) fn size(&self) -> usize {
+    self.size
+  }
+
+  /// Returns a copy of the icon scaled to `size` by `size` pixels. Every pixel of the copy takes the average colour of
+  /// the opaque pixels of the area of the icon that it covers, and is transparent if most of that area is, so that
+  /// scaling down keeps the outline of the icon smooth and scaling up keeps the pixels crisp.
+  pub(crate) fn scaled(&self, size: usize) -> Self {
+    if size == self.size || size == 0 {
+      return self.clone();
+    }
+    let source_range = |target: usize| {
+      let start = target * self.size / size;
+      start..((target + 1) * self.size / size).max(start + 1)
+    };
+    let mut pixels = Vec::with_capacity(size * size);
+    let mut is_transparent = Vec::with_capacity(size * size);
+    for y in 0..size {
+      for x in 0..size {
+        let area = source_range(y)
+          .flat_map(|source_y| source_range(x).map(move |source_x| source_y * self.size + source_x))
+          .collect::<Vec<_>>();
+        let opaque = area
+          .iter()
+          .filter(|index| !self.is_transparent[**index])
+          .map(|index| self.pixels[*index])
+          .collect::<Vec<_>>();
+        if opaque.is_empty() || opaque.len() * 2 < area.len() {
+          pixels.push([0; 3]);
+          is_transparent.push(true);
+          continue;
+        }
+        let mut pixel = [0; 3];
+        for (channel, value) in pixel.iter_mut().enumerate() {
+          *value = (opaque.iter().map(|colour| colour[channel] as usize).sum::<usize>() / opaque.len()) as u8;
+        }
+        pixels.push(pixel);
+        is_transparent.push(false);
+      }
+    }
+
+    Self {
+      size,
+      pixels,
+      is_transparent,
+    }
+  }
+
+  /// Returns a copy of the icon that only uses two colours, e.g. those of the active high contrast theme: `light` for
+  /// opaque pixels that are light and `dark` for all other opaque pixels. Both colours must be given in the byte order
+  /// of ICO files, i.e. blue, green, red.
+  pub(crate) fn with_high_contrast(&self, light: [u8; 3], dark: [u8; 3]) -> Self {
+    let pixels = self
+      .pixels
+      .iter()
+      .map(|[blue, green, red]| {
+        let luminance = (114 * *blue as u32 + 587 * *green as u32 + 299 * *red as u32) / 1000;
+        if luminance >= LIGHT_LUMINANCE_THRESHOLD { light } else { dark }
+      })
+      .collect();
+
+    Self { pixels, ..self.clone() }
+  }
+
+  fn rows_from_bottom(&self) -> impl Iterator<Item = impl Iterator<Item = (&[u8; 3], &bool)>> {
+    (0..self.size).rev().map(move |y| {
+      let row = y * self.size..(y + 1) * self.size;
+      self.pixels[row.clone()].iter().zip(self.is_transparent[row].iter())
+    })
+  }
+}
+
+/// Returns the length in bytes of a row of 24-bit pixels, which is padded to a multiple of four bytes.
+fn pixel_row_length(size: usize) -> usize {
+  (size * 3).div_ceil(4) * 4
+}
+
+/// Returns the length in bytes of a row of the transparency mask, which uses one bit per pixel and is padded to a
+/// multiple of four bytes.
+fn mask_row_length(size: usize) -> usize {
+  size.div_ceil(32) * 4
+}
+
+#[cfg(test)]
+impl IconBitmap {
+  /// Returns the colour of the pixel at the given position, or `None` if it is transparent.
+  pub(crate) fn pixel(&self, x: usize, y: usize) -> Option<[u8; 3]> {
+    let index = y * self.size + x;
+    (!self.is_transparent[index]).then_some(self.pixels[index])
+  }
+}
@@ -0,0 +1,66 @@
+use crate::common::WindowHandle;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+const SETTLE_DURATION: Duration = Duration::from_millis(250);
+
+/// Collects location changes of windows and reports each window only once it has stopped moving, so that dragging or
+/// animating a window does not result in a flood of events.
+#[derive(Default)]
+pub struct LocationChangeDebouncer {
+  pending: HashMap<WindowHandle, Instant>,
+}
+
+impl LocationChangeDebouncer {
+  /// Records that the location of the window changed at the given time.
+  pub(crate) fn record(&mut self, handle: WindowHandle, now: Instant) {
+    self.pending.insert(handle, now);
+  }
+
+  /// Removes and returns all windows whose location has not changed for a while.
+  pub(crate) fn take_settled(&mut self, now: Instant) -> Vec<WindowHandle> {
+    let settled = self
+      .pending
+      .iter()
+      .filter(|(_, last_change)| now.duration_since(**last_change) >= SETTLE_DURATION)
+      .map(|(handle, _)| *handle)
+      .collect::<Vec<_>>();
+    for handle in &settled {
+      self.pending.remove(handle);
+    }
+
+    settled
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn take_settled_returns_window_once_it_stopped_moving() {
+    let mut debouncer = LocationChangeDebouncer::default();
+    let start = Instant::now();
+    debouncer.record(WindowHandle::new(1), start);
+    debouncer.record(WindowHandle::new(1), start + Duration::from_millis(200));
+
+    let before_settling = debouncer.take_settled(start + Duration::from_millis(300));
+    let after_settling = debouncer.take_settled(start + Duration::from_millis(450));
+
+    assert!(before_settling.is_empty());
+    assert_eq!(after_settling, vec![WindowHandle::new(1)]);
+  }
+
+  #[test]
+  fn take_settled_returns_each_window_only_once() {
+    let mut debouncer = LocationChangeDebouncer::default();
+    let start = Instant::now();
+    debouncer.record(WindowHandle::new(1), start);
+
+    let first = debouncer.take_settled(start + SETTLE_DURATION);
+    let second = debouncer.take_settled(start + SETTLE_DURATION * 2);
+
+    assert_eq!(first, vec![WindowHandle::new(1)]);
+    assert!(second.is_empty());
+  }
+}
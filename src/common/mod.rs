@@ -1,9 +1,19 @@
+mod bsp_tree;
 mod command;
+mod command_outcome;
+mod cursor_speed;
 mod direction;
 mod drag_state;
+mod focus_history;
+pub mod geometry;
+mod health_summary;
+mod hook_health;
+mod icon_bitmap;
+mod location_change_debouncer;
 mod monitor;
 mod monitor_handle;
 mod monitor_info;
+mod monitor_margins;
 mod monitors;
 mod persistent_workspace_id;
 mod placement;
@@ -13,22 +23,38 @@ mod resize_mode;
 mod resize_state;
 mod scrolling_strips;
 mod sizing;
+mod swipe_state;
 #[cfg(test)]
 mod tests;
 mod transient_workspace_id;
 mod width_preset;
+mod win_key_state;
 mod window;
 mod window_handle;
+mod window_inspection;
 mod window_placement;
+mod window_query;
 mod workspace;
 mod workspace_action;
+mod workspace_badge;
+mod workspace_summary;
+mod workspace_switch_latency;
 
+pub(crate) use crate::common::bsp_tree::BspTree;
 pub use crate::common::command::Command;
+pub use crate::common::command_outcome::CommandOutcome;
+pub use crate::common::cursor_speed::CursorSpeed;
 pub use crate::common::direction::Direction;
 pub use crate::common::drag_state::DragState;
+pub(crate) use crate::common::focus_history::FocusHistory;
+pub use crate::common::health_summary::{HealthSummary, HookHealthSummary};
+pub use crate::common::hook_health::{HookActivity, HookHealth};
+pub(crate) use crate::common::icon_bitmap::IconBitmap;
+pub use crate::common::location_change_debouncer::LocationChangeDebouncer;
 pub use crate::common::monitor::Monitor;
 pub use crate::common::monitor_handle::MonitorHandle;
 pub use crate::common::monitor_info::MonitorInfo;
+pub(crate) use crate::common::monitor_margins::MonitorMargins;
 pub use crate::common::monitors::Monitors;
 pub use crate::common::persistent_workspace_id::PersistentWorkspaceId;
 pub(crate) use crate::common::placement::Placement;
@@ -38,10 +64,17 @@ pub use crate::common::resize_mode::ResizeMode;
 pub use crate::common::resize_state::ResizeState;
 pub(crate) use crate::common::scrolling_strips::ScrollingStrips;
 pub use crate::common::sizing::Sizing;
+pub use crate::common::swipe_state::SwipeState;
 pub use crate::common::transient_workspace_id::TransientWorkspaceId;
 pub(crate) use crate::common::width_preset::WidthPreset;
+pub use crate::common::win_key_state::{WinKeyRelease, WinKeyState};
 pub use crate::common::window::Window;
 pub use crate::common::window_handle::WindowHandle;
+pub use crate::common::window_inspection::WindowInspection;
 pub use crate::common::window_placement::WindowPlacement;
+pub use crate::common::window_query::{WindowQuery, WindowSummary, WindowVisibility};
 pub use crate::common::workspace::Workspace;
 pub use crate::common::workspace_action::WorkspaceAction;
+pub use crate::common::workspace_badge::WorkspaceBadge;
+pub use crate::common::workspace_summary::WorkspaceSummary;
+pub use crate::common::workspace_switch_latency::{WorkspaceSwitchLatency, WorkspaceSwitchTiming};
@@ -0,0 +1,47 @@
+use crate::common::PersistentWorkspaceId;
+use std::collections::HashMap;
+
+/// The window margin of each monitor, for operations that arrange the windows of several monitors at once. Margins can
+/// differ between monitors, e.g. if they are scaled to the resolution of each monitor.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct MonitorMargins {
+  by_monitor: HashMap<[u16; 32], i32>,
+  fallback: i32,
+}
+
+impl MonitorMargins {
+  /// Creates margins that use `fallback` for every monitor without a margin of its own.
+  pub(crate) fn new(fallback: i32) -> Self {
+    Self {
+      by_monitor: HashMap::new(),
+      fallback,
+    }
+  }
+
+  pub(crate) fn insert(&mut self, monitor_id: [u16; 32], margin: i32) {
+    self.by_monitor.insert(monitor_id, margin);
+  }
+
+  pub(crate) fn for_monitor(&self, monitor_id: &[u16; 32]) -> i32 {
+    self.by_monitor.get(monitor_id).copied().unwrap_or(self.fallback)
+  }
+
+  pub(crate) fn for_workspace(&self, workspace: PersistentWorkspaceId) -> i32 {
+    self.for_monitor(&workspace.monitor_id)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn for_workspace_returns_margin_of_its_monitor_or_fallback() {
+    let mut margins = MonitorMargins::new(20);
+    let workspace = PersistentWorkspaceId::new_test(1);
+    margins.insert(workspace.monitor_id, 35);
+
+    assert_eq!(margins.for_workspace(workspace), 35);
+    assert_eq!(margins.for_monitor(&[0; 32]), 20);
+  }
+}
@@ -43,10 +43,6 @@ impl PersistentWorkspaceId {
   pub fn is_same_monitor(&self, other: &Self) -> bool {
     self.monitor_id == other.monitor_id
   }
-
-  pub fn is_same_workspace(&self, other: &Self) -> bool {
-    self.workspace == other.workspace
-  }
 }
 
 impl Display for PersistentWorkspaceId {
@@ -177,20 +173,4 @@ mod tests {
 
     assert!(!id1.is_same_monitor(&id2));
   }
-
-  #[test]
-  fn workspace_id_same_workspace_returns_true() {
-    let id1 = PersistentWorkspaceId::new([1; 32], 1, true);
-    let id2 = PersistentWorkspaceId::new([2; 32], 1, false);
-
-    assert!(id1.is_same_workspace(&id2));
-  }
-
-  #[test]
-  fn workspace_id_different_workspace_returns_false() {
-    let id1 = PersistentWorkspaceId::new([1; 32], 1, true);
-    let id2 = PersistentWorkspaceId::new([1; 32], 2, true);
-
-    assert!(!id1.is_same_workspace(&id2));
-  }
 }
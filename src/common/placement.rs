@@ -1,16 +1,27 @@
 use crate::api::WindowsApi;
-use crate::common::{Direction, MonitorInfo, Rect, Sizing, WindowHandle, WindowPlacement};
+use crate::common::{Direction, MonitorInfo, Point, Rect, Sizing, WindowHandle, WindowPlacement, geometry};
 use crate::utils::MINIMUM_WINDOW_MARGIN;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use windows::Win32::UI::WindowsAndMessaging::SW_MAXIMIZE;
 
 const REGULAR_TOLERANCE_IN_PX: i32 = 2;
 pub(super) const DWM_TOLERANCE_IN_PX: i32 = 8;
+const PLACEMENT_HISTORY_LENGTH: usize = 5;
+/// How much of the width and height of a window must be on a monitor for the user to be able to grab it with the mouse.
+const MINIMUM_VISIBLE_SIZE_IN_PX: i32 = 50;
 
 /// Remembers window positions and applies Windows-aware sizing corrections.
 #[derive(Default)]
 pub(crate) struct Placement {
   pub(super) known_windows: HashMap<String, WindowPlacement>,
+  /// The most recent placements remembered for each window, most recent first. Unlike [`Placement::known_windows`],
+  /// which only holds the most recent placement, these allow older placements to be restored.
+  pub(super) placement_history: HashMap<WindowHandle, VecDeque<WindowPlacement>>,
+  /// The placement and "always on top" state windows had before being pinned as picture-in-picture.
+  pub(super) picture_in_picture_windows: HashMap<WindowHandle, (WindowPlacement, bool)>,
+  /// Whether to place near-maximised windows instantly instead of maximising them first, which causes a visible flash
+  /// on some systems.
+  pub(crate) skip_near_maximise_animation: bool,
 }
 
 impl Placement {
@@ -36,12 +47,40 @@ impl Placement {
     let window_id = format!("{:?}", handle.hwnd);
     if let Some(previous_placement) = self.known_windows.get(&window_id) {
       info!("Restoring previous placement for {}", window_id);
-      api.do_restore_window_placement(handle, previous_placement.clone());
+      let validated_placement = fit_to_current_monitors(api, handle, previous_placement.clone());
+      let previous_rect = api.get_window_rect(handle);
+      api.do_restore_window_placement(handle, validated_placement);
+      self.move_owned_windows(api, handle, previous_rect);
     } else {
       warn!("No previous placement found for {}", window_id);
     }
   }
 
+  /// Restores the placement remembered before the one the window is currently in, cycling back to the most recent
+  /// placement after the oldest. Restores the most recent placement if the window is in none of the remembered
+  /// placements, e.g. because it is near-maximised.
+  pub(crate) fn restore_older<T: WindowsApi>(&self, api: &T, handle: WindowHandle, current_placement: &WindowPlacement) {
+    let window_id = format!("{:?}", handle.hwnd);
+    let Some(history) = self.placement_history.get(&handle).filter(|history| !history.is_empty()) else {
+      warn!("No older placement found for {}", window_id);
+      return;
+    };
+    let index = history
+      .iter()
+      .position(|placement| is_same_position(placement, current_placement))
+      .map_or(0, |index| (index + 1) % history.len());
+    info!(
+      "Restoring placement [{}] of [{}] remembered for {}",
+      index + 1,
+      history.len(),
+      window_id
+    );
+    let validated_placement = fit_to_current_monitors(api, handle, history[index].clone());
+    let previous_rect = api.get_window_rect(handle);
+    api.do_restore_window_placement(handle, validated_placement);
+    self.move_owned_windows(api, handle, previous_rect);
+  }
+
   /// Reports whether a window fills its work area apart from the configured margin.
   pub(crate) fn is_near_maximised<T: WindowsApi>(
     &self,
@@ -58,7 +97,7 @@ impl Placement {
 
     let expected = Sizing::near_maximised(monitor_info.work_area, margin);
     if let Some(rect) = api.get_window_rect(*handle) {
-      let result = is_sizing_within_tolerance(rect, &expected, REGULAR_TOLERANCE_IN_PX);
+      let result = geometry::is_within_tolerance(&rect, &expected, REGULAR_TOLERANCE_IN_PX);
       log_actual_vs_expected(handle, &expected, rect);
       debug!(
         "{} {} near-maximised (tolerance: {})",
@@ -84,7 +123,7 @@ impl Placement {
   ) -> bool {
     let expected = Sizing::three_quarter_near_maximised(monitor_info.work_area, direction, margin);
     if let Some(rect) = api.get_window_rect(*handle) {
-      let result = is_sizing_within_tolerance(rect, &expected, REGULAR_TOLERANCE_IN_PX);
+      let result = geometry::is_within_tolerance(&rect, &expected, REGULAR_TOLERANCE_IN_PX);
       debug!(
         "{} {} three-quarter near-maximised in [{:?}] direction (tolerance: {})",
         handle,
@@ -105,20 +144,103 @@ impl Placement {
   /// Expands a window to its work area while keeping the configured margin.
   pub(crate) fn near_maximise<T: WindowsApi>(&self, api: &T, handle: WindowHandle, monitor_info: MonitorInfo, margin: i32) {
     info!("Near-maximising {}", handle);
+    let previous_rect = api.get_window_rect(handle);
 
-    // First maximise to get the animation effect
-    api.do_maximise_window(handle);
+    // First maximise to get the animation effect, unless disabled - without a margin, maximising is the actual result
+    if !self.skip_near_maximise_animation || margin < MINIMUM_WINDOW_MARGIN {
+      api.do_maximise_window(handle);
+    }
 
     // Then resize the window to the expected size
     if margin >= MINIMUM_WINDOW_MARGIN {
-      self.resize(api, handle, Sizing::near_maximised(monitor_info.work_area, margin), margin);
+      let sizing = Sizing::near_maximised(monitor_info.work_area, margin);
+      self.resize_from(api, handle, sizing, margin, previous_rect);
+    } else {
+      self.move_owned_windows(api, handle, previous_rect);
     }
   }
 
-  /// Applies a size and corrects hidden Windows borders when margins are disabled.
+  /// Applies a size and corrects hidden Windows borders when margins are disabled. Windows that are bitmap-scaled by
+  /// Windows need no translation, because this application declares per-monitor DPI awareness in its manifest, so
+  /// Windows expects and reports the placement of all windows in physical pixels. Sizes that would leave the window
+  /// off-screen are moved onto the nearest monitor (see [`Placement::keep_on_screen`]).
   pub(crate) fn resize<T: WindowsApi>(&self, api: &T, handle: WindowHandle, sizing: Sizing, margin: i32) {
+    self.resize_from(api, handle, sizing, margin, api.get_window_rect(handle));
+  }
+
+  /// Same as [`Placement::resize`], but takes the rect the window had before it was last moved, which is used to move
+  /// the windows it owns along with it.
+  fn resize_from<T: WindowsApi>(
+    &self,
+    api: &T,
+    handle: WindowHandle,
+    sizing: Sizing,
+    margin: i32,
+    previous_rect: Option<Rect>,
+  ) {
+    let sizing = Sizing::from(self.keep_on_screen(api, handle, Rect::from(sizing)));
     api.set_window_placement_and_force_repaint(handle, WindowPlacement::new_from_sizing(sizing.clone()));
     self.correct_hidden_borders(api, handle, &sizing, margin);
+    self.move_owned_windows(api, handle, previous_rect);
+  }
+
+  /// Returns `rect` if enough of it is on one of the current monitors for the user to grab it, otherwise `rect` centred
+  /// within the work area nearest to it, shrunk if it is larger. This is the final guard against placing a window where
+  /// the user cannot reach it, e.g. because the monitor it was computed for has since been disconnected. Returns `rect`
+  /// unchanged if no monitor is known.
+  pub(crate) fn keep_on_screen<T: WindowsApi>(&self, api: &T, handle: WindowHandle, rect: Rect) -> Rect {
+    let work_areas = api
+      .get_all_monitors()
+      .get_all()
+      .iter()
+      .map(|monitor| monitor.work_area)
+      .collect::<Vec<_>>();
+    if geometry::is_visible_within(&rect, &work_areas, MINIMUM_VISIBLE_SIZE_IN_PX) {
+      return rect;
+    }
+    let Some(work_area) = geometry::nearest(&rect, &work_areas) else {
+      return rect;
+    };
+    let rescued_rect = geometry::centre_within(&rect, work_area);
+    warn!("Moved {handle} from {rect} to {rescued_rect} because it would have been off-screen");
+
+    rescued_rect
+  }
+
+  /// Moves the visible windows owned by a window along with it, so that they are not left stranded off-screen or on
+  /// another monitor after the window was moved or resized. Owned windows keep their position relative to the owner's
+  /// top-left corner, which preserves the layout of e.g. tool palettes. While the owner is disabled, i.e. one of its
+  /// modal dialogs is showing, they are re-centred over it instead.
+  fn move_owned_windows<T: WindowsApi>(&self, api: &T, handle: WindowHandle, previous_rect: Option<Rect>) {
+    let Some(owner_rect) = api.get_window_rect(handle) else {
+      return;
+    };
+    let is_showing_modal_dialog = !api.is_window_enabled(handle);
+    let owner_center = Point::from_center_of_rect(&owner_rect);
+    for window in api.get_owned_windows(handle) {
+      let rect = if is_showing_modal_dialog {
+        let width = window.rect.width();
+        let height = window.rect.height();
+        let left = owner_center.x() - width / 2;
+        let top = owner_center.y() - height / 2;
+        Rect::new(left, top, left + width, top + height)
+      } else if let Some(previous_rect) = previous_rect {
+        let dx = owner_rect.left - previous_rect.left;
+        let dy = owner_rect.top - previous_rect.top;
+        Rect::new(
+          window.rect.left + dx,
+          window.rect.top + dy,
+          window.rect.right + dx,
+          window.rect.bottom + dy,
+        )
+      } else {
+        continue;
+      };
+      if rect != window.rect {
+        debug!("Moving {} along with its owner {} to {}", window.handle, handle, rect);
+        api.set_window_position(window.handle, rect);
+      }
+    }
   }
 
   fn correct_hidden_borders<T: WindowsApi>(&self, api: &T, handle: WindowHandle, sizing: &Sizing, margin: i32) {
@@ -136,6 +258,9 @@ impl Placement {
   ///
   /// This extra check may be useful in all cases, but the Windows API behaviour is not sufficiently understood to apply
   /// it more broadly yet.
+  ///
+  /// The `size_tolerance_in_px` allows windows that only resize in increments (e.g. terminals, which resize to multiples
+  /// of their character cell size) to deviate from the expected size and is `0` for all other windows.
   pub(crate) fn is_of_expected_size<T: WindowsApi>(
     &self,
     api: &T,
@@ -143,6 +268,7 @@ impl Placement {
     placement: &WindowPlacement,
     sizing: &Sizing,
     margin: i32,
+    size_tolerance_in_px: i32,
   ) -> bool {
     let rect = placement.normal_position;
     let exact = rect.left == sizing.x
@@ -155,10 +281,19 @@ impl Placement {
       return true;
     }
 
+    if size_tolerance_in_px > 0 && geometry::is_within_tolerance(&rect, sizing, size_tolerance_in_px) {
+      log_actual_vs_expected(&handle, sizing, rect);
+      debug!(
+        "{} is currently of expected size (size_tolerance: {})",
+        handle, size_tolerance_in_px
+      );
+      return true;
+    }
+
     if margin == 0
       && let Some(compensating_rect) = api.get_extended_frame_bounds(handle).or_else(|| api.get_window_rect(handle))
     {
-      let matches = is_sizing_within_tolerance(compensating_rect, sizing, DWM_TOLERANCE_IN_PX);
+      let matches = geometry::is_within_tolerance(&compensating_rect, sizing, DWM_TOLERANCE_IN_PX);
       log_actual_vs_expected(&handle, sizing, compensating_rect);
       debug!(
         "{} {} of expected size (dwm_tolerance: {})",
@@ -174,7 +309,68 @@ impl Placement {
     false
   }
 
-  fn remember(&mut self, handle: WindowHandle, placement: WindowPlacement) {
+  /// Shrinks the window to `rect` and pins it on top of all other windows, remembering its placement and "always on
+  /// top" state so that [`Placement::unpin_picture_in_picture`] can restore them.
+  pub(crate) fn pin_picture_in_picture<T: WindowsApi>(
+    &mut self,
+    api: &T,
+    handle: WindowHandle,
+    placement: WindowPlacement,
+    rect: Rect,
+  ) {
+    info!("Pinning {} as picture-in-picture at {}", handle, rect);
+    let was_always_on_top = api.is_window_always_on_top(handle);
+    self.picture_in_picture_windows.insert(handle, (placement, was_always_on_top));
+    api.set_window_placement_and_force_repaint(handle, WindowPlacement::new_from_sizing(Sizing::from(rect)));
+    api.set_window_always_on_top(handle, true);
+  }
+
+  /// Restores the placement and "always on top" state the window had before it was pinned as picture-in-picture.
+  /// Returns `false` if the window is not pinned.
+  pub(crate) fn unpin_picture_in_picture<T: WindowsApi>(&mut self, api: &T, handle: WindowHandle) -> bool {
+    let Some((placement, was_always_on_top)) = self.picture_in_picture_windows.remove(&handle) else {
+      return false;
+    };
+    info!("Unpinning {} from picture-in-picture", handle);
+    if !was_always_on_top {
+      api.set_window_always_on_top(handle, false);
+    }
+    api.do_restore_window_placement(handle, fit_to_current_monitors(api, handle, placement));
+
+    true
+  }
+
+  /// Returns `true` if a previous position is remembered for the window.
+  pub(crate) fn remembers(&self, handle: WindowHandle) -> bool {
+    self.known_windows.contains_key(&format!("{:?}", handle.hwnd))
+  }
+
+  /// Forgets a window's previous positions, e.g. because they have become stale.
+  pub(crate) fn forget(&mut self, handle: WindowHandle) {
+    if self.known_windows.remove(&format!("{:?}", handle.hwnd)).is_some() {
+      trace!("Removed previous placement for window {}", handle);
+    }
+    self.placement_history.remove(&handle);
+  }
+
+  /// Forgets the previous positions of windows that no longer exist, so that they do not accumulate and are not applied
+  /// to new windows that Windows assigns the same handle to.
+  pub(crate) fn remove_dead_windows<T: WindowsApi>(&mut self, api: &T) {
+    let dead_windows = self
+      .placement_history
+      .keys()
+      .chain(self.picture_in_picture_windows.keys())
+      .filter(|handle| !api.is_window_alive(**handle))
+      .copied()
+      .collect::<Vec<_>>();
+    for handle in dead_windows {
+      self.forget(handle);
+      self.picture_in_picture_windows.remove(&handle);
+    }
+  }
+
+  /// Remembers a window's position so that [`Placement::restore_previous`] can restore it later.
+  pub(crate) fn remember(&mut self, handle: WindowHandle, placement: WindowPlacement) {
     let window_id = format!("{:?}", handle.hwnd);
     if self.known_windows.remove(&window_id).is_some() {
       trace!(
@@ -182,16 +378,49 @@ impl Placement {
         handle
       );
     }
+    let history = self.placement_history.entry(handle).or_default();
+    history.retain(|previous_placement| !is_same_position(previous_placement, &placement));
+    history.push_front(placement.clone());
+    history.truncate(PLACEMENT_HISTORY_LENGTH);
     self.known_windows.insert(window_id, placement);
     trace!("Adding/updating previous placement for window {}", handle);
   }
 }
 
-fn is_sizing_within_tolerance(rect: Rect, expected: &Sizing, tolerance: i32) -> bool {
-  (rect.left - expected.x).abs() <= tolerance
-    && (rect.top - expected.y).abs() <= tolerance
-    && (rect.right - rect.left - expected.width).abs() <= tolerance
-    && (rect.bottom - rect.top - expected.height).abs() <= tolerance
+/// Returns `true` if both placements put the window in the same position, allowing for the offsets of invisible borders.
+fn is_same_position(placement: &WindowPlacement, other: &WindowPlacement) -> bool {
+  geometry::is_within_tolerance(
+    &placement.normal_position,
+    &Sizing::from(other.normal_position),
+    DWM_TOLERANCE_IN_PX,
+  )
+}
+
+/// Returns the placement with its normal position moved inside the work area of a monitor that still exists. A stored
+/// placement can be (partially) off-screen if its monitor has been removed or its resolution has changed since it was
+/// remembered. Rects whose centre is still on a monitor are nudged inside its work area, all others are re-homed to
+/// the centre of the monitor the window is currently on.
+fn fit_to_current_monitors<T: WindowsApi>(api: &T, handle: WindowHandle, mut placement: WindowPlacement) -> WindowPlacement {
+  let rect = placement.normal_position;
+  let centre = rect.center();
+  let fitted = match api
+    .get_all_monitors()
+    .get_all()
+    .into_iter()
+    .find(|monitor| monitor.work_area.contains(&centre))
+  {
+    Some(monitor) => geometry::fit_into(&rect, &monitor.work_area),
+    None => match api.get_monitor_info_for_window(handle) {
+      Some(monitor_info) => geometry::centre_within(&rect, &monitor_info.work_area),
+      None => return placement,
+    },
+  };
+  if fitted != rect {
+    info!("Adjusted previous placement of {handle} from {rect} to {fitted} to fit the current monitors");
+    placement.normal_position = fitted;
+  }
+
+  placement
 }
 
 fn log_actual_vs_expected(handle: &WindowHandle, sizing: &Sizing, rect: Rect) {
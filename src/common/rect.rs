@@ -1,10 +1,11 @@
-use crate::common::Point;
+use crate::common::{Point, geometry};
+use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 use windows::Win32::Foundation::RECT;
 
 /// A simple wrapper around a Windows [`RECT`] i.e. a rectangle defined by its left, top, right, and bottom edges. Its
 /// purpose is simply to abstract away from the Windows API and to provide a handful of utility methods.
-#[derive(Debug, Hash, PartialEq, Eq, Copy, Clone, Default)]
+#[derive(Debug, Hash, PartialEq, Eq, Copy, Clone, Default, Deserialize, Serialize)]
 pub struct Rect {
   pub left: i32,
   pub top: i32,
@@ -46,13 +47,13 @@ impl Rect {
     self.left < other.right && self.right > other.left && self.top < other.bottom && self.bottom > other.top
   }
 
+  /// Returns `true` if this rect covers all of `other`, e.g. if a window is full-screen on a monitor.
+  pub fn covers(&self, other: &Self) -> bool {
+    self.left <= other.left && self.top <= other.top && self.right >= other.right && self.bottom >= other.bottom
+  }
+
   pub fn clamp(&self, other: &Self, margin: i32) -> Self {
-    Self {
-      left: self.left.max(other.left + margin),
-      top: self.top.max(other.top + margin),
-      right: self.right.min(other.right - margin),
-      bottom: self.bottom.min(other.bottom - margin),
-    }
+    geometry::clamp(self, other, margin)
   }
 }
 
@@ -287,4 +288,13 @@ mod tests {
 
     assert!(rect1.intersects(&rect2));
   }
+
+  #[test]
+  fn covers_returns_true_for_equal_and_larger_rects_only() {
+    let monitor_area = Rect::new(0, 0, 1920, 1080);
+
+    assert!(Rect::new(0, 0, 1920, 1080).covers(&monitor_area));
+    assert!(Rect::new(-8, -8, 1928, 1088).covers(&monitor_area));
+    assert!(!Rect::new(0, 0, 1920, 1040).covers(&monitor_area));
+  }
 }
@@ -7,6 +7,7 @@ pub struct ResizeState {
   window_start_rect: Rect,
   window_handle: Option<WindowHandle>,
   resize_mode: ResizeMode,
+  is_free_floating: bool,
 }
 
 impl ResizeState {
@@ -24,6 +25,17 @@ impl ResizeState {
     self.resize_mode = resize_mode;
   }
 
+  /// Sets whether the window is resized on a monitor whose active workspace is free-floating, in which case its edges
+  /// are not snapped to the grid.
+  pub(crate) fn set_free_floating(&mut self, is_free_floating: bool) {
+    self.is_free_floating = is_free_floating;
+  }
+
+  /// Returns `true` if the window is resized on a monitor whose active workspace is free-floating.
+  pub(crate) fn is_free_floating(&self) -> bool {
+    self.is_free_floating
+  }
+
   /// Returns the starting position of the cursor at the beginning of the resize operation.
   pub(crate) fn get_cursor_start_position(&self) -> Point {
     self.cursor_start_position
@@ -55,6 +67,7 @@ impl ResizeState {
     self.cursor_start_position = Point::default();
     self.window_start_rect = Rect::default();
     self.window_handle = None;
+    self.is_free_floating = false;
   }
 }
 
@@ -96,11 +109,13 @@ mod tests {
     let resize_mode = ResizeMode::TopRight;
 
     state.set(cursor_position, window_handle, window_rect, resize_mode);
+    state.set_free_floating(true);
     state.reset();
 
     assert_eq!(state.get_cursor_start_position(), Point::default());
     assert_eq!(state.get_window_start_rect(), Rect::default());
     assert!(state.get_window_handle().is_none());
+    assert!(!state.is_free_floating());
   }
 
   #[test]
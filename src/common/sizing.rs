@@ -1,4 +1,4 @@
-use crate::common::{Direction, Rect};
+use crate::common::{Direction, Point, Rect, geometry};
 
 /// Represents the size and position of a window, as does [`Rect`], but expresses it in terms of its top-left corner,
 /// and width and height. (Could be merged with [`Rect`] but I have kept it separate for now because [`Sizing`] is
@@ -17,82 +17,56 @@ impl Sizing {
   }
 
   pub fn right_half_of_screen(work_area: Rect, margin: i32) -> Self {
-    Self {
-      x: work_area.left + (work_area.right - work_area.left) / 2 + margin / 2,
-      y: work_area.top + margin,
-      width: (work_area.right - work_area.left) / 2 - margin - margin / 2,
-      height: work_area.bottom - work_area.top - margin * 2,
-    }
+    geometry::half(work_area, Direction::Right, margin)
   }
 
   pub fn left_half_of_screen(work_area: Rect, margin: i32) -> Self {
-    Self {
-      x: work_area.left + margin,
-      y: work_area.top + margin,
-      width: (work_area.right - work_area.left) / 2 - margin - margin / 2,
-      height: work_area.bottom - work_area.top - margin * 2,
-    }
+    geometry::half(work_area, Direction::Left, margin)
   }
 
   pub fn top_half_of_screen(work_area: Rect, margin: i32) -> Self {
-    Self {
-      x: work_area.left + margin,
-      y: work_area.top + margin,
-      width: work_area.right - work_area.left - margin * 2,
-      height: (work_area.bottom - work_area.top) / 2 - margin - margin / 2,
-    }
+    geometry::half(work_area, Direction::Up, margin)
   }
 
   pub fn bottom_half_of_screen(work_area: Rect, margin: i32) -> Self {
-    Self {
-      x: work_area.left + margin,
-      y: work_area.top + (work_area.bottom - work_area.top) / 2 + margin / 2,
-      width: work_area.right - work_area.left - margin * 2,
-      height: (work_area.bottom - work_area.top) / 2 - margin - margin / 2,
-    }
+    geometry::half(work_area, Direction::Down, margin)
+  }
+
+  pub fn left_third_of_screen(work_area: Rect, margin: i32) -> Self {
+    geometry::thirds(work_area, 0, 1, margin)
+  }
+
+  pub fn center_third_of_screen(work_area: Rect, margin: i32) -> Self {
+    geometry::thirds(work_area, 1, 1, margin)
+  }
+
+  pub fn right_third_of_screen(work_area: Rect, margin: i32) -> Self {
+    geometry::thirds(work_area, 2, 1, margin)
+  }
+
+  pub fn left_two_thirds_of_screen(work_area: Rect, margin: i32) -> Self {
+    geometry::thirds(work_area, 0, 2, margin)
+  }
+
+  pub fn right_two_thirds_of_screen(work_area: Rect, margin: i32) -> Self {
+    geometry::thirds(work_area, 1, 2, margin)
+  }
+
+  /// Returns a new [`Sizing`] for the quarter of the screen in the corner of `horizontal` and `vertical`, e.g. `Left`
+  /// and `Up` for the top-left quarter. Its edges line up with those of the halves of the screen.
+  pub fn quarter_of_screen(work_area: Rect, horizontal: Direction, vertical: Direction, margin: i32) -> Self {
+    geometry::quarter(work_area, horizontal, vertical, margin)
   }
 
   pub fn near_maximised(work_area: Rect, margin: i32) -> Self {
-    Self {
-      x: work_area.left + margin,
-      y: work_area.top + margin,
-      width: work_area.right - work_area.left - margin * 2,
-      height: work_area.bottom - work_area.top - margin * 2,
-    }
+    geometry::near_maximised(work_area, margin)
   }
 
   /// Returns a new [`Sizing`] that is 75% of the near-maximised size in the dimension corresponding to the given
   /// direction. The edge on the arrow-key side is anchored to the near-maximised edge; a gap of `margin / 2` is
   /// subtracted at the split edge only (matching [`halved`](Self::halved) exactly).
   pub fn three_quarter_near_maximised(work_area: Rect, direction: Direction, margin: i32) -> Self {
-    let near_max = Self::near_maximised(work_area, margin);
-    let half_margin = margin / 2;
-    match direction {
-      Direction::Left => Self {
-        x: near_max.x,
-        y: near_max.y,
-        width: near_max.width * 3 / 4 - half_margin,
-        height: near_max.height,
-      },
-      Direction::Right => Self {
-        x: near_max.x + near_max.width / 4 + half_margin,
-        y: near_max.y,
-        width: near_max.width * 3 / 4 - half_margin,
-        height: near_max.height,
-      },
-      Direction::Up => Self {
-        x: near_max.x,
-        y: near_max.y,
-        width: near_max.width,
-        height: near_max.height * 3 / 4 - half_margin,
-      },
-      Direction::Down => Self {
-        x: near_max.x,
-        y: near_max.y + near_max.height / 4 + half_margin,
-        width: near_max.width,
-        height: near_max.height * 3 / 4 - half_margin,
-      },
-    }
+    geometry::three_quarters(work_area, direction, margin)
   }
 
   /// Returns a new [`Sizing`] occupying the centre half of the near-maximised area in the axis corresponding to
@@ -102,56 +76,81 @@ impl Sizing {
   ///
   /// [`three_quarter_near_maximised`]: Self::three_quarter_near_maximised
   pub fn centre_near_maximised(work_area: Rect, direction: Direction, margin: i32) -> Self {
-    let near_max = Self::near_maximised(work_area, margin);
-    let half_margin = margin / 2;
-    match direction {
-      Direction::Left | Direction::Right => Self {
-        x: near_max.x + near_max.width / 4 + half_margin,
-        y: near_max.y,
-        width: near_max.width / 2 - margin,
-        height: near_max.height,
-      },
-      Direction::Up | Direction::Down => Self {
-        x: near_max.x,
-        y: near_max.y + near_max.height / 4 + half_margin,
-        width: near_max.width,
-        height: near_max.height / 2 - margin,
-      },
+    geometry::centre_half(work_area, direction, margin)
+  }
+
+  /// Returns the equivalent [`Sizing`] on `to_work_area` if this sizing is near-maximised or snapped to a half of
+  /// `from_work_area`, otherwise `None`.
+  pub fn refit(&self, from_work_area: Rect, to_work_area: Rect, margin: i32) -> Option<Self> {
+    let sizings: [fn(Rect, i32) -> Self; 5] = [
+      Self::near_maximised,
+      Self::left_half_of_screen,
+      Self::right_half_of_screen,
+      Self::top_half_of_screen,
+      Self::bottom_half_of_screen,
+    ];
+    sizings
+      .iter()
+      .find(|sizing| *self == sizing(from_work_area, margin))
+      .map(|sizing| sizing(to_work_area, margin))
+  }
+
+  /// Returns the [`Sizing`] of the snap zone that `cursor` hovers over while dragging a window, if it is within
+  /// `distance` of an edge of `work_area`: a quarter in each corner, the left or right half along the left or right
+  /// edge, the third below the cursor along the top edge, and the bottom half along the bottom edge.
+  pub fn for_snap_zone(work_area: Rect, cursor: Point, distance: i32, margin: i32) -> Option<Self> {
+    if distance <= 0 || !work_area.contains(&cursor) {
+      return None;
+    }
+    let horizontal = if cursor.x() < work_area.left + distance {
+      Some(Direction::Left)
+    } else if cursor.x() >= work_area.right - distance {
+      Some(Direction::Right)
+    } else {
+      None
+    };
+    let vertical = if cursor.y() < work_area.top + distance {
+      Some(Direction::Up)
+    } else if cursor.y() >= work_area.bottom - distance {
+      Some(Direction::Down)
+    } else {
+      None
+    };
+    match (horizontal, vertical) {
+      (Some(horizontal), Some(vertical)) => Some(Self::quarter_of_screen(work_area, horizontal, vertical, margin)),
+      (Some(horizontal), None) => Some(geometry::half(work_area, horizontal, margin)),
+      (None, Some(Direction::Up)) => {
+        let third = (3 * (cursor.x() - work_area.left) / work_area.width()).clamp(0, 2);
+        Some(geometry::thirds(work_area, third, 1, margin))
+      }
+      (None, Some(_)) => Some(Self::bottom_half_of_screen(work_area, margin)),
+      (None, None) => None,
     }
   }
 
+  /// Returns the [`Sizing`]s of all snap zones of `work_area` (see [`Self::for_snap_zone`]) in the order in which they
+  /// are numbered in place mode: the three thirds, the left, right and bottom half, and the four quarters.
+  pub fn snap_zones(work_area: Rect, margin: i32) -> Vec<Self> {
+    vec![
+      Self::left_third_of_screen(work_area, margin),
+      Self::center_third_of_screen(work_area, margin),
+      Self::right_third_of_screen(work_area, margin),
+      Self::left_half_of_screen(work_area, margin),
+      Self::right_half_of_screen(work_area, margin),
+      Self::bottom_half_of_screen(work_area, margin),
+      Self::quarter_of_screen(work_area, Direction::Left, Direction::Up, margin),
+      Self::quarter_of_screen(work_area, Direction::Right, Direction::Up, margin),
+      Self::quarter_of_screen(work_area, Direction::Left, Direction::Down, margin),
+      Self::quarter_of_screen(work_area, Direction::Right, Direction::Down, margin),
+    ]
+  }
+
   /// Returns a new [`Sizing`] that is half the size of the current one in the dimension corresponding to the given
   /// direction, keeping the edge on the arrow-key side fixed and contracting the opposite edge inward. A gap of
   /// `margin / 2` is subtracted from each side of the split point, resulting in a total gap of `margin` between the
   /// two halves (consistent with the half-screen margin system).
   pub fn halved(&self, direction: Direction, margin: i32) -> Self {
-    let half_margin = margin / 2;
-    match direction {
-      Direction::Left => Self {
-        x: self.x,
-        y: self.y,
-        width: self.width / 2 - half_margin,
-        height: self.height,
-      },
-      Direction::Right => Self {
-        x: self.x + self.width / 2 + half_margin,
-        y: self.y,
-        width: self.width / 2 - half_margin,
-        height: self.height,
-      },
-      Direction::Up => Self {
-        x: self.x,
-        y: self.y,
-        width: self.width,
-        height: self.height / 2 - half_margin,
-      },
-      Direction::Down => Self {
-        x: self.x,
-        y: self.y + self.height / 2 + half_margin,
-        width: self.width,
-        height: self.height / 2 - half_margin,
-      },
-    }
+    geometry::halve(self, direction, margin)
   }
 }
 
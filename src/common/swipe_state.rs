@@ -0,0 +1,78 @@
+use crate::common::Direction;
+
+const MINIMUM_FINGER_COUNT: u32 = 3;
+const MINIMUM_TRAVEL_AS_SHARE_OF_TOUCHPAD_WIDTH: f32 = 0.2;
+
+/// Represents the state of a multi-finger horizontal swipe on a precision touchpad. Not used for any keyboard or mouse
+/// operations.
+#[derive(Default)]
+pub struct SwipeState {
+  start_x: Option<i32>,
+  is_completed: bool,
+}
+
+impl SwipeState {
+  /// Updates the state with the latest touchpad report and returns the direction of the swipe once the fingers have
+  /// travelled far enough. A swipe is reported at most once until all but two fingers have been lifted again.
+  pub(crate) fn update(&mut self, contact_count: u32, x: i32, touchpad_width: i32) -> Option<Direction> {
+    if contact_count < MINIMUM_FINGER_COUNT {
+      self.reset();
+      return None;
+    }
+    if self.is_completed {
+      return None;
+    }
+    let start_x = *self.start_x.get_or_insert(x);
+    let travel = x - start_x;
+    if (travel.abs() as f32) < touchpad_width.max(1) as f32 * MINIMUM_TRAVEL_AS_SHARE_OF_TOUCHPAD_WIDTH {
+      return None;
+    }
+    self.is_completed = true;
+
+    Some(if travel < 0 { Direction::Left } else { Direction::Right })
+  }
+
+  /// Resets the swipe state. Should be called after the fingers have been lifted.
+  pub(crate) fn reset(&mut self) {
+    self.start_x = None;
+    self.is_completed = false;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn update_returns_direction_once_three_fingers_travelled_far_enough() {
+    let mut state = SwipeState::default();
+
+    let results = [500, 450, 350, 250].map(|x| state.update(3, x, 1_000));
+
+    assert!(matches!(results, [None, None, None, Some(Direction::Left)]));
+  }
+
+  #[test]
+  fn update_ignores_swipes_with_fewer_than_three_fingers() {
+    let mut state = SwipeState::default();
+
+    let results = [0, 500, 1_000].map(|x| state.update(2, x, 1_000));
+
+    assert!(results.iter().all(Option::is_none));
+  }
+
+  #[test]
+  fn update_reports_swipe_only_once_until_fingers_are_lifted() {
+    let mut state = SwipeState::default();
+    state.update(4, 0, 1_000);
+    state.update(4, 300, 1_000);
+
+    let before_lifting = state.update(4, 600, 1_000);
+    state.update(0, 600, 1_000);
+    state.update(3, 600, 1_000);
+    let after_lifting = state.update(3, 900, 1_000);
+
+    assert!(before_lifting.is_none());
+    assert!(matches!(after_lifting, Some(Direction::Right)));
+  }
+}
@@ -0,0 +1,125 @@
+use crate::common::{BspTree, Rect, Sizing, WindowHandle};
+
+const MARGIN: i32 = 20;
+
+fn work_area() -> Rect {
+  Rect::new(0, 0, 1920, 1030)
+}
+
+fn tree_with(handles: &[i32]) -> BspTree {
+  let mut tree = BspTree::default();
+  for handle in handles {
+    let target = tree.windows().last().copied();
+    tree.insert((*handle).into(), target, work_area(), MARGIN);
+  }
+
+  tree
+}
+
+fn region_of(tree: &BspTree, handle: i32) -> Option<Sizing> {
+  let handle = WindowHandle::from(handle);
+  tree
+    .layout(work_area(), MARGIN)
+    .into_iter()
+    .find(|(window, _)| *window == handle)
+    .map(|(_, sizing)| sizing)
+}
+
+#[test]
+fn insert_gives_first_window_the_near_maximised_work_area() {
+  let tree = tree_with(&[1]);
+
+  assert_eq!(
+    tree.layout(work_area(), MARGIN),
+    vec![(1.into(), Sizing::new(20, 20, 1880, 990))]
+  );
+}
+
+#[test]
+fn insert_splits_region_of_target_along_its_longer_side() {
+  let tree = tree_with(&[1, 2, 3]);
+
+  assert_eq!(region_of(&tree, 1), Some(Sizing::new(20, 20, 930, 990)));
+  assert_eq!(region_of(&tree, 2), Some(Sizing::new(970, 20, 930, 485)));
+  assert_eq!(region_of(&tree, 3), Some(Sizing::new(970, 525, 930, 485)));
+}
+
+#[test]
+fn insert_splits_region_of_last_window_if_target_is_not_in_tree() {
+  let mut tree = tree_with(&[1, 2]);
+
+  tree.insert(3.into(), Some(9.into()), work_area(), MARGIN);
+
+  assert_eq!(tree.windows(), vec![1.into(), 2.into(), 3.into()]);
+  assert_eq!(region_of(&tree, 1), Some(Sizing::new(20, 20, 930, 990)));
+  assert_eq!(region_of(&tree, 3), Some(Sizing::new(970, 525, 930, 485)));
+}
+
+#[test]
+fn remove_gives_region_of_window_to_its_sibling() {
+  let mut tree = tree_with(&[1, 2, 3]);
+
+  assert!(tree.remove(2.into()));
+
+  assert!(!tree.contains(2.into()));
+  assert_eq!(region_of(&tree, 1), Some(Sizing::new(20, 20, 930, 990)));
+  assert_eq!(region_of(&tree, 3), Some(Sizing::new(970, 20, 930, 990)));
+}
+
+#[test]
+fn remove_empties_tree_when_removing_last_window_and_ignores_unknown_windows() {
+  let mut tree = tree_with(&[1]);
+
+  assert!(!tree.remove(2.into()));
+  assert!(tree.remove(1.into()));
+
+  assert!(tree.windows().is_empty());
+  assert!(tree.layout(work_area(), MARGIN).is_empty());
+}
+
+#[test]
+fn rotate_turns_split_containing_window() {
+  let mut tree = tree_with(&[1, 2]);
+
+  assert!(tree.rotate(2.into()));
+
+  assert_eq!(region_of(&tree, 1), Some(Sizing::new(20, 20, 1880, 485)));
+  assert_eq!(region_of(&tree, 2), Some(Sizing::new(20, 525, 1880, 485)));
+}
+
+#[test]
+fn rotate_and_swap_do_nothing_without_split() {
+  let mut tree = tree_with(&[1]);
+
+  assert!(!tree.rotate(1.into()));
+  assert!(!tree.swap(1.into()));
+  assert!(!tree.swap(2.into()));
+}
+
+#[test]
+fn swap_trades_places_of_window_and_its_sibling() {
+  let mut tree = tree_with(&[1, 2, 3]);
+
+  assert!(tree.swap(3.into()));
+
+  assert_eq!(region_of(&tree, 1), Some(Sizing::new(20, 20, 930, 990)));
+  assert_eq!(region_of(&tree, 3), Some(Sizing::new(970, 20, 930, 485)));
+  assert_eq!(region_of(&tree, 2), Some(Sizing::new(970, 525, 930, 485)));
+}
+
+#[test]
+fn layout_leaves_margin_between_windows_without_overlapping() {
+  let tree = tree_with(&[1, 2, 3, 4, 5]);
+  let regions = tree.layout(work_area(), MARGIN);
+
+  assert_eq!(regions.len(), 5);
+  for (i, (_, first)) in regions.iter().enumerate() {
+    for (_, second) in regions.iter().skip(i + 1) {
+      let is_apart = first.x + first.width + MARGIN <= second.x
+        || second.x + second.width + MARGIN <= first.x
+        || first.y + first.height + MARGIN <= second.y
+        || second.y + second.height + MARGIN <= first.y;
+      assert!(is_apart, "{first:?} and {second:?} are closer than the margin");
+    }
+  }
+}
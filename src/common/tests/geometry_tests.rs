@@ -0,0 +1,513 @@
+use crate::common::{Direction, Rect, ResizeMode, Sizing, geometry};
+
+const DIRECTIONS: [Direction; 4] = [Direction::Left, Direction::Right, Direction::Up, Direction::Down];
+const CASES: usize = 1_000;
+
+/// A minimal, deterministic xorshift generator so that property tests can cover many inputs while remaining
+/// reproducible without an additional dependency.
+struct Generator(u64);
+
+impl Generator {
+  fn new() -> Self {
+    Self(0x2545_F491_4F6C_DD1D)
+  }
+
+  fn next_in(&mut self, min: i32, max: i32) -> i32 {
+    self.0 ^= self.0 << 13;
+    self.0 ^= self.0 >> 7;
+    self.0 ^= self.0 << 17;
+    min + (self.0 % (max - min + 1) as u64) as i32
+  }
+
+  fn work_area(&mut self) -> Rect {
+    let left = self.next_in(-4_000, 4_000);
+    let top = self.next_in(-2_000, 2_000);
+    Rect::new(left, top, left + self.next_in(400, 7_680), top + self.next_in(300, 4_320))
+  }
+
+  fn margin(&mut self) -> i32 {
+    self.next_in(0, 50)
+  }
+}
+
+fn is_inside(sizing: &Sizing, work_area: Rect, margin: i32) -> bool {
+  let rect = Rect::from(sizing.clone());
+  rect.left >= work_area.left + margin
+    && rect.top >= work_area.top + margin
+    && rect.right <= work_area.right - margin
+    && rect.bottom <= work_area.bottom - margin
+    && rect.width() > 0
+    && rect.height() > 0
+}
+
+#[test]
+fn near_maximised_is_always_inside_work_area_and_respects_margin() {
+  let mut generator = Generator::new();
+
+  for _ in 0..CASES {
+    let (work_area, margin) = (generator.work_area(), generator.margin());
+
+    let sizing = geometry::near_maximised(work_area, margin);
+
+    assert!(is_inside(&sizing, work_area, margin), "{sizing:?} in {work_area}");
+    assert_eq!(Rect::from(sizing).right, work_area.right - margin);
+  }
+}
+
+#[test]
+fn halves_three_quarters_and_centre_halves_are_always_inside_work_area() {
+  let mut generator = Generator::new();
+
+  for _ in 0..CASES {
+    let (work_area, margin) = (generator.work_area(), generator.margin());
+
+    for direction in DIRECTIONS {
+      let half = geometry::half(work_area, direction, margin);
+      let three_quarters = geometry::three_quarters(work_area, direction, margin);
+      let centre_half = geometry::centre_half(work_area, direction, margin);
+
+      assert!(is_inside(&half, work_area, margin), "{half:?} in {work_area}");
+      assert!(
+        is_inside(&three_quarters, work_area, margin),
+        "{three_quarters:?} in {work_area}"
+      );
+      assert!(is_inside(&centre_half, work_area, margin), "{centre_half:?} in {work_area}");
+    }
+  }
+}
+
+#[test]
+fn opposite_halves_never_overlap_and_keep_margin_between_them() {
+  let mut generator = Generator::new();
+
+  for _ in 0..CASES {
+    let (work_area, margin) = (generator.work_area(), generator.margin());
+    let expected_gap = margin / 2 * 2;
+
+    let left = Rect::from(geometry::half(work_area, Direction::Left, margin));
+    let right = Rect::from(geometry::half(work_area, Direction::Right, margin));
+    let top = Rect::from(geometry::half(work_area, Direction::Up, margin));
+    let bottom = Rect::from(geometry::half(work_area, Direction::Down, margin));
+
+    assert_eq!(right.left - left.right, expected_gap, "{left} and {right}");
+    assert_eq!(bottom.top - top.bottom, expected_gap, "{top} and {bottom}");
+  }
+}
+
+#[test]
+fn halve_always_stays_inside_original_sizing() {
+  let mut generator = Generator::new();
+
+  for _ in 0..CASES {
+    let (work_area, margin) = (generator.work_area(), generator.margin());
+    let original = geometry::near_maximised(work_area, margin);
+    let original_rect = Rect::from(original.clone());
+
+    for direction in DIRECTIONS {
+      let halved = geometry::halve(&original, direction, margin);
+
+      assert!(is_inside(&halved, original_rect, 0), "{halved:?} in {original_rect}");
+    }
+  }
+}
+
+#[test]
+fn quarter_dimensions_are_never_larger_than_half_dimensions() {
+  let mut generator = Generator::new();
+
+  for _ in 0..CASES {
+    let (work_area, margin) = (generator.work_area(), generator.margin());
+
+    let (width, height) = geometry::quarter_dimensions(work_area, margin);
+
+    assert!(width > 0 && width <= geometry::half(work_area, Direction::Left, margin).width);
+    assert!(height > 0 && height <= geometry::half(work_area, Direction::Up, margin).height);
+  }
+}
+
+#[test]
+fn clamp_never_moves_any_edge_outside_bounds_minus_margin() {
+  let mut generator = Generator::new();
+
+  for _ in 0..CASES {
+    let (bounds, margin) = (generator.work_area(), generator.margin());
+    let left = generator.next_in(-10_000, 10_000);
+    let top = generator.next_in(-10_000, 10_000);
+    let rect = Rect::new(
+      left,
+      top,
+      left + generator.next_in(1, 10_000),
+      top + generator.next_in(1, 10_000),
+    );
+
+    let clamped = geometry::clamp(&rect, &bounds, margin);
+
+    assert!(clamped.left >= bounds.left + margin && clamped.top >= bounds.top + margin);
+    assert!(clamped.right <= bounds.right - margin && clamped.bottom <= bounds.bottom - margin);
+  }
+}
+
+#[test]
+fn clamp_does_not_change_rect_that_is_already_inside_bounds() {
+  let mut generator = Generator::new();
+
+  for _ in 0..CASES {
+    let (bounds, margin) = (generator.work_area(), generator.margin());
+    let rect = Rect::from(geometry::half(bounds, Direction::Left, margin));
+
+    assert_eq!(geometry::clamp(&rect, &bounds, margin), rect);
+  }
+}
+
+#[test]
+fn interpolate_returns_start_and_target_at_either_end_and_stays_between_them() {
+  let mut generator = Generator::new();
+
+  for _ in 0..CASES {
+    let start = generator.work_area();
+    let target = generator.work_area();
+    let progress = f64::from(generator.next_in(0, 100)) / 100.0;
+
+    let interpolated = geometry::interpolate(start, target, progress);
+
+    assert_eq!(geometry::interpolate(start, target, 0.0), start);
+    assert_eq!(geometry::interpolate(start, target, 1.0), target);
+    assert!(interpolated.left >= start.left.min(target.left) && interpolated.left <= start.left.max(target.left));
+    assert!(
+      interpolated.bottom >= start.bottom.min(target.bottom) && interpolated.bottom <= start.bottom.max(target.bottom)
+    );
+  }
+}
+
+#[test]
+fn scale_for_dpi_keeps_top_left_corner_and_round_trips_within_one_pixel() {
+  let mut generator = Generator::new();
+  let dpis = [96, 120, 144, 168, 192];
+
+  for _ in 0..CASES {
+    let rect = generator.work_area();
+    let source_dpi = dpis[generator.next_in(0, 4) as usize];
+    let target_dpi = dpis[generator.next_in(0, 4) as usize];
+
+    let scaled = geometry::scale_for_dpi(rect, source_dpi, target_dpi);
+    let round_tripped = geometry::scale_for_dpi(scaled, target_dpi, source_dpi);
+
+    assert_eq!((scaled.left, scaled.top), (rect.left, rect.top));
+    assert!(
+      (round_tripped.width() - rect.width()).abs() <= 1,
+      "{rect} became {round_tripped}"
+    );
+    assert!(
+      (round_tripped.height() - rect.height()).abs() <= 1,
+      "{rect} became {round_tripped}"
+    );
+  }
+}
+
+#[test]
+fn scale_for_dpi_doubles_size_when_moving_from_100_to_200_percent() {
+  let rect = Rect::new(10, 20, 110, 70);
+
+  let scaled = geometry::scale_for_dpi(rect, geometry::DEFAULT_DPI, geometry::DEFAULT_DPI * 2);
+
+  assert_eq!(scaled, Rect::new(10, 20, 210, 120));
+}
+
+#[test]
+fn scale_for_dpi_returns_rect_unchanged_if_dpi_is_zero() {
+  let rect = Rect::new(10, 20, 110, 70);
+
+  assert_eq!(geometry::scale_for_dpi(rect, 0, geometry::DEFAULT_DPI), rect);
+}
+
+#[test]
+fn is_within_tolerance_accepts_exact_match_and_rejects_larger_deviation() {
+  let sizing = Sizing::new(10, 10, 100, 100);
+
+  assert!(geometry::is_within_tolerance(&Rect::new(10, 10, 110, 110), &sizing, 0));
+  assert!(geometry::is_within_tolerance(&Rect::new(12, 10, 112, 110), &sizing, 2));
+  assert!(!geometry::is_within_tolerance(&Rect::new(13, 10, 113, 110), &sizing, 2));
+}
+
+#[test]
+fn fit_into_and_centre_within_always_return_rect_inside_bounds_that_is_no_larger_than_original() {
+  let mut generator = Generator::new();
+
+  for _ in 0..CASES {
+    let bounds = generator.work_area();
+    let rect = generator.work_area();
+
+    for fitted in [geometry::fit_into(&rect, &bounds), geometry::centre_within(&rect, &bounds)] {
+      assert!(fitted.left >= bounds.left && fitted.top >= bounds.top, "{fitted} in {bounds}");
+      assert!(
+        fitted.right <= bounds.right && fitted.bottom <= bounds.bottom,
+        "{fitted} in {bounds}"
+      );
+      assert!(
+        fitted.width() <= rect.width() && fitted.height() <= rect.height(),
+        "{fitted} from {rect}"
+      );
+    }
+  }
+}
+
+#[test]
+fn scale_to_fit_keeps_aspect_ratio_and_never_enlarges() {
+  let bounds = Rect::new(100, 100, 500, 300);
+
+  assert_eq!(geometry::scale_to_fit(1600, 900, &bounds), Rect::new(122, 100, 478, 300));
+  assert_eq!(geometry::scale_to_fit(800, 1600, &bounds), Rect::new(250, 100, 350, 300));
+  assert_eq!(geometry::scale_to_fit(200, 100, &bounds), Rect::new(200, 150, 400, 250));
+  assert_eq!(geometry::scale_to_fit(0, 100, &bounds), Rect::new(300, 150, 300, 250));
+}
+
+#[test]
+fn is_visible_within_requires_minimum_overlap_with_any_bounds() {
+  let bounds = [Rect::new(0, 0, 1000, 800), Rect::new(1000, 0, 2000, 800)];
+
+  assert!(geometry::is_visible_within(&Rect::new(100, 100, 500, 400), &bounds, 50));
+  assert!(geometry::is_visible_within(&Rect::new(-300, 100, 60, 400), &bounds, 50));
+  assert!(geometry::is_visible_within(&Rect::new(1500, 790, 1520, 1000), &bounds, 50));
+  assert!(!geometry::is_visible_within(&Rect::new(-300, 100, 40, 400), &bounds, 50));
+  assert!(!geometry::is_visible_within(&Rect::new(2100, 100, 2500, 400), &bounds, 50));
+  assert!(!geometry::is_visible_within(&Rect::new(100, 100, 500, 400), &[], 50));
+}
+
+#[test]
+fn nearest_returns_bounds_containing_centre_or_closest_to_it() {
+  let left = Rect::new(0, 0, 1000, 800);
+  let right = Rect::new(1000, 200, 2000, 1000);
+  let bounds = [left, right];
+
+  assert_eq!(geometry::nearest(&Rect::new(1200, 300, 1600, 600), &bounds), Some(&right));
+  assert_eq!(geometry::nearest(&Rect::new(-900, 0, -500, 400), &bounds), Some(&left));
+  assert_eq!(geometry::nearest(&Rect::new(1800, 1200, 2400, 1600), &bounds), Some(&right));
+  assert_eq!(geometry::nearest(&Rect::new(0, 0, 100, 100), &[]), None);
+}
+
+#[test]
+fn fit_into_does_not_change_rect_that_is_already_inside_bounds() {
+  let mut generator = Generator::new();
+
+  for _ in 0..CASES {
+    let (bounds, margin) = (generator.work_area(), generator.margin());
+    let rect = Rect::from(geometry::half(bounds, Direction::Right, margin));
+
+    assert_eq!(geometry::fit_into(&rect, &bounds), rect);
+  }
+}
+
+#[test]
+fn in_corner_is_always_inside_work_area_and_touches_both_edges_of_the_corner() {
+  let mut generator = Generator::new();
+
+  for _ in 0..CASES {
+    let (work_area, margin) = (generator.work_area(), generator.margin());
+    let (width, height) = (generator.next_in(1, 10_000), generator.next_in(1, 10_000));
+
+    for (horizontal, vertical) in [
+      (Direction::Left, Direction::Up),
+      (Direction::Right, Direction::Up),
+      (Direction::Left, Direction::Down),
+      (Direction::Right, Direction::Down),
+    ] {
+      let rect = geometry::in_corner(work_area, width, height, horizontal, vertical, margin);
+
+      assert!(is_inside(&Sizing::from(rect), work_area, margin), "{rect} in {work_area}");
+      assert!(
+        rect.width() <= width && rect.height() <= height,
+        "{rect} for {width}x{height}"
+      );
+      let expected_x = if matches!(horizontal, Direction::Left) {
+        work_area.left + margin
+      } else {
+        work_area.right - margin
+      };
+      assert!(rect.left == expected_x || rect.right == expected_x, "{rect} in {work_area}");
+      let expected_y = if matches!(vertical, Direction::Up) {
+        work_area.top + margin
+      } else {
+        work_area.bottom - margin
+      };
+      assert!(rect.top == expected_y || rect.bottom == expected_y, "{rect} in {work_area}");
+    }
+  }
+}
+
+#[test]
+fn snap_to_grid_returns_nearest_multiple_of_cell_size() {
+  let mut generator = Generator::new();
+
+  for _ in 0..CASES {
+    let (coordinate, cell_size) = (generator.next_in(-8_000, 8_000), generator.next_in(1, 200));
+    let snapped = geometry::snap_to_grid(coordinate, cell_size);
+
+    assert_eq!(
+      snapped % cell_size,
+      0,
+      "{coordinate} snapped to {snapped} with cell size {cell_size}"
+    );
+    assert!(
+      (snapped - coordinate).abs() * 2 <= cell_size,
+      "{coordinate} snapped to {snapped} with cell size {cell_size}"
+    );
+  }
+}
+
+#[test]
+fn snap_to_grid_returns_coordinate_unchanged_if_grid_is_disabled() {
+  assert_eq!(geometry::snap_to_grid(123, 0), 123);
+  assert_eq!(geometry::snap_to_grid(-123, -10), -123);
+}
+
+#[test]
+fn lock_to_dominant_axis_keeps_only_the_larger_component() {
+  assert_eq!(geometry::lock_to_dominant_axis(40, -12), (40, 0));
+  assert_eq!(geometry::lock_to_dominant_axis(-5, -30), (0, -30));
+  assert_eq!(geometry::lock_to_dominant_axis(7, 7), (7, 0));
+  assert_eq!(geometry::lock_to_dominant_axis(0, 0), (0, 0));
+}
+
+#[test]
+fn snap_resized_edges_to_grid_keeps_fixed_edges_in_place() {
+  let mut generator = Generator::new();
+  let cell_size = 25;
+
+  for _ in 0..CASES {
+    let rect = generator.work_area();
+
+    let snapped = geometry::snap_resized_edges_to_grid(rect, ResizeMode::BottomRight, cell_size);
+    assert_eq!((snapped.left, snapped.top), (rect.left, rect.top), "{snapped} from {rect}");
+    assert_eq!((snapped.right % cell_size, snapped.bottom % cell_size), (0, 0), "{snapped}");
+
+    let snapped = geometry::snap_resized_edges_to_grid(rect, ResizeMode::TopLeft, cell_size);
+    assert_eq!(
+      (snapped.right, snapped.bottom),
+      (rect.right, rect.bottom),
+      "{snapped} from {rect}"
+    );
+    assert_eq!((snapped.left % cell_size, snapped.top % cell_size), (0, 0), "{snapped}");
+  }
+}
+
+#[test]
+fn attract_to_edges_only_moves_rect_by_up_to_distance_and_keeps_its_size() {
+  let mut generator = Generator::new();
+
+  for _ in 0..CASES {
+    let (work_area, margin) = (generator.work_area(), generator.margin());
+    let (rect, window) = (generator.work_area(), generator.work_area());
+    let distance = generator.next_in(0, 50);
+
+    let attracted = geometry::attract_to_edges(rect, &[work_area], &[window], margin, distance);
+
+    assert!((attracted.left - rect.left).abs() <= distance, "{attracted} from {rect}");
+    assert!((attracted.top - rect.top).abs() <= distance, "{attracted} from {rect}");
+    assert_eq!((attracted.width(), attracted.height()), (rect.width(), rect.height()));
+  }
+}
+
+#[test]
+fn attract_to_edges_lines_up_with_work_area_and_neighbouring_window_using_margin() {
+  let work_area = Rect::new(0, 0, 1920, 1080);
+  let neighbour = Rect::new(20, 20, 950, 1060);
+
+  let attracted = geometry::attract_to_edges(Rect::new(975, 32, 1475, 532), &[work_area], &[neighbour], 20, 15);
+
+  assert_eq!(attracted, Rect::new(970, 20, 1470, 520));
+}
+
+#[test]
+fn attract_to_edges_ignores_windows_that_do_not_overlap_along_the_edge() {
+  let work_area = Rect::new(0, 0, 1920, 1080);
+  let window_above = Rect::new(20, 20, 950, 300);
+
+  let attracted = geometry::attract_to_edges(Rect::new(975, 500, 1475, 800), &[work_area], &[window_above], 20, 15);
+
+  assert_eq!(attracted, Rect::new(975, 500, 1475, 800));
+}
+
+#[test]
+fn opposite_halves_are_adjacent_and_stay_adjacent_after_moving_their_shared_edge() {
+  let mut generator = Generator::new();
+
+  for _ in 0..CASES {
+    let (work_area, margin) = (generator.work_area(), generator.margin());
+    let offset = generator.next_in(-100, 100);
+
+    for (side, first, second) in [
+      (Direction::Right, Direction::Left, Direction::Right),
+      (Direction::Down, Direction::Up, Direction::Down),
+    ] {
+      let rect = Rect::from(geometry::half(work_area, first, margin));
+      let other = Rect::from(geometry::half(work_area, second, margin));
+      assert!(geometry::is_adjacent(&rect, &other, side, margin, 1), "{rect} and {other}");
+      assert!(
+        !geometry::is_adjacent(&rect, &other, side.opposite(), margin, 1),
+        "{rect} and {other}"
+      );
+
+      let moved_rect = geometry::move_edge(rect, side, offset);
+      let moved_other = geometry::move_edge(other, side.opposite(), offset);
+
+      assert!(
+        geometry::is_adjacent(&moved_rect, &moved_other, side, margin, 1),
+        "{moved_rect} and {moved_other}"
+      );
+    }
+  }
+}
+
+#[test]
+fn overlap_in_percent_is_symmetric_and_only_reaches_100_for_identical_rects() {
+  let mut generator = Generator::new();
+  for _ in 0..CASES {
+    let work_area = generator.work_area();
+    let margin = generator.margin();
+    let half = Rect::from(geometry::half(work_area, Direction::Left, margin));
+    let quarter = Rect::from(geometry::quarter(work_area, Direction::Left, Direction::Up, margin));
+    let opposite_half = Rect::from(geometry::half(work_area, Direction::Right, margin));
+
+    assert_eq!(geometry::overlap_in_percent(&half, &half), 100);
+    assert_eq!(
+      geometry::overlap_in_percent(&half, &quarter),
+      geometry::overlap_in_percent(&quarter, &half)
+    );
+    assert!((1..100).contains(&geometry::overlap_in_percent(&half, &quarter)));
+    assert_eq!(geometry::overlap_in_percent(&half, &opposite_half), 0);
+  }
+}
+
+#[test]
+fn master_stack_windows_are_inside_work_area_and_never_overlap() {
+  let mut generator = Generator::new();
+
+  for _ in 0..CASES {
+    let (work_area, margin) = (generator.work_area(), generator.margin());
+    let count = generator.next_in(1, 5) as usize;
+    let master_width_in_percent = generator.next_in(10, 90);
+
+    let sizings = geometry::master_stack(work_area, count, master_width_in_percent, margin);
+
+    assert_eq!(sizings.len(), count);
+    for (index, sizing) in sizings.iter().enumerate() {
+      assert!(is_inside(sizing, work_area, margin), "{sizing:?} in {work_area}");
+      for other in &sizings[index + 1..] {
+        assert!(
+          !Rect::from(sizing.clone()).intersects(&Rect::from(other.clone())),
+          "{sizing:?} and {other:?}"
+        );
+      }
+    }
+  }
+}
+
+#[test]
+fn master_stack_near_maximises_single_window() {
+  let work_area = Rect::new(0, 0, 1920, 1080);
+
+  assert_eq!(
+    geometry::master_stack(work_area, 1, 60, 20),
+    vec![geometry::near_maximised(work_area, 20)]
+  );
+}
@@ -0,0 +1,35 @@
+use crate::common::{HookActivity, HookHealth};
+
+#[test]
+fn hook_activity_is_not_stale_before_any_callback_was_recorded() {
+  let activity = HookActivity::new();
+
+  assert!(!activity.is_stale(10 * 60 * 1000));
+}
+
+#[test]
+fn hook_activity_is_stale_only_once_input_was_received_long_after_the_last_callback() {
+  let activity = HookActivity::new();
+  activity.record(1_000);
+
+  assert!(!activity.is_stale(500));
+  assert!(!activity.is_stale(1_000 + 60 * 1000));
+  assert!(activity.is_stale(1_000 + 10 * 60 * 1000));
+}
+
+#[test]
+fn hook_activity_handles_tick_count_wrapping_around() {
+  let activity = HookActivity::new();
+  activity.record(u32::MAX - 1_000);
+
+  assert!(!activity.is_stale(1_000));
+  assert!(activity.is_stale(10 * 60 * 1000));
+}
+
+#[test]
+fn stale_if_only_replaces_registered_health() {
+  assert_eq!(HookHealth::new(true, true).stale_if(true), HookHealth::Stale);
+  assert_eq!(HookHealth::new(true, true).stale_if(false), HookHealth::Registered);
+  assert_eq!(HookHealth::new(true, false).stale_if(true), HookHealth::NotRegistered);
+  assert_eq!(HookHealth::new(false, true).stale_if(true), HookHealth::Disabled);
+}
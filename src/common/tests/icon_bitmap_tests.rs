@@ -0,0 +1,67 @@
+use crate::common::IconBitmap;
+
+const BASE_ICON: &[u8] = include_bytes!("../../../assets/randolf.ico");
+
+fn base_icon() -> IconBitmap {
+  IconBitmap::from_ico(BASE_ICON).expect("Failed to read bundled icon")
+}
+
+#[test]
+fn from_ico_reads_bundled_icon_and_to_ico_writes_it_in_same_format() {
+  let icon = base_icon();
+
+  let ico = icon.to_ico();
+
+  assert_eq!(icon.size(), 32);
+  assert_eq!(ico.len(), BASE_ICON.len());
+  assert_eq!(IconBitmap::from_ico(&ico), Some(icon));
+}
+
+#[test]
+fn from_ico_returns_none_for_unsupported_data() {
+  assert!(IconBitmap::from_ico(&[0; 10]).is_none());
+  assert!(IconBitmap::from_ico(&BASE_ICON[..100]).is_none());
+}
+
+#[test]
+fn scaled_keeps_outline_of_icon() {
+  let icon = base_icon();
+
+  for size in [16, 20, 24, 40, 48] {
+    let scaled = icon.scaled(size);
+
+    assert_eq!(scaled.size(), size);
+    assert!(
+      scaled.pixel(0, 0).is_none(),
+      "Top-left corner is not transparent at size [{size}]"
+    );
+    assert!(
+      scaled.pixel(size / 2, size / 2).is_some(),
+      "Centre is transparent at size [{size}]"
+    );
+  }
+}
+
+#[test]
+fn scaled_icon_with_padded_rows_survives_writing_and_reading_ico() {
+  let scaled = base_icon().scaled(18);
+
+  assert_eq!(IconBitmap::from_ico(&scaled.to_ico()), Some(scaled));
+}
+
+#[test]
+fn with_high_contrast_only_uses_given_colours_and_keeps_transparency() {
+  let (light, dark) = ([255, 255, 255], [0, 0, 0]);
+  let icon = base_icon();
+
+  let high_contrast = icon.with_high_contrast(light, dark);
+
+  for y in 0..icon.size() {
+    for x in 0..icon.size() {
+      let pixel = high_contrast.pixel(x, y);
+      assert_eq!(pixel.is_none(), icon.pixel(x, y).is_none());
+      assert!(pixel.is_none_or(|pixel| pixel == light || pixel == dark));
+    }
+  }
+  assert!(high_contrast.pixel(16, 16).is_some());
+}
@@ -1,3 +1,7 @@
+mod bsp_tree_tests;
+mod geometry_tests;
+mod hook_health_tests;
+mod icon_bitmap_tests;
 mod placement_tests;
 mod scrolling_strips_tests;
 mod sizing_tests;
@@ -9,7 +9,7 @@ fn is_of_expected_size(
   sizing: &Sizing,
   margin: i32,
 ) -> bool {
-  placement_manager.is_of_expected_size(&MockWindowsApi, handle, placement, sizing, margin)
+  placement_manager.is_of_expected_size(&MockWindowsApi, handle, placement, sizing, margin, 0)
 }
 
 fn near_maximise_window(placement: &Placement, handle: WindowHandle, monitor_info: MonitorInfo, margin: i32) {
@@ -28,6 +28,17 @@ fn is_of_expected_size_test() {
   assert!(!is_of_expected_size(&placement_manager, handle, &placement, &sizing, 20));
 }
 
+#[test]
+fn is_of_expected_size_allows_deviation_within_size_tolerance() {
+  let handle = WindowHandle::new(1);
+  let sizing = Sizing::new(0, 0, 100, 100);
+  let placement = WindowPlacement::new_from_sizing(Sizing::new(0, 0, 93, 96));
+  let placement_manager = Placement::default();
+
+  assert!(placement_manager.is_of_expected_size(&MockWindowsApi, handle, &placement, &sizing, 20, 8));
+  assert!(!placement_manager.is_of_expected_size(&MockWindowsApi, handle, &placement, &sizing, 20, 5));
+}
+
 #[test]
 fn near_maximise_window_when_window_is_not_near_maximised() {
   let monitor_handle = MonitorHandle::from(1);
@@ -127,6 +138,35 @@ fn near_maximise_window_with_margin_above_threshold_resizes() {
   assert_ne!(actual_placement.unwrap(), initial_placement);
 }
 
+#[test]
+fn near_maximise_window_without_animation_resizes_without_maximising_first() {
+  let monitor_handle = MonitorHandle::from(1);
+  let window_handle = WindowHandle::new(1);
+  MockWindowsApi::add_or_update_window(
+    window_handle,
+    "Test Window".to_string(),
+    Sizing::new(50, 50, 100, 100),
+    false,
+    false,
+    false,
+  );
+  MockWindowsApi::add_monitor(monitor_handle, Rect::new(0, 0, 200, 200), true);
+  MockWindowsApi::place_window(window_handle, monitor_handle);
+  let placement = Placement {
+    skip_near_maximise_animation: true,
+    ..Placement::default()
+  };
+  let monitor_info = MockWindowsApi
+    .get_monitor_info_for_monitor(monitor_handle)
+    .expect("Failed to get monitor info");
+
+  near_maximise_window(&placement, window_handle, monitor_info, 10);
+
+  let expected_placement = WindowPlacement::new_from_sizing(Sizing::near_maximised(monitor_info.work_area, 10));
+  assert_eq!(MockWindowsApi.get_window_placement(window_handle), Some(expected_placement));
+  assert_eq!(MockWindowsApi.get_foreground_window(), None);
+}
+
 #[test]
 fn near_maximise_or_restore_with_zero_margin_can_restore_initial_position() {
   let monitor_handle = MonitorHandle::from(1);
@@ -249,3 +289,339 @@ fn is_of_expected_size_returns_false_when_difference_is_outside_dwm_tolerance()
     0
   ));
 }
+
+#[test]
+fn restore_previous_moves_placement_inside_work_area_after_resolution_decreased() {
+  let monitor_handle = MonitorHandle::from(1);
+  let window_handle = WindowHandle::new(1);
+  MockWindowsApi::add_or_update_window(
+    window_handle,
+    "Test Window".to_string(),
+    Sizing::new(0, 0, 100, 100),
+    false,
+    false,
+    true,
+  );
+  MockWindowsApi::add_monitor(monitor_handle, Rect::new(0, 0, 200, 200), true);
+  MockWindowsApi::place_window(window_handle, monitor_handle);
+  let mut placement = Placement::default();
+  placement.known_windows.insert(
+    format!("{:?}", window_handle.hwnd),
+    WindowPlacement::new_from_rect(Rect::new(120, 100, 240, 200)),
+  );
+
+  placement.restore_previous(&MockWindowsApi, window_handle);
+
+  let actual_placement = MockWindowsApi
+    .get_window_placement(window_handle)
+    .expect("Failed to get placement");
+  assert_eq!(actual_placement.normal_position, Rect::new(80, 80, 200, 180));
+}
+
+#[test]
+fn restore_previous_shrinks_placement_that_is_larger_than_work_area_after_resolution_decreased() {
+  let monitor_handle = MonitorHandle::from(1);
+  let window_handle = WindowHandle::new(1);
+  MockWindowsApi::add_or_update_window(
+    window_handle,
+    "Test Window".to_string(),
+    Sizing::new(0, 0, 100, 100),
+    false,
+    false,
+    true,
+  );
+  MockWindowsApi::add_monitor(monitor_handle, Rect::new(0, 0, 200, 200), true);
+  MockWindowsApi::place_window(window_handle, monitor_handle);
+  let mut placement = Placement::default();
+  placement.known_windows.insert(
+    format!("{:?}", window_handle.hwnd),
+    WindowPlacement::new_from_rect(Rect::new(-10, -10, 390, 290)),
+  );
+
+  placement.restore_previous(&MockWindowsApi, window_handle);
+
+  let actual_placement = MockWindowsApi
+    .get_window_placement(window_handle)
+    .expect("Failed to get placement");
+  assert_eq!(actual_placement.normal_position, Rect::new(0, 0, 200, 180));
+}
+
+#[test]
+fn restore_previous_re_homes_placement_to_current_monitor_after_its_monitor_was_removed() {
+  let monitor_handle = MonitorHandle::from(1);
+  let window_handle = WindowHandle::new(1);
+  MockWindowsApi::add_or_update_window(
+    window_handle,
+    "Test Window".to_string(),
+    Sizing::new(0, 0, 100, 100),
+    false,
+    false,
+    true,
+  );
+  MockWindowsApi::add_monitor(monitor_handle, Rect::new(0, 0, 200, 200), true);
+  MockWindowsApi::place_window(window_handle, monitor_handle);
+  let mut placement = Placement::default();
+  placement.known_windows.insert(
+    format!("{:?}", window_handle.hwnd),
+    WindowPlacement::new_from_rect(Rect::new(1920, 0, 2000, 60)),
+  );
+
+  placement.restore_previous(&MockWindowsApi, window_handle);
+
+  let actual_placement = MockWindowsApi
+    .get_window_placement(window_handle)
+    .expect("Failed to get placement");
+  assert_eq!(actual_placement.normal_position, Rect::new(60, 60, 140, 120));
+}
+
+#[test]
+fn remember_keeps_limited_history_of_distinct_placements() {
+  let window_handle = WindowHandle::new(1);
+  let mut placement = Placement::default();
+
+  for i in 0..7 {
+    placement.remember(
+      window_handle,
+      WindowPlacement::new_from_rect(Rect::new(i * 10, 0, i * 10 + 50, 50)),
+    );
+  }
+  placement.remember(window_handle, WindowPlacement::new_from_rect(Rect::new(0, 0, 50, 50)));
+
+  let history = placement
+    .placement_history
+    .get(&window_handle)
+    .expect("Expected placement history");
+  let lefts = history
+    .iter()
+    .map(|placement| placement.normal_position.left)
+    .collect::<Vec<_>>();
+  assert_eq!(lefts, vec![0, 60, 50, 40, 30]);
+}
+
+#[test]
+fn forget_removes_previous_placement_and_history() {
+  let window_handle = WindowHandle::new(1);
+  let mut placement = Placement::default();
+  placement.remember(window_handle, WindowPlacement::new_from_rect(Rect::new(0, 0, 50, 50)));
+  placement.remember(window_handle, WindowPlacement::new_from_rect(Rect::new(10, 0, 60, 50)));
+
+  placement.forget(window_handle);
+
+  assert!(!placement.remembers(window_handle));
+  assert!(!placement.placement_history.contains_key(&window_handle));
+}
+
+#[test]
+fn remove_dead_windows_forgets_placements_of_closed_windows_only() {
+  let closed_window = WindowHandle::new(1);
+  let open_window = WindowHandle::new(2);
+  for handle in [closed_window, open_window] {
+    MockWindowsApi::add_or_update_window(
+      handle,
+      "Test Window".to_string(),
+      Sizing::new(0, 0, 50, 50),
+      false,
+      false,
+      true,
+    );
+  }
+  let mut placement = Placement::default();
+  placement.remember(closed_window, WindowPlacement::new_from_rect(Rect::new(0, 0, 50, 50)));
+  placement.remember(open_window, WindowPlacement::new_from_rect(Rect::new(0, 0, 50, 50)));
+  MockWindowsApi.do_close_window(closed_window);
+
+  placement.remove_dead_windows(&MockWindowsApi);
+
+  assert!(!placement.remembers(closed_window));
+  assert!(!placement.placement_history.contains_key(&closed_window));
+  assert!(placement.remembers(open_window));
+  assert!(placement.placement_history.contains_key(&open_window));
+}
+
+#[test]
+fn restore_older_cycles_through_remembered_placements() {
+  let monitor_handle = MonitorHandle::from(1);
+  let window_handle = WindowHandle::new(1);
+  MockWindowsApi::add_or_update_window(
+    window_handle,
+    "Test Window".to_string(),
+    Sizing::new(20, 20, 160, 140),
+    false,
+    false,
+    true,
+  );
+  MockWindowsApi::add_monitor(monitor_handle, Rect::new(0, 0, 200, 200), true);
+  MockWindowsApi::place_window(window_handle, monitor_handle);
+  let mut placement = Placement::default();
+  let original_placement = WindowPlacement::new_from_rect(Rect::new(10, 10, 60, 60));
+  let later_placement = WindowPlacement::new_from_rect(Rect::new(100, 100, 150, 150));
+  placement.remember(window_handle, original_placement.clone());
+  placement.remember(window_handle, later_placement.clone());
+  let restore_older = |placement: &Placement| {
+    let current_placement = MockWindowsApi
+      .get_window_placement(window_handle)
+      .expect("Failed to get placement");
+    placement.restore_older(&MockWindowsApi, window_handle, &current_placement);
+    MockWindowsApi
+      .get_window_placement(window_handle)
+      .expect("Failed to get placement")
+      .normal_position
+  };
+
+  assert_eq!(restore_older(&placement), later_placement.normal_position);
+  assert_eq!(restore_older(&placement), original_placement.normal_position);
+  assert_eq!(restore_older(&placement), later_placement.normal_position);
+}
+
+#[test]
+fn pin_and_unpin_picture_in_picture_restores_previous_placement_and_always_on_top_state() {
+  let monitor_handle = MonitorHandle::from(1);
+  let window_handle = WindowHandle::new(1);
+  let initial_sizing = Sizing::new(50, 50, 100, 100);
+  let initial_placement = WindowPlacement::new_from_sizing(initial_sizing.clone());
+  MockWindowsApi::add_or_update_window(window_handle, "Test Window".to_string(), initial_sizing, false, false, true);
+  MockWindowsApi::add_monitor(monitor_handle, Rect::new(0, 0, 200, 200), true);
+  MockWindowsApi::place_window(window_handle, monitor_handle);
+  let mut placement = Placement::default();
+  let pinned_rect = Rect::new(140, 160, 180, 180);
+
+  placement.pin_picture_in_picture(&MockWindowsApi, window_handle, initial_placement.clone(), pinned_rect);
+
+  let actual_placement = MockWindowsApi
+    .get_window_placement(window_handle)
+    .expect("Failed to get placement after pinning");
+  assert_eq!(actual_placement.normal_position, pinned_rect);
+  assert!(MockWindowsApi.is_window_always_on_top(window_handle));
+
+  assert!(placement.unpin_picture_in_picture(&MockWindowsApi, window_handle));
+
+  let actual_placement = MockWindowsApi
+    .get_window_placement(window_handle)
+    .expect("Failed to get placement after unpinning");
+  assert_eq!(actual_placement, initial_placement);
+  assert!(!MockWindowsApi.is_window_always_on_top(window_handle));
+  assert!(!placement.unpin_picture_in_picture(&MockWindowsApi, window_handle));
+}
+
+#[test]
+fn unpin_picture_in_picture_keeps_window_always_on_top_if_it_was_before() {
+  let monitor_handle = MonitorHandle::from(1);
+  let window_handle = WindowHandle::new(1);
+  let initial_sizing = Sizing::new(50, 50, 100, 100);
+  let initial_placement = WindowPlacement::new_from_sizing(initial_sizing.clone());
+  MockWindowsApi::add_or_update_window(window_handle, "Test Window".to_string(), initial_sizing, false, false, true);
+  MockWindowsApi::add_monitor(monitor_handle, Rect::new(0, 0, 200, 200), true);
+  MockWindowsApi::place_window(window_handle, monitor_handle);
+  MockWindowsApi::mark_window_always_on_top(window_handle);
+  let mut placement = Placement::default();
+
+  placement.pin_picture_in_picture(
+    &MockWindowsApi,
+    window_handle,
+    initial_placement,
+    Rect::new(140, 160, 180, 180),
+  );
+  placement.unpin_picture_in_picture(&MockWindowsApi, window_handle);
+
+  assert!(MockWindowsApi.is_window_always_on_top(window_handle));
+}
+
+#[test]
+fn resize_places_bitmap_scaled_window_at_requested_physical_size() {
+  // A DPI-unaware window on a 150% monitor renders at 96dpi, but its placement is reported in physical pixels to this
+  // per-monitor DPI aware application, which is what the mock does for every window
+  let monitor_handle = MonitorHandle::from(1);
+  let window_handle = WindowHandle::new(1);
+  MockWindowsApi::add_or_update_window(
+    window_handle,
+    "Test Window".to_string(),
+    Sizing::new(75, 75, 150, 150),
+    false,
+    false,
+    true,
+  );
+  MockWindowsApi::add_monitor(monitor_handle, Rect::new(0, 0, 3840, 2160), true);
+  MockWindowsApi::place_window(window_handle, monitor_handle);
+  let placement = Placement::default();
+  let sizing = Sizing::new(30, 30, 1905, 2100);
+
+  placement.resize(&MockWindowsApi, window_handle, sizing.clone(), 30);
+
+  let actual_placement = MockWindowsApi
+    .get_window_placement(window_handle)
+    .expect("Failed to get placement after resizing");
+  assert_eq!(actual_placement.normal_position, Rect::from(sizing.clone()));
+  assert!(is_of_expected_size(&placement, window_handle, &actual_placement, &sizing, 30));
+}
+
+#[test]
+fn resize_moves_sizing_that_would_be_off_screen_onto_nearest_monitor() {
+  let monitor_handle = MonitorHandle::from(1);
+  let window_handle = WindowHandle::new(1);
+  MockWindowsApi::add_or_update_window(
+    window_handle,
+    "Test Window".to_string(),
+    Sizing::new(0, 0, 100, 100),
+    false,
+    false,
+    true,
+  );
+  MockWindowsApi::add_monitor(monitor_handle, Rect::new(0, 0, 200, 200), true);
+  MockWindowsApi::place_window(window_handle, monitor_handle);
+  let placement = Placement::default();
+
+  placement.resize(&MockWindowsApi, window_handle, Sizing::new(1920, 0, 80, 60), 20);
+
+  let actual_placement = MockWindowsApi
+    .get_window_placement(window_handle)
+    .expect("Failed to get placement after resizing");
+  assert_eq!(actual_placement.normal_position, Rect::new(60, 60, 140, 120));
+}
+
+#[test]
+fn resize_moves_owned_windows_along_with_owner_keeping_their_layout() {
+  let monitor_handle = MonitorHandle::from(1);
+  let window_handle = WindowHandle::new(1);
+  let palette_handle = WindowHandle::new(2);
+  let toolbox_handle = WindowHandle::new(3);
+  MockWindowsApi::add_or_update_window(
+    window_handle,
+    "Test Window".to_string(),
+    Sizing::new(100, 100, 400, 300),
+    false,
+    false,
+    true,
+  );
+  MockWindowsApi::add_or_update_window(
+    palette_handle,
+    "Palette".to_string(),
+    Sizing::new(120, 120, 50, 50),
+    false,
+    false,
+    false,
+  );
+  MockWindowsApi::add_or_update_window(
+    toolbox_handle,
+    "Toolbox".to_string(),
+    Sizing::new(450, 300, 80, 60),
+    false,
+    false,
+    false,
+  );
+  MockWindowsApi::set_window_owner(palette_handle, window_handle);
+  MockWindowsApi::set_window_owner(toolbox_handle, window_handle);
+  MockWindowsApi::add_monitor(monitor_handle, Rect::new(0, 0, 2000, 1200), true);
+  MockWindowsApi::place_window(window_handle, monitor_handle);
+  let placement = Placement::default();
+
+  placement.resize(&MockWindowsApi, window_handle, Sizing::new(600, 200, 400, 300), 20);
+
+  assert_eq!(
+    MockWindowsApi.get_window_rect(palette_handle),
+    Some(Rect::new(620, 220, 670, 270))
+  );
+  assert_eq!(
+    MockWindowsApi.get_window_rect(toolbox_handle),
+    Some(Rect::new(950, 400, 1030, 460))
+  );
+}
@@ -1,4 +1,4 @@
-use crate::common::{Direction, Rect, Sizing};
+use crate::common::{Direction, Point, Rect, Sizing};
 
 #[test]
 fn right_half_of_screen_calculates_correct_sizing() {
@@ -44,6 +44,95 @@ fn bottom_half_of_screen_calculates_correct_sizing() {
   assert_eq!(sizing.height, 85);
 }
 
+#[test]
+fn quarter_of_screen_lines_up_with_halves_of_screen() {
+  let work_area = Rect::new(0, 0, 100, 200);
+  let sizing = Sizing::quarter_of_screen(work_area, Direction::Right, Direction::Down, 10);
+
+  assert_eq!(sizing.x, 55);
+  assert_eq!(sizing.y, 105);
+  assert_eq!(sizing.width, 35);
+  assert_eq!(sizing.height, 85);
+}
+
+#[test]
+fn thirds_of_screen_line_up_with_each_other() {
+  let work_area = Rect::new(0, 0, 300, 200);
+
+  assert_eq!(Sizing::left_third_of_screen(work_area, 10), Sizing::new(10, 10, 85, 180));
+  assert_eq!(Sizing::center_third_of_screen(work_area, 10), Sizing::new(105, 10, 90, 180));
+  assert_eq!(Sizing::right_third_of_screen(work_area, 10), Sizing::new(205, 10, 85, 180));
+  assert_eq!(
+    Sizing::left_two_thirds_of_screen(work_area, 10),
+    Sizing::new(10, 10, 185, 180)
+  );
+  assert_eq!(
+    Sizing::right_two_thirds_of_screen(work_area, 10),
+    Sizing::new(105, 10, 185, 180)
+  );
+}
+
+#[test]
+fn for_snap_zone_returns_quarters_in_corners_and_halves_along_side_edges() {
+  let work_area = Rect::new(0, 0, 300, 200);
+  let zone = |x: i32, y: i32| Sizing::for_snap_zone(work_area, Point::new(x, y), 20, 10);
+
+  assert_eq!(
+    zone(5, 5),
+    Some(Sizing::quarter_of_screen(work_area, Direction::Left, Direction::Up, 10))
+  );
+  assert_eq!(
+    zone(295, 195),
+    Some(Sizing::quarter_of_screen(work_area, Direction::Right, Direction::Down, 10))
+  );
+  assert_eq!(zone(5, 100), Some(Sizing::left_half_of_screen(work_area, 10)));
+  assert_eq!(zone(290, 100), Some(Sizing::right_half_of_screen(work_area, 10)));
+  assert_eq!(zone(150, 195), Some(Sizing::bottom_half_of_screen(work_area, 10)));
+}
+
+#[test]
+fn for_snap_zone_returns_third_below_cursor_along_top_edge() {
+  let work_area = Rect::new(0, 0, 300, 200);
+  let zone = |x: i32| Sizing::for_snap_zone(work_area, Point::new(x, 5), 20, 10);
+
+  assert_eq!(zone(50), Some(Sizing::left_third_of_screen(work_area, 10)));
+  assert_eq!(zone(150), Some(Sizing::center_third_of_screen(work_area, 10)));
+  assert_eq!(zone(250), Some(Sizing::right_third_of_screen(work_area, 10)));
+}
+
+#[test]
+fn for_snap_zone_returns_none_away_from_edges_outside_work_area_or_when_disabled() {
+  let work_area = Rect::new(0, 0, 300, 200);
+
+  assert_eq!(Sizing::for_snap_zone(work_area, Point::new(150, 100), 20, 10), None);
+  assert_eq!(Sizing::for_snap_zone(work_area, Point::new(-5, 100), 20, 10), None);
+  assert_eq!(Sizing::for_snap_zone(work_area, Point::new(5, 100), 0, 10), None);
+}
+
+#[test]
+fn snap_zones_returns_every_snap_zone_in_place_mode_order() {
+  let work_area = Rect::new(0, 0, 300, 200);
+  let zone = |x: i32, y: i32| Sizing::for_snap_zone(work_area, Point::new(x, y), 20, 10).expect("Expected snap zone");
+
+  let zones = Sizing::snap_zones(work_area, 10);
+
+  assert_eq!(
+    zones,
+    vec![
+      zone(50, 5),
+      zone(150, 5),
+      zone(250, 5),
+      zone(5, 100),
+      zone(295, 100),
+      zone(150, 195),
+      zone(5, 5),
+      zone(295, 5),
+      zone(5, 195),
+      zone(295, 195),
+    ]
+  );
+}
+
 #[test]
 fn near_maximised_calculates_correct_sizing() {
   let work_area = Rect::new(0, 0, 100, 200);
@@ -344,3 +433,22 @@ fn centre_near_maximised_with_zero_margin_occupies_exact_middle_half() {
   let v = Sizing::centre_near_maximised(work_area, Direction::Up, 0);
   assert_eq!(v, Sizing::new(0, 50, 100, 100));
 }
+
+#[test]
+fn refit_returns_equivalent_sizing_for_snapped_sizing() {
+  let from_work_area = Rect::new(0, 0, 100, 200);
+  let to_work_area = Rect::new(100, 20, 300, 220);
+
+  let result = Sizing::right_half_of_screen(from_work_area, 10).refit(from_work_area, to_work_area, 10);
+
+  assert_eq!(result, Some(Sizing::right_half_of_screen(to_work_area, 10)));
+}
+
+#[test]
+fn refit_returns_none_for_freely_placed_sizing() {
+  let from_work_area = Rect::new(0, 0, 100, 200);
+
+  let result = Sizing::new(5, 5, 50, 50).refit(from_work_area, Rect::new(0, 0, 200, 200), 10);
+
+  assert_eq!(result, None);
+}
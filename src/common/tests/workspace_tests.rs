@@ -1,5 +1,6 @@
 use crate::api::{MockWindowsApi, WindowsApi};
 use crate::common::{Monitor, MonitorHandle, PersistentWorkspaceId, Rect, Sizing, Window, WindowHandle, Workspace};
+use std::time::SystemTime;
 
 impl Workspace {
   /// Creates a new workspace for testing purposes with margin set to 0 and inactive by default.
@@ -78,6 +79,22 @@ fn update_window_rect_if_required_centers_normal_window_when_changing_monitors()
   assert_eq!(updated_window.center, target_monitor.work_area.center());
 }
 
+#[test]
+fn update_window_rect_if_required_preserves_relative_position_of_normal_window_when_enabled() {
+  let source_monitor = Monitor::new_test(1, Rect::new(0, 0, 1000, 800));
+  let target_monitor = Monitor::new_test(2, Rect::new(1000, 0, 2920, 1080));
+  let mut workspace = Workspace::new_test(PersistentWorkspaceId::new(target_monitor.id, 1, true), &target_monitor);
+  workspace.set_preserve_relative_window_position(true);
+  let window = Window::new_test(1, Rect::new(100, 100, 300, 200));
+  MockWindowsApi::add_monitor(source_monitor.handle, source_monitor.monitor_area, true);
+  let mock_api = MockWindowsApi::new();
+
+  let updated_window = workspace.update_window_rect_if_required(window, source_monitor.handle, &mock_api);
+
+  assert_eq!(updated_window.rect, Rect::new(1284, 158, 1484, 258));
+  assert_eq!(updated_window.center, updated_window.rect.center());
+}
+
 #[test]
 fn update_window_rect_if_required_centers_window_when_monitor_info_missing() {
   let source_monitor = Monitor::new_test(1, Rect::new(0, 0, 1024, 768));
@@ -94,6 +111,27 @@ fn update_window_rect_if_required_centers_window_when_monitor_info_missing() {
   assert_eq!(updated_window.center, target_monitor.work_area.center());
 }
 
+#[test]
+fn update_monitor_assigns_monitor_and_refits_stored_windows() {
+  let source_monitor = Monitor::new_test(1, Rect::new(0, 0, 1000, 800));
+  let target_monitor = Monitor::new_test(2, Rect::new(1000, 0, 2920, 1080));
+  let mut workspace = Workspace::new_test(PersistentWorkspaceId::new(source_monitor.id, 2, false), &source_monitor);
+  let near_maximised_window = Window::new_test(1, Sizing::near_maximised(source_monitor.work_area, workspace.margin).into());
+  let normal_window = Window::new_test(2, Rect::new(100, 100, 300, 200));
+  workspace.windows = vec![near_maximised_window, normal_window];
+
+  workspace.update_monitor(&target_monitor);
+
+  assert_eq!(workspace.monitor_handle, target_monitor.handle.as_i64());
+  assert_eq!(workspace.monitor.id, target_monitor.id);
+  assert_eq!(
+    workspace.windows[0].rect,
+    Sizing::near_maximised(target_monitor.work_area, workspace.margin).into()
+  );
+  assert_eq!(workspace.windows[1].rect.width(), 200);
+  assert_eq!(workspace.windows[1].center, target_monitor.work_area.center());
+}
+
 #[test]
 fn move_or_store_and_hide_window_stores_window_if_workspace_is_inactive() {
   let monitor = Monitor::new_test(1, Rect::default());
@@ -128,6 +166,23 @@ fn move_or_store_and_hide_window_moves_window_if_workspace_is_active() {
   assert_eq!(workspace.minimised_windows.len(), 0);
 }
 
+#[test]
+fn move_or_store_and_hide_window_refits_window_moved_from_another_monitor() {
+  let target_monitor = Monitor::new_test(2, Rect::new(0, 0, 1920, 1080));
+  let mut workspace = Workspace::new_active(PersistentWorkspaceId::new(target_monitor.id, 1, true), &target_monitor, 0);
+  let current_monitor_handle = MonitorHandle::from(1);
+  MockWindowsApi::add_monitor(current_monitor_handle, Rect::new(0, 0, 800, 600), true);
+  let mock_api = MockWindowsApi::new();
+  let current_monitor = mock_api.get_monitor_info_for_monitor(current_monitor_handle).unwrap();
+  let window = Window::new_test(1, Sizing::left_half_of_screen(current_monitor.work_area, 0).into());
+  MockWindowsApi::add_or_update_window(window.handle, window.title.clone(), window.rect.into(), false, false, true);
+
+  workspace.move_or_store_and_hide_window(window.clone(), current_monitor_handle, &mock_api);
+
+  let expected_rect: Rect = Sizing::left_half_of_screen(target_monitor.work_area, 0).into();
+  assert_eq!(mock_api.get_window_rect(window.handle), Some(expected_rect));
+}
+
 #[test]
 fn store_and_hide_window_stores_and_hide_window() {
   let monitor = Monitor::new_test(1, Rect::default());
@@ -210,6 +265,40 @@ fn stores_returns_false_if_window_is_not_in_workspace() {
   assert!(!workspace.stores(&WindowHandle::new(2)));
 }
 
+#[test]
+fn store_and_hide_windows_and_restore_windows_return_slowest_window() {
+  let mut workspace = Workspace::new_test(PersistentWorkspaceId::new_test(1), &Monitor::mock_1());
+  MockWindowsApi::add_or_update_window(
+    1.into(),
+    "Test Window 1".to_string(),
+    Sizing::new(0, 0, 100, 100),
+    false,
+    false,
+    true,
+  );
+  MockWindowsApi::add_or_update_window(
+    2.into(),
+    "Test Window 2".to_string(),
+    Sizing::new(100, 100, 100, 100),
+    false,
+    false,
+    true,
+  );
+  let mock_api = MockWindowsApi;
+  let windows = mock_api.get_all_visible_windows();
+
+  let (hidden_window, _) = workspace
+    .store_and_hide_windows(windows.clone(), 1.into(), &mock_api)
+    .expect("No slowest window returned when hiding");
+  let (restored_window, _) = workspace
+    .restore_windows(&mock_api)
+    .expect("No slowest window returned when restoring");
+
+  assert!(windows.contains(&hidden_window));
+  assert!(windows.iter().any(|window| window.handle == restored_window.handle));
+  assert!(workspace.restore_windows(&mock_api).is_none());
+}
+
 #[test]
 fn restore_windows_restores_all_windows() {
   let mut workspace = Workspace::new_test(PersistentWorkspaceId::new_test(1), &Monitor::mock_1());
@@ -228,6 +317,79 @@ fn restore_windows_restores_all_windows() {
   assert!(workspace.get_windows().is_empty());
 }
 
+#[test]
+fn restore_window_restores_only_the_requested_window() {
+  let mut workspace = Workspace::new_test(PersistentWorkspaceId::new_test(1), &Monitor::mock_1());
+  MockWindowsApi::add_or_update_window(
+    1.into(),
+    "Test Window 1".to_string(),
+    Sizing::new(0, 0, 100, 100),
+    false,
+    false,
+    true,
+  );
+  MockWindowsApi::add_or_update_window(
+    2.into(),
+    "Test Window 2".to_string(),
+    Sizing::new(100, 100, 100, 100),
+    false,
+    false,
+    true,
+  );
+  let mock_api = MockWindowsApi;
+  let windows = mock_api.get_all_visible_windows();
+  workspace.store_and_hide_windows(windows, 1.into(), &mock_api);
+
+  let restored_window = workspace.restore_window(1.into(), &mock_api);
+
+  assert_eq!(restored_window.map(|window| window.handle), Some(1.into()));
+  assert!(!mock_api.is_window_hidden(&1.into()));
+  assert!(mock_api.is_window_hidden(&2.into()));
+  assert!(!workspace.stores(&1.into()));
+  assert!(workspace.stores(&2.into()));
+  assert_eq!(workspace.get_window_state_info().len(), 1);
+  assert!(workspace.restore_window(1.into(), &mock_api).is_none());
+}
+
+#[test]
+fn store_and_hide_window_hides_and_restores_unmanaged_owned_windows_with_their_owner() {
+  MockWindowsApi::reset();
+  let mut workspace = Workspace::new_test(PersistentWorkspaceId::new_test(1), &Monitor::mock_1());
+  let owner = WindowHandle::new(1);
+  let tool_window = WindowHandle::new(2);
+  let dialog = WindowHandle::new(3);
+  MockWindowsApi::add_or_update_window(owner, "IDE".to_string(), Sizing::new(0, 0, 800, 600), false, false, true);
+  MockWindowsApi::add_or_update_window(
+    tool_window,
+    "Dock".to_string(),
+    Sizing::new(0, 0, 100, 100),
+    false,
+    false,
+    false,
+  );
+  MockWindowsApi::add_or_update_window(dialog, "Dialog".to_string(), Sizing::new(0, 0, 100, 100), false, false, false);
+  MockWindowsApi::set_window_owner(tool_window, owner);
+  MockWindowsApi::set_window_owner(dialog, owner);
+  MockWindowsApi::mark_window_unmanageable(tool_window);
+  let mock_api = MockWindowsApi;
+  let owner_window = mock_api
+    .get_all_visible_windows()
+    .into_iter()
+    .find(|w| w.handle == owner)
+    .unwrap();
+
+  workspace.store_and_hide_window(owner_window, 1.into(), &mock_api);
+
+  assert!(mock_api.is_window_hidden(&tool_window));
+  assert!(!mock_api.is_window_hidden(&dialog));
+  assert_eq!(workspace.get_windows().len(), 1);
+
+  workspace.restore_windows(&mock_api);
+
+  assert!(!mock_api.is_window_hidden(&owner));
+  assert!(!mock_api.is_window_hidden(&tool_window));
+}
+
 #[test]
 fn restore_windows_handles_empty_workspace() {
   let mut workspace = Workspace::new_test(PersistentWorkspaceId::new_test(1), &Monitor::mock_1());
@@ -297,3 +459,42 @@ fn remove_windows_if_present_handles_empty_workspace() {
   assert!(workspace.windows.is_empty());
   assert!(workspace.minimised_windows.is_empty());
 }
+
+#[test]
+fn remove_dead_windows_removes_closed_and_missing_windows_only() {
+  let monitor = Monitor::new_test(1, Rect::new(0, 0, 1920, 1080));
+  let mut workspace = Workspace::new_test(PersistentWorkspaceId::new(monitor.id, 2, true), &monitor);
+  let mock_api = MockWindowsApi::new();
+  let windows = (1..=3)
+    .map(|i| Window::new_test(i, Rect::new(0, 0, 100, 100)))
+    .collect::<Vec<_>>();
+  for window in &windows {
+    MockWindowsApi::add_or_update_window(window.handle, window.title.clone(), window.rect.into(), false, false, false);
+  }
+  workspace.store_and_hide_windows(windows.clone(), monitor.handle, &mock_api);
+  mock_api.do_close_window(windows[0].handle);
+  MockWindowsApi::remove_window(windows[2].handle);
+
+  let dead_windows = workspace.remove_dead_windows(&mock_api);
+
+  let dead_handles = dead_windows.iter().map(|window| window.handle).collect::<Vec<_>>();
+  assert_eq!(dead_handles, vec![windows[0].handle, windows[2].handle]);
+  assert_eq!(workspace.get_windows(), vec![windows[1].clone()]);
+  assert_eq!(workspace.get_window_state_info(), vec![(windows[1].handle, false)]);
+}
+
+#[test]
+fn last_used_at_is_only_recorded_once_workspace_was_active() {
+  let monitor = Monitor::new_test(1, Rect::new(0, 0, 1920, 1080));
+  let mut workspace = Workspace::new_test(PersistentWorkspaceId::new(monitor.id, 2, true), &monitor);
+  assert_eq!(workspace.last_used_at(), None);
+
+  let before_activation = SystemTime::now();
+  workspace.set_active(true);
+  workspace.set_active(false);
+
+  let last_used_at = workspace.last_used_at().expect("Expected workspace to have been used");
+  assert!(last_used_at >= before_activation);
+  workspace.set_active(false);
+  assert_eq!(workspace.last_used_at(), Some(last_used_at));
+}
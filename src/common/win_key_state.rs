@@ -0,0 +1,151 @@
+/// What releasing the Win key means for the Start menu.
+#[derive(Debug, PartialEq, Eq)]
+pub enum WinKeyRelease {
+  /// The Win key was tapped on its own, so the release must pass through to open the Start menu.
+  Tap,
+  /// The Win key was used together with another key or the mouse.
+  Combination,
+  /// The Win key was not known to be pressed, e.g. because state was reset while it was held.
+  Ignored,
+}
+
+/// Represents the state of the Win key as seen by the keyboard hook, so that a plain tap of the Win key, which opens
+/// the Start menu, can be told apart from using it together with another key or the mouse. Windows itself only knows
+/// about keyboard combinations, which is why mouse combinations require masking the Start menu.
+#[derive(Default)]
+pub struct WinKeyState {
+  is_pressed: bool,
+  is_combination: bool,
+}
+
+impl WinKeyState {
+  /// Returns `true` if the Win key is currently held down.
+  pub(crate) fn is_pressed(&self) -> bool {
+    self.is_pressed
+  }
+
+  /// Records that the Win key was pressed. Returns `true` if it was not pressed before, which means that key repeats
+  /// while holding the Win key return `false`.
+  pub(crate) fn press(&mut self) -> bool {
+    if self.is_pressed {
+      return false;
+    }
+    self.is_pressed = true;
+    self.is_combination = false;
+
+    true
+  }
+
+  /// Records that another key was pressed while the Win key is held down. Windows does not open the Start menu in this
+  /// case, so nothing needs to be masked.
+  pub(crate) fn record_key_combination(&mut self) {
+    if self.is_pressed {
+      self.is_combination = true;
+    }
+  }
+
+  /// Records that a mouse button was used while the Win key is held down. Returns `true` if the Start menu must be
+  /// masked, which is only the case for the first combination since the Win key was pressed.
+  pub(crate) fn record_mouse_combination(&mut self) -> bool {
+    if !self.is_pressed || self.is_combination {
+      return false;
+    }
+    self.is_combination = true;
+
+    true
+  }
+
+  /// Records that the Win key was released and returns what the release means.
+  pub(crate) fn release(&mut self) -> WinKeyRelease {
+    if !self.is_pressed {
+      return WinKeyRelease::Ignored;
+    }
+    let is_combination = self.is_combination;
+    self.reset();
+    if is_combination {
+      WinKeyRelease::Combination
+    } else {
+      WinKeyRelease::Tap
+    }
+  }
+
+  /// Forgets that the Win key is pressed, e.g. when the actual key state is found to be inconsistent.
+  pub(crate) fn reset(&mut self) {
+    self.is_pressed = false;
+    self.is_combination = false;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn release_reports_tap_when_win_key_was_used_on_its_own() {
+    let mut state = WinKeyState::default();
+    state.press();
+
+    assert_eq!(state.release(), WinKeyRelease::Tap);
+    assert!(!state.is_pressed());
+  }
+
+  #[test]
+  fn press_ignores_key_repeats() {
+    let mut state = WinKeyState::default();
+
+    assert!(state.press());
+    assert!(!state.press());
+    assert_eq!(state.release(), WinKeyRelease::Tap);
+  }
+
+  #[test]
+  fn release_reports_combination_after_another_key_was_pressed() {
+    let mut state = WinKeyState::default();
+    state.press();
+    state.record_key_combination();
+
+    assert_eq!(state.release(), WinKeyRelease::Combination);
+  }
+
+  #[test]
+  fn record_mouse_combination_masks_start_menu_only_once_per_press() {
+    let mut state = WinKeyState::default();
+    state.press();
+
+    assert!(state.record_mouse_combination());
+    assert!(!state.record_mouse_combination());
+    assert_eq!(state.release(), WinKeyRelease::Combination);
+    state.press();
+    assert!(state.record_mouse_combination());
+  }
+
+  #[test]
+  fn record_mouse_combination_does_not_mask_start_menu_after_key_combination() {
+    let mut state = WinKeyState::default();
+    state.press();
+    state.record_key_combination();
+
+    assert!(!state.record_mouse_combination());
+  }
+
+  #[test]
+  fn combinations_and_release_are_ignored_when_win_key_is_not_pressed() {
+    let mut state = WinKeyState::default();
+    state.record_key_combination();
+
+    assert!(!state.record_mouse_combination());
+    assert_eq!(state.release(), WinKeyRelease::Ignored);
+  }
+
+  #[test]
+  fn reset_forgets_pressed_win_key() {
+    let mut state = WinKeyState::default();
+    state.press();
+    state.record_key_combination();
+    state.reset();
+
+    assert_eq!(state.release(), WinKeyRelease::Ignored);
+    assert!(state.press());
+    assert_eq!(state.release(), WinKeyRelease::Tap);
+  }
+}
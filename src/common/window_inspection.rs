@@ -0,0 +1,53 @@
+use std::fmt::Display;
+
+/// Describes a window as seen by this application, so that exclusion rules can be debugged interactively.
+pub struct WindowInspection {
+  pub title: String,
+  pub class_name: String,
+  pub process_name: Option<String>,
+  pub is_managed: bool,
+}
+
+impl Display for WindowInspection {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    writeln!(f, "Title: {}", self.title)?;
+    writeln!(f, "Class: {}", self.class_name)?;
+    writeln!(f, "Process: {}", self.process_name.as_deref().unwrap_or("unknown"))?;
+    write!(f, "Managed: {}", if self.is_managed { "yes" } else { "no" })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn display_lists_all_properties_on_separate_lines() {
+    let inspection = WindowInspection {
+      title: "Untitled - Notepad".to_string(),
+      class_name: "Notepad".to_string(),
+      process_name: Some("notepad.exe".to_string()),
+      is_managed: true,
+    };
+
+    assert_eq!(
+      inspection.to_string(),
+      "Title: Untitled - Notepad\nClass: Notepad\nProcess: notepad.exe\nManaged: yes"
+    );
+  }
+
+  #[test]
+  fn display_shows_unknown_process_and_unmanaged_window() {
+    let inspection = WindowInspection {
+      title: String::new(),
+      class_name: "tooltips_class32".to_string(),
+      process_name: None,
+      is_managed: false,
+    };
+
+    assert_eq!(
+      inspection.to_string(),
+      "Title: \nClass: tooltips_class32\nProcess: unknown\nManaged: no"
+    );
+  }
+}
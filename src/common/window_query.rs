@@ -0,0 +1,128 @@
+use crate::common::WindowHandle;
+use serde::{Deserialize, Serialize};
+
+/// Whether a managed window can currently be seen or is stored in an inactive workspace.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowVisibility {
+  Visible,
+  Hidden,
+}
+
+/// Describes a managed window in a structured way, so that the same data can be used for menus, window switchers, and
+/// external tools without enumerating windows again.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowSummary {
+  #[serde(flatten)]
+  pub handle: WindowHandle,
+  pub title: String,
+  pub class_name: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub process_name: Option<String>,
+  pub monitor_id: String,
+  pub workspace: usize,
+  pub visibility: WindowVisibility,
+}
+
+impl WindowSummary {
+  /// Returns `true` if the title or the process name of the window contains the text typed into the window switcher,
+  /// ignoring case. An empty text matches every window.
+  pub fn matches_typed_text(&self, text: &str) -> bool {
+    let text = text.to_lowercase();
+    self.title.to_lowercase().contains(&text)
+      || self
+        .process_name
+        .as_ref()
+        .is_some_and(|name| name.to_lowercase().contains(&text))
+  }
+}
+
+/// Filters for listing managed windows. A window matches if it matches every filter that is set, which means that the
+/// default query matches all windows.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WindowQuery {
+  pub workspace: Option<usize>,
+  pub monitor_id: Option<String>,
+  pub process_name: Option<String>,
+  pub visibility: Option<WindowVisibility>,
+}
+
+impl WindowQuery {
+  /// Returns `true` if the window matches all filters. Process names are compared case-insensitively because Windows
+  /// does not distinguish between `Notepad.exe` and `notepad.exe`.
+  pub fn matches(&self, window: &WindowSummary) -> bool {
+    self.workspace.is_none_or(|workspace| workspace == window.workspace)
+      && self
+        .monitor_id
+        .as_ref()
+        .is_none_or(|monitor_id| *monitor_id == window.monitor_id)
+      && self.process_name.as_ref().is_none_or(|process_name| {
+        window
+          .process_name
+          .as_ref()
+          .is_some_and(|name| name.eq_ignore_ascii_case(process_name))
+      })
+      && self.visibility.is_none_or(|visibility| visibility == window.visibility)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn summary() -> WindowSummary {
+    WindowSummary {
+      handle: WindowHandle::new(1),
+      title: "Untitled - Notepad".to_string(),
+      class_name: "Notepad".to_string(),
+      process_name: Some("Notepad.exe".to_string()),
+      monitor_id: "DISPLAY1".to_string(),
+      workspace: 2,
+      visibility: WindowVisibility::Hidden,
+    }
+  }
+
+  #[test]
+  fn default_query_matches_every_window() {
+    assert!(WindowQuery::default().matches(&summary()));
+  }
+
+  #[test]
+  fn matches_requires_every_filter_that_is_set_to_match() {
+    let query = WindowQuery {
+      workspace: Some(2),
+      monitor_id: Some("DISPLAY1".to_string()),
+      process_name: Some("notepad.exe".to_string()),
+      visibility: Some(WindowVisibility::Hidden),
+    };
+
+    assert!(query.matches(&summary()));
+    assert!(!query.matches(&WindowSummary {
+      workspace: 1,
+      ..summary()
+    }));
+    assert!(!query.matches(&WindowSummary {
+      visibility: WindowVisibility::Visible,
+      ..summary()
+    }));
+    assert!(!query.matches(&WindowSummary {
+      process_name: None,
+      ..summary()
+    }));
+  }
+
+  #[test]
+  fn matches_typed_text_searches_title_and_process_name_ignoring_case() {
+    assert!(summary().matches_typed_text(""));
+    assert!(summary().matches_typed_text("untitled"));
+    assert!(summary().matches_typed_text("NOTEPAD.EXE"));
+    assert!(!summary().matches_typed_text("code"));
+    assert!(
+      !WindowSummary {
+        process_name: None,
+        ..summary()
+      }
+      .matches_typed_text(".exe")
+    );
+  }
+}
@@ -1,6 +1,7 @@
 use crate::api::WindowsApi;
 use crate::common::{Monitor, MonitorHandle, PersistentWorkspaceId, Rect, Sizing, Window, WindowHandle, WorkspaceAction};
 use std::fmt::Display;
+use std::time::{Duration, Instant, SystemTime};
 
 /// Represents a Randolf workspace, which is a collection of zero or more windows that are managed together on a
 /// specific monitor's desktop. Will only ever store windows if the workspace is inactive but is also used to position
@@ -12,8 +13,12 @@ pub struct Workspace {
   pub monitor: Monitor,
   pub(super) windows: Vec<Window>,
   pub(super) minimised_windows: Vec<(WindowHandle, bool)>, // (window_handle, is_minimised)
+  pub(super) owned_windows: Vec<(WindowHandle, Window)>,   // (owner_handle, owned_window)
   pub(super) margin: i32,
+  pub(super) preserve_relative_window_position: bool,
+  last_focused_window: Option<WindowHandle>,
   is_active: bool,
+  deactivated_at: Option<SystemTime>,
 }
 
 impl Workspace {
@@ -25,8 +30,12 @@ impl Workspace {
       monitor: monitor.clone(),
       windows: vec![],
       minimised_windows: vec![],
+      owned_windows: vec![],
       margin,
+      preserve_relative_window_position: false,
+      last_focused_window: None,
       is_active: true,
+      deactivated_at: None,
     }
   }
 
@@ -38,8 +47,12 @@ impl Workspace {
       monitor: monitor.clone(),
       windows: vec![],
       minimised_windows: vec![],
+      owned_windows: vec![],
       margin,
+      preserve_relative_window_position: false,
+      last_focused_window: None,
       is_active: false,
+      deactivated_at: None,
     }
   }
 
@@ -50,15 +63,50 @@ impl Workspace {
 
   /// Sets the workspace as active (if `true`) or inactive (if `false`).
   pub fn set_active(&mut self, is_active: bool) {
+    if self.is_active && !is_active {
+      self.deactivated_at = Some(SystemTime::now());
+    }
     self.is_active = is_active;
   }
 
+  /// Returns when the workspace was last active, which is now if it is active, or `None` if it has not been active since
+  /// it was created.
+  pub fn last_used_at(&self) -> Option<SystemTime> {
+    if self.is_active {
+      return Some(SystemTime::now());
+    }
+
+    self.deactivated_at
+  }
+
+  /// Sets whether windows that are neither near-maximised nor near-snapped keep their relative position within the work
+  /// area (if `true`) or are centred (if `false`) when they are moved to this workspace from a different monitor.
+  pub fn set_preserve_relative_window_position(&mut self, preserve_relative_window_position: bool) {
+    self.preserve_relative_window_position = preserve_relative_window_position;
+  }
+
   /// Allows you to update the `MonitorHandle`, which is a non-persistent identifier of a monitor, for this workspace.
   /// Must be called prior to interacting with the workspace.
   pub fn update_handle(&mut self, monitor_handle: MonitorHandle) {
     self.monitor_handle = monitor_handle.handle as i64;
   }
 
+  /// Assigns this workspace to a different monitor, e.g. because its own monitor was disconnected and a fallback
+  /// monitor is configured, or because its own monitor was reconnected. Stored windows are refitted to the work area of
+  /// the new monitor, so that they are visible when the workspace is restored.
+  pub fn update_monitor(&mut self, monitor: &Monitor) {
+    let previous_work_area = self.monitor.work_area;
+    self.monitor_handle = monitor.handle.handle as i64;
+    self.monitor = monitor.clone();
+    for window in self.windows.iter_mut() {
+      window.rect = match Sizing::from(window.rect).refit(previous_work_area, monitor.work_area, self.margin) {
+        Some(sizing) => sizing.into(),
+        None => centre_within_work_area(window.rect, &monitor.work_area),
+      };
+      window.center = window.rect.center();
+    }
+  }
+
   /// Returns the largest window in the workspace or `None` if none is present. The largest window is defined as the
   /// one covering with the largest area. If multiple windows have the same area, the first one found is returned (not
   /// deterministic).
@@ -66,6 +114,21 @@ impl Workspace {
     self.windows.iter().max_by_key(|w| w.rect.area()).cloned().to_owned()
   }
 
+  /// Returns the window that had focus when the windows of this workspace were stored, or `None` if no stored window
+  /// had focus at the time.
+  pub fn get_last_focused_window(&self) -> Option<Window> {
+    let handle = self.last_focused_window?;
+    self.windows.iter().find(|w| w.handle == handle).cloned()
+  }
+
+  /// Remembers that the stored window had focus when the windows of this workspace were stored before Randolf
+  /// restarted, so that it is focused again when the workspace is activated. Ignored if the window is not stored.
+  pub fn set_last_focused_window(&mut self, handle: WindowHandle) {
+    if self.windows.iter().any(|w| w.handle == handle) {
+      self.last_focused_window = Some(handle);
+    }
+  }
+
   /// Moves the window if the workspace is active, otherwise stores and hides it, so that it can be restored later,
   /// when the workspace is activated, so that an active workspace must never store windows.
   pub fn move_or_store_and_hide_window(
@@ -85,22 +148,106 @@ impl Workspace {
     }
   }
 
+  /// Returns `true` if the workspace does not store any windows.
+  pub fn is_empty(&self) -> bool {
+    self.windows.is_empty()
+  }
+
+  /// Returns the number of windows stored in the workspace.
+  pub fn stored_window_count(&self) -> usize {
+    self.windows.len()
+  }
+
+  /// Returns the windows stored in the workspace.
+  pub fn stored_windows(&self) -> &[Window] {
+    &self.windows
+  }
+
+  /// Returns the windows that are stored in the workspace together with the window that owns them, as pairs of the
+  /// owner's handle and the owned window.
+  pub fn stored_owned_windows(&self) -> &[(WindowHandle, Window)] {
+    &self.owned_windows
+  }
+
   /// Returns `true` if the workspace stores the specified window.
   pub fn stores(&self, handle: &WindowHandle) -> bool {
     self.windows.iter().any(|window| window.handle == *handle)
   }
 
-  /// Stores and hides the specified windows. Clears the list of stored windows before storing the new ones.
+  /// Returns `true` if the specified window is stored in the workspace and was minimised when it was stored.
+  pub fn is_stored_window_minimised(&self, handle: &WindowHandle) -> bool {
+    self
+      .minimised_windows
+      .iter()
+      .any(|(window, is_minimised)| window == handle && *is_minimised)
+  }
+
+  /// Stores a window that was stored in this workspace before Randolf restarted, using the rect and minimised state it
+  /// was stored with, so that it is restored when the workspace is activated. Hides the window if it is visible again.
+  pub fn store_hidden_window(&mut self, window: Window, is_minimised: bool, windows_api: &impl WindowsApi) {
+    if self.stores(&window.handle) {
+      return;
+    }
+    if !windows_api.is_window_hidden(&window.handle) {
+      windows_api.do_hide_window(window.handle);
+    }
+    trace!(
+      "Stored hidden {} \"{}\" in workspace [{}] again",
+      window.handle,
+      window.title_trunc(),
+      self.id
+    );
+    self.minimised_windows.push((window.handle, is_minimised));
+    self.windows.push(window);
+  }
+
+  /// Stores a window that was hidden together with its owner before Randolf restarted, so that it is restored with its
+  /// owner when the workspace is activated. Hides the window if it is visible again. Returns `false` without storing
+  /// the window if the workspace does not store its owner.
+  pub fn store_hidden_owned_window(&mut self, owner: WindowHandle, window: Window, windows_api: &impl WindowsApi) -> bool {
+    if !self.stores(&owner) {
+      return false;
+    }
+    if self
+      .owned_windows
+      .iter()
+      .any(|(_, owned_window)| owned_window.handle == window.handle)
+    {
+      return true;
+    }
+    if !windows_api.is_window_hidden(&window.handle) {
+      windows_api.do_hide_window(window.handle);
+    }
+    trace!(
+      "Stored hidden {} which is owned by {owner} in workspace [{}] again",
+      window.handle, self.id
+    );
+    self.owned_windows.push((owner, window));
+
+    true
+  }
+
+  /// Stores and hides the specified windows. Clears the list of stored windows before storing the new ones. Remembers
+  /// which of the windows had focus, so that it can be focused again when the workspace is restored. Returns the window
+  /// that took the longest to be hidden, if any.
   pub fn store_and_hide_windows(
     &mut self,
     windows: Vec<Window>,
     current_monitor: MonitorHandle,
     windows_api: &impl WindowsApi,
-  ) {
+  ) -> Option<(Window, Duration)> {
     self.clear_windows();
+    self.last_focused_window = windows_api
+      .get_foreground_window()
+      .filter(|handle| windows.iter().any(|w| w.handle == *handle));
+    let mut slowest_window: Option<(Window, Duration)> = None;
     for window in windows.iter() {
+      let started = Instant::now();
       self.store_and_hide_window(window.clone(), current_monitor, windows_api);
+      slowest_window = Self::slower_window(slowest_window, window, started.elapsed());
     }
+
+    slowest_window
   }
 
   /// Removes the specified windows from the workspace. This method should be called after switching workspace and after
@@ -113,15 +260,40 @@ impl Workspace {
     for window in windows.iter() {
       self.windows.retain(|w| w.handle != window.handle);
       self.minimised_windows.retain(|(w, _)| *w != window.handle);
+      self.owned_windows.retain(|(owner, _)| *owner != window.handle);
+    }
+  }
+
+  /// Removes all stored windows that no longer exist or whose process has exited and returns them. Without this, such
+  /// windows would remain in the workspace forever because they can never be restored.
+  pub fn remove_dead_windows(&mut self, windows_api: &impl WindowsApi) -> Vec<Window> {
+    let (alive_windows, dead_windows): (Vec<Window>, Vec<Window>) = self
+      .windows
+      .drain(..)
+      .partition(|window| windows_api.is_window_alive(window.handle));
+    self.windows = alive_windows;
+    self
+      .minimised_windows
+      .retain(|(handle, _)| !dead_windows.iter().any(|window| window.handle == *handle));
+    self.owned_windows.retain(|(owner, owned_window)| {
+      !dead_windows.iter().any(|window| window.handle == *owner) && windows_api.is_window_alive(owned_window.handle)
+    });
+    if self
+      .last_focused_window
+      .is_some_and(|handle| dead_windows.iter().any(|window| window.handle == handle))
+    {
+      self.last_focused_window = None;
     }
+
+    dead_windows
   }
 
   /// Restores all windows that were stored in this workspace by unhiding them. Clears the list of stored windows
-  /// after restoring.
-  pub fn restore_windows(&mut self, api: &impl WindowsApi) {
+  /// after restoring. Returns the window that took the longest to be restored, if any.
+  pub fn restore_windows(&mut self, api: &impl WindowsApi) -> Option<(Window, Duration)> {
     if self.windows.is_empty() && self.minimised_windows.is_empty() {
       debug!("No windows to restore for workspace [{}]", self.id);
-      return;
+      return None;
     }
     if self.windows.len() != self.minimised_windows.len() {
       error!(
@@ -130,9 +302,10 @@ impl Workspace {
         self.windows.len(),
         self.minimised_windows.len()
       );
-      return;
+      return None;
     }
     let mut i = 0;
+    let mut slowest_window: Option<(Window, Duration)> = None;
     for (window_handle, is_minimised) in self.minimised_windows.iter() {
       i += 1;
       if *is_minimised {
@@ -147,7 +320,9 @@ impl Workspace {
               window.title_trunc(),
               self.id
             );
+            let started = Instant::now();
             api.do_restore_window(window, is_minimised);
+            slowest_window = Self::slower_window(slowest_window, window, started.elapsed());
           } else {
             debug!("Attempted to restore window {} but it is already visible", window_handle);
           }
@@ -157,17 +332,67 @@ impl Workspace {
         }
       }
     }
+    for (_, owned_window) in self.owned_windows.iter() {
+      if api.is_window_hidden(&owned_window.handle) {
+        trace!("Restoring owned {} on workspace [{}]", owned_window.handle, self.id);
+        api.do_restore_window(owned_window, &false);
+      }
+    }
     debug!("Restored [{}] window(s) on workspace [{}]", i, self.id);
     self.clear_windows();
+
+    slowest_window
+  }
+
+  /// Returns whichever of the two windows took longer to be hidden or restored.
+  fn slower_window(
+    slowest_window: Option<(Window, Duration)>,
+    window: &Window,
+    duration: Duration,
+  ) -> Option<(Window, Duration)> {
+    match slowest_window {
+      Some((_, slowest_duration)) if slowest_duration >= duration => slowest_window,
+      _ => Some((window.clone(), duration)),
+    }
+  }
+
+  /// Restores a single stored window and the windows it owns by unhiding them, and stops storing them. Returns the
+  /// window or `None` if this workspace does not store it.
+  pub fn restore_window(&mut self, handle: WindowHandle, api: &impl WindowsApi) -> Option<Window> {
+    let window = self.windows.iter().find(|w| w.handle == handle).cloned()?;
+    debug!(
+      "Restoring {} \"{}\" from workspace [{}]",
+      window.handle,
+      window.title_trunc(),
+      self.id
+    );
+    api.do_restore_window(&window, &false);
+    for (_, owned_window) in self.owned_windows.iter().filter(|(owner, _)| *owner == handle) {
+      if api.is_window_hidden(&owned_window.handle) {
+        trace!("Restoring owned {} from workspace [{}]", owned_window.handle, self.id);
+        api.do_restore_window(owned_window, &false);
+      }
+    }
+    self.remove_windows_if_present(std::slice::from_ref(&window));
+    if self.last_focused_window == Some(handle) {
+      self.last_focused_window = None;
+    }
+
+    Some(window)
   }
 
   fn move_window(&mut self, mut window: Window, current_monitor_handle: MonitorHandle, windows_api: &impl WindowsApi) {
     window = self.update_window_rect_if_required(window, current_monitor_handle, windows_api);
     if current_monitor_handle != self.monitor.handle {
+      windows_api.set_window_position_with_dpi_adjustment(
+        window.handle,
+        current_monitor_handle,
+        self.monitor.handle,
+        window.rect,
+      );
+    } else {
       windows_api.set_window_position(window.handle, window.rect);
-      std::thread::sleep(std::time::Duration::from_millis(10));
     }
-    windows_api.set_window_position(window.handle, window.rect);
     windows_api.set_cursor_position(&window.rect.center());
     trace!(
       "Moved {} \"{}\" to active workspace [{}]",
@@ -189,6 +414,7 @@ impl Workspace {
         return;
       }
       window = self.update_window_rect_if_required(window, current_monitor, windows_api);
+      self.store_and_hide_owned_windows(window.handle, windows_api);
       windows_api.do_hide_window(window.handle);
       self.minimised_windows.push((window.handle, false));
       self.windows.push(window.clone());
@@ -207,6 +433,23 @@ impl Workspace {
     }
   }
 
+  /// Hides the visible windows owned by the specified window that are not managed themselves, such as the floating tool
+  /// windows of an IDE, so that they follow their owner instead of remaining visible on the active workspace. They are
+  /// restored together with their owner.
+  fn store_and_hide_owned_windows(&mut self, owner: WindowHandle, windows_api: &impl WindowsApi) {
+    for owned_window in windows_api.get_owned_windows(owner) {
+      if !windows_api.is_not_a_managed_window(&owned_window.handle) {
+        continue;
+      }
+      windows_api.do_hide_window(owned_window.handle);
+      trace!(
+        "Hid {} which is owned by {owner} in workspace [{}]",
+        owned_window.handle, self.id
+      );
+      self.owned_windows.push((owner, owned_window));
+    }
+  }
+
   pub(super) fn update_window_rect_if_required(
     &mut self,
     mut window: Window,
@@ -218,27 +461,11 @@ impl Workspace {
     }
 
     // Check if window was near maximised or near-snapped on current monitor
-    let new_sizing = if let Some(monitor_info) = windows_api.get_monitor_info_for_monitor(current_monitor) {
-      let current_monitor_work_area = monitor_info.work_area;
-      let current_sizing = Sizing::from(window.rect);
-      match current_sizing {
-        sizing if sizing == Sizing::near_maximised(current_monitor_work_area, self.margin) => {
-          Some(Sizing::near_maximised(self.monitor.work_area, self.margin))
-        }
-        sizing if sizing == Sizing::left_half_of_screen(current_monitor_work_area, self.margin) => {
-          Some(Sizing::left_half_of_screen(self.monitor.work_area, self.margin))
-        }
-        sizing if sizing == Sizing::right_half_of_screen(current_monitor_work_area, self.margin) => {
-          Some(Sizing::right_half_of_screen(self.monitor.work_area, self.margin))
-        }
-        sizing if sizing == Sizing::top_half_of_screen(current_monitor_work_area, self.margin) => {
-          Some(Sizing::top_half_of_screen(self.monitor.work_area, self.margin))
-        }
-        sizing if sizing == Sizing::bottom_half_of_screen(current_monitor_work_area, self.margin) => {
-          Some(Sizing::bottom_half_of_screen(self.monitor.work_area, self.margin))
-        }
-        _ => None,
-      }
+    let current_work_area = windows_api
+      .get_monitor_info_for_monitor(current_monitor)
+      .map(|monitor_info| monitor_info.work_area);
+    let new_sizing = if let Some(current_work_area) = current_work_area {
+      Sizing::from(window.rect).refit(current_work_area, self.monitor.work_area, self.margin)
     } else {
       error!(
         "Unable to get monitor info for current monitor {}, cannot detect if window was near-maximised or -snapped",
@@ -252,14 +479,17 @@ impl Workspace {
     if let Some(new_sizing) = new_sizing {
       debug!("{} is currently near-maximised or -snapped", window.handle);
       window.rect = new_sizing.into();
+    } else if self.preserve_relative_window_position
+      && let Some(current_work_area) = current_work_area
+    {
+      debug!(
+        "{} is currently NOT near-maximised or -snapped, keeping its relative position",
+        window.handle
+      );
+      window.rect = keep_relative_position_within_work_area(window.rect, &current_work_area, &self.monitor.work_area);
     } else {
       debug!("{} is currently NOT near-maximised or -snapped", window.handle);
-      let width = window.rect.width();
-      let height = window.rect.height();
-      let target_monitor_work_area_center = self.monitor.work_area.center();
-      let left = target_monitor_work_area_center.x() - (width / 2);
-      let top = target_monitor_work_area_center.y() - (height / 2);
-      window.rect = Rect::new(left, top, left + width, top + height).clamp(&self.monitor.work_area, 10);
+      window.rect = centre_within_work_area(window.rect, &self.monitor.work_area);
     }
 
     window.center = window.rect.center();
@@ -274,9 +504,35 @@ impl Workspace {
   fn clear_windows(&mut self) {
     self.windows.clear();
     self.minimised_windows.clear();
+    self.owned_windows.clear();
+    self.last_focused_window = None;
   }
 }
 
+fn centre_within_work_area(rect: Rect, work_area: &Rect) -> Rect {
+  let width = rect.width();
+  let height = rect.height();
+  let work_area_center = work_area.center();
+  let left = work_area_center.x() - (width / 2);
+  let top = work_area_center.y() - (height / 2);
+
+  Rect::new(left, top, left + width, top + height).clamp(work_area, 10)
+}
+
+/// Moves the rect so that its centre is at the same relative position (i.e. percentage of the work area) within the new
+/// work area as within the previous one, without changing its size.
+fn keep_relative_position_within_work_area(rect: Rect, previous_work_area: &Rect, work_area: &Rect) -> Rect {
+  let center = rect.center();
+  let relative_x = (center.x() - previous_work_area.left) as f32 / previous_work_area.width().max(1) as f32;
+  let relative_y = (center.y() - previous_work_area.top) as f32 / previous_work_area.height().max(1) as f32;
+  let width = rect.width();
+  let height = rect.height();
+  let left = work_area.left + (relative_x * work_area.width() as f32).round() as i32 - (width / 2);
+  let top = work_area.top + (relative_y * work_area.height() as f32).round() as i32 - (height / 2);
+
+  Rect::new(left, top, left + width, top + height).clamp(work_area, 10)
+}
+
 impl Display for Workspace {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     write!(
@@ -0,0 +1,10 @@
+use crate::common::Rect;
+
+/// The workspaces of a monitor as shown in the badge over its work area while the Win key is held, so that users can
+/// learn which number switches to which workspace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceBadge {
+  pub work_area: Rect,
+  /// The number of each workspace on the monitor (if it has one) and whether it is active, in order.
+  pub workspaces: Vec<(Option<usize>, bool)>,
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+/// Describes a workspace in a structured way, so that external tools such as status bars can render a workspace widget
+/// from a single request instead of combining workspace IDs with further queries.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkspaceSummary {
+  pub monitor_id: String,
+  pub workspace: usize,
+  /// The number used by the `switch_to_workspace_<number>` hotkeys, if the workspace has one.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub number: Option<usize>,
+  /// The first line of the workspace's note, if the user has written one.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub name: Option<String>,
+  pub is_active: bool,
+  pub window_count: usize,
+  /// When the workspace was last active as seconds since the Unix epoch, or `None` if it has not been active since
+  /// Randolf started.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub last_used_at: Option<u64>,
+}
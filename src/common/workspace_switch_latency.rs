@@ -0,0 +1,160 @@
+use crate::common::{PersistentWorkspaceId, Window};
+use std::fmt::Display;
+use std::time::Duration;
+
+/// How long each phase of a single workspace switch took. Windows of applications that do not respond stall the calls
+/// that hide or show them (e.g. `SetWindowPos`), which is why the window that took the longest is recorded as well.
+#[derive(Clone, Debug, Default)]
+pub struct WorkspaceSwitchTiming {
+  pub store_and_hide: Duration,
+  pub restore: Duration,
+  pub foreground: Duration,
+  pub slowest_window: Option<(Window, Duration)>,
+}
+
+impl WorkspaceSwitchTiming {
+  pub fn total(&self) -> Duration {
+    self.store_and_hide + self.restore + self.foreground
+  }
+
+  /// Remembers the window if hiding or showing it took longer than for any window recorded so far.
+  pub fn record_window(&mut self, window: Option<(Window, Duration)>) {
+    if let Some((window, duration)) = window
+      && self
+        .slowest_window
+        .as_ref()
+        .is_none_or(|(_, slowest_duration)| duration > *slowest_duration)
+    {
+      self.slowest_window = Some((window, duration));
+    }
+  }
+}
+
+impl Display for WorkspaceSwitchTiming {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "[{}ms] (store and hide [{}ms], restore [{}ms], foreground [{}ms])",
+      self.total().as_millis(),
+      self.store_and_hide.as_millis(),
+      self.restore.as_millis(),
+      self.foreground.as_millis()
+    )?;
+    if let Some((window, duration)) = &self.slowest_window {
+      write!(
+        f,
+        ", slowest window {} \"{}\" took [{}ms]",
+        window.handle,
+        window.title_trunc(),
+        duration.as_millis()
+      )?;
+    }
+
+    Ok(())
+  }
+}
+
+/// Statistics about the workspace switches since Randolf started, as written to the log with every heartbeat. A switch
+/// counts as slow if it took at least the configured threshold, unless the threshold is zero.
+#[derive(Clone, Debug, Default)]
+pub struct WorkspaceSwitchLatency {
+  slow_switch_threshold: Duration,
+  switch_count: usize,
+  slow_switch_count: usize,
+  slowest_switch: Option<(PersistentWorkspaceId, WorkspaceSwitchTiming)>,
+}
+
+impl WorkspaceSwitchLatency {
+  pub fn new(slow_switch_threshold_in_ms: i32) -> Self {
+    Self {
+      slow_switch_threshold: Duration::from_millis(u64::try_from(slow_switch_threshold_in_ms).unwrap_or_default()),
+      ..Self::default()
+    }
+  }
+
+  /// Records the timing of a switch to the workspace. Returns `true` if the switch was slow.
+  pub fn record(&mut self, workspace_id: PersistentWorkspaceId, timing: &WorkspaceSwitchTiming) -> bool {
+    self.switch_count += 1;
+    if self
+      .slowest_switch
+      .as_ref()
+      .is_none_or(|(_, slowest_timing)| timing.total() > slowest_timing.total())
+    {
+      self.slowest_switch = Some((workspace_id, timing.clone()));
+    }
+    let is_slow = !self.slow_switch_threshold.is_zero() && timing.total() >= self.slow_switch_threshold;
+    if is_slow {
+      self.slow_switch_count += 1;
+    }
+
+    is_slow
+  }
+}
+
+impl Display for WorkspaceSwitchLatency {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "Workspace switches: [{}], of which [{}] took at least [{}ms]",
+      self.switch_count,
+      self.slow_switch_count,
+      self.slow_switch_threshold.as_millis()
+    )?;
+    match &self.slowest_switch {
+      Some((workspace_id, timing)) => write!(f, ", slowest switch to [{workspace_id}] took {timing}"),
+      None => Ok(()),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::common::Rect;
+
+  fn timing(store_and_hide_in_ms: u64, restore_in_ms: u64) -> WorkspaceSwitchTiming {
+    WorkspaceSwitchTiming {
+      store_and_hide: Duration::from_millis(store_and_hide_in_ms),
+      restore: Duration::from_millis(restore_in_ms),
+      ..WorkspaceSwitchTiming::default()
+    }
+  }
+
+  #[test]
+  fn record_window_keeps_the_slowest_window() {
+    let mut timing = WorkspaceSwitchTiming::default();
+
+    timing.record_window(Some((Window::new_test(1, Rect::default()), Duration::from_millis(20))));
+    timing.record_window(Some((Window::new_test(2, Rect::default()), Duration::from_millis(300))));
+    timing.record_window(Some((Window::new_test(3, Rect::default()), Duration::from_millis(100))));
+    timing.record_window(None);
+
+    let (window, duration) = timing.slowest_window.expect("Slowest window not recorded");
+    assert_eq!(window.handle.hwnd, 2);
+    assert_eq!(duration, Duration::from_millis(300));
+  }
+
+  #[test]
+  fn record_counts_switches_reaching_the_threshold_as_slow_and_keeps_the_slowest_switch() {
+    let mut latency = WorkspaceSwitchLatency::new(500);
+
+    assert!(!latency.record(PersistentWorkspaceId::new_test(1), &timing(100, 100)));
+    assert!(latency.record(PersistentWorkspaceId::new_test(2), &timing(100, 700)));
+    assert!(latency.record(PersistentWorkspaceId::new_test(1), &timing(250, 250)));
+
+    assert_eq!(latency.switch_count, 3);
+    assert_eq!(latency.slow_switch_count, 2);
+    let (workspace_id, slowest_timing) = latency.slowest_switch.expect("Slowest switch not recorded");
+    assert_eq!(workspace_id, PersistentWorkspaceId::new_test(2));
+    assert_eq!(slowest_timing.total(), Duration::from_millis(800));
+  }
+
+  #[test]
+  fn record_never_counts_switches_as_slow_if_threshold_is_zero() {
+    let mut latency = WorkspaceSwitchLatency::new(0);
+
+    assert!(!latency.record(PersistentWorkspaceId::new_test(1), &timing(5_000, 5_000)));
+    assert_eq!(latency.slow_switch_count, 0);
+    assert_eq!(latency.switch_count, 1);
+  }
+}
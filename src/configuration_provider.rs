@@ -1,12 +1,41 @@
-use crate::files::{FileManager, FileType};
+use crate::common::{CursorSpeed, Direction};
+use crate::files::{FileFormat, FileManager, FileType};
+use crate::utils::{EXCLUSION_SETTINGS_LOCK, MOUSE_BINDINGS_LOCK};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
 
 pub const WINDOW_MARGIN: &str = "window_margin";
 pub const ALLOW_SELECTING_SAME_CENTER_WINDOWS: &str = "allow_selecting_same_center_windows";
+pub const FOCUS_FALLBACK_POLICY: &str = "focus_fallback_policy";
+pub const SNAP_TO_THIRDS: &str = "snap_to_thirds";
+pub const MONITOR_ORDERING: &str = "monitor_ordering";
 pub const FORCE_USING_ADMIN_PRIVILEGES: &str = "force_using_admin_privileges";
 pub const ADDITIONAL_WORKSPACE_COUNT: &str = "additional_workspace_count";
 pub const ENABLE_FEATURES_USING_MOUSE: &str = "enable_features_using_mouse";
+pub const ENABLE_TOUCHPAD_GESTURES: &str = "enable_touchpad_gestures";
+pub const ENABLE_USAGE_METRICS: &str = "enable_usage_metrics";
+pub const ENABLE_EFFICIENCY_MODE: &str = "enable_efficiency_mode";
+pub const KEEP_ALWAYS_ON_TOP_WINDOWS_VISIBLE: &str = "keep_always_on_top_windows_visible";
+pub const PRESERVE_RELATIVE_WINDOW_POSITION: &str = "preserve_relative_window_position";
+pub const ENABLE_COMMAND_FILE: &str = "enable_command_file";
+pub const IPC_SERVER_PORT: &str = "ipc_server_port";
+pub const SKIP_NEAR_MAXIMISE_ANIMATION: &str = "skip_near_maximise_animation";
+pub const NOTIFY_ON_FAILED_COMMANDS: &str = "notify_on_failed_commands";
+pub const DATA_FILE_FORMAT: &str = "data_file_format";
 pub const DELAY_IN_MS_BEFORE_DRAGGING_IS_ALLOWED: &str = "delay_in_ms_before_dragging_is_allowed";
+pub const GRID_SNAPPING_CELL_SIZE_IN_PX: &str = "grid_snapping_cell_size_in_px";
+pub const MAGNETIC_EDGE_DISTANCE_IN_PX: &str = "magnetic_edge_distance_in_px";
+pub const SNAP_ZONE_DISTANCE_IN_PX: &str = "snap_zone_distance_in_px";
+pub const AUTO_SNAP_THRESHOLD_IN_PERCENT: &str = "auto_snap_threshold_in_percent";
+pub const EVENT_LOOP_INTERVAL_IN_MS: &str = "event_loop_interval_in_ms";
+pub const HEARTBEAT_INTERVAL_IN_S: &str = "heartbeat_interval_in_s";
+pub const STARTUP_DELAY_IN_MS: &str = "startup_delay_in_ms";
+pub const CLOSE_CONFIRMATION_TIMEOUT_IN_MS: &str = "close_confirmation_timeout_in_ms";
+pub const WORKSPACE_BADGES_DELAY_IN_MS: &str = "workspace_badges_delay_in_ms";
+pub const SLOW_WORKSPACE_SWITCH_THRESHOLD_IN_MS: &str = "slow_workspace_switch_threshold_in_ms";
 pub const ALLOW_MOVING_CURSOR_AFTER_OPEN_CLOSE_OR_MINIMISE: &str = "allow_moving_cursor_after_open_close_or_minimise";
 pub const SCROLLING_ANIMATION_DURATION_IN_MS: &str = "animation_duration_in_ms";
 pub const SCROLLING_RECONCILIATION_INTERVAL_IN_MS: &str = "reconciliation_interval_in_ms";
@@ -18,6 +47,13 @@ const DEFAULT_WINDOW_MARGIN_VALUE: i32 = 20;
 const DEFAULT_DELAY_IN_MS_BEFORE_DRAGGING_IS_ALLOWED: i32 = 750;
 const DEFAULT_SCROLLING_ANIMATION_DURATION_IN_MS: i32 = 120;
 const DEFAULT_SCROLLING_RECONCILIATION_INTERVAL_IN_MS: i32 = 250;
+const DEFAULT_PICTURE_IN_PICTURE_WIDTH: i32 = 480;
+const DEFAULT_PICTURE_IN_PICTURE_HEIGHT: i32 = 270;
+const DEFAULT_FOCUS_BORDER_COLOUR: &str = "#0078D4";
+const DEFAULT_FOCUS_BORDER_WIDTH_IN_PX: i32 = 4;
+const DEFAULT_AUTO_WINDOW_MARGIN_PERCENT_OF_MONITOR_WIDTH: f32 = 1.0;
+const DEFAULT_AUTO_WINDOW_MARGIN_MIN_IN_PX: i32 = 10;
+const DEFAULT_AUTO_WINDOW_MARGIN_MAX_IN_PX: i32 = 40;
 
 #[derive(Debug, Serialize, Deserialize, Default)]
 struct Configuration {
@@ -29,7 +65,35 @@ struct Configuration {
   #[serde(default)]
   scrolling_layout: ScrollingLayoutConfiguration,
   #[serde(default)]
+  picture_in_picture: PictureInPictureConfiguration,
+  #[serde(default)]
+  focus_border: FocusBorderConfiguration,
+  #[serde(default)]
+  mouse_bindings: MouseBindingsConfiguration,
+  #[serde(default)]
+  auto_window_margin: AutoWindowMarginConfiguration,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  monitor_workspace_count: Vec<MonitorWorkspaceCount>,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  workspace_fallback: Vec<WorkspaceFallback>,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  free_floating_workspace: Vec<FreeFloatingWorkspace>,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  tiled_workspace: Vec<TiledWorkspace>,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  do_not_disturb_workspace: Vec<DoNotDisturbWorkspace>,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  screen_sharing_workspace: Vec<ScreenSharingWorkspace>,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  workspace_numbering: Vec<WorkspaceNumbering>,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  cursor_speed: Vec<CursorSpeedProfile>,
+  #[serde(default)]
   pub hotkey: Vec<CustomHotkey>,
+  #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+  keybindings: BTreeMap<String, Vec<String>>,
+  #[serde(default)]
+  pub rule: Vec<WindowRule>,
   #[serde(default)]
   pub exclusion_settings: ExclusionSettings,
 }
@@ -46,8 +110,52 @@ struct GeneralConfiguration {
   enable_features_using_mouse: bool,
   #[serde(default = "default_delay_in_ms_before_dragging_is_allowed")]
   delay_in_ms_before_dragging_is_allowed: i32,
+  #[serde(default = "default_grid_snapping_cell_size_in_px")]
+  grid_snapping_cell_size_in_px: i32,
+  #[serde(default = "default_magnetic_edge_distance_in_px")]
+  magnetic_edge_distance_in_px: i32,
+  #[serde(default = "default_snap_zone_distance_in_px")]
+  snap_zone_distance_in_px: i32,
+  #[serde(default = "default_auto_snap_threshold_in_percent")]
+  auto_snap_threshold_in_percent: i32,
+  #[serde(default = "default_event_loop_interval_in_ms")]
+  event_loop_interval_in_ms: i32,
+  #[serde(default = "default_heartbeat_interval_in_s")]
+  heartbeat_interval_in_s: i32,
+  #[serde(default = "default_startup_delay_in_ms")]
+  startup_delay_in_ms: i32,
+  #[serde(default = "default_close_confirmation_timeout_in_ms")]
+  close_confirmation_timeout_in_ms: i32,
+  #[serde(default = "default_workspace_badges_delay_in_ms")]
+  workspace_badges_delay_in_ms: i32,
+  #[serde(default = "default_slow_workspace_switch_threshold_in_ms")]
+  slow_workspace_switch_threshold_in_ms: i32,
   #[serde(default = "default_allow_moving_cursor_after_close_or_minimise")]
   allow_moving_cursor_after_open_close_or_minimise: bool,
+  #[serde(default = "default_enable_touchpad_gestures")]
+  enable_touchpad_gestures: bool,
+  #[serde(default = "default_enable_usage_metrics")]
+  enable_usage_metrics: bool,
+  #[serde(default = "default_enable_efficiency_mode")]
+  enable_efficiency_mode: bool,
+  #[serde(default = "default_keep_always_on_top_windows_visible")]
+  keep_always_on_top_windows_visible: bool,
+  #[serde(default = "default_preserve_relative_window_position")]
+  preserve_relative_window_position: bool,
+  #[serde(default = "default_enable_command_file")]
+  enable_command_file: bool,
+  #[serde(default = "default_ipc_server_port")]
+  ipc_server_port: i32,
+  #[serde(default = "default_skip_near_maximise_animation")]
+  skip_near_maximise_animation: bool,
+  #[serde(default = "default_notify_on_failed_commands")]
+  notify_on_failed_commands: bool,
+  /// The format of the files in which Randolf stores the state of workspaces and usage metrics.
+  #[serde(default)]
+  data_file_format: FileFormat,
+  /// The language of the tray menu and notifications, e.g. `de`, or `None` to use the language of Windows.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  language: Option<String>,
 }
 
 fn default_window_margin() -> i32 {
@@ -128,6 +236,163 @@ fn validate_features_using_mouse(config_str: &str, configuration_provider: &mut
   }
 }
 
+fn default_enable_touchpad_gestures() -> bool {
+  false
+}
+
+fn validate_touchpad_gestures(config_str: &str, configuration_provider: &mut ConfigurationProvider) {
+  if !config_str.contains(ENABLE_TOUCHPAD_GESTURES) {
+    warn!(
+      "[{}] was missing; adding it now with default value: {}",
+      ENABLE_TOUCHPAD_GESTURES,
+      default_enable_touchpad_gestures()
+    );
+    configuration_provider.set_bool(ENABLE_TOUCHPAD_GESTURES, default_enable_touchpad_gestures());
+  }
+}
+
+fn default_enable_usage_metrics() -> bool {
+  false
+}
+
+fn validate_usage_metrics(config_str: &str, configuration_provider: &mut ConfigurationProvider) {
+  if !config_str.contains(ENABLE_USAGE_METRICS) {
+    warn!(
+      "[{}] was missing; adding it now with default value: {}",
+      ENABLE_USAGE_METRICS,
+      default_enable_usage_metrics()
+    );
+    configuration_provider.set_bool(ENABLE_USAGE_METRICS, default_enable_usage_metrics());
+  }
+}
+
+fn default_enable_efficiency_mode() -> bool {
+  false
+}
+
+fn validate_efficiency_mode(config_str: &str, configuration_provider: &mut ConfigurationProvider) {
+  if !config_str.contains(ENABLE_EFFICIENCY_MODE) {
+    warn!(
+      "[{}] was missing; adding it now with default value: {}",
+      ENABLE_EFFICIENCY_MODE,
+      default_enable_efficiency_mode()
+    );
+    configuration_provider.set_bool(ENABLE_EFFICIENCY_MODE, default_enable_efficiency_mode());
+  }
+}
+
+fn default_keep_always_on_top_windows_visible() -> bool {
+  true
+}
+
+fn validate_keep_always_on_top_windows_visible(config_str: &str, configuration_provider: &mut ConfigurationProvider) {
+  if !config_str.contains(KEEP_ALWAYS_ON_TOP_WINDOWS_VISIBLE) {
+    warn!(
+      "[{}] was missing; adding it now with default value: {}",
+      KEEP_ALWAYS_ON_TOP_WINDOWS_VISIBLE,
+      default_keep_always_on_top_windows_visible()
+    );
+    configuration_provider.set_bool(
+      KEEP_ALWAYS_ON_TOP_WINDOWS_VISIBLE,
+      default_keep_always_on_top_windows_visible(),
+    );
+  }
+}
+
+fn default_preserve_relative_window_position() -> bool {
+  false
+}
+
+fn validate_preserve_relative_window_position(config_str: &str, configuration_provider: &mut ConfigurationProvider) {
+  if !config_str.contains(PRESERVE_RELATIVE_WINDOW_POSITION) {
+    warn!(
+      "[{}] was missing; adding it now with default value: {}",
+      PRESERVE_RELATIVE_WINDOW_POSITION,
+      default_preserve_relative_window_position()
+    );
+    configuration_provider.set_bool(PRESERVE_RELATIVE_WINDOW_POSITION, default_preserve_relative_window_position());
+  }
+}
+
+fn default_enable_command_file() -> bool {
+  false
+}
+
+fn validate_enable_command_file(config_str: &str, configuration_provider: &mut ConfigurationProvider) {
+  if !config_str.contains(ENABLE_COMMAND_FILE) {
+    warn!(
+      "[{}] was missing; adding it now with default value: {}",
+      ENABLE_COMMAND_FILE,
+      default_enable_command_file()
+    );
+    configuration_provider.set_bool(ENABLE_COMMAND_FILE, default_enable_command_file());
+  }
+}
+
+fn default_ipc_server_port() -> i32 {
+  0
+}
+
+fn validate_ipc_server_port(config_str: &str, configuration_provider: &mut ConfigurationProvider) {
+  if !config_str.contains(IPC_SERVER_PORT) {
+    warn!(
+      "[{}] was missing; adding it now with default value: {}",
+      IPC_SERVER_PORT,
+      default_ipc_server_port()
+    );
+    configuration_provider.set_i32(IPC_SERVER_PORT, default_ipc_server_port());
+  } else if u16::try_from(configuration_provider.config.general.ipc_server_port).is_err() {
+    warn!(
+      "[{}] is not a valid port, setting to default value: {}",
+      IPC_SERVER_PORT,
+      default_ipc_server_port()
+    );
+    configuration_provider.set_i32(IPC_SERVER_PORT, default_ipc_server_port());
+  }
+}
+
+fn default_skip_near_maximise_animation() -> bool {
+  false
+}
+
+fn validate_skip_near_maximise_animation(config_str: &str, configuration_provider: &mut ConfigurationProvider) {
+  if !config_str.contains(SKIP_NEAR_MAXIMISE_ANIMATION) {
+    warn!(
+      "[{}] was missing; adding it now with default value: {}",
+      SKIP_NEAR_MAXIMISE_ANIMATION,
+      default_skip_near_maximise_animation()
+    );
+    configuration_provider.set_bool(SKIP_NEAR_MAXIMISE_ANIMATION, default_skip_near_maximise_animation());
+  }
+}
+
+fn validate_data_file_format(config_str: &str, configuration_provider: &mut ConfigurationProvider) {
+  if !config_str.contains(DATA_FILE_FORMAT) {
+    warn!(
+      "[{}] was missing; adding it now with default value: {:?}",
+      DATA_FILE_FORMAT,
+      FileFormat::default()
+    );
+    configuration_provider.config.general.data_file_format = FileFormat::default();
+    configuration_provider.save_config_or_log_error();
+  }
+}
+
+fn default_notify_on_failed_commands() -> bool {
+  false
+}
+
+fn validate_notify_on_failed_commands(config_str: &str, configuration_provider: &mut ConfigurationProvider) {
+  if !config_str.contains(NOTIFY_ON_FAILED_COMMANDS) {
+    warn!(
+      "[{}] was missing; adding it now with default value: {}",
+      NOTIFY_ON_FAILED_COMMANDS,
+      default_notify_on_failed_commands()
+    );
+    configuration_provider.set_bool(NOTIFY_ON_FAILED_COMMANDS, default_notify_on_failed_commands());
+  }
+}
+
 fn default_delay_in_ms_before_dragging_is_allowed() -> i32 {
   DEFAULT_DELAY_IN_MS_BEFORE_DRAGGING_IS_ALLOWED
 }
@@ -146,6 +411,232 @@ fn validate_delay_in_ms_before_dragging_is_allowed(config_str: &str, configurati
   }
 }
 
+fn default_grid_snapping_cell_size_in_px() -> i32 {
+  0
+}
+
+fn validate_grid_snapping_cell_size_in_px(config_str: &str, configuration_provider: &mut ConfigurationProvider) {
+  if !config_str.contains(GRID_SNAPPING_CELL_SIZE_IN_PX) {
+    warn!(
+      "[{}] was missing; adding it now with default value: {}",
+      GRID_SNAPPING_CELL_SIZE_IN_PX,
+      default_grid_snapping_cell_size_in_px()
+    );
+    configuration_provider.set_i32(GRID_SNAPPING_CELL_SIZE_IN_PX, default_grid_snapping_cell_size_in_px());
+  } else if configuration_provider.config.general.grid_snapping_cell_size_in_px < 0 {
+    warn!(
+      "[{}] is negative, setting to default value: {}",
+      GRID_SNAPPING_CELL_SIZE_IN_PX,
+      default_grid_snapping_cell_size_in_px()
+    );
+    configuration_provider.set_i32(GRID_SNAPPING_CELL_SIZE_IN_PX, default_grid_snapping_cell_size_in_px());
+  }
+}
+
+fn default_magnetic_edge_distance_in_px() -> i32 {
+  0
+}
+
+fn validate_magnetic_edge_distance_in_px(config_str: &str, configuration_provider: &mut ConfigurationProvider) {
+  if !config_str.contains(MAGNETIC_EDGE_DISTANCE_IN_PX) {
+    warn!(
+      "[{}] was missing; adding it now with default value: {}",
+      MAGNETIC_EDGE_DISTANCE_IN_PX,
+      default_magnetic_edge_distance_in_px()
+    );
+    configuration_provider.set_i32(MAGNETIC_EDGE_DISTANCE_IN_PX, default_magnetic_edge_distance_in_px());
+  } else if configuration_provider.config.general.magnetic_edge_distance_in_px < 0 {
+    warn!(
+      "[{}] is negative, setting to default value: {}",
+      MAGNETIC_EDGE_DISTANCE_IN_PX,
+      default_magnetic_edge_distance_in_px()
+    );
+    configuration_provider.set_i32(MAGNETIC_EDGE_DISTANCE_IN_PX, default_magnetic_edge_distance_in_px());
+  }
+}
+
+fn default_snap_zone_distance_in_px() -> i32 {
+  0
+}
+
+fn validate_snap_zone_distance_in_px(config_str: &str, configuration_provider: &mut ConfigurationProvider) {
+  if !config_str.contains(SNAP_ZONE_DISTANCE_IN_PX) {
+    warn!(
+      "[{}] was missing; adding it now with default value: {}",
+      SNAP_ZONE_DISTANCE_IN_PX,
+      default_snap_zone_distance_in_px()
+    );
+    configuration_provider.set_i32(SNAP_ZONE_DISTANCE_IN_PX, default_snap_zone_distance_in_px());
+  } else if configuration_provider.config.general.snap_zone_distance_in_px < 0 {
+    warn!(
+      "[{}] is negative, setting to default value: {}",
+      SNAP_ZONE_DISTANCE_IN_PX,
+      default_snap_zone_distance_in_px()
+    );
+    configuration_provider.set_i32(SNAP_ZONE_DISTANCE_IN_PX, default_snap_zone_distance_in_px());
+  }
+}
+
+fn default_auto_snap_threshold_in_percent() -> i32 {
+  0
+}
+
+fn validate_auto_snap_threshold_in_percent(config_str: &str, configuration_provider: &mut ConfigurationProvider) {
+  if !config_str.contains(AUTO_SNAP_THRESHOLD_IN_PERCENT) {
+    warn!(
+      "[{}] was missing; adding it now with default value: {}",
+      AUTO_SNAP_THRESHOLD_IN_PERCENT,
+      default_auto_snap_threshold_in_percent()
+    );
+    configuration_provider.set_i32(AUTO_SNAP_THRESHOLD_IN_PERCENT, default_auto_snap_threshold_in_percent());
+  } else if !(0..=100).contains(&configuration_provider.config.general.auto_snap_threshold_in_percent) {
+    warn!(
+      "[{}] is not between 0 and 100, setting to default value: {}",
+      AUTO_SNAP_THRESHOLD_IN_PERCENT,
+      default_auto_snap_threshold_in_percent()
+    );
+    configuration_provider.set_i32(AUTO_SNAP_THRESHOLD_IN_PERCENT, default_auto_snap_threshold_in_percent());
+  }
+}
+
+fn default_event_loop_interval_in_ms() -> i32 {
+  20
+}
+
+fn validate_event_loop_interval_in_ms(config_str: &str, configuration_provider: &mut ConfigurationProvider) {
+  if !config_str.contains(EVENT_LOOP_INTERVAL_IN_MS) {
+    warn!(
+      "[{}] was missing; adding it now with default value: {}",
+      EVENT_LOOP_INTERVAL_IN_MS,
+      default_event_loop_interval_in_ms()
+    );
+    configuration_provider.set_i32(EVENT_LOOP_INTERVAL_IN_MS, default_event_loop_interval_in_ms());
+  } else if configuration_provider.config.general.event_loop_interval_in_ms < 1 {
+    warn!(
+      "[{}] is less than 1, setting to default value: {}",
+      EVENT_LOOP_INTERVAL_IN_MS,
+      default_event_loop_interval_in_ms()
+    );
+    configuration_provider.set_i32(EVENT_LOOP_INTERVAL_IN_MS, default_event_loop_interval_in_ms());
+  }
+}
+
+fn default_heartbeat_interval_in_s() -> i32 {
+  5
+}
+
+fn validate_heartbeat_interval_in_s(config_str: &str, configuration_provider: &mut ConfigurationProvider) {
+  if !config_str.contains(HEARTBEAT_INTERVAL_IN_S) {
+    warn!(
+      "[{}] was missing; adding it now with default value: {}",
+      HEARTBEAT_INTERVAL_IN_S,
+      default_heartbeat_interval_in_s()
+    );
+    configuration_provider.set_i32(HEARTBEAT_INTERVAL_IN_S, default_heartbeat_interval_in_s());
+  } else if configuration_provider.config.general.heartbeat_interval_in_s < 0 {
+    warn!(
+      "[{}] is negative, setting to default value: {}",
+      HEARTBEAT_INTERVAL_IN_S,
+      default_heartbeat_interval_in_s()
+    );
+    configuration_provider.set_i32(HEARTBEAT_INTERVAL_IN_S, default_heartbeat_interval_in_s());
+  }
+}
+
+fn default_startup_delay_in_ms() -> i32 {
+  0
+}
+
+fn validate_startup_delay_in_ms(config_str: &str, configuration_provider: &mut ConfigurationProvider) {
+  if !config_str.contains(STARTUP_DELAY_IN_MS) {
+    warn!(
+      "[{}] was missing; adding it now with default value: {}",
+      STARTUP_DELAY_IN_MS,
+      default_startup_delay_in_ms()
+    );
+    configuration_provider.set_i32(STARTUP_DELAY_IN_MS, default_startup_delay_in_ms());
+  } else if configuration_provider.config.general.startup_delay_in_ms < 0 {
+    warn!(
+      "[{}] is negative, setting to default value: {}",
+      STARTUP_DELAY_IN_MS,
+      default_startup_delay_in_ms()
+    );
+    configuration_provider.set_i32(STARTUP_DELAY_IN_MS, default_startup_delay_in_ms());
+  }
+}
+
+fn default_close_confirmation_timeout_in_ms() -> i32 {
+  2000
+}
+
+fn validate_close_confirmation_timeout_in_ms(config_str: &str, configuration_provider: &mut ConfigurationProvider) {
+  if !config_str.contains(CLOSE_CONFIRMATION_TIMEOUT_IN_MS) {
+    warn!(
+      "[{}] was missing; adding it now with default value: {}",
+      CLOSE_CONFIRMATION_TIMEOUT_IN_MS,
+      default_close_confirmation_timeout_in_ms()
+    );
+    configuration_provider.set_i32(CLOSE_CONFIRMATION_TIMEOUT_IN_MS, default_close_confirmation_timeout_in_ms());
+  } else if configuration_provider.config.general.close_confirmation_timeout_in_ms < 1 {
+    warn!(
+      "[{}] is less than 1, setting to default value: {}",
+      CLOSE_CONFIRMATION_TIMEOUT_IN_MS,
+      default_close_confirmation_timeout_in_ms()
+    );
+    configuration_provider.set_i32(CLOSE_CONFIRMATION_TIMEOUT_IN_MS, default_close_confirmation_timeout_in_ms());
+  }
+}
+
+fn default_workspace_badges_delay_in_ms() -> i32 {
+  0
+}
+
+fn validate_workspace_badges_delay_in_ms(config_str: &str, configuration_provider: &mut ConfigurationProvider) {
+  if !config_str.contains(WORKSPACE_BADGES_DELAY_IN_MS) {
+    warn!(
+      "[{}] was missing; adding it now with default value: {}",
+      WORKSPACE_BADGES_DELAY_IN_MS,
+      default_workspace_badges_delay_in_ms()
+    );
+    configuration_provider.set_i32(WORKSPACE_BADGES_DELAY_IN_MS, default_workspace_badges_delay_in_ms());
+  } else if configuration_provider.config.general.workspace_badges_delay_in_ms < 0 {
+    warn!(
+      "[{}] is negative, setting to default value: {}",
+      WORKSPACE_BADGES_DELAY_IN_MS,
+      default_workspace_badges_delay_in_ms()
+    );
+    configuration_provider.set_i32(WORKSPACE_BADGES_DELAY_IN_MS, default_workspace_badges_delay_in_ms());
+  }
+}
+
+fn default_slow_workspace_switch_threshold_in_ms() -> i32 {
+  500
+}
+
+fn validate_slow_workspace_switch_threshold_in_ms(config_str: &str, configuration_provider: &mut ConfigurationProvider) {
+  if !config_str.contains(SLOW_WORKSPACE_SWITCH_THRESHOLD_IN_MS) {
+    warn!(
+      "[{}] was missing; adding it now with default value: {}",
+      SLOW_WORKSPACE_SWITCH_THRESHOLD_IN_MS,
+      default_slow_workspace_switch_threshold_in_ms()
+    );
+    configuration_provider.set_i32(
+      SLOW_WORKSPACE_SWITCH_THRESHOLD_IN_MS,
+      default_slow_workspace_switch_threshold_in_ms(),
+    );
+  } else if configuration_provider.config.general.slow_workspace_switch_threshold_in_ms < 0 {
+    warn!(
+      "[{}] is negative, setting to default value: {}",
+      SLOW_WORKSPACE_SWITCH_THRESHOLD_IN_MS,
+      default_slow_workspace_switch_threshold_in_ms()
+    );
+    configuration_provider.set_i32(
+      SLOW_WORKSPACE_SWITCH_THRESHOLD_IN_MS,
+      default_slow_workspace_switch_threshold_in_ms(),
+    );
+  }
+}
+
 fn default_allow_moving_cursor_after_close_or_minimise() -> bool {
   true
 }
@@ -175,20 +666,43 @@ impl Default for GeneralConfiguration {
       additional_workspace_count: default_additional_workspace_count(),
       enable_features_using_mouse: default_enable_features_using_mouse(),
       delay_in_ms_before_dragging_is_allowed: default_delay_in_ms_before_dragging_is_allowed(),
+      grid_snapping_cell_size_in_px: default_grid_snapping_cell_size_in_px(),
+      magnetic_edge_distance_in_px: default_magnetic_edge_distance_in_px(),
+      snap_zone_distance_in_px: default_snap_zone_distance_in_px(),
+      auto_snap_threshold_in_percent: default_auto_snap_threshold_in_percent(),
+      event_loop_interval_in_ms: default_event_loop_interval_in_ms(),
+      heartbeat_interval_in_s: default_heartbeat_interval_in_s(),
+      startup_delay_in_ms: default_startup_delay_in_ms(),
+      close_confirmation_timeout_in_ms: default_close_confirmation_timeout_in_ms(),
+      workspace_badges_delay_in_ms: default_workspace_badges_delay_in_ms(),
+      slow_workspace_switch_threshold_in_ms: default_slow_workspace_switch_threshold_in_ms(),
       allow_moving_cursor_after_open_close_or_minimise: default_allow_moving_cursor_after_close_or_minimise(),
+      enable_touchpad_gestures: default_enable_touchpad_gestures(),
+      enable_usage_metrics: default_enable_usage_metrics(),
+      keep_always_on_top_windows_visible: default_keep_always_on_top_windows_visible(),
+      preserve_relative_window_position: default_preserve_relative_window_position(),
+      enable_command_file: default_enable_command_file(),
+      ipc_server_port: default_ipc_server_port(),
+      skip_near_maximise_animation: default_skip_near_maximise_animation(),
+      notify_on_failed_commands: default_notify_on_failed_commands(),
+      data_file_format: FileFormat::default(),
+      enable_efficiency_mode: default_enable_efficiency_mode(),
+      language: None,
     }
   }
 }
 
-#[derive(Debug, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 struct LayoutConfiguration {
   #[serde(default)]
   default: Layout,
+  #[serde(default)]
+  monitor_ordering: MonitorOrdering,
   #[serde(default, skip_serializing_if = "Vec::is_empty")]
   monitor: Vec<MonitorLayoutConfiguration>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct MonitorLayoutConfiguration {
   id: String,
   mode: Layout,
@@ -205,6 +719,20 @@ pub enum Layout {
   Scrolling,
 }
 
+/// Strategy for ordering monitors, which determines the order of workspaces e.g. for workspace hotkeys. Existing
+/// configuration files without it are given [`MonitorOrdering::Centres`], the ordering of earlier versions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MonitorOrdering {
+  /// Rows of vertically overlapping monitors from top to bottom, each ordered from left to right.
+  #[default]
+  Rows,
+  /// Columns of horizontally overlapping monitors from left to right, each ordered from top to bottom.
+  Columns,
+  /// Monitor centres from left to right and, for monitors with the same horizontal centre, from top to bottom.
+  Centres,
+}
+
 fn repair_obsolete_empty_monitor_list(config_str: &str) -> Option<String> {
   if !config_str.contains("[[layout.monitor]]") || !config_str.lines().any(|line| line.trim() == "monitor = []") {
     return None;
@@ -219,8 +747,12 @@ fn repair_obsolete_empty_monitor_list(config_str: &str) -> Option<String> {
 }
 
 fn validate_layout_sections(config_str: &str, configuration_provider: &mut ConfigurationProvider) {
-  let missing_layout = !config_str.contains("[layout]");
-  let missing_spatial = !config_str.contains("[spatial_layout]");
+  if !config_str.contains(MONITOR_ORDERING) {
+    info!("[{MONITOR_ORDERING}] is missing, keeping the monitor ordering of earlier versions");
+    configuration_provider.config.layout.monitor_ordering = MonitorOrdering::Centres;
+  }
+  let missing_layout = !config_str.contains("[layout]") || !config_str.contains(MONITOR_ORDERING);
+  let missing_spatial = !config_str.contains("[spatial_layout]") || !config_str.contains(FOCUS_FALLBACK_POLICY);
   let missing_scrolling = !config_str.contains("[scrolling_layout]")
     || !config_str.contains(SCROLLING_ANIMATION_DURATION_IN_MS)
     || !config_str.contains(SCROLLING_RECONCILIATION_INTERVAL_IN_MS);
@@ -250,6 +782,12 @@ fn validate_layout_sections(config_str: &str, configuration_provider: &mut Confi
 struct SpatialLayoutConfiguration {
   #[serde(default = "default_allow_selecting_same_center_windows")]
   allow_selecting_same_center_windows: bool,
+  #[serde(default)]
+  focus_fallback_policy: FocusFallbackPolicy,
+  #[serde(default)]
+  focus_cycle_order: FocusCycleOrder,
+  #[serde(default)]
+  snap_to_thirds: bool,
 }
 
 fn default_allow_selecting_same_center_windows() -> bool {
@@ -260,10 +798,37 @@ impl Default for SpatialLayoutConfiguration {
   fn default() -> Self {
     Self {
       allow_selecting_same_center_windows: default_allow_selecting_same_center_windows(),
+      focus_fallback_policy: FocusFallbackPolicy::default(),
+      focus_cycle_order: FocusCycleOrder::default(),
+      snap_to_thirds: false,
     }
   }
 }
 
+/// Strategy for choosing the window to focus after the foreground window was closed or minimised.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FocusFallbackPolicy {
+  /// The visible window whose centre is closest to the cursor.
+  #[default]
+  Closest,
+  /// The visible window with the largest area.
+  Largest,
+  /// The visible window that was focused most recently, falling back to the closest window.
+  MostRecentlyUsed,
+}
+
+/// The order in which the windows of a workspace are focused when cycling through them.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FocusCycleOrder {
+  /// From left to right and, for windows with the same left edge, from top to bottom.
+  #[default]
+  Spatial,
+  /// From the most to the least recently focused window, followed by windows that were never focused.
+  MostRecentlyUsed,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct ScrollingLayoutConfiguration {
   #[serde(default = "default_scrolling_animation_duration_in_ms")]
@@ -289,30 +854,651 @@ impl Default for ScrollingLayoutConfiguration {
   }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CustomHotkey {
-  pub name: String,
-  pub path: String,
-  pub hotkey: String,
-  pub execute_as_admin: bool,
+/// Settings for the picture-in-picture command, which shrinks the foreground window and pins it to a corner.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PictureInPictureConfiguration {
+  #[serde(default = "default_picture_in_picture_width")]
+  pub width: i32,
+  #[serde(default = "default_picture_in_picture_height")]
+  pub height: i32,
+  #[serde(default)]
+  pub corner: Corner,
 }
 
-/// Settings for excluding certain windows from being managed by the application. This is useful for ignoring
-/// system windows or other applications that should not be affected by this application at all i.e. they should not
-/// be moved, selected, etc.
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ExclusionSettings {
-  #[serde(default = "default_excluded_window_titles")]
-  pub window_titles: Vec<String>,
-  #[serde(default = "default_excluded_window_classes")]
-  pub window_class_names: Vec<String>,
+fn default_picture_in_picture_width() -> i32 {
+  DEFAULT_PICTURE_IN_PICTURE_WIDTH
 }
 
-impl Default for ExclusionSettings {
+fn default_picture_in_picture_height() -> i32 {
+  DEFAULT_PICTURE_IN_PICTURE_HEIGHT
+}
+
+impl Default for PictureInPictureConfiguration {
   fn default() -> Self {
     Self {
-      window_titles: default_excluded_window_titles(),
-      window_class_names: default_excluded_window_classes(),
+      width: default_picture_in_picture_width(),
+      height: default_picture_in_picture_height(),
+      corner: Corner::default(),
+    }
+  }
+}
+
+/// A corner of a monitor's work area.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Corner {
+  TopLeft,
+  TopRight,
+  BottomLeft,
+  #[default]
+  BottomRight,
+}
+
+impl Corner {
+  /// Returns the horizontal and vertical [`Direction`] pointing towards this corner.
+  pub fn to_directions(self) -> (Direction, Direction) {
+    match self {
+      Corner::TopLeft => (Direction::Left, Direction::Up),
+      Corner::TopRight => (Direction::Right, Direction::Up),
+      Corner::BottomLeft => (Direction::Left, Direction::Down),
+      Corner::BottomRight => (Direction::Right, Direction::Down),
+    }
+  }
+}
+
+fn validate_picture_in_picture(config_str: &str, configuration_provider: &mut ConfigurationProvider) {
+  let picture_in_picture = &mut configuration_provider.config.picture_in_picture;
+  let is_missing = !config_str.contains("[picture_in_picture]");
+  let is_invalid = picture_in_picture.width <= 0 || picture_in_picture.height <= 0;
+  if is_invalid {
+    warn!(
+      "Picture-in-picture size [{}x{}] is invalid, setting it to default value: {}x{}",
+      picture_in_picture.width,
+      picture_in_picture.height,
+      DEFAULT_PICTURE_IN_PICTURE_WIDTH,
+      DEFAULT_PICTURE_IN_PICTURE_HEIGHT
+    );
+    picture_in_picture.width = DEFAULT_PICTURE_IN_PICTURE_WIDTH;
+    picture_in_picture.height = DEFAULT_PICTURE_IN_PICTURE_HEIGHT;
+  }
+  if is_missing || is_invalid {
+    configuration_provider.save_config_or_log_error();
+  }
+}
+
+/// Settings for the border that is drawn around the foreground window, which makes it easy to see which window has
+/// focus on large or multi-monitor setups.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FocusBorderConfiguration {
+  #[serde(default)]
+  pub enabled: bool,
+  /// The colour of the border, given as `#RRGGBB`.
+  #[serde(default = "default_focus_border_colour")]
+  pub colour: String,
+  #[serde(default = "default_focus_border_width_in_px")]
+  pub width_in_px: i32,
+}
+
+fn default_focus_border_colour() -> String {
+  DEFAULT_FOCUS_BORDER_COLOUR.to_string()
+}
+
+fn default_focus_border_width_in_px() -> i32 {
+  DEFAULT_FOCUS_BORDER_WIDTH_IN_PX
+}
+
+impl Default for FocusBorderConfiguration {
+  fn default() -> Self {
+    Self {
+      enabled: false,
+      colour: default_focus_border_colour(),
+      width_in_px: default_focus_border_width_in_px(),
+    }
+  }
+}
+
+impl FocusBorderConfiguration {
+  /// Returns the colour of the border as a `COLORREF` (i.e. `0x00BBGGRR`), falling back to the default colour if the
+  /// configured one is not a valid `#RRGGBB` value.
+  pub fn get_colour(&self) -> u32 {
+    parse_colour(&self.colour)
+      .or_else(|| parse_colour(DEFAULT_FOCUS_BORDER_COLOUR))
+      .unwrap_or_default()
+  }
+}
+
+fn validate_focus_border(config_str: &str, configuration_provider: &mut ConfigurationProvider) {
+  let focus_border = &mut configuration_provider.config.focus_border;
+  let is_missing = !config_str.contains("[focus_border]");
+  let is_colour_invalid = parse_colour(&focus_border.colour).is_none();
+  if is_colour_invalid {
+    warn!(
+      "Focus border colour [{}] is not a valid #RRGGBB value, setting it to default value: {DEFAULT_FOCUS_BORDER_COLOUR}",
+      focus_border.colour
+    );
+    focus_border.colour = default_focus_border_colour();
+  }
+  let is_width_invalid = focus_border.width_in_px <= 0;
+  if is_width_invalid {
+    warn!(
+      "Focus border width [{}] is invalid, setting it to default value: {DEFAULT_FOCUS_BORDER_WIDTH_IN_PX}",
+      focus_border.width_in_px
+    );
+    focus_border.width_in_px = DEFAULT_FOCUS_BORDER_WIDTH_IN_PX;
+  }
+  if is_missing || is_colour_invalid || is_width_invalid {
+    configuration_provider.save_config_or_log_error();
+  }
+}
+
+/// Settings for moving and resizing windows with the mouse (see `enable_features_using_mouse`), i.e. which keys must be
+/// held down and which mouse buttons move or resize the window under the cursor.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MouseBindingsConfiguration {
+  /// The keys that must all be held down to move or resize windows with the mouse.
+  #[serde(default = "default_mouse_bindings_modifier")]
+  pub modifier: Vec<ModifierKey>,
+  #[serde(default = "default_mouse_bindings_move_button")]
+  pub move_button: MouseButton,
+  #[serde(default = "default_mouse_bindings_resize_button")]
+  pub resize_button: MouseButton,
+}
+
+fn default_mouse_bindings_modifier() -> Vec<ModifierKey> {
+  vec![ModifierKey::Win]
+}
+
+fn default_mouse_bindings_move_button() -> MouseButton {
+  MouseButton::Left
+}
+
+fn default_mouse_bindings_resize_button() -> MouseButton {
+  MouseButton::Right
+}
+
+impl Default for MouseBindingsConfiguration {
+  fn default() -> Self {
+    Self {
+      modifier: default_mouse_bindings_modifier(),
+      move_button: default_mouse_bindings_move_button(),
+      resize_button: default_mouse_bindings_resize_button(),
+    }
+  }
+}
+
+impl MouseBindingsConfiguration {
+  /// Returns `true` if `key` is one of the keys that must be held down to move or resize windows with the mouse.
+  pub fn is_modifier(&self, key: ModifierKey) -> bool {
+    self.modifier.contains(&key)
+  }
+}
+
+/// A key that can be held down to move or resize windows with the mouse. Either the left or the right variant of the
+/// key can be used.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModifierKey {
+  Win,
+  Alt,
+  Ctrl,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MouseButton {
+  Left,
+  Right,
+  Middle,
+}
+
+fn validate_mouse_bindings(config_str: &str, configuration_provider: &mut ConfigurationProvider) {
+  let mouse_bindings = &mut configuration_provider.config.mouse_bindings;
+  let is_missing = !config_str.contains("[mouse_bindings]");
+  let is_modifier_invalid = mouse_bindings.modifier.is_empty();
+  if is_modifier_invalid {
+    warn!(
+      "Mouse bindings modifier is empty, setting it to default value: {:?}",
+      default_mouse_bindings_modifier()
+    );
+    mouse_bindings.modifier = default_mouse_bindings_modifier();
+  }
+  let are_buttons_invalid = mouse_bindings.move_button == mouse_bindings.resize_button;
+  if are_buttons_invalid {
+    warn!(
+      "Mouse bindings use [{:?}] to both move and resize windows, setting them to default values: {:?} and {:?}",
+      mouse_bindings.move_button,
+      default_mouse_bindings_move_button(),
+      default_mouse_bindings_resize_button()
+    );
+    mouse_bindings.move_button = default_mouse_bindings_move_button();
+    mouse_bindings.resize_button = default_mouse_bindings_resize_button();
+  }
+  if is_missing || is_modifier_invalid || are_buttons_invalid {
+    configuration_provider.save_config_or_log_error();
+  }
+}
+
+/// Converts a `#RRGGBB` colour to a `COLORREF` (i.e. `0x00BBGGRR`), returning `None` if it is not a valid colour.
+fn parse_colour(colour: &str) -> Option<u32> {
+  let rgb = colour
+    .strip_prefix('#')
+    .filter(|hex| hex.len() == 6)
+    .and_then(|hex| u32::from_str_radix(hex, 16).ok())?;
+  let (red, green, blue) = ((rgb >> 16) & 0xFF, (rgb >> 8) & 0xFF, rgb & 0xFF);
+
+  Some((blue << 16) | (green << 8) | red)
+}
+
+/// Settings for scaling the window margin with the resolution of each monitor, so that the same configuration works on
+/// monitors with very different resolutions. Margins of individual monitors can be overridden.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AutoWindowMarginConfiguration {
+  #[serde(default)]
+  enabled: bool,
+  #[serde(default = "default_auto_window_margin_percent_of_monitor_width")]
+  percent_of_monitor_width: f32,
+  #[serde(default = "default_auto_window_margin_min_in_px")]
+  min_in_px: i32,
+  #[serde(default = "default_auto_window_margin_max_in_px")]
+  max_in_px: i32,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  monitor: Vec<MonitorWindowMarginConfiguration>,
+}
+
+/// The window margin of a monitor, which takes precedence over both `window_margin` and the automatic margin.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct MonitorWindowMarginConfiguration {
+  id: String,
+  margin: i32,
+}
+
+fn default_auto_window_margin_percent_of_monitor_width() -> f32 {
+  DEFAULT_AUTO_WINDOW_MARGIN_PERCENT_OF_MONITOR_WIDTH
+}
+
+fn default_auto_window_margin_min_in_px() -> i32 {
+  DEFAULT_AUTO_WINDOW_MARGIN_MIN_IN_PX
+}
+
+fn default_auto_window_margin_max_in_px() -> i32 {
+  DEFAULT_AUTO_WINDOW_MARGIN_MAX_IN_PX
+}
+
+impl Default for AutoWindowMarginConfiguration {
+  fn default() -> Self {
+    Self {
+      enabled: false,
+      percent_of_monitor_width: default_auto_window_margin_percent_of_monitor_width(),
+      min_in_px: default_auto_window_margin_min_in_px(),
+      max_in_px: default_auto_window_margin_max_in_px(),
+      monitor: vec![],
+    }
+  }
+}
+
+fn validate_auto_window_margin(config_str: &str, configuration_provider: &mut ConfigurationProvider) {
+  let auto_window_margin = &mut configuration_provider.config.auto_window_margin;
+  let is_missing = !config_str.contains("[auto_window_margin]");
+  let is_percentage_invalid =
+    !auto_window_margin.percent_of_monitor_width.is_finite() || auto_window_margin.percent_of_monitor_width <= 0.0;
+  if is_percentage_invalid {
+    warn!(
+      "Automatic window margin percentage [{}] is invalid, setting it to default value: {DEFAULT_AUTO_WINDOW_MARGIN_PERCENT_OF_MONITOR_WIDTH}",
+      auto_window_margin.percent_of_monitor_width
+    );
+    auto_window_margin.percent_of_monitor_width = DEFAULT_AUTO_WINDOW_MARGIN_PERCENT_OF_MONITOR_WIDTH;
+  }
+  let is_range_invalid = auto_window_margin.min_in_px < 0 || auto_window_margin.max_in_px < auto_window_margin.min_in_px;
+  if is_range_invalid {
+    warn!(
+      "Automatic window margin range [{}-{}] is invalid, setting it to default value: {}-{}",
+      auto_window_margin.min_in_px,
+      auto_window_margin.max_in_px,
+      DEFAULT_AUTO_WINDOW_MARGIN_MIN_IN_PX,
+      DEFAULT_AUTO_WINDOW_MARGIN_MAX_IN_PX
+    );
+    auto_window_margin.min_in_px = DEFAULT_AUTO_WINDOW_MARGIN_MIN_IN_PX;
+    auto_window_margin.max_in_px = DEFAULT_AUTO_WINDOW_MARGIN_MAX_IN_PX;
+  }
+  let invalid_overrides = auto_window_margin
+    .monitor
+    .iter()
+    .filter(|override_| override_.margin < 0)
+    .map(|override_| override_.id.clone())
+    .collect::<Vec<_>>();
+  if !invalid_overrides.is_empty() {
+    warn!("Ignoring negative window margin of monitor(s) {invalid_overrides:?}");
+    auto_window_margin.monitor.retain(|override_| override_.margin >= 0);
+  }
+  if is_missing || is_percentage_invalid || is_range_invalid || !invalid_overrides.is_empty() {
+    configuration_provider.save_config_or_log_error();
+  }
+}
+
+/// Records how many workspaces a monitor other than the primary monitor has in addition to its first workspace, so that
+/// workspaces created on it are recreated after a restart. Updated whenever a workspace is created or deleted on the
+/// monitor. The primary monitor uses `additional_workspace_count` in the `[general]` section instead.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorWorkspaceCount {
+  /// The device name of the monitor, e.g. `\\.\DISPLAY2`.
+  pub monitor: String,
+  pub additional_workspace_count: i32,
+}
+
+fn validate_monitor_workspace_counts(configuration_provider: &mut ConfigurationProvider) {
+  for monitor_workspace_count in &mut configuration_provider.config.monitor_workspace_count {
+    if !(0..=8).contains(&monitor_workspace_count.additional_workspace_count) {
+      warn!(
+        "[{ADDITIONAL_WORKSPACE_COUNT}] of monitor [{}] is not between 0 and 8, setting it to 0",
+        monitor_workspace_count.monitor
+      );
+      monitor_workspace_count.additional_workspace_count = 0;
+    }
+  }
+}
+
+/// Selects a workspace, or all workspaces of a monitor, in the settings that only apply to certain workspaces.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceSelector {
+  /// The device name of the monitor the workspace belongs to, e.g. `\\.\DISPLAY2`.
+  pub monitor: String,
+  /// The number of the workspace on that monitor. Applies to all workspaces of the monitor if omitted.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub workspace: Option<usize>,
+}
+
+impl WorkspaceSelector {
+  /// Returns `true` if the given workspace on the monitor with the given device name is selected.
+  pub fn applies_to(&self, monitor_id: &str, workspace: usize) -> bool {
+    self.monitor == monitor_id && self.workspace.is_none_or(|w| w == workspace)
+  }
+}
+
+/// Specifies the monitor on which a workspace is shown while its own monitor is disconnected. Without a fallback, the
+/// workspace becomes unreachable until its monitor is reconnected.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceFallback {
+  #[serde(flatten)]
+  pub selector: WorkspaceSelector,
+  /// The device name of the monitor to use instead or `primary`, which resolves to the current primary monitor.
+  pub fallback: String,
+}
+
+/// Marks a workspace as free-floating. While such a workspace is active, Randolf does not snap, resize, or arrange any
+/// windows on its monitor and does not apply window rules to them, so the workspace only acts as a container that can
+/// be switched to and from. Useful for legacy applications that misbehave when resized.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FreeFloatingWorkspace {
+  #[serde(flatten)]
+  pub selector: WorkspaceSelector,
+}
+
+/// Arranges the windows of a workspace automatically, either in a master-stack layout or as a binary space partition
+/// (see [`TilingArrangement`]). The workspace is re-tiled whenever a window is added to or removed from it. Only applies
+/// to monitors using the spatial layout.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TiledWorkspace {
+  #[serde(flatten)]
+  pub selector: WorkspaceSelector,
+  /// How the windows are arranged.
+  #[serde(default)]
+  pub arrangement: TilingArrangement,
+  /// The share of the width of the work area that the master window takes up. Only used by the master-stack arrangement.
+  #[serde(default = "default_master_width_in_percent")]
+  pub master_width_in_percent: i32,
+}
+
+/// How the windows of a tiled workspace are arranged.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TilingArrangement {
+  /// The first window fills the master area on the left and all other windows are stacked on top of each other on the
+  /// right.
+  #[default]
+  MasterStack,
+  /// Each new window splits the region of the focused window in half, along the longer side of the region.
+  Bsp,
+}
+
+fn default_master_width_in_percent() -> i32 {
+  60
+}
+
+/// Marks a workspace as do-not-disturb. While such a workspace is active, Randolf takes the foreground back from
+/// applications that steal it from one of the workspace's windows without any user input, and logs them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DoNotDisturbWorkspace {
+  #[serde(flatten)]
+  pub selector: WorkspaceSelector,
+}
+
+/// Marks a workspace on which screens are shared or presentations are given. While such a workspace is active, Randolf
+/// pauses moving windows and the cursor as long as a visible window on its monitor that matches one of `class_names`
+/// covers the whole monitor, regardless of which window is in the foreground, so that nothing jumps around while the
+/// audience is watching.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScreenSharingWorkspace {
+  #[serde(flatten)]
+  pub selector: WorkspaceSelector,
+  /// The class names of the windows that pause Randolf while they are full-screen. Defaults to the meeting windows of
+  /// Zoom and Microsoft Teams and the slide show window of PowerPoint.
+  #[serde(default = "default_screen_sharing_class_names")]
+  pub class_names: Vec<String>,
+}
+
+fn default_screen_sharing_class_names() -> Vec<String> {
+  [
+    "ZPContentViewWndClass",
+    "ConfMultiTabContentWndClass",
+    "TeamsWebView",
+    "screenClass",
+  ]
+  .map(String::from)
+  .to_vec()
+}
+
+fn validate_tiled_workspaces(configuration_provider: &mut ConfigurationProvider) {
+  for tiled_workspace in &mut configuration_provider.config.tiled_workspace {
+    if !(10..=90).contains(&tiled_workspace.master_width_in_percent) {
+      warn!(
+        "[master_width_in_percent] of tiled workspace on monitor [{}] is not between 10 and 90, using default value: {}",
+        tiled_workspace.selector.monitor,
+        default_master_width_in_percent()
+      );
+      tiled_workspace.master_width_in_percent = default_master_width_in_percent();
+    }
+  }
+}
+
+/// Assigns the numbers used by the workspace hotkeys to the workspaces of a monitor, e.g. so that `Win` + `4` always
+/// switches to the first workspace of the second monitor, regardless of how many workspaces the first monitor has.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceNumbering {
+  /// The device name of the monitor, e.g. `\\.\DISPLAY2`, or `primary`, which resolves to the current primary monitor.
+  pub monitor: String,
+  /// The number of the first workspace of the monitor. Its other workspaces use the numbers that follow.
+  pub first_number: usize,
+}
+
+impl WorkspaceNumbering {
+  /// Returns `true` if this numbering applies to the monitor with the given device name.
+  pub fn applies_to(&self, monitor_id: &str, is_primary_monitor: bool) -> bool {
+    self.monitor == monitor_id || (self.monitor == "primary" && is_primary_monitor)
+  }
+}
+
+fn validate_workspace_numbering(configuration_provider: &mut ConfigurationProvider) {
+  configuration_provider.config.workspace_numbering.retain(|numbering| {
+    if numbering.first_number == 0 {
+      warn!(
+        "Ignoring workspace numbering of monitor [{}] because workspace numbers start at 1",
+        numbering.monitor
+      );
+    }
+    numbering.first_number > 0
+  });
+}
+
+/// The speed of the cursor while it is on a monitor, e.g. a lower speed on a high-DPI monitor, so that moving the mouse
+/// by the same distance feels the same on every monitor. Applied whenever Randolf moves the cursor to the monitor.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CursorSpeedProfile {
+  /// The device name of the monitor, e.g. `\\.\DISPLAY2`, or `primary`, which resolves to the current primary monitor.
+  pub monitor: String,
+  /// The speed of the cursor from `1` (slowest) to `20` (fastest).
+  pub speed: u32,
+  /// Whether Windows accelerates the cursor ("Enhance pointer precision"). Keeps the setting of Windows if omitted.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub enhance_pointer_precision: Option<bool>,
+}
+
+impl CursorSpeedProfile {
+  /// Returns the cursor speed of this profile, keeping the acceleration of the given cursor speed unless this profile
+  /// specifies it.
+  pub fn apply_to(&self, cursor_speed: CursorSpeed) -> CursorSpeed {
+    cursor_speed.with(
+      self.speed,
+      self.enhance_pointer_precision.unwrap_or(cursor_speed.is_accelerated()),
+    )
+  }
+}
+
+fn validate_cursor_speed_profiles(configuration_provider: &mut ConfigurationProvider) {
+  configuration_provider.config.cursor_speed.retain(|profile| {
+    let is_valid = (CursorSpeed::MINIMUM_SPEED..=CursorSpeed::MAXIMUM_SPEED).contains(&profile.speed);
+    if !is_valid {
+      warn!(
+        "Ignoring cursor speed of monitor [{}] because [{}] is not between {} and {}",
+        profile.monitor,
+        profile.speed,
+        CursorSpeed::MINIMUM_SPEED,
+        CursorSpeed::MAXIMUM_SPEED
+      );
+    }
+    is_valid
+  });
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CustomHotkey {
+  pub name: String,
+  pub path: String,
+  pub hotkey: String,
+  pub execute_as_admin: bool,
+}
+
+/// A rule that applies one or more actions to windows matching all of its criteria. A rule without any criteria does
+/// not match any window.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct WindowRule {
+  /// Matches windows with exactly this class name.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub class_name: Option<String>,
+  /// Matches windows whose title contains this text.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub title_contains: Option<String>,
+  /// Matches windows that belong to a process running with (if `true`) or without (if `false`) admin privileges.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub is_elevated: Option<bool>,
+  /// Near-maximises matching windows when they are shown for the first time.
+  #[serde(default)]
+  pub auto_near_maximise: bool,
+  /// Colours the border of matching windows when they are shown for the first time, given as `#RRGGBB`.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub border_colour: Option<String>,
+  /// Pauses moving windows and the cursor while a matching window is in the foreground, e.g. while sharing the screen.
+  #[serde(default)]
+  pub pause_while_foreground: bool,
+  /// The number of pixels by which the size of matching windows may deviate from the expected size, e.g. for terminals
+  /// which resize to multiples of their character cell size.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub size_tolerance_in_px: Option<i32>,
+  /// Requires pressing the close hotkey a second time to close matching windows, e.g. to protect unsaved work.
+  #[serde(default)]
+  pub confirm_before_close: bool,
+  /// Moves matching windows to this workspace of `monitor` when they are shown for the first time.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub workspace: Option<usize>,
+  /// The device name of the monitor of `workspace`, e.g. `\\.\DISPLAY2`, or `primary`, which is the default.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub monitor: Option<String>,
+}
+
+impl WindowRule {
+  /// Returns `true` if the rule has at least one criterion and the window matches all of them.
+  pub fn matches(&self, title: &str, class_name: &str, is_elevated: bool) -> bool {
+    if self.class_name.is_none() && self.title_contains.is_none() && self.is_elevated.is_none() {
+      return false;
+    }
+    let is_class_name_match = self.class_name.as_ref().is_none_or(|expected| expected == class_name);
+    let is_title_match = self
+      .title_contains
+      .as_ref()
+      .is_none_or(|expected| title.contains(expected.as_str()));
+    let is_elevated_match = self.is_elevated.is_none_or(|expected| expected == is_elevated);
+
+    is_class_name_match && is_title_match && is_elevated_match
+  }
+
+  /// Returns the border colour as a `COLORREF` (i.e. `0x00BBGGRR`), if it is set and a valid `#RRGGBB` value.
+  pub fn get_border_colour(&self) -> Option<u32> {
+    let colour = self.border_colour.as_ref()?;
+    let parsed = parse_colour(colour);
+    if parsed.is_none() {
+      warn!("Ignoring border colour [{colour}] of window rule because it is not a valid #RRGGBB value");
+    }
+
+    parsed
+  }
+
+  /// Returns `true` if the rule moves matching windows to the workspace with the given number on the monitor with the
+  /// given device name.
+  pub fn moves_to_workspace(&self, monitor_id: &str, is_primary_monitor: bool, workspace: usize) -> bool {
+    let monitor = self.monitor.as_deref().unwrap_or("primary");
+    self.workspace == Some(workspace) && (monitor == monitor_id || (monitor == "primary" && is_primary_monitor))
+  }
+
+  /// Returns the size tolerance, if it is set and not negative.
+  pub fn get_size_tolerance_in_px(&self) -> Option<i32> {
+    let tolerance = self.size_tolerance_in_px?;
+    if tolerance < 0 {
+      warn!("Ignoring size tolerance [{tolerance}] of window rule because it is negative");
+      return None;
+    }
+
+    Some(tolerance)
+  }
+}
+
+/// Settings for excluding certain windows from being managed by the application. This is useful for ignoring
+/// system windows or other applications that should not be affected by this application at all i.e. they should not
+/// be moved, selected, etc.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExclusionSettings {
+  #[serde(default = "default_excluded_window_titles")]
+  pub window_titles: Vec<String>,
+  #[serde(default = "default_excluded_window_classes")]
+  pub window_class_names: Vec<String>,
+  #[serde(default = "default_ignore_elevated_windows")]
+  pub ignore_elevated_windows: bool,
+  /// Class names of tool windows (e.g. floating docks) owned by another window which are managed like any other window,
+  /// instead of following their owner.
+  #[serde(default = "default_managed_tool_window_classes")]
+  pub managed_tool_window_classes: Vec<String>,
+  /// Whether windows that the user stopped managing with the `toggle_manage_window` hotkey remain ignored after Randolf
+  /// has restarted.
+  #[serde(default = "default_persist_ignored_windows")]
+  pub persist_ignored_windows: bool,
+}
+
+impl Default for ExclusionSettings {
+  fn default() -> Self {
+    Self {
+      window_titles: default_excluded_window_titles(),
+      window_class_names: default_excluded_window_classes(),
+      ignore_elevated_windows: default_ignore_elevated_windows(),
+      managed_tool_window_classes: default_managed_tool_window_classes(),
+      persist_ignored_windows: default_persist_ignored_windows(),
     }
   }
 }
@@ -360,55 +1546,312 @@ fn validate_excluded_window_classes(config_str: &str, configuration_provider: &m
   }
 }
 
-pub struct ConfigurationProvider {
-  file_manager: FileManager<Configuration>,
-  config: Configuration,
+fn default_ignore_elevated_windows() -> bool {
+  false
 }
 
-impl ConfigurationProvider {
-  pub fn new() -> Self {
-    let mut file_manager = FileManager::new(CONFIGURATION_FILE_NAME, FileType::Config);
-    file_manager.set_content_prefix(CONFIGURATION_FILE_PREFIX);
-
-    Self::new_with(file_manager)
+fn validate_ignore_elevated_windows(config_str: &str, configuration_provider: &mut ConfigurationProvider) {
+  if !config_str.contains("ignore_elevated_windows") {
+    warn!(
+      "[{}] was missing; saving it now with default value: {}",
+      "ignore_elevated_windows",
+      default_ignore_elevated_windows()
+    );
+    configuration_provider.save_config_or_log_error();
   }
+}
 
-  fn new_with(file_manager: FileManager<Configuration>) -> Self {
-    let (config, config_string) = file_manager
-      .load_or_create_with_repair(repair_obsolete_empty_monitor_list)
-      .expect("Failed to load configuration");
-    let mut configuration_provider = ConfigurationProvider { file_manager, config };
-    configuration_provider.validate_config(config_string);
+fn default_persist_ignored_windows() -> bool {
+  false
+}
 
-    configuration_provider
+fn validate_persist_ignored_windows(config_str: &str, configuration_provider: &mut ConfigurationProvider) {
+  if !config_str.contains("persist_ignored_windows") {
+    warn!(
+      "[{}] was missing; saving it now with default value: {}",
+      "persist_ignored_windows",
+      default_persist_ignored_windows()
+    );
+    configuration_provider.save_config_or_log_error();
   }
+}
 
-  pub fn log_current_config(&self) {
-    info!("{:?}", self.config);
-  }
+fn default_managed_tool_window_classes() -> Vec<String> {
+  vec![]
+}
 
-  // TODO: Consider validating hotkeys
-  fn validate_config(&mut self, config_string: Option<String>) {
-    if let Some(config_as_string) = config_string {
-      validate_window_margin(&config_as_string, self);
-      validate_force_using_admin_privileges(&config_as_string, self);
-      validate_workspace_count(&config_as_string, self);
-      validate_features_using_mouse(&config_as_string, self);
-      validate_delay_in_ms_before_dragging_is_allowed(&config_as_string, self);
-      validate_allow_moving_cursor_after_close_or_minimise(&config_as_string, self);
-      validate_layout_sections(&config_as_string, self);
-      validate_excluded_window_titles(&config_as_string, self);
-      validate_excluded_window_classes(&config_as_string, self);
-    } else {
-      warn!("Failed to validate configuration: configuration string not available");
-    }
+fn validate_managed_tool_window_classes(config_str: &str, configuration_provider: &mut ConfigurationProvider) {
+  if !config_str.contains("managed_tool_window_classes") {
+    warn!(
+      "[{}] was missing; saving it now with default value: {:#?}",
+      "managed_tool_window_classes",
+      default_managed_tool_window_classes()
+    );
+    configuration_provider.save_config_or_log_error();
   }
+}
 
-  pub fn get_bool(&self, name: &str) -> bool {
-    match name {
-      ALLOW_SELECTING_SAME_CENTER_WINDOWS => self.config.spatial_layout.allow_selecting_same_center_windows,
+/// The parts of the configuration that define how workspaces are set up, i.e. the number of workspaces per monitor, the
+/// layout presets, the workspace fallback monitors, the free-floating, tiled, do-not-disturb, and screen sharing
+/// workspaces, the workspace numbering, and the window rules. Can be exported to a single file and imported on another
+/// machine.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorkspaceProfile {
+  #[serde(default = "default_additional_workspace_count")]
+  additional_workspace_count: i32,
+  #[serde(default)]
+  layout: LayoutConfiguration,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  monitor_workspace_count: Vec<MonitorWorkspaceCount>,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  workspace_fallback: Vec<WorkspaceFallback>,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  free_floating_workspace: Vec<FreeFloatingWorkspace>,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  tiled_workspace: Vec<TiledWorkspace>,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  do_not_disturb_workspace: Vec<DoNotDisturbWorkspace>,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  screen_sharing_workspace: Vec<ScreenSharingWorkspace>,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  workspace_numbering: Vec<WorkspaceNumbering>,
+  #[serde(default)]
+  rule: Vec<WindowRule>,
+}
+
+impl Default for WorkspaceProfile {
+  fn default() -> Self {
+    Self {
+      additional_workspace_count: default_additional_workspace_count(),
+      layout: LayoutConfiguration::default(),
+      monitor_workspace_count: vec![],
+      workspace_fallback: vec![],
+      free_floating_workspace: vec![],
+      tiled_workspace: vec![],
+      do_not_disturb_workspace: vec![],
+      screen_sharing_workspace: vec![],
+      workspace_numbering: vec![],
+      rule: vec![],
+    }
+  }
+}
+
+impl WorkspaceProfile {
+  /// Replaces the device name of every monitor the profile refers to that is not one of the connected monitors, using
+  /// `choose_monitor`, which is called once per such monitor with its device name and returns the device name of the
+  /// connected monitor to use instead. All settings for a monitor are dropped if `choose_monitor` returns `None`.
+  pub fn resolve_monitor_ids(
+    &mut self,
+    connected_monitor_ids: &[String],
+    mut choose_monitor: impl FnMut(&str) -> Option<String>,
+  ) {
+    let mut referenced_monitor_ids = self
+      .layout
+      .monitor
+      .iter()
+      .map(|monitor| monitor.id.clone())
+      .chain(self.monitor_workspace_count.iter().map(|count| count.monitor.clone()))
+      .chain(
+        self
+          .workspace_fallback
+          .iter()
+          .flat_map(|fallback| [fallback.selector.monitor.clone(), fallback.fallback.clone()]),
+      )
+      .chain(
+        self
+          .free_floating_workspace
+          .iter()
+          .map(|workspace| workspace.selector.monitor.clone()),
+      )
+      .chain(
+        self
+          .tiled_workspace
+          .iter()
+          .map(|workspace| workspace.selector.monitor.clone()),
+      )
+      .chain(
+        self
+          .do_not_disturb_workspace
+          .iter()
+          .map(|workspace| workspace.selector.monitor.clone()),
+      )
+      .chain(
+        self
+          .screen_sharing_workspace
+          .iter()
+          .map(|workspace| workspace.selector.monitor.clone()),
+      )
+      .chain(self.workspace_numbering.iter().map(|numbering| numbering.monitor.clone()))
+      .filter(|id| id != "primary" && !connected_monitor_ids.contains(id))
+      .collect::<Vec<_>>();
+    referenced_monitor_ids.sort();
+    referenced_monitor_ids.dedup();
+    for monitor_id in referenced_monitor_ids {
+      match choose_monitor(&monitor_id) {
+        Some(replacement) => {
+          info!("Using monitor [{replacement}] for imported settings of monitor [{monitor_id}]");
+          let replace = |id: &mut String| {
+            if *id == monitor_id {
+              id.clone_from(&replacement);
+            }
+          };
+          self.layout.monitor.iter_mut().for_each(|monitor| replace(&mut monitor.id));
+          self
+            .monitor_workspace_count
+            .iter_mut()
+            .for_each(|count| replace(&mut count.monitor));
+          self.workspace_fallback.iter_mut().for_each(|fallback| {
+            replace(&mut fallback.selector.monitor);
+            replace(&mut fallback.fallback);
+          });
+          self
+            .free_floating_workspace
+            .iter_mut()
+            .for_each(|workspace| replace(&mut workspace.selector.monitor));
+          self
+            .tiled_workspace
+            .iter_mut()
+            .for_each(|workspace| replace(&mut workspace.selector.monitor));
+          self
+            .do_not_disturb_workspace
+            .iter_mut()
+            .for_each(|workspace| replace(&mut workspace.selector.monitor));
+          self
+            .screen_sharing_workspace
+            .iter_mut()
+            .for_each(|workspace| replace(&mut workspace.selector.monitor));
+          self
+            .workspace_numbering
+            .iter_mut()
+            .for_each(|numbering| replace(&mut numbering.monitor));
+        }
+        None => {
+          info!("Skipping imported settings of monitor [{monitor_id}]");
+          self.layout.monitor.retain(|monitor| monitor.id != monitor_id);
+          self.monitor_workspace_count.retain(|count| count.monitor != monitor_id);
+          self
+            .workspace_fallback
+            .retain(|fallback| fallback.selector.monitor != monitor_id && fallback.fallback != monitor_id);
+          self
+            .free_floating_workspace
+            .retain(|workspace| workspace.selector.monitor != monitor_id);
+          self
+            .tiled_workspace
+            .retain(|workspace| workspace.selector.monitor != monitor_id);
+          self
+            .do_not_disturb_workspace
+            .retain(|workspace| workspace.selector.monitor != monitor_id);
+          self
+            .screen_sharing_workspace
+            .retain(|workspace| workspace.selector.monitor != monitor_id);
+          self.workspace_numbering.retain(|numbering| numbering.monitor != monitor_id);
+        }
+      }
+    }
+  }
+}
+
+pub struct ConfigurationProvider {
+  file_manager: FileManager<Configuration>,
+  config: Configuration,
+  shared_exclusion_settings: Arc<RwLock<ExclusionSettings>>,
+  shared_mouse_bindings: Arc<RwLock<MouseBindingsConfiguration>>,
+}
+
+impl ConfigurationProvider {
+  pub fn new() -> Self {
+    let mut file_manager = FileManager::new(CONFIGURATION_FILE_NAME, FileType::Config);
+    file_manager.set_content_prefix(CONFIGURATION_FILE_PREFIX);
+
+    Self::new_with(file_manager)
+  }
+
+  fn new_with(file_manager: FileManager<Configuration>) -> Self {
+    let (config, config_string) = file_manager
+      .load_or_create_with_repair(repair_obsolete_empty_monitor_list)
+      .expect("Failed to load configuration");
+    let shared_exclusion_settings = Arc::new(RwLock::new(config.exclusion_settings.clone()));
+    let mut configuration_provider = ConfigurationProvider {
+      file_manager,
+      config,
+      shared_exclusion_settings,
+      shared_mouse_bindings: Arc::new(RwLock::new(MouseBindingsConfiguration::default())),
+    };
+    configuration_provider.validate_config(config_string);
+    *configuration_provider
+      .shared_mouse_bindings
+      .write()
+      .expect(MOUSE_BINDINGS_LOCK) = configuration_provider.config.mouse_bindings.clone();
+
+    configuration_provider
+  }
+
+  pub fn log_current_config(&self) {
+    info!("{:?}", self.config);
+  }
+
+  // TODO: Consider validating hotkeys
+  fn validate_config(&mut self, config_string: Option<String>) {
+    if let Some(config_as_string) = config_string {
+      validate_window_margin(&config_as_string, self);
+      validate_force_using_admin_privileges(&config_as_string, self);
+      validate_workspace_count(&config_as_string, self);
+      validate_features_using_mouse(&config_as_string, self);
+      validate_touchpad_gestures(&config_as_string, self);
+      validate_usage_metrics(&config_as_string, self);
+      validate_efficiency_mode(&config_as_string, self);
+      validate_keep_always_on_top_windows_visible(&config_as_string, self);
+      validate_preserve_relative_window_position(&config_as_string, self);
+      validate_enable_command_file(&config_as_string, self);
+      validate_ipc_server_port(&config_as_string, self);
+      validate_skip_near_maximise_animation(&config_as_string, self);
+      validate_notify_on_failed_commands(&config_as_string, self);
+      validate_data_file_format(&config_as_string, self);
+      validate_picture_in_picture(&config_as_string, self);
+      validate_focus_border(&config_as_string, self);
+      validate_mouse_bindings(&config_as_string, self);
+      validate_auto_window_margin(&config_as_string, self);
+      validate_delay_in_ms_before_dragging_is_allowed(&config_as_string, self);
+      validate_grid_snapping_cell_size_in_px(&config_as_string, self);
+      validate_magnetic_edge_distance_in_px(&config_as_string, self);
+      validate_snap_zone_distance_in_px(&config_as_string, self);
+      validate_auto_snap_threshold_in_percent(&config_as_string, self);
+      validate_event_loop_interval_in_ms(&config_as_string, self);
+      validate_heartbeat_interval_in_s(&config_as_string, self);
+      validate_startup_delay_in_ms(&config_as_string, self);
+      validate_close_confirmation_timeout_in_ms(&config_as_string, self);
+      validate_workspace_badges_delay_in_ms(&config_as_string, self);
+      validate_slow_workspace_switch_threshold_in_ms(&config_as_string, self);
+      validate_allow_moving_cursor_after_close_or_minimise(&config_as_string, self);
+      validate_layout_sections(&config_as_string, self);
+      validate_excluded_window_titles(&config_as_string, self);
+      validate_excluded_window_classes(&config_as_string, self);
+      validate_ignore_elevated_windows(&config_as_string, self);
+      validate_managed_tool_window_classes(&config_as_string, self);
+      validate_persist_ignored_windows(&config_as_string, self);
+      validate_monitor_workspace_counts(self);
+      validate_workspace_numbering(self);
+      validate_cursor_speed_profiles(self);
+      validate_tiled_workspaces(self);
+    } else {
+      warn!("Failed to validate configuration: configuration string not available");
+    }
+  }
+
+  pub fn get_bool(&self, name: &str) -> bool {
+    match name {
+      ALLOW_SELECTING_SAME_CENTER_WINDOWS => self.config.spatial_layout.allow_selecting_same_center_windows,
+      SNAP_TO_THIRDS => self.config.spatial_layout.snap_to_thirds,
       FORCE_USING_ADMIN_PRIVILEGES => self.config.general.force_using_admin_privileges,
       ENABLE_FEATURES_USING_MOUSE => self.config.general.enable_features_using_mouse,
+      ENABLE_TOUCHPAD_GESTURES => self.config.general.enable_touchpad_gestures,
+      ENABLE_USAGE_METRICS => self.config.general.enable_usage_metrics,
+      ENABLE_EFFICIENCY_MODE => self.config.general.enable_efficiency_mode,
+      KEEP_ALWAYS_ON_TOP_WINDOWS_VISIBLE => self.config.general.keep_always_on_top_windows_visible,
+      PRESERVE_RELATIVE_WINDOW_POSITION => self.config.general.preserve_relative_window_position,
+      ENABLE_COMMAND_FILE => self.config.general.enable_command_file,
+      SKIP_NEAR_MAXIMISE_ANIMATION => self.config.general.skip_near_maximise_animation,
+      NOTIFY_ON_FAILED_COMMANDS => self.config.general.notify_on_failed_commands,
       ALLOW_MOVING_CURSOR_AFTER_OPEN_CLOSE_OR_MINIMISE => {
         self.config.general.allow_moving_cursor_after_open_close_or_minimise
       }
@@ -424,8 +1867,17 @@ impl ConfigurationProvider {
   pub fn set_bool(&mut self, name: &str, value: bool) {
     match name {
       ALLOW_SELECTING_SAME_CENTER_WINDOWS => self.config.spatial_layout.allow_selecting_same_center_windows = value,
+      SNAP_TO_THIRDS => self.config.spatial_layout.snap_to_thirds = value,
       FORCE_USING_ADMIN_PRIVILEGES => self.config.general.force_using_admin_privileges = value,
       ENABLE_FEATURES_USING_MOUSE => self.config.general.enable_features_using_mouse = value,
+      ENABLE_TOUCHPAD_GESTURES => self.config.general.enable_touchpad_gestures = value,
+      ENABLE_USAGE_METRICS => self.config.general.enable_usage_metrics = value,
+      ENABLE_EFFICIENCY_MODE => self.config.general.enable_efficiency_mode = value,
+      KEEP_ALWAYS_ON_TOP_WINDOWS_VISIBLE => self.config.general.keep_always_on_top_windows_visible = value,
+      PRESERVE_RELATIVE_WINDOW_POSITION => self.config.general.preserve_relative_window_position = value,
+      ENABLE_COMMAND_FILE => self.config.general.enable_command_file = value,
+      SKIP_NEAR_MAXIMISE_ANIMATION => self.config.general.skip_near_maximise_animation = value,
+      NOTIFY_ON_FAILED_COMMANDS => self.config.general.notify_on_failed_commands = value,
       ALLOW_MOVING_CURSOR_AFTER_OPEN_CLOSE_OR_MINIMISE => {
         self.config.general.allow_moving_cursor_after_open_close_or_minimise = value
       }
@@ -447,6 +1899,121 @@ impl ConfigurationProvider {
     self.save_config_or_log_error();
   }
 
+  /// Returns the strategy for ordering monitors and therefore workspaces.
+  pub fn get_monitor_ordering(&self) -> MonitorOrdering {
+    self.config.layout.monitor_ordering
+  }
+
+  /// Returns the configured language of the tray menu and notifications, if any.
+  /// Returns the format of the files in which the state of workspaces and usage metrics is stored.
+  pub fn get_data_file_format(&self) -> FileFormat {
+    self.config.general.data_file_format
+  }
+
+  pub fn get_language(&self) -> Option<&str> {
+    self.config.general.language.as_deref()
+  }
+
+  /// Returns the size and corner used by the picture-in-picture command.
+  pub fn get_picture_in_picture(&self) -> &PictureInPictureConfiguration {
+    &self.config.picture_in_picture
+  }
+
+  /// Returns whether and how the border around the foreground window is drawn.
+  pub fn get_focus_border(&self) -> &FocusBorderConfiguration {
+    &self.config.focus_border
+  }
+
+  /// Returns the recorded workspace counts of monitors other than the primary monitor.
+  pub fn get_monitor_workspace_counts(&self) -> Vec<MonitorWorkspaceCount> {
+    self.config.monitor_workspace_count.clone()
+  }
+
+  /// Records the number of additional workspaces of a monitor other than the primary monitor and saves the
+  /// configuration to file. Monitors without additional workspaces are removed.
+  pub fn set_monitor_workspace_count(&mut self, monitor_id: &str, additional_workspace_count: i32) {
+    self
+      .config
+      .monitor_workspace_count
+      .retain(|monitor_workspace_count| monitor_workspace_count.monitor != monitor_id);
+    if additional_workspace_count > 0 {
+      self.config.monitor_workspace_count.push(MonitorWorkspaceCount {
+        monitor: monitor_id.to_string(),
+        additional_workspace_count,
+      });
+    }
+    self.save_config_or_log_error();
+  }
+
+  /// Returns the configured fallback monitors for workspaces whose monitor is disconnected.
+  pub fn get_workspace_fallbacks(&self) -> Vec<WorkspaceFallback> {
+    self.config.workspace_fallback.clone()
+  }
+
+  /// Returns `true` if the given workspace on the monitor with the given device name is configured as free-floating.
+  pub fn is_workspace_free_floating(&self, monitor_id: &str, workspace: usize) -> bool {
+    self
+      .config
+      .free_floating_workspace
+      .iter()
+      .any(|free_floating_workspace| free_floating_workspace.selector.applies_to(monitor_id, workspace))
+  }
+
+  /// Returns `true` if the given workspace on the monitor with the given device name is configured as do-not-disturb.
+  pub fn is_workspace_do_not_disturb(&self, monitor_id: &str, workspace: usize) -> bool {
+    self
+      .config
+      .do_not_disturb_workspace
+      .iter()
+      .any(|do_not_disturb_workspace| do_not_disturb_workspace.selector.applies_to(monitor_id, workspace))
+  }
+
+  /// Returns the screen sharing configuration of the given workspace on the monitor with the given device name, if the
+  /// workspace is configured as a screen sharing workspace.
+  pub fn get_screen_sharing_workspace(&self, monitor_id: &str, workspace: usize) -> Option<&ScreenSharingWorkspace> {
+    self
+      .config
+      .screen_sharing_workspace
+      .iter()
+      .find(|screen_sharing_workspace| screen_sharing_workspace.selector.applies_to(monitor_id, workspace))
+  }
+
+  /// Returns the tiling configuration of the given workspace on the monitor with the given device name, if the workspace
+  /// is configured as tiled.
+  pub fn get_tiled_workspace(&self, monitor_id: &str, workspace: usize) -> Option<&TiledWorkspace> {
+    self
+      .config
+      .tiled_workspace
+      .iter()
+      .find(|tiled_workspace| tiled_workspace.selector.applies_to(monitor_id, workspace))
+  }
+
+  /// Returns the configured numbering of the workspace hotkeys per monitor.
+  pub fn get_workspace_numbering(&self) -> &[WorkspaceNumbering] {
+    &self.config.workspace_numbering
+  }
+
+  /// Returns the cursor speed profile of the monitor with the given device name or, for the primary monitor, of
+  /// `primary`, if there is one.
+  pub fn get_cursor_speed_profile(&self, monitor_id: &str, is_primary: bool) -> Option<&CursorSpeedProfile> {
+    let profiles = &self.config.cursor_speed;
+    profiles.iter().find(|profile| profile.monitor == monitor_id).or_else(|| {
+      is_primary
+        .then(|| profiles.iter().find(|profile| profile.monitor == "primary"))
+        .flatten()
+    })
+  }
+
+  /// Returns the policy used to select a window after closing or minimising the foreground window.
+  pub fn get_focus_fallback_policy(&self) -> FocusFallbackPolicy {
+    self.config.spatial_layout.focus_fallback_policy
+  }
+
+  /// Returns the order in which the windows of a workspace are focused when cycling through them.
+  pub fn get_focus_cycle_order(&self) -> FocusCycleOrder {
+    self.config.spatial_layout.focus_cycle_order
+  }
+
   /// Resolves a monitor's configured layout.
   pub fn layout_for_monitor(&self, monitor_id: &str, is_primary: bool) -> Layout {
     self
@@ -463,11 +2030,47 @@ impl ConfigurationProvider {
       .map_or(self.config.layout.default, |override_| override_.mode)
   }
 
+  /// Returns the window margin to use on a monitor: the margin configured for the monitor (or, for the primary
+  /// monitor, for `primary`) if there is one, else a percentage of the width of the monitor that is clamped to the
+  /// configured range if automatic margins are enabled, else `window_margin`.
+  pub fn window_margin_for_monitor(&self, monitor_id: &str, is_primary: bool, monitor_width: i32) -> i32 {
+    let auto_window_margin = &self.config.auto_window_margin;
+    let override_ = auto_window_margin
+      .monitor
+      .iter()
+      .find(|override_| override_.id == monitor_id)
+      .or_else(|| {
+        is_primary
+          .then(|| auto_window_margin.monitor.iter().find(|override_| override_.id == "primary"))
+          .flatten()
+      });
+    if let Some(override_) = override_ {
+      return override_.margin;
+    }
+    if !auto_window_margin.enabled {
+      return self.config.general.window_margin;
+    }
+    let margin = (monitor_width as f32 * auto_window_margin.percent_of_monitor_width / 100.0).round() as i32;
+
+    margin.clamp(auto_window_margin.min_in_px, auto_window_margin.max_in_px)
+  }
+
   pub fn get_i32(&self, name: &str) -> i32 {
     match name {
       WINDOW_MARGIN => self.config.general.window_margin,
       ADDITIONAL_WORKSPACE_COUNT => self.config.general.additional_workspace_count,
       DELAY_IN_MS_BEFORE_DRAGGING_IS_ALLOWED => self.config.general.delay_in_ms_before_dragging_is_allowed,
+      GRID_SNAPPING_CELL_SIZE_IN_PX => self.config.general.grid_snapping_cell_size_in_px,
+      MAGNETIC_EDGE_DISTANCE_IN_PX => self.config.general.magnetic_edge_distance_in_px,
+      SNAP_ZONE_DISTANCE_IN_PX => self.config.general.snap_zone_distance_in_px,
+      AUTO_SNAP_THRESHOLD_IN_PERCENT => self.config.general.auto_snap_threshold_in_percent,
+      EVENT_LOOP_INTERVAL_IN_MS => self.config.general.event_loop_interval_in_ms,
+      HEARTBEAT_INTERVAL_IN_S => self.config.general.heartbeat_interval_in_s,
+      STARTUP_DELAY_IN_MS => self.config.general.startup_delay_in_ms,
+      CLOSE_CONFIRMATION_TIMEOUT_IN_MS => self.config.general.close_confirmation_timeout_in_ms,
+      WORKSPACE_BADGES_DELAY_IN_MS => self.config.general.workspace_badges_delay_in_ms,
+      SLOW_WORKSPACE_SWITCH_THRESHOLD_IN_MS => self.config.general.slow_workspace_switch_threshold_in_ms,
+      IPC_SERVER_PORT => self.config.general.ipc_server_port,
       SCROLLING_ANIMATION_DURATION_IN_MS => self.config.scrolling_layout.animation_duration_in_ms,
       SCROLLING_RECONCILIATION_INTERVAL_IN_MS => self.config.scrolling_layout.reconciliation_interval_in_ms,
       &_ => {
@@ -484,6 +2087,17 @@ impl ConfigurationProvider {
       WINDOW_MARGIN => self.config.general.window_margin = value,
       ADDITIONAL_WORKSPACE_COUNT => self.config.general.additional_workspace_count = value,
       DELAY_IN_MS_BEFORE_DRAGGING_IS_ALLOWED => self.config.general.delay_in_ms_before_dragging_is_allowed = value,
+      GRID_SNAPPING_CELL_SIZE_IN_PX => self.config.general.grid_snapping_cell_size_in_px = value,
+      MAGNETIC_EDGE_DISTANCE_IN_PX => self.config.general.magnetic_edge_distance_in_px = value,
+      SNAP_ZONE_DISTANCE_IN_PX => self.config.general.snap_zone_distance_in_px = value,
+      AUTO_SNAP_THRESHOLD_IN_PERCENT => self.config.general.auto_snap_threshold_in_percent = value,
+      EVENT_LOOP_INTERVAL_IN_MS => self.config.general.event_loop_interval_in_ms = value,
+      HEARTBEAT_INTERVAL_IN_S => self.config.general.heartbeat_interval_in_s = value,
+      STARTUP_DELAY_IN_MS => self.config.general.startup_delay_in_ms = value,
+      CLOSE_CONFIRMATION_TIMEOUT_IN_MS => self.config.general.close_confirmation_timeout_in_ms = value,
+      WORKSPACE_BADGES_DELAY_IN_MS => self.config.general.workspace_badges_delay_in_ms = value,
+      SLOW_WORKSPACE_SWITCH_THRESHOLD_IN_MS => self.config.general.slow_workspace_switch_threshold_in_ms = value,
+      IPC_SERVER_PORT => self.config.general.ipc_server_port = value,
       SCROLLING_ANIMATION_DURATION_IN_MS => self.config.scrolling_layout.animation_duration_in_ms = value,
       SCROLLING_RECONCILIATION_INTERVAL_IN_MS => self.config.scrolling_layout.reconciliation_interval_in_ms = value,
       &_ => {
@@ -497,10 +2111,77 @@ impl ConfigurationProvider {
     &self.config.hotkey
   }
 
+  /// Returns the key combinations configured for a built-in command in the `[keybindings]` section, or `None` if the
+  /// command is not listed there and its default hotkeys apply.
+  pub fn get_keybinding(&self, name: &str) -> Option<&[String]> {
+    self.config.keybindings.get(name).map(Vec::as_slice)
+  }
+
+  /// Returns the names of all commands listed in the `[keybindings]` section.
+  pub fn get_keybinding_names(&self) -> Vec<String> {
+    self.config.keybindings.keys().cloned().collect()
+  }
+
+  pub fn get_window_rules(&self) -> &[WindowRule] {
+    &self.config.rule
+  }
+
+  /// Returns the parts of the configuration that define how workspaces are set up.
+  pub fn export_workspace_profile(&self) -> WorkspaceProfile {
+    WorkspaceProfile {
+      additional_workspace_count: self.config.general.additional_workspace_count,
+      layout: self.config.layout.clone(),
+      monitor_workspace_count: self.config.monitor_workspace_count.clone(),
+      workspace_fallback: self.config.workspace_fallback.clone(),
+      free_floating_workspace: self.config.free_floating_workspace.clone(),
+      tiled_workspace: self.config.tiled_workspace.clone(),
+      do_not_disturb_workspace: self.config.do_not_disturb_workspace.clone(),
+      screen_sharing_workspace: self.config.screen_sharing_workspace.clone(),
+      workspace_numbering: self.config.workspace_numbering.clone(),
+      rule: self.config.rule.clone(),
+    }
+  }
+
+  /// Replaces the parts of the configuration that define how workspaces are set up and saves the configuration to
+  /// file. Monitor IDs should be resolved using [`WorkspaceProfile::resolve_monitor_ids`] first.
+  pub fn import_workspace_profile(&mut self, profile: WorkspaceProfile) {
+    self.config.general.additional_workspace_count = profile.additional_workspace_count;
+    self.config.layout = profile.layout;
+    self.config.monitor_workspace_count = profile.monitor_workspace_count;
+    self.config.workspace_fallback = profile.workspace_fallback;
+    self.config.free_floating_workspace = profile.free_floating_workspace;
+    self.config.tiled_workspace = profile.tiled_workspace;
+    self.config.do_not_disturb_workspace = profile.do_not_disturb_workspace;
+    self.config.screen_sharing_workspace = profile.screen_sharing_workspace;
+    self.config.workspace_numbering = profile.workspace_numbering;
+    self.config.rule = profile.rule;
+    self.save_config_or_log_error();
+    match fs::read_to_string(self.file_manager.get_path()) {
+      Ok(config_string) => self.validate_config(Some(config_string)),
+      Err(err) => warn!("Failed to validate imported workspace profile: {err}"),
+    }
+  }
+
   pub fn get_exclusion_settings(&self) -> &ExclusionSettings {
     &self.config.exclusion_settings
   }
 
+  /// Returns the exclusion settings as a shared reference that is updated whenever the configuration is reloaded, so
+  /// that holders of it, e.g. the Windows API, pick up changes without a restart.
+  pub fn get_shared_exclusion_settings(&self) -> Arc<RwLock<ExclusionSettings>> {
+    self.shared_exclusion_settings.clone()
+  }
+
+  /// Returns the mouse bindings as a shared reference that is updated whenever the configuration is reloaded, so that
+  /// the mouse and keyboard hooks pick up changes without a restart.
+  pub fn get_shared_mouse_bindings(&self) -> Arc<RwLock<MouseBindingsConfiguration>> {
+    self.shared_mouse_bindings.clone()
+  }
+
+  pub fn get_path_to_configuration_file(&self) -> &Path {
+    self.file_manager.get_path()
+  }
+
   pub fn reload_configuration(&mut self) {
     let (config, config_string) = self
       .file_manager
@@ -508,6 +2189,9 @@ impl ConfigurationProvider {
       .expect("Failed to reload file");
     self.config = config;
     self.validate_config(config_string);
+    *self.shared_exclusion_settings.write().expect(EXCLUSION_SETTINGS_LOCK) = self.config.exclusion_settings.clone();
+    *self.shared_mouse_bindings.write().expect(MOUSE_BINDINGS_LOCK) = self.config.mouse_bindings.clone();
+    info!("Reloaded configuration from [{}]", self.file_manager.get_path().display());
   }
 
   fn save_config_or_log_error(&mut self) {
@@ -525,81 +2209,626 @@ mod tests {
   use std::io::Write;
   use std::path::PathBuf;
 
-  impl ConfigurationProvider {
-    pub fn default() -> Self {
-      Self {
-        file_manager: FileManager::default(),
-        config: Configuration::default(),
-      }
-    }
+  impl ConfigurationProvider {
+    pub fn default() -> Self {
+      Self {
+        file_manager: FileManager::default(),
+        config: Configuration::default(),
+        shared_exclusion_settings: Arc::new(RwLock::new(ExclusionSettings::default())),
+        shared_mouse_bindings: Arc::new(RwLock::new(MouseBindingsConfiguration::default())),
+      }
+    }
+
+    pub fn default_with_hotkeys(hotkeys: Vec<CustomHotkey>) -> Self {
+      Self {
+        file_manager: FileManager::default(),
+        config: Configuration {
+          general: GeneralConfiguration::default(),
+          hotkey: hotkeys,
+          exclusion_settings: ExclusionSettings::default(),
+          ..Configuration::default()
+        },
+        shared_exclusion_settings: Arc::new(RwLock::new(ExclusionSettings::default())),
+        shared_mouse_bindings: Arc::new(RwLock::new(MouseBindingsConfiguration::default())),
+      }
+    }
+
+    pub fn new_test(temp_path: PathBuf) -> Self {
+      let file_manager = FileManager::new_test(temp_path);
+      Self::new_with(file_manager)
+    }
+
+    fn new_test_without_validation(temp_path: PathBuf, config: Configuration) -> Self {
+      let file_manager = FileManager::new_test(temp_path);
+      let shared_exclusion_settings = Arc::new(RwLock::new(config.exclusion_settings.clone()));
+      let shared_mouse_bindings = Arc::new(RwLock::new(config.mouse_bindings.clone()));
+      Self {
+        file_manager,
+        config,
+        shared_exclusion_settings,
+        shared_mouse_bindings,
+      }
+    }
+
+    /// Sets the focus fallback policy without saving it.
+    pub fn set_focus_fallback_policy(&mut self, policy: FocusFallbackPolicy) {
+      self.config.spatial_layout.focus_fallback_policy = policy;
+    }
+
+    /// Sets the focus cycle order without saving it.
+    pub fn set_focus_cycle_order(&mut self, order: FocusCycleOrder) {
+      self.config.spatial_layout.focus_cycle_order = order;
+    }
+
+    /// Enables or disables snapping to thirds without saving it.
+    pub fn set_snap_to_thirds(&mut self, snap_to_thirds: bool) {
+      self.config.spatial_layout.snap_to_thirds = snap_to_thirds;
+    }
+
+    /// Sets the threshold for snapping windows after dragging them without saving it.
+    pub fn set_auto_snap_threshold_in_percent(&mut self, threshold_in_percent: i32) {
+      self.config.general.auto_snap_threshold_in_percent = threshold_in_percent;
+    }
+
+    /// Sets the key combinations of a built-in command without saving them.
+    pub fn set_keybinding(&mut self, name: &str, key_combinations: &[&str]) {
+      self
+        .config
+        .keybindings
+        .insert(name.to_string(), key_combinations.iter().map(|s| s.to_string()).collect());
+    }
+
+    /// Marks a workspace as free-floating without saving it.
+    pub fn add_free_floating_workspace(&mut self, monitor: &str, workspace: Option<usize>) {
+      self.config.free_floating_workspace.push(FreeFloatingWorkspace {
+        selector: WorkspaceSelector {
+          monitor: monitor.to_string(),
+          workspace,
+        },
+      });
+    }
+
+    /// Marks a workspace as tiled without saving it.
+    pub fn add_tiled_workspace(&mut self, monitor: &str, workspace: Option<usize>, master_width_in_percent: i32) {
+      self.config.tiled_workspace.push(TiledWorkspace {
+        selector: WorkspaceSelector {
+          monitor: monitor.to_string(),
+          workspace,
+        },
+        arrangement: TilingArrangement::MasterStack,
+        master_width_in_percent,
+      });
+    }
+
+    /// Marks a workspace as tiled with the BSP arrangement without saving it.
+    pub fn add_bsp_tiled_workspace(&mut self, monitor: &str, workspace: Option<usize>) {
+      self.config.tiled_workspace.push(TiledWorkspace {
+        selector: WorkspaceSelector {
+          monitor: monitor.to_string(),
+          workspace,
+        },
+        arrangement: TilingArrangement::Bsp,
+        master_width_in_percent: default_master_width_in_percent(),
+      });
+    }
+
+    /// Marks a workspace as do-not-disturb without saving it.
+    pub fn add_do_not_disturb_workspace(&mut self, monitor: &str, workspace: Option<usize>) {
+      self.config.do_not_disturb_workspace.push(DoNotDisturbWorkspace {
+        selector: WorkspaceSelector {
+          monitor: monitor.to_string(),
+          workspace,
+        },
+      });
+    }
+
+    /// Marks a workspace as a screen sharing workspace with the default class names without saving it.
+    pub fn add_screen_sharing_workspace(&mut self, monitor: &str, workspace: Option<usize>) {
+      self.config.screen_sharing_workspace.push(ScreenSharingWorkspace {
+        selector: WorkspaceSelector {
+          monitor: monitor.to_string(),
+          workspace,
+        },
+        class_names: default_screen_sharing_class_names(),
+      });
+    }
+
+    /// Sets the number of the first workspace hotkey of a monitor without saving it.
+    pub fn add_workspace_numbering(&mut self, monitor: &str, first_number: usize) {
+      self.config.workspace_numbering.push(WorkspaceNumbering {
+        monitor: monitor.to_string(),
+        first_number,
+      });
+    }
+
+    /// Adds a cursor speed profile for a monitor without saving it.
+    pub fn add_cursor_speed_profile(&mut self, monitor: &str, speed: u32, enhance_pointer_precision: Option<bool>) {
+      self.config.cursor_speed.push(CursorSpeedProfile {
+        monitor: monitor.to_string(),
+        speed,
+        enhance_pointer_precision,
+      });
+    }
+
+    /// Adds a window rule without saving it.
+    pub fn add_window_rule(&mut self, rule: WindowRule) {
+      self.config.rule.push(rule);
+    }
+
+    /// Removes all window rules without saving it.
+    pub fn clear_window_rules(&mut self) {
+      self.config.rule.clear();
+    }
+
+    /// Adds a monitor override without saving it.
+    pub fn set_monitor_layout(&mut self, id: &str, layout: Layout) {
+      self.config.layout.monitor.push(MonitorLayoutConfiguration {
+        id: id.to_string(),
+        mode: layout,
+      });
+    }
+  }
+
+  #[test]
+  fn scrolling_layout_loads_animation_and_reconciliation_durations() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(CONFIGURATION_FILE_NAME);
+    fs::write(
+      &path,
+      r#"
+        [general]
+        [layout]
+        [spatial_layout]
+        [scrolling_layout]
+        animation_duration_in_ms = 75
+        reconciliation_interval_in_ms = 400
+        [exclusion_settings]
+      "#,
+    )
+    .expect("Failed to write config file");
+    let configuration_provider = ConfigurationProvider::new_test(path);
+
+    assert_eq!(configuration_provider.get_i32(SCROLLING_ANIMATION_DURATION_IN_MS), 75);
+    assert_eq!(configuration_provider.get_i32(SCROLLING_RECONCILIATION_INTERVAL_IN_MS), 400);
+  }
+
+  #[test]
+  fn scrolling_layout_replaces_negative_durations_with_defaults() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(CONFIGURATION_FILE_NAME);
+    fs::write(
+      &path,
+      r#"
+        [general]
+        [layout]
+        [spatial_layout]
+        [scrolling_layout]
+        animation_duration_in_ms = -1
+        reconciliation_interval_in_ms = -1
+        [exclusion_settings]
+      "#,
+    )
+    .expect("Failed to write config file");
+
+    let configuration_provider = ConfigurationProvider::new_test(path);
+
+    assert_eq!(
+      configuration_provider.get_i32(SCROLLING_ANIMATION_DURATION_IN_MS),
+      DEFAULT_SCROLLING_ANIMATION_DURATION_IN_MS
+    );
+    assert_eq!(
+      configuration_provider.get_i32(SCROLLING_RECONCILIATION_INTERVAL_IN_MS),
+      DEFAULT_SCROLLING_RECONCILIATION_INTERVAL_IN_MS
+    );
+  }
+
+  #[test]
+  fn picture_in_picture_loads_size_and_corner() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(CONFIGURATION_FILE_NAME);
+    fs::write(
+      &path,
+      r#"
+        [general]
+        [picture_in_picture]
+        width = 640
+        height = 360
+        corner = "top_left"
+        [exclusion_settings]
+      "#,
+    )
+    .expect("Failed to write config file");
+
+    let configuration_provider = ConfigurationProvider::new_test(path);
+
+    let picture_in_picture = configuration_provider.get_picture_in_picture();
+    assert_eq!(picture_in_picture.width, 640);
+    assert_eq!(picture_in_picture.height, 360);
+    assert_eq!(picture_in_picture.corner, Corner::TopLeft);
+  }
+
+  #[test]
+  fn workspace_fallbacks_are_loaded_and_match_workspaces() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(CONFIGURATION_FILE_NAME);
+    fs::write(
+      &path,
+      r#"
+        [general]
+        [[workspace_fallback]]
+        monitor = "DISPLAY2"
+        fallback = "primary"
+        [[workspace_fallback]]
+        monitor = "DISPLAY3"
+        workspace = 2
+        fallback = "DISPLAY1"
+        [exclusion_settings]
+      "#,
+    )
+    .expect("Failed to write config file");
+
+    let configuration_provider = ConfigurationProvider::new_test(path);
+
+    let fallbacks = configuration_provider.get_workspace_fallbacks();
+    assert_eq!(fallbacks.len(), 2);
+    assert!(fallbacks[0].selector.applies_to("DISPLAY2", 1));
+    assert!(fallbacks[0].selector.applies_to("DISPLAY2", 3));
+    assert!(!fallbacks[0].selector.applies_to("DISPLAY3", 1));
+    assert!(fallbacks[1].selector.applies_to("DISPLAY3", 2));
+    assert!(!fallbacks[1].selector.applies_to("DISPLAY3", 1));
+    assert_eq!(fallbacks[1].fallback, "DISPLAY1");
+  }
+
+  #[test]
+  fn free_floating_workspaces_are_loaded_and_match_workspaces() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(CONFIGURATION_FILE_NAME);
+    fs::write(
+      &path,
+      r#"
+        [general]
+        [[free_floating_workspace]]
+        monitor = "DISPLAY1"
+        workspace = 3
+        [[free_floating_workspace]]
+        monitor = "DISPLAY2"
+        [exclusion_settings]
+      "#,
+    )
+    .expect("Failed to write config file");
+
+    let configuration_provider = ConfigurationProvider::new_test(path);
+
+    assert!(configuration_provider.is_workspace_free_floating("DISPLAY1", 3));
+    assert!(!configuration_provider.is_workspace_free_floating("DISPLAY1", 1));
+    assert!(configuration_provider.is_workspace_free_floating("DISPLAY2", 1));
+    assert!(configuration_provider.is_workspace_free_floating("DISPLAY2", 2));
+    assert!(!configuration_provider.is_workspace_free_floating("DISPLAY3", 3));
+  }
+
+  #[test]
+  fn do_not_disturb_workspaces_are_loaded_and_match_workspaces() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(CONFIGURATION_FILE_NAME);
+    fs::write(
+      &path,
+      r#"
+        [general]
+        [[do_not_disturb_workspace]]
+        monitor = "DISPLAY1"
+        workspace = 2
+        [exclusion_settings]
+      "#,
+    )
+    .expect("Failed to write config file");
+
+    let configuration_provider = ConfigurationProvider::new_test(path);
+
+    assert!(configuration_provider.is_workspace_do_not_disturb("DISPLAY1", 2));
+    assert!(!configuration_provider.is_workspace_do_not_disturb("DISPLAY1", 1));
+    assert!(!configuration_provider.is_workspace_do_not_disturb("DISPLAY2", 2));
+  }
+
+  #[test]
+  fn set_monitor_workspace_count_saves_count_and_removes_monitors_without_additional_workspaces() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(CONFIGURATION_FILE_NAME);
+    let mut configuration_provider = ConfigurationProvider::new_test(path.clone());
+
+    configuration_provider.set_monitor_workspace_count("DISPLAY2", 2);
+    configuration_provider.set_monitor_workspace_count("DISPLAY3", 1);
+    configuration_provider.set_monitor_workspace_count("DISPLAY3", 0);
+
+    let reloaded_configuration_provider = ConfigurationProvider::new_test(path);
+    let counts = reloaded_configuration_provider
+      .get_monitor_workspace_counts()
+      .into_iter()
+      .map(|count| (count.monitor, count.additional_workspace_count))
+      .collect::<Vec<_>>();
+    assert_eq!(counts, vec![("DISPLAY2".to_string(), 2)]);
+  }
+
+  #[test]
+  fn screen_sharing_workspaces_are_loaded_with_default_class_names() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(CONFIGURATION_FILE_NAME);
+    fs::write(
+      &path,
+      r#"
+        [general]
+        [[screen_sharing_workspace]]
+        monitor = "DISPLAY1"
+        [[screen_sharing_workspace]]
+        monitor = "DISPLAY2"
+        workspace = 2
+        class_names = ["CustomPresenter"]
+        [exclusion_settings]
+      "#,
+    )
+    .expect("Failed to write config file");
+
+    let configuration_provider = ConfigurationProvider::new_test(path);
+
+    let all_workspaces = configuration_provider
+      .get_screen_sharing_workspace("DISPLAY1", 3)
+      .expect("Screen sharing workspace not found");
+    assert_eq!(all_workspaces.class_names, default_screen_sharing_class_names());
+    let single_workspace = configuration_provider
+      .get_screen_sharing_workspace("DISPLAY2", 2)
+      .expect("Screen sharing workspace not found");
+    assert_eq!(single_workspace.class_names, vec!["CustomPresenter".to_string()]);
+    assert!(configuration_provider.get_screen_sharing_workspace("DISPLAY2", 1).is_none());
+  }
+
+  #[test]
+  fn tiled_workspaces_are_loaded_with_arrangement_and_replace_invalid_master_width_with_default() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(CONFIGURATION_FILE_NAME);
+    fs::write(
+      &path,
+      r#"
+        [general]
+        [[tiled_workspace]]
+        monitor = "DISPLAY1"
+        workspace = 2
+        master_width_in_percent = 50
+        [[tiled_workspace]]
+        monitor = "DISPLAY2"
+        master_width_in_percent = 95
+        [[tiled_workspace]]
+        monitor = "DISPLAY3"
+        arrangement = "bsp"
+        [exclusion_settings]
+      "#,
+    )
+    .expect("Failed to write config file");
+
+    let configuration_provider = ConfigurationProvider::new_test(path);
+
+    let get_master_width = |monitor_id: &str, workspace: usize| {
+      configuration_provider
+        .get_tiled_workspace(monitor_id, workspace)
+        .map(|tiled_workspace| tiled_workspace.master_width_in_percent)
+    };
+    assert_eq!(get_master_width("DISPLAY1", 2), Some(50));
+    assert_eq!(get_master_width("DISPLAY1", 1), None);
+    assert_eq!(get_master_width("DISPLAY2", 3), Some(default_master_width_in_percent()));
+    assert_eq!(get_master_width("DISPLAY4", 1), None);
+    let get_arrangement = |monitor_id: &str| {
+      configuration_provider
+        .get_tiled_workspace(monitor_id, 1)
+        .map(|tiled_workspace| tiled_workspace.arrangement)
+    };
+    assert_eq!(get_arrangement("DISPLAY2"), Some(TilingArrangement::MasterStack));
+    assert_eq!(get_arrangement("DISPLAY3"), Some(TilingArrangement::Bsp));
+  }
+
+  #[test]
+  fn workspace_numbering_is_loaded_and_ignores_first_number_zero() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(CONFIGURATION_FILE_NAME);
+    fs::write(
+      &path,
+      r#"
+        [general]
+        [[workspace_numbering]]
+        monitor = "primary"
+        first_number = 1
+        [[workspace_numbering]]
+        monitor = "DISPLAY2"
+        first_number = 4
+        [[workspace_numbering]]
+        monitor = "DISPLAY3"
+        first_number = 0
+        [exclusion_settings]
+      "#,
+    )
+    .expect("Failed to write config file");
+
+    let configuration_provider = ConfigurationProvider::new_test(path);
+
+    let numbering = configuration_provider.get_workspace_numbering();
+    assert_eq!(numbering.len(), 2);
+    assert!(numbering[0].applies_to("DISPLAY1", true));
+    assert!(!numbering[0].applies_to("DISPLAY1", false));
+    assert!(numbering[1].applies_to("DISPLAY2", false));
+    assert_eq!(numbering[1].first_number, 4);
+  }
+
+  #[test]
+  fn cursor_speed_profiles_are_loaded_and_ignore_invalid_speeds() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(CONFIGURATION_FILE_NAME);
+    fs::write(
+      &path,
+      r#"
+        [general]
+        [[cursor_speed]]
+        monitor = "primary"
+        speed = 6
+        [[cursor_speed]]
+        monitor = "DISPLAY2"
+        speed = 14
+        enhance_pointer_precision = false
+        [[cursor_speed]]
+        monitor = "DISPLAY3"
+        speed = 21
+        [exclusion_settings]
+      "#,
+    )
+    .expect("Failed to write config file");
+
+    let configuration_provider = ConfigurationProvider::new_test(path);
+
+    let get_cursor_speed = |monitor_id: &str, is_primary: bool| {
+      configuration_provider
+        .get_cursor_speed_profile(monitor_id, is_primary)
+        .map(|profile| profile.apply_to(CursorSpeed::new(10, true)))
+    };
+    assert_eq!(get_cursor_speed("DISPLAY1", true), Some(CursorSpeed::new(6, true)));
+    assert_eq!(get_cursor_speed("DISPLAY2", true), Some(CursorSpeed::new(14, false)));
+    assert_eq!(get_cursor_speed("DISPLAY1", false), None);
+    assert_eq!(get_cursor_speed("DISPLAY3", false), None);
+  }
+
+  #[test]
+  fn window_margin_for_monitor_prefers_override_then_auto_margin_then_window_margin() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(CONFIGURATION_FILE_NAME);
+    fs::write(
+      &path,
+      r#"
+        [general]
+        window_margin = 20
+
+        [auto_window_margin]
+        enabled = true
+        percent_of_monitor_width = 1.0
+        min_in_px = 10
+        max_in_px = 30
+
+        [[auto_window_margin.monitor]]
+        id = "primary"
+        margin = 5
+
+        [[auto_window_margin.monitor]]
+        id = "DISPLAY1"
+        margin = 15
+
+        [exclusion_settings]
+      "#,
+    )
+    .expect("Failed to write config file");
+    let mut configuration_provider = ConfigurationProvider::new_test(path);
+
+    assert_eq!(configuration_provider.window_margin_for_monitor("DISPLAY1", true, 1920), 15);
+    assert_eq!(configuration_provider.window_margin_for_monitor("DISPLAY2", true, 1920), 5);
+    assert_eq!(configuration_provider.window_margin_for_monitor("DISPLAY2", false, 1920), 19);
+    assert_eq!(configuration_provider.window_margin_for_monitor("DISPLAY2", false, 3840), 30);
+    assert_eq!(configuration_provider.window_margin_for_monitor("DISPLAY2", false, 800), 10);
+
+    configuration_provider.config.auto_window_margin.enabled = false;
+    assert_eq!(configuration_provider.window_margin_for_monitor("DISPLAY2", false, 3840), 20);
+    assert_eq!(configuration_provider.window_margin_for_monitor("DISPLAY1", false, 3840), 15);
+  }
+
+  #[test]
+  fn auto_window_margin_replaces_invalid_values_with_defaults() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(CONFIGURATION_FILE_NAME);
+    fs::write(
+      &path,
+      r#"
+        [general]
+        [auto_window_margin]
+        enabled = true
+        percent_of_monitor_width = -1.0
+        min_in_px = 50
+        max_in_px = 20
+
+        [[auto_window_margin.monitor]]
+        id = "DISPLAY1"
+        margin = -5
+
+        [exclusion_settings]
+      "#,
+    )
+    .expect("Failed to write config file");
+
+    let configuration_provider = ConfigurationProvider::new_test(path);
 
-    pub fn default_with_hotkeys(hotkeys: Vec<CustomHotkey>) -> Self {
-      Self {
-        file_manager: FileManager::default(),
-        config: Configuration {
-          general: GeneralConfiguration::default(),
-          hotkey: hotkeys,
-          exclusion_settings: ExclusionSettings::default(),
-          ..Configuration::default()
-        },
-      }
-    }
+    let auto_window_margin = &configuration_provider.config.auto_window_margin;
+    assert!(auto_window_margin.enabled);
+    assert_eq!(
+      auto_window_margin.percent_of_monitor_width,
+      DEFAULT_AUTO_WINDOW_MARGIN_PERCENT_OF_MONITOR_WIDTH
+    );
+    assert_eq!(auto_window_margin.min_in_px, DEFAULT_AUTO_WINDOW_MARGIN_MIN_IN_PX);
+    assert_eq!(auto_window_margin.max_in_px, DEFAULT_AUTO_WINDOW_MARGIN_MAX_IN_PX);
+    assert!(auto_window_margin.monitor.is_empty());
+  }
 
-    pub fn new_test(temp_path: PathBuf) -> Self {
-      let file_manager = FileManager::new_test(temp_path);
-      Self::new_with(file_manager)
-    }
+  #[test]
+  fn focus_border_replaces_invalid_colour_and_width_with_defaults() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(CONFIGURATION_FILE_NAME);
+    fs::write(
+      &path,
+      r#"
+        [general]
+        [focus_border]
+        enabled = true
+        colour = "red"
+        width_in_px = 0
+        [exclusion_settings]
+      "#,
+    )
+    .expect("Failed to write config file");
 
-    fn new_test_without_validation(temp_path: PathBuf, config: Configuration) -> Self {
-      let file_manager = FileManager::new_test(temp_path);
-      Self { file_manager, config }
-    }
+    let configuration_provider = ConfigurationProvider::new_test(path);
 
-    /// Adds a monitor override without saving it.
-    pub fn set_monitor_layout(&mut self, id: &str, layout: Layout) {
-      self.config.layout.monitor.push(MonitorLayoutConfiguration {
-        id: id.to_string(),
-        mode: layout,
-      });
-    }
+    let focus_border = configuration_provider.get_focus_border();
+    assert!(focus_border.enabled);
+    assert_eq!(focus_border.colour, DEFAULT_FOCUS_BORDER_COLOUR);
+    assert_eq!(focus_border.width_in_px, DEFAULT_FOCUS_BORDER_WIDTH_IN_PX);
+    assert_eq!(focus_border.get_colour(), 0x00D47800);
   }
 
   #[test]
-  fn scrolling_layout_loads_animation_and_reconciliation_durations() {
+  fn mouse_bindings_replace_empty_modifier_and_identical_buttons_with_defaults() {
     let directory = create_temp_directory();
     let path = directory.path().join(CONFIGURATION_FILE_NAME);
     fs::write(
       &path,
       r#"
         [general]
-        [layout]
-        [spatial_layout]
-        [scrolling_layout]
-        animation_duration_in_ms = 75
-        reconciliation_interval_in_ms = 400
+        [mouse_bindings]
+        modifier = []
+        move_button = "middle"
+        resize_button = "middle"
         [exclusion_settings]
       "#,
     )
     .expect("Failed to write config file");
+
     let configuration_provider = ConfigurationProvider::new_test(path);
 
-    assert_eq!(configuration_provider.get_i32(SCROLLING_ANIMATION_DURATION_IN_MS), 75);
-    assert_eq!(configuration_provider.get_i32(SCROLLING_RECONCILIATION_INTERVAL_IN_MS), 400);
+    let mouse_bindings = configuration_provider.get_shared_mouse_bindings();
+    let mouse_bindings = mouse_bindings.read().expect(MOUSE_BINDINGS_LOCK);
+    assert_eq!(*mouse_bindings, MouseBindingsConfiguration::default());
   }
 
   #[test]
-  fn scrolling_layout_replaces_negative_durations_with_defaults() {
+  fn picture_in_picture_replaces_invalid_size_with_defaults() {
     let directory = create_temp_directory();
     let path = directory.path().join(CONFIGURATION_FILE_NAME);
     fs::write(
       &path,
       r#"
         [general]
-        [layout]
-        [spatial_layout]
-        [scrolling_layout]
-        animation_duration_in_ms = -1
-        reconciliation_interval_in_ms = -1
+        [picture_in_picture]
+        width = 0
+        height = -10
         [exclusion_settings]
       "#,
     )
@@ -607,14 +2836,10 @@ mod tests {
 
     let configuration_provider = ConfigurationProvider::new_test(path);
 
-    assert_eq!(
-      configuration_provider.get_i32(SCROLLING_ANIMATION_DURATION_IN_MS),
-      DEFAULT_SCROLLING_ANIMATION_DURATION_IN_MS
-    );
-    assert_eq!(
-      configuration_provider.get_i32(SCROLLING_RECONCILIATION_INTERVAL_IN_MS),
-      DEFAULT_SCROLLING_RECONCILIATION_INTERVAL_IN_MS
-    );
+    let picture_in_picture = configuration_provider.get_picture_in_picture();
+    assert_eq!(picture_in_picture.width, DEFAULT_PICTURE_IN_PICTURE_WIDTH);
+    assert_eq!(picture_in_picture.height, DEFAULT_PICTURE_IN_PICTURE_HEIGHT);
+    assert_eq!(picture_in_picture.corner, Corner::BottomRight);
   }
 
   #[test]
@@ -632,6 +2857,31 @@ mod tests {
     assert_eq!(configuration_provider.get_default_layout(), Layout::Scrolling);
   }
 
+  #[test]
+  fn focus_fallback_policy_loads_snake_case_value() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(CONFIGURATION_FILE_NAME);
+    fs::write(
+      &path,
+      r#"
+        [general]
+        [layout]
+        [spatial_layout]
+        focus_fallback_policy = "most_recently_used"
+        [scrolling_layout]
+        [exclusion_settings]
+      "#,
+    )
+    .expect("Failed to write config file");
+
+    let configuration_provider = ConfigurationProvider::new_test(path);
+
+    assert_eq!(
+      configuration_provider.get_focus_fallback_policy(),
+      FocusFallbackPolicy::MostRecentlyUsed
+    );
+  }
+
   #[test]
   fn set_default_layout_persists_without_changing_monitor_overrides() {
     let directory = create_temp_directory();
@@ -738,6 +2988,7 @@ mod tests {
     assert_eq!(config.general.window_margin, DEFAULT_WINDOW_MARGIN_VALUE);
     assert!(config.spatial_layout.allow_selecting_same_center_windows);
     assert_eq!(config.layout.default, Layout::Spatial);
+    assert_eq!(config.layout.monitor_ordering, MonitorOrdering::Rows);
     assert_eq!(config.general.additional_workspace_count, 2);
     assert!(config.hotkey.is_empty());
     assert!(path.exists(), "Config file should have been created");
@@ -760,22 +3011,70 @@ mod tests {
         additional_workspace_count: 5,
         enable_features_using_mouse: true,
         delay_in_ms_before_dragging_is_allowed: 1000,
+        grid_snapping_cell_size_in_px: 0,
+        magnetic_edge_distance_in_px: 0,
+        snap_zone_distance_in_px: 0,
+        auto_snap_threshold_in_percent: 0,
+        event_loop_interval_in_ms: 20,
+        heartbeat_interval_in_s: 5,
+        startup_delay_in_ms: 0,
+        close_confirmation_timeout_in_ms: 2000,
+        workspace_badges_delay_in_ms: 0,
+        slow_workspace_switch_threshold_in_ms: 500,
         allow_moving_cursor_after_open_close_or_minimise: false,
+        enable_touchpad_gestures: false,
+        enable_usage_metrics: false,
+        keep_always_on_top_windows_visible: true,
+        preserve_relative_window_position: false,
+        enable_command_file: false,
+        ipc_server_port: 0,
+        skip_near_maximise_animation: false,
+        notify_on_failed_commands: false,
+        data_file_format: FileFormat::Json,
+        enable_efficiency_mode: false,
+        language: None,
       },
       layout: LayoutConfiguration {
         default: Layout::Scrolling,
+        monitor_ordering: MonitorOrdering::Columns,
         monitor: vec![],
       },
       spatial_layout: SpatialLayoutConfiguration {
         allow_selecting_same_center_windows: false,
+        focus_fallback_policy: FocusFallbackPolicy::Largest,
+        focus_cycle_order: FocusCycleOrder::MostRecentlyUsed,
+        snap_to_thirds: true,
       },
       scrolling_layout: ScrollingLayoutConfiguration::default(),
+      picture_in_picture: PictureInPictureConfiguration::default(),
+      focus_border: FocusBorderConfiguration::default(),
+      mouse_bindings: MouseBindingsConfiguration::default(),
+      auto_window_margin: AutoWindowMarginConfiguration::default(),
+      monitor_workspace_count: vec![],
+      workspace_fallback: vec![],
+      free_floating_workspace: vec![],
+      tiled_workspace: vec![],
+      do_not_disturb_workspace: vec![],
+      screen_sharing_workspace: vec![],
+      workspace_numbering: vec![],
+      cursor_speed: vec![],
       hotkey: vec![CustomHotkey {
         name: "Test App".to_string(),
         path: "C:\\test.exe".to_string(),
         hotkey: "y".to_string(),
         execute_as_admin: true,
       }],
+      rule: vec![WindowRule {
+        class_name: None,
+        title_contains: Some("Calculator".to_string()),
+        is_elevated: None,
+        auto_near_maximise: true,
+        border_colour: Some("#FF0000".to_string()),
+        pause_while_foreground: false,
+        size_tolerance_in_px: None,
+        confirm_before_close: false,
+      }],
+      keybindings: BTreeMap::new(),
       exclusion_settings: ExclusionSettings::default(),
     };
     let toml_string = toml::to_string_pretty(&custom_config).expect("Failed to serialize config");
@@ -785,15 +3084,28 @@ mod tests {
 
     let loaded_config = configuration_provider.config;
     assert_eq!(loaded_config.general.window_margin, 50);
+    assert_eq!(loaded_config.layout.monitor_ordering, MonitorOrdering::Columns);
     assert!(!loaded_config.spatial_layout.allow_selecting_same_center_windows);
+    assert_eq!(
+      loaded_config.spatial_layout.focus_fallback_policy,
+      FocusFallbackPolicy::Largest
+    );
+    assert_eq!(
+      loaded_config.spatial_layout.focus_cycle_order,
+      FocusCycleOrder::MostRecentlyUsed
+    );
     assert!(loaded_config.general.force_using_admin_privileges);
     assert_eq!(loaded_config.general.additional_workspace_count, 5);
     assert!(loaded_config.general.enable_features_using_mouse);
     assert_eq!(loaded_config.general.delay_in_ms_before_dragging_is_allowed, 1000);
+    assert_eq!(loaded_config.general.data_file_format, FileFormat::Json);
     assert_eq!(loaded_config.layout.default, Layout::Scrolling);
     assert_eq!(loaded_config.hotkey.len(), 1);
     assert_eq!(loaded_config.hotkey[0].name, "Test App");
     assert!(loaded_config.hotkey[0].execute_as_admin);
+    assert_eq!(loaded_config.rule.len(), 1);
+    assert_eq!(loaded_config.rule[0].title_contains.as_deref(), Some("Calculator"));
+    assert!(loaded_config.rule[0].auto_near_maximise);
     assert_eq!(
       loaded_config.exclusion_settings.window_titles,
       default_excluded_window_titles()
@@ -862,6 +3174,11 @@ mod tests {
       "Should use default value for [default_additional_workspace_count]"
     );
     assert_eq!(loaded_config.layout.default, Layout::Spatial);
+    assert_eq!(
+      loaded_config.layout.monitor_ordering,
+      MonitorOrdering::Centres,
+      "Should keep the monitor ordering of earlier versions for existing files"
+    );
     assert_eq!(loaded_config.hotkey.len(), 1);
     assert_eq!(loaded_config.hotkey[0].name, "Test App");
     assert_eq!(
@@ -907,7 +3224,11 @@ mod tests {
     assert!(config_string.contains(additional_workspace_count.as_str()));
     assert!(config_string.contains("[layout]"));
     assert!(config_string.contains("default = \"spatial\""));
+    assert!(config_string.contains("monitor_ordering = \"centres\""));
     assert!(config_string.contains("[spatial_layout]"));
+    assert!(config_string.contains("ignore_elevated_windows = false"));
+    assert!(config_string.contains("persist_ignored_windows = false"));
+    assert!(config_string.contains("focus_fallback_policy = \"closest\""));
     assert!(config_string.contains("[scrolling_layout]"));
     assert!(config_string.contains("animation_duration_in_ms = 120"));
     assert!(config_string.contains("reconciliation_interval_in_ms = 250"));
@@ -931,6 +3252,117 @@ mod tests {
     assert!(config_string.contains("window_margin = 20"));
   }
 
+  #[test]
+  fn validate_config_updates_event_loop_interval_if_zero_value_loaded() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(CONFIGURATION_FILE_NAME);
+    let config_string = r#"
+      [general]
+      event_loop_interval_in_ms = 0
+      heartbeat_interval_in_s = 0
+      "#;
+    fs::write(&path, config_string).expect("Failed to write config file");
+    let mut config = Configuration::default();
+    config.general.event_loop_interval_in_ms = 0;
+    config.general.heartbeat_interval_in_s = 0;
+    let mut configuration_provider = ConfigurationProvider::new_test_without_validation(path.clone(), config);
+
+    configuration_provider.validate_config(Some(config_string.into()));
+
+    assert_eq!(configuration_provider.get_i32(EVENT_LOOP_INTERVAL_IN_MS), 20);
+    assert_eq!(configuration_provider.get_i32(HEARTBEAT_INTERVAL_IN_S), 0);
+  }
+
+  #[test]
+  fn validate_config_resets_ipc_server_port_if_out_of_range() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(CONFIGURATION_FILE_NAME);
+    let config_string = r#"
+      [general]
+      ipc_server_port = 70000
+      "#;
+    fs::write(&path, config_string).expect("Failed to write config file");
+    let mut config = Configuration::default();
+    config.general.ipc_server_port = 70_000;
+    let mut configuration_provider = ConfigurationProvider::new_test_without_validation(path.clone(), config);
+
+    configuration_provider.validate_config(Some(config_string.into()));
+
+    assert_eq!(configuration_provider.get_i32(IPC_SERVER_PORT), 0);
+  }
+
+  #[test]
+  fn validate_config_updates_startup_delay_if_negative_value_loaded() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(CONFIGURATION_FILE_NAME);
+    let config_string = r#"
+      [general]
+      startup_delay_in_ms = -500
+      "#;
+    fs::write(&path, config_string).expect("Failed to write config file");
+    let mut config = Configuration::default();
+    config.general.startup_delay_in_ms = -500;
+    let mut configuration_provider = ConfigurationProvider::new_test_without_validation(path.clone(), config);
+
+    configuration_provider.validate_config(Some(config_string.into()));
+
+    assert_eq!(configuration_provider.get_i32(STARTUP_DELAY_IN_MS), 0);
+  }
+
+  #[test]
+  fn validate_config_updates_close_confirmation_timeout_if_zero_value_loaded() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(CONFIGURATION_FILE_NAME);
+    let config_string = r#"
+      [general]
+      close_confirmation_timeout_in_ms = 0
+      "#;
+    fs::write(&path, config_string).expect("Failed to write config file");
+    let mut config = Configuration::default();
+    config.general.close_confirmation_timeout_in_ms = 0;
+    let mut configuration_provider = ConfigurationProvider::new_test_without_validation(path.clone(), config);
+
+    configuration_provider.validate_config(Some(config_string.into()));
+
+    assert_eq!(configuration_provider.get_i32(CLOSE_CONFIRMATION_TIMEOUT_IN_MS), 2000);
+  }
+
+  #[test]
+  fn validate_config_updates_workspace_badges_delay_if_negative_value_loaded() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(CONFIGURATION_FILE_NAME);
+    let config_string = r#"
+      [general]
+      workspace_badges_delay_in_ms = -1
+      "#;
+    fs::write(&path, config_string).expect("Failed to write config file");
+    let mut config = Configuration::default();
+    config.general.workspace_badges_delay_in_ms = -1;
+    let mut configuration_provider = ConfigurationProvider::new_test_without_validation(path.clone(), config);
+
+    configuration_provider.validate_config(Some(config_string.into()));
+
+    assert_eq!(configuration_provider.get_i32(WORKSPACE_BADGES_DELAY_IN_MS), 0);
+  }
+
+  #[test]
+  fn validate_config_updates_slow_workspace_switch_threshold_if_negative_value_loaded() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(CONFIGURATION_FILE_NAME);
+    let config_string = r#"
+      [general]
+      slow_workspace_switch_threshold_in_ms = -1
+      "#;
+    fs::write(&path, config_string).expect("Failed to write config file");
+    let mut config = Configuration::default();
+    config.general.slow_workspace_switch_threshold_in_ms = -1;
+    let mut configuration_provider = ConfigurationProvider::new_test_without_validation(path.clone(), config);
+
+    configuration_provider.validate_config(Some(config_string.into()));
+
+    assert_eq!(configuration_provider.get_i32(SLOW_WORKSPACE_SWITCH_THRESHOLD_IN_MS), 500);
+  }
+
   #[test]
   fn validate_config_preserves_window_margin_if_zero_value_loaded() {
     let directory = create_temp_directory();
@@ -972,6 +3404,51 @@ mod tests {
     assert_eq!(configuration_provider.config.general.additional_workspace_count, 8);
   }
 
+  #[test]
+  fn reload_configuration_updates_shared_exclusion_settings() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(CONFIGURATION_FILE_NAME);
+    let mut configuration_provider = ConfigurationProvider::new_test(path.clone());
+    let shared_exclusion_settings = configuration_provider.get_shared_exclusion_settings();
+    let mut new_config = Configuration::default();
+    new_config.exclusion_settings.window_titles = vec!["Excluded Window".to_string()];
+    configuration_provider
+      .file_manager
+      .save(&new_config)
+      .expect("Failed to write new config file");
+
+    configuration_provider.reload_configuration();
+
+    let exclusion_settings = shared_exclusion_settings.read().expect(EXCLUSION_SETTINGS_LOCK);
+    assert_eq!(exclusion_settings.window_titles, vec!["Excluded Window".to_string()]);
+  }
+
+  #[test]
+  fn reload_configuration_updates_shared_mouse_bindings() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(CONFIGURATION_FILE_NAME);
+    let mut configuration_provider = ConfigurationProvider::new_test(path.clone());
+    let shared_mouse_bindings = configuration_provider.get_shared_mouse_bindings();
+    let mut new_config = Configuration::default();
+    new_config.mouse_bindings = MouseBindingsConfiguration {
+      modifier: vec![ModifierKey::Alt, ModifierKey::Ctrl],
+      move_button: MouseButton::Middle,
+      resize_button: MouseButton::Left,
+    };
+    configuration_provider
+      .file_manager
+      .save(&new_config)
+      .expect("Failed to write new config file");
+
+    configuration_provider.reload_configuration();
+
+    let mouse_bindings = shared_mouse_bindings.read().expect(MOUSE_BINDINGS_LOCK);
+    assert!(mouse_bindings.is_modifier(ModifierKey::Alt));
+    assert!(!mouse_bindings.is_modifier(ModifierKey::Win));
+    assert_eq!(mouse_bindings.move_button, MouseButton::Middle);
+    assert_eq!(mouse_bindings.resize_button, MouseButton::Left);
+  }
+
   #[test]
   fn reload_configuration_replaces_prior_configuration() {
     let directory = create_temp_directory();
@@ -985,22 +3462,61 @@ mod tests {
         additional_workspace_count: 8,
         enable_features_using_mouse: false,
         delay_in_ms_before_dragging_is_allowed: 500,
+        grid_snapping_cell_size_in_px: 0,
+        magnetic_edge_distance_in_px: 0,
+        snap_zone_distance_in_px: 0,
+        auto_snap_threshold_in_percent: 0,
+        event_loop_interval_in_ms: 20,
+        heartbeat_interval_in_s: 5,
+        startup_delay_in_ms: 0,
+        close_confirmation_timeout_in_ms: 2000,
+        workspace_badges_delay_in_ms: 0,
+        slow_workspace_switch_threshold_in_ms: 500,
         allow_moving_cursor_after_open_close_or_minimise: false,
+        enable_touchpad_gestures: false,
+        enable_usage_metrics: false,
+        keep_always_on_top_windows_visible: true,
+        preserve_relative_window_position: false,
+        enable_command_file: false,
+        ipc_server_port: 0,
+        skip_near_maximise_animation: false,
+        notify_on_failed_commands: false,
+        data_file_format: FileFormat::Json,
+        enable_efficiency_mode: false,
+        language: None,
       },
       layout: LayoutConfiguration {
         default: Layout::Scrolling,
+        monitor_ordering: MonitorOrdering::default(),
         monitor: vec![],
       },
       spatial_layout: SpatialLayoutConfiguration {
         allow_selecting_same_center_windows: true,
+        focus_fallback_policy: FocusFallbackPolicy::MostRecentlyUsed,
+        focus_cycle_order: FocusCycleOrder::default(),
+        snap_to_thirds: false,
       },
       scrolling_layout: ScrollingLayoutConfiguration::default(),
+      picture_in_picture: PictureInPictureConfiguration::default(),
+      focus_border: FocusBorderConfiguration::default(),
+      mouse_bindings: MouseBindingsConfiguration::default(),
+      auto_window_margin: AutoWindowMarginConfiguration::default(),
+      monitor_workspace_count: vec![],
+      workspace_fallback: vec![],
+      free_floating_workspace: vec![],
+      tiled_workspace: vec![],
+      do_not_disturb_workspace: vec![],
+      screen_sharing_workspace: vec![],
+      workspace_numbering: vec![],
+      cursor_speed: vec![],
       hotkey: vec![CustomHotkey {
         name: "Test App".to_string(),
         path: "C:\\test.exe".to_string(),
         hotkey: "y".to_string(),
         execute_as_admin: true,
       }],
+      rule: vec![],
+      keybindings: BTreeMap::new(),
       exclusion_settings: ExclusionSettings::default(),
     };
     configuration_provider
@@ -1021,6 +3537,10 @@ mod tests {
     assert_eq!(configuration_provider.config.general.additional_workspace_count, 8);
     assert!(!configuration_provider.config.general.enable_features_using_mouse);
     assert_eq!(configuration_provider.config.layout.default, Layout::Scrolling);
+    assert_eq!(
+      configuration_provider.get_focus_fallback_policy(),
+      FocusFallbackPolicy::MostRecentlyUsed
+    );
     assert_eq!(
       configuration_provider.config.general.delay_in_ms_before_dragging_is_allowed,
       500
@@ -1029,4 +3549,148 @@ mod tests {
     assert_eq!(configuration_provider.config.hotkey[0].name, "Test App");
     assert!(configuration_provider.config.hotkey[0].execute_as_admin);
   }
+
+  #[test]
+  fn import_workspace_profile_replaces_workspace_settings_of_other_configuration() {
+    let directory = create_temp_directory();
+    let mut source = ConfigurationProvider::new_test(directory.path().join("source.toml"));
+    source.set_i32(ADDITIONAL_WORKSPACE_COUNT, 4);
+    source.set_default_layout(Layout::Scrolling);
+    source.add_window_rule(WindowRule {
+      class_name: Some("Notepad".to_string()),
+      ..WindowRule::default()
+    });
+    let exported = toml::to_string(&source.export_workspace_profile()).expect("Failed to serialise profile");
+    let mut target = ConfigurationProvider::new_test(directory.path().join("target.toml"));
+
+    target.import_workspace_profile(toml::from_str(&exported).expect("Failed to deserialise profile"));
+
+    assert_eq!(target.get_i32(ADDITIONAL_WORKSPACE_COUNT), 4);
+    assert_eq!(target.get_default_layout(), Layout::Scrolling);
+    assert_eq!(target.get_window_rules().len(), 1);
+    let saved = fs::read_to_string(directory.path().join("target.toml")).expect("Failed to read config file");
+    assert!(saved.contains("additional_workspace_count = 4"));
+  }
+
+  #[test]
+  fn resolve_monitor_ids_replaces_or_drops_settings_of_unknown_monitors() {
+    let mut profile = WorkspaceProfile {
+      layout: LayoutConfiguration {
+        default: Layout::Spatial,
+        monitor_ordering: MonitorOrdering::default(),
+        monitor: vec![
+          MonitorLayoutConfiguration {
+            id: "primary".to_string(),
+            mode: Layout::Scrolling,
+          },
+          MonitorLayoutConfiguration {
+            id: "DISPLAY1".to_string(),
+            mode: Layout::Scrolling,
+          },
+          MonitorLayoutConfiguration {
+            id: "DISPLAY2".to_string(),
+            mode: Layout::Scrolling,
+          },
+          MonitorLayoutConfiguration {
+            id: "DISPLAY3".to_string(),
+            mode: Layout::Spatial,
+          },
+        ],
+      },
+      workspace_fallback: vec![WorkspaceFallback {
+        selector: WorkspaceSelector {
+          monitor: "DISPLAY3".to_string(),
+          workspace: None,
+        },
+        fallback: "primary".to_string(),
+      }],
+      ..WorkspaceProfile::default()
+    };
+    let mut asked = vec![];
+
+    profile.resolve_monitor_ids(&["DISPLAY1".to_string()], |id| {
+      asked.push(id.to_string());
+      (id == "DISPLAY2").then(|| "DISPLAY1".to_string())
+    });
+
+    assert_eq!(asked, vec!["DISPLAY2", "DISPLAY3"]);
+    let ids = profile.layout.monitor.iter().map(|m| m.id.as_str()).collect::<Vec<_>>();
+    assert_eq!(ids, vec!["primary", "DISPLAY1", "DISPLAY1"]);
+    assert!(profile.workspace_fallback.is_empty());
+  }
+
+  #[test]
+  fn window_rule_matches_only_when_all_criteria_match() {
+    let rule = WindowRule {
+      class_name: Some("CalcFrame".to_string()),
+      title_contains: Some("Calc".to_string()),
+      is_elevated: Some(false),
+      auto_near_maximise: true,
+      border_colour: None,
+      pause_while_foreground: false,
+      size_tolerance_in_px: None,
+      confirm_before_close: false,
+    };
+
+    assert!(rule.matches("Calculator", "CalcFrame", false));
+    assert!(!rule.matches("Calculator", "CalcFrame", true));
+    assert!(!rule.matches("Calculator", "Notepad", false));
+    assert!(!rule.matches("Notepad", "CalcFrame", false));
+  }
+
+  #[test]
+  fn window_rule_without_criteria_matches_nothing() {
+    let rule = WindowRule {
+      auto_near_maximise: true,
+      ..WindowRule::default()
+    };
+
+    assert!(!rule.matches("Calculator", "CalcFrame", false));
+  }
+
+  #[test]
+  fn window_rule_converts_border_colour_to_colorref() {
+    let rule = |colour: &str| WindowRule {
+      border_colour: Some(colour.to_string()),
+      ..WindowRule::default()
+    };
+
+    assert_eq!(rule("#FF8000").get_border_colour(), Some(0x000080FF));
+    assert_eq!(rule("FF8000").get_border_colour(), None);
+    assert_eq!(rule("#FF80").get_border_colour(), None);
+    assert_eq!(WindowRule::default().get_border_colour(), None);
+  }
+
+  #[test]
+  fn window_rule_moves_to_workspace_of_primary_monitor_unless_monitor_is_set() {
+    let rule = WindowRule {
+      class_name: Some("Slack".to_string()),
+      workspace: Some(3),
+      ..WindowRule::default()
+    };
+    assert!(rule.moves_to_workspace("\\\\.\\DISPLAY1", true, 3));
+    assert!(!rule.moves_to_workspace("\\\\.\\DISPLAY1", true, 2));
+    assert!(!rule.moves_to_workspace("\\\\.\\DISPLAY2", false, 3));
+
+    let rule = WindowRule {
+      monitor: Some("\\\\.\\DISPLAY2".to_string()),
+      ..rule
+    };
+    assert!(rule.moves_to_workspace("\\\\.\\DISPLAY2", false, 3));
+    assert!(!rule.moves_to_workspace("\\\\.\\DISPLAY1", true, 3));
+    assert!(!WindowRule::default().moves_to_workspace("\\\\.\\DISPLAY1", true, 1));
+  }
+
+  #[test]
+  fn window_rule_ignores_negative_size_tolerance() {
+    let rule = |tolerance: i32| WindowRule {
+      size_tolerance_in_px: Some(tolerance),
+      ..WindowRule::default()
+    };
+
+    assert_eq!(rule(12).get_size_tolerance_in_px(), Some(12));
+    assert_eq!(rule(0).get_size_tolerance_in_px(), Some(0));
+    assert_eq!(rule(-1).get_size_tolerance_in_px(), None);
+    assert_eq!(WindowRule::default().get_size_tolerance_in_px(), None);
+  }
 }
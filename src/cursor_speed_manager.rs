@@ -0,0 +1,172 @@
+use crate::api::WindowsApi;
+use crate::common::{CursorSpeed, MonitorHandle};
+use crate::configuration_provider::ConfigurationProvider;
+use crate::utils::CONFIGURATION_PROVIDER_LOCK;
+use std::sync::{Arc, Mutex};
+
+/// Applies the cursor speed profile of the monitor the cursor is on, so that flicking the mouse covers a similar
+/// distance on monitors with very different DPI. Restores the cursor speed that was set in Windows when the cursor
+/// moves to a monitor without a profile or Randolf exits.
+pub struct CursorSpeedManager<T: WindowsApi> {
+  configuration_provider: Arc<Mutex<ConfigurationProvider>>,
+  windows_api: T,
+  /// The cursor speed that was set in Windows before a profile was applied, or `None` if no profile is applied.
+  original_cursor_speed: Option<CursorSpeed>,
+  /// The monitor the cursor was on when the cursor speed was last updated.
+  monitor_handle: Option<MonitorHandle>,
+}
+
+impl<T: WindowsApi> CursorSpeedManager<T> {
+  pub fn new(configuration_provider: Arc<Mutex<ConfigurationProvider>>, windows_api: T) -> Self {
+    Self {
+      configuration_provider,
+      windows_api,
+      original_cursor_speed: None,
+      monitor_handle: None,
+    }
+  }
+
+  /// Applies the cursor speed profile of the monitor under the cursor or, if the monitor has none, restores the cursor
+  /// speed that was set in Windows. Does nothing unless the cursor moved to another monitor since the last call, so it
+  /// is cheap enough to be polled, which catches the user moving the mouse across monitors, and must also be called
+  /// after every command that may have moved the cursor, so that the new speed applies straight away.
+  pub fn update(&mut self) {
+    let cursor_position = self.windows_api.get_cursor_position();
+    let monitor_handle = self.windows_api.get_monitor_handle_for_point(&cursor_position);
+    if self.monitor_handle == Some(monitor_handle) {
+      return;
+    }
+    self.monitor_handle = Some(monitor_handle);
+    let monitors = self.windows_api.get_all_monitors();
+    let Some(monitor) = monitors.get_by_handle(monitor_handle) else {
+      return;
+    };
+    let profile = self
+      .configuration_provider
+      .lock()
+      .expect(CONFIGURATION_PROVIDER_LOCK)
+      .get_cursor_speed_profile(&monitor.id_to_string(), monitor.is_primary)
+      .cloned();
+    let Some(profile) = profile else {
+      self.restore();
+      return;
+    };
+    let Some(current_cursor_speed) = self.windows_api.get_cursor_speed() else {
+      return;
+    };
+    let original_cursor_speed = *self.original_cursor_speed.get_or_insert(current_cursor_speed);
+    let cursor_speed = profile.apply_to(original_cursor_speed);
+    if cursor_speed != current_cursor_speed {
+      debug!("Setting cursor {} on {}", cursor_speed, monitor);
+      self.windows_api.set_cursor_speed(&cursor_speed);
+    }
+  }
+
+  /// Restores the cursor speed that was set in Windows before a profile was applied. Does nothing if no profile is
+  /// applied.
+  pub fn restore(&mut self) {
+    self.monitor_handle = None;
+    if let Some(original_cursor_speed) = self.original_cursor_speed.take() {
+      debug!("Restoring cursor {}", original_cursor_speed);
+      self.windows_api.set_cursor_speed(&original_cursor_speed);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::api::MockWindowsApi;
+  use crate::common::Monitor;
+
+  fn cursor_speed_manager(configuration_provider: ConfigurationProvider) -> CursorSpeedManager<MockWindowsApi> {
+    let primary_monitor = Monitor::mock_1();
+    let secondary_monitor = Monitor::mock_2();
+    for monitor in [&primary_monitor, &secondary_monitor] {
+      MockWindowsApi::add_monitor_with_full_details(
+        monitor.id,
+        monitor.handle,
+        monitor.monitor_area,
+        monitor.work_area,
+        monitor.is_primary,
+      );
+    }
+    MockWindowsApi::set_cursor_position(primary_monitor.center);
+
+    CursorSpeedManager::new(Arc::new(Mutex::new(configuration_provider)), MockWindowsApi)
+  }
+
+  #[test]
+  fn update_applies_profile_of_monitor_under_cursor_and_restores_original_speed_on_other_monitor() {
+    let mut configuration_provider = ConfigurationProvider::default();
+    configuration_provider.add_cursor_speed_profile("DISPLAY2", 4, Some(false));
+    let mut manager = cursor_speed_manager(configuration_provider);
+    let original_cursor_speed = MockWindowsApi.get_cursor_speed();
+
+    MockWindowsApi::set_cursor_position(Monitor::mock_2().center);
+    manager.update();
+    assert_eq!(MockWindowsApi.get_cursor_speed(), Some(CursorSpeed::new(4, false)));
+
+    MockWindowsApi::set_cursor_position(Monitor::mock_1().center);
+    manager.update();
+    assert_eq!(MockWindowsApi.get_cursor_speed(), original_cursor_speed);
+  }
+
+  #[test]
+  fn update_keeps_original_acceleration_if_profile_does_not_specify_it() {
+    let mut configuration_provider = ConfigurationProvider::default();
+    configuration_provider.add_cursor_speed_profile("primary", 15, None);
+    let mut manager = cursor_speed_manager(configuration_provider);
+    MockWindowsApi.set_cursor_speed(&CursorSpeed::new(8, true));
+
+    manager.update();
+
+    assert_eq!(MockWindowsApi.get_cursor_speed(), Some(CursorSpeed::new(15, true)));
+  }
+
+  #[test]
+  fn update_restores_original_mouse_parameters_exactly_when_leaving_monitor_with_profile() {
+    let mut configuration_provider = ConfigurationProvider::default();
+    configuration_provider.add_cursor_speed_profile("DISPLAY2", 4, Some(false));
+    let mut manager = cursor_speed_manager(configuration_provider);
+    let original_cursor_speed = CursorSpeed::from_mouse_parameters(10, [4, 12, 2]);
+    MockWindowsApi.set_cursor_speed(&original_cursor_speed);
+
+    MockWindowsApi::set_cursor_position(Monitor::mock_2().center);
+    manager.update();
+    MockWindowsApi::set_cursor_position(Monitor::mock_1().center);
+    manager.update();
+
+    assert_eq!(MockWindowsApi.get_cursor_speed(), Some(original_cursor_speed));
+  }
+
+  #[test]
+  fn update_does_nothing_while_cursor_stays_on_same_monitor() {
+    let mut configuration_provider = ConfigurationProvider::default();
+    configuration_provider.add_cursor_speed_profile("DISPLAY2", 4, None);
+    let mut manager = cursor_speed_manager(configuration_provider);
+    MockWindowsApi::set_cursor_position(Monitor::mock_2().center);
+    manager.update();
+    MockWindowsApi.set_cursor_speed(&CursorSpeed::new(12, true));
+
+    manager.update();
+
+    assert_eq!(MockWindowsApi.get_cursor_speed(), Some(CursorSpeed::new(12, true)));
+  }
+
+  #[test]
+  fn restore_restores_original_speed_and_does_nothing_if_no_profile_was_applied() {
+    let mut configuration_provider = ConfigurationProvider::default();
+    configuration_provider.add_cursor_speed_profile("DISPLAY2", 4, None);
+    let mut manager = cursor_speed_manager(configuration_provider);
+    MockWindowsApi.set_cursor_speed(&CursorSpeed::new(12, true));
+
+    manager.restore();
+    assert_eq!(MockWindowsApi.get_cursor_speed(), Some(CursorSpeed::new(12, true)));
+
+    MockWindowsApi::set_cursor_position(Monitor::mock_2().center);
+    manager.update();
+    manager.restore();
+    assert_eq!(MockWindowsApi.get_cursor_speed(), Some(CursorSpeed::new(12, true)));
+  }
+}
@@ -0,0 +1,26 @@
+use crate::api::real_windows_api_for_display_changes::WindowsApiForDisplayChanges;
+use crate::common::{Command, HookHealth};
+use crossbeam_channel::Sender;
+
+/// Listens to monitors being connected, disconnected, or rearranged, e.g. when docking or undocking a laptop, and turns
+/// them into [`Command::DisplayChanged`], so that workspaces do not keep stale monitor state until the next periodic
+/// check. Also turns changes of a work area, e.g. when the taskbar is moved, into [`Command::WorkAreaChanged`].
+pub struct DisplayChangeManager {
+  api: WindowsApiForDisplayChanges,
+}
+
+impl DisplayChangeManager {
+  pub fn new(sender: Sender<Command>) -> Self {
+    Self {
+      api: WindowsApiForDisplayChanges::new(sender),
+    }
+  }
+
+  pub fn initialise(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+    self.api.initialise()
+  }
+
+  pub fn hook_health() -> HookHealth {
+    WindowsApiForDisplayChanges::hook_health()
+  }
+}
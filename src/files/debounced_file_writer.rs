@@ -0,0 +1,167 @@
+use crate::files::write_atomically;
+use crossbeam_channel::{Receiver, RecvTimeoutError, Sender, bounded, unbounded};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+enum WriteRequest {
+  Write(String),
+  Flush(Sender<()>),
+}
+
+/// Writes a single file on a dedicated thread, so that it is the only writer of the file no matter where changes come
+/// from. Changes are coalesced: the file is written at most once per `delay`, with the content of the latest change.
+/// Any pending change is written when the writer is flushed or dropped.
+pub(crate) struct DebouncedFileWriter {
+  sender: Option<Sender<WriteRequest>>,
+  thread: Option<JoinHandle<()>>,
+}
+
+impl DebouncedFileWriter {
+  pub(crate) fn new(file_path: PathBuf, delay: Duration) -> Self {
+    let (sender, receiver) = unbounded();
+    let thread = thread::Builder::new()
+      .name("file-writer".to_string())
+      .spawn(move || Self::run(&file_path, delay, &receiver))
+      .expect("Failed to spawn file writer thread");
+
+    Self {
+      sender: Some(sender),
+      thread: Some(thread),
+    }
+  }
+
+  /// Queues the content to be written to the file once the delay since the first unwritten change has passed.
+  pub(crate) fn write(&self, content: String) {
+    if let Some(sender) = &self.sender
+      && sender.send(WriteRequest::Write(content)).is_err()
+    {
+      error!("Failed to queue file write because the file writer thread has stopped");
+    }
+  }
+
+  /// Writes any pending change to the file immediately and blocks until it has been written.
+  pub(crate) fn flush(&self) {
+    let Some(sender) = &self.sender else {
+      return;
+    };
+    let (done_sender, done_receiver) = bounded(1);
+    if sender.send(WriteRequest::Flush(done_sender)).is_err() || done_receiver.recv().is_err() {
+      error!("Failed to flush file writer because the file writer thread has stopped");
+    }
+  }
+
+  fn run(file_path: &Path, delay: Duration, receiver: &Receiver<WriteRequest>) {
+    let mut pending: Option<(String, Instant)> = None;
+    loop {
+      let request = match &pending {
+        Some((_, deadline)) => match receiver.recv_deadline(*deadline) {
+          Ok(request) => Some(request),
+          Err(RecvTimeoutError::Timeout) => None,
+          Err(RecvTimeoutError::Disconnected) => break,
+        },
+        None => match receiver.recv() {
+          Ok(request) => Some(request),
+          Err(_) => break,
+        },
+      };
+      match request {
+        Some(WriteRequest::Write(content)) => {
+          let deadline = pending.map_or_else(|| Instant::now() + delay, |(_, deadline)| deadline);
+          pending = Some((content, deadline));
+        }
+        Some(WriteRequest::Flush(done_sender)) => {
+          if let Some((content, _)) = pending.take() {
+            Self::write_now(file_path, &content);
+          }
+          let _ = done_sender.send(());
+        }
+        None => {
+          if let Some((content, _)) = pending.take() {
+            Self::write_now(file_path, &content);
+          }
+        }
+      }
+    }
+    if let Some((content, _)) = pending {
+      Self::write_now(file_path, &content);
+    }
+  }
+
+  fn write_now(file_path: &Path, content: &str) {
+    info!("Saving [{}]", file_path.display());
+    if let Err(err) = write_atomically(file_path, content) {
+      error!("Failed to save [{}]: {err}", file_path.display());
+    }
+  }
+}
+
+impl Drop for DebouncedFileWriter {
+  fn drop(&mut self) {
+    // Disconnecting the channel makes the thread write any pending change and stop
+    self.sender.take();
+    if let Some(thread) = self.thread.take()
+      && thread.join().is_err()
+    {
+      error!("File writer thread panicked");
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::utils::create_temp_directory;
+  use std::fs;
+
+  #[test]
+  fn write_coalesces_changes_and_writes_latest_content_after_delay() {
+    let directory = create_temp_directory();
+    let path = directory.path().join("test.toml");
+    let writer = DebouncedFileWriter::new(path.clone(), Duration::from_millis(200));
+
+    writer.write("first".to_string());
+    writer.write("second".to_string());
+
+    assert!(!path.exists());
+    thread::sleep(Duration::from_millis(600));
+    assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+  }
+
+  #[test]
+  fn flush_writes_pending_change_immediately() {
+    let directory = create_temp_directory();
+    let path = directory.path().join("test.toml");
+    let writer = DebouncedFileWriter::new(path.clone(), Duration::from_secs(60));
+
+    writer.write("content".to_string());
+    writer.flush();
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), "content");
+  }
+
+  #[test]
+  fn drop_writes_pending_change() {
+    let directory = create_temp_directory();
+    let path = directory.path().join("test.toml");
+    let writer = DebouncedFileWriter::new(path.clone(), Duration::from_secs(60));
+
+    writer.write("content".to_string());
+    drop(writer);
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), "content");
+  }
+
+  #[test]
+  fn write_does_not_leave_temporary_file_behind() {
+    let directory = create_temp_directory();
+    let path = directory.path().join("test.toml");
+    let writer = DebouncedFileWriter::new(path.clone(), Duration::from_secs(60));
+
+    writer.write("content".to_string());
+    writer.flush();
+
+    assert_eq!(fs::read_dir(directory.path()).unwrap().count(), 1);
+  }
+}
@@ -0,0 +1,46 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+/// The format in which a `FileManager` stores its file. TOML is easy to read and to edit by hand, while JSON is
+/// written without any formatting, which makes saving and loading large files (e.g. with many workspaces and windows)
+/// faster.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileFormat {
+  #[default]
+  Toml,
+  Json,
+}
+
+impl FileFormat {
+  /// Returns the file extension used for files in this format.
+  pub fn extension(self) -> &'static str {
+    match self {
+      FileFormat::Toml => "toml",
+      FileFormat::Json => "json",
+    }
+  }
+
+  /// Returns the format that files can be converted from when they do not exist in this format yet.
+  pub(crate) fn other(self) -> Self {
+    match self {
+      FileFormat::Toml => FileFormat::Json,
+      FileFormat::Json => FileFormat::Toml,
+    }
+  }
+
+  pub(crate) fn serialise<T: Serialize>(self, t: &T) -> Result<String, Box<dyn Error>> {
+    match self {
+      FileFormat::Toml => Ok(toml::to_string_pretty(t)?),
+      FileFormat::Json => Ok(serde_json::to_string(t)?),
+    }
+  }
+
+  pub(crate) fn deserialise<T: DeserializeOwned>(self, content: &str) -> Result<T, Box<dyn Error>> {
+    match self {
+      FileFormat::Toml => Ok(toml::from_str(content)?),
+      FileFormat::Json => Ok(serde_json::from_str(content)?),
+    }
+  }
+}
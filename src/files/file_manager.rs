@@ -1,3 +1,5 @@
+use crate::files::DebouncedFileWriter;
+use crate::files::file_format::FileFormat;
 use crate::files::file_type::FileType;
 use crate::utils::{PROJECT_DIR_APPLICATION_NAME, PROJECT_DIR_ORGANISATION_NAME, PROJECT_DIR_QUALIFIER};
 use directories::ProjectDirs;
@@ -5,14 +7,20 @@ use serde::Serialize;
 use serde::de::DeserializeOwned;
 use std::error::Error;
 use std::fs;
-use std::io::ErrorKind;
+use std::fs::File;
+use std::io::{ErrorKind, Write};
 use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 /// A struct to manage file operations for a single file, located at `file_path` and deserialised to type `T`. Allows
 /// you to load, create, reload, and save this file.
 pub struct FileManager<T: Default + Serialize + DeserializeOwned> {
   file_path: PathBuf,
   file_prefix: String,
+  /// The format of the file, which is TOML unless set otherwise, see [`FileManager::set_format`].
+  format: FileFormat,
+  /// Writes the file on a separate thread if saving is delayed, see [`FileManager::set_save_delay`].
+  writer: Option<DebouncedFileWriter>,
   _marker: std::marker::PhantomData<T>,
 }
 
@@ -22,16 +30,44 @@ impl<T: Default + Serialize + DeserializeOwned> FileManager<T> {
       file_path: Self::get_path_to_file(file_name, file_type)
         .unwrap_or_else(|err| panic!("Failed to get path to {file_name}: {err}:")),
       file_prefix: String::new(),
+      format: FileFormat::default(),
+      writer: None,
       _marker: Default::default(),
     }
   }
 
+  pub fn get_path(&self) -> &Path {
+    &self.file_path
+  }
+
   /// Set the prefix to be added to the file content e.g. `# This is a comment`. Make sure the prefix ends with a
-  /// newline.
+  /// newline. The prefix is only added to TOML files.
   pub fn set_content_prefix(&mut self, prefix: &str) {
     self.file_prefix = prefix.to_string();
   }
 
+  /// Stores the file in the given format, replacing the extension of its name to match the format. If the file does not
+  /// exist in this format yet but in the other one, it is converted when it is loaded next (see
+  /// [`FileManager::load_or_create`]). Must be called before [`FileManager::set_save_delay`].
+  pub fn set_format(&mut self, format: FileFormat) {
+    self.file_path.set_extension(format.extension());
+    self.format = format;
+  }
+
+  /// Makes [`FileManager::save`] hand the content over to a dedicated writer thread, which writes the file at most once
+  /// per `delay` instead of on every save. Intended for files that change frequently. Call [`FileManager::flush`]
+  /// before the application exits to make sure that the latest content has been written.
+  pub fn set_save_delay(&mut self, delay: Duration) {
+    self.writer = Some(DebouncedFileWriter::new(self.file_path.clone(), delay));
+  }
+
+  /// Writes any content that is waiting to be saved, if saving is delayed, and blocks until it has been written.
+  pub fn flush(&self) {
+    if let Some(writer) = &self.writer {
+      writer.flush();
+    }
+  }
+
   pub fn get_path_to_directory(file_type: FileType) -> Result<PathBuf, Box<dyn Error>> {
     if let Some(project_directories) = ProjectDirs::from(
       PROJECT_DIR_QUALIFIER,
@@ -77,25 +113,89 @@ impl<T: Default + Serialize + DeserializeOwned> FileManager<T> {
     self.load_or_create_with_repair(|_| None)
   }
 
+  /// Loads the file like [`FileManager::load_or_create`] but, if the file is corrupt (i.e. it cannot be read as text or
+  /// parsed), moves it aside as a backup and creates a default file in its place. Returns the path to the backup, if
+  /// one was created, so that callers can inform the user.
+  pub fn load_or_replace_if_corrupt(&self) -> Result<(T, Option<PathBuf>), Box<dyn Error>> {
+    match self.load_or_create() {
+      Ok((t, _)) => Ok((t, None)),
+      Err(err) if Self::is_corrupt_file_error(err.as_ref()) => {
+        let backup_path = back_up(&self.file_path)?;
+        warn!(
+          "Moved corrupt [{}] to [{}] and created a new file instead",
+          self.file_path.display(),
+          backup_path.display()
+        );
+        let (t, _) = self.load_or_create()?;
+
+        Ok((t, Some(backup_path)))
+      }
+      Err(err) => Err(err),
+    }
+  }
+
+  fn is_corrupt_file_error(err: &(dyn Error + 'static)) -> bool {
+    err.is::<toml::de::Error>()
+      || err.is::<serde_json::Error>()
+      || err
+        .downcast_ref::<std::io::Error>()
+        .is_some_and(|err| err.kind() == ErrorKind::InvalidData)
+  }
+
+  /// Loads the file from the other format if it only exists in that format, e.g. because the format was changed in the
+  /// configuration, then saves it in the current format and moves the file in the other format aside as a backup, so
+  /// that switching back later converts the latest content again. Returns `None` if there is nothing to convert.
+  fn convert_from_other_format(&self) -> Result<Option<T>, Box<dyn Error>> {
+    let other_format = self.format.other();
+    let other_path = self.file_path.with_extension(other_format.extension());
+    let file_content = match fs::read_to_string(&other_path) {
+      Ok(file_content) => file_content,
+      Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+      Err(err) => return Err(Box::new(err)),
+    };
+    let t: T = other_format.deserialise(&file_content)?;
+    write_atomically(&self.file_path, &self.to_content(&t)?)?;
+    let backup_path = back_up(&other_path)?;
+    info!(
+      "Converted [{}] to [{}] and moved the original to [{}]",
+      other_path.display(),
+      self.file_path.display(),
+      backup_path.display()
+    );
+
+    Ok(Some(t))
+  }
+
+  /// Serialises `t` in the format of the file, adding the content prefix to TOML files.
+  fn to_content(&self, t: &T) -> Result<String, Box<dyn Error>> {
+    let content = self.format.serialise(t)?;
+
+    Ok(match self.format {
+      FileFormat::Toml => format!("{}{}", self.file_prefix, content),
+      FileFormat::Json => content,
+    })
+  }
+
   /// Loads the file after applying and persisting an optional text repair.
   pub fn load_or_create_with_repair(
     &self,
     repair: impl FnOnce(&str) -> Option<String>,
   ) -> Result<(T, Option<String>), Box<dyn Error>> {
+    self.flush();
     match fs::read_to_string(&self.file_path) {
       Ok(file_content) => {
         let file_content = if let Some(repaired) = repair(&file_content) {
           warn!("Repairing [{}] before parsing", self.file_path.display());
-          fs::write(&self.file_path, &repaired)?;
+          write_atomically(&self.file_path, &repaired)?;
           repaired
         } else {
           file_content
         };
-        let t: T = match toml::from_str(&file_content) {
+        let t: T = match self.format.deserialise(&file_content) {
           Ok(parsed) => parsed,
           Err(err) => {
             error!("Failed to parse [{}]: {}", self.file_path.display(), err);
-            return Err(Box::new(err));
+            return Err(err);
           }
         };
 
@@ -103,10 +203,12 @@ impl<T: Default + Serialize + DeserializeOwned> FileManager<T> {
       }
       Err(err) => {
         if err.kind() == ErrorKind::NotFound {
+          if let Some(t) = self.convert_from_other_format()? {
+            return Ok((t, None));
+          }
           info!("File not found, creating default file: {}", self.file_path.display());
           let t = T::default();
-          let toml_string = toml::to_string_pretty(&t)?;
-          fs::write(&self.file_path, format!("{}{}", self.file_prefix, toml_string))?;
+          write_atomically(&self.file_path, &self.to_content(&t)?)?;
 
           Ok((t, None))
         } else {
@@ -118,22 +220,55 @@ impl<T: Default + Serialize + DeserializeOwned> FileManager<T> {
     }
   }
 
+  /// Saves the file, either immediately or, if saving is delayed (see [`FileManager::set_save_delay`]), on the writer
+  /// thread. Either way, the file is replaced atomically so that it is never left partially written.
   pub fn save(&self, t: &T) -> Result<(), Box<dyn Error>> {
-    info!("Saving [{}]", self.file_path.display());
-    let toml_string = toml::to_string_pretty(t)?;
-    fs::write(&self.file_path, format!("{}{}", self.file_prefix, toml_string))?;
+    let content = self.to_content(t)?;
+    if let Some(writer) = &self.writer {
+      writer.write(content);
+    } else {
+      info!("Saving [{}]", self.file_path.display());
+      write_atomically(&self.file_path, &content)?;
+    }
 
     Ok(())
   }
 }
 
+/// Renames the file to `<file name>.<timestamp>.bak` in the same directory and returns the new path.
+fn back_up(file_path: &Path) -> Result<PathBuf, Box<dyn Error>> {
+  let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+  let file_name = file_path
+    .file_name()
+    .ok_or("File path does not contain a file name")?
+    .to_string_lossy();
+  let backup_path = file_path.with_file_name(format!("{file_name}.{timestamp}.bak"));
+  fs::rename(file_path, &backup_path)?;
+
+  Ok(backup_path)
+}
+
+/// Writes the content to a temporary file next to the file and then renames it to replace the file, so that readers
+/// (including Randolf after a crash) only ever see either the previous or the new content.
+pub(crate) fn write_atomically(file_path: &Path, content: &str) -> std::io::Result<()> {
+  let file_name = file_path
+    .file_name()
+    .ok_or_else(|| std::io::Error::new(ErrorKind::InvalidInput, "File path does not contain a file name"))?
+    .to_string_lossy();
+  let temporary_path = file_path.with_file_name(format!("{file_name}.tmp"));
+  let mut file = File::create(&temporary_path)?;
+  file.write_all(content.as_bytes())?;
+  file.sync_all()?;
+  drop(file);
+
+  fs::rename(&temporary_path, file_path)
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
   use crate::utils::create_temp_directory;
   use serde::Deserialize;
-  use std::fs::File;
-  use std::io::Write;
 
   #[derive(Default, Serialize, Deserialize)]
   struct TestConfig {
@@ -146,6 +281,8 @@ mod tests {
       FileManager {
         file_path: PathBuf::new(),
         file_prefix: String::new(),
+        format: FileFormat::default(),
+        writer: None,
         _marker: Default::default(),
       }
     }
@@ -154,6 +291,8 @@ mod tests {
       FileManager {
         file_path: path,
         file_prefix: String::new(),
+        format: FileFormat::default(),
+        writer: None,
         _marker: Default::default(),
       }
     }
@@ -259,4 +398,139 @@ mod tests {
       assert!(err.to_string().contains("missing field `key`"));
     }
   }
+
+  #[test]
+  fn load_or_replace_if_corrupt_backs_up_corrupt_file_and_creates_default_file() {
+    let temp_dir = create_temp_directory();
+    let file_path = temp_dir.path().join("corrupt_config.toml");
+    fs::write(&file_path, "key = \"test\"\nvalue = ").unwrap();
+    let file_manager = FileManager::<TestConfig>::new_test(file_path.clone());
+
+    let (config, backup_path) = file_manager
+      .load_or_replace_if_corrupt()
+      .expect("Failed to replace corrupt config");
+
+    let backup_path = backup_path.expect("Expected a backup of the corrupt file");
+    assert_eq!(config.key, "");
+    assert_eq!(config.value, 0);
+    assert_eq!(fs::read_to_string(&backup_path).unwrap(), "key = \"test\"\nvalue = ");
+    assert!(
+      backup_path
+        .file_name()
+        .unwrap()
+        .to_string_lossy()
+        .starts_with("corrupt_config.toml.")
+    );
+    assert!(fs::read_to_string(&file_path).unwrap().contains("value = 0"));
+  }
+
+  #[test]
+  fn load_or_replace_if_corrupt_does_not_back_up_valid_file() {
+    let temp_dir = create_temp_directory();
+    let file_path = temp_dir.path().join("valid_config.toml");
+    fs::write(&file_path, "key = \"test\"\nvalue = 42").unwrap();
+    let file_manager = FileManager::<TestConfig>::new_test(file_path);
+
+    let (config, backup_path) = file_manager.load_or_replace_if_corrupt().expect("Failed to load config");
+
+    assert_eq!(config.value, 42);
+    assert!(backup_path.is_none());
+    assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+  }
+
+  #[test]
+  fn save_replaces_file_without_leaving_temporary_file_behind() {
+    let temp_dir = create_temp_directory();
+    let file_path = temp_dir.path().join("saved_config.toml");
+    fs::write(&file_path, "key = \"old\"\nvalue = 1").unwrap();
+    let file_manager = FileManager::<TestConfig>::new_test(file_path.clone());
+
+    file_manager
+      .save(&TestConfig {
+        key: "new".to_string(),
+        value: 2,
+      })
+      .expect("Failed to save config");
+
+    assert!(fs::read_to_string(&file_path).unwrap().contains("key = \"new\""));
+    assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+  }
+
+  #[test]
+  fn save_with_save_delay_writes_file_once_flushed() {
+    let temp_dir = create_temp_directory();
+    let file_path = temp_dir.path().join("delayed_config.toml");
+    let mut file_manager = FileManager::<TestConfig>::new_test(file_path.clone());
+    file_manager.set_save_delay(Duration::from_secs(60));
+
+    file_manager
+      .save(&TestConfig {
+        key: "test".to_string(),
+        value: 42,
+      })
+      .expect("Failed to save config");
+
+    assert!(!file_path.exists());
+    file_manager.flush();
+    assert!(fs::read_to_string(&file_path).unwrap().contains("value = 42"));
+  }
+
+  #[test]
+  fn load_or_create_with_save_delay_loads_content_that_is_waiting_to_be_saved() {
+    let temp_dir = create_temp_directory();
+    let file_path = temp_dir.path().join("delayed_config.toml");
+    let mut file_manager = FileManager::<TestConfig>::new_test(file_path);
+    file_manager.set_save_delay(Duration::from_secs(60));
+    file_manager
+      .save(&TestConfig {
+        key: "test".to_string(),
+        value: 42,
+      })
+      .expect("Failed to save config");
+
+    let (config, _) = file_manager.load_or_create().expect("Failed to load config");
+
+    assert_eq!(config.value, 42);
+  }
+
+  #[test]
+  fn save_with_json_format_writes_compact_json_without_prefix_that_can_be_loaded() {
+    let temp_dir = create_temp_directory();
+    let mut file_manager = FileManager::<TestConfig>::new_test(temp_dir.path().join("json_config.toml"));
+    file_manager.set_content_prefix("# Prefix\n");
+    file_manager.set_format(FileFormat::Json);
+
+    file_manager
+      .save(&TestConfig {
+        key: "test".to_string(),
+        value: 42,
+      })
+      .expect("Failed to save config");
+    let (config, _) = file_manager.load_or_create().expect("Failed to load config");
+
+    let file_path = temp_dir.path().join("json_config.json");
+    assert_eq!(file_manager.get_path(), file_path);
+    assert_eq!(fs::read_to_string(&file_path).unwrap(), r#"{"key":"test","value":42}"#);
+    assert_eq!(config.key, "test");
+    assert_eq!(config.value, 42);
+  }
+
+  #[test]
+  fn load_or_create_converts_file_that_only_exists_in_other_format() {
+    let temp_dir = create_temp_directory();
+    let toml_path = temp_dir.path().join("converted_config.toml");
+    fs::write(&toml_path, "key = \"test\"\nvalue = 42").unwrap();
+    let mut file_manager = FileManager::<TestConfig>::new_test(toml_path.clone());
+    file_manager.set_format(FileFormat::Json);
+
+    let (config, _) = file_manager.load_or_create().expect("Failed to load config");
+
+    assert_eq!(config.value, 42);
+    assert!(!toml_path.exists());
+    assert_eq!(
+      fs::read_to_string(temp_dir.path().join("converted_config.json")).unwrap(),
+      r#"{"key":"test","value":42}"#
+    );
+    assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 2);
+  }
 }
@@ -0,0 +1,58 @@
+use crate::common::WindowHandle;
+use crate::files::{FileManager, FileType};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::path::PathBuf;
+
+pub const IGNORED_WINDOWS_FILE_NAME: &str = "ignored_windows.toml";
+const IGNORED_WINDOWS_FILE_PREFIX: &str = "# This file was written by Randolf and lists the windows that you stopped managing with the\n\
+  # \"toggle_manage_window\" hotkey. It is overwritten every time a window is toggled and read at startup if\n\
+  # \"persist_ignored_windows\" is enabled.\n\n";
+
+/// A window that the user stopped managing at runtime. Window handles are reused by Windows, which is why the class name
+/// is stored too, so that a window is only ignored again after a restart if it still belongs to the same application.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct IgnoredWindow {
+  #[serde(flatten)]
+  pub handle: WindowHandle,
+  pub class_name: String,
+}
+
+/// The windows that the user stopped managing at runtime, written to the data folder so that they remain ignored after
+/// Randolf has restarted.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct IgnoredWindowsFile {
+  pub windows: Vec<IgnoredWindow>,
+}
+
+impl IgnoredWindowsFile {
+  pub fn new(windows: Vec<IgnoredWindow>) -> Self {
+    Self { windows }
+  }
+
+  /// Writes the ignored windows to the data folder, replacing any previous list, and returns its path.
+  pub fn write_to_data_folder(&self) -> Result<PathBuf, Box<dyn Error>> {
+    let file_manager = Self::file_manager();
+    file_manager.save(self)?;
+
+    Ok(file_manager.get_path().to_path_buf())
+  }
+
+  /// Reads the ignored windows from the data folder, or returns an empty list if no window was ignored yet.
+  pub fn read_from_data_folder() -> Result<Self, Box<dyn Error>> {
+    let file_manager = Self::file_manager();
+    if !file_manager.get_path().exists() {
+      return Ok(Self::default());
+    }
+    let (ignored_windows, _) = file_manager.load_or_create()?;
+
+    Ok(ignored_windows)
+  }
+
+  fn file_manager() -> FileManager<IgnoredWindowsFile> {
+    let mut file_manager = FileManager::new(IGNORED_WINDOWS_FILE_NAME, FileType::Data);
+    file_manager.set_content_prefix(IGNORED_WINDOWS_FILE_PREFIX);
+
+    file_manager
+  }
+}
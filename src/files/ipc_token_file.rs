@@ -0,0 +1,30 @@
+use crate::files::{FileManager, FileType};
+use randolf_ipc::IPC_TOKEN_FILE_NAME;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::path::PathBuf;
+
+const IPC_TOKEN_FILE_PREFIX: &str = "# This file was written by Randolf and contains the token that IPC clients must send to\n\
+  # authenticate. It is replaced with a new token every time Randolf starts, so do not share or edit it.\n\n";
+
+/// The token that IPC clients must send before any other request, written to the data folder so that only processes
+/// that can read the files of the user running Randolf can control it.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct IpcTokenFile {
+  pub token: String,
+}
+
+impl IpcTokenFile {
+  pub fn new(token: String) -> Self {
+    Self { token }
+  }
+
+  /// Writes the token to the data folder, replacing any previous token, and returns its path.
+  pub fn write_to_data_folder(&self) -> Result<PathBuf, Box<dyn Error>> {
+    let mut file_manager = FileManager::<IpcTokenFile>::new(IPC_TOKEN_FILE_NAME, FileType::Data);
+    file_manager.set_content_prefix(IPC_TOKEN_FILE_PREFIX);
+    file_manager.save(self)?;
+
+    Ok(file_manager.get_path().to_path_buf())
+  }
+}
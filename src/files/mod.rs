@@ -1,7 +1,29 @@
+mod debounced_file_writer;
+mod file_format;
 mod file_manager;
 mod file_type;
+mod ignored_windows_file;
+mod ipc_token_file;
+mod session_file;
+mod translation_file;
+mod usage_metrics_file;
+mod window_list_file;
+mod workspace_list_file;
+mod workspace_notes_file;
 pub mod workspaces_file;
+mod zip_archive;
 
+pub use crate::files::debounced_file_writer::*;
+pub use crate::files::file_format::*;
 pub use crate::files::file_manager::*;
 pub use crate::files::file_type::*;
+pub use crate::files::ignored_windows_file::*;
+pub use crate::files::ipc_token_file::*;
+pub use crate::files::session_file::*;
+pub use crate::files::translation_file::*;
+pub use crate::files::usage_metrics_file::*;
+pub use crate::files::window_list_file::*;
+pub use crate::files::workspace_list_file::*;
+pub use crate::files::workspace_notes_file::*;
 pub use crate::files::workspaces_file::*;
+pub use crate::files::zip_archive::*;
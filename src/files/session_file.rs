@@ -0,0 +1,212 @@
+use crate::common::{PersistentWorkspaceId, Rect, WindowHandle, WindowSummary};
+use crate::files::{FileManager, FileType};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::path::PathBuf;
+
+pub const SESSION_FILE_NAME: &str = "session.toml";
+const SESSION_FILE_PREFIX: &str = "# This file was written by Randolf and contains the placement, workspace, and monitor of every\n\
+  # managed window at the time the session was saved. It is overwritten every time the session is saved and\n\
+  # re-applied when the session is restored.\n\n";
+
+/// How a window of a session matches a window that currently exists.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum MatchQuality {
+  SameApplication,
+  SameTitle,
+  SameHandle,
+}
+
+/// A managed window as it was placed when the session was saved, including everything that is required to recognise
+/// it again after its application, or Windows, was restarted and to relaunch its application if it is not running.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SessionWindow {
+  #[serde(flatten)]
+  pub handle: WindowHandle,
+  pub title: String,
+  pub class_name: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub process_name: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub executable_path: Option<String>,
+  pub workspace: PersistentWorkspaceId,
+  pub rect: Rect,
+}
+
+impl SessionWindow {
+  /// Returns how well the window matches this window of the session, or `None` if it belongs to another application.
+  /// Window handles are reused by Windows, which is why the same handle only counts if the application is the same.
+  fn match_quality(&self, window: &WindowSummary) -> Option<MatchQuality> {
+    if window.class_name != self.class_name || window.process_name != self.process_name {
+      return None;
+    }
+
+    Some(if window.handle == self.handle {
+      MatchQuality::SameHandle
+    } else if window.title == self.title {
+      MatchQuality::SameTitle
+    } else {
+      MatchQuality::SameApplication
+    })
+  }
+}
+
+/// The managed windows of a session, written to the data folder so that their placement can be re-applied later.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct SessionFile {
+  pub windows: Vec<SessionWindow>,
+}
+
+impl SessionFile {
+  pub fn new(windows: Vec<SessionWindow>) -> Self {
+    Self { windows }
+  }
+
+  /// Writes the session to the data folder, replacing any previous session, and returns its path.
+  pub fn write_to_data_folder(&self) -> Result<PathBuf, Box<dyn Error>> {
+    let file_manager = Self::file_manager();
+    file_manager.save(self)?;
+
+    Ok(file_manager.get_path().to_path_buf())
+  }
+
+  /// Reads the most recently saved session from the data folder. Fails if no session was saved yet.
+  pub fn read_from_data_folder() -> Result<Self, Box<dyn Error>> {
+    let file_manager = Self::file_manager();
+    if !file_manager.get_path().exists() {
+      return Err(format!("there is no saved session at [{}]", file_manager.get_path().display()).into());
+    }
+    let (session, _) = file_manager.load_or_create()?;
+
+    Ok(session)
+  }
+
+  /// Assigns each window of the session to at most one of the given windows, preferring the window with the same handle,
+  /// then one with the same title, and then any other window of the same application. Returns the assigned windows and
+  /// the windows of the session that none of the given windows matches.
+  pub fn match_windows(&self, windows: &[WindowSummary]) -> (Vec<(WindowHandle, &SessionWindow)>, Vec<&SessionWindow>) {
+    let mut assigned = vec![None; self.windows.len()];
+    for quality in [
+      MatchQuality::SameHandle,
+      MatchQuality::SameTitle,
+      MatchQuality::SameApplication,
+    ] {
+      for (i, session_window) in self.windows.iter().enumerate() {
+        if assigned[i].is_some() {
+          continue;
+        }
+        let handle = windows
+          .iter()
+          .filter(|window| !assigned.contains(&Some(window.handle)))
+          .find(|window| session_window.match_quality(window) == Some(quality))
+          .map(|window| window.handle);
+        assigned[i] = handle;
+      }
+    }
+
+    let (matched, missing): (Vec<_>, Vec<_>) = self.windows.iter().zip(assigned).partition(|(_, handle)| handle.is_some());
+
+    (
+      matched
+        .into_iter()
+        .filter_map(|(session_window, handle)| Some((handle?, session_window)))
+        .collect(),
+      missing.into_iter().map(|(session_window, _)| session_window).collect(),
+    )
+  }
+
+  fn file_manager() -> FileManager<SessionFile> {
+    let mut file_manager = FileManager::new(SESSION_FILE_NAME, FileType::Data);
+    file_manager.set_content_prefix(SESSION_FILE_PREFIX);
+
+    file_manager
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::common::WindowVisibility;
+
+  fn session_window(hwnd: isize, title: &str, process_name: &str) -> SessionWindow {
+    SessionWindow {
+      handle: WindowHandle::new(hwnd),
+      title: title.to_string(),
+      class_name: "Class".to_string(),
+      process_name: Some(process_name.to_string()),
+      executable_path: Some(format!("C:\\Program Files\\{process_name}")),
+      workspace: PersistentWorkspaceId::new_test(2),
+      rect: Rect::new(0, 0, 100, 100),
+    }
+  }
+
+  fn summary(hwnd: isize, title: &str, process_name: &str) -> WindowSummary {
+    WindowSummary {
+      handle: WindowHandle::new(hwnd),
+      title: title.to_string(),
+      class_name: "Class".to_string(),
+      process_name: Some(process_name.to_string()),
+      monitor_id: "P_DISPLAY".to_string(),
+      workspace: 1,
+      visibility: WindowVisibility::Visible,
+    }
+  }
+
+  #[test]
+  fn match_windows_prefers_same_handle_then_same_title_then_same_application() {
+    let session = SessionFile::new(vec![
+      session_window(1, "Notes", "notepad.exe"),
+      session_window(2, "Report", "notepad.exe"),
+      session_window(3, "Inbox", "outlook.exe"),
+      session_window(4, "Music", "spotify.exe"),
+    ]);
+    let windows = vec![
+      summary(10, "Todo", "notepad.exe"),
+      summary(11, "Report", "notepad.exe"),
+      summary(1, "Notes (modified)", "notepad.exe"),
+      summary(3, "Inbox", "code.exe"),
+      summary(12, "Calendar", "outlook.exe"),
+    ];
+
+    let (matched, missing) = session.match_windows(&windows);
+
+    let matched = matched
+      .into_iter()
+      .map(|(handle, session_window)| (handle.hwnd, session_window.handle.hwnd))
+      .collect::<Vec<_>>();
+    assert_eq!(matched, vec![(1, 1), (11, 2), (12, 3)]);
+    assert_eq!(missing, vec![&session.windows[3]]);
+  }
+
+  #[test]
+  fn match_windows_assigns_each_window_only_once() {
+    let session = SessionFile::new(vec![
+      session_window(1, "Notes", "notepad.exe"),
+      session_window(2, "Notes", "notepad.exe"),
+    ]);
+    let windows = vec![summary(10, "Notes", "notepad.exe")];
+
+    let (matched, missing) = session.match_windows(&windows);
+
+    assert_eq!(matched.len(), 1);
+    assert_eq!(matched[0].0, WindowHandle::new(10));
+    assert_eq!(missing, vec![&session.windows[1]]);
+  }
+
+  #[test]
+  fn session_windows_survive_round_trip() {
+    let session = SessionFile::new(vec![
+      session_window(1, "Notes", "notepad.exe"),
+      SessionWindow {
+        process_name: None,
+        executable_path: None,
+        ..session_window(2, "Elevated", "")
+      },
+    ]);
+
+    let content = toml::to_string(&session).expect("Failed to serialise session");
+    let parsed = toml::from_str::<SessionFile>(&content).expect("Failed to parse session");
+
+    assert_eq!(parsed.windows, session.windows);
+  }
+}
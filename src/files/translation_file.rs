@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The texts of the tray menu and of notifications in a single language, keyed by the names of
+/// [`crate::localisation::Text`] variants in snake case, e.g. `open_config_folder = "Konfigurationsordner öffnen"`.
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct TranslationFile {
+  #[serde(flatten)]
+  pub texts: HashMap<String, String>,
+}
@@ -0,0 +1,81 @@
+use crate::files::FileManager;
+use crate::localisation::Text;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+/// Counts how often each command has been used. Stored locally in the data folder only and never sent anywhere.
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct UsageMetricsFile {
+  pub commands: BTreeMap<String, u64>,
+}
+
+impl UsageMetricsFile {
+  /// Increments the count of the given command and saves the changes using the provided file manager.
+  pub(crate) fn increment(&mut self, file_manager: &FileManager<UsageMetricsFile>, command_name: &str) {
+    *self.commands.entry(command_name.to_string()).or_default() += 1;
+    if let Err(err) = file_manager.save(self) {
+      error!("Failed to save usage metrics: {err}");
+    }
+  }
+}
+
+impl Display for UsageMetricsFile {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    if self.commands.is_empty() {
+      return write!(f, "{}", Text::NoUsageMetricsRecorded.localised());
+    }
+    let mut commands = self.commands.iter().collect::<Vec<_>>();
+    commands.sort_by(|(a_name, a_count), (b_name, b_count)| b_count.cmp(a_count).then(a_name.cmp(b_name)));
+    let lines = commands
+      .iter()
+      .map(|(name, count)| format!("{name}: {count}"))
+      .collect::<Vec<_>>();
+
+    write!(f, "{}", lines.join("\n"))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::utils::create_temp_directory;
+
+  #[test]
+  fn increment_counts_commands_and_saves_them_to_file() {
+    let directory = create_temp_directory();
+    let path = directory.path().join("usage.toml");
+    let file_manager = FileManager::<UsageMetricsFile>::new_test(path.clone());
+    let mut usage_metrics = UsageMetricsFile::default();
+
+    usage_metrics.increment(&file_manager, "Move window");
+    usage_metrics.increment(&file_manager, "Move window");
+    usage_metrics.increment(&file_manager, "Close window");
+
+    assert_eq!(usage_metrics.commands.get("Move window"), Some(&2));
+    assert_eq!(usage_metrics.commands.get("Close window"), Some(&1));
+    let saved = std::fs::read_to_string(path).expect("Failed to read usage metrics file");
+    assert!(saved.contains("\"Move window\" = 2"));
+  }
+
+  #[test]
+  fn to_string_lists_most_used_commands_first() {
+    let usage_metrics = UsageMetricsFile {
+      commands: BTreeMap::from([
+        ("Close window".to_string(), 1),
+        ("Switch workspace".to_string(), 7),
+        ("Move window".to_string(), 3),
+      ]),
+    };
+
+    assert_eq!(
+      usage_metrics.to_string(),
+      "Switch workspace: 7\nMove window: 3\nClose window: 1"
+    );
+  }
+
+  #[test]
+  fn to_string_explains_when_no_commands_were_recorded() {
+    assert_eq!(UsageMetricsFile::default().to_string(), "No commands recorded yet");
+  }
+}
@@ -0,0 +1,31 @@
+use crate::common::WindowSummary;
+use crate::files::{FileManager, FileType};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::path::PathBuf;
+
+pub const WINDOW_LIST_FILE_NAME: &str = "windows.toml";
+const WINDOW_LIST_FILE_PREFIX: &str = "# This file was written by Randolf and lists the managed windows that matched the most\n\
+  # recent \"list-windows\" command. It is overwritten every time the command is executed.\n\n";
+
+/// The managed windows that matched a [`WindowQuery`][crate::common::WindowQuery], written to the data folder so that
+/// external tools can read them.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct WindowListFile {
+  pub windows: Vec<WindowSummary>,
+}
+
+impl WindowListFile {
+  pub fn new(windows: Vec<WindowSummary>) -> Self {
+    Self { windows }
+  }
+
+  /// Writes the window list to the data folder, replacing any previous list, and returns its path.
+  pub fn write_to_data_folder(&self) -> Result<PathBuf, Box<dyn Error>> {
+    let mut file_manager = FileManager::<WindowListFile>::new(WINDOW_LIST_FILE_NAME, FileType::Data);
+    file_manager.set_content_prefix(WINDOW_LIST_FILE_PREFIX);
+    file_manager.save(self)?;
+
+    Ok(file_manager.get_path().to_path_buf())
+  }
+}
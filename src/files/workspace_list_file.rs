@@ -0,0 +1,30 @@
+use crate::common::WorkspaceSummary;
+use crate::files::{FileManager, FileType};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::path::PathBuf;
+
+pub const WORKSPACE_LIST_FILE_NAME: &str = "workspace_list.toml";
+const WORKSPACE_LIST_FILE_PREFIX: &str = "# This file was written by Randolf and describes all workspaces at the time of the most\n\
+  # recent \"list-workspaces\" command. It is overwritten every time the command is executed.\n\n";
+
+/// Every workspace together with its metadata, written to the data folder so that external tools can read them.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct WorkspaceListFile {
+  pub workspaces: Vec<WorkspaceSummary>,
+}
+
+impl WorkspaceListFile {
+  pub fn new(workspaces: Vec<WorkspaceSummary>) -> Self {
+    Self { workspaces }
+  }
+
+  /// Writes the workspace list to the data folder, replacing any previous list, and returns its path.
+  pub fn write_to_data_folder(&self) -> Result<PathBuf, Box<dyn Error>> {
+    let mut file_manager = FileManager::<WorkspaceListFile>::new(WORKSPACE_LIST_FILE_NAME, FileType::Data);
+    file_manager.set_content_prefix(WORKSPACE_LIST_FILE_PREFIX);
+    file_manager.save(self)?;
+
+    Ok(file_manager.get_path().to_path_buf())
+  }
+}
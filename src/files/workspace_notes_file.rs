@@ -0,0 +1,43 @@
+use crate::common::PersistentWorkspaceId;
+use serde::{Deserialize, Serialize};
+
+/// The notes that the user has written for their workspaces, e.g. to label what each workspace is used for. Stored in
+/// the data folder and edited by the user directly.
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct WorkspaceNotesFile {
+  #[serde(default, rename = "note")]
+  pub notes: Vec<WorkspaceNote>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct WorkspaceNote {
+  pub monitor: String,
+  pub workspace: usize,
+  pub text: String,
+}
+
+impl WorkspaceNotesFile {
+  /// Returns the note of the given workspace, if there is one.
+  pub fn get(&self, id: PersistentWorkspaceId) -> Option<&WorkspaceNote> {
+    let monitor = id.id_to_string();
+    self
+      .notes
+      .iter()
+      .find(|note| note.monitor == monitor && note.workspace == id.workspace)
+  }
+
+  /// Adds an empty note for the given workspace, unless it already has one, so that the user only needs to fill in the
+  /// text. Returns `true` if a note was added.
+  pub fn add_empty(&mut self, id: PersistentWorkspaceId) -> bool {
+    if self.get(id).is_some() {
+      return false;
+    }
+    self.notes.push(WorkspaceNote {
+      monitor: id.id_to_string(),
+      workspace: id.workspace,
+      text: String::new(),
+    });
+
+    true
+  }
+}
@@ -1,12 +1,75 @@
-use crate::common::{PersistentWorkspaceId, Window, WindowHandle};
+use crate::common::{PersistentWorkspaceId, Rect, Window, WindowHandle, Workspace};
 use crate::files::FileManager;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::fmt::Display;
 
+/// A window hidden in an inactive workspace, including everything that is required to hide it in the same workspace
+/// again after Randolf has restarted. The rect is missing for windows written by older versions of Randolf. The owner
+/// is only set for windows that are hidden together with the window that owns them (e.g. the tool windows of an IDE).
+/// At most one window per workspace is marked as last focused, which is the window that had focus when the windows of
+/// the workspace were stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct StoredWindow {
+  #[serde(flatten)]
+  pub handle: WindowHandle,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub rect: Option<Rect>,
+  #[serde(default)]
+  pub is_minimised: bool,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub owner: Option<WindowHandle>,
+  #[serde(default)]
+  pub is_last_focused: bool,
+}
+
+impl StoredWindow {
+  pub fn new(window: &Window, is_minimised: bool) -> Self {
+    Self {
+      handle: window.handle,
+      rect: Some(window.rect),
+      is_minimised,
+      owner: None,
+      is_last_focused: false,
+    }
+  }
+
+  /// Creates a stored window for a window that is hidden together with the window that owns it.
+  pub fn new_owned(window: &Window, owner: WindowHandle) -> Self {
+    Self {
+      handle: window.handle,
+      rect: Some(window.rect),
+      is_minimised: false,
+      owner: Some(owner),
+      is_last_focused: false,
+    }
+  }
+
+  /// Returns every window stored in the workspace, using the rect and minimised state that the workspace stores for it,
+  /// followed by the windows stored together with their owner. Marks the window that had focus when the windows were
+  /// stored as last focused.
+  pub fn all_from(workspace: &Workspace) -> Vec<Self> {
+    let last_focused_window = workspace.get_last_focused_window().map(|window| window.handle);
+    let windows = workspace.stored_windows().iter().map(|window| Self {
+      is_last_focused: last_focused_window == Some(window.handle),
+      ..Self::new(window, workspace.is_stored_window_minimised(&window.handle))
+    });
+    let owned_windows = workspace
+      .stored_owned_windows()
+      .iter()
+      .map(|(owner, window)| Self::new_owned(window, *owner));
+
+    windows.chain(owned_windows).collect()
+  }
+}
+
 #[derive(Debug, Deserialize, Serialize, Default)]
 pub struct WorkspacesFile {
-  pub workspaces: HashMap<PersistentWorkspaceId, HashSet<WindowHandle>>,
+  pub workspaces: HashMap<PersistentWorkspaceId, Vec<StoredWindow>>,
+  #[serde(skip)]
+  is_saving_deferred: bool,
+  #[serde(skip)]
+  has_unsaved_changes: bool,
 }
 
 impl WorkspacesFile {
@@ -14,38 +77,47 @@ impl WorkspacesFile {
   pub fn new() -> Self {
     Self {
       workspaces: HashMap::new(),
+      is_saving_deferred: false,
+      has_unsaved_changes: false,
+    }
+  }
+
+  /// Stops changes from being written to disk until [`WorkspacesFile::save_deferred_changes`] is called. Used to
+  /// persist the result of several workspace operations with a single save.
+  pub(crate) fn defer_saving(&mut self) {
+    self.is_saving_deferred = true;
+  }
+
+  /// Resumes saving after [`WorkspacesFile::defer_saving`] and writes any changes made in the meantime to disk.
+  pub(crate) fn save_deferred_changes(&mut self, file_manager: &FileManager<WorkspacesFile>) {
+    self.is_saving_deferred = false;
+    if self.has_unsaved_changes {
+      self.save(file_manager);
     }
   }
 
-  /// Adds a window handle to the specified workspace and saves the changed using the provided file manager.
+  /// Adds a window to the specified workspace, replacing any previous entry for the same window, and saves the changes
+  /// using the provided file manager.
   pub(crate) fn add(
     &mut self,
     file_manager: &FileManager<WorkspacesFile>,
     workspace_id: &PersistentWorkspaceId,
-    window_handle: &WindowHandle,
+    window: StoredWindow,
   ) {
-    if let Some(workspace) = self.workspaces.get_mut(workspace_id) {
-      workspace.insert(*window_handle);
-    } else {
-      self.workspaces.insert(*workspace_id, HashSet::from([*window_handle]));
-    }
-    self.save(file_manager);
+    self.add_all(file_manager, workspace_id, &[window]);
   }
 
-  // Adds all window handles to the specified workspace and saves the changes using the provided file manager.
+  /// Adds all windows to the specified workspace, replacing any previous entries for the same windows, and saves the
+  /// changes using the provided file manager.
   pub(crate) fn add_all(
     &mut self,
     file_manager: &FileManager<WorkspacesFile>,
     workspace_id: &PersistentWorkspaceId,
-    windows: &[Window],
+    windows: &[StoredWindow],
   ) {
-    if let Some(workspace) = self.workspaces.get_mut(workspace_id) {
-      workspace.extend(windows.iter().map(|w| w.handle));
-    } else {
-      self
-        .workspaces
-        .insert(*workspace_id, windows.iter().map(|w| w.handle).collect());
-    }
+    let workspace = self.workspaces.entry(*workspace_id).or_default();
+    workspace.retain(|stored| !windows.iter().any(|window| window.handle == stored.handle));
+    workspace.extend_from_slice(windows);
     self.save(file_manager);
   }
 
@@ -73,11 +145,37 @@ impl WorkspacesFile {
       if id == excluded_workspace_id {
         continue;
       }
-      windows.retain(|&handle| !handles_to_remove.contains(&handle));
+      windows.retain(|window| {
+        !handles_to_remove.contains(&window.handle) && !window.owner.is_some_and(|owner| handles_to_remove.contains(&owner))
+      });
     }
     self.save(file_manager);
   }
 
+  /// Removes all window handles for which `is_dead` returns `true` from all workspaces and returns them. Only saves the
+  /// changes using the provided file manager if any window handle was removed.
+  pub(crate) fn remove_dead_windows(
+    &mut self,
+    file_manager: &FileManager<WorkspacesFile>,
+    is_dead: impl Fn(WindowHandle) -> bool,
+  ) -> Vec<WindowHandle> {
+    let mut dead_handles = Vec::new();
+    for windows in self.workspaces.values_mut() {
+      windows.retain(|window| {
+        if is_dead(window.handle) {
+          dead_handles.push(window.handle);
+          return false;
+        }
+        true
+      });
+    }
+    if !dead_handles.is_empty() {
+      self.save(file_manager);
+    }
+
+    dead_handles
+  }
+
   /// Clears all workspaces and saves the changes using the provided file manager.
   pub(crate) fn clear(&mut self, file_manager: &FileManager<WorkspacesFile>) {
     self.workspaces.clear();
@@ -86,7 +184,12 @@ impl WorkspacesFile {
 
   // TODO: Check whether serialisation can be done a little cleaner (no duplicate entries, etc.)
   fn save(&mut self, file_manager: &FileManager<WorkspacesFile>) {
+    if self.is_saving_deferred {
+      self.has_unsaved_changes = true;
+      return;
+    }
     file_manager.save(self).expect("Failed to save workspace file");
+    self.has_unsaved_changes = false;
   }
 }
 
@@ -95,7 +198,7 @@ impl Display for WorkspacesFile {
     for (i, (workspace_id, windows)) in self.workspaces.iter().enumerate() {
       write!(f, "{}: [", workspace_id)?;
       for (j, window) in windows.iter().enumerate() {
-        write!(f, "{}", window)?;
+        write!(f, "{}", window.handle)?;
         if j < windows.len() - 1 {
           write!(f, ", ")?;
         }
@@ -112,10 +215,15 @@ impl Display for WorkspacesFile {
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::common::Rect;
+  use crate::api::MockWindowsApi;
+  use crate::common::{Monitor, Rect};
   use crate::utils::create_temp_directory;
   use std::fs;
 
+  fn stored_window(hwnd: isize) -> StoredWindow {
+    StoredWindow::new(&Window::new_test(hwnd, Rect::default()), false)
+  }
+
   #[test]
   fn clear_removes_all_workspaces() {
     let directory = create_temp_directory();
@@ -123,9 +231,8 @@ mod tests {
     let file_manager = FileManager::new_test(file);
     let mut workspace_file = WorkspacesFile::new();
     let workspace_id = PersistentWorkspaceId::new_test(1);
-    let window_handle = WindowHandle::from(1);
 
-    workspace_file.add(&file_manager, &workspace_id, &window_handle);
+    workspace_file.add(&file_manager, &workspace_id, stored_window(1));
     workspace_file.clear(&file_manager);
 
     assert!(workspace_file.workspaces.is_empty());
@@ -138,9 +245,8 @@ mod tests {
     let file_manager = FileManager::new_test(file.clone());
     let mut workspace_file = WorkspacesFile::new();
     let workspace_id = PersistentWorkspaceId::new_test(1);
-    let window_handle = WindowHandle::from(1);
 
-    workspace_file.add(&file_manager, &workspace_id, &window_handle);
+    workspace_file.add(&file_manager, &workspace_id, stored_window(1));
     workspace_file.clear(&file_manager);
 
     assert_eq!(
@@ -156,13 +262,110 @@ mod tests {
     let file_manager = FileManager::new_test(file.clone());
     let mut workspace_file = WorkspacesFile::new();
     let workspace_id = PersistentWorkspaceId::new_test(1);
-    let windows = vec![Window::new_test(1, Rect::default()), Window::new_test(2, Rect::default())];
+    let windows = vec![stored_window(1), stored_window(2)];
 
     workspace_file.add_all(&file_manager, &workspace_id, &windows);
 
     assert_eq!(workspace_file.workspaces[&workspace_id].len(), 2);
   }
 
+  #[test]
+  fn add_replaces_previous_entry_of_same_window() {
+    let directory = create_temp_directory();
+    let file = directory.path().join("test.toml");
+    let file_manager = FileManager::new_test(file);
+    let mut workspace_file = WorkspacesFile::new();
+    let workspace_id = PersistentWorkspaceId::new_test(1);
+    let moved_window = StoredWindow::new(&Window::new_test(1, Rect::new(10, 10, 110, 110)), false);
+
+    workspace_file.add(&file_manager, &workspace_id, stored_window(1));
+    workspace_file.add(&file_manager, &workspace_id, moved_window);
+
+    assert_eq!(workspace_file.workspaces[&workspace_id], vec![moved_window]);
+  }
+
+  #[test]
+  fn stored_windows_survive_round_trip_and_windows_without_rect_can_be_read() {
+    let mut workspace_file = WorkspacesFile::new();
+    let workspace_id = PersistentWorkspaceId::new_test(1);
+    let window = StoredWindow::new(&Window::new_test(1, Rect::new(10, 20, 110, 220)), true);
+    workspace_file.workspaces.insert(workspace_id, vec![window]);
+
+    let serialised = toml::to_string(&workspace_file).expect("Failed to serialise workspaces file");
+    let deserialised: WorkspacesFile = toml::from_str(&serialised).expect("Failed to deserialise workspaces file");
+    let legacy: WorkspacesFile =
+      toml::from_str("[[workspaces.\"P_DISPLAY|1|true\"]]\nhwnd = 2\n").expect("Failed to deserialise legacy file");
+
+    assert_eq!(deserialised.workspaces[&workspace_id], vec![window]);
+    assert_eq!(
+      legacy.workspaces[&workspace_id],
+      vec![StoredWindow {
+        handle: WindowHandle::from(2),
+        rect: None,
+        is_minimised: false,
+        owner: None,
+        is_last_focused: false,
+      }]
+    );
+  }
+
+  #[test]
+  fn all_from_includes_owned_windows_which_survive_round_trip() {
+    let owner = Window::new_test(1, Rect::new(0, 0, 800, 600));
+    let owned_window = Window::new_test(2, Rect::new(10, 10, 110, 110));
+    for window in [&owner, &owned_window] {
+      MockWindowsApi::add_or_update_window(window.handle, window.title.clone(), window.rect.into(), false, true, false);
+    }
+    let workspace_id = PersistentWorkspaceId::new_test(1);
+    let mut workspace = Workspace::new_test(workspace_id, &Monitor::mock_1());
+    workspace.store_hidden_window(owner.clone(), false, &MockWindowsApi);
+    workspace.store_hidden_owned_window(owner.handle, owned_window.clone(), &MockWindowsApi);
+    let mut workspace_file = WorkspacesFile::new();
+    workspace_file
+      .workspaces
+      .insert(workspace_id, StoredWindow::all_from(&workspace));
+
+    let serialised = toml::to_string(&workspace_file).expect("Failed to serialise workspaces file");
+    let deserialised: WorkspacesFile = toml::from_str(&serialised).expect("Failed to deserialise workspaces file");
+
+    assert_eq!(
+      deserialised.workspaces[&workspace_id],
+      vec![
+        StoredWindow::new(&owner, false),
+        StoredWindow::new_owned(&owned_window, owner.handle)
+      ]
+    );
+  }
+
+  #[test]
+  fn all_from_marks_last_focused_window_which_survives_round_trip() {
+    let focused_window = Window::new_test(1, Rect::new(0, 0, 800, 600));
+    let other_window = Window::new_test(2, Rect::new(10, 10, 110, 110));
+    for window in [&focused_window, &other_window] {
+      MockWindowsApi::add_or_update_window(window.handle, window.title.clone(), window.rect.into(), false, false, false);
+    }
+    MockWindowsApi::set_foreground_window(focused_window.handle);
+    let workspace_id = PersistentWorkspaceId::new_test(1);
+    let mut workspace = Workspace::new_test(workspace_id, &Monitor::mock_1());
+    workspace.store_and_hide_windows(vec![focused_window.clone(), other_window.clone()], 1.into(), &MockWindowsApi);
+    let mut workspace_file = WorkspacesFile::new();
+    workspace_file
+      .workspaces
+      .insert(workspace_id, StoredWindow::all_from(&workspace));
+
+    let serialised = toml::to_string(&workspace_file).expect("Failed to serialise workspaces file");
+    let deserialised: WorkspacesFile = toml::from_str(&serialised).expect("Failed to deserialise workspaces file");
+
+    let is_last_focused = deserialised.workspaces[&workspace_id]
+      .iter()
+      .map(|window| (window.handle, window.is_last_focused))
+      .collect::<Vec<_>>();
+    assert_eq!(
+      is_last_focused,
+      vec![(focused_window.handle, true), (other_window.handle, false)]
+    );
+  }
+
   #[test]
   fn add_all_adds_updates_file_on_disk() {
     let directory = create_temp_directory();
@@ -170,7 +373,7 @@ mod tests {
     let file_manager = FileManager::new_test(file.clone());
     let mut workspace_file = WorkspacesFile::new();
     let workspace_id = PersistentWorkspaceId::new_test(1);
-    let windows = vec![Window::new_test(1, Rect::default()), Window::new_test(2, Rect::default())];
+    let windows = vec![stored_window(1), stored_window(2)];
 
     workspace_file.add_all(&file_manager, &workspace_id, &windows);
 
@@ -179,6 +382,30 @@ mod tests {
     assert!(file.contains("[[workspaces.\"P_DISPLAY|1|true\"]]\nhwnd = 2\n"));
   }
 
+  #[test]
+  fn remove_dead_windows_removes_dead_handles_from_all_workspaces_and_updates_file_on_disk() {
+    let directory = create_temp_directory();
+    let file = directory.path().join("test.toml");
+    let file_manager = FileManager::new_test(file.clone());
+    let mut workspace_file = WorkspacesFile::new();
+    let workspace_id_1 = PersistentWorkspaceId::new_test(1);
+    let workspace_id_2 = PersistentWorkspaceId::new_test(2);
+    workspace_file.add(&file_manager, &workspace_id_1, stored_window(1));
+    workspace_file.add(&file_manager, &workspace_id_1, stored_window(2));
+    workspace_file.add(&file_manager, &workspace_id_2, stored_window(3));
+
+    let dead_handles = workspace_file.remove_dead_windows(&file_manager, |handle| handle != WindowHandle::from(2));
+
+    let mut dead_handles = dead_handles.into_iter().map(|handle| handle.hwnd).collect::<Vec<_>>();
+    dead_handles.sort();
+    assert_eq!(dead_handles, vec![1, 3]);
+    assert_eq!(workspace_file.workspaces[&workspace_id_1], vec![stored_window(2)]);
+    assert!(workspace_file.workspaces[&workspace_id_2].is_empty());
+    let file = fs::read_to_string(file).expect("Failed to read config file");
+    assert!(file.contains("hwnd = 2\n"));
+    assert!(!file.contains("hwnd = 1\n"));
+  }
+
   #[test]
   fn remove_workspace_removes_specified_workspace() {
     let directory = create_temp_directory();
@@ -186,8 +413,7 @@ mod tests {
     let file_manager = FileManager::new_test(file);
     let mut workspace_file = WorkspacesFile::new();
     let workspace_id = PersistentWorkspaceId::new_test(1);
-    let window_handle = WindowHandle::from(1);
-    workspace_file.add(&file_manager, &workspace_id, &window_handle);
+    workspace_file.add(&file_manager, &workspace_id, stored_window(1));
 
     workspace_file.remove_workspace(&file_manager, &workspace_id);
 
@@ -201,8 +427,7 @@ mod tests {
     let file_manager = FileManager::new_test(file.clone());
     let mut workspace_file = WorkspacesFile::new();
     let workspace_id = PersistentWorkspaceId::new_test(1);
-    let window_handle = WindowHandle::from(1);
-    workspace_file.add(&file_manager, &workspace_id, &window_handle);
+    workspace_file.add(&file_manager, &workspace_id, stored_window(1));
 
     workspace_file.remove_workspace(&file_manager, &workspace_id);
 
@@ -221,14 +446,14 @@ mod tests {
     let workspace_id_2 = PersistentWorkspaceId::new_test(2);
     let window_1 = Window::new_test(1, Rect::default());
     let window_2 = Window::new_test(2, Rect::default());
-    workspace_file.add(&file_manager, &workspace_id_1, &window_1.handle);
-    workspace_file.add(&file_manager, &workspace_id_2, &window_2.handle);
+    workspace_file.add(&file_manager, &workspace_id_1, StoredWindow::new(&window_1, false));
+    workspace_file.add(&file_manager, &workspace_id_2, StoredWindow::new(&window_2, false));
 
     workspace_file.remove_all_excluding(&file_manager, &workspace_id_1, &[window_1.clone(), window_2.clone()]);
 
     assert!(workspace_file.workspaces.contains_key(&workspace_id_1));
     assert_eq!(workspace_file.workspaces[&workspace_id_1].len(), 1);
-    assert!(workspace_file.workspaces[&workspace_id_1].contains(&window_1.handle));
+    assert_eq!(workspace_file.workspaces[&workspace_id_1][0].handle, window_1.handle);
     assert!(workspace_file.workspaces.contains_key(&workspace_id_2));
     assert_eq!(workspace_file.workspaces[&workspace_id_2].len(), 0);
   }
@@ -244,9 +469,9 @@ mod tests {
     let window_1 = Window::new_test(1, Rect::default());
     let window_2 = Window::new_test(2, Rect::default());
     let window_3 = Window::new_test(4, Rect::default());
-    workspace_file.add(&file_manager, &workspace_id_1, &window_1.handle);
-    workspace_file.add(&file_manager, &workspace_id_1, &window_2.handle);
-    workspace_file.add(&file_manager, &workspace_id_2, &window_3.handle);
+    workspace_file.add(&file_manager, &workspace_id_1, StoredWindow::new(&window_1, false));
+    workspace_file.add(&file_manager, &workspace_id_1, StoredWindow::new(&window_2, false));
+    workspace_file.add(&file_manager, &workspace_id_2, StoredWindow::new(&window_3, false));
 
     workspace_file.remove_all_excluding(
       &file_manager,
@@ -260,13 +485,45 @@ mod tests {
     assert!(file.contains("[[workspaces.\"P_DISPLAY|1|true\"]]\nhwnd = 2\n"));
   }
 
+  #[test]
+  fn defer_saving_writes_all_changes_once_saving_resumes() {
+    let directory = create_temp_directory();
+    let file = directory.path().join("test.toml");
+    let file_manager = FileManager::new_test(file.clone());
+    let mut workspace_file = WorkspacesFile::new();
+    let workspace_id = PersistentWorkspaceId::new_test(1);
+    workspace_file.defer_saving();
+
+    workspace_file.add(&file_manager, &workspace_id, stored_window(1));
+    workspace_file.add(&file_manager, &workspace_id, stored_window(2));
+
+    assert!(!file.exists());
+    workspace_file.save_deferred_changes(&file_manager);
+    let file = fs::read_to_string(file).expect("Failed to read config file");
+    assert!(file.contains("[[workspaces.\"P_DISPLAY|1|true\"]]\nhwnd = 1\n"));
+    assert!(file.contains("[[workspaces.\"P_DISPLAY|1|true\"]]\nhwnd = 2\n"));
+  }
+
+  #[test]
+  fn save_deferred_changes_does_not_write_file_without_changes() {
+    let directory = create_temp_directory();
+    let file = directory.path().join("test.toml");
+    let file_manager = FileManager::new_test(file.clone());
+    let mut workspace_file = WorkspacesFile::new();
+    workspace_file.defer_saving();
+
+    workspace_file.save_deferred_changes(&file_manager);
+
+    assert!(!file.exists());
+  }
+
   #[test]
   fn display_formats_workspaces_correctly() {
     let mut workspace_file = WorkspacesFile::new();
     let workspace_id = PersistentWorkspaceId::new_test(1);
     let window_handle = WindowHandle::from(1);
 
-    workspace_file.workspaces.insert(workspace_id, HashSet::from([window_handle]));
+    workspace_file.workspaces.insert(workspace_id, vec![stored_window(1)]);
 
     let formatted = format!("{}", workspace_file);
     assert!(formatted.contains(&format!("{}: [{}]", workspace_id, window_handle)));
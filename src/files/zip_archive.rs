@@ -0,0 +1,141 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const LOCAL_FILE_HEADER_SIGNATURE: u32 = 0x0403_4b50;
+const CENTRAL_DIRECTORY_HEADER_SIGNATURE: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x0605_4b50;
+const VERSION: u16 = 20;
+const DOS_DATE_1980_01_01: u16 = 0x21;
+
+/// A minimal writer for zip archives that stores files without compression. Sufficient for bundling a handful of small
+/// text files into a single file that can be opened by Windows Explorer, without pulling in a dependency.
+#[derive(Default)]
+pub struct ZipArchive {
+  entries: Vec<(String, Vec<u8>)>,
+}
+
+impl ZipArchive {
+  pub fn add(&mut self, name: &str, content: impl Into<Vec<u8>>) {
+    self.entries.push((name.to_string(), content.into()));
+  }
+
+  pub fn write(&self, path: &Path) -> io::Result<()> {
+    fs::write(path, self.to_bytes())
+  }
+
+  fn to_bytes(&self) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let mut central_directory = Vec::new();
+    for (name, content) in &self.entries {
+      let offset = bytes.len() as u32;
+      let crc = crc32(content);
+      let size = content.len() as u32;
+
+      push_u32(&mut bytes, LOCAL_FILE_HEADER_SIGNATURE);
+      push_u16(&mut bytes, VERSION);
+      push_common_header_fields(&mut bytes, crc, size, name);
+      push_u16(&mut bytes, 0); // Extra field length
+      bytes.extend_from_slice(name.as_bytes());
+      bytes.extend_from_slice(content);
+
+      push_u32(&mut central_directory, CENTRAL_DIRECTORY_HEADER_SIGNATURE);
+      push_u16(&mut central_directory, VERSION); // Version made by
+      push_u16(&mut central_directory, VERSION); // Version needed to extract
+      push_common_header_fields(&mut central_directory, crc, size, name);
+      push_u16(&mut central_directory, 0); // Extra field length
+      push_u16(&mut central_directory, 0); // File comment length
+      push_u16(&mut central_directory, 0); // Disk number start
+      push_u16(&mut central_directory, 0); // Internal file attributes
+      push_u32(&mut central_directory, 0); // External file attributes
+      push_u32(&mut central_directory, offset);
+      central_directory.extend_from_slice(name.as_bytes());
+    }
+
+    let central_directory_offset = bytes.len() as u32;
+    let central_directory_size = central_directory.len() as u32;
+    bytes.extend_from_slice(&central_directory);
+    push_u32(&mut bytes, END_OF_CENTRAL_DIRECTORY_SIGNATURE);
+    push_u16(&mut bytes, 0); // Number of this disk
+    push_u16(&mut bytes, 0); // Disk where central directory starts
+    push_u16(&mut bytes, self.entries.len() as u16);
+    push_u16(&mut bytes, self.entries.len() as u16);
+    push_u32(&mut bytes, central_directory_size);
+    push_u32(&mut bytes, central_directory_offset);
+    push_u16(&mut bytes, 0); // Comment length
+
+    bytes
+  }
+}
+
+/// Pushes the fields from "general purpose bit flag" to "file name length" which local file headers and central
+/// directory headers have in common.
+fn push_common_header_fields(bytes: &mut Vec<u8>, crc: u32, size: u32, name: &str) {
+  push_u16(bytes, 0); // General purpose bit flag
+  push_u16(bytes, 0); // Compression method i.e. stored
+  push_u16(bytes, 0); // Last modification time
+  push_u16(bytes, DOS_DATE_1980_01_01);
+  push_u32(bytes, crc);
+  push_u32(bytes, size); // Compressed size
+  push_u32(bytes, size); // Uncompressed size
+  push_u16(bytes, name.len() as u16);
+}
+
+fn push_u16(bytes: &mut Vec<u8>, value: u16) {
+  bytes.extend_from_slice(&value.to_le_bytes());
+}
+
+fn push_u32(bytes: &mut Vec<u8>, value: u32) {
+  bytes.extend_from_slice(&value.to_le_bytes());
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+  let mut crc = 0xFFFF_FFFF_u32;
+  for byte in bytes {
+    crc ^= u32::from(*byte);
+    for _ in 0..8 {
+      crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+    }
+  }
+
+  !crc
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn crc32_returns_standard_check_value() {
+    assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+  }
+
+  #[test]
+  fn to_bytes_creates_archive_with_local_headers_and_end_of_central_directory() {
+    let mut archive = ZipArchive::default();
+    archive.add("first.txt", "Hello");
+    archive.add("second.txt", "World!");
+
+    let bytes = archive.to_bytes();
+
+    assert_eq!(bytes[0..4], LOCAL_FILE_HEADER_SIGNATURE.to_le_bytes());
+    assert_eq!(bytes[30..39], *b"first.txt");
+    assert_eq!(bytes[39..44], *b"Hello");
+    let end_of_central_directory = &bytes[bytes.len() - 22..];
+    assert_eq!(
+      end_of_central_directory[0..4],
+      END_OF_CENTRAL_DIRECTORY_SIGNATURE.to_le_bytes()
+    );
+    assert_eq!(end_of_central_directory[10..12], 2u16.to_le_bytes());
+  }
+
+  #[test]
+  fn to_bytes_creates_valid_empty_archive() {
+    let archive = ZipArchive::default();
+
+    let bytes = archive.to_bytes();
+
+    assert_eq!(bytes.len(), 22);
+    assert_eq!(bytes[0..4], END_OF_CENTRAL_DIRECTORY_SIGNATURE.to_le_bytes());
+  }
+}
@@ -2,19 +2,28 @@ use crate::common::{Command, Direction, PersistentWorkspaceId};
 use crate::configuration_provider::ConfigurationProvider;
 use crate::utils::CONFIGURATION_PROVIDER_LOCK;
 use crossbeam_channel::Sender;
+use std::collections::HashMap;
+use std::fmt::Display;
 use std::str::FromStr;
 use std::sync::{Arc, Mutex};
 use std::thread;
+use std::thread::JoinHandle;
 use win_hotkeys::{InterruptHandle, VKey};
 
 const BACKSLASH: u32 = 0xDC;
+const PAGE_UP: u32 = 0x21;
+const PAGE_DOWN: u32 = 0x22;
 const MAIN_MOD: VKey = VKey::LWin;
 const SECONDARY_MOD: VKey = VKey::Shift;
 const TERTIARY_MOD: VKey = VKey::Control;
+const QUATERNARY_MOD: VKey = VKey::Menu;
 
 pub struct HotkeyManager {
   hkm: win_hotkeys::HotkeyManager<Command>,
   configuration_provider: Arc<Mutex<ConfigurationProvider>>,
+  keybinding_names: Vec<String>,
+  failed_hotkeys: Vec<String>,
+  numbered_workspace_ids: Vec<(usize, PersistentWorkspaceId)>,
 }
 
 // TODO: Try to make MOD_NOREPEAT work again
@@ -23,6 +32,9 @@ impl HotkeyManager {
     Self {
       hkm: win_hotkeys::HotkeyManager::new(),
       configuration_provider,
+      keybinding_names: Vec::new(),
+      failed_hotkeys: Vec::new(),
+      numbered_workspace_ids: Vec::new(),
     }
   }
 
@@ -33,84 +45,196 @@ impl HotkeyManager {
     let mut hotkey_manager = HotkeyManager::new(configuration_provider.clone());
 
     // Move cursor
-    hotkey_manager.register_move_cursor_hotkey(Direction::Left, VKey::Left);
-    hotkey_manager.register_move_cursor_hotkey(Direction::Down, VKey::Down);
-    hotkey_manager.register_move_cursor_hotkey(Direction::Up, VKey::Up);
-    hotkey_manager.register_move_cursor_hotkey(Direction::Right, VKey::Right);
+    hotkey_manager.register_move_cursor_hotkey(Direction::Left, &[VKey::Left]);
+    hotkey_manager.register_move_cursor_hotkey(Direction::Down, &[VKey::Down]);
+    hotkey_manager.register_move_cursor_hotkey(Direction::Up, &[VKey::Up]);
+    hotkey_manager.register_move_cursor_hotkey(Direction::Right, &[VKey::Right]);
+
+    // Throw cursor
+    hotkey_manager.register_throw_cursor_hotkey(Direction::Left, &[VKey::Left]);
+    hotkey_manager.register_throw_cursor_hotkey(Direction::Down, &[VKey::Down]);
+    hotkey_manager.register_throw_cursor_hotkey(Direction::Up, &[VKey::Up]);
+    hotkey_manager.register_throw_cursor_hotkey(Direction::Right, &[VKey::Right]);
+
+    // Cycle focus through the windows of the current workspace, which has no default hotkeys to avoid clashing with
+    // the Windows shortcuts
+    hotkey_manager.register_configurable_hotkey("focus_next_window", vec![], || Command::FocusNextWindow);
+    hotkey_manager.register_configurable_hotkey("focus_previous_window", vec![], || Command::FocusPreviousWindow);
 
     // Move window
-    hotkey_manager.register_move_window_hotkey(Direction::Left, VKey::Left);
-    hotkey_manager.register_move_window_hotkey(Direction::Down, VKey::Down);
-    hotkey_manager.register_move_window_hotkey(Direction::Up, VKey::Up);
-    hotkey_manager.register_move_window_hotkey(Direction::Right, VKey::Right);
-    hotkey_manager.register_move_window_hotkey(Direction::Left, VKey::H);
-    hotkey_manager.register_move_window_hotkey(Direction::Down, VKey::J);
-    hotkey_manager.register_move_window_hotkey(Direction::Up, VKey::K);
-    hotkey_manager.register_move_window_hotkey(Direction::Right, VKey::L);
+    hotkey_manager.register_move_window_hotkey(Direction::Left, &[VKey::Left, VKey::H]);
+    hotkey_manager.register_move_window_hotkey(Direction::Down, &[VKey::Down, VKey::J]);
+    hotkey_manager.register_move_window_hotkey(Direction::Up, &[VKey::Up, VKey::K]);
+    hotkey_manager.register_move_window_hotkey(Direction::Right, &[VKey::Right, VKey::L]);
 
     // Resize window
-    hotkey_manager.register_resize_spatial_window_hotkey(Direction::Left, VKey::Left);
-    hotkey_manager.register_resize_spatial_window_hotkey(Direction::Down, VKey::Down);
-    hotkey_manager.register_resize_spatial_window_hotkey(Direction::Up, VKey::Up);
-    hotkey_manager.register_resize_spatial_window_hotkey(Direction::Right, VKey::Right);
-    hotkey_manager.register_resize_spatial_window_hotkey(Direction::Left, VKey::H);
-    hotkey_manager.register_resize_spatial_window_hotkey(Direction::Down, VKey::J);
-    hotkey_manager.register_resize_spatial_window_hotkey(Direction::Up, VKey::K);
-    hotkey_manager.register_resize_spatial_window_hotkey(Direction::Right, VKey::L);
+    hotkey_manager.register_resize_spatial_window_hotkey(Direction::Left, &[VKey::Left, VKey::H]);
+    hotkey_manager.register_resize_spatial_window_hotkey(Direction::Down, &[VKey::Down, VKey::J]);
+    hotkey_manager.register_resize_spatial_window_hotkey(Direction::Up, &[VKey::Up, VKey::K]);
+    hotkey_manager.register_resize_spatial_window_hotkey(Direction::Right, &[VKey::Right, VKey::L]);
+
+    // Move window to the active workspace of another monitor, which has no default hotkeys because moving a window in
+    // a direction already reaches the next monitor once the window is snapped to that side
+    for direction in [Direction::Left, Direction::Down, Direction::Up, Direction::Right] {
+      hotkey_manager.register_move_window_to_monitor_hotkey(direction);
+    }
+
+    // Move edge shared with neighbouring windows
+    hotkey_manager.register_move_shared_edge_hotkey(Direction::Left, &[VKey::Left, VKey::H]);
+    hotkey_manager.register_move_shared_edge_hotkey(Direction::Down, &[VKey::Down, VKey::J]);
+    hotkey_manager.register_move_shared_edge_hotkey(Direction::Up, &[VKey::Up, VKey::K]);
+    hotkey_manager.register_move_shared_edge_hotkey(Direction::Right, &[VKey::Right, VKey::L]);
+
+    // Rotate and swap windows of tiled workspaces, which have no default hotkeys because tiling is opt-in
+    hotkey_manager.register_configurable_hotkey("rotate_tiled_split", vec![], || Command::RotateTiledSplit);
+    hotkey_manager.register_configurable_hotkey("swap_tiled_windows", vec![], || Command::SwapTiledWindows);
 
     // Resize Scrolling Layout window, globally overriding Windows virtual-desktop switching
-    hotkey_manager.register_resize_scrolling_window_hotkey(Direction::Left, VKey::Left);
-    hotkey_manager.register_resize_scrolling_window_hotkey(Direction::Right, VKey::Right);
+    hotkey_manager.register_resize_scrolling_window_hotkey(Direction::Left, &[VKey::Left]);
+    hotkey_manager.register_resize_scrolling_window_hotkey(Direction::Right, &[VKey::Right]);
 
     // Other window management
     hotkey_manager.register_close_window_hotkey(VKey::Q);
     hotkey_manager.register_near_maximise_window_hotkey(VKey::CustomKeyCode(BACKSLASH as u16));
     hotkey_manager.register_minimise_window_hotkey(VKey::CustomKeyCode(BACKSLASH as u16));
+    hotkey_manager.register_restore_older_placement_hotkey(VKey::CustomKeyCode(BACKSLASH as u16));
+    hotkey_manager.register_picture_in_picture_hotkey(VKey::P);
+    hotkey_manager.register_monocle_hotkey(VKey::M);
+    hotkey_manager.register_workspace_note_hotkey(VKey::N);
+    hotkey_manager.register_toggle_manage_window_hotkey(VKey::I);
+    // Keyboard alternative to dragging windows into snap zones, which has no default hotkey because it is optional
+    hotkey_manager.register_configurable_hotkey("enter_place_mode", vec![], || Command::EnterPlaceMode);
 
     // Workspace management
-    hotkey_manager.register_switch_workspace_hotkeys(&workspace_ids);
-    hotkey_manager.register_move_window_to_workspace_hotkeys(&workspace_ids);
+    let numbered_workspace_ids = hotkey_manager.number_workspaces(&workspace_ids);
+    hotkey_manager.register_switch_workspace_hotkeys(&numbered_workspace_ids);
+    hotkey_manager.register_move_window_to_workspace_hotkeys(&numbered_workspace_ids);
+    hotkey_manager.numbered_workspace_ids = numbered_workspace_ids;
+    hotkey_manager.register_switch_to_adjacent_workspace_hotkeys(
+      VKey::CustomKeyCode(PAGE_DOWN as u16),
+      VKey::CustomKeyCode(PAGE_UP as u16),
+    );
+    // Workspace-aware replacement for Alt+Tab, which has no default hotkey because it is optional
+    hotkey_manager.register_configurable_hotkey("open_window_switcher", vec![], || Command::OpenWindowSwitcher);
+
+    // Create/delete workspaces, globally overriding Windows virtual-desktop creation/deletion
+    hotkey_manager.register_create_workspace_hotkey(VKey::D);
+    hotkey_manager.register_delete_workspace_hotkey(VKey::F4);
 
     // Launch application
     hotkey_manager.register_application_hotkeys();
 
+    hotkey_manager.warn_about_unknown_keybindings();
+
     hotkey_manager
   }
 
-  pub fn initialise(mut self, command_sender: Sender<Command>) -> InterruptHandle {
+  /// Returns the key combinations that could not be registered, e.g. because another application already uses them,
+  /// each described as the key combination followed by the name of its command.
+  pub fn get_failed_hotkeys(&self) -> &[String] {
+    &self.failed_hotkeys
+  }
+
+  /// Returns the workspaces ordered by the number of their workspace hotkeys, together with that number.
+  pub fn get_numbered_workspace_ids(&self) -> &[(usize, PersistentWorkspaceId)] {
+    &self.numbered_workspace_ids
+  }
+
+  /// Starts listening for hotkeys on a separate thread. Returns the handle to stop listening and the handle of the
+  /// thread, which finishes when listening stops, whether because it was interrupted or because it failed.
+  pub fn initialise(mut self, command_sender: Sender<Command>) -> (InterruptHandle, JoinHandle<()>) {
     self.hkm.register_channel(command_sender);
     let interrupt_handle = self.hkm.interrupt_handle();
-    thread::spawn(move || {
+    let event_loop = thread::spawn(move || {
       self.hkm.event_loop();
     });
 
-    interrupt_handle
+    (interrupt_handle, event_loop)
   }
 
   fn register_near_maximise_window_hotkey(&mut self, key: VKey) {
-    self
-      .hkm
-      .register_hotkey(key, &[MAIN_MOD], || Command::NearMaximiseWindow)
-      .unwrap_or_else(|err| panic!("Failed to register hotkey for {:?}: {err}", Command::NearMaximiseWindow));
+    let defaults = vec![KeyCombination::new(key, &[MAIN_MOD])];
+    self.register_configurable_hotkey("near_maximise_window", defaults, || Command::NearMaximiseWindow);
   }
 
   fn register_minimise_window_hotkey(&mut self, key: VKey) {
-    self
-      .hkm
-      .register_hotkey(key, &[MAIN_MOD, SECONDARY_MOD], || Command::MinimiseWindow)
-      .unwrap_or_else(|err| panic!("Failed to register hotkey for {:?}: {err}", Command::MinimiseWindow));
+    let defaults = vec![KeyCombination::new(key, &[MAIN_MOD, SECONDARY_MOD])];
+    self.register_configurable_hotkey("minimise_window", defaults, || Command::MinimiseWindow);
+  }
+
+  fn register_restore_older_placement_hotkey(&mut self, key: VKey) {
+    let defaults = vec![KeyCombination::new(key, &[MAIN_MOD, QUATERNARY_MOD])];
+    self.register_configurable_hotkey("restore_older_placement", defaults, || Command::RestoreOlderPlacement);
+  }
+
+  fn register_picture_in_picture_hotkey(&mut self, key: VKey) {
+    let defaults = vec![KeyCombination::new(key, &[MAIN_MOD, QUATERNARY_MOD])];
+    self.register_configurable_hotkey("toggle_picture_in_picture", defaults, || Command::TogglePictureInPicture);
+  }
+
+  fn register_monocle_hotkey(&mut self, key: VKey) {
+    let defaults = vec![KeyCombination::new(key, &[MAIN_MOD, QUATERNARY_MOD])];
+    self.register_configurable_hotkey("toggle_monocle", defaults, || Command::ToggleMonocle);
+  }
+
+  fn register_workspace_note_hotkey(&mut self, key: VKey) {
+    let defaults = vec![KeyCombination::new(key, &[MAIN_MOD, QUATERNARY_MOD])];
+    self.register_configurable_hotkey("toggle_workspace_note", defaults, || Command::ToggleWorkspaceNote);
+  }
+
+  fn register_toggle_manage_window_hotkey(&mut self, key: VKey) {
+    let defaults = vec![KeyCombination::new(key, &[MAIN_MOD, QUATERNARY_MOD])];
+    self.register_configurable_hotkey("toggle_manage_window", defaults, || Command::ToggleManageWindow);
   }
 
   fn register_close_window_hotkey(&mut self, key: VKey) {
-    self
-      .hkm
-      .register_hotkey(key, &[MAIN_MOD, SECONDARY_MOD], || Command::CloseWindow)
-      .unwrap_or_else(|err| panic!("Failed to register hotkey for {:?}: {err}", Command::CloseWindow));
+    let defaults = vec![KeyCombination::new(key, &[MAIN_MOD, SECONDARY_MOD])];
+    self.register_configurable_hotkey("close_window", defaults, || Command::CloseWindow);
   }
 
-  fn register_switch_workspace_hotkeys(&mut self, workspace_ids: &[PersistentWorkspaceId]) {
-    for (i, workspace_id) in workspace_ids.iter().enumerate() {
-      let key_number = i + 1;
+  /// Assigns a number to each workspace, starting at 1. Workspaces of monitors with a `[[workspace_numbering]]` section
+  /// use the numbers from the configured first number onwards, while all other workspaces use the lowest numbers that
+  /// remain, in the given order. Returns the workspaces ordered by their number.
+  fn number_workspaces(&self, workspace_ids: &[PersistentWorkspaceId]) -> Vec<(usize, PersistentWorkspaceId)> {
+    let workspace_numbering = self
+      .configuration_provider
+      .lock()
+      .expect(CONFIGURATION_PROVIDER_LOCK)
+      .get_workspace_numbering()
+      .to_vec();
+    let mut numbered_workspace_ids: Vec<(usize, PersistentWorkspaceId)> = Vec::new();
+    let mut unnumbered_workspace_ids = Vec::new();
+    let mut workspace_count_by_monitor: HashMap<[u16; 32], usize> = HashMap::new();
+    for workspace_id in workspace_ids {
+      let index_on_monitor = workspace_count_by_monitor.entry(workspace_id.monitor_id).or_default();
+      let number = workspace_numbering
+        .iter()
+        .find(|numbering| numbering.applies_to(&workspace_id.id_to_string(), workspace_id.is_on_primary_monitor()))
+        .map(|numbering| numbering.first_number + *index_on_monitor);
+      *index_on_monitor += 1;
+      match number {
+        Some(number) if numbered_workspace_ids.iter().any(|(n, _)| *n == number) => {
+          warn!("Workspace number [{number}] for [{workspace_id}] is already in use, using the next free number instead");
+          unnumbered_workspace_ids.push(*workspace_id);
+        }
+        Some(number) => numbered_workspace_ids.push((number, *workspace_id)),
+        None => unnumbered_workspace_ids.push(*workspace_id),
+      }
+    }
+    let mut next_number = 1;
+    for workspace_id in unnumbered_workspace_ids {
+      while numbered_workspace_ids.iter().any(|(n, _)| *n == next_number) {
+        next_number += 1;
+      }
+      numbered_workspace_ids.push((next_number, workspace_id));
+    }
+    numbered_workspace_ids.sort_by_key(|(number, _)| *number);
+
+    numbered_workspace_ids
+  }
+
+  fn register_switch_workspace_hotkeys(&mut self, numbered_workspace_ids: &[(usize, PersistentWorkspaceId)]) {
+    for &(key_number, workspace_id) in numbered_workspace_ids {
       if key_number >= 9 {
         warn!(
           "Cannot bind workspace number [{}] to a hotkey because it is greater than 9",
@@ -120,10 +244,12 @@ impl HotkeyManager {
       }
       match VKey::from_keyname(key_number.to_string().as_str()) {
         Ok(key) => {
-          self.register_switch_workspace_hotkey(key, workspace_id);
+          if !self.register_switch_workspace_hotkey(key_number, key, &workspace_id) {
+            continue;
+          }
         }
         Err(err) => {
-          warn!("Failed to parse workspace hotkey [{}]: {err}", i);
+          warn!("Failed to parse workspace hotkey [{}]: {err}", key_number);
           continue;
         }
       }
@@ -134,17 +260,22 @@ impl HotkeyManager {
     }
   }
 
-  fn register_switch_workspace_hotkey(&mut self, key: VKey, workspace_id: &PersistentWorkspaceId) {
+  /// Returns `true` if the default key combination was registered.
+  fn register_switch_workspace_hotkey(
+    &mut self,
+    key_number: usize,
+    key: VKey,
+    workspace_id: &PersistentWorkspaceId,
+  ) -> bool {
     let id = *workspace_id;
-    self
-      .hkm
-      .register_hotkey(key, &[MAIN_MOD], move || Command::SwitchWorkspace(id))
-      .unwrap_or_else(|err| panic!("Failed to register hotkey for {:?}: {err}", Command::SwitchWorkspace(id)));
+    let defaults = vec![KeyCombination::new(key, &[MAIN_MOD])];
+    self.register_configurable_hotkey(&format!("switch_to_workspace_{key_number}"), defaults, move || {
+      Command::SwitchWorkspace(id)
+    })
   }
 
-  fn register_move_window_to_workspace_hotkeys(&mut self, workspace_ids: &[PersistentWorkspaceId]) {
-    for (i, workspace_id) in workspace_ids.iter().enumerate() {
-      let key_number = i + 1;
+  fn register_move_window_to_workspace_hotkeys(&mut self, numbered_workspace_ids: &[(usize, PersistentWorkspaceId)]) {
+    for &(key_number, workspace_id) in numbered_workspace_ids {
       if key_number >= 9 {
         warn!(
           "Cannot bind workspace number [{}] to a hotkey because it is greater than 9",
@@ -154,10 +285,12 @@ impl HotkeyManager {
       }
       match VKey::from_keyname(key_number.to_string().as_str()) {
         Ok(key) => {
-          self.register_move_window_to_workspace_hotkey(key, workspace_id);
+          if !self.register_move_window_to_workspace_hotkey(key_number, key, &workspace_id) {
+            continue;
+          }
         }
         Err(err) => {
-          warn!("Failed to parse workspace hotkey [{}]: {err}", i);
+          warn!("Failed to parse workspace hotkey [{}]: {err}", key_number);
           continue;
         }
       }
@@ -168,17 +301,37 @@ impl HotkeyManager {
     }
   }
 
-  fn register_move_window_to_workspace_hotkey(&mut self, key: VKey, workspace_id: &PersistentWorkspaceId) {
+  /// Returns `true` if the default key combination was registered.
+  fn register_move_window_to_workspace_hotkey(
+    &mut self,
+    key_number: usize,
+    key: VKey,
+    workspace_id: &PersistentWorkspaceId,
+  ) -> bool {
     let id = *workspace_id;
-    self
-      .hkm
-      .register_hotkey(key, &[MAIN_MOD, SECONDARY_MOD], move || Command::MoveWindowToWorkspace(id))
-      .unwrap_or_else(|err| {
-        panic!(
-          "Failed to register hotkey for {:?}: {err}",
-          Command::MoveWindowToWorkspace(id)
-        )
-      });
+    let defaults = vec![KeyCombination::new(key, &[MAIN_MOD, SECONDARY_MOD])];
+    self.register_configurable_hotkey(&format!("move_window_to_workspace_{key_number}"), defaults, move || {
+      Command::MoveWindowToWorkspace(id)
+    })
+  }
+
+  /// Registers the hotkeys that switch to the next and previous workspace on the monitor under the cursor, which do not
+  /// depend on the number of workspaces.
+  fn register_switch_to_adjacent_workspace_hotkeys(&mut self, next_key: VKey, previous_key: VKey) {
+    let defaults = vec![KeyCombination::new(next_key, &[MAIN_MOD])];
+    self.register_configurable_hotkey("switch_to_next_workspace", defaults, || Command::NextWorkspace);
+    let defaults = vec![KeyCombination::new(previous_key, &[MAIN_MOD])];
+    self.register_configurable_hotkey("switch_to_previous_workspace", defaults, || Command::PreviousWorkspace);
+  }
+
+  fn register_create_workspace_hotkey(&mut self, key: VKey) {
+    let defaults = vec![KeyCombination::new(key, &[MAIN_MOD, TERTIARY_MOD])];
+    self.register_configurable_hotkey("create_workspace", defaults, || Command::CreateWorkspace);
+  }
+
+  fn register_delete_workspace_hotkey(&mut self, key: VKey) {
+    let defaults = vec![KeyCombination::new(key, &[MAIN_MOD, TERTIARY_MOD])];
+    self.register_configurable_hotkey("delete_workspace", defaults, || Command::DeleteWorkspace);
   }
 
   fn register_application_hotkeys(&mut self) {
@@ -215,46 +368,185 @@ impl HotkeyManager {
     );
   }
 
-  fn register_move_cursor_hotkey(&mut self, direction: Direction, key: VKey) {
-    self
-      .hkm
-      .register_hotkey(key, &[MAIN_MOD], move || Command::MoveCursor(direction))
-      .unwrap_or_else(|err| panic!("Failed to register hotkey for {:?}: {err}", Command::MoveCursor(direction)));
+  fn register_move_cursor_hotkey(&mut self, direction: Direction, keys: &[VKey]) {
+    let defaults = KeyCombination::for_each_key(keys, &[MAIN_MOD]);
+    let name = format!("move_cursor_{}", direction_name(direction));
+    self.register_configurable_hotkey(&name, defaults, move || Command::MoveCursor(direction));
   }
 
-  fn register_move_window_hotkey(&mut self, direction: Direction, key: VKey) {
-    self
-      .hkm
-      .register_hotkey(key, &[MAIN_MOD, VKey::Shift], move || Command::MoveWindow(direction))
-      .unwrap_or_else(|err| panic!("Failed to register hotkey for {:?}: {err}", Command::MoveWindow(direction)));
+  fn register_throw_cursor_hotkey(&mut self, direction: Direction, keys: &[VKey]) {
+    let defaults = KeyCombination::for_each_key(keys, &[MAIN_MOD, QUATERNARY_MOD]);
+    let name = format!("throw_cursor_{}", direction_name(direction));
+    self.register_configurable_hotkey(&name, defaults, move || Command::ThrowCursor(direction));
   }
 
-  fn register_resize_spatial_window_hotkey(&mut self, direction: Direction, key: VKey) {
-    self
-      .hkm
-      .register_hotkey(key, &[MAIN_MOD, SECONDARY_MOD, TERTIARY_MOD], move || {
-        Command::ResizeSpatialWindow(direction)
-      })
-      .unwrap_or_else(|err| {
-        panic!(
-          "Failed to register hotkey for {:?}: {err}",
-          Command::ResizeSpatialWindow(direction)
-        )
-      });
+  fn register_move_window_hotkey(&mut self, direction: Direction, keys: &[VKey]) {
+    let defaults = KeyCombination::for_each_key(keys, &[MAIN_MOD, SECONDARY_MOD]);
+    let name = format!("move_window_{}", direction_name(direction));
+    self.register_configurable_hotkey(&name, defaults, move || Command::MoveWindow(direction));
   }
 
-  fn register_resize_scrolling_window_hotkey(&mut self, direction: Direction, key: VKey) {
-    self
-      .hkm
-      .register_hotkey(key, &[MAIN_MOD, TERTIARY_MOD], move || {
-        Command::ResizeScrollingWindow(direction)
+  fn register_move_window_to_monitor_hotkey(&mut self, direction: Direction) {
+    let name = format!("move_window_to_monitor_{}", direction_name(direction));
+    self.register_configurable_hotkey(&name, vec![], move || Command::MoveWindowToMonitor(direction));
+  }
+
+  fn register_resize_spatial_window_hotkey(&mut self, direction: Direction, keys: &[VKey]) {
+    let defaults = KeyCombination::for_each_key(keys, &[MAIN_MOD, SECONDARY_MOD, TERTIARY_MOD]);
+    let name = format!("resize_spatial_window_{}", direction_name(direction));
+    self.register_configurable_hotkey(&name, defaults, move || Command::ResizeSpatialWindow(direction));
+  }
+
+  fn register_move_shared_edge_hotkey(&mut self, direction: Direction, keys: &[VKey]) {
+    let defaults = KeyCombination::for_each_key(keys, &[MAIN_MOD, SECONDARY_MOD, QUATERNARY_MOD]);
+    let name = format!("move_shared_edge_{}", direction_name(direction));
+    self.register_configurable_hotkey(&name, defaults, move || Command::MoveSharedEdge(direction));
+  }
+
+  fn register_resize_scrolling_window_hotkey(&mut self, direction: Direction, keys: &[VKey]) {
+    let defaults = KeyCombination::for_each_key(keys, &[MAIN_MOD, TERTIARY_MOD]);
+    let name = format!("resize_scrolling_window_{}", direction_name(direction));
+    self.register_configurable_hotkey(&name, defaults, move || Command::ResizeScrollingWindow(direction));
+  }
+
+  /// Registers the hotkeys of a built-in command. Uses the key combinations listed for the command in the
+  /// `[keybindings]` section of the configuration, if any, and the default key combinations otherwise. Returns `true`
+  /// if the default key combinations were used.
+  fn register_configurable_hotkey<F>(&mut self, name: &str, default_combinations: Vec<KeyCombination>, callback: F) -> bool
+  where
+    F: Fn() -> Command + Clone + Send + 'static,
+  {
+    self.keybinding_names.push(name.to_string());
+    let configured_combinations = self.get_configured_key_combinations(name);
+    let is_default = configured_combinations.is_none();
+    for combination in configured_combinations.unwrap_or(default_combinations) {
+      match self
+        .hkm
+        .register_hotkey(combination.key, &combination.modifiers, callback.clone())
+      {
+        Ok(_) if !is_default => debug!("Registered hotkey {combination} for [{name}] from configuration"),
+        Ok(_) => {}
+        Err(err) => {
+          warn!("Failed to register hotkey {combination} for [{name}]: {err}");
+          self.failed_hotkeys.push(format!("{combination} for [{name}]"));
+        }
+      }
+    }
+
+    is_default
+  }
+
+  /// Returns the valid key combinations configured for the command, or `None` if the command is not configured or
+  /// none of its key combinations are valid. An empty list of key combinations disables the command's hotkeys.
+  fn get_configured_key_combinations(&self, name: &str) -> Option<Vec<KeyCombination>> {
+    let configured = self
+      .configuration_provider
+      .lock()
+      .expect(CONFIGURATION_PROVIDER_LOCK)
+      .get_keybinding(name)?
+      .to_vec();
+    let combinations = configured
+      .iter()
+      .filter_map(|combination| match combination.parse::<KeyCombination>() {
+        Ok(combination) => Some(combination),
+        Err(err) => {
+          warn!("Ignored key combination [{combination}] for [{name}]: {err}");
+          None
+        }
       })
-      .unwrap_or_else(|err| {
-        panic!(
-          "Failed to register hotkey for {:?}: {err}",
-          Command::ResizeScrollingWindow(direction)
-        )
-      });
+      .collect::<Vec<_>>();
+    if combinations.is_empty() && !configured.is_empty() {
+      warn!("Using default hotkeys for [{name}] because none of its key combinations are valid");
+      return None;
+    }
+
+    Some(combinations)
+  }
+
+  /// Warns about commands in the `[keybindings]` section that do not exist, e.g. because of a typo or because they
+  /// refer to a workspace that does not exist.
+  fn warn_about_unknown_keybindings(&self) {
+    let configured_names = self
+      .configuration_provider
+      .lock()
+      .expect(CONFIGURATION_PROVIDER_LOCK)
+      .get_keybinding_names();
+    for name in configured_names.iter().filter(|name| !self.keybinding_names.contains(name)) {
+      warn!("Ignored keybinding for [{name}] because there is no such command");
+    }
+  }
+}
+
+/// A key together with the modifiers that must be held while pressing it, e.g. `Win+Shift+Left`.
+#[derive(Debug, Clone)]
+struct KeyCombination {
+  key: VKey,
+  modifiers: Vec<VKey>,
+}
+
+impl KeyCombination {
+  fn new(key: VKey, modifiers: &[VKey]) -> Self {
+    Self {
+      key,
+      modifiers: modifiers.to_vec(),
+    }
+  }
+
+  fn for_each_key(keys: &[VKey], modifiers: &[VKey]) -> Vec<Self> {
+    keys.iter().map(|key| Self::new(*key, modifiers)).collect()
+  }
+}
+
+impl FromStr for KeyCombination {
+  type Err = String;
+
+  /// Parses a key combination such as `Win+Shift+Left`, where the last part is the key and all other parts are
+  /// modifiers. Modifiers are `Win`, `Shift`, `Ctrl`, and `Alt`, and keys use the names of `win_hotkeys`.
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let mut parts = s.split('+').map(str::trim).collect::<Vec<_>>();
+    let key = match parts.pop() {
+      Some(key) if !key.is_empty() => parse_key(key)?,
+      _ => return Err("a key combination must end with a key".to_string()),
+    };
+    let modifiers = parts.into_iter().map(parse_modifier).collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Self { key, modifiers })
+  }
+}
+
+impl Display for KeyCombination {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    for modifier in &self.modifiers {
+      write!(f, "[{modifier}] + ")?;
+    }
+    write!(f, "[{}]", self.key)
+  }
+}
+
+fn parse_key(key: &str) -> Result<VKey, String> {
+  if key == "\\" || key.eq_ignore_ascii_case("backslash") {
+    return Ok(VKey::CustomKeyCode(BACKSLASH as u16));
+  }
+
+  VKey::from_str(key).map_err(|err| err.to_string())
+}
+
+fn parse_modifier(modifier: &str) -> Result<VKey, String> {
+  match modifier.to_ascii_lowercase().as_str() {
+    "win" => Ok(MAIN_MOD),
+    "shift" => Ok(SECONDARY_MOD),
+    "ctrl" | "control" => Ok(TERTIARY_MOD),
+    "alt" => Ok(QUATERNARY_MOD),
+    _ => Err(format!("[{modifier}] is not a modifier, use Win, Shift, Ctrl, or Alt")),
+  }
+}
+
+fn direction_name(direction: Direction) -> &'static str {
+  match direction {
+    Direction::Left => "left",
+    Direction::Right => "right",
+    Direction::Up => "up",
+    Direction::Down => "down",
   }
 }
 
@@ -274,7 +566,8 @@ mod tests {
       PersistentWorkspaceId::new_test(3),
     ];
 
-    hotkey_manager.register_switch_workspace_hotkeys(&workspace_ids);
+    let numbered_workspace_ids = hotkey_manager.number_workspaces(&workspace_ids);
+    hotkey_manager.register_switch_workspace_hotkeys(&numbered_workspace_ids);
 
     testing_logger::validate(|captured_logs| {
       assert_eq!(captured_logs.len(), 3);
@@ -292,6 +585,34 @@ mod tests {
     });
   }
 
+  #[test]
+  fn number_workspaces_uses_configured_first_numbers_and_fills_remaining_numbers_in_order() {
+    let mut configuration_provider = ConfigurationProvider::default();
+    configuration_provider.add_workspace_numbering("DISPLAY2", 4);
+    let hotkey_manager = HotkeyManager::new(Arc::new(Mutex::new(configuration_provider)));
+    let mut monitor_id = [0; 32];
+    for (i, character) in "DISPLAY2".encode_utf16().enumerate() {
+      monitor_id[i] = character;
+    }
+    let primary = PersistentWorkspaceId::new_test;
+    let secondary = |workspace| PersistentWorkspaceId::new(monitor_id, workspace, false);
+
+    let numbered_workspace_ids =
+      hotkey_manager.number_workspaces(&[primary(1), primary(2), secondary(1), secondary(2), primary(3), primary(4)]);
+
+    assert_eq!(
+      numbered_workspace_ids,
+      vec![
+        (1, primary(1)),
+        (2, primary(2)),
+        (3, primary(3)),
+        (4, secondary(1)),
+        (5, secondary(2)),
+        (6, primary(4)),
+      ]
+    );
+  }
+
   #[test]
   fn register_switch_workspace_hotkeys_skips_workspace_ids_greater_than_9() {
     testing_logger::setup();
@@ -301,7 +622,8 @@ mod tests {
       workspace_ids.push(PersistentWorkspaceId::new_test(i));
     }
 
-    hotkey_manager.register_switch_workspace_hotkeys(&workspace_ids);
+    let numbered_workspace_ids = hotkey_manager.number_workspaces(&workspace_ids);
+    hotkey_manager.register_switch_workspace_hotkeys(&numbered_workspace_ids);
 
     testing_logger::validate(|captured_logs| {
       assert_eq!(captured_logs.len(), 9);
@@ -313,6 +635,119 @@ mod tests {
     });
   }
 
+  #[test]
+  fn register_switch_workspace_hotkeys_uses_configured_keybindings_and_defaults_for_the_rest() {
+    testing_logger::setup();
+    let mut configuration_provider = ConfigurationProvider::default();
+    configuration_provider.set_keybinding("switch_to_workspace_1", &["Alt+F1"]);
+    let mut hotkey_manager = HotkeyManager::new(Arc::new(Mutex::new(configuration_provider)));
+    let workspace_ids = vec![PersistentWorkspaceId::new_test(1), PersistentWorkspaceId::new_test(2)];
+
+    let numbered_workspace_ids = hotkey_manager.number_workspaces(&workspace_ids);
+    hotkey_manager.register_switch_workspace_hotkeys(&numbered_workspace_ids);
+
+    testing_logger::validate(|captured_logs| {
+      assert_eq!(captured_logs.len(), 2);
+      assert_eq!(
+        captured_logs[0].body,
+        format!(
+          "Registered hotkey [{}] + [{}] for [switch_to_workspace_1] from configuration",
+          QUATERNARY_MOD,
+          VKey::F1
+        )
+      );
+      assert_eq!(
+        captured_logs[1].body,
+        format!(
+          "Registered hotkey [{}] + [2] to switch to workspace [wsp#P_DISPLAY-2]",
+          MAIN_MOD
+        )
+      );
+    });
+  }
+
+  #[test]
+  fn register_switch_to_adjacent_workspace_hotkeys_uses_configured_keybindings_and_defaults_for_the_rest() {
+    testing_logger::setup();
+    let mut configuration_provider = ConfigurationProvider::default();
+    configuration_provider.set_keybinding("switch_to_previous_workspace", &["Win+Ctrl+F11"]);
+    let mut hotkey_manager = HotkeyManager::new(Arc::new(Mutex::new(configuration_provider)));
+
+    hotkey_manager.register_switch_to_adjacent_workspace_hotkeys(
+      VKey::CustomKeyCode(PAGE_DOWN as u16),
+      VKey::CustomKeyCode(PAGE_UP as u16),
+    );
+
+    assert!(
+      hotkey_manager
+        .keybinding_names
+        .contains(&"switch_to_next_workspace".to_string())
+    );
+    testing_logger::validate(|captured_logs| {
+      assert_eq!(captured_logs.len(), 1);
+      assert_eq!(
+        captured_logs[0].body,
+        format!(
+          "Registered hotkey [{}] + [{}] + [{}] for [switch_to_previous_workspace] from configuration",
+          MAIN_MOD,
+          TERTIARY_MOD,
+          VKey::F11
+        )
+      );
+    });
+  }
+
+  #[test]
+  fn new_with_hotkeys_warns_about_unknown_and_invalid_keybindings() {
+    testing_logger::setup();
+    let mut configuration_provider = ConfigurationProvider::default();
+    configuration_provider.set_keybinding("move_window_sideways", &["Win+S"]);
+    configuration_provider.set_keybinding("close_window", &["Hyper+Q"]);
+
+    HotkeyManager::new_with_hotkeys(Arc::new(Mutex::new(configuration_provider)), vec![]);
+
+    testing_logger::validate(|captured_logs| {
+      let warnings = captured_logs
+        .iter()
+        .filter(|log| log.level == Warn)
+        .map(|log| log.body.as_str())
+        .collect::<Vec<_>>();
+      assert_eq!(
+        warnings,
+        vec![
+          "Ignored key combination [Hyper+Q] for [close_window]: [Hyper] is not a modifier, use Win, Shift, Ctrl, or Alt",
+          "Using default hotkeys for [close_window] because none of its key combinations are valid",
+          "Ignored keybinding for [move_window_sideways] because there is no such command",
+        ]
+      );
+    });
+  }
+
+  #[test]
+  fn key_combination_parses_modifiers_and_key() {
+    let combination = "Win + Shift+Left"
+      .parse::<KeyCombination>()
+      .expect("Failed to parse key combination");
+    assert_eq!(
+      combination.to_string(),
+      format!("[{}] + [{}] + [{}]", MAIN_MOD, SECONDARY_MOD, VKey::Left)
+    );
+    let combination = "ctrl+alt+backslash"
+      .parse::<KeyCombination>()
+      .expect("Failed to parse key combination");
+    assert_eq!(
+      combination.to_string(),
+      format!(
+        "[{}] + [{}] + [{}]",
+        TERTIARY_MOD,
+        QUATERNARY_MOD,
+        VKey::CustomKeyCode(BACKSLASH as u16)
+      )
+    );
+    assert!("Win+".parse::<KeyCombination>().is_err());
+    assert!("Win+Invalid".parse::<KeyCombination>().is_err());
+  }
+
   #[test]
   fn register_application_hotkeys_test() {
     testing_logger::setup();
@@ -0,0 +1,239 @@
+//! The protocol spoken by Randolf's IPC server and its clients, such as `randolf-cli`. Every request and every response
+//! is a single line containing a JSON object, so that clients in any language can use the IPC server with nothing but
+//! a JSON parser. This library is shared by Randolf and `randolf-cli`, so it must not depend on anything else in this
+//! package.
+
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, Write};
+
+/// The name of the file in Randolf's data folder that contains the token for [`IpcRequest::Authenticate`], as the TOML
+/// key `token`.
+pub const IPC_TOKEN_FILE_NAME: &str = "ipc_token.toml";
+
+/// The maximum length of a request in bytes, including its line break. Requests are short, so anything longer is
+/// rejected rather than buffered, which means a client cannot exhaust the memory of the server by never ending a line.
+pub const MAX_REQUEST_LENGTH_IN_BYTES: u64 = 64 * 1024;
+
+/// A request sent to the IPC server, e.g. `{"request":"command","line":"move-window left"}`. Each request is answered
+/// with exactly one [`IpcResponse`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "request", rename_all = "kebab-case")]
+pub enum IpcRequest {
+  /// Must be sent before any other request on a connection, with the token that Randolf writes to
+  /// [`IPC_TOKEN_FILE_NAME`] in its data folder when it starts. It is answered with [`IpcResponse::Ok`] or, if the
+  /// token is wrong, with [`IpcResponse::Error`], after which the server closes the connection.
+  Authenticate { token: String },
+  /// Executes a line using the syntax of the command file, e.g. `move-window left`. Unlike in the command file,
+  /// `list-windows` and `list-workspaces` are answered with [`IpcResponse::Data`] instead of writing a file.
+  Command { line: String },
+  /// Asks for Randolf's version, how long it has been running, the health of its hooks, and its workspaces, which is
+  /// answered with [`IpcResponse::Data`].
+  Health,
+  /// Turns the connection into a stream of events. It is answered with [`IpcResponse::Ok`], after which the server only
+  /// sends [`IpcResponse::Event`]s and ignores any further requests.
+  SubscribeEvents,
+}
+
+/// The answer of the IPC server to a single request, e.g. `{"response":"no-op","reason":"..."}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "response", rename_all = "kebab-case")]
+pub enum IpcResponse {
+  /// The request was executed.
+  Ok,
+  /// The command was valid but there was nothing to do, e.g. because the target workspace is already active.
+  NoOp { reason: String },
+  /// The request was a query and this is its result, e.g. `{"windows":[...]}` for `list-windows`, which uses the same
+  /// structure as the corresponding file in the data folder.
+  Data { data: serde_json::Value },
+  /// The request was rejected, e.g. because it could not be parsed, or the command failed.
+  Error { reason: String },
+  /// Something happened that subscribers may want to react to. Only sent to connections that subscribed to events.
+  Event { event: IpcEvent },
+}
+
+/// An event that is published to all IPC clients that subscribed to events, e.g.
+/// `{"event":"drag-completed","hwnd":123,"rect":{"left":0,"top":0,"right":960,"bottom":1040}}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+pub enum IpcEvent {
+  DragStarted {
+    hwnd: isize,
+  },
+  /// The window and its rect after dragging it, if it still exists.
+  DragCompleted {
+    hwnd: isize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rect: Option<IpcRect>,
+  },
+  ResizeStarted {
+    hwnd: isize,
+  },
+  /// The window and its rect after resizing it, if it still exists.
+  ResizeCompleted {
+    hwnd: isize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rect: Option<IpcRect>,
+  },
+}
+
+/// The rect of a window in an [`IpcEvent`], in screen coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IpcRect {
+  pub left: i32,
+  pub top: i32,
+  pub right: i32,
+  pub bottom: i32,
+}
+
+impl IpcRequest {
+  /// Writes the request as a single line of JSON.
+  pub fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+    write_line(self, writer)
+  }
+
+  /// Reads a request written by [`IpcRequest::write_to`]. Returns an [`io::ErrorKind::InvalidInput`] error without
+  /// reading the rest of the line if the request is longer than [`MAX_REQUEST_LENGTH_IN_BYTES`].
+  pub fn read_from(reader: &mut impl BufRead) -> io::Result<Self> {
+    read_line(reader, MAX_REQUEST_LENGTH_IN_BYTES)
+  }
+}
+
+impl IpcResponse {
+  /// Writes the response as a single line of JSON.
+  pub fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+    write_line(self, writer)
+  }
+
+  /// Reads a response written by [`IpcResponse::write_to`].
+  pub fn read_from(reader: &mut impl BufRead) -> io::Result<Self> {
+    read_line(reader, u64::MAX)
+  }
+}
+
+fn write_line<T: Serialize>(message: &T, writer: &mut impl Write) -> io::Result<()> {
+  serde_json::to_writer(&mut *writer, message)?;
+  writeln!(writer)
+}
+
+/// Reads the next line of at most `max_length_in_bytes` bytes and parses it as JSON. Returns an
+/// [`io::ErrorKind::UnexpectedEof`] error if the connection was closed, an [`io::ErrorKind::InvalidInput`] error if the
+/// line is too long, and an [`io::ErrorKind::InvalidData`] error if the line is not a valid message.
+fn read_line<T: for<'de> Deserialize<'de>>(reader: &mut impl BufRead, max_length_in_bytes: u64) -> io::Result<T> {
+  let mut bytes = vec![];
+  let length = reader.take(max_length_in_bytes).read_until(b'\n', &mut bytes)?;
+  if length == 0 {
+    return Err(io::Error::new(
+      io::ErrorKind::UnexpectedEof,
+      "connection closed before a message was received",
+    ));
+  }
+  if length as u64 == max_length_in_bytes && bytes.last() != Some(&b'\n') {
+    return Err(io::Error::new(
+      io::ErrorKind::InvalidInput,
+      format!("message exceeds the maximum length of [{max_length_in_bytes}] bytes"),
+    ));
+  }
+  let line = String::from_utf8(bytes).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+  serde_json::from_str(line.trim_end()).map_err(|err| {
+    io::Error::new(
+      io::ErrorKind::InvalidData,
+      format!("[{}] is not a valid message: {err}", line.trim_end()),
+    )
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::Cursor;
+
+  #[test]
+  fn responses_can_be_read_back_after_being_written() {
+    let responses = [
+      IpcResponse::Ok,
+      IpcResponse::Data {
+        data: serde_json::json!({ "windows": [{ "title": "Notepad\nUntitled" }] }),
+      },
+      IpcResponse::NoOp {
+        reason: "workspace [wsp#P_DISPLAY-1] is already the current workspace".to_string(),
+      },
+      IpcResponse::Error {
+        reason: "[foo] is not a known command".to_string(),
+      },
+      IpcResponse::Event {
+        event: IpcEvent::DragCompleted {
+          hwnd: 1,
+          rect: Some(IpcRect {
+            left: 0,
+            top: 0,
+            right: 960,
+            bottom: 1040,
+          }),
+        },
+      },
+      IpcResponse::Ok,
+    ];
+    let mut buffer = vec![];
+    for response in &responses {
+      response.write_to(&mut buffer).expect("Failed to write response");
+    }
+
+    assert_eq!(buffer.iter().filter(|byte| **byte == b'\n').count(), responses.len());
+    let mut reader = Cursor::new(buffer);
+    for response in responses {
+      assert_eq!(
+        IpcResponse::read_from(&mut reader).expect("Failed to read response"),
+        response
+      );
+    }
+    assert_eq!(
+      IpcResponse::read_from(&mut reader).expect_err("Expected an error").kind(),
+      io::ErrorKind::UnexpectedEof
+    );
+  }
+
+  #[test]
+  fn requests_are_written_as_tagged_json_objects() {
+    let mut buffer = vec![];
+    IpcRequest::Command {
+      line: "move-window left".to_string(),
+    }
+    .write_to(&mut buffer)
+    .expect("Failed to write request");
+    IpcRequest::SubscribeEvents
+      .write_to(&mut buffer)
+      .expect("Failed to write request");
+
+    assert_eq!(
+      String::from_utf8(buffer).expect("Request is not UTF-8"),
+      "{\"request\":\"command\",\"line\":\"move-window left\"}\n{\"request\":\"subscribe-events\"}\n"
+    );
+  }
+
+  #[test]
+  fn events_omit_rect_of_windows_that_no_longer_exist() {
+    let event = IpcEvent::ResizeCompleted { hwnd: 7, rect: None };
+
+    assert_eq!(
+      serde_json::to_string(&event).expect("Failed to serialise event"),
+      "{\"event\":\"resize-completed\",\"hwnd\":7}"
+    );
+  }
+
+  #[test]
+  fn read_from_rejects_requests_that_exceed_maximum_length() {
+    let line = format!("{}\n", "a".repeat(MAX_REQUEST_LENGTH_IN_BYTES as usize));
+
+    let error = IpcRequest::read_from(&mut Cursor::new(line)).expect_err("Expected an error");
+
+    assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+  }
+
+  #[test]
+  fn read_from_rejects_invalid_messages() {
+    let error = IpcResponse::read_from(&mut Cursor::new("ok\n")).expect_err("Expected an error");
+
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+  }
+}
@@ -0,0 +1,653 @@
+use crate::api::generate_random_token;
+use crate::command_file_manager::parse_command;
+use crate::common::{Command, CommandOutcome, Rect, WindowQuery};
+use crate::configuration_provider::{ConfigurationProvider, IPC_SERVER_PORT};
+use crate::files::{IpcTokenFile, WindowListFile, WorkspaceListFile};
+use crate::utils::CONFIGURATION_PROVIDER_LOCK;
+use crossbeam_channel::{Receiver, Sender, bounded, unbounded};
+use randolf_ipc::{IpcEvent, IpcRect, IpcRequest, IpcResponse, MAX_REQUEST_LENGTH_IN_BYTES};
+use serde::Serialize;
+use std::io::{self, BufReader};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// How long to wait for the main loop to answer a query or to report the outcome of a command.
+const QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long a new connection may take to authenticate before it is closed, so that connections that never send a
+/// request do not keep their threads alive.
+const AUTHENTICATION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Accepts [`IpcRequest`]s from other processes, e.g. AutoHotkey scripts, status bars, or `randolf-cli`, over a TCP
+/// connection to the loopback interface if `ipc_server_port` is set. Since any process can connect to the loopback
+/// interface, a connection must first authenticate with the random token that is written to the data folder on startup
+/// (see [`IpcRequest::Authenticate`]), which only the user running Randolf can read. After that, each request is
+/// answered with an [`IpcResponse`], unless it is [`IpcRequest::SubscribeEvents`], after which the connection only
+/// receives the events published via [`IpcEventPublisher`]. Connections are handled on their own threads, so that a
+/// client that keeps its connection open does not block other clients or the main loop.
+pub struct IpcServer {
+  port: u16,
+  sender: Sender<Command>,
+  event_publisher: IpcEventPublisher,
+}
+
+impl IpcServer {
+  pub fn new(
+    configuration_provider: Arc<Mutex<ConfigurationProvider>>,
+    sender: Sender<Command>,
+    event_publisher: IpcEventPublisher,
+  ) -> Self {
+    let port = match configuration_provider.try_lock() {
+      Ok(guard) => u16::try_from(guard.get_i32(IPC_SERVER_PORT)).unwrap_or_default(),
+      Err(err) => {
+        error!(
+          "IPC server is disabled because: {} with error: {}",
+          CONFIGURATION_PROVIDER_LOCK, err
+        );
+        0
+      }
+    };
+
+    Self {
+      port,
+      sender,
+      event_publisher,
+    }
+  }
+
+  /// Starts listening for connections in the background unless the IPC server is disabled.
+  pub fn initialise(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+    if self.port == 0 {
+      debug!("IPC server is disabled");
+      return Ok(());
+    }
+    let token = generate_random_token().ok_or("Failed to generate a token for IPC clients")?;
+    let token_path = IpcTokenFile::new(token.clone()).write_to_data_folder()?;
+    debug!("Wrote token for IPC clients to [{}]", token_path.display());
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, self.port))?;
+    info!("Listening for IPC connections on [{}]", listener.local_addr()?);
+    let sender = self.sender.clone();
+    let event_publisher = self.event_publisher.clone();
+    std::thread::spawn(move || accept_connections(listener, token, sender, event_publisher));
+
+    Ok(())
+  }
+}
+
+impl From<Rect> for IpcRect {
+  fn from(rect: Rect) -> Self {
+    Self {
+      left: rect.left,
+      top: rect.top,
+      right: rect.right,
+      bottom: rect.bottom,
+    }
+  }
+}
+
+/// Publishes [`IpcEvent`]s to all IPC clients that subscribed to them. Clones share the same subscribers, so that the
+/// main loop can publish events while the threads of the IPC server add subscribers.
+#[derive(Clone, Default)]
+pub struct IpcEventPublisher {
+  subscribers: Arc<Mutex<Vec<Sender<IpcEvent>>>>,
+}
+
+impl IpcEventPublisher {
+  /// Sends the event to all subscribers and forgets subscribers whose connection was closed.
+  pub fn publish(&self, event: IpcEvent) {
+    let Ok(mut subscribers) = self.subscribers.lock() else {
+      warn!("Failed to publish IPC event [{event:?}] because the subscribers are unavailable");
+      return;
+    };
+    if subscribers.is_empty() {
+      return;
+    }
+    trace!("Publishing IPC event [{event:?}] to [{}] subscriber(s)", subscribers.len());
+    subscribers.retain(|subscriber| subscriber.send(event).is_ok());
+  }
+
+  fn subscribe(&self) -> Receiver<IpcEvent> {
+    let (sender, receiver) = unbounded();
+    match self.subscribers.lock() {
+      Ok(mut subscribers) => subscribers.push(sender),
+      Err(err) => warn!("Failed to subscribe to IPC events: {err}"),
+    }
+
+    receiver
+  }
+}
+
+fn accept_connections(listener: TcpListener, token: String, sender: Sender<Command>, event_publisher: IpcEventPublisher) {
+  let token = Arc::new(token);
+  for stream in listener.incoming() {
+    match stream {
+      Ok(stream) => {
+        let token = Arc::clone(&token);
+        let sender = sender.clone();
+        let event_publisher = event_publisher.clone();
+        std::thread::spawn(move || handle_connection(stream, &token, &sender, &event_publisher));
+      }
+      Err(err) => warn!("Failed to accept IPC connection: {err}"),
+    }
+  }
+}
+
+/// Answers every request received on the connection until the client closes it or subscribes to events, provided that
+/// the first request authenticates the client with the token within [`AUTHENTICATION_TIMEOUT`]. Requests longer than
+/// [`MAX_REQUEST_LENGTH_IN_BYTES`] close the connection.
+fn handle_connection(stream: TcpStream, token: &str, sender: &Sender<Command>, event_publisher: &IpcEventPublisher) {
+  let peer = stream
+    .peer_addr()
+    .map_or_else(|_| "unknown".to_string(), |address| address.to_string());
+  trace!("Accepted IPC connection from [{peer}]");
+  if let Err(err) = stream.set_read_timeout(Some(AUTHENTICATION_TIMEOUT)) {
+    warn!("Failed to handle IPC connection from [{peer}]: {err}");
+    return;
+  }
+  let mut writer = match stream.try_clone() {
+    Ok(writer) => writer,
+    Err(err) => {
+      warn!("Failed to handle IPC connection from [{peer}]: {err}");
+      return;
+    }
+  };
+  let mut reader = BufReader::new(stream);
+  let is_authenticated = matches!(
+    IpcRequest::read_from(&mut reader),
+    Ok(IpcRequest::Authenticate { token: received_token }) if is_valid_token(&received_token, token)
+  );
+  let response = match is_authenticated {
+    true => IpcResponse::Ok,
+    false => {
+      warn!("Rejected IPC connection from [{peer}] because it did not authenticate with the current token");
+      IpcResponse::Error {
+        reason: "the first request must authenticate with the current token".to_string(),
+      }
+    }
+  };
+  if let Err(err) = response.write_to(&mut writer) {
+    warn!("Failed to respond to IPC connection [{peer}]: {err}");
+    return;
+  }
+  if !is_authenticated {
+    return;
+  }
+  if let Err(err) = reader.get_ref().set_read_timeout(None) {
+    warn!("Failed to handle IPC connection from [{peer}]: {err}");
+    return;
+  }
+  loop {
+    let response = match IpcRequest::read_from(&mut reader) {
+      Ok(IpcRequest::SubscribeEvents) => {
+        send_events(&mut writer, event_publisher, &peer);
+        return;
+      }
+      Ok(IpcRequest::Command { line }) => handle_request(line.trim(), sender),
+      Ok(IpcRequest::Health) => query_health(sender),
+      Ok(IpcRequest::Authenticate { .. }) => IpcResponse::Error {
+        reason: "the connection is already authenticated".to_string(),
+      },
+      Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => break,
+      Err(err) if err.kind() == io::ErrorKind::InvalidInput => {
+        warn!("Closed IPC connection from [{peer}] because: {err}");
+        return;
+      }
+      Err(err) if err.kind() == io::ErrorKind::InvalidData => {
+        warn!("Rejected invalid IPC request from [{peer}]: {err}");
+        IpcResponse::Error { reason: err.to_string() }
+      }
+      Err(err) => {
+        warn!("Failed to read from IPC connection [{peer}]: {err}");
+        return;
+      }
+    };
+    if let Err(err) = response.write_to(&mut writer) {
+      warn!("Failed to respond to IPC connection [{peer}]: {err}");
+      return;
+    }
+  }
+  trace!("Closed IPC connection from [{peer}]");
+}
+
+/// Returns `true` if the received token matches the expected one. The tokens are compared in constant time, so that the
+/// time it takes to reject a token does not reveal how many of its characters are correct.
+fn is_valid_token(received_token: &str, token: &str) -> bool {
+  received_token.len() == token.len()
+    && received_token
+      .bytes()
+      .zip(token.bytes())
+      .fold(0, |difference, (received, expected)| difference | (received ^ expected))
+      == 0
+}
+
+/// Sends every published event to the connection until the client closes it.
+fn send_events(writer: &mut TcpStream, event_publisher: &IpcEventPublisher, peer: &str) {
+  let events = event_publisher.subscribe();
+  info!("IPC client [{peer}] subscribed to events");
+  if let Err(err) = IpcResponse::Ok.write_to(writer) {
+    warn!("Failed to respond to IPC connection [{peer}]: {err}");
+    return;
+  }
+  for event in events {
+    if (IpcResponse::Event { event }).write_to(writer).is_err() {
+      break;
+    }
+  }
+  trace!("Closed IPC event subscription of [{peer}]");
+}
+
+/// Parses a command line, queues the resulting command, and waits for its outcome, returning the response for the
+/// client. Unlike in the command file, `list-windows` and `list-workspaces` answer with the matching windows or
+/// workspaces instead of writing them to the data folder.
+fn handle_request(line: &str, sender: &Sender<Command>) -> IpcResponse {
+  let command = match parse_command(line) {
+    Ok(command) => command,
+    Err(err) => {
+      warn!("Rejected invalid IPC request [{line}]: {err}");
+      return IpcResponse::Error { reason: err };
+    }
+  };
+  info!("IPC client requested [{line}]");
+  match command {
+    Command::ListWindows(query) => query_windows(query, sender),
+    Command::ListWorkspaces => query_workspaces(sender),
+    command => execute_command(command, sender),
+  }
+}
+
+/// Asks the main loop to execute the command and report its outcome, because only the main loop knows whether there was
+/// anything to do and whether it succeeded.
+fn execute_command(command: Command, sender: &Sender<Command>) -> IpcResponse {
+  let (outcome_sender, outcome_receiver) = bounded(1);
+  if let Err(err) = sender.send(Command::ReportingOutcome(Box::new(command), outcome_sender)) {
+    return IpcResponse::Error { reason: err.to_string() };
+  }
+  match outcome_receiver.recv_timeout(QUERY_TIMEOUT) {
+    Ok(CommandOutcome::Success) => IpcResponse::Ok,
+    Ok(CommandOutcome::NoOp(reason)) => IpcResponse::NoOp { reason },
+    Ok(CommandOutcome::Error(reason)) => IpcResponse::Error { reason },
+    Err(err) => IpcResponse::Error {
+      reason: format!("Randolf did not report the outcome of the command: {err}"),
+    },
+  }
+}
+
+/// Asks the main loop for the windows matching the query, because only the main loop can access the window manager,
+/// and returns them in the same structure as the window list in the data folder.
+fn query_windows(query: WindowQuery, sender: &Sender<Command>) -> IpcResponse {
+  let (reply_sender, reply_receiver) = bounded(1);
+  if let Err(err) = sender.send(Command::QueryWindows(query, reply_sender)) {
+    return IpcResponse::Error { reason: err.to_string() };
+  }
+  match reply_receiver.recv_timeout(QUERY_TIMEOUT) {
+    Ok(windows) => to_data(&WindowListFile::new(windows)),
+    Err(err) => IpcResponse::Error {
+      reason: format!("Randolf did not answer the query: {err}"),
+    },
+  }
+}
+
+/// Asks the main loop to describe all workspaces and returns them in the same format as the workspace list in the data
+/// folder, so that status bars can render every workspace from a single request.
+fn query_workspaces(sender: &Sender<Command>) -> IpcResponse {
+  let (reply_sender, reply_receiver) = bounded(1);
+  if let Err(err) = sender.send(Command::QueryWorkspaces(reply_sender)) {
+    return IpcResponse::Error { reason: err.to_string() };
+  }
+  match reply_receiver.recv_timeout(QUERY_TIMEOUT) {
+    Ok(workspaces) => to_data(&WorkspaceListFile::new(workspaces)),
+    Err(err) => IpcResponse::Error {
+      reason: format!("Randolf did not answer the query: {err}"),
+    },
+  }
+}
+
+/// Asks the main loop for Randolf's health, because only the main loop knows the workspaces and the registered hotkeys.
+fn query_health(sender: &Sender<Command>) -> IpcResponse {
+  let (reply_sender, reply_receiver) = bounded(1);
+  if let Err(err) = sender.send(Command::QueryHealth(reply_sender)) {
+    return IpcResponse::Error { reason: err.to_string() };
+  }
+  match reply_receiver.recv_timeout(QUERY_TIMEOUT) {
+    Ok(health) => to_data(&health),
+    Err(err) => IpcResponse::Error {
+      reason: format!("Randolf did not answer the query: {err}"),
+    },
+  }
+}
+
+fn to_data(result: &impl Serialize) -> IpcResponse {
+  match serde_json::to_value(result) {
+    Ok(data) => IpcResponse::Data { data },
+    Err(err) => IpcResponse::Error {
+      reason: format!("Failed to serialise the result of the query: {err}"),
+    },
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::common::{
+    HealthSummary, HookHealth, HookHealthSummary, WindowHandle, WindowSummary, WindowVisibility, WorkspaceSummary,
+  };
+  use crossbeam_channel::unbounded;
+  use std::io::Write;
+
+  const TOKEN: &str = "0123456789abcdef";
+
+  /// Answers every command with the given outcomes, in order, like the main loop would, and returns the commands.
+  fn spawn_main_loop(receiver: Receiver<Command>, outcomes: Vec<CommandOutcome>) -> std::thread::JoinHandle<Vec<Command>> {
+    std::thread::spawn(move || {
+      let mut commands = vec![];
+      for outcome in outcomes {
+        let (command, outcome_sender) = receiver.recv().expect("Failed to receive command").into_parts();
+        outcome_sender
+          .expect("Expected a command reporting its outcome")
+          .send(outcome)
+          .expect("Failed to reply");
+        commands.push(command);
+      }
+      commands
+    })
+  }
+
+  #[test]
+  fn handle_request_executes_valid_commands_and_reports_invalid_ones() {
+    let (sender, receiver) = unbounded();
+    let main_loop = spawn_main_loop(receiver.clone(), vec![CommandOutcome::Success]);
+
+    assert_eq!(handle_request("move-window left", &sender), IpcResponse::Ok);
+    assert_eq!(
+      handle_request("move-window", &sender),
+      IpcResponse::Error {
+        reason: "[move-window] requires a direction".to_string()
+      }
+    );
+    assert_eq!(
+      handle_request("", &sender),
+      IpcResponse::Error {
+        reason: "line is empty".to_string()
+      }
+    );
+
+    let commands = main_loop.join().expect("Main loop thread panicked");
+    assert!(matches!(commands[..], [Command::MoveWindow(_)]));
+    assert!(receiver.try_recv().is_err());
+  }
+
+  #[test]
+  fn handle_request_reports_outcome_of_command() {
+    let (sender, receiver) = unbounded();
+    let main_loop = spawn_main_loop(
+      receiver,
+      vec![
+        CommandOutcome::no_op("workspace [wsp#P_DISPLAY-1] is already the current workspace"),
+        CommandOutcome::error("workspace number [7] does not exist"),
+      ],
+    );
+
+    let no_op = handle_request("switch-workspace 1", &sender);
+    let error = handle_request("switch-workspace 7", &sender);
+    main_loop.join().expect("Main loop thread panicked");
+
+    assert_eq!(
+      no_op,
+      IpcResponse::NoOp {
+        reason: "workspace [wsp#P_DISPLAY-1] is already the current workspace".to_string()
+      }
+    );
+    assert_eq!(
+      error,
+      IpcResponse::Error {
+        reason: "workspace number [7] does not exist".to_string()
+      }
+    );
+  }
+
+  #[test]
+  fn handle_request_answers_list_windows_with_windows_from_main_loop() {
+    let (sender, receiver) = unbounded();
+    let main_loop = std::thread::spawn(move || match receiver.recv() {
+      Ok(Command::QueryWindows(query, reply_sender)) => {
+        assert_eq!(query.workspace, Some(2));
+        reply_sender
+          .send(vec![WindowSummary {
+            handle: WindowHandle::new(1),
+            title: "Untitled - Notepad".to_string(),
+            class_name: "Notepad".to_string(),
+            process_name: None,
+            monitor_id: "DISPLAY1".to_string(),
+            workspace: 2,
+            visibility: WindowVisibility::Visible,
+          }])
+          .expect("Failed to reply");
+      }
+      other => panic!("Expected a window query but received {other:?}"),
+    });
+
+    let response = handle_request("list-windows workspace=2", &sender);
+    main_loop.join().expect("Main loop thread panicked");
+
+    let IpcResponse::Data { data } = response else {
+      panic!("Expected data but received {response:?}");
+    };
+    let window_list = serde_json::from_value::<WindowListFile>(data).expect("Failed to parse window list");
+    assert_eq!(window_list.windows.len(), 1);
+    assert_eq!(window_list.windows[0].title, "Untitled - Notepad");
+  }
+
+  #[test]
+  fn handle_request_answers_list_workspaces_with_workspaces_from_main_loop() {
+    let (sender, receiver) = unbounded();
+    let main_loop = std::thread::spawn(move || match receiver.recv() {
+      Ok(Command::QueryWorkspaces(reply_sender)) => {
+        reply_sender
+          .send(vec![WorkspaceSummary {
+            monitor_id: "DISPLAY1".to_string(),
+            workspace: 1,
+            number: Some(1),
+            name: Some("Email and chat".to_string()),
+            is_active: true,
+            window_count: 3,
+            last_used_at: Some(1_700_000_000),
+          }])
+          .expect("Failed to reply");
+      }
+      other => panic!("Expected a workspace query but received {other:?}"),
+    });
+
+    let response = handle_request("list-workspaces", &sender);
+    main_loop.join().expect("Main loop thread panicked");
+
+    let IpcResponse::Data { data } = response else {
+      panic!("Expected data but received {response:?}");
+    };
+    let workspace_list = serde_json::from_value::<WorkspaceListFile>(data).expect("Failed to parse workspace list");
+    assert_eq!(workspace_list.workspaces.len(), 1);
+    assert_eq!(workspace_list.workspaces[0].name, Some("Email and chat".to_string()));
+    assert_eq!(workspace_list.workspaces[0].window_count, 3);
+  }
+
+  #[test]
+  fn query_health_answers_with_health_from_main_loop() {
+    let (sender, receiver) = unbounded();
+    let main_loop = std::thread::spawn(move || match receiver.recv() {
+      Ok(Command::QueryHealth(reply_sender)) => {
+        reply_sender
+          .send(HealthSummary {
+            version: "1.2.3".to_string(),
+            uptime_in_s: 42,
+            hooks: HookHealthSummary {
+              hotkeys: HookHealth::Registered,
+              keyboard: HookHealth::Stale,
+              window_events: HookHealth::Registered,
+              touchpad_gestures: HookHealth::Disabled,
+              display_changes: HookHealth::NotRegistered,
+            },
+            workspaces: vec![],
+          })
+          .expect("Failed to reply");
+      }
+      other => panic!("Expected a health query but received {other:?}"),
+    });
+
+    let response = query_health(&sender);
+    main_loop.join().expect("Main loop thread panicked");
+
+    let IpcResponse::Data { data } = response else {
+      panic!("Expected data but received {response:?}");
+    };
+    assert_eq!(data["version"], "1.2.3");
+    assert_eq!(data["uptime_in_s"], 42);
+    assert_eq!(data["hooks"]["keyboard"], "stale");
+    assert_eq!(data["hooks"]["display_changes"], "not-registered");
+    let health = serde_json::from_value::<HealthSummary>(data).expect("Failed to parse health");
+    assert_eq!(health.hooks.touchpad_gestures, HookHealth::Disabled);
+  }
+
+  #[test]
+  fn handle_connection_answers_each_request_until_client_disconnects() {
+    let (sender, receiver) = unbounded();
+    let main_loop = spawn_main_loop(receiver, vec![CommandOutcome::Success]);
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).expect("Failed to bind listener");
+    let address = listener.local_addr().expect("Failed to get address");
+    let server = std::thread::spawn(move || {
+      let (stream, _) = listener.accept().expect("Failed to accept connection");
+      handle_connection(stream, TOKEN, &sender, &IpcEventPublisher::default());
+    });
+    let mut client = TcpStream::connect(address).expect("Failed to connect");
+
+    authenticate(TOKEN).write_to(&mut client).expect("Failed to write");
+    command("next-workspace").write_to(&mut client).expect("Failed to write");
+    command("unknown-command").write_to(&mut client).expect("Failed to write");
+    client.write_all(b"next-workspace\n").expect("Failed to write");
+    client.shutdown(std::net::Shutdown::Write).expect("Failed to shut down");
+    let mut reader = BufReader::new(client);
+    let authenticated = IpcResponse::read_from(&mut reader).expect("Failed to read authentication response");
+    let first = IpcResponse::read_from(&mut reader).expect("Failed to read first response");
+    let second = IpcResponse::read_from(&mut reader).expect("Failed to read second response");
+    let third = IpcResponse::read_from(&mut reader).expect("Failed to read third response");
+    server.join().expect("Server thread panicked");
+
+    assert_eq!(authenticated, IpcResponse::Ok);
+    assert_eq!(first, IpcResponse::Ok);
+    assert_eq!(
+      second,
+      IpcResponse::Error {
+        reason: "[unknown-command] is not a known command".to_string()
+      }
+    );
+    assert!(matches!(third, IpcResponse::Error { .. }));
+    assert!(IpcResponse::read_from(&mut reader).is_err());
+    let commands = main_loop.join().expect("Main loop thread panicked");
+    assert!(matches!(commands[..], [Command::NextWorkspace]));
+  }
+
+  #[test]
+  fn handle_connection_rejects_clients_that_do_not_authenticate_with_token() {
+    let (sender, receiver) = unbounded();
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).expect("Failed to bind listener");
+    let address = listener.local_addr().expect("Failed to get address");
+    let server = std::thread::spawn(move || {
+      for _ in 0..2 {
+        let (stream, _) = listener.accept().expect("Failed to accept connection");
+        handle_connection(stream, TOKEN, &sender, &IpcEventPublisher::default());
+      }
+    });
+
+    let mut responses = vec![];
+    for first_request in [authenticate("fedcba9876543210"), command("next-workspace")] {
+      let mut client = TcpStream::connect(address).expect("Failed to connect");
+      first_request.write_to(&mut client).expect("Failed to write");
+      let mut reader = BufReader::new(client);
+      responses.push(IpcResponse::read_from(&mut reader).expect("Failed to read response"));
+      assert!(IpcResponse::read_from(&mut reader).is_err());
+    }
+    server.join().expect("Server thread panicked");
+
+    assert!(responses.iter().all(|response| matches!(response, IpcResponse::Error { .. })));
+    assert!(receiver.try_recv().is_err());
+  }
+
+  #[test]
+  fn handle_connection_rejects_clients_that_send_overlong_requests() {
+    let (sender, receiver) = unbounded();
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).expect("Failed to bind listener");
+    let address = listener.local_addr().expect("Failed to get address");
+    let server = std::thread::spawn(move || {
+      let (stream, _) = listener.accept().expect("Failed to accept connection");
+      handle_connection(stream, TOKEN, &sender, &IpcEventPublisher::default());
+    });
+    let mut client = TcpStream::connect(address).expect("Failed to connect");
+
+    client
+      .write_all(&vec![b'a'; MAX_REQUEST_LENGTH_IN_BYTES as usize])
+      .expect("Failed to write");
+    let mut reader = BufReader::new(client);
+    let response = IpcResponse::read_from(&mut reader).expect("Failed to read response");
+    server.join().expect("Server thread panicked");
+
+    assert!(matches!(response, IpcResponse::Error { .. }));
+    assert!(IpcResponse::read_from(&mut reader).is_err());
+    assert!(receiver.try_recv().is_err());
+  }
+
+  #[test]
+  fn is_valid_token_requires_exact_token() {
+    assert!(is_valid_token(TOKEN, TOKEN));
+    assert!(!is_valid_token(&format!("{TOKEN}0"), TOKEN));
+    assert!(!is_valid_token(&TOKEN[1..], TOKEN));
+    assert!(!is_valid_token("fedcba9876543210", TOKEN));
+    assert!(!is_valid_token("", TOKEN));
+  }
+
+  #[test]
+  fn handle_connection_sends_published_events_to_subscribers() {
+    let (sender, _receiver) = unbounded();
+    let event_publisher = IpcEventPublisher::default();
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).expect("Failed to bind listener");
+    let address = listener.local_addr().expect("Failed to get address");
+    let server_event_publisher = event_publisher.clone();
+    std::thread::spawn(move || {
+      let (stream, _) = listener.accept().expect("Failed to accept connection");
+      handle_connection(stream, TOKEN, &sender, &server_event_publisher);
+    });
+    let mut client = TcpStream::connect(address).expect("Failed to connect");
+
+    authenticate(TOKEN).write_to(&mut client).expect("Failed to write");
+    IpcRequest::SubscribeEvents.write_to(&mut client).expect("Failed to write");
+    let mut reader = BufReader::new(client);
+    let authenticated = IpcResponse::read_from(&mut reader).expect("Failed to read authentication response");
+    let subscribed = IpcResponse::read_from(&mut reader).expect("Failed to read subscription response");
+    let drag_started = IpcEvent::DragStarted { hwnd: 7 };
+    let drag_completed = IpcEvent::DragCompleted {
+      hwnd: 7,
+      rect: Some(IpcRect::from(Rect::new(0, 0, 960, 1040))),
+    };
+    event_publisher.publish(drag_started);
+    event_publisher.publish(drag_completed);
+
+    assert_eq!(authenticated, IpcResponse::Ok);
+    assert_eq!(subscribed, IpcResponse::Ok);
+    assert_eq!(
+      IpcResponse::read_from(&mut reader).expect("Failed to read first event"),
+      IpcResponse::Event { event: drag_started }
+    );
+    assert_eq!(
+      IpcResponse::read_from(&mut reader).expect("Failed to read second event"),
+      IpcResponse::Event { event: drag_completed }
+    );
+  }
+
+  fn authenticate(token: &str) -> IpcRequest {
+    IpcRequest::Authenticate {
+      token: token.to_string(),
+    }
+  }
+
+  fn command(line: &str) -> IpcRequest {
+    IpcRequest::Command { line: line.to_string() }
+  }
+}
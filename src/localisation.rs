@@ -0,0 +1,405 @@
+use crate::api::get_user_locale_name;
+use crate::files::{FileManager, FileType, TranslationFile};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+const TRANSLATIONS_DIRECTORY_NAME: &str = "translations";
+/// The translations that are bundled with the application, by language. Translation files in the config folder take
+/// precedence over these.
+const BUNDLED_TRANSLATIONS: &[(&str, &str)] = &[("de", include_str!("../assets/translations/de.toml"))];
+
+static TRANSLATIONS: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+/// A text that is shown to the user in the tray menu, a notification or a dialog. Log messages are not localised so that
+/// log files remain readable for anyone helping with an issue.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Text {
+  ExploreDebugSettings,
+  PrintMonitorLayout,
+  InspectWindowUnderCursor,
+  LogTraceMessages,
+  CreateSupportBundle,
+  CreateSupportBundleWithWindowTitles,
+  SetWindowMargin,
+  /// Contains the placeholder `{margin}`.
+  DefaultWindowMargin,
+  SetDefaultLayout,
+  SpatialLayout,
+  ScrollingLayout,
+  CreateWorkspace,
+  DeleteWorkspace,
+  RestoreAllHiddenWindows,
+  ExportWorkspaceProfile,
+  ImportWorkspaceProfile,
+  SaveSession,
+  RestoreSession,
+  AllowSelectingSameCenterWindows,
+  ForceUsingAdminPrivileges,
+  UsageMetrics,
+  RecordUsageMetrics,
+  ShowUsageMetrics,
+  OpenExecutableFolder,
+  OpenConfigFolder,
+  OpenDataFolder,
+  ReloadConfiguration,
+  Reinitialise,
+  RestartWithAdminPrivileges,
+  Restart,
+  Exit,
+  AdminRequiredTooltip,
+  WorkspacesFileResetTooltip,
+  HotkeysLostTooltip,
+  HotkeysLostMessage,
+  /// Contains the placeholder `{hotkeys}`.
+  HotkeysNotRegisteredMessage,
+  /// Contains the placeholders `{command}` and `{reason}`.
+  CommandFailedTooltip,
+  CloseConfirmationPrompt,
+  WindowSwitcherPlaceholder,
+  PlaceModeHint,
+  /// Contains the placeholder `{path}`.
+  NoWorkspaceProfileToImportMessage,
+  WorkspaceProfileImportedQuestion,
+  /// Contains the placeholders `{monitor}` and `{replacement}`.
+  ReplacementMonitorQuestion,
+  /// Contains the placeholders `{monitor}` and `{count}`.
+  MoveParkedWindowsQuestion,
+  UsageMetricsTitle,
+  UsageMetricsRecordingDisabled,
+  NoUsageMetricsRecorded,
+}
+
+impl Text {
+  pub const ALL: [Text; 47] = [
+    Text::ExploreDebugSettings,
+    Text::PrintMonitorLayout,
+    Text::InspectWindowUnderCursor,
+    Text::LogTraceMessages,
+    Text::CreateSupportBundle,
+    Text::CreateSupportBundleWithWindowTitles,
+    Text::SetWindowMargin,
+    Text::DefaultWindowMargin,
+    Text::SetDefaultLayout,
+    Text::SpatialLayout,
+    Text::ScrollingLayout,
+    Text::CreateWorkspace,
+    Text::DeleteWorkspace,
+    Text::RestoreAllHiddenWindows,
+    Text::ExportWorkspaceProfile,
+    Text::ImportWorkspaceProfile,
+    Text::SaveSession,
+    Text::RestoreSession,
+    Text::AllowSelectingSameCenterWindows,
+    Text::ForceUsingAdminPrivileges,
+    Text::UsageMetrics,
+    Text::RecordUsageMetrics,
+    Text::ShowUsageMetrics,
+    Text::OpenExecutableFolder,
+    Text::OpenConfigFolder,
+    Text::OpenDataFolder,
+    Text::ReloadConfiguration,
+    Text::Reinitialise,
+    Text::RestartWithAdminPrivileges,
+    Text::Restart,
+    Text::Exit,
+    Text::AdminRequiredTooltip,
+    Text::WorkspacesFileResetTooltip,
+    Text::HotkeysLostTooltip,
+    Text::HotkeysLostMessage,
+    Text::HotkeysNotRegisteredMessage,
+    Text::CommandFailedTooltip,
+    Text::CloseConfirmationPrompt,
+    Text::WindowSwitcherPlaceholder,
+    Text::PlaceModeHint,
+    Text::NoWorkspaceProfileToImportMessage,
+    Text::WorkspaceProfileImportedQuestion,
+    Text::ReplacementMonitorQuestion,
+    Text::MoveParkedWindowsQuestion,
+    Text::UsageMetricsTitle,
+    Text::UsageMetricsRecordingDisabled,
+    Text::NoUsageMetricsRecorded,
+  ];
+
+  /// Returns the key of the text in translation files.
+  pub fn key(self) -> &'static str {
+    match self {
+      Text::ExploreDebugSettings => "explore_debug_settings",
+      Text::PrintMonitorLayout => "print_monitor_layout",
+      Text::InspectWindowUnderCursor => "inspect_window_under_cursor",
+      Text::LogTraceMessages => "log_trace_messages",
+      Text::CreateSupportBundle => "create_support_bundle",
+      Text::CreateSupportBundleWithWindowTitles => "create_support_bundle_with_window_titles",
+      Text::SetWindowMargin => "set_window_margin",
+      Text::DefaultWindowMargin => "default_window_margin",
+      Text::SetDefaultLayout => "set_default_layout",
+      Text::SpatialLayout => "spatial_layout",
+      Text::ScrollingLayout => "scrolling_layout",
+      Text::CreateWorkspace => "create_workspace",
+      Text::DeleteWorkspace => "delete_workspace",
+      Text::RestoreAllHiddenWindows => "restore_all_hidden_windows",
+      Text::ExportWorkspaceProfile => "export_workspace_profile",
+      Text::ImportWorkspaceProfile => "import_workspace_profile",
+      Text::SaveSession => "save_session",
+      Text::RestoreSession => "restore_session",
+      Text::AllowSelectingSameCenterWindows => "allow_selecting_same_center_windows",
+      Text::ForceUsingAdminPrivileges => "force_using_admin_privileges",
+      Text::UsageMetrics => "usage_metrics",
+      Text::RecordUsageMetrics => "record_usage_metrics",
+      Text::ShowUsageMetrics => "show_usage_metrics",
+      Text::OpenExecutableFolder => "open_executable_folder",
+      Text::OpenConfigFolder => "open_config_folder",
+      Text::OpenDataFolder => "open_data_folder",
+      Text::ReloadConfiguration => "reload_configuration",
+      Text::Reinitialise => "reinitialise",
+      Text::RestartWithAdminPrivileges => "restart_with_admin_privileges",
+      Text::Restart => "restart",
+      Text::Exit => "exit",
+      Text::AdminRequiredTooltip => "admin_required_tooltip",
+      Text::WorkspacesFileResetTooltip => "workspaces_file_reset_tooltip",
+      Text::HotkeysLostTooltip => "hotkeys_lost_tooltip",
+      Text::HotkeysLostMessage => "hotkeys_lost_message",
+      Text::HotkeysNotRegisteredMessage => "hotkeys_not_registered_message",
+      Text::CommandFailedTooltip => "command_failed_tooltip",
+      Text::CloseConfirmationPrompt => "close_confirmation_prompt",
+      Text::WindowSwitcherPlaceholder => "window_switcher_placeholder",
+      Text::PlaceModeHint => "place_mode_hint",
+      Text::NoWorkspaceProfileToImportMessage => "no_workspace_profile_to_import_message",
+      Text::WorkspaceProfileImportedQuestion => "workspace_profile_imported_question",
+      Text::ReplacementMonitorQuestion => "replacement_monitor_question",
+      Text::MoveParkedWindowsQuestion => "move_parked_windows_question",
+      Text::UsageMetricsTitle => "usage_metrics_title",
+      Text::UsageMetricsRecordingDisabled => "usage_metrics_recording_disabled",
+      Text::NoUsageMetricsRecorded => "no_usage_metrics_recorded",
+    }
+  }
+
+  pub fn english(self) -> &'static str {
+    match self {
+      Text::ExploreDebugSettings => "Explore debug settings",
+      Text::PrintMonitorLayout => "Print monitor layout to log file",
+      Text::InspectWindowUnderCursor => "Inspect window under cursor while holding Ctrl + Alt",
+      Text::LogTraceMessages => "Log trace messages for one minute",
+      Text::CreateSupportBundle => "Create support bundle in data folder",
+      Text::CreateSupportBundleWithWindowTitles => "Create support bundle including window titles in data folder",
+      Text::SetWindowMargin => "Set window margin to...",
+      Text::DefaultWindowMargin => "{margin} px (default)",
+      Text::SetDefaultLayout => "Set default layout...",
+      Text::SpatialLayout => "Spatial",
+      Text::ScrollingLayout => "Scrolling",
+      Text::CreateWorkspace => "Create workspace on current monitor",
+      Text::DeleteWorkspace => "Delete last workspace on current monitor",
+      Text::RestoreAllHiddenWindows => "Restore all hidden windows",
+      Text::ExportWorkspaceProfile => "Export workspace profile to data folder",
+      Text::ImportWorkspaceProfile => "Import workspace profile from data folder",
+      Text::SaveSession => "Save session to data folder",
+      Text::RestoreSession => "Restore session (relaunches missing applications)",
+      Text::AllowSelectingSameCenterWindows => "Allow selecting same center windows",
+      Text::ForceUsingAdminPrivileges => "Force using admin privileges",
+      Text::UsageMetrics => "Usage metrics (stored locally only)",
+      Text::RecordUsageMetrics => "Record usage metrics",
+      Text::ShowUsageMetrics => "Show usage metrics",
+      Text::OpenExecutableFolder => "Open executable folder",
+      Text::OpenConfigFolder => "Open config folder",
+      Text::OpenDataFolder => "Open data folder",
+      Text::ReloadConfiguration => "Reload configuration (applies rules and exclusions)",
+      Text::Reinitialise => "Reinitialise (e.g. after a display driver reset)",
+      Text::RestartWithAdminPrivileges => "Restart with admin privileges",
+      Text::Restart => "Restart",
+      Text::Exit => "Exit (restores any hidden windows)",
+      Text::AdminRequiredTooltip => "Randolf - run as admin to manage elevated windows",
+      Text::WorkspacesFileResetTooltip => "Randolf - workspaces file was corrupt and has been reset, see log file",
+      Text::HotkeysLostTooltip => "Randolf - some hotkeys stopped working, see log file",
+      Text::HotkeysLostMessage => "Randolf stopped receiving hotkeys and could not recover them. Please restart Randolf.",
+      Text::HotkeysNotRegisteredMessage => {
+        "Randolf could not register the following hotkeys again after they stopped working:\n\n{hotkeys}\n\nAnother \
+        application may be using them now. Please close it or restart Randolf."
+      }
+      Text::CommandFailedTooltip => "Randolf - {command} failed because {reason}",
+      Text::CloseConfirmationPrompt => "Press the hotkey again to close this window",
+      Text::WindowSwitcherPlaceholder => "Type to filter - Enter switches to the window, Shift+Enter brings it here",
+      Text::PlaceModeHint => "Press the number of a zone to select it - Enter places the window there, Esc cancels",
+      Text::NoWorkspaceProfileToImportMessage => {
+        "There is no workspace profile to import. Copy an exported profile to:\n\n{path}"
+      }
+      Text::WorkspaceProfileImportedQuestion => {
+        "The workspace profile was imported.\n\nDo you want to restart Randolf now to apply it?"
+      }
+      Text::ReplacementMonitorQuestion => {
+        "The workspace profile contains settings for monitor [{monitor}], which is not connected to this machine.\n\n\
+        Do you want to apply them to monitor [{replacement}] instead? Select \"No\" to choose another monitor or to skip \
+        these settings."
+      }
+      Text::MoveParkedWindowsQuestion => {
+        "Monitor [{monitor}] was disconnected while [{count}] window(s) were hidden in its inactive workspaces.\n\n\
+        Do you want to move these windows to the current workspace of the primary monitor? Select \"No\" to keep them \
+        hidden until the monitor is reconnected."
+      }
+      Text::UsageMetricsTitle => "Randolf usage metrics",
+      Text::UsageMetricsRecordingDisabled => "Recording usage metrics is currently disabled.",
+      Text::NoUsageMetricsRecorded => "No commands recorded yet",
+    }
+  }
+
+  /// Returns the text in the language selected via [`Localisation::initialise`] or in English if there is no
+  /// translation for it.
+  pub fn localised(self) -> &'static str {
+    TRANSLATIONS
+      .get()
+      .and_then(|translations| translations.get(self.key()))
+      .map(String::as_str)
+      .unwrap_or(self.english())
+  }
+}
+
+/// Selects the language of the tray menu, notifications and dialogs. Translations are read from the bundled translation
+/// files and from `translations/<language>.toml` in the config folder, which allows anyone to add or improve a
+/// translation without building the application.
+pub struct Localisation;
+
+impl Localisation {
+  /// Loads the translations for the given language or, if `None`, for the language of the user's locale. Must be called
+  /// before any text is localised, e.g. before the tray menu is created, because the language cannot be changed later.
+  pub fn initialise(language: Option<&str>) {
+    let Some(language) = language.map(str::to_string).or_else(get_user_locale_name) else {
+      info!("Using English for tray menu and notifications because no language is configured or detected");
+      return;
+    };
+    let community_directory = match FileManager::<TranslationFile>::get_path_to_directory(FileType::Config) {
+      Ok(directory) => Some(directory.join(TRANSLATIONS_DIRECTORY_NAME)),
+      Err(err) => {
+        warn!("Failed to get path to translations in config folder: {err}");
+        None
+      }
+    };
+    let translations = load_translations(&language, community_directory.as_deref());
+    if translations.is_empty() {
+      info!("Using English for tray menu and notifications because there are no translations for [{language}]");
+    } else {
+      info!("Using [{language}] translations for tray menu and notifications");
+    }
+    if TRANSLATIONS.set(translations).is_err() {
+      warn!("Ignoring request to change language to [{language}] because translations are already loaded");
+    }
+  }
+}
+
+/// Loads the translations for a language such as `de-AT` from the files for its primary subtag (e.g. `de.toml`) and
+/// then for the full language (e.g. `de-at.toml`), so that regional translations only need to contain the texts that
+/// differ. For each language, the file in the community directory takes precedence over the bundled one. Texts that
+/// are not translated in any file are left out, which makes them fall back to English.
+fn load_translations(language: &str, community_directory: Option<&Path>) -> HashMap<String, String> {
+  let language = language.trim().to_lowercase().replace('_', "-");
+  let primary_subtag = language.split('-').next().unwrap_or_default().to_string();
+  let mut candidates = vec![primary_subtag];
+  if !candidates.contains(&language) {
+    candidates.push(language);
+  }
+
+  let mut translations = HashMap::new();
+  for candidate in candidates.iter().filter(|candidate| !candidate.is_empty()) {
+    if let Some((_, content)) = BUNDLED_TRANSLATIONS.iter().find(|(name, _)| name == candidate) {
+      translations.extend(parse_translations(content, &format!("bundled [{candidate}] translations")));
+    }
+    let Some(path) = community_directory.map(|directory| directory.join(format!("{candidate}.toml"))) else {
+      continue;
+    };
+    if !path.exists() {
+      continue;
+    }
+    match fs::read_to_string(&path) {
+      Ok(content) => translations.extend(parse_translations(&content, &format!("[{}]", path.display()))),
+      Err(err) => error!("Failed to read translations from [{}]: {err}", path.display()),
+    }
+  }
+
+  translations
+}
+
+fn parse_translations(content: &str, source: &str) -> HashMap<String, String> {
+  match toml::from_str::<TranslationFile>(content) {
+    Ok(file) => {
+      for key in file.texts.keys() {
+        if !Text::ALL.iter().any(|text| text.key() == key) {
+          warn!("Ignoring unknown text [{key}] in {source}");
+        }
+      }
+      file.texts
+    }
+    Err(err) => {
+      error!("Failed to parse {source}: {err}");
+      HashMap::new()
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::utils::create_temp_directory;
+
+  #[test]
+  fn bundled_translations_translate_every_text_and_keep_placeholders() {
+    for (language, content) in BUNDLED_TRANSLATIONS {
+      let translations = parse_translations(content, language);
+      for text in Text::ALL {
+        let translation = translations
+          .get(text.key())
+          .unwrap_or_else(|| panic!("Missing [{}] in [{language}] translations", text.key()));
+        for placeholder in [
+          "{margin}",
+          "{hotkeys}",
+          "{command}",
+          "{reason}",
+          "{path}",
+          "{monitor}",
+          "{replacement}",
+          "{count}",
+        ] {
+          assert_eq!(
+            text.english().contains(placeholder),
+            translation.contains(placeholder),
+            "Placeholder {placeholder} of [{}] not kept in [{language}] translations",
+            text.key()
+          );
+        }
+      }
+      assert_eq!(translations.len(), Text::ALL.len());
+    }
+  }
+
+  #[test]
+  fn load_translations_falls_back_to_primary_subtag() {
+    let translations = load_translations("de-AT", None);
+
+    assert_eq!(translations.get(Text::Restart.key()).unwrap(), "Neu starten");
+  }
+
+  #[test]
+  fn load_translations_returns_nothing_for_unknown_language() {
+    assert!(load_translations("xx-YY", None).is_empty());
+    assert!(load_translations("", None).is_empty());
+  }
+
+  #[test]
+  fn load_translations_prefers_community_translations() {
+    let directory = create_temp_directory();
+    fs::write(directory.path().join("de.toml"), "restart = \"Neustart\"\n").expect("Failed to write translations");
+    fs::write(directory.path().join("de-ch.toml"), "exit = \"Beenden\"\n").expect("Failed to write translations");
+
+    let translations = load_translations("de_CH", Some(directory.path()));
+
+    assert_eq!(translations.get(Text::Restart.key()).unwrap(), "Neustart");
+    assert_eq!(translations.get(Text::Exit.key()).unwrap(), "Beenden");
+    assert_eq!(translations.get(Text::OpenDataFolder.key()).unwrap(), "Datenordner öffnen");
+  }
+
+  #[test]
+  fn load_translations_ignores_invalid_community_translations() {
+    let directory = create_temp_directory();
+    fs::write(directory.path().join("fr.toml"), "restart = ").expect("Failed to write translations");
+
+    assert!(load_translations("fr", Some(directory.path())).is_empty());
+  }
+}
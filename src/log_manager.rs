@@ -2,13 +2,14 @@
 
 use crate::configuration_provider::ConfigurationProvider;
 use crate::files::{FileManager, FileType};
+use crate::utils::LOG_FILE_NAME;
 use log::LevelFilter;
 use simplelog::{ColorChoice, CombinedLogger, ConfigBuilder, SharedLogger, TermLogger, TerminalMode, WriteLogger};
 use std::fs::File;
 use std::sync::{Arc, Mutex};
 
-#[cfg(not(debug_assertions))]
-const LOG_FILE_NAME: &str = "randolf.log";
+/// The maximum level that is logged unless trace logging was enabled temporarily.
+const DEFAULT_LOG_LEVEL: LevelFilter = LevelFilter::Debug;
 
 pub struct LogManager;
 
@@ -31,7 +32,7 @@ impl LogManager {
       .set_thread_level(LevelFilter::Off)
       .build();
     let mut loggers: Vec<Box<dyn SharedLogger>> = vec![TermLogger::new(
-      LevelFilter::Debug,
+      LevelFilter::Trace,
       config.clone(),
       TerminalMode::Mixed,
       ColorChoice::Auto,
@@ -54,6 +55,21 @@ impl LogManager {
 
     let count = loggers.len();
     CombinedLogger::init(loggers).expect("Failed to initialise logger");
-    info!("Initialised [{}] logger(s)", count);
+
+    // The loggers accept everything, so that the level can be changed at runtime by changing the global maximum level
+    log::set_max_level(DEFAULT_LOG_LEVEL);
+    info!("Initialised [{}] logger(s) with log level [{}]", count, DEFAULT_LOG_LEVEL);
+  }
+
+  /// Logs trace messages until [`LogManager::restore_default_log_level`] is called, e.g. to reproduce an intermittent
+  /// issue without restarting and losing the broken state.
+  pub fn enable_trace_logging() {
+    log::set_max_level(LevelFilter::Trace);
+    info!("Changed log level to [{}]", LevelFilter::Trace);
+  }
+
+  pub fn restore_default_log_level() {
+    log::set_max_level(DEFAULT_LOG_LEVEL);
+    info!("Restored log level to [{}]", DEFAULT_LOG_LEVEL);
   }
 }
@@ -2,17 +2,33 @@
 
 mod api;
 mod application_launcher;
+mod command_bus;
+mod command_file_manager;
 mod common;
 mod configuration_provider;
+mod cursor_speed_manager;
+mod display_change_manager;
 mod files;
 mod hotkey_manager;
+mod ipc_server;
+mod localisation;
 mod log_manager;
+mod overlay;
+mod support_bundle;
+mod task_runner;
+mod touchpad_gesture_manager;
 mod tray_menu_manager;
+mod usage_metrics_manager;
 mod utils;
 mod window_drag_manager;
+mod window_event_manager;
+mod window_inspector;
 mod window_manager;
+mod workspace_backend;
 mod workspace_guard;
 mod workspace_manager;
+mod workspace_note_manager;
+mod workspace_profile_manager;
 
 #[macro_use]
 extern crate log;
@@ -20,32 +36,71 @@ extern crate simplelog;
 
 use crate::api::{RealWindowsApi, WindowsApi};
 use crate::application_launcher::ApplicationLauncher;
+use crate::command_bus::CommandBus;
+use crate::command_file_manager::CommandFileManager;
 use crate::configuration_provider::{
-  ConfigurationProvider, FORCE_USING_ADMIN_PRIVILEGES, SCROLLING_RECONCILIATION_INTERVAL_IN_MS,
+  ConfigurationProvider, ENABLE_EFFICIENCY_MODE, EVENT_LOOP_INTERVAL_IN_MS, FORCE_USING_ADMIN_PRIVILEGES,
+  HEARTBEAT_INTERVAL_IN_S, NOTIFY_ON_FAILED_COMMANDS, SCROLLING_RECONCILIATION_INTERVAL_IN_MS, STARTUP_DELAY_IN_MS,
 };
-use crate::files::FileType;
+use crate::cursor_speed_manager::CursorSpeedManager;
+use crate::display_change_manager::DisplayChangeManager;
+use crate::files::{FileType, IgnoredWindowsFile, SessionFile, WindowListFile, WorkspaceListFile};
 use crate::hotkey_manager::HotkeyManager;
+use crate::ipc_server::{IpcEventPublisher, IpcServer};
+use crate::localisation::{Localisation, Text};
 use crate::log_manager::LogManager;
+use crate::overlay::{
+  ClosePromptOverlay, FocusBorderOverlay, PlaceModeOverlay, WindowSwitcherOverlay, WorkspaceBadgesOverlay,
+};
+use crate::support_bundle::SupportBundle;
+use crate::task_runner::TaskRunner;
+use crate::touchpad_gesture_manager::TouchpadGestureManager;
 use crate::tray_menu_manager::TrayMenuManager;
+use crate::usage_metrics_manager::UsageMetricsManager;
 use crate::utils::CONFIGURATION_PROVIDER_LOCK;
 use crate::window_drag_manager::WindowDragManager;
+use crate::window_event_manager::WindowEventManager;
+use crate::window_inspector::WindowInspector;
 use crate::window_manager::WindowManager;
-use common::Command;
-use crossbeam_channel::{Receiver, unbounded};
+use crate::workspace_note_manager::WorkspaceNoteManager;
+use crate::workspace_profile_manager::WorkspaceProfileManager;
+use common::{
+  Command, CommandOutcome, HealthSummary, HookHealth, HookHealthSummary, PersistentWorkspaceId, WorkspaceSummary,
+};
+use crossbeam_channel::{Receiver, Sender, unbounded};
+use randolf_ipc::{IpcEvent, IpcRect};
 use std::cell::RefCell;
 use std::rc::Rc;
 use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
 use std::time::{Duration, Instant};
 use win_hotkeys::InterruptHandle;
 
-const EVENT_LOOP_SLEEP_DURATION: Duration = Duration::from_millis(20);
-const HEART_BEAT_DURATION: Duration = Duration::from_secs(5);
+const DEAD_WINDOW_CLEANUP_INTERVAL: Duration = Duration::from_secs(60);
+const DISCONNECTED_MONITOR_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+const COMMAND_FILE_CHECK_INTERVAL: Duration = Duration::from_millis(250);
+const CURSOR_MONITOR_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+const HOTKEY_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+const TRAY_ICON_STYLE_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+const TRAY_NOTIFICATION_EXPIRY_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+const MAX_HOTKEY_RECOVERY_ATTEMPTS: u32 = 3;
+const HOTKEY_RECOVERY_RESET_DELAY: Duration = Duration::from_secs(60);
+const SHELL_READINESS_CHECK_INTERVAL: Duration = Duration::from_millis(250);
+const SHELL_READINESS_TIMEOUT: Duration = Duration::from_secs(30);
+const TRACE_LOGGING_DURATION: Duration = Duration::from_secs(60);
 
 fn main() {
   LogManager::new_initialised();
 
-  // Create configuration manager and tray menu
+  // Create configuration manager and wait for the Windows shell, then select the language and create the tray menu
   let configuration_manager = Arc::new(Mutex::new(ConfigurationProvider::new()));
+  wait_until_shell_is_ready(&configuration_manager);
+  Localisation::initialise(
+    configuration_manager
+      .lock()
+      .expect(CONFIGURATION_PROVIDER_LOCK)
+      .get_language(),
+  );
   let (command_sender, command_receiver) = unbounded();
   let tray_menu_manager = Rc::new(RefCell::new(TrayMenuManager::new_initialised(
     configuration_manager.clone(),
@@ -57,11 +112,12 @@ fn main() {
     configuration_manager
       .lock()
       .expect(CONFIGURATION_PROVIDER_LOCK)
-      .get_exclusion_settings(),
+      .get_shared_exclusion_settings(),
   );
   let launcher = Rc::new(RefCell::new(ApplicationLauncher::new_initialised(
     configuration_manager.clone(),
     windows_api.clone(),
+    TaskRunner::new(command_sender.clone()),
   )));
 
   // Log loaded configuration for reference
@@ -87,69 +143,295 @@ fn main() {
     configuration_manager.clone(),
     windows_api.clone(),
   )));
+  if let Some(backup_path) = wm.borrow_mut().take_corrupt_workspaces_file_backup() {
+    tray_menu_manager.borrow().notify_workspaces_file_was_reset(&backup_path);
+  }
+  if is_persisting_ignored_windows(&configuration_manager) {
+    match IgnoredWindowsFile::read_from_data_folder() {
+      Ok(ignored_windows) => wm.borrow_mut().restore_ignored_windows(&ignored_windows),
+      Err(err) => warn!("Failed to restore ignored windows: {}", err),
+    }
+  }
   wm.borrow_mut().reconcile_layouts();
   let workspace_ids = wm.borrow_mut().get_ordered_permanent_workspace_ids();
-  let hkm = HotkeyManager::new_with_hotkeys(configuration_manager.clone(), workspace_ids);
-  let interrupt_handle = hkm.initialise(command_sender.clone());
+  let hotkeys = register_hotkeys(&configuration_manager, &command_sender, workspace_ids);
 
   // Create window drag manager (for mouse-based features)
-  let mut window_drag_manager = WindowDragManager::new(configuration_manager.clone(), command_sender.clone());
+  wm.borrow().refresh_free_floating_monitors();
+  let mut window_drag_manager = WindowDragManager::new(
+    configuration_manager.clone(),
+    command_sender.clone(),
+    wm.borrow().get_shared_free_floating_monitors(),
+  );
   if let Err(e) = window_drag_manager.initialise() {
     error!("Failed to initialise window drag manager: {}", e);
     panic!("Exiting now because application failed to initialise window drag manager");
   }
 
+  // Create touchpad gesture manager (for swiping between workspaces)
+  let mut touchpad_gesture_manager = TouchpadGestureManager::new(configuration_manager.clone(), command_sender.clone());
+  if let Err(e) = touchpad_gesture_manager.initialise() {
+    error!("Failed to initialise touchpad gesture manager: {}", e);
+  }
+
+  // Create window event manager (for detecting windows moved or renamed by other applications)
+  let mut window_event_manager = WindowEventManager::new(command_sender.clone());
+  if let Err(e) = window_event_manager.initialise() {
+    error!("Failed to initialise window event manager: {}", e);
+  }
+
+  // Create display change manager (for detecting monitors being connected, disconnected, or rearranged)
+  let mut display_change_manager = DisplayChangeManager::new(command_sender.clone());
+  if let Err(e) = display_change_manager.initialise() {
+    error!("Failed to initialise display change manager: {}", e);
+  }
+
+  // Create window inspector (for debugging exclusion rules, enabled via the tray menu)
+  let mut window_inspector = WindowInspector::new(windows_api.clone());
+  if let Err(e) = window_inspector.initialise() {
+    error!("Failed to initialise window inspector: {}", e);
+  }
+
   // Run event loop
-  let scrolling_reconciliation_interval_in_ms = configuration_manager
-    .lock()
-    .expect(CONFIGURATION_PROVIDER_LOCK)
-    .get_i32(SCROLLING_RECONCILIATION_INTERVAL_IN_MS);
-  let scrolling_reconciliation_interval =
-    Duration::from_millis(u64::try_from(scrolling_reconciliation_interval_in_ms).unwrap_or_default());
   run_loop(
     configuration_manager,
+    command_sender,
     command_receiver,
     tray_menu_manager,
     launcher,
     wm,
-    interrupt_handle,
-    scrolling_reconciliation_interval,
+    hotkeys,
   );
 }
 
 fn run_loop(
   configuration_manager: Arc<Mutex<ConfigurationProvider>>,
+  command_sender: Sender<Command>,
   command_receiver: Receiver<Command>,
   tray_menu_manager: Rc<RefCell<TrayMenuManager>>,
   launcher: Rc<RefCell<ApplicationLauncher<RealWindowsApi>>>,
   wm: Rc<RefCell<WindowManager<RealWindowsApi>>>,
-  interrupt_handle: InterruptHandle,
-  scrolling_reconciliation_interval: Duration,
+  mut hotkeys: RegisteredHotkeys,
 ) {
-  #[cfg(debug_assertions)]
+  let started_at = Instant::now();
+  let (scrolling_reconciliation_interval_in_ms, event_loop_interval_in_ms, heartbeat_interval_in_s, is_efficiency_mode) = {
+    let guard = configuration_manager.lock().expect(CONFIGURATION_PROVIDER_LOCK);
+    (
+      guard.get_i32(SCROLLING_RECONCILIATION_INTERVAL_IN_MS),
+      guard.get_i32(EVENT_LOOP_INTERVAL_IN_MS),
+      guard.get_i32(HEARTBEAT_INTERVAL_IN_S),
+      guard.get_bool(ENABLE_EFFICIENCY_MODE),
+    )
+  };
+  if is_efficiency_mode {
+    api::set_efficiency_mode(true);
+  }
+  let scrolling_reconciliation_interval =
+    Duration::from_millis(u64::try_from(scrolling_reconciliation_interval_in_ms).unwrap_or_default());
+  let event_loop_interval = Duration::from_millis(u64::try_from(event_loop_interval_in_ms).unwrap_or(1).max(1));
+  let heartbeat_interval = Duration::from_secs(u64::try_from(heartbeat_interval_in_s).unwrap_or_default());
+  let mut usage_metrics_manager = UsageMetricsManager::new(configuration_manager.clone());
+  let mut workspace_note_manager = WorkspaceNoteManager::default();
+  if let Err(e) = workspace_note_manager.initialise() {
+    error!("Failed to initialise workspace notes: {}", e);
+  }
+  let mut focus_border_overlay = FocusBorderOverlay::new(configuration_manager.clone());
+  focus_border_overlay.update(wm.borrow().get_managed_foreground_window());
+  let mut close_prompt_overlay = ClosePromptOverlay::new(configuration_manager.clone());
+  let mut workspace_badges_overlay = WorkspaceBadgesOverlay::new(configuration_manager.clone());
+  let mut window_switcher_overlay = WindowSwitcherOverlay::new(command_sender.clone());
+  let mut place_mode_overlay = PlaceModeOverlay::new(command_sender.clone());
+  let shared_exclusion_settings = configuration_manager
+    .lock()
+    .expect(CONFIGURATION_PROVIDER_LOCK)
+    .get_shared_exclusion_settings();
+  let mut cursor_speed_manager =
+    CursorSpeedManager::new(configuration_manager.clone(), RealWindowsApi::new(shared_exclusion_settings));
+  let mut command_file_manager = CommandFileManager::new(configuration_manager.clone(), command_sender.clone());
+  let workspace_profile_manager = WorkspaceProfileManager::new(configuration_manager.clone());
+  let event_publisher = IpcEventPublisher::default();
+  let mut ipc_server = IpcServer::new(configuration_manager.clone(), command_sender.clone(), event_publisher.clone());
+  if let Err(e) = ipc_server.initialise() {
+    error!("Failed to initialise IPC server: {}", e);
+  }
   let mut last_heartbeat = Instant::now();
+  let mut last_command: Option<(String, Duration)> = None;
   let mut last_scrolling_layout_reconciliation = Instant::now();
+  let mut last_dead_window_cleanup = Instant::now();
+  let mut last_disconnected_monitor_check = Instant::now();
+  let mut last_command_file_check = Instant::now();
+  let mut last_cursor_monitor_check = Instant::now();
+  let mut last_hotkey_check = Instant::now();
+  let mut last_tray_icon_style_check = Instant::now();
+  let mut last_tray_notification_expiry_check = Instant::now();
+  let mut trace_logging_until: Option<Instant> = None;
+  let mut command_bus = CommandBus::new(command_receiver);
 
   loop {
     api::do_process_windows_messages();
-    if let Ok(command) = command_receiver.try_recv() {
+    if let Some(command) = command_bus.next_command() {
+      let (command, outcome_sender) = command.into_parts();
       info!("Command received: {}", command);
+      let command_started = Instant::now();
+      let command_name = command.to_string();
+      let moves_windows_or_cursor = command.moves_windows_or_cursor();
+      let usage_metric_name = command.usage_metric_name();
+      let mut outcome = CommandOutcome::Success;
       match command {
-        Command::NearMaximiseWindow => wm.borrow_mut().near_maximise_or_restore(),
-        Command::MinimiseWindow => wm.borrow_mut().minimise_window(),
-        Command::MoveWindow(direction) => wm.borrow_mut().move_window(direction),
-        Command::ResizeSpatialWindow(direction) => wm.borrow_mut().resize_spatial_window(direction),
-        Command::ResizeScrollingWindow(direction) => wm.borrow_mut().resize_scrolling_window(direction),
-        Command::MouseResizeCompleted(window) => wm.borrow_mut().finish_mouse_resize(window),
+        command if command.targets_foreground_window() && wm.borrow().is_foreground_window_out_of_reach() => {
+          tray_menu_manager.borrow().notify_window_requires_admin();
+          outcome = CommandOutcome::error("the foreground window is elevated, run Randolf as admin to manage it");
+        }
+        command if command.moves_windows_or_cursor() && wm.borrow().is_paused_by_window() => {
+          outcome = CommandOutcome::no_op("a window that pauses Randolf is in the foreground or full-screen");
+        }
+        Command::NearMaximiseWindow => outcome = wm.borrow_mut().near_maximise_or_restore(),
+        Command::RestoreOlderPlacement => outcome = wm.borrow_mut().restore_older_placement(),
+        Command::TogglePictureInPicture => outcome = wm.borrow_mut().toggle_picture_in_picture(),
+        Command::ToggleMonocle => outcome = wm.borrow_mut().toggle_monocle(),
+        Command::ToggleWorkspaceNote => workspace_note_manager.toggle(wm.borrow().get_active_workspace_under_cursor()),
+        Command::ToggleManageWindow => {
+          outcome = wm.borrow_mut().toggle_manage_window();
+          focus_border_overlay.update(wm.borrow().get_managed_foreground_window());
+          if is_persisting_ignored_windows(&configuration_manager)
+            && let Err(err) = wm.borrow().capture_ignored_windows().write_to_data_folder()
+          {
+            warn!("Failed to persist ignored windows: {}", err);
+          }
+        }
+        Command::MinimiseWindow => outcome = wm.borrow_mut().minimise_window(),
+        Command::MoveWindow(direction) => outcome = wm.borrow_mut().move_window(direction),
+        Command::ResizeSpatialWindow(direction) => outcome = wm.borrow_mut().resize_spatial_window(direction),
+        Command::MoveSharedEdge(direction) => outcome = wm.borrow_mut().move_shared_edge(direction),
+        Command::RotateTiledSplit => outcome = wm.borrow_mut().rotate_tiled_split(),
+        Command::SwapTiledWindows => outcome = wm.borrow_mut().swap_tiled_windows(),
+        Command::ResizeScrollingWindow(direction) => outcome = wm.borrow_mut().resize_scrolling_window(direction),
+        Command::MouseDragStarted(window) => event_publisher.publish(IpcEvent::DragStarted { hwnd: window.hwnd }),
+        Command::MouseDragCompleted(window, snap_zone) => {
+          wm.borrow_mut().finish_mouse_drag(window, snap_zone);
+          let rect = wm.borrow().get_window_rect(window).map(IpcRect::from);
+          event_publisher.publish(IpcEvent::DragCompleted { hwnd: window.hwnd, rect });
+        }
+        Command::MouseResizeStarted(window) => event_publisher.publish(IpcEvent::ResizeStarted { hwnd: window.hwnd }),
+        Command::MouseResizeCompleted(window) => {
+          wm.borrow_mut().finish_mouse_resize(window);
+          let rect = wm.borrow().get_window_rect(window).map(IpcRect::from);
+          event_publisher.publish(IpcEvent::ResizeCompleted { hwnd: window.hwnd, rect });
+        }
+        Command::WindowLocationChanged(window) => wm.borrow_mut().forget_placement_if_moved_externally(window),
+        Command::WindowTitleChanged(window) => wm.borrow_mut().reevaluate_window_after_title_change(window),
+        Command::ForegroundWindowChanged(window) => {
+          wm.borrow_mut().guard_foreground_window(window);
+          focus_border_overlay.update(wm.borrow().get_managed_foreground_window());
+        }
         Command::MoveCursor(direction) => wm.borrow_mut().move_cursor(direction),
-        Command::CloseWindow => wm.borrow_mut().close_window(),
+        Command::ThrowCursor(direction) => wm.borrow_mut().throw_cursor(direction),
+        Command::FocusNextWindow => outcome = wm.borrow_mut().cycle_focus(true),
+        Command::FocusPreviousWindow => outcome = wm.borrow_mut().cycle_focus(false),
+        Command::CloseWindow => {
+          outcome = wm.borrow_mut().close_window();
+          close_prompt_overlay.update(wm.borrow().get_window_awaiting_close_confirmation());
+        }
         Command::SwitchWorkspace(id) => {
-          wm.borrow_mut().switch_workspace(id);
-          tray_menu_manager.borrow_mut().update_tray_icon(id);
+          outcome = wm.borrow_mut().switch_workspace(id);
+          if outcome.is_success() {
+            tray_menu_manager.borrow_mut().update_tray_icon(id);
+            workspace_note_manager.refresh(wm.borrow().get_active_workspace_under_cursor());
+          }
+        }
+        Command::SwitchToWorkspaceNumber(number) => match workspace_id_for_number(&hotkeys.numbered_workspace_ids, number) {
+          Some(id) => {
+            outcome = wm.borrow_mut().switch_workspace(id);
+            if outcome.is_success() {
+              tray_menu_manager.borrow_mut().update_tray_icon(id);
+              workspace_note_manager.refresh(wm.borrow().get_active_workspace_under_cursor());
+            }
+          }
+          None => outcome = CommandOutcome::error(format!("workspace number [{number}] does not exist")),
+        },
+        Command::NextWorkspace | Command::PreviousWorkspace => {
+          let is_next = matches!(command, Command::NextWorkspace);
+          let switched_to = wm.borrow_mut().switch_to_adjacent_workspace(is_next);
+          match switched_to {
+            Ok(id) => {
+              tray_menu_manager.borrow_mut().update_tray_icon(id);
+              workspace_note_manager.refresh(wm.borrow().get_active_workspace_under_cursor());
+            }
+            Err(adjacent_outcome) => outcome = adjacent_outcome,
+          }
+        }
+        Command::MoveWindowToWorkspace(id) => outcome = wm.borrow_mut().move_window_to_workspace(id),
+        Command::MoveWindowToWorkspaceNumber(number) => {
+          outcome = match workspace_id_for_number(&hotkeys.numbered_workspace_ids, number) {
+            Some(id) => wm.borrow_mut().move_window_to_workspace(id),
+            None => CommandOutcome::error(format!("workspace number [{number}] does not exist")),
+          }
+        }
+        Command::MoveWindowToMonitor(direction) => outcome = wm.borrow_mut().move_window_to_monitor(direction),
+        Command::MoveWindowToMonitorNumber(number) => outcome = wm.borrow_mut().move_window_to_monitor_number(number),
+        Command::BringWindowHere(window) => outcome = wm.borrow_mut().bring_window_here(window),
+        Command::OpenWindowSwitcher => match wm.borrow().get_active_workspace_under_cursor() {
+          Some((_, work_area)) => window_switcher_overlay.open(wm.borrow().list_windows_for_switcher(), work_area),
+          None => outcome = CommandOutcome::error("there is no active workspace under the cursor"),
+        },
+        Command::EnterPlaceMode => match wm.borrow().get_place_mode_zones() {
+          Ok((window, work_area, zones)) => place_mode_overlay.open(window, work_area, zones),
+          Err(place_mode_outcome) => outcome = place_mode_outcome,
+        },
+        Command::PlaceWindowInZone(window, zone) => outcome = wm.borrow_mut().place_window_in_zone(window, zone),
+        Command::SwitchToWindow(window) => {
+          let switched_to = wm.borrow_mut().switch_to_window(window);
+          match switched_to {
+            Ok(Some(id)) => {
+              tray_menu_manager.borrow_mut().update_tray_icon(id);
+              workspace_note_manager.refresh(wm.borrow().get_active_workspace_under_cursor());
+            }
+            Ok(None) => {}
+            Err(switch_outcome) => outcome = switch_outcome,
+          }
+        }
+        Command::CreateWorkspace => {
+          outcome = wm.borrow_mut().create_workspace();
+          if outcome.is_success() {
+            reregister_hotkeys_if_workspaces_changed(&mut hotkeys, &configuration_manager, &command_sender, &wm);
+          }
+        }
+        Command::DeleteWorkspace => {
+          outcome = wm.borrow_mut().delete_workspace();
+          if outcome.is_success() {
+            reregister_hotkeys_if_workspaces_changed(&mut hotkeys, &configuration_manager, &command_sender, &wm);
+          }
+        }
+        Command::RestoreAllHiddenWindows => wm.borrow_mut().restore_all_managed_windows(),
+        Command::Reinitialise => {
+          // The mouse, touchpad, and window event hooks do not depend on monitor state, so only hotkeys are re-registered
+          wm.borrow_mut().reinitialise();
+          reregister_hotkeys_if_workspaces_changed(&mut hotkeys, &configuration_manager, &command_sender, &wm);
+          workspace_note_manager.refresh(wm.borrow().get_active_workspace_under_cursor());
+        }
+        Command::DisplayChanged => {
+          info!("Display configuration changed, refreshing monitors and workspaces");
+          wm.borrow_mut().reinitialise();
+          for (monitor_id, parked_window_count) in wm.borrow_mut().find_parked_windows_of_disconnected_monitors() {
+            ask_to_move_parked_windows(monitor_id, parked_window_count, command_sender.clone());
+          }
+          reregister_hotkeys_if_workspaces_changed(&mut hotkeys, &configuration_manager, &command_sender, &wm);
+          workspace_note_manager.refresh(wm.borrow().get_active_workspace_under_cursor());
+        }
+        Command::WorkAreaChanged => wm.borrow_mut().refit_windows_after_work_area_change(),
+        Command::MoveParkedWindowsToPrimaryMonitor(monitor_id) => {
+          wm.borrow_mut().move_parked_windows_to_primary_monitor(monitor_id)
+        }
+        Command::DragWindows(is_enabled) => {
+          // Temporarily leave efficiency mode so that dragging and resizing windows remains smooth
+          if is_efficiency_mode {
+            api::set_efficiency_mode(!is_enabled);
+          }
+          tray_menu_manager.borrow_mut().set_window_drag_icon(is_enabled);
+          workspace_badges_overlay.set_win_key_held(is_enabled);
         }
-        Command::MoveWindowToWorkspace(id) => wm.borrow_mut().move_window_to_workspace(id),
-        Command::DragWindows(is_enabled) => tray_menu_manager.borrow_mut().set_window_drag_icon(is_enabled),
         Command::OpenApplication(path, as_admin) => launcher.borrow_mut().launch(path, None, as_admin),
+        Command::LaunchedApplicationReady => launcher.borrow().set_cursor_position(),
         Command::OpenRandolfExecutableFolder => {
           let args = launcher.borrow_mut().get_executable_folder();
           launcher.borrow_mut().launch("explorer.exe".to_string(), Some(&args), false);
@@ -158,13 +440,109 @@ fn run_loop(
           let args = launcher.borrow_mut().get_project_folder(FileType::Config);
           launcher.borrow_mut().launch("explorer.exe".to_string(), Some(&args), false);
         }
+        Command::CreateSupportBundle(include_window_titles) => {
+          let configuration_path = configuration_manager
+            .lock()
+            .expect(CONFIGURATION_PROVIDER_LOCK)
+            .get_path_to_configuration_file()
+            .to_path_buf();
+          let state = wm.borrow_mut().describe_state(include_window_titles);
+          let monitors = wm.borrow().describe_monitors();
+          match SupportBundle::new(state, monitors, &configuration_path, include_window_titles).write_to_data_folder() {
+            Ok(path) => {
+              info!("Created support bundle at [{}]", path.display());
+              let args = launcher.borrow_mut().get_project_folder(FileType::Data);
+              launcher.borrow_mut().launch("explorer.exe".to_string(), Some(&args), false);
+            }
+            Err(err) => outcome = CommandOutcome::error(format!("failed to create support bundle: {err}")),
+          }
+        }
+        Command::EnableTraceLogging => {
+          // Enabling it again while it is enabled extends the duration
+          LogManager::enable_trace_logging();
+          trace_logging_until = Some(Instant::now() + TRACE_LOGGING_DURATION);
+        }
+        Command::ExportWorkspaceProfile => match workspace_profile_manager.export_to_data_folder() {
+          Ok(path) => {
+            info!("Exported workspace profile to [{}]", path.display());
+            let args = launcher.borrow_mut().get_project_folder(FileType::Data);
+            launcher.borrow_mut().launch("explorer.exe".to_string(), Some(&args), false);
+          }
+          Err(err) => outcome = CommandOutcome::error(format!("failed to export workspace profile: {err}")),
+        },
+        Command::ImportWorkspaceProfile => workspace_profile_manager.import_from_data_folder(command_sender.clone()),
+        Command::SaveSession => match wm.borrow().capture_session().write_to_data_folder() {
+          Ok(path) => info!("Saved session to [{}]", path.display()),
+          Err(err) => outcome = CommandOutcome::error(format!("failed to save session: {err}")),
+        },
+        Command::RestoreSession(is_relaunching_missing_applications) => match SessionFile::read_from_data_folder() {
+          Ok(session) => {
+            let missing_windows = wm.borrow_mut().restore_session(&session);
+            if is_relaunching_missing_applications {
+              let executable_paths = missing_windows
+                .iter()
+                .filter_map(|window| window.executable_path.clone())
+                .collect();
+              launcher.borrow().relaunch_for_session(executable_paths);
+            }
+          }
+          Err(err) => outcome = CommandOutcome::error(format!("failed to restore session: {err}")),
+        },
+        Command::ReconcileWindowRules => outcome = wm.borrow_mut().reconcile_window_rules(),
+        Command::RescueOffscreenWindows => outcome = wm.borrow_mut().rescue_offscreen_windows(),
+        Command::ShowUsageMetrics => {
+          let summary = usage_metrics_manager.summary();
+          std::thread::spawn(move || api::show_message(Text::UsageMetricsTitle.localised(), &summary));
+        }
+        Command::SetUsageMetricsRecording(is_enabled) => usage_metrics_manager.set_enabled(is_enabled),
+        Command::ListWindows(query) => {
+          let windows = wm.borrow().list_windows(&query);
+          match WindowListFile::new(windows).write_to_data_folder() {
+            Ok(path) => info!("Listed managed windows matching {query:?} in [{}]", path.display()),
+            Err(err) => outcome = CommandOutcome::error(format!("failed to list managed windows: {err}")),
+          }
+        }
+        Command::QueryWindows(query, reply_sender) => {
+          if reply_sender.send(wm.borrow().list_windows(&query)).is_err() {
+            warn!("Failed to answer window query because the requester is no longer waiting");
+          }
+        }
+        Command::ListWorkspaces => {
+          let workspaces = list_workspaces(&wm, &hotkeys.numbered_workspace_ids, &workspace_note_manager);
+          match WorkspaceListFile::new(workspaces).write_to_data_folder() {
+            Ok(path) => info!("Listed workspaces in [{}]", path.display()),
+            Err(err) => outcome = CommandOutcome::error(format!("failed to list workspaces: {err}")),
+          }
+        }
+        Command::QueryWorkspaces(reply_sender) => {
+          let workspaces = list_workspaces(&wm, &hotkeys.numbered_workspace_ids, &workspace_note_manager);
+          if reply_sender.send(workspaces).is_err() {
+            warn!("Failed to answer workspace query because the requester is no longer waiting");
+          }
+        }
+        Command::QueryHealth(reply_sender) => {
+          let health = HealthSummary {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            uptime_in_s: started_at.elapsed().as_secs(),
+            hooks: get_hook_health(&hotkeys),
+            workspaces: list_workspaces(&wm, &hotkeys.numbered_workspace_ids, &workspace_note_manager),
+          };
+          if reply_sender.send(health).is_err() {
+            warn!("Failed to answer health query because the requester is no longer waiting");
+          }
+        }
+        Command::ReportingOutcome(command, _) => {
+          outcome = CommandOutcome::error(format!("[{command}] is nested in another command reporting its outcome"));
+        }
         Command::OpenRandolfDataFolder => {
           let args = launcher.borrow_mut().get_project_folder(FileType::Data);
           launcher.borrow_mut().launch("explorer.exe".to_string(), Some(&args), false);
         }
         Command::RestartRandolf(as_admin) => {
           wm.borrow_mut().restore_all_managed_windows();
-          interrupt_handle.interrupt();
+          cursor_speed_manager.restore();
+          usage_metrics_manager.flush();
+          hotkeys.interrupt_handle.interrupt();
           let as_admin = configuration_manager
             .lock()
             .expect(CONFIGURATION_PROVIDER_LOCK)
@@ -176,22 +554,355 @@ fn run_loop(
         }
         Command::Exit => {
           wm.borrow_mut().restore_all_managed_windows();
-          interrupt_handle.interrupt();
+          cursor_speed_manager.restore();
+          usage_metrics_manager.flush();
+          hotkeys.interrupt_handle.interrupt();
           info!("Application exited cleanly");
           std::process::exit(0);
         }
       }
+      if moves_windows_or_cursor {
+        cursor_speed_manager.update();
+      }
+      wm.borrow().refresh_free_floating_monitors();
+      if workspace_badges_overlay.is_shown() {
+        workspace_badges_overlay.show(wm.borrow_mut().list_workspace_badges(&hotkeys.numbered_workspace_ids));
+      }
+      usage_metrics_manager.record(usage_metric_name, &outcome);
+      report_command_outcome(
+        &command_name,
+        outcome,
+        outcome_sender,
+        &configuration_manager,
+        &tray_menu_manager,
+      );
+      last_command = Some((command_name, command_started.elapsed()));
     }
     run_if_due(
       &mut last_scrolling_layout_reconciliation,
       scrolling_reconciliation_interval,
-      || wm.borrow_mut().reconcile_layouts(),
+      || {
+        if !wm.borrow().is_paused_by_window() {
+          wm.borrow_mut().reconcile_layouts();
+        }
+      },
     );
-    #[cfg(debug_assertions)]
-    run_if_due(&mut last_heartbeat, HEART_BEAT_DURATION, || {
-      trace!("Still listening for events...");
+    run_if_due(&mut last_dead_window_cleanup, DEAD_WINDOW_CLEANUP_INTERVAL, || {
+      wm.borrow_mut().remove_dead_windows()
+    });
+    run_if_due(
+      &mut last_disconnected_monitor_check,
+      DISCONNECTED_MONITOR_CHECK_INTERVAL,
+      || {
+        if wm.borrow().has_stale_monitor_state() {
+          info!("Detected stale monitor state, e.g. after a display driver reset, reinitialising now");
+          command_sender
+            .send(Command::Reinitialise)
+            .expect("Failed to send reinitialise command");
+        }
+        for (monitor_id, parked_window_count) in wm.borrow_mut().find_parked_windows_of_disconnected_monitors() {
+          ask_to_move_parked_windows(monitor_id, parked_window_count, command_sender.clone());
+        }
+        wm.borrow_mut().add_workspaces_for_new_monitors();
+        wm.borrow().refresh_free_floating_monitors();
+        reregister_hotkeys_if_workspaces_changed(&mut hotkeys, &configuration_manager, &command_sender, &wm);
+      },
+    );
+    run_if_due(&mut last_command_file_check, COMMAND_FILE_CHECK_INTERVAL, || {
+      command_file_manager.process_appended_commands()
+    });
+    run_if_due(&mut last_cursor_monitor_check, CURSOR_MONITOR_CHECK_INTERVAL, || {
+      cursor_speed_manager.update()
+    });
+    run_if_due(&mut last_hotkey_check, HOTKEY_CHECK_INTERVAL, || {
+      recover_hotkeys_if_lost(&mut hotkeys, &configuration_manager, &command_sender, &tray_menu_manager)
+    });
+    run_if_due(&mut last_tray_icon_style_check, TRAY_ICON_STYLE_CHECK_INTERVAL, || {
+      tray_menu_manager.borrow_mut().refresh_icon_style()
     });
-    std::thread::sleep(EVENT_LOOP_SLEEP_DURATION);
+    if workspace_badges_overlay.is_due() {
+      workspace_badges_overlay.show(wm.borrow_mut().list_workspace_badges(&hotkeys.numbered_workspace_ids));
+    }
+    run_if_due(
+      &mut last_tray_notification_expiry_check,
+      TRAY_NOTIFICATION_EXPIRY_CHECK_INTERVAL,
+      || tray_menu_manager.borrow().clear_expired_notification(),
+    );
+    if trace_logging_until.is_some_and(|until| Instant::now() >= until) {
+      LogManager::restore_default_log_level();
+      trace_logging_until = None;
+    }
+    if !heartbeat_interval.is_zero() {
+      run_if_due(&mut last_heartbeat, heartbeat_interval, || {
+        log_heartbeat(command_bus.queue_depth(), &last_command);
+        debug!("{}", wm.borrow().describe_window_metrics());
+        debug!("{}", wm.borrow().describe_workspace_switch_latency());
+        let hooks = get_hook_health(&hotkeys);
+        debug!(
+          "Hooks: hotkeys [{}], keyboard [{}], window events [{}], touchpad gestures [{}], display changes [{}]",
+          hooks.hotkeys, hooks.keyboard, hooks.window_events, hooks.touchpad_gestures, hooks.display_changes
+        );
+      });
+    }
+    std::thread::sleep(event_loop_interval);
+  }
+}
+
+/// The currently registered hotkeys, together with the ordered workspaces that the workspace hotkeys were registered
+/// for, the number of each workspace's hotkeys, the key combinations that failed to register, when they were
+/// registered, how often the hotkeys had to be recovered, and the key combinations that were lost while recovering them.
+struct RegisteredHotkeys {
+  interrupt_handle: InterruptHandle,
+  event_loop: JoinHandle<()>,
+  workspace_ids: Vec<PersistentWorkspaceId>,
+  numbered_workspace_ids: Vec<(usize, PersistentWorkspaceId)>,
+  failed_hotkeys: Vec<String>,
+  registered_at: Instant,
+  recovery_attempts: u32,
+  lost_hotkeys: Vec<String>,
+}
+
+impl RegisteredHotkeys {
+  /// Returns the health of the keyboard hook through which the hotkeys are received, which is registered for as long as
+  /// the event loop listening to the hotkeys is running.
+  fn hook_health(&self) -> HookHealth {
+    HookHealth::new(true, !self.event_loop.is_finished())
+  }
+}
+
+/// Returns the health of every hook, as written to the log with every heartbeat and reported to IPC clients.
+fn get_hook_health(hotkeys: &RegisteredHotkeys) -> HookHealthSummary {
+  HookHealthSummary {
+    hotkeys: hotkeys.hook_health(),
+    keyboard: WindowDragManager::hook_health(),
+    window_events: WindowEventManager::hook_health(),
+    touchpad_gestures: TouchpadGestureManager::hook_health(),
+    display_changes: DisplayChangeManager::hook_health(),
+  }
+}
+
+/// Registers all hotkeys, including one per workspace in the given order, and returns the handle to stop listening to
+/// them again.
+fn register_hotkeys(
+  configuration_manager: &Arc<Mutex<ConfigurationProvider>>,
+  command_sender: &Sender<Command>,
+  workspace_ids: Vec<PersistentWorkspaceId>,
+) -> RegisteredHotkeys {
+  debug!(
+    "Registering hotkeys for ordered workspaces: [{}]",
+    workspace_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ")
+  );
+  let hkm = HotkeyManager::new_with_hotkeys(configuration_manager.clone(), workspace_ids.clone());
+  let failed_hotkeys = hkm.get_failed_hotkeys().to_vec();
+  let numbered_workspace_ids = hkm.get_numbered_workspace_ids().to_vec();
+  let (interrupt_handle, event_loop) = hkm.initialise(command_sender.clone());
+  RegisteredHotkeys {
+    interrupt_handle,
+    event_loop,
+    workspace_ids,
+    numbered_workspace_ids,
+    failed_hotkeys,
+    registered_at: Instant::now(),
+    recovery_attempts: 0,
+    lost_hotkeys: Vec::new(),
+  }
+}
+
+/// Registers all hotkeys again if listening to them stopped unexpectedly, e.g. because the keyboard hook failed after
+/// the system resumed from sleep. Notifies the user if key combinations that worked before can no longer be registered,
+/// e.g. because another application took them in the meantime, or once if recovering keeps failing. Forgets about
+/// previous attempts once the hotkeys have been working for a while again.
+///
+/// Windows may also remove keyboard hooks silently, which does not stop the thread listening to the hotkeys. The
+/// keyboard hook of the mouse-based features serves as a liveness probe for this case: if it has not been called for a
+/// while even though the user provided input since, it is re-installed. The hotkeys can only be registered again once
+/// their thread stopped, so the user is informed that some hotkeys may have stopped working.
+fn recover_hotkeys_if_lost(
+  hotkeys: &mut RegisteredHotkeys,
+  configuration_manager: &Arc<Mutex<ConfigurationProvider>>,
+  command_sender: &Sender<Command>,
+  tray_menu_manager: &Rc<RefCell<TrayMenuManager>>,
+) {
+  if WindowDragManager::is_keyboard_hook_stale() {
+    warn!("The keyboard hook has not been called for a while despite input, Windows may have removed the keyboard hooks");
+    WindowDragManager::reinstall_keyboard_hook();
+    tray_menu_manager.borrow().notify_hotkeys_may_have_stopped_working();
+  }
+  if !hotkeys.event_loop.is_finished() {
+    if hotkeys.recovery_attempts > 0 && hotkeys.registered_at.elapsed() >= HOTKEY_RECOVERY_RESET_DELAY {
+      info!(
+        "Hotkeys have been working again for [{}s] after [{}] recovery attempt(s)",
+        HOTKEY_RECOVERY_RESET_DELAY.as_secs(),
+        hotkeys.recovery_attempts.min(MAX_HOTKEY_RECOVERY_ATTEMPTS)
+      );
+      hotkeys.recovery_attempts = 0;
+      hotkeys.lost_hotkeys.clear();
+    }
+    return;
+  }
+  if hotkeys.recovery_attempts > MAX_HOTKEY_RECOVERY_ATTEMPTS {
+    return;
+  }
+  if hotkeys.recovery_attempts == MAX_HOTKEY_RECOVERY_ATTEMPTS {
+    hotkeys.recovery_attempts += 1;
+    tray_menu_manager
+      .borrow()
+      .notify_hotkeys_could_not_be_recovered(&hotkeys.lost_hotkeys);
+    return;
+  }
+  let recovery_attempts = hotkeys.recovery_attempts + 1;
+  warn!("Hotkeys stopped working, re-registering them (attempt [{recovery_attempts}] of [{MAX_HOTKEY_RECOVERY_ATTEMPTS}])");
+  let previously_failed_hotkeys = std::mem::take(&mut hotkeys.failed_hotkeys);
+  let mut lost_hotkeys = std::mem::take(&mut hotkeys.lost_hotkeys);
+  *hotkeys = register_hotkeys(configuration_manager, command_sender, hotkeys.workspace_ids.clone());
+  hotkeys.recovery_attempts = recovery_attempts;
+  let newly_lost_hotkeys = hotkeys
+    .failed_hotkeys
+    .iter()
+    .filter(|hotkey| !previously_failed_hotkeys.contains(hotkey))
+    .cloned()
+    .collect::<Vec<_>>();
+  if !newly_lost_hotkeys.is_empty() {
+    tray_menu_manager
+      .borrow()
+      .notify_hotkeys_could_not_be_recovered(&newly_lost_hotkeys);
+  }
+  lost_hotkeys.extend(newly_lost_hotkeys);
+  hotkeys.lost_hotkeys = lost_hotkeys;
+}
+
+/// Returns the ID of the workspace with the given number, which is the same number as used by its workspace hotkeys.
+fn workspace_id_for_number(
+  numbered_workspace_ids: &[(usize, PersistentWorkspaceId)],
+  number: usize,
+) -> Option<PersistentWorkspaceId> {
+  numbered_workspace_ids
+    .iter()
+    .find(|(workspace_number, _)| *workspace_number == number)
+    .map(|(_, id)| *id)
+}
+
+/// Describes all workspaces, including the number used by their workspace hotkeys and the name taken from their note.
+fn list_workspaces(
+  wm: &Rc<RefCell<WindowManager<RealWindowsApi>>>,
+  numbered_workspace_ids: &[(usize, PersistentWorkspaceId)],
+  workspace_note_manager: &WorkspaceNoteManager,
+) -> Vec<WorkspaceSummary> {
+  let mut workspaces = wm.borrow_mut().list_workspaces();
+  for workspace in workspaces.iter_mut() {
+    workspace.number = numbered_workspace_ids
+      .iter()
+      .find(|(_, id)| id.id_to_string() == workspace.monitor_id && id.workspace == workspace.workspace)
+      .map(|(number, _)| *number);
+  }
+  workspace_note_manager.name_workspaces(&mut workspaces);
+
+  workspaces
+}
+
+/// Re-registers all hotkeys if the ordered workspaces differ from the ones the workspace hotkeys were registered for,
+/// e.g. because a monitor was connected, disconnected, or rearranged, or because a workspace was created or deleted.
+fn reregister_hotkeys_if_workspaces_changed(
+  hotkeys: &mut RegisteredHotkeys,
+  configuration_manager: &Arc<Mutex<ConfigurationProvider>>,
+  command_sender: &Sender<Command>,
+  wm: &Rc<RefCell<WindowManager<RealWindowsApi>>>,
+) {
+  let workspace_ids = wm.borrow_mut().get_ordered_permanent_workspace_ids();
+  if workspace_ids == hotkeys.workspace_ids {
+    return;
+  }
+  info!("Workspaces or their order changed, re-registering hotkeys");
+  hotkeys.interrupt_handle.interrupt();
+  *hotkeys = register_hotkeys(configuration_manager, command_sender, workspace_ids);
+}
+
+/// Logs the outcome of a command unless it succeeded, reports it to whoever is waiting for it (e.g. an IPC client), and
+/// shows errors in the tray if `notify_on_failed_commands` is enabled until the next command succeeds.
+fn report_command_outcome(
+  command_name: &str,
+  outcome: CommandOutcome,
+  outcome_sender: Option<Sender<CommandOutcome>>,
+  configuration_manager: &Arc<Mutex<ConfigurationProvider>>,
+  tray_menu_manager: &Rc<RefCell<TrayMenuManager>>,
+) {
+  match &outcome {
+    CommandOutcome::Success => tray_menu_manager.borrow().clear_command_failed_notification(),
+    CommandOutcome::NoOp(reason) => info!("Ignored command [{command_name}] because {reason}"),
+    CommandOutcome::Error(reason) => {
+      warn!("Failed to execute command [{command_name}] because {reason}");
+      if configuration_manager
+        .lock()
+        .expect(CONFIGURATION_PROVIDER_LOCK)
+        .get_bool(NOTIFY_ON_FAILED_COMMANDS)
+      {
+        tray_menu_manager.borrow().notify_command_failed(command_name, reason);
+      }
+    }
+  }
+  if let Some(outcome_sender) = outcome_sender
+    && outcome_sender.send(outcome).is_err()
+  {
+    warn!("Failed to report outcome of command [{command_name}] because the requester is no longer waiting");
+  }
+}
+
+/// Asks the user, without blocking the main loop, whether the windows parked in the inactive workspaces of a
+/// disconnected monitor should be moved to the primary monitor or remain hidden until the monitor is reconnected.
+fn ask_to_move_parked_windows(monitor_id: [u16; 32], parked_window_count: usize, command_sender: Sender<Command>) {
+  let monitor_name = String::from_utf16_lossy(&monitor_id).trim_end_matches('\0').to_string();
+  std::thread::spawn(move || {
+    let message = Text::MoveParkedWindowsQuestion
+      .localised()
+      .replace("{monitor}", &monitor_name)
+      .replace("{count}", &parked_window_count.to_string());
+    if api::ask_question("Randolf", &message) {
+      command_sender
+        .send(Command::MoveParkedWindowsToPrimaryMonitor(monitor_id))
+        .expect("Failed to send move parked windows command");
+    } else {
+      info!("Keeping windows parked in workspaces of disconnected monitor [{monitor_name}]");
+    }
+  });
+}
+
+/// Logs that the event loop is still alive, together with the number of queued commands and how long it took to
+/// execute the last command, which helps diagnosing sluggish command handling.
+fn log_heartbeat(queue_depth: usize, last_command: &Option<(String, Duration)>) {
+  match last_command {
+    Some((command, latency)) => debug!(
+      "Still listening for events, [{queue_depth}] command(s) queued, last command [{command}] took [{}ms]",
+      latency.as_millis()
+    ),
+    None => debug!("Still listening for events, [{queue_depth}] command(s) queued, no command executed yet"),
+  }
+}
+
+/// Waits for the configured startup delay and then until the Windows shell is ready, so that Randolf does not race the
+/// shell when it is launched automatically after logging in, which can result in a missing tray icon or an incorrect
+/// list of monitors. Gives up waiting for the shell after [`SHELL_READINESS_TIMEOUT`].
+fn wait_until_shell_is_ready(configuration_manager: &Arc<Mutex<ConfigurationProvider>>) {
+  let startup_delay_in_ms = configuration_manager
+    .lock()
+    .expect(CONFIGURATION_PROVIDER_LOCK)
+    .get_i32(STARTUP_DELAY_IN_MS);
+  if startup_delay_in_ms > 0 {
+    info!("Delaying startup by {startup_delay_in_ms} ms");
+    std::thread::sleep(Duration::from_millis(u64::try_from(startup_delay_in_ms).unwrap_or_default()));
+  }
+  let waiting_since = Instant::now();
+  while !api::is_shell_ready() {
+    if waiting_since.elapsed() >= SHELL_READINESS_TIMEOUT {
+      warn!(
+        "Windows shell is not ready after {:?}, continuing anyway",
+        SHELL_READINESS_TIMEOUT
+      );
+      return;
+    }
+    std::thread::sleep(SHELL_READINESS_CHECK_INTERVAL);
+  }
+  if !waiting_since.elapsed().is_zero() {
+    debug!("Waited {:?} for the Windows shell to be ready", waiting_since.elapsed());
   }
 }
 
@@ -202,3 +913,12 @@ fn run_if_due(last_run: &mut Instant, interval: Duration, task: impl FnOnce()) {
   task();
   *last_run = Instant::now();
 }
+
+/// Returns `true` if windows that the user stopped managing at runtime should remain ignored after a restart.
+fn is_persisting_ignored_windows(configuration_manager: &Arc<Mutex<ConfigurationProvider>>) -> bool {
+  configuration_manager
+    .lock()
+    .expect(CONFIGURATION_PROVIDER_LOCK)
+    .get_exclusion_settings()
+    .persist_ignored_windows
+}
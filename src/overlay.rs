@@ -0,0 +1,579 @@
+use crate::api::real_windows_api_for_close_prompt::WindowsApiForClosePrompt;
+use crate::api::real_windows_api_for_focus_border::WindowsApiForFocusBorder;
+use crate::api::real_windows_api_for_place_mode::WindowsApiForPlaceMode;
+use crate::api::real_windows_api_for_window_switcher::WindowsApiForWindowSwitcher;
+use crate::api::real_windows_api_for_workspace_badges::WindowsApiForWorkspaceBadges;
+use crate::common::{Command, Rect, Sizing, WindowHandle, WindowSummary, WorkspaceBadge};
+use crate::configuration_provider::{
+  CLOSE_CONFIRMATION_TIMEOUT_IN_MS, ConfigurationProvider, DELAY_IN_MS_BEFORE_DRAGGING_IS_ALLOWED,
+  WORKSPACE_BADGES_DELAY_IN_MS,
+};
+use crate::localisation::Text;
+use crate::utils::CONFIGURATION_PROVIDER_LOCK;
+use crossbeam_channel::Sender;
+use std::cell::RefCell;
+use std::ffi::c_void;
+use std::iter;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+use windows::Win32::Foundation::{COLORREF, E_INVALIDARG, HINSTANCE, HWND, LPARAM, LRESULT, RECT, WPARAM};
+use windows::Win32::Graphics::Gdi::{BeginPaint, EndPaint, HDC, PAINTSTRUCT};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::WindowsAndMessaging::*;
+use windows::core::{PCWSTR, w};
+
+const WINDOW_CLASS_NAME: PCWSTR = w!("RandolfOverlay");
+/// The message that is posted to the host window to wake an overlay, whose ID is passed as its `WPARAM`.
+const WM_WAKE_OVERLAY: u32 = WM_APP;
+const TIMER_ID: usize = 1;
+
+static HOST_WINDOW: OnceLock<Result<isize, String>> = OnceLock::new();
+static PENDING_OVERLAYS: Mutex<Vec<PendingOverlay>> = Mutex::new(Vec::new());
+static OVERLAY_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+  /// The overlays that were added to the host, indexed by their ID. Only used by the thread that runs the message loop
+  /// of the host, which owns the windows of all overlays.
+  static OVERLAYS: RefCell<Vec<HostedOverlay>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Draws a coloured border around the foreground window, so that it is obvious which window has focus on large or
+/// multi-monitor setups. The border is configured in the `[focus_border]` section of the configuration file, which is
+/// read on every update so that changes take effect with the next change of focus after reloading the configuration.
+/// The border window is only created once the border is enabled.
+pub struct FocusBorderOverlay {
+  configuration_manager: Arc<Mutex<ConfigurationProvider>>,
+  is_border_window_created: bool,
+}
+
+impl FocusBorderOverlay {
+  pub fn new(configuration_manager: Arc<Mutex<ConfigurationProvider>>) -> Self {
+    Self {
+      configuration_manager,
+      is_border_window_created: false,
+    }
+  }
+
+  /// Draws the border around the given window or, if there is none (e.g. because the foreground window is not managed
+  /// by this application) or the border is disabled, hides it.
+  pub fn update(&mut self, window: Option<WindowHandle>) {
+    let settings = self
+      .configuration_manager
+      .lock()
+      .expect(CONFIGURATION_PROVIDER_LOCK)
+      .get_focus_border()
+      .clone();
+    if !settings.enabled {
+      if self.is_border_window_created {
+        WindowsApiForFocusBorder::hide();
+      }
+      return;
+    }
+    if !self.is_border_window_created {
+      self.is_border_window_created = true;
+      if let Err(err) = WindowsApiForFocusBorder::initialise() {
+        error!("Failed to initialise focus border: {err}");
+      }
+    }
+    match window {
+      Some(window) => WindowsApiForFocusBorder::show(window, settings.get_colour(), settings.width_in_px),
+      None => WindowsApiForFocusBorder::hide(),
+    }
+  }
+}
+
+/// Asks the user to press the close hotkey again before a window matching a window rule with `confirm_before_close` is
+/// closed. The prompt is shown over the centre of the window and disappears once the close confirmation timeout has
+/// expired. The prompt window is only created once a prompt is needed.
+pub struct ClosePromptOverlay {
+  configuration_manager: Arc<Mutex<ConfigurationProvider>>,
+  is_prompt_window_created: bool,
+}
+
+impl ClosePromptOverlay {
+  pub fn new(configuration_manager: Arc<Mutex<ConfigurationProvider>>) -> Self {
+    Self {
+      configuration_manager,
+      is_prompt_window_created: false,
+    }
+  }
+
+  /// Shows the prompt over the window that awaits confirmation to close it or, if there is none, hides it.
+  pub fn update(&mut self, window: Option<WindowHandle>) {
+    let Some(window) = window else {
+      if self.is_prompt_window_created {
+        WindowsApiForClosePrompt::hide();
+      }
+      return;
+    };
+    if !self.is_prompt_window_created {
+      self.is_prompt_window_created = true;
+      if let Err(err) = WindowsApiForClosePrompt::initialise() {
+        error!("Failed to initialise close prompt: {err}");
+      }
+    }
+    let timeout = self
+      .configuration_manager
+      .lock()
+      .expect(CONFIGURATION_PROVIDER_LOCK)
+      .get_i32(CLOSE_CONFIRMATION_TIMEOUT_IN_MS);
+    let duration = Duration::from_millis(u64::try_from(timeout).unwrap_or_default());
+    WindowsApiForClosePrompt::show(window, Text::CloseConfirmationPrompt.localised(), duration);
+  }
+}
+
+/// Shows a badge in the centre of each monitor that lists the numbers of its workspaces and highlights the active one
+/// once the Win key has been held for `workspace_badges_delay_in_ms`, so that new users can learn the workspace
+/// hotkeys. The keyboard hook only reports the Win key once it has been held long enough to drag windows, so the badges
+/// never appear before that. The badge windows are only created once badges are needed.
+pub struct WorkspaceBadgesOverlay {
+  configuration_manager: Arc<Mutex<ConfigurationProvider>>,
+  is_badge_window_created: bool,
+  is_shown: bool,
+  due_at: Option<Instant>,
+}
+
+impl WorkspaceBadgesOverlay {
+  pub fn new(configuration_manager: Arc<Mutex<ConfigurationProvider>>) -> Self {
+    Self {
+      configuration_manager,
+      is_badge_window_created: false,
+      is_shown: false,
+      due_at: None,
+    }
+  }
+
+  /// Schedules the badges to be shown once the Win key has been held for the configured delay, given that it has just
+  /// been held long enough to drag windows, or hides them when it is released. Does nothing if the badges are disabled.
+  pub fn set_win_key_held(&mut self, is_held: bool) {
+    if !is_held {
+      self.due_at = None;
+      if self.is_shown {
+        self.is_shown = false;
+        WindowsApiForWorkspaceBadges::hide();
+      }
+      return;
+    }
+    let (badges_delay_in_ms, drag_delay_in_ms) = {
+      let guard = self.configuration_manager.lock().expect(CONFIGURATION_PROVIDER_LOCK);
+      (
+        guard.get_i32(WORKSPACE_BADGES_DELAY_IN_MS),
+        guard.get_i32(DELAY_IN_MS_BEFORE_DRAGGING_IS_ALLOWED),
+      )
+    };
+    if badges_delay_in_ms <= 0 {
+      return;
+    }
+    let remaining_delay_in_ms = u64::try_from(badges_delay_in_ms.saturating_sub(drag_delay_in_ms)).unwrap_or_default();
+    self.due_at = Some(Instant::now() + Duration::from_millis(remaining_delay_in_ms));
+  }
+
+  pub fn is_shown(&self) -> bool {
+    self.is_shown
+  }
+
+  /// Returns `true` if the Win key has been held long enough for the badges to be shown but they are not shown yet.
+  pub fn is_due(&self) -> bool {
+    self.due_at.is_some_and(|due_at| Instant::now() >= due_at)
+  }
+
+  pub fn show(&mut self, badges: Vec<WorkspaceBadge>) {
+    self.due_at = None;
+    if !self.is_badge_window_created {
+      self.is_badge_window_created = true;
+      if let Err(err) = WindowsApiForWorkspaceBadges::initialise() {
+        error!("Failed to initialise workspace badges: {err}");
+      }
+    }
+    self.is_shown = true;
+    WindowsApiForWorkspaceBadges::show(badges);
+  }
+}
+
+/// Lists the managed windows, starting with those of the current workspace, so that the user can switch to any of them
+/// by typing part of its title or process name, which also switches to the workspace of the window if it is hidden.
+/// This is an optional replacement for Alt+Tab, which does not know about workspaces, and has no default hotkey. The
+/// switcher window is only created once it is opened for the first time.
+pub struct WindowSwitcherOverlay {
+  command_sender: Sender<Command>,
+  is_switcher_window_created: bool,
+}
+
+impl WindowSwitcherOverlay {
+  pub fn new(command_sender: Sender<Command>) -> Self {
+    Self {
+      command_sender,
+      is_switcher_window_created: false,
+    }
+  }
+
+  /// Opens the switcher in the centre of the given work area or, if it is open already, highlights the next window, so
+  /// that pressing the hotkey repeatedly cycles through the windows like Alt+Tab does.
+  pub fn open(&mut self, windows: Vec<WindowSummary>, work_area: Rect) {
+    if !self.is_switcher_window_created {
+      self.is_switcher_window_created = true;
+      if let Err(err) = WindowsApiForWindowSwitcher::initialise(self.command_sender.clone()) {
+        error!("Failed to initialise window switcher: {err}");
+      }
+    }
+    if WindowsApiForWindowSwitcher::is_open() {
+      WindowsApiForWindowSwitcher::select_next_window();
+      return;
+    }
+    WindowsApiForWindowSwitcher::open(windows, work_area, Text::WindowSwitcherPlaceholder.localised());
+  }
+}
+
+/// Shows the snap zones of the monitor of the foreground window, each labelled with a number, so that the user can
+/// place the window in one of them using the keyboard alone. Place mode has no default hotkey and its overlay is only
+/// created once place mode is entered for the first time.
+pub struct PlaceModeOverlay {
+  command_sender: Sender<Command>,
+  is_place_mode_window_created: bool,
+}
+
+impl PlaceModeOverlay {
+  pub fn new(command_sender: Sender<Command>) -> Self {
+    Self {
+      command_sender,
+      is_place_mode_window_created: false,
+    }
+  }
+
+  /// Enters place mode for the given window, unless place mode is open already.
+  pub fn open(&mut self, window: WindowHandle, work_area: Rect, zones: Vec<Sizing>) {
+    if !self.is_place_mode_window_created {
+      self.is_place_mode_window_created = true;
+      if let Err(err) = WindowsApiForPlaceMode::initialise(self.command_sender.clone()) {
+        error!("Failed to initialise place mode: {err}");
+      }
+    }
+    if WindowsApiForPlaceMode::is_open() {
+      return;
+    }
+    WindowsApiForPlaceMode::open(window, work_area, zones, Text::PlaceModeHint.localised());
+  }
+}
+
+/// Identifies an overlay that was added to the [`OverlayHost`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OverlayId(usize);
+
+/// How the windows of an overlay are created. Layered windows are drawn with the opacity `alpha`, where `0` is fully
+/// transparent and `255` is opaque.
+#[derive(Debug, Clone, Copy)]
+pub struct OverlayOptions {
+  pub title: &'static str,
+  pub ex_style: WINDOW_EX_STYLE,
+  pub style: WINDOW_STYLE,
+  pub alpha: Option<u8>,
+}
+
+impl OverlayOptions {
+  /// Returns the options of a topmost window that can neither be clicked nor activated, like most overlays.
+  pub fn click_through(title: &'static str, alpha: u8) -> Self {
+    Self {
+      title,
+      ex_style: WS_EX_TOPMOST | WS_EX_TOOLWINDOW | WS_EX_NOACTIVATE | WS_EX_LAYERED | WS_EX_TRANSPARENT,
+      style: WS_POPUP,
+      alpha: Some(alpha),
+    }
+  }
+}
+
+/// The part of an overlay that lives on the thread of the [`OverlayHost`], which owns its windows. The rest of the
+/// application changes what an overlay shows through state shared with it, e.g. a static, and then wakes it with
+/// [`OverlayHost::wake`]. `hwnd` is always the first window of the overlay, which the host creates when it is added.
+pub trait OverlayWindow {
+  /// Brings the windows of the overlay up to date after it was woken. Returns after how long [`OverlayWindow::tick`]
+  /// should be called if the overlay has to keep checking something that it is not woken for, e.g. whether a prompt
+  /// has expired, or `None` to sleep until the overlay is woken again.
+  fn update(&mut self, hwnd: HWND) -> Option<Duration>;
+
+  /// Called once the delay returned by [`OverlayWindow::update`] or the previous tick has passed. Returns the delay
+  /// until the next tick, if any, just like [`OverlayWindow::update`] does.
+  fn tick(&mut self, hwnd: HWND) -> Option<Duration> {
+    self.update(hwnd)
+  }
+
+  /// Paints the window `hwnd` of the overlay, whose client area is `rect`, between `BeginPaint` and `EndPaint`.
+  fn paint(&mut self, hwnd: HWND, hdc: HDC, rect: RECT);
+
+  /// Handles any other message sent to the window `hwnd` of the overlay, e.g. typed input. Returns `None` to leave the
+  /// message to the default window procedure.
+  fn handle_message(&mut self, _hwnd: HWND, _message: u32, _w_param: WPARAM, _l_param: LPARAM) -> Option<LRESULT> {
+    None
+  }
+}
+
+/// An overlay that was added but whose windows have not been created by the host yet.
+struct PendingOverlay {
+  options: OverlayOptions,
+  create: Box<dyn FnOnce() -> Box<dyn OverlayWindow> + Send>,
+}
+
+/// An overlay and its windows. The overlay is taken out while it handles a message, so that messages which Windows
+/// sends to its windows in the meantime (e.g. `WM_ACTIVATE` while it calls `SetForegroundWindow`) cannot borrow it
+/// twice. Such messages are left to the default window procedure instead.
+struct HostedOverlay {
+  options: OverlayOptions,
+  windows: Vec<isize>,
+  overlay: Option<Box<dyn OverlayWindow>>,
+}
+
+/// Runs all overlays, such as the focus border or the window switcher, on a single thread with a single message loop.
+/// Overlays sleep until they are woken by posting a message to the hidden host window, so that they neither poll nor
+/// wake the CPU while nothing changes, and only keep a timer running while they have something to check, e.g. while a
+/// prompt is shown. The thread is started once the first overlay is added.
+pub struct OverlayHost;
+
+impl OverlayHost {
+  /// Adds an overlay whose windows are created with the given options and whose state is created by `create`, both on
+  /// the thread of the host. The overlay is woken once its first window has been created.
+  pub fn add(
+    options: OverlayOptions,
+    create: impl FnOnce() -> Box<dyn OverlayWindow> + Send + 'static,
+  ) -> Result<OverlayId, Box<dyn std::error::Error>> {
+    Self::host_window()?;
+    let id = {
+      let mut pending_overlays = PENDING_OVERLAYS.lock().expect("Failed to lock pending overlays");
+      pending_overlays.push(PendingOverlay {
+        options,
+        create: Box::new(create),
+      });
+      OverlayId(OVERLAY_COUNT.fetch_add(1, Ordering::Relaxed))
+    };
+    Self::wake(id);
+
+    Ok(id)
+  }
+
+  /// Asks the overlay to bring its windows up to date, e.g. after its state was changed by another thread. Returns
+  /// straight away, since the overlay is updated on the thread of the host.
+  pub fn wake(id: OverlayId) {
+    let Some(Ok(hwnd)) = HOST_WINDOW.get() else {
+      return;
+    };
+    unsafe {
+      if let Err(err) = PostMessageW(Some(HWND(*hwnd as *mut c_void)), WM_WAKE_OVERLAY, WPARAM(id.0), LPARAM(0)) {
+        warn!("Failed to wake overlay {}: {err}", id.0);
+      }
+    }
+  }
+
+  /// Creates another window for the overlay that owns `hwnd`, e.g. because it shows one window per monitor. Must only
+  /// be called by the overlay itself.
+  pub fn create_additional_window(hwnd: HWND) -> windows::core::Result<HWND> {
+    let Some((index, options)) = Self::find_overlay(hwnd) else {
+      return Err(E_INVALIDARG.into());
+    };
+    let additional_hwnd = Self::create_window(&options)?;
+    OVERLAYS.with_borrow_mut(|overlays| overlays[index].windows.push(additional_hwnd.0 as isize));
+
+    Ok(additional_hwnd)
+  }
+
+  /// Returns the host window, starting the thread of the host and waiting for it to create the window if necessary.
+  fn host_window() -> Result<HWND, Box<dyn std::error::Error>> {
+    let hwnd = HOST_WINDOW.get_or_init(|| {
+      let (sender, receiver) = crossbeam_channel::bounded(1);
+      thread::Builder::new()
+        .name("overlays".to_string())
+        .spawn(move || Self::create_host_window_and_run_message_loop(sender))
+        .map_err(|err| err.to_string())?;
+      receiver.recv().map_err(|err| err.to_string())?
+    });
+    match hwnd {
+      Ok(hwnd) => Ok(HWND(*hwnd as *mut c_void)),
+      Err(err) => Err(format!("Overlays are disabled because: {err}").into()),
+    }
+  }
+
+  fn create_host_window_and_run_message_loop(sender: Sender<Result<isize, String>>) {
+    let hwnd = Self::register_class_and_create_host_window();
+    let is_created = hwnd.is_ok();
+    if let Err(err) = sender.send(hwnd.map(|hwnd| hwnd.0 as isize).map_err(|err| err.to_string())) {
+      error!("Failed to report overlay host window: {err}");
+    }
+    if !is_created {
+      return;
+    }
+    debug!("Created overlay host window");
+
+    unsafe {
+      let mut message = MSG::default();
+      while GetMessageW(&mut message, None, 0, 0).as_bool() {
+        let _ = TranslateMessage(&message);
+        DispatchMessageW(&message);
+      }
+    }
+  }
+
+  fn register_class_and_create_host_window() -> windows::core::Result<HWND> {
+    unsafe {
+      let h_module = GetModuleHandleW(None)?;
+      let h_instance = HINSTANCE(h_module.0);
+      let window_class = WNDCLASSW {
+        lpfnWndProc: Some(Self::window_procedure),
+        hInstance: h_instance,
+        lpszClassName: WINDOW_CLASS_NAME,
+        ..Default::default()
+      };
+      if RegisterClassW(&window_class) == 0 {
+        return Err(windows::core::Error::from_win32());
+      }
+      // A message-only window is never shown and only receives the messages that wake overlays
+      CreateWindowExW(
+        WINDOW_EX_STYLE::default(),
+        WINDOW_CLASS_NAME,
+        w!("Randolf Overlays"),
+        WINDOW_STYLE::default(),
+        0,
+        0,
+        0,
+        0,
+        Some(HWND_MESSAGE),
+        None,
+        Some(h_instance),
+        None,
+      )
+    }
+  }
+
+  fn create_window(options: &OverlayOptions) -> windows::core::Result<HWND> {
+    let title = options.title.encode_utf16().chain(iter::once(0)).collect::<Vec<u16>>();
+    unsafe {
+      let h_module = GetModuleHandleW(None)?;
+      let hwnd = CreateWindowExW(
+        options.ex_style,
+        WINDOW_CLASS_NAME,
+        PCWSTR(title.as_ptr()),
+        options.style,
+        0,
+        0,
+        0,
+        0,
+        None,
+        None,
+        Some(HINSTANCE(h_module.0)),
+        None,
+      )?;
+      if let Some(alpha) = options.alpha {
+        SetLayeredWindowAttributes(hwnd, COLORREF(0), alpha, LWA_ALPHA)?;
+      }
+
+      Ok(hwnd)
+    }
+  }
+
+  /// Creates the first window of each overlay that was added since the last time, in the order of their IDs. Overlays
+  /// whose window cannot be created are kept without windows, so that the IDs of all other overlays remain valid.
+  fn create_pending_overlays() {
+    let pending_overlays = std::mem::take(&mut *PENDING_OVERLAYS.lock().expect("Failed to lock pending overlays"));
+    for pending_overlay in pending_overlays {
+      let options = pending_overlay.options;
+      let windows = match Self::create_window(&options) {
+        Ok(hwnd) => {
+          debug!("Created {} window", options.title);
+          vec![hwnd.0 as isize]
+        }
+        Err(err) => {
+          error!("{} is disabled because: {err}", options.title);
+          vec![]
+        }
+      };
+      let overlay = Some((pending_overlay.create)());
+      OVERLAYS.with_borrow_mut(|overlays| {
+        overlays.push(HostedOverlay {
+          options,
+          windows,
+          overlay,
+        })
+      });
+    }
+  }
+
+  fn find_overlay(hwnd: HWND) -> Option<(usize, OverlayOptions)> {
+    OVERLAYS.with_borrow(|overlays| {
+      overlays
+        .iter()
+        .position(|overlay| overlay.windows.contains(&(hwnd.0 as isize)))
+        .map(|index| (index, overlays[index].options))
+    })
+  }
+
+  /// Calls `f` with the overlay and its first window, unless the overlay has no windows or is handling another message.
+  fn with_overlay<T>(index: usize, f: impl FnOnce(&mut dyn OverlayWindow, HWND) -> T) -> Option<T> {
+    let (mut overlay, hwnd) = OVERLAYS.with_borrow_mut(|overlays| {
+      let hosted_overlay = overlays.get_mut(index)?;
+      let hwnd = *hosted_overlay.windows.first()?;
+      Some((hosted_overlay.overlay.take()?, HWND(hwnd as *mut c_void)))
+    })?;
+    let result = f(overlay.as_mut(), hwnd);
+    OVERLAYS.with_borrow_mut(|overlays| overlays[index].overlay = Some(overlay));
+
+    Some(result)
+  }
+
+  /// Updates or ticks the overlay and then sets or kills the timer of its first window, depending on whether it asked
+  /// to be ticked.
+  fn update(index: usize, is_tick: bool) {
+    let Some((hwnd, delay)) = Self::with_overlay(index, |overlay, hwnd| {
+      let delay = match is_tick {
+        true => overlay.tick(hwnd),
+        false => overlay.update(hwnd),
+      };
+      (hwnd, delay)
+    }) else {
+      return;
+    };
+    unsafe {
+      match delay {
+        Some(delay) => {
+          let delay_in_ms = u32::try_from(delay.as_millis()).unwrap_or(u32::MAX);
+          if SetTimer(Some(hwnd), TIMER_ID, delay_in_ms, None) == 0 {
+            warn!("Failed to set timer of overlay {index}");
+          }
+        }
+        None => {
+          let _ = KillTimer(Some(hwnd), TIMER_ID);
+        }
+      }
+    }
+  }
+
+  extern "system" fn window_procedure(hwnd: HWND, message: u32, w_param: WPARAM, l_param: LPARAM) -> LRESULT {
+    match message {
+      WM_WAKE_OVERLAY => {
+        Self::create_pending_overlays();
+        Self::update(w_param.0, false);
+        LRESULT(0)
+      }
+      WM_TIMER => {
+        if let Some((index, _)) = Self::find_overlay(hwnd) {
+          Self::update(index, true);
+        }
+        LRESULT(0)
+      }
+      WM_PAINT => unsafe {
+        let mut paint_struct = PAINTSTRUCT::default();
+        let hdc = BeginPaint(hwnd, &mut paint_struct);
+        let mut rect = RECT::default();
+        let _ = GetClientRect(hwnd, &mut rect);
+        if let Some((index, _)) = Self::find_overlay(hwnd) {
+          Self::with_overlay(index, |overlay, _| overlay.paint(hwnd, hdc, rect));
+        }
+        let _ = EndPaint(hwnd, &paint_struct);
+        LRESULT(0)
+      },
+      _ => Self::find_overlay(hwnd)
+        .and_then(|(index, _)| {
+          Self::with_overlay(index, |overlay, _| overlay.handle_message(hwnd, message, w_param, l_param))
+        })
+        .flatten()
+        .unwrap_or_else(|| unsafe { DefWindowProcW(hwnd, message, w_param, l_param) }),
+    }
+  }
+}
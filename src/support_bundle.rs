@@ -0,0 +1,169 @@
+use crate::files::{FileManager, FileType, ZipArchive};
+use crate::utils::LOG_FILE_NAME;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SECRET_KEY_FRAGMENTS: [&str; 5] = ["password", "secret", "token", "api_key", "credential"];
+const REDACTED_VALUE: &str = "\"<redacted>\"";
+const REDACTED_TEXT: &str = "<redacted>";
+const USER_PROFILE_PLACEHOLDER: &str = "%USERPROFILE%";
+const MAXIMUM_LOG_LINES: usize = 5_000;
+
+/// Bundles the most recent log lines, a dump of the current state, the configuration, and the monitor topology into a
+/// single zip file in the data folder, so that users can attach it to bug reports. Secrets and the user's profile
+/// path are stripped from the configuration before it is added. Unless window titles are included, all quoted text is
+/// redacted from the log lines, because that is how window titles are logged.
+pub struct SupportBundle {
+  state: String,
+  monitors: String,
+  configuration_path: PathBuf,
+  include_window_titles: bool,
+}
+
+impl SupportBundle {
+  pub fn new(state: String, monitors: String, configuration_path: &Path, include_window_titles: bool) -> Self {
+    Self {
+      state,
+      monitors,
+      configuration_path: configuration_path.to_path_buf(),
+      include_window_titles,
+    }
+  }
+
+  /// Writes the support bundle to the data folder and returns its path.
+  pub fn write_to_data_folder(&self) -> Result<PathBuf, Box<dyn Error>> {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let path = FileManager::<String>::get_path_to_file(&format!("randolf-support-bundle-{timestamp}.zip"), FileType::Data)?;
+    let mut archive = ZipArchive::default();
+    archive.add("version.txt", env!("CARGO_PKG_VERSION"));
+    archive.add("state.txt", self.state.as_str());
+    archive.add("monitors.txt", self.monitors.as_str());
+    archive.add("randolf.toml", self.read_configuration());
+    archive.add(LOG_FILE_NAME, self.read_recent_logs());
+    archive.write(&path)?;
+
+    Ok(path)
+  }
+
+  fn read_configuration(&self) -> String {
+    match fs::read_to_string(&self.configuration_path) {
+      Ok(configuration) => strip_secrets(&configuration, std::env::var("USERPROFILE").ok().as_deref()),
+      Err(err) => format!("# Failed to read [{}]: {err}", self.configuration_path.display()),
+    }
+  }
+
+  fn read_recent_logs(&self) -> String {
+    let log_file = FileManager::<String>::get_path_to_file(LOG_FILE_NAME, FileType::Data)
+      .and_then(|path| fs::read_to_string(path).map_err(Into::into));
+    match log_file {
+      Ok(logs) => {
+        let lines = logs.lines().collect::<Vec<_>>();
+        let recent_lines = &lines[lines.len().saturating_sub(MAXIMUM_LOG_LINES)..];
+        match self.include_window_titles {
+          true => recent_lines.join("\n"),
+          false => recent_lines
+            .iter()
+            .map(|line| redact_quoted_text(line))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        }
+      }
+      Err(err) => format!("No log file available (only release builds write one): {err}"),
+    }
+  }
+}
+
+/// Returns the configuration with the values of all keys that look like they hold a secret redacted and with the
+/// user's profile path, which usually contains their name, replaced by a placeholder.
+fn strip_secrets(configuration: &str, user_profile: Option<&str>) -> String {
+  configuration
+    .lines()
+    .map(|line| match line.split_once('=') {
+      Some((key, _))
+        if SECRET_KEY_FRAGMENTS
+          .iter()
+          .any(|fragment| key.to_lowercase().contains(fragment)) =>
+      {
+        format!("{key}= {REDACTED_VALUE}")
+      }
+      _ => line.to_string(),
+    })
+    .map(|line| match user_profile {
+      Some(user_profile) if !user_profile.is_empty() => line
+        .replace(user_profile, USER_PROFILE_PLACEHOLDER)
+        .replace(&user_profile.replace('\\', "\\\\"), USER_PROFILE_PLACEHOLDER),
+      _ => line,
+    })
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+/// Returns the log line with the text between each pair of double quotes redacted, since window titles are always
+/// logged in double quotes, e.g. `Moved 0x1234 "Inbox - Outlook" to workspace [2]`.
+fn redact_quoted_text(line: &str) -> String {
+  line
+    .split('"')
+    .enumerate()
+    .map(|(index, part)| match index % 2 == 1 && !part.is_empty() {
+      true => REDACTED_TEXT,
+      false => part,
+    })
+    .collect::<Vec<_>>()
+    .join("\"")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn strip_secrets_redacts_values_of_secret_keys() {
+    let configuration = "window_margin = 20\napi_token = \"abc\"\nProxyPassword = \"123\"";
+
+    let stripped = strip_secrets(configuration, None);
+
+    assert_eq!(
+      stripped,
+      "window_margin = 20\napi_token = \"<redacted>\"\nProxyPassword = \"<redacted>\""
+    );
+  }
+
+  #[test]
+  fn strip_secrets_replaces_user_profile_in_paths() {
+    let configuration = "path = \"C:\\\\Users\\\\kim\\\\AppData\\\\Local\\\\app.exe\"";
+
+    let stripped = strip_secrets(configuration, Some("C:\\Users\\kim"));
+
+    assert_eq!(stripped, "path = \"%USERPROFILE%\\\\AppData\\\\Local\\\\app.exe\"");
+  }
+
+  #[test]
+  fn redact_quoted_text_redacts_window_titles_in_log_lines() {
+    let line = "Moved 0x1234 \"Inbox - Outlook\" to workspace [2] after \"\" and \"Notes\"";
+
+    let redacted = redact_quoted_text(line);
+
+    assert_eq!(
+      redacted,
+      "Moved 0x1234 \"<redacted>\" to workspace [2] after \"\" and \"<redacted>\""
+    );
+  }
+
+  #[test]
+  fn redact_quoted_text_keeps_lines_without_quotes_unchanged() {
+    let line = "Switched to workspace [wsp#P_DISPLAY1-2]";
+
+    assert_eq!(redact_quoted_text(line), line);
+  }
+
+  #[test]
+  fn strip_secrets_keeps_configuration_without_secrets_unchanged() {
+    let configuration = "[general]\nwindow_margin = 20\n\n[[hotkey]]\nname = \"Browser\"";
+
+    let stripped = strip_secrets(configuration, Some(""));
+
+    assert_eq!(stripped, configuration);
+  }
+}
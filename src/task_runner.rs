@@ -0,0 +1,65 @@
+use crate::common::Command;
+use crossbeam_channel::Sender;
+use std::thread;
+
+/// Runs slow operations, such as waiting for a launched application to become ready, on a worker thread so that they
+/// never block the main loop. A task can post its result back to the main loop as a [`Command`], which is then
+/// executed like any other command.
+#[derive(Clone)]
+pub struct TaskRunner {
+  command_sender: Sender<Command>,
+}
+
+impl TaskRunner {
+  pub fn new(command_sender: Sender<Command>) -> Self {
+    Self { command_sender }
+  }
+
+  /// Runs the task on a new, named worker thread and sends the command it returns, if any, to the main loop.
+  pub fn spawn<F>(&self, name: &str, task: F)
+  where
+    F: FnOnce() -> Option<Command> + Send + 'static,
+  {
+    let command_sender = self.command_sender.clone();
+    let task_name = name.to_string();
+    let result = thread::Builder::new().name(name.to_string()).spawn(move || {
+      trace!("Running task [{task_name}]");
+      if let Some(command) = task()
+        && let Err(err) = command_sender.send(command)
+      {
+        warn!("Failed to post result of task [{task_name}] to main loop: {err}");
+      }
+    });
+    if let Err(err) = result {
+      error!("Failed to spawn worker thread for task [{name}]: {err}");
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crossbeam_channel::unbounded;
+  use std::time::Duration;
+
+  #[test]
+  fn spawn_posts_command_returned_by_task_to_main_loop() {
+    let (sender, receiver) = unbounded();
+    let task_runner = TaskRunner::new(sender);
+
+    task_runner.spawn("test-task", || Some(Command::MinimiseWindow));
+
+    let command = receiver.recv_timeout(Duration::from_secs(1));
+    assert!(matches!(command, Ok(Command::MinimiseWindow)));
+  }
+
+  #[test]
+  fn spawn_does_not_post_anything_when_task_returns_none() {
+    let (sender, receiver) = unbounded();
+    let task_runner = TaskRunner::new(sender);
+
+    task_runner.spawn("test-task", || None);
+
+    assert!(receiver.recv_timeout(Duration::from_millis(100)).is_err());
+  }
+}
@@ -0,0 +1,60 @@
+use crate::api::real_windows_api_for_gestures::WindowsApiForGestures;
+use crate::common::{Command, HookHealth};
+use crate::configuration_provider::{ConfigurationProvider, ENABLE_TOUCHPAD_GESTURES};
+use crate::utils::CONFIGURATION_PROVIDER_LOCK;
+use crossbeam_channel::Sender;
+use std::sync::{Arc, Mutex};
+
+pub struct TouchpadGestureManager {
+  api: Option<WindowsApiForGestures>,
+}
+
+impl TouchpadGestureManager {
+  pub fn new(configuration_provider: Arc<Mutex<ConfigurationProvider>>, sender: Sender<Command>) -> Self {
+    let is_enabled = match configuration_provider.try_lock() {
+      Ok(guard) => guard.get_bool(ENABLE_TOUCHPAD_GESTURES),
+      Err(err) => {
+        error!(
+          "Touchpad gestures are disabled because: {} with error: {}",
+          CONFIGURATION_PROVIDER_LOCK, err
+        );
+
+        return Self { api: None };
+      }
+    };
+    match is_enabled {
+      true => Self {
+        api: Some(WindowsApiForGestures::new(sender)),
+      },
+      false => Self { api: None },
+    }
+  }
+
+  pub fn initialise(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(api) = &mut self.api {
+      api.initialise()
+    } else {
+      Ok(())
+    }
+  }
+
+  pub fn hook_health() -> HookHealth {
+    WindowsApiForGestures::hook_health()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crossbeam_channel::unbounded;
+
+  #[test]
+  fn touchpad_gesture_manager_is_disabled_by_default() {
+    let (sender, _receiver) = unbounded();
+    let configuration_provider = Arc::new(Mutex::new(ConfigurationProvider::default()));
+    let mut manager = TouchpadGestureManager::new(configuration_provider, sender);
+
+    assert!(manager.initialise().is_ok());
+    assert!(manager.api.is_none());
+  }
+}
@@ -1,23 +1,109 @@
-use crate::api::get_all_monitors;
-use crate::common::{Command, PersistentWorkspaceId};
+use crate::api::{
+  get_all_monitors, get_high_contrast_colours, get_small_icon_size_in_px, render_text_coverage, show_message,
+};
+use crate::common::{Command, IconBitmap, PersistentWorkspaceId};
 use crate::configuration_provider::{
-  ALLOW_SELECTING_SAME_CENTER_WINDOWS, ConfigurationProvider, FORCE_USING_ADMIN_PRIVILEGES, Layout, WINDOW_MARGIN,
+  ALLOW_SELECTING_SAME_CENTER_WINDOWS, ConfigurationProvider, ENABLE_USAGE_METRICS, FORCE_USING_ADMIN_PRIVILEGES, Layout,
+  WINDOW_MARGIN,
 };
+use crate::localisation::Text;
 use crate::utils::{CONFIGURATION_PROVIDER_LOCK, TRAY_ICON_LOCK, TRAY_ICON_OPEN};
+use crate::window_inspector::WindowInspector;
 use crossbeam_channel::{Receiver, Sender, unbounded};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, AtomicU8};
 use std::sync::{Arc, Mutex, MutexGuard};
 use std::thread;
+use std::time::{Duration, Instant};
 use trayicon::*;
 
 static WORKSPACE: AtomicU8 = AtomicU8::new(1);
 static IS_DRAG_ICON_SHOWN: AtomicBool = AtomicBool::new(false);
+const ICON_SIZE_IN_PX: usize = 32;
+const BADGE_FONT_HEIGHT_IN_PX: i32 = 18;
+const BADGE_PADDING_IN_PX: usize = 1;
+/// The colours of the workspace number badge in the byte order of the icon's pixels, i.e. blue, green, red.
+const BADGE_BACKGROUND_COLOUR: [u8; 3] = [47, 43, 109];
+const BADGE_TEXT_COLOUR: [u8; 3] = [203, 215, 233];
+const APPLICATION_ICON: &[u8] = include_bytes!("../assets/randolf.ico");
+const DRAG_ICON: &[u8] = include_bytes!("../assets/randolf-drag.ico");
+const DEFAULT_TOOLTIP: &str = "Randolf";
+const NOTIFICATION_DURATION: Duration = Duration::from_secs(60);
 
 pub struct TrayMenuManager {
   configuration_provider: Arc<Mutex<ConfigurationProvider>>,
   menu: Option<Arc<Mutex<TrayIcon<Event>>>>,
+  icon_style: TrayIconStyle,
   workspace_tray_icons: Vec<Icon>,
+  /// Icons for workspace numbers without a bundled icon, generated when first needed.
+  generated_workspace_tray_icons: RefCell<HashMap<usize, Icon>>,
   drag_icon: Icon,
+  /// The notification currently shown via the tooltip of the tray icon, if any.
+  notification: Cell<Option<Notification>>,
+}
+
+/// A notification shown via the tooltip of the tray icon, which the tray icon keeps until it is replaced. Since the
+/// tray icon does not support balloon notifications, the default tooltip is restored after [`NOTIFICATION_DURATION`]
+/// so that an outdated notification does not linger.
+#[derive(Copy, Clone, Debug)]
+struct Notification {
+  shown_at: Instant,
+  /// Whether the notification is about a failed command and becomes outdated as soon as a command succeeds.
+  is_cleared_by_successful_command: bool,
+}
+
+/// The size and colours of the tray icons, which are generated from the bundled 32x32 icons to match the system DPI and,
+/// if high contrast mode is on, the colours of the high contrast theme.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+struct TrayIconStyle {
+  size_in_px: usize,
+  /// The text and background colours of the high contrast theme, if high contrast mode is on.
+  high_contrast_colours: Option<([u8; 3], [u8; 3])>,
+}
+
+impl TrayIconStyle {
+  fn current() -> Self {
+    Self {
+      size_in_px: usize::try_from(get_small_icon_size_in_px())
+        .ok()
+        .filter(|size| *size > 0)
+        .unwrap_or(ICON_SIZE_IN_PX),
+      high_contrast_colours: get_high_contrast_colours(),
+    }
+  }
+
+  /// Creates an icon in this style from a 32x32 ICO file, such as the bundled icons. Returns `None` if the ICO file has
+  /// an unsupported format.
+  fn create_icon(&self, ico: &[u8]) -> Option<Icon> {
+    let mut bitmap = IconBitmap::from_ico(ico)?.scaled(self.size_in_px);
+    if let Some((light, dark)) = self.high_contrast_colours {
+      bitmap = bitmap.with_high_contrast(light, dark);
+    }
+    let size = bitmap.size();
+
+    // The tray icon requires a static buffer, which is acceptable because icons are only created again if the style
+    // changes, e.g. when high contrast mode is turned on or off
+    match Icon::from_buffer(
+      Box::leak(bitmap.to_ico().into_boxed_slice()),
+      Some(size as _),
+      Some(size as _),
+    ) {
+      Ok(icon) => Some(icon),
+      Err(err) => {
+        error!("Failed to create tray icon in style {self:?}: {err}");
+        None
+      }
+    }
+  }
+
+  /// Creates an icon in this style from a bundled icon or, if that fails, uses the bundled icon as it is.
+  fn create_bundled_icon(&self, ico: &'static [u8]) -> Icon {
+    self
+      .create_icon(ico)
+      .unwrap_or_else(|| Icon::from_buffer(ico, Some(32), Some(32)).expect("Failed to create icon from buffer"))
+  }
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
@@ -31,7 +117,21 @@ enum Event {
   SetDefaultLayout(Layout),
   ToggleSelectingSameCenterWindows,
   ToggleForceUsingAdminPrivileges,
+  ToggleUsageMetrics,
+  ShowUsageMetrics,
   LogMonitorLayout,
+  ToggleWindowInspector,
+  CreateSupportBundle(bool),
+  EnableTraceLogging,
+  CreateWorkspace,
+  DeleteWorkspace,
+  RestoreAllHiddenWindows,
+  ExportWorkspaceProfile,
+  ImportWorkspaceProfile,
+  SaveSession,
+  RestoreSession,
+  Reinitialise,
+  ReloadConfiguration,
   RestartRandolf(bool),
   OpenRandolfExecutableFolder,
   OpenRandolfConfigFolder,
@@ -40,12 +140,15 @@ enum Event {
 
 impl TrayMenuManager {
   fn new(configuration_provider: Arc<Mutex<ConfigurationProvider>>) -> Self {
+    let icon_style = TrayIconStyle::current();
     Self {
       configuration_provider,
       menu: None,
-      workspace_tray_icons: vec![],
-      drag_icon: Icon::from_buffer(include_bytes!("../assets/randolf-drag.ico"), Some(32), Some(32))
-        .expect("Failed to create drag icon from buffer"),
+      icon_style,
+      workspace_tray_icons: Self::create_workspace_icons(icon_style),
+      generated_workspace_tray_icons: RefCell::new(HashMap::new()),
+      drag_icon: icon_style.create_bundled_icon(DRAG_ICON),
+      notification: Cell::new(None),
     }
   }
 
@@ -58,14 +161,19 @@ impl TrayMenuManager {
     let tray = manager.create_tray_icon(tray_event_sender);
     manager.menu = Some(Arc::from(Mutex::new(tray)));
     manager.initialise(tray_event_receiver, command_sender);
-    manager.workspace_tray_icons = (1..=9).map(Self::create_icon).collect();
     debug!("Created tray icon & menu");
 
     manager
   }
 
-  fn create_icon(index: u8) -> Icon {
-    let icon_data = match index {
+  fn create_workspace_icons(icon_style: TrayIconStyle) -> Vec<Icon> {
+    (1..=9)
+      .map(|index| icon_style.create_bundled_icon(Self::bundled_workspace_icon(index)))
+      .collect()
+  }
+
+  fn bundled_workspace_icon(index: u8) -> &'static [u8] {
+    match index {
       1 => include_bytes!("../assets/randolf-1.ico"),
       2 => include_bytes!("../assets/randolf-2.ico"),
       3 => include_bytes!("../assets/randolf-3.ico"),
@@ -76,9 +184,37 @@ impl TrayMenuManager {
       8 => include_bytes!("../assets/randolf-8.ico"),
       9 => include_bytes!("../assets/randolf-9.ico"),
       _ => panic!("Invalid icon index"),
+    }
+  }
+
+  /// Generates an icon showing the workspace number in a badge in the bottom-right corner of the application icon.
+  fn generate_icon(&self, workspace: usize) -> Option<Icon> {
+    let coverage = render_text_coverage(&workspace.to_string(), BADGE_FONT_HEIGHT_IN_PX, ICON_SIZE_IN_PX as i32)?;
+    let Some(icon_data) = draw_badge_onto_icon(APPLICATION_ICON, &coverage) else {
+      warn!("Failed to draw badge for workspace [{workspace}] onto tray icon");
+      return None;
     };
+    let icon = self.icon_style.create_icon(&icon_data)?;
+    debug!("Generated tray icon for workspace [{workspace}]");
+
+    Some(icon)
+  }
 
-    Icon::from_buffer(icon_data, Some(32), Some(32)).expect("Failed to create icon from buffer")
+  /// Calls `f` with the tray icon for the workspace number, which is either one of the bundled icons or an icon that is
+  /// generated once and then cached. Returns `None` if no icon could be generated.
+  fn with_workspace_icon<R>(&self, workspace: usize, f: impl FnOnce(&Icon) -> R) -> Option<R> {
+    if let Some(icon) = workspace
+      .checked_sub(1)
+      .and_then(|index| self.workspace_tray_icons.get(index))
+    {
+      return Some(f(icon));
+    }
+    let mut generated_icons = self.generated_workspace_tray_icons.borrow_mut();
+    if !generated_icons.contains_key(&workspace) {
+      generated_icons.insert(workspace, self.generate_icon(workspace)?);
+    }
+
+    generated_icons.get(&workspace).map(f)
   }
 
   fn create_tray_icon(&mut self, tx: Sender<Event>) -> TrayIcon<Event> {
@@ -86,8 +222,8 @@ impl TrayMenuManager {
       .sender(move |e| {
         let _ = tx.send(*e);
       })
-      .icon_from_buffer(include_bytes!("../assets/randolf.ico"))
-      .tooltip("Randolf")
+      .icon(self.icon_style.create_bundled_icon(APPLICATION_ICON))
+      .tooltip(DEFAULT_TOOLTIP)
       .on_right_click(Event::RightClickTrayIcon)
       .on_click(Event::LeftClickTrayIcon)
       .on_double_click(Event::DoubleClickTrayIcon)
@@ -115,6 +251,28 @@ impl TrayMenuManager {
           get_all_monitors().print_layout();
           info!("Logged monitor layout");
         }
+        Event::ToggleWindowInspector => {
+          let is_enabled = WindowInspector::is_enabled();
+          if let Err(result) = tray_icon
+            .lock()
+            .expect(TRAY_ICON_LOCK)
+            .set_menu_item_checkable(Event::ToggleWindowInspector, !is_enabled)
+          {
+            error!("Failed to toggle menu item: {result}");
+          }
+          WindowInspector::set_enabled(!is_enabled);
+          debug!("Set [{:?}] to [{}]", Event::ToggleWindowInspector, !is_enabled);
+        }
+        Event::CreateSupportBundle(include_window_titles) => {
+          command_sender
+            .send(Command::CreateSupportBundle(include_window_titles))
+            .expect("Failed to send create support bundle command");
+        }
+        Event::EnableTraceLogging => {
+          command_sender
+            .send(Command::EnableTraceLogging)
+            .expect("Failed to send enable trace logging command");
+        }
         Event::SetMargin(margin) => {
           let current_margin = { unlocked_config_provider(&config_provider).get_i32(WINDOW_MARGIN) };
           if current_margin != margin {
@@ -163,6 +321,67 @@ impl TrayMenuManager {
           config.set_bool(FORCE_USING_ADMIN_PRIVILEGES, !is_enabled);
           debug!("Set [{:?}] to [{}]", Event::ToggleForceUsingAdminPrivileges, !is_enabled);
         }
+        Event::ToggleUsageMetrics => {
+          let mut config = unlocked_config_provider(&config_provider);
+          let is_enabled = config.get_bool(ENABLE_USAGE_METRICS);
+          if let Err(result) = tray_icon
+            .lock()
+            .expect(TRAY_ICON_LOCK)
+            .set_menu_item_checkable(Event::ToggleUsageMetrics, !is_enabled)
+          {
+            error!("Failed to toggle menu item: {result}");
+          }
+          config.set_bool(ENABLE_USAGE_METRICS, !is_enabled);
+          debug!("Set [{:?}] to [{}]", Event::ToggleUsageMetrics, !is_enabled);
+          command_sender
+            .send(Command::SetUsageMetricsRecording(!is_enabled))
+            .expect("Failed to send set usage metrics recording command");
+        }
+        Event::ShowUsageMetrics => {
+          command_sender
+            .send(Command::ShowUsageMetrics)
+            .expect("Failed to send show usage metrics command");
+        }
+        Event::CreateWorkspace => {
+          command_sender
+            .send(Command::CreateWorkspace)
+            .expect("Failed to send create workspace command");
+        }
+        Event::DeleteWorkspace => {
+          command_sender
+            .send(Command::DeleteWorkspace)
+            .expect("Failed to send delete workspace command");
+        }
+        Event::RestoreAllHiddenWindows => {
+          command_sender
+            .send(Command::RestoreAllHiddenWindows)
+            .expect("Failed to send restore all hidden windows command");
+        }
+        Event::ExportWorkspaceProfile => {
+          command_sender
+            .send(Command::ExportWorkspaceProfile)
+            .expect("Failed to send export workspace profile command");
+        }
+        Event::ImportWorkspaceProfile => {
+          command_sender
+            .send(Command::ImportWorkspaceProfile)
+            .expect("Failed to send import workspace profile command");
+        }
+        Event::SaveSession => {
+          command_sender
+            .send(Command::SaveSession)
+            .expect("Failed to send save session command");
+        }
+        Event::RestoreSession => {
+          command_sender
+            .send(Command::RestoreSession(true))
+            .expect("Failed to send restore session command");
+        }
+        Event::Reinitialise => {
+          command_sender
+            .send(Command::Reinitialise)
+            .expect("Failed to send reinitialise command");
+        }
         Event::OpenRandolfExecutableFolder => {
           command_sender
             .send(Command::OpenRandolfExecutableFolder)
@@ -178,6 +397,13 @@ impl TrayMenuManager {
             .send(Command::OpenRandolfDataFolder)
             .expect("Failed to send open randolf data folder command");
         }
+        Event::ReloadConfiguration => {
+          unlocked_config_provider(&config_provider).reload_configuration();
+          let menu = build_menu(&config_provider);
+          if let Err(err) = tray_icon.lock().expect(TRAY_ICON_LOCK).set_menu(&menu) {
+            error!("Failed to set menu: {err}");
+          }
+        }
         Event::RestartRandolf(as_admin) => {
           let mut config = unlocked_config_provider(&config_provider);
           config.reload_configuration();
@@ -199,51 +425,228 @@ impl TrayMenuManager {
     if !workspace_id.is_on_primary_monitor() {
       return;
     }
-    if workspace_id.workspace > self.workspace_tray_icons.len() {
+    let Ok(workspace) = u8::try_from(workspace_id.workspace) else {
       error!(
-        "Workspace ID [{}] is out of bounds for tray icons (max: [{}]) - ignoring request",
-        workspace_id.workspace,
-        self.workspace_tray_icons.len()
+        "Workspace ID [{}] is out of bounds for tray icons - ignoring request",
+        workspace_id.workspace
       );
       return;
-    }
-    WORKSPACE.store(workspace_id.workspace as u8, std::sync::atomic::Ordering::Relaxed);
+    };
+    WORKSPACE.store(workspace, std::sync::atomic::Ordering::Relaxed);
     if IS_DRAG_ICON_SHOWN.load(std::sync::atomic::Ordering::Relaxed) {
       debug!("Not updating tray icon because window drag icon is shown");
       return;
     }
-    let icon = &self.workspace_tray_icons[workspace_id.workspace - 1];
     let tray_icon = Arc::clone(self.menu.as_ref().unwrap());
-    if let Err(err) = tray_icon.lock().expect(TRAY_ICON_LOCK).set_icon(icon) {
-      error!("Failed to set workspace tray icon to [{}]: {err}", workspace_id.workspace);
-    } else {
-      debug!(
-        "Set tray icon [{}/{}] to reflect active workspace on primary monitor",
-        workspace_id.workspace,
-        self.workspace_tray_icons.len()
-      );
+    match self.with_workspace_icon(workspace_id.workspace, |icon| {
+      tray_icon.lock().expect(TRAY_ICON_LOCK).set_icon(icon)
+    }) {
+      Some(Ok(())) => debug!(
+        "Set tray icon [{}] to reflect active workspace on primary monitor",
+        workspace_id.workspace
+      ),
+      Some(Err(err)) => error!("Failed to set workspace tray icon to [{}]: {err}", workspace_id.workspace),
+      None => error!(
+        "Failed to create tray icon for workspace [{}] - ignoring request",
+        workspace_id.workspace
+      ),
     }
   }
 
   pub fn set_window_drag_icon(&self, is_enabled: bool) {
     let tray_icon = Arc::clone(self.menu.as_ref().unwrap());
-    let icon = if is_enabled {
-      IS_DRAG_ICON_SHOWN.store(true, std::sync::atomic::Ordering::Relaxed);
-
-      &self.drag_icon
+    let set_icon = |icon: &Icon| tray_icon.lock().expect(TRAY_ICON_LOCK).set_icon(icon);
+    IS_DRAG_ICON_SHOWN.store(is_enabled, std::sync::atomic::Ordering::Relaxed);
+    let result = if is_enabled {
+      Some(set_icon(&self.drag_icon))
     } else {
-      IS_DRAG_ICON_SHOWN.store(false, std::sync::atomic::Ordering::Relaxed);
-
-      &self.workspace_tray_icons[WORKSPACE.load(std::sync::atomic::Ordering::Relaxed) as usize - 1]
+      self.with_workspace_icon(WORKSPACE.load(std::sync::atomic::Ordering::Relaxed) as usize, set_icon)
     };
-    if let Err(err) = tray_icon.lock().expect(TRAY_ICON_LOCK).set_icon(icon) {
-      error!("Failed to set window drag tray icon to [{is_enabled}]: {err}");
+    match result {
+      Some(Ok(())) => debug!("Set window drag icon to [{}]", is_enabled),
+      Some(Err(err)) => error!("Failed to set window drag tray icon to [{is_enabled}]: {err}"),
+      None => error!("Failed to set window drag tray icon to [{is_enabled}] because no workspace icon is available"),
+    }
+  }
+
+  /// Creates the tray icons again if the system DPI changed or high contrast mode was turned on or off since they were
+  /// created, and shows the icon that reflects the current state again.
+  pub fn refresh_icon_style(&mut self) {
+    let icon_style = TrayIconStyle::current();
+    if icon_style == self.icon_style {
+      return;
+    }
+    info!("Tray icon style changed to {icon_style:?}, creating tray icons again");
+    self.icon_style = icon_style;
+    self.workspace_tray_icons = Self::create_workspace_icons(icon_style);
+    self.generated_workspace_tray_icons.borrow_mut().clear();
+    self.drag_icon = icon_style.create_bundled_icon(DRAG_ICON);
+    self.set_window_drag_icon(IS_DRAG_ICON_SHOWN.load(std::sync::atomic::Ordering::Relaxed));
+  }
+
+  /// Informs the user that the workspaces file was corrupt and has been replaced, which means that windows hidden by a
+  /// previous session may still be hidden.
+  pub fn notify_workspaces_file_was_reset(&self, backup_path: &Path) {
+    warn!(
+      "The workspaces file was corrupt and has been reset; windows hidden before may need to be restored manually, \
+      the corrupt file was kept at [{}]",
+      backup_path.display()
+    );
+    self.show_notification(Text::WorkspacesFileResetTooltip.localised(), false);
+  }
+
+  /// Informs the user that the window they tried to interact with cannot be managed because it belongs to an elevated
+  /// process, while this application is not running with admin privileges.
+  pub fn notify_window_requires_admin(&self) {
+    warn!("Ignoring command because the foreground window is elevated; run Randolf as admin to manage this window");
+    self.show_notification(Text::AdminRequiredTooltip.localised(), false);
+  }
+
+  /// Informs the user why a command failed via the tooltip of the tray icon until the next command succeeds or the
+  /// notification expires. Expected to be called only if `notify_on_failed_commands` is enabled, after the failure has
+  /// been logged.
+  pub fn notify_command_failed(&self, command: &str, reason: &str) {
+    let tooltip = Text::CommandFailedTooltip
+      .localised()
+      .replace("{command}", command)
+      .replace("{reason}", reason);
+    self.show_notification(&tooltip, true);
+  }
+
+  /// Restores the default tooltip of the tray icon once a command succeeded, if it still shows why an earlier command
+  /// failed.
+  pub fn clear_command_failed_notification(&self) {
+    if self
+      .notification
+      .get()
+      .is_some_and(|notification| notification.is_cleared_by_successful_command)
+    {
+      self.clear_notification();
+    }
+  }
+
+  /// Restores the default tooltip of the tray icon if a notification has been shown for longer than
+  /// [`NOTIFICATION_DURATION`].
+  pub fn clear_expired_notification(&self) {
+    if self
+      .notification
+      .get()
+      .is_some_and(|notification| notification.shown_at.elapsed() >= NOTIFICATION_DURATION)
+    {
+      self.clear_notification();
+    }
+  }
+
+  fn show_notification(&self, text: &str, is_cleared_by_successful_command: bool) {
+    self.set_tooltip(text);
+    self.notification.set(Some(Notification {
+      shown_at: Instant::now(),
+      is_cleared_by_successful_command,
+    }));
+  }
+
+  fn clear_notification(&self) {
+    self.notification.set(None);
+    self.set_tooltip(DEFAULT_TOOLTIP);
+  }
+
+  fn set_tooltip(&self, text: &str) {
+    let tray_icon = Arc::clone(self.menu.as_ref().unwrap());
+    if let Err(err) = tray_icon.lock().expect(TRAY_ICON_LOCK).set_tooltip(text) {
+      error!("Failed to set tray icon tooltip: {err}");
+    }
+  }
+
+  /// Informs the user via the tooltip of the tray icon that hotkeys may have stopped working because Windows appears to
+  /// have removed the keyboard hooks, which the log file explains in more detail.
+  pub fn notify_hotkeys_may_have_stopped_working(&self) {
+    let tray_icon = Arc::clone(self.menu.as_ref().unwrap());
+    if let Err(err) = tray_icon
+      .lock()
+      .expect(TRAY_ICON_LOCK)
+      .set_tooltip(Text::HotkeysLostTooltip.localised())
+    {
+      error!("Failed to set tray icon tooltip: {err}");
+    }
+  }
+
+  /// Informs the user that hotkeys stopped working and could not be recovered by registering them again, listing the
+  /// key combinations that failed to register, if any. Unlike other notifications, this also shows a message box
+  /// because the user would otherwise only notice that pressing hotkeys no longer does anything.
+  pub fn notify_hotkeys_could_not_be_recovered(&self, failed_hotkeys: &[String]) {
+    let text = if failed_hotkeys.is_empty() {
+      Text::HotkeysLostMessage
     } else {
-      debug!("Set window drag icon to [{}]", is_enabled);
+      Text::HotkeysNotRegisteredMessage
+    };
+    let hotkeys = failed_hotkeys.join("\n");
+    let english_message = text.english().replace("{hotkeys}", &hotkeys);
+    warn!("{}", english_message.replace("\n\n", " ").replace('\n', ", "));
+    let message = text.localised().replace("{hotkeys}", &hotkeys);
+    let tray_icon = Arc::clone(self.menu.as_ref().unwrap());
+    if let Err(err) = tray_icon
+      .lock()
+      .expect(TRAY_ICON_LOCK)
+      .set_tooltip(Text::HotkeysLostTooltip.localised())
+    {
+      error!("Failed to set tray icon tooltip: {err}");
     }
+    thread::spawn(move || show_message("Randolf", &message));
   }
 }
 
+/// Draws a badge showing the text from the coverage mask (see [`render_text_coverage`]) onto a copy of a 32x32 ICO file
+/// with 24-bit colours and a transparency mask, such as the bundled icons. The badge covers the area of the text plus
+/// padding and is opaque, so that the text is legible regardless of the icon underneath. Returns `None` if the icon
+/// has a different format or the coverage mask contains no text.
+fn draw_badge_onto_icon(base_icon: &[u8], coverage: &[u8]) -> Option<Vec<u8>> {
+  let read_u32 =
+    |offset: usize| -> Option<u32> { Some(u32::from_le_bytes(base_icon.get(offset..offset + 4)?.try_into().ok()?)) };
+  let image_offset = read_u32(18)? as usize;
+  let header_size = read_u32(image_offset)? as usize;
+  let width = read_u32(image_offset + 4)? as usize;
+  let bit_count = u16::from_le_bytes(base_icon.get(image_offset + 14..image_offset + 16)?.try_into().ok()?);
+  if width != ICON_SIZE_IN_PX || bit_count != 24 || coverage.len() != ICON_SIZE_IN_PX * ICON_SIZE_IN_PX {
+    return None;
+  }
+  let pixels_offset = image_offset + header_size;
+  let pixel_row_length = ICON_SIZE_IN_PX * 3;
+  let mask_offset = pixels_offset + pixel_row_length * ICON_SIZE_IN_PX;
+  let mask_row_length = ICON_SIZE_IN_PX / 8;
+  if base_icon.len() < mask_offset + mask_row_length * ICON_SIZE_IN_PX {
+    return None;
+  }
+
+  let covered_pixels = coverage
+    .iter()
+    .enumerate()
+    .filter(|(_, alpha)| **alpha > 0)
+    .map(|(index, _)| index);
+  let (min_x, max_x, min_y, max_y) = covered_pixels.fold(None, |bounds, index| {
+    let (x, y) = (index % ICON_SIZE_IN_PX, index / ICON_SIZE_IN_PX);
+    Some(match bounds {
+      None => (x, x, y, y),
+      Some((min_x, max_x, min_y, max_y)) => (min_x.min(x), max_x.max(x), min_y.min(y), max_y.max(y)),
+    })
+  })?;
+  let mut icon = base_icon.to_vec();
+  for y in min_y.saturating_sub(BADGE_PADDING_IN_PX)..=(max_y + BADGE_PADDING_IN_PX).min(ICON_SIZE_IN_PX - 1) {
+    // Rows are stored from the bottom up
+    let row = ICON_SIZE_IN_PX - 1 - y;
+    for x in min_x.saturating_sub(BADGE_PADDING_IN_PX)..=(max_x + BADGE_PADDING_IN_PX).min(ICON_SIZE_IN_PX - 1) {
+      let alpha = coverage[y * ICON_SIZE_IN_PX + x] as u32;
+      let pixel = pixels_offset + row * pixel_row_length + x * 3;
+      let colours = BADGE_BACKGROUND_COLOUR.iter().zip(BADGE_TEXT_COLOUR.iter());
+      for (channel, (background, text)) in icon[pixel..pixel + 3].iter_mut().zip(colours) {
+        *channel = ((*background as u32 * (255 - alpha) + *text as u32 * alpha) / 255) as u8;
+      }
+      icon[mask_offset + row * mask_row_length + x / 8] &= !(0x80 >> (x % 8));
+    }
+  }
+
+  Some(icon)
+}
+
 fn unlocked_config_provider(config_provider: &Arc<Mutex<ConfigurationProvider>>) -> MutexGuard<'_, ConfigurationProvider> {
   config_provider.lock().expect(CONFIGURATION_PROVIDER_LOCK)
 }
@@ -253,6 +656,7 @@ fn build_menu(config_provider: &Arc<Mutex<ConfigurationProvider>>) -> MenuBuilde
   let current_margin: i32 = config.get_i32(WINDOW_MARGIN);
   let current_layout = config.get_default_layout();
   let icon_bytes = include_bytes!("../assets/randolf.ico");
+  let default_margin_label = Text::DefaultWindowMargin.localised().replace("{margin}", "20");
 
   MenuBuilder::new()
     .with(MenuItem::Item {
@@ -263,17 +667,29 @@ fn build_menu(config_provider: &Arc<Mutex<ConfigurationProvider>>) -> MenuBuilde
     })
     .separator()
     .submenu(
-      "Explore debug settings",
-      MenuBuilder::new().item("Print monitor layout to log file", Event::LogMonitorLayout),
+      Text::ExploreDebugSettings.localised(),
+      MenuBuilder::new()
+        .item(Text::PrintMonitorLayout.localised(), Event::LogMonitorLayout)
+        .checkable(
+          Text::InspectWindowUnderCursor.localised(),
+          WindowInspector::is_enabled(),
+          Event::ToggleWindowInspector,
+        )
+        .item(Text::LogTraceMessages.localised(), Event::EnableTraceLogging)
+        .item(Text::CreateSupportBundle.localised(), Event::CreateSupportBundle(false))
+        .item(
+          Text::CreateSupportBundleWithWindowTitles.localised(),
+          Event::CreateSupportBundle(true),
+        ),
     )
     .separator()
     .submenu(
-      "Set window margin to...",
+      Text::SetWindowMargin.localised(),
       MenuBuilder::new()
         .checkable("0 px", 0 == current_margin, Event::SetMargin(0))
         .checkable("10 px", 10 == current_margin, Event::SetMargin(10))
         .checkable("15 px", 15 == current_margin, Event::SetMargin(15))
-        .checkable("20 px (default)", 20 == current_margin, Event::SetMargin(20))
+        .checkable(&default_margin_label, 20 == current_margin, Event::SetMargin(20))
         .checkable("30 px", 30 == current_margin, Event::SetMargin(30))
         .checkable("40 px", 40 == current_margin, Event::SetMargin(40))
         .checkable("50 px", 50 == current_margin, Event::SetMargin(50))
@@ -281,36 +697,56 @@ fn build_menu(config_provider: &Arc<Mutex<ConfigurationProvider>>) -> MenuBuilde
         .checkable("100 px", 100 == current_margin, Event::SetMargin(100))
         .checkable("150 px", 150 == current_margin, Event::SetMargin(150)),
     )
-    .submenu("Set default layout...", build_default_layout_menu(current_layout))
+    .submenu(Text::SetDefaultLayout.localised(), build_default_layout_menu(current_layout))
+    .separator()
+    .item(Text::CreateWorkspace.localised(), Event::CreateWorkspace)
+    .item(Text::DeleteWorkspace.localised(), Event::DeleteWorkspace)
+    .item(Text::RestoreAllHiddenWindows.localised(), Event::RestoreAllHiddenWindows)
+    .item(Text::ExportWorkspaceProfile.localised(), Event::ExportWorkspaceProfile)
+    .item(Text::ImportWorkspaceProfile.localised(), Event::ImportWorkspaceProfile)
+    .item(Text::SaveSession.localised(), Event::SaveSession)
+    .item(Text::RestoreSession.localised(), Event::RestoreSession)
     .separator()
     .checkable(
-      "Allow selecting same center windows",
+      Text::AllowSelectingSameCenterWindows.localised(),
       config.get_bool(ALLOW_SELECTING_SAME_CENTER_WINDOWS),
       Event::ToggleSelectingSameCenterWindows,
     )
     .checkable(
-      "Force using admin privileges",
+      Text::ForceUsingAdminPrivileges.localised(),
       config.get_bool(FORCE_USING_ADMIN_PRIVILEGES),
       Event::ToggleForceUsingAdminPrivileges,
     )
+    .submenu(
+      Text::UsageMetrics.localised(),
+      MenuBuilder::new()
+        .checkable(
+          Text::RecordUsageMetrics.localised(),
+          config.get_bool(ENABLE_USAGE_METRICS),
+          Event::ToggleUsageMetrics,
+        )
+        .item(Text::ShowUsageMetrics.localised(), Event::ShowUsageMetrics),
+    )
     .separator()
-    .item("Open executable folder", Event::OpenRandolfExecutableFolder)
-    .item("Open config folder", Event::OpenRandolfConfigFolder)
-    .item("Open data folder", Event::OpenRandolfDataFolder)
-    .item("Restart with admin privileges", Event::RestartRandolf(true))
-    .item("Restart", Event::RestartRandolf(false))
-    .item("Exit (restores any hidden windows)", Event::Exit)
+    .item(Text::OpenExecutableFolder.localised(), Event::OpenRandolfExecutableFolder)
+    .item(Text::OpenConfigFolder.localised(), Event::OpenRandolfConfigFolder)
+    .item(Text::OpenDataFolder.localised(), Event::OpenRandolfDataFolder)
+    .item(Text::ReloadConfiguration.localised(), Event::ReloadConfiguration)
+    .item(Text::Reinitialise.localised(), Event::Reinitialise)
+    .item(Text::RestartWithAdminPrivileges.localised(), Event::RestartRandolf(true))
+    .item(Text::Restart.localised(), Event::RestartRandolf(false))
+    .item(Text::Exit.localised(), Event::Exit)
 }
 
 fn build_default_layout_menu(current_layout: Layout) -> MenuBuilder<Event> {
   MenuBuilder::new()
     .checkable(
-      "Spatial",
+      Text::SpatialLayout.localised(),
       current_layout == Layout::Spatial,
       Event::SetDefaultLayout(Layout::Spatial),
     )
     .checkable(
-      "Scrolling",
+      Text::ScrollingLayout.localised(),
       current_layout == Layout::Scrolling,
       Event::SetDefaultLayout(Layout::Scrolling),
     )
@@ -354,9 +790,8 @@ mod test {
       assert_eq!(
         captured_logs[1].body,
         format!(
-          "Set tray icon [{}/{}] to reflect active workspace on primary monitor",
-          workspace_id.workspace,
-          manager.workspace_tray_icons.len()
+          "Set tray icon [{}] to reflect active workspace on primary monitor",
+          workspace_id.workspace
         )
       );
     });
@@ -382,6 +817,29 @@ mod test {
     assert_eq!(WORKSPACE.load(std::sync::atomic::Ordering::Relaxed), 1);
   }
 
+  #[test]
+  #[serial]
+  fn update_tray_icon_generates_and_caches_icon_for_workspace_without_bundled_icon() {
+    reset();
+    testing_logger::setup();
+    let configuration_provider = Arc::new(Mutex::new(ConfigurationProvider::default()));
+    let manager = TrayMenuManager::new_initialised(configuration_provider, unbounded().0);
+
+    manager.update_tray_icon(PersistentWorkspaceId::new_test(12));
+    manager.update_tray_icon(PersistentWorkspaceId::new_test(12));
+
+    testing_logger::validate(|captured_logs| {
+      assert_eq!(captured_logs.len(), 4);
+      assert_eq!(captured_logs[1].body, "Generated tray icon for workspace [12]");
+      assert_eq!(
+        captured_logs[3].body,
+        "Set tray icon [12] to reflect active workspace on primary monitor"
+      );
+    });
+    assert_eq!(manager.generated_workspace_tray_icons.borrow().len(), 1);
+    assert_eq!(WORKSPACE.load(std::sync::atomic::Ordering::Relaxed), 12);
+  }
+
   #[test]
   #[serial]
   fn update_tray_icon_ignores_request_when_index_out_of_bounds() {
@@ -390,18 +848,62 @@ mod test {
     let configuration_provider = Arc::new(Mutex::new(ConfigurationProvider::default()));
     let manager = TrayMenuManager::new_initialised(configuration_provider, unbounded().0);
 
-    manager.update_tray_icon(PersistentWorkspaceId::new_test(123));
+    manager.update_tray_icon(PersistentWorkspaceId::new_test(1234));
 
     testing_logger::validate(|captured_logs| {
       assert_eq!(captured_logs.len(), 2);
       assert_eq!(
         captured_logs[1].body,
-        "Workspace ID [123] is out of bounds for tray icons (max: [9]) - ignoring request"
+        "Workspace ID [1234] is out of bounds for tray icons - ignoring request"
       );
     });
     assert_eq!(WORKSPACE.load(std::sync::atomic::Ordering::Relaxed), 1);
   }
 
+  #[test]
+  fn draw_badge_onto_icon_draws_opaque_badge_around_text() {
+    let base_icon = include_bytes!("../assets/randolf.ico");
+    let mut coverage = vec![0; ICON_SIZE_IN_PX * ICON_SIZE_IN_PX];
+    coverage[29 * ICON_SIZE_IN_PX + 29] = 255;
+    coverage[29 * ICON_SIZE_IN_PX + 30] = 255;
+
+    let icon = draw_badge_onto_icon(base_icon, &coverage).expect("Failed to draw badge");
+
+    let pixels_offset = 22 + 40;
+    let mask_offset = pixels_offset + ICON_SIZE_IN_PX * ICON_SIZE_IN_PX * 3;
+    let pixel = |x: usize, y: usize| {
+      let index = pixels_offset + ((ICON_SIZE_IN_PX - 1 - y) * ICON_SIZE_IN_PX + x) * 3;
+      &icon[index..index + 3]
+    };
+    let is_transparent =
+      |x: usize, y: usize| icon[mask_offset + (ICON_SIZE_IN_PX - 1 - y) * 4 + x / 8] & (0x80 >> (x % 8)) != 0;
+    assert_eq!(icon.len(), base_icon.len());
+    assert_eq!(pixel(29, 29), BADGE_TEXT_COLOUR);
+    assert_eq!(pixel(28, 30), BADGE_BACKGROUND_COLOUR);
+    assert!(!is_transparent(31, 30));
+    assert!(is_transparent(27, 29));
+    assert!(is_transparent(0, 0));
+  }
+
+  #[test]
+  fn tray_icon_style_creates_icons_in_any_size_and_in_high_contrast() {
+    let style = TrayIconStyle {
+      size_in_px: 24,
+      high_contrast_colours: Some(([255, 255, 255], [0, 0, 0])),
+    };
+
+    assert!(style.create_icon(APPLICATION_ICON).is_some());
+    assert!(style.create_icon(&[0; 10]).is_none());
+  }
+
+  #[test]
+  fn draw_badge_onto_icon_returns_none_without_text() {
+    let coverage = vec![0; ICON_SIZE_IN_PX * ICON_SIZE_IN_PX];
+
+    assert!(draw_badge_onto_icon(include_bytes!("../assets/randolf.ico"), &coverage).is_none());
+    assert!(draw_badge_onto_icon(&[0; 10], &[255; 4]).is_none());
+  }
+
   #[test]
   #[serial]
   fn update_tray_icon_does_not_change_if_drag_icon_shown() {
@@ -497,24 +999,24 @@ mod test {
   }
 
   #[test]
-  fn create_icon_creates_icon() {
-    let icon = TrayMenuManager::create_icon(4);
+  fn bundled_workspace_icon_returns_icon_for_index() {
     assert_eq!(
-      icon,
-      Icon::from_buffer(include_bytes!("../assets/randolf-4.ico"), Some(32), Some(32),).unwrap()
+      TrayMenuManager::bundled_workspace_icon(4),
+      &include_bytes!("../assets/randolf-4.ico")[..]
     );
   }
 
   #[test]
   #[should_panic(expected = "Invalid icon index")]
-  fn create_icon_panics_for_invalid_index() {
-    TrayMenuManager::create_icon(10);
+  fn bundled_workspace_icon_panics_for_invalid_index() {
+    TrayMenuManager::bundled_workspace_icon(10);
   }
 
   #[test]
-  fn create_icon_creates_different_icons_for_different_indices() {
-    let icon1 = TrayMenuManager::create_icon(1);
-    let icon2 = TrayMenuManager::create_icon(2);
-    assert_ne!(icon1, icon2);
+  fn create_workspace_icons_creates_different_icons_for_different_indices() {
+    let icons = TrayMenuManager::create_workspace_icons(TrayIconStyle::current());
+
+    assert_eq!(icons.len(), 9);
+    assert_ne!(icons[0], icons[1]);
   }
 }
@@ -0,0 +1,170 @@
+use crate::common::CommandOutcome;
+use crate::configuration_provider::{ConfigurationProvider, ENABLE_USAGE_METRICS};
+use crate::files::{FileManager, FileType, UsageMetricsFile};
+use crate::localisation::Text;
+use crate::utils::CONFIGURATION_PROVIDER_LOCK;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const USAGE_METRICS_FILE_NAME: &str = "usage_metrics.toml";
+const USAGE_METRICS_FILE_SAVE_DELAY: Duration = Duration::from_secs(5);
+const USAGE_METRICS_FILE_PREFIX: &str = "# This file is automatically generated and updated by Randolf.\n\
+  # It counts how often you have used each feature and is never sent anywhere.\n\
+  # You can disable recording via the tray menu and delete this file at any time.\n\n";
+
+/// Records how often each command is used, if the user has opted in via `enable_usage_metrics`. The metrics are only
+/// stored in the data folder and can be viewed via the tray menu. Since the metrics change with almost every command,
+/// the file is written at most once per [`USAGE_METRICS_FILE_SAVE_DELAY`] and must be flushed before exiting.
+pub struct UsageMetricsManager {
+  is_enabled: bool,
+  file_manager: FileManager<UsageMetricsFile>,
+  usage_metrics: Option<UsageMetricsFile>,
+}
+
+impl UsageMetricsManager {
+  pub fn new(configuration_provider: Arc<Mutex<ConfigurationProvider>>) -> Self {
+    let (is_enabled, data_file_format) = {
+      let guard = configuration_provider.lock().expect(CONFIGURATION_PROVIDER_LOCK);
+      (guard.get_bool(ENABLE_USAGE_METRICS), guard.get_data_file_format())
+    };
+    let mut file_manager = FileManager::new(USAGE_METRICS_FILE_NAME, FileType::Data);
+    file_manager.set_content_prefix(USAGE_METRICS_FILE_PREFIX);
+    file_manager.set_format(data_file_format);
+    file_manager.set_save_delay(USAGE_METRICS_FILE_SAVE_DELAY);
+    Self::new_with(is_enabled, file_manager)
+  }
+
+  fn new_with(is_enabled: bool, file_manager: FileManager<UsageMetricsFile>) -> Self {
+    Self {
+      is_enabled,
+      file_manager,
+      usage_metrics: None,
+    }
+  }
+
+  /// Enables or disables recording, e.g. after the user toggled it via the tray menu.
+  pub fn set_enabled(&mut self, is_enabled: bool) {
+    self.is_enabled = is_enabled;
+  }
+
+  /// Counts the command with the given usage metric name (see [`crate::common::Command::usage_metric_name`]), if
+  /// recording usage metrics is enabled and the command succeeded. Commands that were ignored, e.g. because the
+  /// foreground window paused Randolf, or that failed are not counted, since the user did not actually use the feature.
+  pub fn record(&mut self, usage_metric_name: Option<&str>, outcome: &CommandOutcome) {
+    let Some(name) = usage_metric_name else {
+      return;
+    };
+    if !self.is_enabled || !outcome.is_success() {
+      return;
+    }
+    let file_manager = &self.file_manager;
+    self
+      .usage_metrics
+      .get_or_insert_with(|| Self::load(file_manager))
+      .increment(file_manager, name);
+  }
+
+  /// Returns a human-readable summary of the recorded usage metrics.
+  pub fn summary(&mut self) -> String {
+    if !self.is_enabled {
+      let usage_metrics = match &self.usage_metrics {
+        Some(usage_metrics) => usage_metrics.to_string(),
+        None if self.file_manager.get_path().exists() => Self::load(&self.file_manager).to_string(),
+        None => UsageMetricsFile::default().to_string(),
+      };
+      return format!("{}\n\n{usage_metrics}", Text::UsageMetricsRecordingDisabled.localised());
+    }
+    let file_manager = &self.file_manager;
+    self.usage_metrics.get_or_insert_with(|| Self::load(file_manager)).to_string()
+  }
+
+  /// Writes any recorded usage metrics that have not been saved yet. Must be called before the application exits.
+  pub fn flush(&self) {
+    self.file_manager.flush();
+  }
+
+  /// Loads the usage metrics file only once it is needed, so that no file is created for users who have not opted in.
+  fn load(file_manager: &FileManager<UsageMetricsFile>) -> UsageMetricsFile {
+    match file_manager.load_or_create() {
+      Ok((usage_metrics, _)) => usage_metrics,
+      Err(err) => {
+        error!("Failed to load usage metrics, starting from scratch: {err}");
+        UsageMetricsFile::default()
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::common::{Command, Direction};
+  use crate::utils::create_temp_directory;
+
+  fn usage_metrics_manager(is_enabled: bool, path: std::path::PathBuf) -> UsageMetricsManager {
+    UsageMetricsManager::new_with(is_enabled, FileManager::new_test(path))
+  }
+
+  fn record(manager: &mut UsageMetricsManager, command: Command, outcome: CommandOutcome) {
+    manager.record(command.usage_metric_name(), &outcome);
+  }
+
+  #[test]
+  fn record_counts_user_commands_when_enabled() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(USAGE_METRICS_FILE_NAME);
+    let mut manager = usage_metrics_manager(true, path.clone());
+
+    record(&mut manager, Command::MoveWindow(Direction::Left), CommandOutcome::Success);
+    record(&mut manager, Command::MoveWindow(Direction::Right), CommandOutcome::Success);
+    record(&mut manager, Command::LaunchedApplicationReady, CommandOutcome::Success);
+
+    assert_eq!(manager.summary(), "Move window: 2");
+    assert!(path.exists());
+  }
+
+  #[test]
+  fn record_skips_commands_that_were_ignored_or_failed() {
+    let directory = create_temp_directory();
+    let mut manager = usage_metrics_manager(true, directory.path().join(USAGE_METRICS_FILE_NAME));
+
+    record(&mut manager, Command::CloseWindow, CommandOutcome::Success);
+    record(
+      &mut manager,
+      Command::MoveWindow(Direction::Left),
+      CommandOutcome::no_op("the foreground window matches a window rule that pauses Randolf"),
+    );
+    record(
+      &mut manager,
+      Command::CreateWorkspace,
+      CommandOutcome::error("maximum number of workspaces reached"),
+    );
+
+    assert_eq!(manager.summary(), "Close window: 1");
+  }
+
+  #[test]
+  fn record_does_not_create_file_when_disabled() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(USAGE_METRICS_FILE_NAME);
+    let mut manager = usage_metrics_manager(false, path.clone());
+
+    record(&mut manager, Command::CloseWindow, CommandOutcome::Success);
+
+    assert!(!path.exists());
+  }
+
+  #[test]
+  fn summary_explains_when_recording_is_disabled() {
+    let directory = create_temp_directory();
+    let mut manager = usage_metrics_manager(false, directory.path().join(USAGE_METRICS_FILE_NAME));
+
+    let summary = manager.summary();
+
+    assert!(!directory.path().join(USAGE_METRICS_FILE_NAME).exists());
+    assert_eq!(
+      summary,
+      "Recording usage metrics is currently disabled.\n\nNo commands recorded yet"
+    );
+  }
+}
@@ -1,8 +1,14 @@
 pub const CONFIGURATION_PROVIDER_LOCK: &str = "Failed to acquire lock for configuration provider";
 pub const TRAY_ICON_LOCK: &str = "Failed to acquire lock for tray icon";
+pub const EXCLUSION_SETTINGS_LOCK: &str = "Failed to acquire lock for exclusion settings";
+pub const MOUSE_BINDINGS_LOCK: &str = "Failed to acquire lock for mouse bindings";
+pub const FREE_FLOATING_MONITORS_LOCK: &str = "Failed to acquire lock for free-floating monitors";
+pub const IGNORED_WINDOWS_LOCK: &str = "Failed to acquire lock for ignored windows";
 pub const TRAY_ICON_OPEN: &str = "Failed to open tray menu";
 pub const PROJECT_DIR_QUALIFIER: &str = "io";
 pub const PROJECT_DIR_ORGANISATION_NAME: &str = "kimgoetzke";
 pub const PROJECT_DIR_APPLICATION_NAME: &str = "randolf";
+pub const LOG_FILE_NAME: &str = "randolf.log";
 pub const MINIMUM_WINDOW_MARGIN: i32 = 5;
 pub const MINIMUM_WINDOW_DIMENSION: i32 = 250;
+pub const MAXIMUM_WORKSPACE_COUNT: usize = 9;
@@ -1,18 +1,27 @@
 use crate::api::real_windows_api_for_dragging::WindowsApiForDragging;
-use crate::common::Command;
+use crate::common::{Command, HookHealth};
 use crate::configuration_provider::{
-  ConfigurationProvider, DELAY_IN_MS_BEFORE_DRAGGING_IS_ALLOWED, ENABLE_FEATURES_USING_MOUSE,
+  ConfigurationProvider, DELAY_IN_MS_BEFORE_DRAGGING_IS_ALLOWED, ENABLE_FEATURES_USING_MOUSE, GRID_SNAPPING_CELL_SIZE_IN_PX,
+  MAGNETIC_EDGE_DISTANCE_IN_PX, SNAP_ZONE_DISTANCE_IN_PX, WINDOW_MARGIN,
 };
 use crate::utils::CONFIGURATION_PROVIDER_LOCK;
 use crossbeam_channel::Sender;
-use std::sync::{Arc, Mutex};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, RwLock};
 
 pub struct WindowDragManager {
   api: Option<WindowsApiForDragging>,
 }
 
 impl WindowDragManager {
-  pub fn new(configuration_provider: Arc<Mutex<ConfigurationProvider>>, sender: Sender<Command>) -> Self {
+  /// Creates the manager. `free_floating_monitors` are the monitors whose active workspace is free-floating (see
+  /// [`crate::window_manager::WindowManager::get_shared_free_floating_monitors`]), on which windows are neither snapped
+  /// to the grid, nor attracted to edges, nor snapped into snap zones.
+  pub fn new(
+    configuration_provider: Arc<Mutex<ConfigurationProvider>>,
+    sender: Sender<Command>,
+    free_floating_monitors: Arc<RwLock<HashSet<[u16; 32]>>>,
+  ) -> Self {
     let guard = match configuration_provider.try_lock() {
       Ok(guard) => guard,
       Err(err) => {
@@ -26,9 +35,23 @@ impl WindowDragManager {
     };
     let is_enabled = guard.get_bool(ENABLE_FEATURES_USING_MOUSE);
     let delay_in_ms = guard.get_i32(DELAY_IN_MS_BEFORE_DRAGGING_IS_ALLOWED) as u32;
+    let grid_cell_size_in_px = guard.get_i32(GRID_SNAPPING_CELL_SIZE_IN_PX);
+    let magnetic_edge_distance_in_px = guard.get_i32(MAGNETIC_EDGE_DISTANCE_IN_PX);
+    let snap_zone_distance_in_px = guard.get_i32(SNAP_ZONE_DISTANCE_IN_PX);
+    let window_margin = guard.get_i32(WINDOW_MARGIN);
+    let mouse_bindings = guard.get_shared_mouse_bindings();
     match is_enabled {
       true => Self {
-        api: Some(WindowsApiForDragging::new(sender, delay_in_ms)),
+        api: Some(WindowsApiForDragging::new(
+          sender,
+          delay_in_ms,
+          grid_cell_size_in_px,
+          magnetic_edge_distance_in_px,
+          snap_zone_distance_in_px,
+          window_margin,
+          mouse_bindings,
+          free_floating_monitors,
+        )),
       },
       false => Self { api: None },
     }
@@ -41,6 +64,21 @@ impl WindowDragManager {
       Ok(())
     }
   }
+
+  pub fn hook_health() -> HookHealth {
+    WindowsApiForDragging::hook_health()
+  }
+
+  /// Returns `true` if the keyboard hook has not been called for a while even though the user provided input since,
+  /// which means that Windows most likely removed it silently, e.g. after the system resumed from sleep.
+  pub fn is_keyboard_hook_stale() -> bool {
+    WindowsApiForDragging::is_keyboard_hook_stale()
+  }
+
+  /// Installs the keyboard hook again. Must be called on the thread that initialised this manager.
+  pub fn reinstall_keyboard_hook() {
+    WindowsApiForDragging::reinstall_keyboard_hook()
+  }
 }
 
 #[cfg(test)]
@@ -54,7 +92,7 @@ mod tests {
   fn window_drag_manager_initialises_with_enabled_feature() {
     let (sender, _receiver) = unbounded();
     let configuration_provider = Arc::new(Mutex::new(ConfigurationProvider::default()));
-    let mut manager = WindowDragManager::new(configuration_provider, sender);
+    let mut manager = WindowDragManager::new(configuration_provider, sender, Default::default());
 
     assert!(manager.initialise().is_ok());
     assert!(manager.api.is_some());
@@ -68,7 +106,7 @@ mod tests {
       .lock()
       .expect("Failed to lock configuration provider")
       .set_bool(ENABLE_FEATURES_USING_MOUSE, false);
-    let mut manager = WindowDragManager::new(configuration_provider, sender);
+    let mut manager = WindowDragManager::new(configuration_provider, sender, Default::default());
 
     assert!(manager.initialise().is_ok());
     assert!(manager.api.is_none());
@@ -87,7 +125,7 @@ mod tests {
       }
     });
 
-    let mut manager = WindowDragManager::new(configuration_provider_clone, sender);
+    let mut manager = WindowDragManager::new(configuration_provider_clone, sender, Default::default());
 
     assert!(manager.initialise().is_ok());
     assert!(manager.api.is_none());
@@ -0,0 +1,25 @@
+use crate::api::real_windows_api_for_window_events::WindowsApiForWindowEvents;
+use crate::common::{Command, HookHealth};
+use crossbeam_channel::Sender;
+
+/// Listens to window events raised by other processes, e.g. when Windows Snap or FancyZones moves a window or when a
+/// window changes its title, and turns them into commands for the main loop.
+pub struct WindowEventManager {
+  api: WindowsApiForWindowEvents,
+}
+
+impl WindowEventManager {
+  pub fn new(sender: Sender<Command>) -> Self {
+    Self {
+      api: WindowsApiForWindowEvents::new(sender),
+    }
+  }
+
+  pub fn initialise(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+    self.api.initialise()
+  }
+
+  pub fn hook_health() -> HookHealth {
+    WindowsApiForWindowEvents::hook_health()
+  }
+}
@@ -0,0 +1,28 @@
+use crate::api::RealWindowsApi;
+use crate::api::real_windows_api_for_inspector::WindowsApiForInspector;
+
+/// Shows details of the window under the cursor while `Ctrl` + `Alt` is held, once enabled via the tray menu. Intended
+/// for debugging exclusion rules interactively, which is why it is not persisted in the configuration.
+pub struct WindowInspector {
+  api: WindowsApiForInspector,
+}
+
+impl WindowInspector {
+  pub fn new(windows_api: RealWindowsApi) -> Self {
+    Self {
+      api: WindowsApiForInspector::new(windows_api),
+    }
+  }
+
+  pub fn initialise(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+    self.api.initialise()
+  }
+
+  pub fn is_enabled() -> bool {
+    WindowsApiForInspector::is_enabled()
+  }
+
+  pub fn set_enabled(is_enabled: bool) {
+    WindowsApiForInspector::set_enabled(is_enabled);
+  }
+}
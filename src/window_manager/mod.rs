@@ -1,8 +1,10 @@
+mod monocle_mode;
 mod navigation;
 mod scrolling_layout;
 mod spatial_layout;
 #[cfg(test)]
 mod tests;
+mod tiling_layout;
 #[allow(clippy::module_inception)]
 mod window_manager;
 
@@ -0,0 +1,91 @@
+use crate::api::WindowsApi;
+use crate::common::{PersistentWorkspaceId, Placement, WindowHandle, WindowPlacement, Workspace, geometry};
+use crate::workspace_backend::WorkspaceBackend;
+use std::collections::HashMap;
+
+/// Shows a single near-maximised window per workspace while all other windows of the workspace are hidden, so that the
+/// user can focus on one window at a time. The hidden windows are stored in a [`Workspace`] that is never activated,
+/// which hides and restores them just like the windows of a workspace that is switched away from and back to.
+#[derive(Debug, Default)]
+pub(super) struct MonocleMode {
+  monocles: HashMap<PersistentWorkspaceId, Monocle>,
+}
+
+#[derive(Debug)]
+struct Monocle {
+  window: WindowHandle,
+  previous_placement: WindowPlacement,
+  hidden_windows: Workspace,
+}
+
+impl MonocleMode {
+  /// Returns `true` if the workspace is in monocle mode.
+  pub(super) fn is_active(&self, workspace: PersistentWorkspaceId) -> bool {
+    self.monocles.contains_key(&workspace)
+  }
+
+  /// Near-maximises `window` and hides all other visible windows of the active workspace, remembering the placement of
+  /// `window` so that [`MonocleMode::exit`] can restore it.
+  pub(super) fn enter<T: WindowsApi>(
+    &mut self,
+    api: &T,
+    workspace_manager: &impl WorkspaceBackend,
+    placement: &Placement,
+    workspace: PersistentWorkspaceId,
+    window: WindowHandle,
+    margin: i32,
+  ) {
+    let Some(monitor) = workspace_manager.monitor_for_workspace(workspace) else {
+      return;
+    };
+    let Some(previous_placement) = api.get_window_placement(window) else {
+      return;
+    };
+    let other_windows = api
+      .get_all_visible_windows()
+      .into_iter()
+      .filter(|other| {
+        other.handle != window && workspace_manager.active_workspace_for_window(other.handle) == Some(workspace)
+      })
+      .collect::<Vec<_>>();
+    info!(
+      "Entering monocle mode with {} on workspace {}, hiding [{}] other window(s)",
+      window,
+      workspace,
+      other_windows.len()
+    );
+    let mut hidden_windows = Workspace::new_inactive(workspace, &monitor, margin);
+    hidden_windows.store_and_hide_windows(other_windows, monitor.handle, api);
+    placement.resize(api, window, geometry::near_maximised(monitor.work_area, margin), margin);
+    api.set_foreground_window(window);
+    self.monocles.insert(
+      workspace,
+      Monocle {
+        window,
+        previous_placement,
+        hidden_windows,
+      },
+    );
+  }
+
+  /// Restores the windows hidden when the workspace entered monocle mode as well as the previous placement of the
+  /// monocle window. Does nothing if the workspace is not in monocle mode.
+  pub(super) fn exit<T: WindowsApi>(&mut self, api: &T, workspace: PersistentWorkspaceId) {
+    let Some(mut monocle) = self.monocles.remove(&workspace) else {
+      return;
+    };
+    info!("Leaving monocle mode with {} on workspace {}", monocle.window, workspace);
+    monocle.hidden_windows.restore_windows(api);
+    if api.is_window_alive(monocle.window) {
+      api.do_restore_window_placement(monocle.window, monocle.previous_placement);
+    }
+  }
+
+  /// Leaves monocle mode on every workspace, e.g. before all hidden windows are restored because Randolf exits.
+  pub(super) fn exit_all<T: WindowsApi>(&mut self, api: &T) {
+    let workspaces = self.monocles.keys().copied().collect::<Vec<_>>();
+    for workspace in workspaces {
+      self.exit(api, workspace);
+    }
+  }
+}
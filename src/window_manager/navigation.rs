@@ -1,15 +1,19 @@
 use crate::api::WindowsApi;
-use crate::common::{Direction, Monitor, Point, Window, WindowHandle};
+use crate::common::{Direction, FocusHistory, Monitor, Point, Window, WindowHandle};
+use crate::configuration_provider::{FocusCycleOrder, FocusFallbackPolicy};
+use std::cmp::Reverse;
 use windows::Win32::UI::Shell::IVirtualDesktopManager;
 
-/// Moves focus and the cursor to the best window or monitor in a direction.
+/// Moves focus and the cursor to the best window or monitor in a direction. Returns the window that was focused, if
+/// any, so that it can be remembered as visited before Windows reports the change of the foreground window.
 pub(super) fn move_cursor<T: WindowsApi>(
   api: &T,
   direction: Direction,
   windows: &[&Window],
   virtual_desktop_manager: Option<&IVirtualDesktopManager>,
   allow_selecting_same_center_windows: bool,
-) {
+  focus_history: &FocusHistory,
+) -> Option<WindowHandle> {
   let cursor_position = api.get_cursor_position();
   let (reference_point, reference_window) = match find_window_at_cursor(api, &cursor_position, windows) {
     Some(window) => (Point::from_center_of_rect(&window.rect), Some(window)),
@@ -29,13 +33,14 @@ pub(super) fn move_cursor<T: WindowsApi>(
       &current_desktop,
       reference_window,
       allow_selecting_same_center_windows,
+      focus_history,
     )
   });
 
   if let Some(target_window) = target {
     let target_point = Point::from_center_of_rect(&target_window.rect);
     move_focus_to_window(api, direction, target_window, &target_point);
-    return;
+    return Some(target_window.handle);
   }
 
   trace!("No window found in [{:?}] direction, attempting to find monitor", direction);
@@ -48,12 +53,76 @@ pub(super) fn move_cursor<T: WindowsApi>(
       direction
     ),
   }
+
+  None
+}
+
+/// Moves the cursor to the centre of the monitor in the given direction, ignoring any windows that
+/// [`move_cursor`] would otherwise select first.
+pub(super) fn throw_cursor<T: WindowsApi>(api: &T, direction: Direction) {
+  let monitors = api.get_all_monitors();
+  let current_monitor = api.get_monitor_handle_for_point(&api.get_cursor_position());
+  match monitors.get(direction, current_monitor) {
+    Some(target_monitor) => move_focus_to_monitor(api, direction, target_monitor),
+    None => info!("No monitor found in [{:?}] direction, did not throw cursor", direction),
+  }
+}
+
+/// Orders the windows for cycling through them. Windows with identical positions, e.g. windows that perfectly overlap,
+/// are ordered by their handle so that every one of them can be reached.
+pub(super) fn order_windows_for_focus_cycle(
+  windows: &[&Window],
+  order: FocusCycleOrder,
+  focus_history: &FocusHistory,
+) -> Vec<WindowHandle> {
+  let mut ordered = windows.to_vec();
+  ordered.sort_by_key(|window| (window.rect.left, window.rect.top, window.handle.hwnd));
+  if order == FocusCycleOrder::MostRecentlyUsed {
+    // Stable sort, so windows that were never focused remain in spatial order
+    ordered.sort_by_key(|window| focus_history.recency_of(window.handle).unwrap_or(usize::MAX));
+  }
+
+  ordered.into_iter().map(|window| window.handle).collect()
+}
+
+/// Returns the window after the current window in the order or, if `is_next` is `false`, the one before it, wrapping
+/// around at either end. Starts at the first or last window if the current window is not part of the order.
+pub(super) fn select_adjacent_window_in_cycle(
+  order: &[WindowHandle],
+  current: Option<WindowHandle>,
+  is_next: bool,
+) -> Option<WindowHandle> {
+  if order.is_empty() {
+    return None;
+  }
+  let index = match (
+    current.and_then(|current| order.iter().position(|handle| *handle == current)),
+    is_next,
+  ) {
+    (Some(index), true) => (index + 1) % order.len(),
+    (Some(index), false) => (index + order.len() - 1) % order.len(),
+    (None, true) => 0,
+    (None, false) => order.len() - 1,
+  };
+
+  order.get(index).copied()
 }
 
-/// Focuses the visible window nearest the cursor, ignoring the supplied window.
-pub(super) fn find_and_select_closest_window<T: WindowsApi>(api: &T, ignored_window: WindowHandle) {
+/// Focuses the visible window selected by the fallback policy, ignoring the supplied window. Policies other than
+/// [`FocusFallbackPolicy::Closest`] fall back to the closest window if they cannot find a candidate.
+pub(super) fn find_and_select_fallback_window<T: WindowsApi>(
+  api: &T,
+  ignored_window: WindowHandle,
+  policy: FocusFallbackPolicy,
+  focus_history: &FocusHistory,
+) {
   let cursor_position = api.get_cursor_position();
-  if let Some(window) = find_closest_window(api, cursor_position, Some(ignored_window)) {
+  let selected = match policy {
+    FocusFallbackPolicy::Closest => None,
+    FocusFallbackPolicy::Largest => find_largest_window(api, Some(ignored_window)),
+    FocusFallbackPolicy::MostRecentlyUsed => find_most_recently_used_window(api, focus_history, Some(ignored_window)),
+  };
+  if let Some(window) = selected.or_else(|| find_closest_window(api, cursor_position, Some(ignored_window))) {
     api.set_foreground_window(window);
     let window_info = api
       .get_window_placement(window)
@@ -64,6 +133,31 @@ pub(super) fn find_and_select_closest_window<T: WindowsApi>(api: &T, ignored_win
   }
 }
 
+/// Finds the visible window with the largest area.
+pub(super) fn find_largest_window<T: WindowsApi>(api: &T, ignored_window: Option<WindowHandle>) -> Option<WindowHandle> {
+  api
+    .get_all_visible_windows()
+    .iter()
+    .filter(|window| ignored_window != Some(window.handle))
+    .max_by_key(|window| window.rect.area())
+    .map(|window| window.handle)
+}
+
+/// Finds the visible window that was focused most recently according to the focus history.
+pub(super) fn find_most_recently_used_window<T: WindowsApi>(
+  api: &T,
+  focus_history: &FocusHistory,
+  ignored_window: Option<WindowHandle>,
+) -> Option<WindowHandle> {
+  let candidates = api
+    .get_all_visible_windows()
+    .iter()
+    .map(|window| window.handle)
+    .filter(|handle| ignored_window != Some(*handle))
+    .collect::<Vec<_>>();
+  focus_history.most_recent_of(&candidates)
+}
+
 /// Finds the visible window nearest a point, preferring the smallest when distances tie.
 pub(super) fn find_closest_window<T: WindowsApi>(
   api: &T,
@@ -189,11 +283,12 @@ pub(super) fn select_window_in_direction<'window>(
   windows: &[&'window Window],
   reference_window: Option<&Window>,
   allow_selecting_same_center_windows: bool,
+  focus_history: &FocusHistory,
 ) -> Option<&'window Window> {
   // Cycle same-centre windows first
   if allow_selecting_same_center_windows
     && let Some(reference_window) = reference_window
-    && let Some(next_window) = find_next_same_center_window(reference_window, windows)
+    && let Some(next_window) = find_next_same_center_window(reference_window, windows, focus_history)
   {
     return Some(next_window);
   }
@@ -242,7 +337,15 @@ pub(super) fn select_window_in_direction<'window>(
   closest_window
 }
 
-fn find_next_same_center_window<'window>(reference_window: &Window, windows: &[&'window Window]) -> Option<&'window Window> {
+/// Returns the window sharing the centre of `reference_window` that was visited least recently, so that repeated moves
+/// visit every overlapping window once before returning to the first one, even if the window that is detected under
+/// the cursor is not the one that was visited last. Windows that were never visited, or that were visited equally long
+/// ago, follow the reference window in the order of their handles.
+fn find_next_same_center_window<'window>(
+  reference_window: &Window,
+  windows: &[&'window Window],
+  focus_history: &FocusHistory,
+) -> Option<&'window Window> {
   let mut same_center = windows
     .iter()
     .copied()
@@ -257,5 +360,13 @@ fn find_next_same_center_window<'window>(reference_window: &Window, windows: &[&
   let reference_index = same_center
     .iter()
     .position(|window| window.handle == reference_window.handle)?;
-  same_center.get((reference_index + 1) % same_center.len()).copied()
+  let count = same_center.len();
+  (1..count)
+    .map(|offset| same_center[(reference_index + offset) % count])
+    .enumerate()
+    .min_by_key(|(offset, window)| {
+      let recency = focus_history.recency_of(window.handle).unwrap_or(usize::MAX);
+      (Reverse(recency), *offset)
+    })
+    .map(|(_, window)| window)
 }
@@ -1,8 +1,9 @@
 use crate::api::WindowsApi;
 use crate::common::{
-  Direction, PersistentWorkspaceId, Point, Rect, ScrollingStrips, Sizing, WidthPreset, Window, WindowHandle,
+  Direction, MonitorMargins, PersistentWorkspaceId, Point, Rect, ScrollingStrips, Sizing, WidthPreset, Window, WindowHandle,
+  geometry,
 };
-use crate::workspace_manager::WorkspaceManager;
+use crate::workspace_backend::WorkspaceBackend;
 use std::collections::{HashMap, HashSet};
 use std::time::Duration;
 use windows::Win32::UI::Shell::IVirtualDesktopManager;
@@ -52,7 +53,7 @@ impl ScrollingLayout {
   pub(super) fn insert<T: WindowsApi + Clone>(
     &mut self,
     api: &T,
-    workspace_manager: &WorkspaceManager<T>,
+    workspace_manager: &impl WorkspaceBackend,
     workspace: PersistentWorkspaceId,
     window: WindowHandle,
     preset: Option<WidthPreset>,
@@ -73,9 +74,9 @@ impl ScrollingLayout {
   pub(super) fn deactivate<T: WindowsApi + Clone>(
     &mut self,
     api: &T,
-    workspace_manager: &WorkspaceManager<T>,
+    workspace_manager: &impl WorkspaceBackend,
     workspaces: &[PersistentWorkspaceId],
-    margin: i32,
+    margins: &MonitorMargins,
   ) {
     let screen_areas = api
       .get_all_monitors()
@@ -94,6 +95,7 @@ impl ScrollingLayout {
           .get_window_rect(handle)
           .is_some_and(|rect| !screen_areas.iter().any(|area| rect.intersects(area)));
         if off_screen {
+          let margin = margins.for_workspace(*workspace);
           api.set_window_position(handle, Rect::from(assigned_sizing(monitor.work_area, margin, preset)));
         }
       }
@@ -106,10 +108,10 @@ impl ScrollingLayout {
   pub(super) fn reconcile<T: WindowsApi + Clone>(
     &mut self,
     api: &T,
-    workspace_manager: &WorkspaceManager<T>,
+    workspace_manager: &impl WorkspaceBackend,
     active_workspaces: &[PersistentWorkspaceId],
     virtual_desktop_manager: Option<&IVirtualDesktopManager>,
-    margin: i32,
+    margins: &MonitorMargins,
   ) {
     if active_workspaces.is_empty() {
       return;
@@ -134,11 +136,11 @@ impl ScrollingLayout {
       &visible_members,
       foreground,
       &transferred_presets,
-      margin,
+      margins,
     );
 
     for workspace in active_workspaces {
-      self.reflow(api, workspace_manager, *workspace, margin);
+      self.reflow(api, workspace_manager, *workspace, margins.for_workspace(*workspace));
     }
     self.reconcile_focus(
       api,
@@ -146,7 +148,7 @@ impl ScrollingLayout {
       foreground,
       previous_workspace,
       newly_focused_workspace,
-      margin,
+      margins,
     );
     self.previous_foreground_window = api.get_foreground_window();
   }
@@ -155,7 +157,7 @@ impl ScrollingLayout {
   fn get_visible_members_by_workspace<T: WindowsApi + Clone>(
     &self,
     api: &T,
-    workspace_manager: &WorkspaceManager<T>,
+    workspace_manager: &impl WorkspaceBackend,
     active_workspaces: &[PersistentWorkspaceId],
     virtual_desktop_manager: Option<&IVirtualDesktopManager>,
   ) -> MembersByWorkspace {
@@ -201,20 +203,20 @@ impl ScrollingLayout {
   }
 
   /// Adopts initial strips or incrementally reconciles them, returning the workspace containing the newest focus.
-  fn reconcile_memberships<T: WindowsApi + Clone>(
+  fn reconcile_memberships(
     &mut self,
-    workspace_manager: &WorkspaceManager<T>,
+    workspace_manager: &impl WorkspaceBackend,
     active_workspaces: &[PersistentWorkspaceId],
     visible_members: &MembersByWorkspace,
     foreground: Option<WindowHandle>,
     transferred_presets: &HashMap<WindowHandle, WidthPreset>,
-    margin: i32,
+    margins: &MonitorMargins,
   ) -> Option<PersistentWorkspaceId> {
     if !self.initialised {
       for workspace in active_workspaces {
-        let usable_width = workspace_manager
-          .monitor_for_workspace(*workspace)
-          .map_or(1, |monitor| usable_width(monitor.work_area, margin));
+        let usable_width = workspace_manager.monitor_for_workspace(*workspace).map_or(1, |monitor| {
+          usable_width(monitor.work_area, margins.for_workspace(*workspace))
+        });
         let members = visible_members
           .get(workspace)
           .into_iter()
@@ -236,7 +238,7 @@ impl ScrollingLayout {
           visible_members,
           foreground,
           transferred_presets,
-          margin,
+          margins.for_workspace(*workspace),
         )
         .is_some()
       {
@@ -247,9 +249,9 @@ impl ScrollingLayout {
   }
 
   /// Removes missing members, inserts newly visible members, and returns any new member selected for focus.
-  fn reconcile_workspace<T: WindowsApi + Clone>(
+  fn reconcile_workspace(
     &mut self,
-    workspace_manager: &WorkspaceManager<T>,
+    workspace_manager: &impl WorkspaceBackend,
     workspace: PersistentWorkspaceId,
     visible_members: &MembersByWorkspace,
     foreground: Option<WindowHandle>,
@@ -288,26 +290,28 @@ impl ScrollingLayout {
   fn reconcile_focus<T: WindowsApi + Clone>(
     &mut self,
     api: &T,
-    workspace_manager: &WorkspaceManager<T>,
+    workspace_manager: &impl WorkspaceBackend,
     foreground: Option<WindowHandle>,
     previous_workspace: Option<PersistentWorkspaceId>,
     newly_focused_workspace: Option<PersistentWorkspaceId>,
-    margin: i32,
+    margins: &MonitorMargins,
   ) {
     if let Some(workspace) = newly_focused_workspace {
+      let margin = margins.for_workspace(workspace);
       self.reflow(api, workspace_manager, workspace, margin);
       self.focus(api, workspace_manager, workspace, margin);
     } else if let Some(handle) = foreground
       && let Some(workspace) = self.strips.get_workspace_containing(handle)
     {
       self.strips.set_active(workspace, handle);
-      self.reflow(api, workspace_manager, workspace, margin);
+      self.reflow(api, workspace_manager, workspace, margins.for_workspace(workspace));
     } else if let Some(workspace) = previous_workspace
       && workspace_manager.is_workspace_active(workspace)
       && self
         .previous_foreground_window
         .is_some_and(|handle| self.strips.get_workspace_containing(handle).is_none())
     {
+      let margin = margins.for_workspace(workspace);
       self.reflow(api, workspace_manager, workspace, margin);
       self.focus(api, workspace_manager, workspace, margin);
     }
@@ -324,7 +328,7 @@ impl ScrollingLayout {
   pub(super) fn reflow<T: WindowsApi + Clone>(
     &mut self,
     api: &T,
-    workspace_manager: &WorkspaceManager<T>,
+    workspace_manager: &impl WorkspaceBackend,
     workspace: PersistentWorkspaceId,
     margin: i32,
   ) {
@@ -365,7 +369,7 @@ impl ScrollingLayout {
   pub(super) fn focus<T: WindowsApi + Clone>(
     &self,
     api: &T,
-    workspace_manager: &WorkspaceManager<T>,
+    workspace_manager: &impl WorkspaceBackend,
     workspace: PersistentWorkspaceId,
     margin: i32,
   ) {
@@ -391,7 +395,7 @@ impl ScrollingLayout {
   pub(super) fn resize_window<T: WindowsApi + Clone>(
     &mut self,
     api: &T,
-    workspace_manager: &WorkspaceManager<T>,
+    workspace_manager: &impl WorkspaceBackend,
     direction: Direction,
     margin: i32,
   ) {
@@ -412,7 +416,7 @@ impl ScrollingLayout {
   pub(super) fn finish_mouse_resize<T: WindowsApi + Clone>(
     &mut self,
     api: &T,
-    workspace_manager: &WorkspaceManager<T>,
+    workspace_manager: &impl WorkspaceBackend,
     handle: WindowHandle,
     margin: i32,
   ) {
@@ -438,7 +442,7 @@ impl ScrollingLayout {
   pub(super) fn move_focus<T: WindowsApi + Clone>(
     &mut self,
     api: &T,
-    workspace_manager: &WorkspaceManager<T>,
+    workspace_manager: &impl WorkspaceBackend,
     direction: Direction,
     margin: i32,
     animation_duration: Duration,
@@ -460,7 +464,7 @@ impl ScrollingLayout {
   pub(super) fn reorder<T: WindowsApi + Clone>(
     &mut self,
     api: &T,
-    workspace_manager: &WorkspaceManager<T>,
+    workspace_manager: &impl WorkspaceBackend,
     direction: Direction,
     margin: i32,
   ) {
@@ -481,7 +485,7 @@ impl ScrollingLayout {
   pub(super) fn remove_and_refocus<T: WindowsApi + Clone>(
     &mut self,
     api: &T,
-    workspace_manager: &WorkspaceManager<T>,
+    workspace_manager: &impl WorkspaceBackend,
     member: WindowHandle,
     margin: i32,
   ) {
@@ -495,7 +499,7 @@ impl ScrollingLayout {
   }
 
   /// Brings wholly off-screen strip windows back onto their workspace monitor at their assigned widths.
-  pub(super) fn restore_off_screen<T: WindowsApi>(&self, api: &T, margin: i32) {
+  pub(super) fn restore_off_screen<T: WindowsApi>(&self, api: &T, margins: &MonitorMargins) {
     let monitors = api.get_all_monitors();
     let screen_areas = monitors
       .get_all()
@@ -516,6 +520,7 @@ impl ScrollingLayout {
           .get_window_rect(handle)
           .is_some_and(|rect| !screen_areas.iter().any(|area| rect.intersects(area)));
         if off_screen && let Some(preset) = self.strips.get_width_preset(workspace, handle) {
+          let margin = margins.for_monitor(&monitor.id);
           api.set_window_position(handle, Rect::from(assigned_sizing(monitor.work_area, margin, preset)));
         }
       }
@@ -525,7 +530,7 @@ impl ScrollingLayout {
   fn animate<T: WindowsApi + Clone>(
     &mut self,
     api: &T,
-    workspace_manager: &WorkspaceManager<T>,
+    workspace_manager: &impl WorkspaceBackend,
     workspace: PersistentWorkspaceId,
     outgoing: WindowHandle,
     margin: i32,
@@ -552,7 +557,7 @@ impl ScrollingLayout {
         .iter()
         .map(|(handle, target)| {
           let start = starts.get(handle).copied().unwrap_or(*target);
-          (*handle, interpolate_rect(start, *target, eased_progress))
+          (*handle, geometry::interpolate(start, *target, eased_progress))
         })
         .collect::<Vec<_>>();
       let failures = api.set_window_positions(&positions, outgoing);
@@ -591,15 +596,3 @@ fn assigned_sizing(work_area: Rect, margin: i32, preset: WidthPreset) -> Sizing
     near_maximised.height,
   )
 }
-
-fn interpolate_rect(start: Rect, target: Rect, progress: f64) -> Rect {
-  fn coordinate(start: i32, target: i32, progress: f64) -> i32 {
-    (f64::from(start) + f64::from(target - start) * progress.clamp(0.0, 1.0)).round() as i32
-  }
-  Rect::new(
-    coordinate(start.left, target.left, progress),
-    coordinate(start.top, target.top, progress),
-    coordinate(start.right, target.right, progress),
-    coordinate(start.bottom, target.bottom, progress),
-  )
-}
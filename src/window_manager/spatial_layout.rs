@@ -1,26 +1,93 @@
 use super::navigation;
 use crate::api::WindowsApi;
-use crate::common::{Direction, Monitor, MonitorInfo, Placement, Point, Rect, Sizing, WindowHandle, WindowPlacement};
+use crate::common::{
+  Direction, FocusHistory, Monitor, MonitorHandle, MonitorInfo, MonitorMargins, Placement, Point, Rect, Sizing, Window,
+  WindowHandle, WindowPlacement, geometry,
+};
+use crate::configuration_provider::{FocusCycleOrder, FocusFallbackPolicy};
 use crate::utils::MINIMUM_WINDOW_DIMENSION;
+use std::collections::{HashMap, HashSet};
+
+/// The fraction of the work area (i.e. 1/20th or 5%) by which a shared edge is moved.
+const SHARED_EDGE_STEP_DIVISOR: i32 = 20;
+
+/// The number of pixels by which the gap between two windows may deviate from the margin for them to share an edge.
+const SHARED_EDGE_TOLERANCE_IN_PX: i32 = 10;
 
 /// A layout that does not manage any windows. Handles geometry-based window movement, resizing, and follow-up focus.
 #[derive(Debug, Default)]
-pub(super) struct SpatialLayout;
+pub(super) struct SpatialLayout {
+  pub(super) focus_history: FocusHistory,
+  pub(super) known_work_areas: HashMap<MonitorHandle, Rect>,
+  pub(super) known_windows: Option<HashSet<WindowHandle>>,
+  focus_cycle: Option<FocusCycle>,
+}
+
+/// The order of the windows that were last cycled through and the window that was selected, so that repeatedly cycling
+/// continues in the same order even though focusing a window changes the focus history.
+#[derive(Debug)]
+struct FocusCycle {
+  order: Vec<WindowHandle>,
+  selected: WindowHandle,
+}
 
 impl SpatialLayout {
-  /// Places the foreground window on half a monitor or moves it to the next monitor.
-  pub(super) fn move_window<T: WindowsApi>(&self, api: &T, placement: &Placement, direction: Direction, margin: i32) {
+  /// Places the foreground window on half or a quarter of a monitor or moves it to the next monitor:
+  /// - A window on half a monitor moves into the quarter of that half in the direction, if the direction is
+  ///   perpendicular to the half, e.g. pressing left and then up results in the top-left quarter
+  /// - A window on a quarter moves into the adjacent quarter in the direction, if there is one
+  /// - If `snap_to_thirds` is enabled, a window on the half of the monitor in a horizontal direction moves to the two
+  ///   thirds and then the third of the monitor in that direction on repeated presses
+  /// - Any other window is placed on the half of the monitor in the direction
+  /// - A window that already touches the edge of the monitor in the direction moves to the next monitor
+  ///
+  /// The `size_tolerance_in_px` is passed on to [`Placement::is_of_expected_size`].
+  pub(super) fn move_window<T: WindowsApi>(
+    &self,
+    api: &T,
+    placement: &Placement,
+    direction: Direction,
+    margin: i32,
+    size_tolerance_in_px: i32,
+    snap_to_thirds: bool,
+  ) {
     let Some((handle, current_placement, monitor_info)) = window_and_monitor_info(api) else {
       return;
     };
-    let sizing = match direction {
-      Direction::Left => Sizing::left_half_of_screen(monitor_info.work_area, margin),
-      Direction::Right => Sizing::right_half_of_screen(monitor_info.work_area, margin),
-      Direction::Up => Sizing::top_half_of_screen(monitor_info.work_area, margin),
-      Direction::Down => Sizing::bottom_half_of_screen(monitor_info.work_area, margin),
+    let work_area = monitor_info.work_area;
+    let is_snapped_to =
+      |sizing: &Sizing| placement.is_of_expected_size(api, handle, &current_placement, sizing, margin, size_tolerance_in_px);
+    let corners = [Direction::Left, Direction::Right]
+      .into_iter()
+      .flat_map(|horizontal| [(horizontal, Direction::Up), (horizontal, Direction::Down)]);
+    let perpendicular_sides = if direction.is_horizontal() {
+      [Direction::Up, Direction::Down]
+    } else {
+      [Direction::Left, Direction::Right]
+    };
+    let sizing = match corners
+      .find(|(horizontal, vertical)| is_snapped_to(&Sizing::quarter_of_screen(work_area, *horizontal, *vertical, margin)))
+    {
+      Some((horizontal, vertical)) if direction == horizontal || direction == vertical => None,
+      Some((_, vertical)) if direction.is_horizontal() => {
+        Some(Sizing::quarter_of_screen(work_area, direction, vertical, margin))
+      }
+      Some((horizontal, _)) => Some(Sizing::quarter_of_screen(work_area, horizontal, direction, margin)),
+      None => match perpendicular_sides
+        .into_iter()
+        .find(|side| is_snapped_to(&geometry::half(work_area, *side, margin)))
+      {
+        Some(side) if direction.is_horizontal() => Some(Sizing::quarter_of_screen(work_area, direction, side, margin)),
+        Some(side) => Some(Sizing::quarter_of_screen(work_area, side, direction, margin)),
+        None if snap_to_thirds && direction.is_horizontal() => next_horizontal_sizing(work_area, direction, margin)
+          .into_iter()
+          .find(|(current, _)| is_snapped_to(current))
+          .map_or_else(|| Some(geometry::half(work_area, direction, margin)), |(_, next)| next),
+        None => Some(geometry::half(work_area, direction, margin)).filter(|half| !is_snapped_to(half)),
+      },
     };
 
-    if placement.is_of_expected_size(api, handle, &current_placement, &sizing, margin) {
+    let Some(sizing) = sizing else {
       let monitors = api.get_all_monitors();
       let current_monitor = api.get_monitor_handle_for_window_handle(handle);
       if let Some(target_monitor) = monitors.get(direction, current_monitor) {
@@ -30,14 +97,52 @@ impl SpatialLayout {
         debug!("No monitor found in [{:?}] direction, did not move window", direction);
       }
       return;
-    }
+    };
 
     let cursor_target = Point::from_center_of_sizing(&sizing);
     placement.resize(api, handle, sizing, margin);
     api.set_cursor_position(&cursor_target);
   }
 
-  /// Moves and near-maximises a window on a target monitor.
+  /// Snaps a window that was dragged using the mouse to the half or quarter of its monitor that it overlaps the most,
+  /// if it overlaps it by at least `threshold_in_percent` (see [`geometry::overlap_in_percent`]). Windows that were
+  /// dropped somewhere else keep the position they were dropped at.
+  pub(super) fn snap_after_drag<T: WindowsApi>(
+    &self,
+    api: &T,
+    placement: &Placement,
+    window: WindowHandle,
+    margin: i32,
+    threshold_in_percent: i32,
+  ) {
+    let (Some(rect), Some(monitor_info)) = (api.get_window_rect(window), api.get_monitor_info_for_window(window)) else {
+      return;
+    };
+    let work_area = monitor_info.work_area;
+    let halves = [Direction::Left, Direction::Right, Direction::Up, Direction::Down]
+      .into_iter()
+      .map(|direction| geometry::half(work_area, direction, margin));
+    let quarters = [Direction::Left, Direction::Right].into_iter().flat_map(|horizontal| {
+      [Direction::Up, Direction::Down]
+        .into_iter()
+        .map(move |vertical| geometry::quarter(work_area, horizontal, vertical, margin))
+    });
+    let Some((overlap_in_percent, sizing)) = halves
+      .chain(quarters)
+      .map(|sizing| (geometry::overlap_in_percent(&rect, &Rect::from(sizing.clone())), sizing))
+      .max_by_key(|(overlap_in_percent, _)| *overlap_in_percent)
+      .filter(|(overlap_in_percent, _)| *overlap_in_percent >= threshold_in_percent)
+    else {
+      return;
+    };
+    debug!(
+      "Snapping {} after dragging because it overlaps {:?} by {}%",
+      window, sizing, overlap_in_percent
+    );
+    placement.resize(api, window, sizing, margin);
+  }
+
+  /// Moves and near-maximises a window on a target monitor, translating its size to the DPI of the target monitor.
   pub(super) fn move_window_to_monitor<T: WindowsApi>(
     &self,
     api: &T,
@@ -46,13 +151,22 @@ impl SpatialLayout {
     target: &Monitor,
     margin: i32,
   ) {
-    api.set_window_position(handle, target.work_area);
+    let current_monitor = api.get_monitor_handle_for_window_handle(handle);
+    api.set_window_position_with_dpi_adjustment(handle, current_monitor, target.handle, target.work_area);
     placement.near_maximise(api, handle, MonitorInfo::from(target), margin);
     api.set_cursor_position(&target.center);
   }
 
-  /// Steps the foreground window through the spatial sizes for a direction.
-  pub(super) fn resize_window<T: WindowsApi>(&self, api: &T, placement: &Placement, direction: Direction, margin: i32) {
+  /// Steps the foreground window through the spatial sizes for a direction. The `size_tolerance_in_px` is passed on to
+  /// [`Placement::is_of_expected_size`].
+  pub(super) fn resize_window<T: WindowsApi>(
+    &self,
+    api: &T,
+    placement: &Placement,
+    direction: Direction,
+    margin: i32,
+    size_tolerance_in_px: i32,
+  ) {
     let Some((handle, current_placement, monitor_info)) = window_and_monitor_info(api) else {
       return;
     };
@@ -75,7 +189,11 @@ impl SpatialLayout {
     );
 
     // Calculate minimum permitted dimensions
-    let (mut min_width, mut min_height) = calculate_minimum_resize_dimensions(work_area, margin);
+    let (quarter_width, quarter_height) = geometry::quarter_dimensions(work_area, margin);
+    let (mut min_width, mut min_height) = (
+      MINIMUM_WINDOW_DIMENSION.max(quarter_width),
+      MINIMUM_WINDOW_DIMENSION.max(quarter_height),
+    );
     if let Some((application_min_width, application_min_height)) = api.get_minimum_window_dimensions(handle) {
       min_width = min_width.max(application_min_width);
       min_height = min_height.max(application_min_height);
@@ -93,7 +211,7 @@ impl SpatialLayout {
     placement.resize(api, handle, new_sizing.clone(), margin);
     let has_resize_succeeded = api
       .get_window_placement(handle)
-      .is_some_and(|actual| placement.is_of_expected_size(api, handle, &actual, &new_sizing, margin));
+      .is_some_and(|actual| placement.is_of_expected_size(api, handle, &actual, &new_sizing, margin, size_tolerance_in_px));
     if !has_resize_succeeded {
       warn!(
         "Restoring {} because Windows did not apply the complete requested resize",
@@ -105,25 +223,235 @@ impl SpatialLayout {
     api.set_cursor_position(&cursor_target);
   }
 
-  /// Focuses the nearest remaining window after a close or minimise when enabled.
-  pub(super) fn after_close_or_minimise<T: WindowsApi>(&self, api: &T, window: WindowHandle, move_cursor: bool) {
-    if move_cursor {
-      navigation::find_and_select_closest_window(api, window);
+  /// Moves the edge that the foreground window shares with its neighbours in the given direction, resizing the
+  /// foreground window and all windows on the other side of the edge at once, like a splitter. For horizontal
+  /// directions, the right edge is preferred over the left edge and, for vertical directions, the bottom edge is
+  /// preferred over the top edge.
+  pub(super) fn move_shared_edge<T: WindowsApi>(&self, api: &T, placement: &Placement, direction: Direction, margin: i32) {
+    let Some((handle, current_placement, monitor_info)) = window_and_monitor_info(api) else {
+      return;
+    };
+    let rect = current_placement.normal_position;
+    let work_area = monitor_info.work_area;
+    let (sides, step) = match direction {
+      Direction::Left | Direction::Right => (
+        [Direction::Right, Direction::Left],
+        work_area.width() / SHARED_EDGE_STEP_DIVISOR,
+      ),
+      Direction::Up | Direction::Down => (
+        [Direction::Down, Direction::Up],
+        work_area.height() / SHARED_EDGE_STEP_DIVISOR,
+      ),
+    };
+    let offset = if matches!(direction, Direction::Left | Direction::Up) {
+      -step
+    } else {
+      step
+    };
+
+    // Find the windows on the same monitor that share an edge with the foreground window
+    let monitor = api.get_monitor_handle_for_window_handle(handle);
+    let candidates: Vec<(WindowHandle, Rect)> = api
+      .get_all_visible_windows()
+      .into_iter()
+      .filter(|window| window.handle != handle && api.get_monitor_handle_for_window_handle(window.handle) == monitor)
+      .filter_map(|window| {
+        api
+          .get_window_placement(window.handle)
+          .map(|window_placement| (window.handle, window_placement.normal_position))
+      })
+      .collect();
+    let Some((side, neighbours)) = sides
+      .into_iter()
+      .map(|side| {
+        let neighbours = candidates
+          .iter()
+          .filter(|(_, other)| geometry::is_adjacent(&rect, other, side, margin, SHARED_EDGE_TOLERANCE_IN_PX))
+          .copied()
+          .collect::<Vec<_>>();
+        (side, neighbours)
+      })
+      .find(|(_, neighbours)| !neighbours.is_empty())
+    else {
+      debug!("{} does not share an edge with any other window, did not move edge", handle);
+      return;
+    };
+
+    // Resize all windows at once unless any of them would become too small
+    let resized_rect = geometry::move_edge(rect, side, offset);
+    let resized_neighbours: Vec<(WindowHandle, Rect)> = neighbours
+      .iter()
+      .map(|(neighbour, neighbour_rect)| (*neighbour, geometry::move_edge(*neighbour_rect, side.opposite(), offset)))
+      .collect();
+    let is_too_small = |rect: &Rect| rect.width() < MINIMUM_WINDOW_DIMENSION || rect.height() < MINIMUM_WINDOW_DIMENSION;
+    if is_too_small(&resized_rect) || resized_neighbours.iter().any(|(_, rect)| is_too_small(rect)) {
+      debug!(
+        "Not moving [{:?}] edge of {} because a window would become smaller than the minimum",
+        side, handle
+      );
+      return;
+    }
+    debug!(
+      "Moving [{:?}] edge of {} shared with [{}] window(s) by {}px",
+      side,
+      handle,
+      resized_neighbours.len(),
+      offset
+    );
+    placement.resize(api, handle, Sizing::from(resized_rect), margin);
+    for (neighbour, neighbour_rect) in resized_neighbours {
+      placement.resize(api, neighbour, Sizing::from(neighbour_rect), margin);
+    }
+  }
+
+  /// Remembers the work area of every monitor, so that a later change of the work area can be detected.
+  pub(super) fn record_work_areas<T: WindowsApi>(&mut self, api: &T) {
+    for monitor in api.get_all_monitors().get_all() {
+      self.known_work_areas.insert(monitor.handle, monitor.work_area);
+    }
+  }
+
+  /// Re-fits near-maximised and half-snapped windows on each of the given monitors whose work area changed since it was
+  /// last recorded, e.g. because the taskbar was moved or an app bar was registered. The new work areas of all other
+  /// monitors are only recorded.
+  pub(super) fn refit_windows_after_work_area_change<T: WindowsApi>(
+    &mut self,
+    api: &T,
+    placement: &Placement,
+    margins: &MonitorMargins,
+    monitor_ids: &[[u16; 32]],
+  ) {
+    for monitor in api.get_all_monitors().get_all() {
+      let Some(previous_work_area) = self.known_work_areas.insert(monitor.handle, monitor.work_area) else {
+        continue;
+      };
+      if previous_work_area == monitor.work_area || !monitor_ids.contains(&monitor.id) {
+        continue;
+      }
+      info!(
+        "Work area of [{}] changed from {} to {}, re-fitting its windows",
+        monitor, previous_work_area, monitor.work_area
+      );
+      let margin = margins.for_monitor(&monitor.id);
+      for window in api
+        .get_all_visible_windows()
+        .iter()
+        .filter(|window| monitor.monitor_area.contains(&window.center))
+      {
+        let Some(current_placement) = api.get_window_placement(window.handle) else {
+          continue;
+        };
+        let current_sizing = Sizing::from(current_placement.normal_position);
+        if let Some(new_sizing) = current_sizing.refit(previous_work_area, monitor.work_area, margin) {
+          debug!(
+            "Re-fitting {} \"{}\" to the new work area",
+            window.handle,
+            window.title_trunc()
+          );
+          placement.resize(api, window.handle, new_sizing, margin);
+        }
+      }
+    }
+  }
+
+  /// Remembers the foreground window so that it can be preferred when focus needs to fall back to another window.
+  pub(super) fn record_foreground_window<T: WindowsApi>(&mut self, api: &T) {
+    if let Some(window) = api.get_foreground_window() {
+      self.focus_history.record(window);
     }
   }
-}
 
-fn calculate_minimum_resize_dimensions(work_area: Rect, margin: i32) -> (i32, i32) {
-  let quarter_width = Sizing::left_half_of_screen(work_area, margin)
-    .halved(Direction::Left, margin)
-    .width;
-  let quarter_height = Sizing::top_half_of_screen(work_area, margin)
-    .halved(Direction::Up, margin)
-    .height;
-  (
-    MINIMUM_WINDOW_DIMENSION.max(quarter_width),
-    MINIMUM_WINDOW_DIMENSION.max(quarter_height),
-  )
+  /// Focuses the next or previous of the given windows and moves the cursor to its centre. Continues the previous cycle
+  /// if the window it selected still has focus and the windows have not changed, and starts a new cycle in the
+  /// configured order otherwise. Returns the focused window, or `None` if there is no other window to focus.
+  pub(super) fn cycle_focus<T: WindowsApi>(
+    &mut self,
+    api: &T,
+    windows: &[&Window],
+    order: FocusCycleOrder,
+    is_next: bool,
+  ) -> Option<WindowHandle> {
+    let current = api.get_foreground_window();
+    let order = match self.focus_cycle.take() {
+      Some(cycle)
+        if current == Some(cycle.selected)
+          && cycle.order.len() == windows.len()
+          && windows.iter().all(|window| cycle.order.contains(&window.handle)) =>
+      {
+        cycle.order
+      }
+      _ => navigation::order_windows_for_focus_cycle(windows, order, &self.focus_history),
+    };
+    let selected = navigation::select_adjacent_window_in_cycle(&order, current, is_next)?;
+    if current == Some(selected) {
+      return None;
+    }
+    let window = windows.iter().find(|window| window.handle == selected)?;
+    api.set_cursor_position(&Point::from_center_of_rect(&window.rect));
+    api.set_foreground_window(selected);
+    info!(
+      "Cycled focus to {} \"{}\" ({} of {})",
+      selected,
+      window.title_trunc(),
+      order.iter().position(|handle| *handle == selected).unwrap_or_default() + 1,
+      order.len()
+    );
+    self.focus_cycle = Some(FocusCycle { order, selected });
+
+    Some(selected)
+  }
+
+  /// Returns the visible windows that have been shown for the first time since the last call. Windows that already
+  /// exist on the first call are not reported. Windows that are hidden (e.g. by switching workspaces) remain known, so
+  /// they are not reported again when they are shown again.
+  pub(super) fn take_newly_shown_windows<T: WindowsApi>(&mut self, api: &T) -> Vec<Window> {
+    let visible_windows = api.get_all_visible_windows();
+    let Some(known_windows) = self.known_windows.as_mut() else {
+      self.known_windows = Some(visible_windows.into_iter().map(|window| window.handle).collect());
+      return Vec::new();
+    };
+    let existing_windows = api
+      .get_all_windows()
+      .into_iter()
+      .map(|window| window.handle)
+      .collect::<HashSet<_>>();
+    known_windows.retain(|handle| existing_windows.contains(handle));
+
+    visible_windows
+      .into_iter()
+      .filter(|window| known_windows.insert(window.handle))
+      .collect()
+  }
+
+  /// Returns the window if it is visible but has not been reported as shown before, e.g. because its title excluded it
+  /// until now, and remembers it, so that [`SpatialLayout::take_newly_shown_windows`] does not report it again.
+  pub(super) fn take_newly_shown_window<T: WindowsApi>(&mut self, api: &T, handle: WindowHandle) -> Option<Window> {
+    let known_windows = self.known_windows.as_mut()?;
+    if known_windows.contains(&handle) || api.is_not_a_managed_window(&handle) {
+      return None;
+    }
+    let window = api
+      .get_all_visible_windows()
+      .into_iter()
+      .find(|window| window.handle == handle)?;
+    known_windows.insert(handle);
+
+    Some(window)
+  }
+
+  /// Focuses a remaining window, chosen by the fallback policy, after a close or minimise when enabled.
+  pub(super) fn after_close_or_minimise<T: WindowsApi>(
+    &mut self,
+    api: &T,
+    window: WindowHandle,
+    move_cursor: bool,
+    policy: FocusFallbackPolicy,
+  ) {
+    self.focus_history.forget(window);
+    if move_cursor {
+      navigation::find_and_select_fallback_window(api, window, policy, &self.focus_history);
+    }
+  }
 }
 
 fn window_and_monitor_info<T: WindowsApi>(api: &T) -> Option<(WindowHandle, WindowPlacement, MonitorInfo)> {
@@ -132,3 +460,27 @@ fn window_and_monitor_info<T: WindowsApi>(api: &T) -> Option<(WindowHandle, Wind
   let monitor_info = api.get_monitor_info_for_window(window)?;
   Some((window, placement, monitor_info))
 }
+
+/// Returns the sizings that a window cycles through when it is repeatedly moved in the horizontal `direction`, each
+/// paired with the sizing that follows it. The third of the monitor is followed by `None` because the window moves to
+/// the next monitor from there.
+fn next_horizontal_sizing(work_area: Rect, direction: Direction, margin: i32) -> [(Sizing, Option<Sizing>); 3] {
+  let (half, two_thirds, third) = match direction {
+    Direction::Left => (
+      Sizing::left_half_of_screen(work_area, margin),
+      Sizing::left_two_thirds_of_screen(work_area, margin),
+      Sizing::left_third_of_screen(work_area, margin),
+    ),
+    _ => (
+      Sizing::right_half_of_screen(work_area, margin),
+      Sizing::right_two_thirds_of_screen(work_area, margin),
+      Sizing::right_third_of_screen(work_area, margin),
+    ),
+  };
+
+  [
+    (half, Some(two_thirds.clone())),
+    (two_thirds, Some(third.clone())),
+    (third, None),
+  ]
+}
@@ -1,5 +1,6 @@
 use crate::api::{MockWindowsApi, WindowsApi};
-use crate::common::{Direction, MonitorHandle, Point, Rect, Sizing, Window, WindowHandle};
+use crate::common::{Direction, FocusHistory, MonitorHandle, Point, Rect, Sizing, Window, WindowHandle};
+use crate::configuration_provider::{FocusCycleOrder, FocusFallbackPolicy};
 use crate::window_manager::WindowManager;
 use crate::window_manager::navigation::find_closest_window as super_find_closest_window;
 use crate::window_manager::navigation::select_window_in_direction;
@@ -22,19 +23,62 @@ fn select_window_in_direction_cycles_through_all_windows() {
   let windows = [&third, &first, &second];
 
   assert_eq!(
-    select_window_in_direction(&first.center, Direction::Right, &windows, Some(&first), true).map(|window| window.handle),
+    select_window_in_direction(
+      &first.center,
+      Direction::Right,
+      &windows,
+      Some(&first),
+      true,
+      &FocusHistory::default()
+    )
+    .map(|window| window.handle),
     Some(second.handle)
   );
   assert_eq!(
-    select_window_in_direction(&second.center, Direction::Right, &windows, Some(&second), true).map(|window| window.handle),
+    select_window_in_direction(
+      &second.center,
+      Direction::Right,
+      &windows,
+      Some(&second),
+      true,
+      &FocusHistory::default()
+    )
+    .map(|window| window.handle),
     Some(third.handle)
   );
   assert_eq!(
-    select_window_in_direction(&third.center, Direction::Right, &windows, Some(&third), true).map(|window| window.handle),
+    select_window_in_direction(
+      &third.center,
+      Direction::Right,
+      &windows,
+      Some(&third),
+      true,
+      &FocusHistory::default()
+    )
+    .map(|window| window.handle),
     Some(first.handle)
   );
 }
 
+#[test]
+fn select_window_in_direction_cycles_to_least_recently_visited_window_regardless_of_reference() {
+  let rect = Rect::new(0, 0, 100, 100);
+  let first = Window::new_test(1, rect);
+  let second = Window::new_test(2, rect);
+  let third = Window::new_test(3, rect);
+  let windows = [&third, &first, &second];
+  let mut focus_history = FocusHistory::default();
+  focus_history.record(first.handle);
+  focus_history.record(second.handle);
+
+  let selected = select_window_in_direction(&first.center, Direction::Left, &windows, Some(&first), true, &focus_history);
+  assert_eq!(selected.map(|window| window.handle), Some(third.handle));
+
+  focus_history.record(third.handle);
+  let selected = select_window_in_direction(&third.center, Direction::Left, &windows, Some(&third), true, &focus_history);
+  assert_eq!(selected.map(|window| window.handle), Some(first.handle));
+}
+
 #[test]
 fn select_window_in_direction_uses_direction_when_disabled() {
   let reference = Window::new_test(1, Rect::new(0, 0, 100, 100));
@@ -42,7 +86,14 @@ fn select_window_in_direction_uses_direction_when_disabled() {
   let right = Window::new_test(3, Rect::new(100, 0, 200, 100));
   let windows = [&reference, &same_center, &right];
 
-  let selected = select_window_in_direction(&reference.center, Direction::Right, &windows, Some(&reference), false);
+  let selected = select_window_in_direction(
+    &reference.center,
+    Direction::Right,
+    &windows,
+    Some(&reference),
+    false,
+    &FocusHistory::default(),
+  );
 
   assert_eq!(selected.map(|window| window.handle), Some(right.handle));
 }
@@ -54,7 +105,14 @@ fn select_window_in_direction_falls_back_to_closest_window_in_direction() {
   let furthest_right = Window::new_test(3, Rect::new(200, 0, 300, 100));
   let windows = [&reference, &furthest_right, &closest_right];
 
-  let selected = select_window_in_direction(&reference.center, Direction::Right, &windows, Some(&reference), true);
+  let selected = select_window_in_direction(
+    &reference.center,
+    Direction::Right,
+    &windows,
+    Some(&reference),
+    true,
+    &FocusHistory::default(),
+  );
 
   assert_eq!(selected.map(|window| window.handle), Some(closest_right.handle));
 }
@@ -96,6 +154,38 @@ fn move_cursor_does_nothing_when_there_is_no_window_or_monitor_to_move_to() {
   assert_eq!(manager.windows_api.get_cursor_position(), initial_cursor_position);
 }
 
+#[test]
+fn throw_cursor_moves_cursor_to_center_of_monitor_even_if_a_window_is_in_the_way() {
+  let current_monitor_handle = MonitorHandle::from(1);
+  let target_monitor_handle = MonitorHandle::from(2);
+  let target_monitor_area = Rect::new(200, 0, 400, 200);
+  let window_handle = WindowHandle::new(1);
+  let sizing = Sizing::right_half_of_screen(Rect::new(0, 0, 200, 180), 20);
+  MockWindowsApi::set_cursor_position(Point::new(0, 0));
+  MockWindowsApi::add_or_update_window(window_handle, "Test".to_string(), sizing, false, false, false);
+  MockWindowsApi::add_monitor(current_monitor_handle, Rect::new(0, 0, 200, 200), true);
+  MockWindowsApi::add_monitor(target_monitor_handle, target_monitor_area, false);
+  MockWindowsApi::place_window(window_handle, current_monitor_handle);
+  let mut manager = WindowManager::default(MockWindowsApi);
+
+  manager.throw_cursor(Direction::Right);
+
+  assert_eq!(manager.windows_api.get_cursor_position(), target_monitor_area.center());
+  assert_eq!(manager.windows_api.get_foreground_window(), None);
+}
+
+#[test]
+fn throw_cursor_does_nothing_when_there_is_no_monitor_in_direction() {
+  let initial_cursor_position = Point::new(0, 0);
+  MockWindowsApi::set_cursor_position(initial_cursor_position);
+  MockWindowsApi::add_monitor(MonitorHandle::from(1), Rect::new(0, 0, 200, 200), true);
+  let mut manager = WindowManager::default(MockWindowsApi);
+
+  manager.throw_cursor(Direction::Left);
+
+  assert_eq!(manager.windows_api.get_cursor_position(), initial_cursor_position);
+}
+
 #[test]
 fn close_window_does_not_move_cursor_to_closest_window_when_disabled() {
   let window_handle_1 = WindowHandle::new(1);
@@ -122,6 +212,221 @@ fn close_window_does_not_move_cursor_to_closest_window_when_disabled() {
   assert_eq!(manager.windows_api.get_cursor_position(), Point::default());
 }
 
+#[test]
+fn close_window_moves_focus_to_largest_window_when_policy_is_largest() {
+  let closed_window = WindowHandle::new(1);
+  let small_window = WindowHandle::new(2);
+  let large_window = WindowHandle::new(3);
+  MockWindowsApi::set_cursor_position(Point::new(50, 50));
+  MockWindowsApi::add_or_update_window(
+    closed_window,
+    "Closed".to_string(),
+    Sizing::new(0, 0, 100, 100),
+    false,
+    false,
+    true,
+  );
+  MockWindowsApi::add_or_update_window(
+    small_window,
+    "Small".to_string(),
+    Sizing::new(40, 40, 20, 20),
+    false,
+    false,
+    false,
+  );
+  MockWindowsApi::add_or_update_window(
+    large_window,
+    "Large".to_string(),
+    Sizing::new(200, 0, 400, 300),
+    false,
+    false,
+    false,
+  );
+  let mut manager = WindowManager::default(MockWindowsApi);
+  manager
+    .configuration_provider
+    .lock()
+    .unwrap()
+    .set_focus_fallback_policy(FocusFallbackPolicy::Largest);
+
+  manager.close_window();
+
+  assert_eq!(manager.windows_api.get_foreground_window(), Some(large_window));
+  assert_eq!(manager.windows_api.get_cursor_position(), Point::new(400, 150));
+}
+
+#[test]
+fn close_window_moves_focus_to_most_recently_used_window_when_policy_is_most_recently_used() {
+  let closed_window = WindowHandle::new(1);
+  let closest_window = WindowHandle::new(2);
+  let recent_window = WindowHandle::new(3);
+  MockWindowsApi::set_cursor_position(Point::new(50, 50));
+  MockWindowsApi::add_or_update_window(
+    closed_window,
+    "Closed".to_string(),
+    Sizing::new(0, 0, 100, 100),
+    false,
+    false,
+    false,
+  );
+  MockWindowsApi::add_or_update_window(
+    closest_window,
+    "Closest".to_string(),
+    Sizing::new(40, 40, 20, 20),
+    false,
+    false,
+    false,
+  );
+  MockWindowsApi::add_or_update_window(
+    recent_window,
+    "Recent".to_string(),
+    Sizing::new(200, 0, 400, 300),
+    false,
+    false,
+    true,
+  );
+  let mut manager = WindowManager::default(MockWindowsApi);
+  manager
+    .configuration_provider
+    .lock()
+    .unwrap()
+    .set_focus_fallback_policy(FocusFallbackPolicy::MostRecentlyUsed);
+  manager.spatial.record_foreground_window(&manager.windows_api);
+  MockWindowsApi::set_foreground_window(closed_window);
+  manager.spatial.record_foreground_window(&manager.windows_api);
+
+  manager.close_window();
+
+  assert_eq!(manager.windows_api.get_foreground_window(), Some(recent_window));
+}
+
+#[test]
+fn close_window_falls_back_to_closest_window_when_focus_history_is_empty() {
+  let closed_window = WindowHandle::new(1);
+  let closest_window = WindowHandle::new(2);
+  let far_window = WindowHandle::new(3);
+  MockWindowsApi::set_cursor_position(Point::new(50, 50));
+  MockWindowsApi::add_or_update_window(
+    closed_window,
+    "Closed".to_string(),
+    Sizing::new(0, 0, 100, 100),
+    false,
+    false,
+    true,
+  );
+  MockWindowsApi::add_or_update_window(
+    closest_window,
+    "Closest".to_string(),
+    Sizing::new(40, 40, 20, 20),
+    false,
+    false,
+    false,
+  );
+  MockWindowsApi::add_or_update_window(
+    far_window,
+    "Far".to_string(),
+    Sizing::new(200, 0, 400, 300),
+    false,
+    false,
+    false,
+  );
+  let mut manager = WindowManager::default(MockWindowsApi);
+  manager
+    .configuration_provider
+    .lock()
+    .unwrap()
+    .set_focus_fallback_policy(FocusFallbackPolicy::MostRecentlyUsed);
+
+  manager.close_window();
+
+  assert_eq!(manager.windows_api.get_foreground_window(), Some(closest_window));
+}
+
+#[test]
+fn cycle_focus_reaches_every_window_including_perfectly_overlapping_windows() {
+  let monitor_handle = MonitorHandle::from(1);
+  let left_window = WindowHandle::new(1);
+  let overlapping_window = WindowHandle::new(2);
+  let other_overlapping_window = WindowHandle::new(3);
+  let other_monitor_window = WindowHandle::new(4);
+  MockWindowsApi::add_monitor(monitor_handle, Rect::new(0, 0, 400, 200), true);
+  MockWindowsApi::add_monitor(MonitorHandle::from(2), Rect::new(400, 0, 800, 200), false);
+  let windows = [
+    (left_window, Sizing::new(0, 0, 100, 100), monitor_handle),
+    (overlapping_window, Sizing::new(200, 0, 100, 100), monitor_handle),
+    (other_overlapping_window, Sizing::new(200, 0, 100, 100), monitor_handle),
+    (other_monitor_window, Sizing::new(500, 0, 100, 100), MonitorHandle::from(2)),
+  ];
+  for (handle, sizing, monitor) in windows {
+    MockWindowsApi::add_or_update_window(handle, "Test".to_string(), sizing, false, false, handle == left_window);
+    MockWindowsApi::place_window(handle, monitor);
+  }
+  let mut manager = WindowManager::default(MockWindowsApi);
+
+  let mut focused_windows = Vec::new();
+  for _ in 0..3 {
+    assert!(manager.cycle_focus(true).is_success());
+    focused_windows.push(manager.windows_api.get_foreground_window().expect("a window has focus"));
+  }
+
+  assert_eq!(
+    focused_windows,
+    vec![overlapping_window, other_overlapping_window, left_window]
+  );
+  assert_eq!(manager.windows_api.get_cursor_position(), Point::new(50, 50));
+}
+
+#[test]
+fn cycle_focus_keeps_most_recently_used_order_while_cycling() {
+  let monitor_handle = MonitorHandle::from(1);
+  let handles = [WindowHandle::new(1), WindowHandle::new(2), WindowHandle::new(3)];
+  MockWindowsApi::add_monitor(monitor_handle, Rect::new(0, 0, 400, 200), true);
+  for (i, handle) in handles.iter().enumerate() {
+    let sizing = Sizing::new(i as i32 * 100, 0, 100, 100);
+    MockWindowsApi::add_or_update_window(*handle, "Test".to_string(), sizing, false, false, false);
+    MockWindowsApi::place_window(*handle, monitor_handle);
+  }
+  let mut manager = WindowManager::default(MockWindowsApi);
+  manager
+    .configuration_provider
+    .lock()
+    .unwrap()
+    .set_focus_cycle_order(FocusCycleOrder::MostRecentlyUsed);
+  for handle in [handles[0], handles[2], handles[1]] {
+    MockWindowsApi::set_foreground_window(handle);
+    manager.spatial.record_foreground_window(&manager.windows_api);
+  }
+
+  manager.cycle_focus(true);
+  manager.spatial.record_foreground_window(&manager.windows_api);
+  manager.cycle_focus(true);
+
+  assert_eq!(manager.windows_api.get_foreground_window(), Some(handles[0]));
+
+  manager.cycle_focus(false);
+
+  assert_eq!(manager.windows_api.get_foreground_window(), Some(handles[2]));
+}
+
+#[test]
+fn cycle_focus_is_a_no_op_without_another_window() {
+  let window_handle = WindowHandle::new(1);
+  MockWindowsApi::add_monitor(MonitorHandle::from(1), Rect::new(0, 0, 400, 200), true);
+  MockWindowsApi::add_or_update_window(
+    window_handle,
+    "Test".to_string(),
+    Sizing::new(0, 0, 100, 100),
+    false,
+    false,
+    true,
+  );
+  MockWindowsApi::place_window(window_handle, MonitorHandle::from(1));
+  let mut manager = WindowManager::default(MockWindowsApi);
+
+  assert!(!manager.cycle_focus(false).is_success());
+  assert_eq!(manager.windows_api.get_foreground_window(), Some(window_handle));
+}
+
 #[test]
 fn find_closest_window_returns_none_when_no_windows_are_visible() {
   let cursor_position = Point::new(100, 100);
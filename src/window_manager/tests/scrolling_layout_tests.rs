@@ -2,6 +2,7 @@ use crate::api::{MockWindowsApi, WindowsApi};
 use crate::common::{Direction, Point, Rect, Sizing, WindowHandle, WindowPlacement};
 use crate::window_manager::WindowManager;
 use crate::window_manager::tests::test_support::scrolling_manager;
+use crate::workspace_backend::WorkspaceBackend;
 
 #[test]
 fn disabled_scrolling_layout_does_not_reposition_windows() {
@@ -1,5 +1,6 @@
 use crate::api::{MockWindowsApi, WindowsApi};
-use crate::common::{Direction, MonitorHandle, Point, Rect, Sizing, WindowHandle, WindowPlacement};
+use crate::common::{Direction, MonitorHandle, MonitorMargins, Point, Rect, Sizing, WindowHandle, WindowPlacement};
+use crate::configuration_provider::WindowRule;
 use crate::utils::MINIMUM_WINDOW_DIMENSION;
 use crate::window_manager::WindowManager;
 
@@ -28,6 +29,46 @@ fn move_window_on_the_same_monitor() {
   assert_eq!(manager.windows_api.get_cursor_position(), expected_cursor_position)
 }
 
+#[test]
+fn move_window_recentres_modal_dialog_over_window() {
+  let monitor_handle = MonitorHandle::from(1);
+  let window_handle = WindowHandle::new(1);
+  let dialog_handle = WindowHandle::new(2);
+  let sizing = Sizing::new(20, 20, 160, 160);
+  MockWindowsApi::add_or_update_window(window_handle, "Test Window".to_string(), sizing, false, false, true);
+  MockWindowsApi::add_or_update_window(
+    dialog_handle,
+    "Dialog".to_string(),
+    Sizing::new(80, 80, 40, 40),
+    false,
+    false,
+    false,
+  );
+  MockWindowsApi::set_window_owner(dialog_handle, window_handle);
+  MockWindowsApi::mark_window_disabled(window_handle);
+  MockWindowsApi::add_monitor(monitor_handle, Rect::new(0, 0, 200, 200), true);
+  MockWindowsApi::place_window(window_handle, monitor_handle);
+  MockWindowsApi::place_window(dialog_handle, monitor_handle);
+  let mut manager = WindowManager::default(MockWindowsApi);
+
+  manager.move_window(Direction::Right);
+
+  let window_rect = manager
+    .windows_api
+    .get_window_rect(window_handle)
+    .expect("Failed to get window rect");
+  let dialog_rect = manager
+    .windows_api
+    .get_window_rect(dialog_handle)
+    .expect("Failed to get dialog rect");
+  assert_eq!(
+    Point::from_center_of_rect(&dialog_rect),
+    Point::from_center_of_rect(&window_rect)
+  );
+  assert_eq!(dialog_rect.width(), 40);
+  assert_eq!(dialog_rect.height(), 40);
+}
+
 #[test]
 fn move_window_when_window_is_already_at_target_location() {
   let monitor_handle = MonitorHandle::from(1);
@@ -67,6 +108,214 @@ fn move_window_to_another_monitor() {
   assert_eq!(manager.windows_api.get_cursor_position(), Point::new(300, 100))
 }
 
+#[test]
+fn move_window_snaps_window_on_half_into_quarters() {
+  let monitor_handle = MonitorHandle::from(1);
+  let window_handle = WindowHandle::new(1);
+  let work_area = Rect::new(0, 0, 200, 180);
+  let sizing = Sizing::left_half_of_screen(work_area, 20);
+  MockWindowsApi::add_or_update_window(window_handle, "Test Window".to_string(), sizing, false, false, true);
+  MockWindowsApi::add_monitor(monitor_handle, Rect::new(0, 0, 200, 200), true);
+  MockWindowsApi::place_window(window_handle, monitor_handle);
+  let mut manager = WindowManager::default(MockWindowsApi);
+
+  manager.move_window(Direction::Up);
+
+  let top_left = Sizing::quarter_of_screen(work_area, Direction::Left, Direction::Up, 20);
+  let actual_placement = manager.windows_api.get_window_placement(window_handle);
+  assert_eq!(actual_placement, Some(WindowPlacement::new_from_sizing(top_left.clone())));
+  assert_eq!(
+    manager.windows_api.get_cursor_position(),
+    Point::from_center_of_sizing(&top_left)
+  );
+
+  manager.move_window(Direction::Right);
+
+  let top_right = Sizing::quarter_of_screen(work_area, Direction::Right, Direction::Up, 20);
+  let actual_placement = manager.windows_api.get_window_placement(window_handle);
+  assert_eq!(actual_placement, Some(WindowPlacement::new_from_sizing(top_right)));
+}
+
+#[test]
+fn move_window_cycles_through_half_two_thirds_and_third_when_snapping_to_thirds() {
+  let monitor_handle = MonitorHandle::from(1);
+  let window_handle = WindowHandle::new(1);
+  let work_area = Rect::new(0, 0, 200, 180);
+  MockWindowsApi::add_or_update_window(
+    window_handle,
+    "Test Window".to_string(),
+    Sizing::near_maximised(work_area, 20),
+    false,
+    false,
+    true,
+  );
+  MockWindowsApi::add_monitor(monitor_handle, Rect::new(0, 0, 200, 200), true);
+  MockWindowsApi::add_monitor(2.into(), Rect::new(200, 0, 400, 200), false);
+  MockWindowsApi::place_window(window_handle, monitor_handle);
+  let mut manager = WindowManager::default(MockWindowsApi);
+  manager.configuration_provider.lock().unwrap().set_snap_to_thirds(true);
+
+  for expected_sizing in [
+    Sizing::left_half_of_screen(work_area, 20),
+    Sizing::left_two_thirds_of_screen(work_area, 20),
+    Sizing::left_third_of_screen(work_area, 20),
+  ] {
+    manager.move_window(Direction::Left);
+
+    let actual_placement = manager.windows_api.get_window_placement(window_handle);
+    assert_eq!(actual_placement, Some(WindowPlacement::new_from_sizing(expected_sizing)));
+  }
+
+  manager.move_window(Direction::Right);
+
+  let actual_placement = manager.windows_api.get_window_placement(window_handle);
+  let expected_sizing = Sizing::right_half_of_screen(work_area, 20);
+  assert_eq!(actual_placement, Some(WindowPlacement::new_from_sizing(expected_sizing)));
+}
+
+#[test]
+fn move_window_to_another_monitor_when_window_is_on_third_at_edge_of_monitor() {
+  let monitor_handle_1 = MonitorHandle::from(1);
+  let window_handle = WindowHandle::new(1);
+  let sizing = Sizing::right_third_of_screen(Rect::new(0, 0, 200, 180), 20);
+  MockWindowsApi::add_or_update_window(window_handle, "Test Window".to_string(), sizing, false, false, true);
+  MockWindowsApi::add_monitor(monitor_handle_1, Rect::new(0, 0, 200, 200), true);
+  MockWindowsApi::add_monitor(2.into(), Rect::new(200, 0, 400, 200), false);
+  MockWindowsApi::place_window(window_handle, monitor_handle_1);
+  let mut manager = WindowManager::default(MockWindowsApi);
+  manager.configuration_provider.lock().unwrap().set_snap_to_thirds(true);
+
+  manager.move_window(Direction::Right);
+
+  let actual_placement = manager.windows_api.get_window_placement(window_handle);
+  let expected_placement = WindowPlacement::new_from_sizing(Sizing::near_maximised(Rect::new(200, 0, 400, 180), 20));
+  assert_eq!(actual_placement, Some(expected_placement));
+}
+
+#[test]
+fn move_window_to_another_monitor_when_window_is_in_quarter_at_edge_of_monitor() {
+  let monitor_handle_1 = MonitorHandle::from(1);
+  let window_handle = WindowHandle::new(1);
+  let sizing = Sizing::quarter_of_screen(Rect::new(0, 0, 200, 180), Direction::Right, Direction::Down, 20);
+  MockWindowsApi::add_or_update_window(window_handle, "Test Window".to_string(), sizing, false, false, true);
+  MockWindowsApi::add_monitor(monitor_handle_1, Rect::new(0, 0, 200, 200), true);
+  MockWindowsApi::add_monitor(2.into(), Rect::new(200, 0, 400, 200), false);
+  MockWindowsApi::place_window(window_handle, monitor_handle_1);
+  let mut manager = WindowManager::default(MockWindowsApi);
+
+  manager.move_window(Direction::Right);
+
+  let actual_placement = manager.windows_api.get_window_placement(window_handle);
+  let expected_placement = WindowPlacement::new_from_sizing(Sizing::near_maximised(Rect::new(200, 0, 400, 180), 20));
+  assert_eq!(actual_placement, Some(expected_placement));
+}
+
+#[test]
+fn move_window_to_another_monitor_when_window_is_within_size_tolerance_of_rule() {
+  let monitor_handle_1 = MonitorHandle::from(1);
+  let window_handle = WindowHandle::new(1);
+  let mut sizing = Sizing::right_half_of_screen(Rect::new(0, 0, 200, 180), 20);
+  sizing.width -= 6;
+  sizing.height -= 3;
+  MockWindowsApi::add_or_update_window(window_handle, "Terminal".to_string(), sizing, false, false, true);
+  MockWindowsApi::add_monitor(monitor_handle_1, Rect::new(0, 0, 200, 200), true);
+  MockWindowsApi::add_monitor(2.into(), Rect::new(200, 0, 400, 200), false);
+  MockWindowsApi::place_window(window_handle, monitor_handle_1);
+  let mut manager = WindowManager::default(MockWindowsApi);
+  manager.configuration_provider.lock().unwrap().add_window_rule(WindowRule {
+    title_contains: Some("Terminal".to_string()),
+    size_tolerance_in_px: Some(8),
+    ..WindowRule::default()
+  });
+
+  manager.move_window(Direction::Right);
+
+  let actual_placement = manager.windows_api.get_window_placement(window_handle);
+  let expected_placement = WindowPlacement::new_from_sizing(Sizing::near_maximised(Rect::new(200, 0, 400, 180), 20));
+  assert_eq!(actual_placement, Some(expected_placement));
+}
+
+#[test]
+fn finish_mouse_drag_snaps_window_into_half_it_mostly_overlaps() {
+  let monitor_handle = MonitorHandle::from(1);
+  let window_handle = WindowHandle::new(1);
+  let sizing = Sizing::new(30, 25, 60, 135);
+  MockWindowsApi::add_or_update_window(window_handle, "Test Window".to_string(), sizing, false, false, true);
+  MockWindowsApi::add_monitor(monitor_handle, Rect::new(0, 0, 200, 200), true);
+  MockWindowsApi::place_window(window_handle, monitor_handle);
+  let mut manager = WindowManager::default(MockWindowsApi);
+  manager
+    .configuration_provider
+    .lock()
+    .unwrap()
+    .set_auto_snap_threshold_in_percent(75);
+
+  manager.finish_mouse_drag(window_handle, None);
+
+  let actual_placement = manager.windows_api.get_window_placement(window_handle);
+  let expected_sizing = Sizing::left_half_of_screen(Rect::new(0, 0, 200, 180), 20);
+  assert_eq!(actual_placement, Some(WindowPlacement::new_from_sizing(expected_sizing)));
+}
+
+#[test]
+fn finish_mouse_drag_keeps_window_where_it_was_dropped_if_overlap_is_below_threshold() {
+  let monitor_handle = MonitorHandle::from(1);
+  let window_handle = WindowHandle::new(1);
+  let sizing = Sizing::new(30, 25, 60, 135);
+  MockWindowsApi::add_or_update_window(window_handle, "Test Window".to_string(), sizing.clone(), false, false, true);
+  MockWindowsApi::add_monitor(monitor_handle, Rect::new(0, 0, 200, 200), true);
+  MockWindowsApi::place_window(window_handle, monitor_handle);
+  let mut manager = WindowManager::default(MockWindowsApi);
+  manager
+    .configuration_provider
+    .lock()
+    .unwrap()
+    .set_auto_snap_threshold_in_percent(90);
+
+  manager.finish_mouse_drag(window_handle, None);
+
+  let actual_placement = manager.windows_api.get_window_placement(window_handle);
+  assert_eq!(actual_placement, Some(WindowPlacement::new_from_sizing(sizing)));
+}
+
+#[test]
+fn finish_mouse_drag_snaps_window_into_snap_zone_regardless_of_threshold() {
+  let monitor_handle = MonitorHandle::from(1);
+  let window_handle = WindowHandle::new(1);
+  let sizing = Sizing::new(30, 25, 60, 135);
+  MockWindowsApi::add_or_update_window(window_handle, "Test Window".to_string(), sizing, false, false, true);
+  MockWindowsApi::add_monitor(monitor_handle, Rect::new(0, 0, 200, 200), true);
+  MockWindowsApi::place_window(window_handle, monitor_handle);
+  let mut manager = WindowManager::default(MockWindowsApi);
+  let snap_zone = Sizing::quarter_of_screen(Rect::new(0, 0, 200, 180), Direction::Right, Direction::Down, 20);
+
+  manager.finish_mouse_drag(window_handle, Some(snap_zone.clone()));
+
+  let actual_placement = manager.windows_api.get_window_placement(window_handle);
+  assert_eq!(actual_placement, Some(WindowPlacement::new_from_sizing(snap_zone)));
+}
+
+#[test]
+fn place_window_in_zone_moves_foreground_window_into_snap_zone_selected_in_place_mode() {
+  let monitor_handle = MonitorHandle::from(1);
+  let window_handle = WindowHandle::new(1);
+  let sizing = Sizing::new(30, 25, 60, 135);
+  MockWindowsApi::add_or_update_window(window_handle, "Test Window".to_string(), sizing, false, false, true);
+  MockWindowsApi::add_monitor(monitor_handle, Rect::new(0, 0, 200, 200), true);
+  MockWindowsApi::place_window(window_handle, monitor_handle);
+  let mut manager = WindowManager::default(MockWindowsApi);
+
+  let (window, work_area, zones) = manager.get_place_mode_zones().expect("Expected place mode to apply");
+  let outcome = manager.place_window_in_zone(window, zones[1].clone());
+
+  assert_eq!(window, window_handle);
+  assert_eq!(zones, Sizing::snap_zones(Rect::new(0, 0, 200, 180), 20));
+  assert_eq!(work_area, Rect::new(0, 0, 200, 180));
+  assert!(outcome.is_success());
+  let actual_placement = manager.windows_api.get_window_placement(window_handle);
+  assert_eq!(actual_placement, Some(WindowPlacement::new_from_sizing(zones[1].clone())));
+}
+
 #[test]
 fn resize_spatial_window_steps_three_quarter_left_down_to_left_half_of_screen() {
   let monitor_handle = MonitorHandle::from(1);
@@ -772,3 +1021,143 @@ fn resize_spatial_window_steps_near_maximised_down_to_three_quarter_up() {
     Point::from_center_of_sizing(&expected_sizing)
   );
 }
+
+#[test]
+fn move_shared_edge_resizes_foreground_window_and_its_neighbour_like_a_splitter() {
+  let monitor_handle = MonitorHandle::from(1);
+  let left_window = WindowHandle::new(1);
+  let right_window = WindowHandle::new(2);
+  let work_area = Rect::new(0, 0, 2000, 1000);
+  let left_sizing = Sizing::left_half_of_screen(work_area, 20);
+  let right_sizing = Sizing::right_half_of_screen(work_area, 20);
+  MockWindowsApi::add_or_update_window(left_window, "Left".to_string(), left_sizing, false, false, false);
+  MockWindowsApi::add_or_update_window(right_window, "Right".to_string(), right_sizing, false, false, true);
+  MockWindowsApi::add_monitor(monitor_handle, Rect::new(0, 0, 2000, 1020), true);
+  MockWindowsApi::place_window(left_window, monitor_handle);
+  MockWindowsApi::place_window(right_window, monitor_handle);
+  let mut manager = WindowManager::default(MockWindowsApi);
+
+  manager.move_shared_edge(Direction::Left);
+
+  assert_eq!(
+    manager.windows_api.get_window_placement(left_window).unwrap().normal_position,
+    Rect::new(20, 20, 890, 980)
+  );
+  assert_eq!(
+    manager
+      .windows_api
+      .get_window_placement(right_window)
+      .unwrap()
+      .normal_position,
+    Rect::new(910, 20, 1980, 980)
+  );
+}
+
+#[test]
+fn move_shared_edge_does_nothing_if_foreground_window_has_no_neighbours() {
+  let monitor_handle = MonitorHandle::from(1);
+  let window_handle = WindowHandle::new(1);
+  let sizing = Sizing::left_half_of_screen(Rect::new(0, 0, 2000, 1000), 20);
+  MockWindowsApi::add_or_update_window(window_handle, "Test Window".to_string(), sizing.clone(), false, false, true);
+  MockWindowsApi::add_monitor(monitor_handle, Rect::new(0, 0, 2000, 1020), true);
+  MockWindowsApi::place_window(window_handle, monitor_handle);
+  let mut manager = WindowManager::default(MockWindowsApi);
+
+  manager.move_shared_edge(Direction::Right);
+
+  assert_eq!(
+    manager.windows_api.get_window_placement(window_handle),
+    Some(WindowPlacement::new_from_sizing(sizing))
+  );
+}
+
+#[test]
+fn refit_windows_after_work_area_change_refits_snapped_windows_only() {
+  let monitor_handle = MonitorHandle::from(1);
+  let monitor_area = Rect::new(0, 0, 400, 200);
+  let old_work_area = Rect::new(0, 0, 400, 180);
+  let new_work_area = Rect::new(0, 40, 400, 200);
+  let snapped_window = WindowHandle::new(1);
+  let free_window = WindowHandle::new(2);
+  let free_sizing = Sizing::new(100, 50, 120, 100);
+  MockWindowsApi::add_monitor_with_full_details([1; 32], monitor_handle, monitor_area, old_work_area, true);
+  MockWindowsApi::add_or_update_window(
+    snapped_window,
+    "Snapped".to_string(),
+    Sizing::left_half_of_screen(old_work_area, 20),
+    false,
+    false,
+    true,
+  );
+  MockWindowsApi::add_or_update_window(free_window, "Free".to_string(), free_sizing.clone(), false, false, false);
+  let mut manager = WindowManager::default(MockWindowsApi);
+  manager.spatial.record_work_areas(&manager.windows_api);
+  MockWindowsApi::set_monitor_work_area(monitor_handle, new_work_area);
+
+  manager.spatial.refit_windows_after_work_area_change(
+    &manager.windows_api,
+    &manager.placement,
+    &MonitorMargins::new(20),
+    &[[1; 32]],
+  );
+
+  assert_eq!(
+    manager.windows_api.get_window_placement(snapped_window),
+    Some(WindowPlacement::new_from_sizing(Sizing::left_half_of_screen(
+      new_work_area,
+      20
+    )))
+  );
+  assert_eq!(
+    manager.windows_api.get_window_placement(free_window),
+    Some(WindowPlacement::new_from_sizing(free_sizing))
+  );
+}
+
+#[test]
+fn refit_windows_after_work_area_change_does_nothing_when_work_area_is_unchanged() {
+  let monitor_handle = MonitorHandle::from(1);
+  let window_handle = WindowHandle::new(1);
+  let sizing = Sizing::near_maximised(Rect::new(0, 0, 200, 180), 20);
+  MockWindowsApi::add_monitor(monitor_handle, Rect::new(0, 0, 200, 200), true);
+  MockWindowsApi::add_or_update_window(window_handle, "Test Window".to_string(), sizing.clone(), false, false, true);
+  let mut manager = WindowManager::default(MockWindowsApi);
+  manager.spatial.record_work_areas(&manager.windows_api);
+
+  manager.spatial.refit_windows_after_work_area_change(
+    &manager.windows_api,
+    &manager.placement,
+    &MonitorMargins::new(20),
+    &[[1; 32]],
+  );
+
+  assert_eq!(
+    manager.windows_api.get_window_placement(window_handle),
+    Some(WindowPlacement::new_from_sizing(sizing))
+  );
+}
+
+#[test]
+fn refit_windows_after_work_area_change_ignores_monitors_that_are_not_given() {
+  let monitor_handle = MonitorHandle::from(1);
+  let old_work_area = Rect::new(0, 0, 400, 180);
+  let sizing = Sizing::left_half_of_screen(old_work_area, 20);
+  let window_handle = WindowHandle::new(1);
+  MockWindowsApi::add_monitor_with_full_details([1; 32], monitor_handle, Rect::new(0, 0, 400, 200), old_work_area, true);
+  MockWindowsApi::add_or_update_window(window_handle, "Snapped".to_string(), sizing.clone(), false, false, true);
+  let mut manager = WindowManager::default(MockWindowsApi);
+  manager.spatial.record_work_areas(&manager.windows_api);
+  MockWindowsApi::set_monitor_work_area(monitor_handle, Rect::new(0, 40, 400, 200));
+
+  manager.spatial.refit_windows_after_work_area_change(
+    &manager.windows_api,
+    &manager.placement,
+    &MonitorMargins::new(20),
+    &[[2; 32]],
+  );
+
+  assert_eq!(
+    manager.windows_api.get_window_placement(window_handle),
+    Some(WindowPlacement::new_from_sizing(sizing))
+  );
+}
@@ -12,11 +12,16 @@ impl WindowManager<MockWindowsApi> {
       configuration_provider: Arc::new(Mutex::new(ConfigurationProvider::default())),
       placement: Default::default(),
       allow_moving_cursor_after_close_or_minimise: true,
+      monocle: Default::default(),
       scrolling: Default::default(),
       spatial: Default::default(),
+      tiling: Default::default(),
       workspace_manager: WorkspaceManager::default(),
       virtual_desktop_manager: None,
       windows_api: api,
+      pending_close_confirmation: None,
+      rule_placed_windows: Default::default(),
+      free_floating_monitors: Default::default(),
     }
   }
 }
@@ -32,11 +37,16 @@ pub(super) fn scrolling_manager() -> (WindowManager<MockWindowsApi>, tempfile::T
     configuration_provider,
     placement: Default::default(),
     allow_moving_cursor_after_close_or_minimise: true,
+    monocle: Default::default(),
     scrolling: Default::default(),
     spatial: Default::default(),
+    tiling: Default::default(),
     workspace_manager,
     virtual_desktop_manager: None,
     windows_api: MockWindowsApi,
+    pending_close_confirmation: None,
+    rule_placed_windows: Default::default(),
+    free_floating_monitors: Default::default(),
   };
   (manager, directory)
 }
@@ -1,11 +1,14 @@
 use crate::api::{MockWindowsApi, WindowsApi};
 use crate::common::{
-  Direction, Monitor, MonitorHandle, PersistentWorkspaceId, Point, Rect, Sizing, WindowHandle, WindowPlacement, Workspace,
+  CommandOutcome, Direction, Monitor, MonitorHandle, PersistentWorkspaceId, Point, Rect, Sizing, WindowHandle,
+  WindowPlacement, WindowQuery, WindowVisibility, Workspace, geometry,
 };
-use crate::configuration_provider::{ConfigurationProvider, Layout};
+use crate::configuration_provider::{ConfigurationProvider, Layout, WindowRule};
+use crate::files::{IgnoredWindow, IgnoredWindowsFile, SessionWindow};
 use crate::utils::create_temp_directory;
 use crate::window_manager::WindowManager;
 use crate::window_manager::tests::test_support::scrolling_manager;
+use crate::workspace_backend::WorkspaceBackend;
 use crate::workspace_manager::WorkspaceManager;
 use std::sync::{Arc, Mutex};
 
@@ -63,11 +66,16 @@ fn vertical_mixed_layout_manager_with_widths(
     configuration_provider,
     placement: Default::default(),
     allow_moving_cursor_after_close_or_minimise: true,
+    monocle: Default::default(),
     scrolling: Default::default(),
     spatial: Default::default(),
+    tiling: Default::default(),
     workspace_manager,
     virtual_desktop_manager: None,
     windows_api: MockWindowsApi,
+    pending_close_confirmation: None,
+    rule_placed_windows: Default::default(),
+    free_floating_monitors: Default::default(),
   };
   manager.reconcile_layouts();
   (manager, target_monitor)
@@ -116,11 +124,16 @@ fn move_window_with_mixed_layout_routes_move_by_foreground_monitor() {
     configuration_provider,
     placement: Default::default(),
     allow_moving_cursor_after_close_or_minimise: true,
+    monocle: Default::default(),
     scrolling: Default::default(),
     spatial: Default::default(),
+    tiling: Default::default(),
     workspace_manager,
     virtual_desktop_manager: None,
     windows_api: MockWindowsApi,
+    pending_close_confirmation: None,
+    rule_placed_windows: Default::default(),
+    free_floating_monitors: Default::default(),
   };
 
   manager.move_window(Direction::Up);
@@ -386,11 +399,16 @@ fn move_window_with_spatial_monitor_crossing_is_adopted_by_scrolling_reconciliat
     configuration_provider,
     placement: Default::default(),
     allow_moving_cursor_after_close_or_minimise: true,
+    monocle: Default::default(),
     scrolling: Default::default(),
     spatial: Default::default(),
+    tiling: Default::default(),
     workspace_manager,
     virtual_desktop_manager: None,
     windows_api: MockWindowsApi,
+    pending_close_confirmation: None,
+    rule_placed_windows: Default::default(),
+    free_floating_monitors: Default::default(),
   };
 
   manager.move_window(Direction::Left);
@@ -529,11 +547,16 @@ fn reconcile_layouts_only_manages_scrolling_monitors() {
     configuration_provider,
     placement: Default::default(),
     allow_moving_cursor_after_close_or_minimise: true,
+    monocle: Default::default(),
     scrolling: Default::default(),
     spatial: Default::default(),
+    tiling: Default::default(),
     workspace_manager,
     virtual_desktop_manager: None,
     windows_api: MockWindowsApi,
+    pending_close_confirmation: None,
+    rule_placed_windows: Default::default(),
+    free_floating_monitors: Default::default(),
   };
 
   manager.reconcile_layouts();
@@ -559,11 +582,16 @@ fn reconcile_layouts_when_changing_default_from_spatial_to_scrolling_adopts_acti
     configuration_provider: configuration_provider.clone(),
     placement: Default::default(),
     allow_moving_cursor_after_close_or_minimise: true,
+    monocle: Default::default(),
     scrolling: Default::default(),
     spatial: Default::default(),
+    tiling: Default::default(),
     workspace_manager,
     virtual_desktop_manager: None,
     windows_api: MockWindowsApi,
+    pending_close_confirmation: None,
+    rule_placed_windows: Default::default(),
+    free_floating_monitors: Default::default(),
   };
   assert!(manager.scrolling.get_workspace_containing(1.into()).is_none());
 
@@ -683,11 +711,16 @@ fn move_window_to_workspace_when_moving_from_spatial_to_scrolling_inserts_strip_
     configuration_provider,
     placement: Default::default(),
     allow_moving_cursor_after_close_or_minimise: true,
+    monocle: Default::default(),
     scrolling: Default::default(),
     spatial: Default::default(),
+    tiling: Default::default(),
     workspace_manager,
     virtual_desktop_manager: None,
     windows_api: MockWindowsApi,
+    pending_close_confirmation: None,
+    rule_placed_windows: Default::default(),
+    free_floating_monitors: Default::default(),
   };
   manager.reconcile_layouts();
 
@@ -698,3 +731,837 @@ fn move_window_to_workspace_when_moving_from_spatial_to_scrolling_inserts_strip_
     Some(primary_workspace.into())
   );
 }
+
+#[test]
+fn is_foreground_window_out_of_reach_returns_true_for_elevated_window_when_not_running_as_admin() {
+  MockWindowsApi::reset();
+  let handle = WindowHandle::new(1);
+  MockWindowsApi::add_or_update_window(
+    handle,
+    "Elevated".to_string(),
+    Sizing::new(0, 0, 400, 300),
+    false,
+    false,
+    true,
+  );
+  MockWindowsApi::mark_window_elevated(handle);
+  MockWindowsApi::set_running_as_admin(false);
+  let manager = WindowManager::default(MockWindowsApi);
+
+  let result = manager.is_foreground_window_out_of_reach();
+
+  assert!(result);
+}
+
+#[test]
+fn is_foreground_window_out_of_reach_returns_false_for_elevated_window_when_running_as_admin() {
+  MockWindowsApi::reset();
+  let handle = WindowHandle::new(1);
+  MockWindowsApi::add_or_update_window(
+    handle,
+    "Elevated".to_string(),
+    Sizing::new(0, 0, 400, 300),
+    false,
+    false,
+    true,
+  );
+  MockWindowsApi::mark_window_elevated(handle);
+  let manager = WindowManager::default(MockWindowsApi);
+
+  let result = manager.is_foreground_window_out_of_reach();
+
+  assert!(!result);
+}
+
+#[test]
+fn is_foreground_window_out_of_reach_returns_false_for_regular_window_when_not_running_as_admin() {
+  MockWindowsApi::reset();
+  let handle = WindowHandle::new(1);
+  MockWindowsApi::add_or_update_window(handle, "Regular".to_string(), Sizing::new(0, 0, 400, 300), false, false, true);
+  MockWindowsApi::set_running_as_admin(false);
+  let manager = WindowManager::default(MockWindowsApi);
+
+  let result = manager.is_foreground_window_out_of_reach();
+
+  assert!(!result);
+}
+
+#[test]
+fn switch_to_adjacent_workspace_wraps_around_on_monitor_under_cursor() {
+  MockWindowsApi::reset();
+  let directory = create_temp_directory();
+  let mut manager = WindowManager::default(MockWindowsApi);
+  manager.workspace_manager = WorkspaceManager::new_test(true, directory.path().join("workspaces.toml"));
+  let primary_active_workspace = PersistentWorkspaceId::from(*crate::workspace_manager::tests::primary_active_ws_id());
+  let primary_inactive_workspace = PersistentWorkspaceId::from(*crate::workspace_manager::tests::primary_inactive_ws_id());
+
+  let first = manager.switch_to_adjacent_workspace(true);
+  let second = manager.switch_to_adjacent_workspace(true);
+
+  assert_eq!(first, Ok(primary_inactive_workspace));
+  assert_eq!(second, Ok(primary_active_workspace));
+  assert!(manager.workspace_manager.is_workspace_active(primary_active_workspace));
+}
+
+#[test]
+fn switch_workspace_reports_whether_it_switched() {
+  MockWindowsApi::reset();
+  let directory = create_temp_directory();
+  let mut manager = WindowManager::default(MockWindowsApi);
+  manager.workspace_manager = WorkspaceManager::new_test(true, directory.path().join("workspaces.toml"));
+  let primary_active_workspace = PersistentWorkspaceId::from(*crate::workspace_manager::tests::primary_active_ws_id());
+  let primary_inactive_workspace = PersistentWorkspaceId::from(*crate::workspace_manager::tests::primary_inactive_ws_id());
+  let unknown_workspace = PersistentWorkspaceId::new_test(9);
+
+  let already_active = manager.switch_workspace(primary_active_workspace);
+  let unknown = manager.switch_workspace(unknown_workspace);
+  let switched = manager.switch_workspace(primary_inactive_workspace);
+
+  assert_eq!(
+    already_active,
+    CommandOutcome::NoOp(format!(
+      "workspace [{primary_active_workspace}] is already the current workspace"
+    ))
+  );
+  assert_eq!(
+    unknown,
+    CommandOutcome::Error(format!("workspace [{unknown_workspace}] does not exist"))
+  );
+  assert_eq!(switched, CommandOutcome::Success);
+}
+
+#[test]
+fn reconcile_layouts_near_maximises_window_matching_rule_only_when_first_shown() {
+  MockWindowsApi::reset();
+  let directory = create_temp_directory();
+  let mut manager = WindowManager::default(MockWindowsApi);
+  manager.workspace_manager = WorkspaceManager::new_test(true, directory.path().join("workspaces.toml"));
+  manager.configuration_provider.lock().unwrap().add_window_rule(WindowRule {
+    title_contains: Some("Calculator".to_string()),
+    auto_near_maximise: true,
+    ..WindowRule::default()
+  });
+  manager.reconcile_layouts();
+  let primary_monitor = crate::workspace_manager::tests::primary_monitor();
+  let handle = WindowHandle::new(2);
+  let original_sizing = Sizing::new(100, 100, 200, 200);
+  MockWindowsApi::add_or_update_window(handle, "Calculator".to_string(), original_sizing.clone(), false, false, false);
+  MockWindowsApi::place_window(handle, primary_monitor.handle);
+
+  manager.reconcile_layouts();
+
+  let expected = Rect::from(Sizing::near_maximised(primary_monitor.work_area, 20));
+  assert_eq!(MockWindowsApi.get_window_rect(handle), Some(expected));
+
+  MockWindowsApi::add_or_update_window(handle, "Calculator".to_string(), original_sizing.clone(), false, false, false);
+  manager.reconcile_layouts();
+
+  assert_eq!(MockWindowsApi.get_window_rect(handle), Some(Rect::from(original_sizing)));
+}
+
+#[test]
+fn reconcile_layouts_moves_window_matching_rule_to_workspace_and_reconcile_window_rules_moves_it_back() {
+  MockWindowsApi::reset();
+  let directory = create_temp_directory();
+  let mut manager = WindowManager::default(MockWindowsApi);
+  manager.workspace_manager = WorkspaceManager::new_test(true, directory.path().join("workspaces.toml"));
+  manager.configuration_provider.lock().unwrap().add_window_rule(WindowRule {
+    title_contains: Some("Slack".to_string()),
+    workspace: Some(2),
+    ..WindowRule::default()
+  });
+  manager.reconcile_layouts();
+  let primary_monitor = crate::workspace_manager::tests::primary_monitor();
+  let handle = WindowHandle::new(2);
+  let sizing = Sizing::new(100, 100, 200, 200);
+  MockWindowsApi::add_or_update_window(handle, "Slack".to_string(), sizing.clone(), false, false, false);
+  MockWindowsApi::place_window(handle, primary_monitor.handle);
+
+  manager.reconcile_layouts();
+
+  assert!(MockWindowsApi.is_window_hidden(&handle));
+  assert!(matches!(manager.reconcile_window_rules(), CommandOutcome::NoOp(_)));
+
+  manager.configuration_provider.lock().unwrap().clear_window_rules();
+  let outcome = manager.reconcile_window_rules();
+
+  assert_eq!(outcome, CommandOutcome::Success);
+  assert!(!MockWindowsApi.is_window_hidden(&handle));
+  assert_eq!(MockWindowsApi.get_window_rect(handle), Some(Rect::from(sizing)));
+  assert!(manager.rule_placed_windows.is_empty());
+}
+
+#[test]
+fn rescue_offscreen_windows_moves_only_windows_that_are_off_screen_onto_nearest_monitor() {
+  MockWindowsApi::reset();
+  let mut manager = WindowManager::default(MockWindowsApi);
+  MockWindowsApi::add_monitor(MonitorHandle::from(1), Rect::new(0, 0, 1920, 1080), true);
+  let offscreen_handle = WindowHandle::new(1);
+  let visible_handle = WindowHandle::new(2);
+  let visible_sizing = Sizing::new(1800, 100, 400, 300);
+  MockWindowsApi::add_or_update_window(
+    offscreen_handle,
+    "Off-screen".to_string(),
+    Sizing::new(-2000, 0, 800, 600),
+    false,
+    false,
+    false,
+  );
+  MockWindowsApi::add_or_update_window(
+    visible_handle,
+    "Partially visible".to_string(),
+    visible_sizing.clone(),
+    false,
+    false,
+    true,
+  );
+
+  let outcome = manager.rescue_offscreen_windows();
+
+  assert_eq!(outcome, CommandOutcome::Success);
+  assert_eq!(
+    MockWindowsApi.get_window_rect(offscreen_handle),
+    Some(Rect::new(560, 230, 1360, 830))
+  );
+  assert_eq!(
+    MockWindowsApi.get_window_rect(visible_handle),
+    Some(Rect::from(visible_sizing))
+  );
+  assert!(matches!(manager.rescue_offscreen_windows(), CommandOutcome::NoOp(_)));
+}
+
+#[test]
+fn reconcile_layouts_colours_border_of_elevated_window_matching_rule() {
+  MockWindowsApi::reset();
+  let directory = create_temp_directory();
+  let mut manager = WindowManager::default(MockWindowsApi);
+  manager.workspace_manager = WorkspaceManager::new_test(true, directory.path().join("workspaces.toml"));
+  manager.configuration_provider.lock().unwrap().add_window_rule(WindowRule {
+    is_elevated: Some(true),
+    border_colour: Some("#FF0000".to_string()),
+    ..WindowRule::default()
+  });
+  manager.reconcile_layouts();
+  let primary_monitor = crate::workspace_manager::tests::primary_monitor();
+  let elevated = WindowHandle::new(2);
+  let regular = WindowHandle::new(3);
+  let sizing = Sizing::new(100, 100, 200, 200);
+  MockWindowsApi::add_or_update_window(elevated, "Terminal".to_string(), sizing.clone(), false, false, false);
+  MockWindowsApi::add_or_update_window(regular, "Notepad".to_string(), sizing, false, false, false);
+  MockWindowsApi::place_window(elevated, primary_monitor.handle);
+  MockWindowsApi::place_window(regular, primary_monitor.handle);
+  MockWindowsApi::mark_window_elevated(elevated);
+
+  manager.reconcile_layouts();
+
+  assert_eq!(MockWindowsApi::get_window_border_colour(elevated), Some(0x000000FF));
+  assert_eq!(MockWindowsApi::get_window_border_colour(regular), None);
+}
+
+#[test]
+fn reevaluate_window_after_title_change_applies_rule_only_once_window_becomes_managed() {
+  MockWindowsApi::reset();
+  let directory = create_temp_directory();
+  let mut manager = WindowManager::default(MockWindowsApi);
+  manager.workspace_manager = WorkspaceManager::new_test(true, directory.path().join("workspaces.toml"));
+  manager.configuration_provider.lock().unwrap().add_window_rule(WindowRule {
+    title_contains: Some("Notepad".to_string()),
+    border_colour: Some("#FF0000".to_string()),
+    ..WindowRule::default()
+  });
+  manager.reconcile_layouts();
+  let primary_monitor = crate::workspace_manager::tests::primary_monitor();
+  let handle = WindowHandle::new(2);
+  let sizing = Sizing::new(100, 100, 200, 200);
+  MockWindowsApi::add_or_update_window(handle, String::new(), sizing.clone(), false, false, false);
+  MockWindowsApi::place_window(handle, primary_monitor.handle);
+  MockWindowsApi::mark_window_unmanageable(handle);
+  manager.reevaluate_window_after_title_change(handle);
+  let border_colour_while_unmanaged = MockWindowsApi::get_window_border_colour(handle);
+
+  MockWindowsApi::add_or_update_window(handle, "Notepad".to_string(), sizing, false, false, false);
+  manager.reevaluate_window_after_title_change(handle);
+
+  assert_eq!(border_colour_while_unmanaged, None);
+  assert_eq!(MockWindowsApi::get_window_border_colour(handle), Some(0x000000FF));
+  assert!(manager.spatial.take_newly_shown_windows(&MockWindowsApi).is_empty());
+}
+
+#[test]
+fn describe_state_redacts_window_titles_unless_they_are_included() {
+  MockWindowsApi::reset();
+  let directory = create_temp_directory();
+  let mut manager = WindowManager::default(MockWindowsApi);
+  manager.workspace_manager = WorkspaceManager::new_test(true, directory.path().join("workspaces.toml"));
+
+  let redacted_state = manager.describe_state(false);
+  let full_state = manager.describe_state(true);
+
+  assert!(!redacted_state.contains("Test Window"));
+  assert!(redacted_state.contains("\"<redacted>\""));
+  assert!(full_state.contains("\"Test Window\""));
+}
+
+#[test]
+fn is_paused_by_window_when_foreground_window_matches_pausing_rule() {
+  MockWindowsApi::reset();
+  let directory = create_temp_directory();
+  let mut manager = WindowManager::default(MockWindowsApi);
+  manager.workspace_manager = WorkspaceManager::new_test(true, directory.path().join("workspaces.toml"));
+  manager.configuration_provider.lock().unwrap().add_window_rule(WindowRule {
+    class_name: Some("ScreenShareWindow".to_string()),
+    pause_while_foreground: true,
+    ..WindowRule::default()
+  });
+  let sharing = WindowHandle::new(2);
+  let sizing = Sizing::new(100, 100, 200, 200);
+  MockWindowsApi::add_or_update_window(sharing, "Meeting".to_string(), sizing, false, false, false);
+  MockWindowsApi::set_window_class_name(sharing, "ScreenShareWindow");
+
+  assert!(!manager.is_paused_by_window());
+
+  MockWindowsApi::set_foreground_window(sharing);
+
+  assert!(manager.is_paused_by_window());
+}
+
+#[test]
+fn is_paused_by_window_when_screen_sharing_window_is_full_screen_on_active_screen_sharing_workspace() {
+  MockWindowsApi::reset();
+  let directory = create_temp_directory();
+  let mut manager = WindowManager::default(MockWindowsApi);
+  manager.workspace_manager = WorkspaceManager::new_test(true, directory.path().join("workspaces.toml"));
+  let primary_monitor = crate::workspace_manager::tests::primary_monitor();
+  manager
+    .configuration_provider
+    .lock()
+    .unwrap()
+    .add_screen_sharing_workspace(&primary_monitor.id_to_string(), None);
+  let meeting = WindowHandle::new(2);
+  let sizing = Sizing::new(100, 100, 400, 300);
+  MockWindowsApi::add_or_update_window(meeting, "Zoom Meeting".to_string(), sizing, false, false, false);
+  MockWindowsApi::place_window(meeting, primary_monitor.handle);
+  MockWindowsApi::set_window_class_name(meeting, "ZPContentViewWndClass");
+
+  assert!(!manager.is_paused_by_window());
+
+  let full_screen = Sizing::from(primary_monitor.monitor_area);
+  MockWindowsApi::add_or_update_window(meeting, "Zoom Meeting".to_string(), full_screen, false, false, false);
+
+  assert!(manager.is_paused_by_window());
+}
+
+#[test]
+fn close_window_only_closes_window_matching_confirming_rule_when_requested_again() {
+  MockWindowsApi::reset();
+  let mut manager = WindowManager::default(MockWindowsApi);
+  manager.configuration_provider.lock().unwrap().add_window_rule(WindowRule {
+    title_contains: Some("Unsaved".to_string()),
+    confirm_before_close: true,
+    ..WindowRule::default()
+  });
+  let editor = WindowHandle::new(1);
+  let sizing = Sizing::new(0, 0, 200, 200);
+  MockWindowsApi::add_or_update_window(editor, "Unsaved notes".to_string(), sizing, false, false, true);
+
+  let outcome = manager.close_window();
+
+  assert!(matches!(outcome, CommandOutcome::NoOp(_)));
+  assert_eq!(manager.get_window_awaiting_close_confirmation(), Some(editor));
+  assert_eq!(manager.windows_api.get_all_visible_windows().len(), 1);
+
+  let outcome = manager.close_window();
+
+  assert!(outcome.is_success());
+  assert_eq!(manager.get_window_awaiting_close_confirmation(), None);
+  assert!(manager.windows_api.get_all_visible_windows().is_empty());
+}
+
+#[test]
+fn forget_placement_if_moved_externally_forgets_placement_of_window_that_is_no_longer_near_maximised() {
+  MockWindowsApi::reset();
+  let directory = create_temp_directory();
+  let mut manager = WindowManager::default(MockWindowsApi);
+  manager.workspace_manager = WorkspaceManager::new_test(true, directory.path().join("workspaces.toml"));
+  let handle = WindowHandle::new(1);
+  manager.near_maximise_or_restore();
+  MockWindowsApi::add_or_update_window(
+    handle,
+    "Test Window".to_string(),
+    Sizing::new(0, 0, 960, 1040),
+    false,
+    false,
+    true,
+  );
+
+  manager.forget_placement_if_moved_externally(handle);
+
+  assert!(!manager.placement.remembers(handle));
+}
+
+#[test]
+fn forget_placement_if_moved_externally_keeps_placement_of_window_that_is_still_near_maximised() {
+  MockWindowsApi::reset();
+  let directory = create_temp_directory();
+  let mut manager = WindowManager::default(MockWindowsApi);
+  manager.workspace_manager = WorkspaceManager::new_test(true, directory.path().join("workspaces.toml"));
+  let handle = WindowHandle::new(1);
+  manager.near_maximise_or_restore();
+
+  manager.forget_placement_if_moved_externally(handle);
+
+  assert!(manager.placement.remembers(handle));
+}
+
+#[test]
+fn list_windows_lists_visible_and_hidden_managed_windows_matching_query() {
+  let (mut manager, _directory) = scrolling_manager();
+  let primary_monitor = crate::workspace_manager::tests::primary_monitor();
+  let visible_window = WindowHandle::new(2);
+  MockWindowsApi::add_or_update_window(
+    visible_window,
+    "Untitled - Notepad".to_string(),
+    Sizing::new(200, 50, 50, 50),
+    false,
+    false,
+    false,
+  );
+  MockWindowsApi::place_window(visible_window, primary_monitor.handle);
+  MockWindowsApi::set_window_process_name(visible_window, "Notepad.exe");
+  let hidden_workspace = PersistentWorkspaceId::from(*crate::workspace_manager::tests::primary_inactive_ws_id());
+  manager.move_window_to_workspace(hidden_workspace);
+
+  let windows = manager.list_windows(&WindowQuery::default());
+
+  assert_eq!(windows.len(), 2);
+  assert_eq!(windows[0].handle, visible_window);
+  assert_eq!(windows[0].visibility, WindowVisibility::Visible);
+  assert_eq!(windows[0].workspace, 1);
+  assert_eq!(windows[0].monitor_id, hidden_workspace.id_to_string());
+  assert_eq!(windows[1].handle, WindowHandle::new(1));
+  assert_eq!(windows[1].visibility, WindowVisibility::Hidden);
+  assert_eq!(windows[1].workspace, hidden_workspace.workspace);
+  let notepad_windows = manager.list_windows(&WindowQuery {
+    process_name: Some("notepad.exe".to_string()),
+    ..WindowQuery::default()
+  });
+  assert_eq!(notepad_windows.len(), 1);
+  assert_eq!(notepad_windows[0].title, "Untitled - Notepad");
+  assert!(
+    manager
+      .list_windows(&WindowQuery {
+        visibility: Some(WindowVisibility::Hidden),
+        workspace: Some(1),
+        ..WindowQuery::default()
+      })
+      .is_empty()
+  );
+}
+
+#[test]
+fn switch_to_window_switches_to_workspace_of_hidden_window_before_focusing_it() {
+  let (mut manager, _directory) = scrolling_manager();
+  let hidden_window = WindowHandle::new(1);
+  let hidden_workspace = PersistentWorkspaceId::from(*crate::workspace_manager::tests::primary_inactive_ws_id());
+  manager.move_window_to_workspace(hidden_workspace);
+
+  let switched_to = manager.switch_to_window(hidden_window);
+
+  assert_eq!(switched_to, Ok(Some(hidden_workspace)));
+  assert_eq!(manager.windows_api.get_foreground_window(), Some(hidden_window));
+  let windows = manager.list_windows_for_switcher();
+  assert_eq!(windows.len(), 1);
+  assert_eq!(windows[0].visibility, WindowVisibility::Visible);
+  assert!(manager.switch_to_window(WindowHandle::new(99)).is_err());
+}
+
+#[test]
+fn list_workspaces_describes_activity_and_window_count_of_every_workspace() {
+  let (mut manager, _directory) = scrolling_manager();
+  let active_workspace = PersistentWorkspaceId::from(*crate::workspace_manager::tests::primary_active_ws_id());
+  let inactive_workspace = PersistentWorkspaceId::from(*crate::workspace_manager::tests::primary_inactive_ws_id());
+  manager.move_window_to_workspace(inactive_workspace);
+
+  let workspaces = manager.list_workspaces();
+
+  let find = |id: PersistentWorkspaceId| {
+    workspaces
+      .iter()
+      .find(|workspace| workspace.monitor_id == id.id_to_string() && workspace.workspace == id.workspace)
+      .expect("Expected workspace to be listed")
+  };
+  assert_eq!(
+    workspaces.len(),
+    manager.workspace_manager.get_ordered_permanent_workspace_ids().len()
+  );
+  assert!(find(active_workspace).is_active);
+  assert_eq!(find(active_workspace).window_count, 0);
+  assert!(find(active_workspace).last_used_at.is_some());
+  assert!(!find(inactive_workspace).is_active);
+  assert_eq!(find(inactive_workspace).window_count, 1);
+  assert_eq!(find(inactive_workspace).last_used_at, None);
+  assert!(
+    workspaces
+      .iter()
+      .all(|workspace| workspace.name.is_none() && workspace.number.is_none())
+  );
+}
+
+#[test]
+fn list_workspace_badges_shows_numbers_and_active_workspace_per_monitor() {
+  let (mut manager, _directory) = scrolling_manager();
+  let active_workspace = PersistentWorkspaceId::from(*crate::workspace_manager::tests::primary_active_ws_id());
+  let inactive_workspace = PersistentWorkspaceId::from(*crate::workspace_manager::tests::primary_inactive_ws_id());
+  let primary_monitor = crate::workspace_manager::tests::primary_monitor();
+
+  let badges = manager.list_workspace_badges(&[(1, active_workspace), (2, inactive_workspace)]);
+
+  let primary_badge = badges
+    .iter()
+    .find(|badge| badge.work_area == primary_monitor.work_area)
+    .expect("Expected a badge for the primary monitor");
+  assert_eq!(primary_badge.workspaces[..2], [(Some(1), true), (Some(2), false)]);
+  assert_eq!(
+    badges.iter().map(|badge| badge.workspaces.len()).sum::<usize>(),
+    manager.workspace_manager.get_ordered_permanent_workspace_ids().len()
+  );
+  assert!(
+    badges
+      .iter()
+      .all(|badge| { badge.workspaces.iter().filter(|(_, is_active)| *is_active).count() == 1 })
+  );
+}
+
+#[test]
+fn restore_session_returns_windows_to_the_workspace_they_were_saved_on() {
+  let (mut manager, _directory) = scrolling_manager();
+  let primary_monitor = crate::workspace_manager::tests::primary_monitor();
+  let notepad_window = WindowHandle::new(2);
+  MockWindowsApi::add_or_update_window(
+    notepad_window,
+    "Untitled - Notepad".to_string(),
+    Sizing::new(200, 50, 50, 50),
+    false,
+    false,
+    false,
+  );
+  MockWindowsApi::place_window(notepad_window, primary_monitor.handle);
+  MockWindowsApi::set_window_process_name(notepad_window, "notepad.exe");
+  let hidden_workspace = PersistentWorkspaceId::from(*crate::workspace_manager::tests::primary_inactive_ws_id());
+  manager.move_window_to_workspace(hidden_workspace);
+
+  let mut session = manager.capture_session();
+
+  assert_eq!(session.windows.len(), 2);
+  assert_eq!(session.windows[0].handle, notepad_window);
+  assert_eq!(
+    session.windows[0].executable_path.as_deref(),
+    Some("C:\\Program Files\\notepad.exe")
+  );
+  assert_eq!(session.windows[1].handle, WindowHandle::new(1));
+  assert_eq!(session.windows[1].workspace, hidden_workspace);
+
+  manager.restore_all_managed_windows();
+  assert!(!manager.windows_api.is_window_hidden(&WindowHandle::new(1)));
+  session.windows.push(SessionWindow {
+    handle: WindowHandle::new(3),
+    process_name: Some("missing.exe".to_string()),
+    ..session.windows[0].clone()
+  });
+
+  let missing_windows = manager.restore_session(&session);
+
+  assert!(manager.windows_api.is_window_hidden(&WindowHandle::new(1)));
+  assert!(!manager.windows_api.is_window_hidden(&notepad_window));
+  assert_eq!(missing_windows.len(), 1);
+  assert_eq!(missing_windows[0].handle, WindowHandle::new(3));
+}
+
+#[test]
+fn free_floating_workspace_suppresses_moving_windows_and_window_rules() {
+  MockWindowsApi::reset();
+  let directory = create_temp_directory();
+  let mut manager = WindowManager::default(MockWindowsApi);
+  manager.workspace_manager = WorkspaceManager::new_test(true, directory.path().join("workspaces.toml"));
+  let primary_monitor = crate::workspace_manager::tests::primary_monitor();
+  {
+    let mut configuration_provider = manager.configuration_provider.lock().unwrap();
+    configuration_provider.add_free_floating_workspace(&primary_monitor.id_to_string(), Some(1));
+    configuration_provider.add_window_rule(WindowRule {
+      title_contains: Some("Legacy".to_string()),
+      auto_near_maximise: true,
+      ..WindowRule::default()
+    });
+  }
+  manager.reconcile_layouts();
+  let handle = WindowHandle::new(2);
+  let sizing = Sizing::new(100, 100, 200, 200);
+  MockWindowsApi::add_or_update_window(handle, "Legacy App".to_string(), sizing.clone(), false, false, true);
+  MockWindowsApi::place_window(handle, primary_monitor.handle);
+
+  manager.reconcile_layouts();
+  manager.move_window(Direction::Left);
+  manager.near_maximise_or_restore();
+
+  assert_eq!(MockWindowsApi.get_window_rect(handle), Some(Rect::from(sizing)));
+}
+
+#[test]
+fn refresh_free_floating_monitors_shares_monitors_whose_active_workspace_is_free_floating() {
+  MockWindowsApi::reset();
+  let directory = create_temp_directory();
+  let mut manager = WindowManager::default(MockWindowsApi);
+  manager.workspace_manager = WorkspaceManager::new_test(true, directory.path().join("workspaces.toml"));
+  let primary_monitor = crate::workspace_manager::tests::primary_monitor();
+  let free_floating_monitors = manager.get_shared_free_floating_monitors();
+
+  manager.refresh_free_floating_monitors();
+  assert!(free_floating_monitors.read().unwrap().is_empty());
+
+  manager
+    .configuration_provider
+    .lock()
+    .unwrap()
+    .add_free_floating_workspace(&primary_monitor.id_to_string(), Some(1));
+  manager.refresh_free_floating_monitors();
+
+  let free_floating_monitors = free_floating_monitors.read().unwrap();
+  assert_eq!(free_floating_monitors.len(), 1);
+  assert!(free_floating_monitors.contains(&primary_monitor.id));
+}
+
+#[test]
+fn tiled_workspace_is_retiled_when_windows_are_added_or_removed() {
+  MockWindowsApi::reset();
+  let directory = create_temp_directory();
+  let mut manager = WindowManager::default(MockWindowsApi);
+  manager.workspace_manager = WorkspaceManager::new_test(true, directory.path().join("workspaces.toml"));
+  let primary_monitor = crate::workspace_manager::tests::primary_monitor();
+  manager
+    .configuration_provider
+    .lock()
+    .unwrap()
+    .add_tiled_workspace(&primary_monitor.id_to_string(), Some(1), 50);
+  let master = WindowHandle::new(1);
+  let (first, second) = (WindowHandle::new(2), WindowHandle::new(3));
+  for (handle, title) in [(first, "First"), (second, "Second")] {
+    MockWindowsApi::add_or_update_window(
+      handle,
+      title.to_string(),
+      Sizing::new(300, 300, 400, 300),
+      false,
+      false,
+      false,
+    );
+    MockWindowsApi::place_window(handle, primary_monitor.handle);
+  }
+  let assert_tiled = |windows: &[WindowHandle]| {
+    let sizings = geometry::master_stack(primary_monitor.work_area, windows.len(), 50, 20);
+    for (handle, sizing) in windows.iter().zip(sizings) {
+      assert_eq!(
+        MockWindowsApi.get_window_placement(*handle),
+        Some(WindowPlacement::new_from_sizing(sizing)),
+        "{handle} is not tiled as expected"
+      );
+    }
+  };
+
+  manager.reconcile_layouts();
+  assert_tiled(&[master, first, second]);
+
+  MockWindowsApi::remove_window(first);
+  manager.reconcile_layouts();
+  assert_tiled(&[master, second]);
+
+  MockWindowsApi::add_or_update_window(first, "First".to_string(), Sizing::new(0, 0, 100, 100), false, false, false);
+  MockWindowsApi::place_window(first, primary_monitor.handle);
+  manager.reconcile_layouts();
+  assert_tiled(&[master, second, first]);
+}
+
+#[test]
+fn bsp_tiled_workspace_splits_windows_and_can_swap_and_rotate_them() {
+  MockWindowsApi::reset();
+  let directory = create_temp_directory();
+  let mut manager = WindowManager::default(MockWindowsApi);
+  manager.workspace_manager = WorkspaceManager::new_test(true, directory.path().join("workspaces.toml"));
+  let primary_monitor = crate::workspace_manager::tests::primary_monitor();
+  manager
+    .configuration_provider
+    .lock()
+    .unwrap()
+    .add_bsp_tiled_workspace(&primary_monitor.id_to_string(), Some(1));
+  let (first, second) = (WindowHandle::new(1), WindowHandle::new(2));
+  MockWindowsApi::add_or_update_window(
+    second,
+    "Second".to_string(),
+    Sizing::new(300, 300, 400, 300),
+    false,
+    false,
+    true,
+  );
+  MockWindowsApi::place_window(second, primary_monitor.handle);
+  let assert_placed = |handle: WindowHandle, sizing: Sizing| {
+    assert_eq!(
+      MockWindowsApi.get_window_placement(handle),
+      Some(WindowPlacement::new_from_sizing(sizing)),
+      "{handle} is not tiled as expected"
+    );
+  };
+
+  manager.reconcile_layouts();
+  assert_placed(first, Sizing::new(20, 20, 930, 990));
+  assert_placed(second, Sizing::new(970, 20, 930, 990));
+
+  manager.swap_tiled_windows();
+  assert_placed(second, Sizing::new(20, 20, 930, 990));
+  assert_placed(first, Sizing::new(970, 20, 930, 990));
+
+  manager.rotate_tiled_split();
+  assert_placed(second, Sizing::new(20, 20, 1880, 485));
+  assert_placed(first, Sizing::new(20, 525, 1880, 485));
+}
+
+#[test]
+fn guard_foreground_window_takes_foreground_back_on_do_not_disturb_workspace_unless_user_switched() {
+  MockWindowsApi::reset();
+  let directory = create_temp_directory();
+  let mut manager = WindowManager::default(MockWindowsApi);
+  manager.workspace_manager = WorkspaceManager::new_test(true, directory.path().join("workspaces.toml"));
+  let primary_monitor = crate::workspace_manager::tests::primary_monitor();
+  manager
+    .configuration_provider
+    .lock()
+    .unwrap()
+    .add_do_not_disturb_workspace(&primary_monitor.id_to_string(), Some(1));
+  let (writer, chat) = (WindowHandle::new(1), WindowHandle::new(2));
+  MockWindowsApi::set_window_process_name(writer, "writer.exe");
+  MockWindowsApi::add_or_update_window(chat, "Chat".to_string(), Sizing::new(300, 300, 400, 300), false, false, true);
+  MockWindowsApi::place_window(chat, primary_monitor.handle);
+  MockWindowsApi::set_window_process_name(chat, "chat.exe");
+  manager.guard_foreground_window(writer);
+
+  MockWindowsApi::set_milliseconds_since_last_input(5_000);
+  manager.guard_foreground_window(chat);
+  assert_eq!(MockWindowsApi.get_foreground_window(), Some(writer));
+
+  MockWindowsApi::set_milliseconds_since_last_input(0);
+  MockWindowsApi::set_foreground_window(chat);
+  manager.guard_foreground_window(chat);
+  assert_eq!(MockWindowsApi.get_foreground_window(), Some(chat));
+}
+
+#[test]
+fn toggle_monocle_hides_other_windows_of_workspace_and_restores_them_with_previous_placement() {
+  MockWindowsApi::reset();
+  let directory = create_temp_directory();
+  let mut manager = WindowManager::default(MockWindowsApi);
+  manager.workspace_manager = WorkspaceManager::new_test(true, directory.path().join("workspaces.toml"));
+  let primary_monitor = crate::workspace_manager::tests::primary_monitor();
+  let (focused, other) = (WindowHandle::new(1), WindowHandle::new(2));
+  MockWindowsApi::add_or_update_window(
+    other,
+    "Other".to_string(),
+    Sizing::new(300, 300, 400, 300),
+    false,
+    false,
+    false,
+  );
+  MockWindowsApi::place_window(other, primary_monitor.handle);
+  let previous_placement = MockWindowsApi.get_window_placement(focused);
+
+  manager.toggle_monocle();
+
+  assert!(MockWindowsApi.is_window_hidden(&other));
+  assert_eq!(
+    MockWindowsApi.get_window_placement(focused),
+    Some(WindowPlacement::new_from_sizing(geometry::near_maximised(
+      primary_monitor.work_area,
+      20
+    )))
+  );
+
+  manager.toggle_monocle();
+
+  assert!(!MockWindowsApi.is_window_hidden(&other));
+  assert_eq!(MockWindowsApi.get_window_placement(focused), previous_placement);
+}
+
+#[test]
+fn toggle_manage_window_ignores_foreground_window_until_it_is_toggled_again() {
+  MockWindowsApi::reset();
+  let directory = create_temp_directory();
+  let mut manager = WindowManager::default(MockWindowsApi);
+  manager.workspace_manager = WorkspaceManager::new_test(true, directory.path().join("workspaces.toml"));
+  let window = WindowHandle::new(1);
+
+  assert_eq!(manager.toggle_manage_window(), CommandOutcome::Success);
+
+  assert!(MockWindowsApi.is_window_ignored(window));
+  assert_eq!(manager.get_managed_foreground_window(), None);
+  assert_eq!(manager.capture_ignored_windows().windows.len(), 1);
+
+  assert_eq!(manager.toggle_manage_window(), CommandOutcome::Success);
+
+  assert!(!MockWindowsApi.is_window_ignored(window));
+  assert_eq!(manager.get_managed_foreground_window(), Some(window));
+  assert!(manager.capture_ignored_windows().windows.is_empty());
+}
+
+#[test]
+fn restore_ignored_windows_only_ignores_windows_that_still_belong_to_the_same_application() {
+  MockWindowsApi::reset();
+  let directory = create_temp_directory();
+  let mut manager = WindowManager::default(MockWindowsApi);
+  manager.workspace_manager = WorkspaceManager::new_test(true, directory.path().join("workspaces.toml"));
+  let (same_application, other_application) = (WindowHandle::new(1), WindowHandle::new(2));
+  MockWindowsApi::add_or_update_window(
+    other_application,
+    "Other".to_string(),
+    Sizing::new(300, 300, 400, 300),
+    false,
+    false,
+    false,
+  );
+  MockWindowsApi::set_window_class_name(same_application, "Notepad");
+  MockWindowsApi::set_window_class_name(other_application, "Chrome_WidgetWin_1");
+  let file = IgnoredWindowsFile::new(vec![
+    IgnoredWindow {
+      handle: same_application,
+      class_name: "Notepad".to_string(),
+    },
+    IgnoredWindow {
+      handle: other_application,
+      class_name: "Notepad".to_string(),
+    },
+    IgnoredWindow {
+      handle: WindowHandle::new(3),
+      class_name: "Notepad".to_string(),
+    },
+  ]);
+
+  manager.restore_ignored_windows(&file);
+
+  assert_eq!(MockWindowsApi.get_ignored_windows(), vec![same_application]);
+}
+
+#[test]
+fn move_window_to_monitor_moves_foreground_window_to_active_workspace_of_monitor_in_direction() {
+  MockWindowsApi::reset();
+  let directory = create_temp_directory();
+  let mut manager = WindowManager::default(MockWindowsApi);
+  manager.workspace_manager = WorkspaceManager::new_test(true, directory.path().join("workspaces.toml"));
+  let window = WindowHandle::new(1);
+
+  assert_eq!(manager.move_window_to_monitor(Direction::Left), CommandOutcome::Success);
+
+  let secondary_work_area = Rect::new(-800, 0, 0, 550);
+  let center = MockWindowsApi.get_window_rect(window).unwrap().center();
+  assert!(
+    secondary_work_area.contains(&center),
+    "{center} should be within {secondary_work_area}"
+  );
+  assert!(!manager.move_window_to_monitor(Direction::Up).is_success());
+  assert!(!manager.move_window_to_monitor_number(3).is_success());
+  assert!(!manager.move_window_to_monitor_number(0).is_success());
+}
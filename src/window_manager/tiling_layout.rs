@@ -0,0 +1,187 @@
+use crate::api::WindowsApi;
+use crate::common::{BspTree, MonitorMargins, PersistentWorkspaceId, Placement, WindowHandle, geometry};
+use crate::configuration_provider::TilingArrangement;
+use crate::workspace_backend::WorkspaceBackend;
+use std::collections::HashMap;
+
+/// A layout that arranges the windows of tiled workspaces in a master-stack layout or as a binary space partition (see
+/// [`TiledWorkspace`](crate::configuration_provider::TiledWorkspace)). Remembers the order of the windows of each
+/// workspace, so that windows keep their place when other windows are added or removed, and only re-tiles a workspace
+/// if its windows changed.
+#[derive(Debug, Default)]
+pub(super) struct TilingLayout {
+  tiled_windows: HashMap<PersistentWorkspaceId, Vec<WindowHandle>>,
+  bsp_trees: HashMap<PersistentWorkspaceId, BspTree>,
+  last_focused_window: Option<WindowHandle>,
+}
+
+impl TilingLayout {
+  /// Re-tiles each of the given active workspaces, paired with its arrangement and the master width in percent to use,
+  /// whose visible windows changed since it was last tiled, e.g. because a window was opened, closed, minimised, or
+  /// moved to it. In the master-stack arrangement, new windows are added to the bottom of the stack. In the BSP
+  /// arrangement, new windows split the region of the window that was focused before them. Windows are ordered by
+  /// position the first time a workspace is tiled.
+  pub(super) fn reconcile<T: WindowsApi>(
+    &mut self,
+    api: &T,
+    workspace_manager: &impl WorkspaceBackend,
+    placement: &Placement,
+    tiled_workspaces: &[(PersistentWorkspaceId, TilingArrangement, i32)],
+    margins: &MonitorMargins,
+  ) {
+    let is_tiled_as = |workspace: &PersistentWorkspaceId, arrangement: TilingArrangement| {
+      !workspace_manager.is_workspace_active(*workspace)
+        || tiled_workspaces.iter().any(|(id, a, _)| id == workspace && *a == arrangement)
+    };
+    self
+      .tiled_windows
+      .retain(|workspace, _| is_tiled_as(workspace, TilingArrangement::MasterStack));
+    self
+      .bsp_trees
+      .retain(|workspace, _| is_tiled_as(workspace, TilingArrangement::Bsp));
+    if tiled_workspaces.is_empty() {
+      return;
+    }
+    let mut visible_windows = api.get_all_visible_windows();
+    visible_windows.sort_by_key(|window| (window.rect.left, window.rect.top, window.handle.hwnd));
+    let foreground_window = api.get_foreground_window();
+    self.remember_focused_window(foreground_window);
+
+    for (workspace, arrangement, master_width_in_percent) in tiled_workspaces {
+      let Some(monitor) = workspace_manager.monitor_for_workspace(*workspace) else {
+        continue;
+      };
+      let margin = margins.for_workspace(*workspace);
+      let windows = visible_windows
+        .iter()
+        .filter(|window| workspace_manager.active_workspace_for_window(window.handle) == Some(*workspace))
+        .map(|window| window.handle)
+        .collect::<Vec<_>>();
+      let sizings = match arrangement {
+        TilingArrangement::MasterStack => {
+          let previous_windows = self.tiled_windows.get(workspace).cloned().unwrap_or_default();
+          let mut tiled_windows = previous_windows
+            .iter()
+            .copied()
+            .filter(|handle| windows.contains(handle))
+            .collect::<Vec<_>>();
+          tiled_windows.extend(windows.into_iter().filter(|handle| !previous_windows.contains(handle)));
+          if tiled_windows == previous_windows {
+            continue;
+          }
+          let sizings = geometry::master_stack(monitor.work_area, tiled_windows.len(), *master_width_in_percent, margin);
+          let sizings = tiled_windows.iter().copied().zip(sizings).collect::<Vec<_>>();
+          self.tiled_windows.insert(*workspace, tiled_windows);
+          sizings
+        }
+        TilingArrangement::Bsp => {
+          let tree = self.bsp_trees.entry(*workspace).or_default();
+          let mut has_changed = false;
+          for handle in tree.windows() {
+            if !windows.contains(&handle) {
+              has_changed |= tree.remove(handle);
+            }
+          }
+          for handle in windows {
+            if !tree.contains(handle) {
+              tree.insert(handle, self.last_focused_window, monitor.work_area, margin);
+              has_changed = true;
+            }
+          }
+          if !has_changed {
+            continue;
+          }
+          tree.layout(monitor.work_area, margin)
+        }
+      };
+
+      debug!("Tiling [{}] window(s) on workspace {}", sizings.len(), workspace);
+      for (handle, sizing) in sizings {
+        placement.resize(api, handle, sizing, margin);
+      }
+    }
+    self.remember_focused_window(foreground_window);
+  }
+
+  /// Remembers the foreground window if it is part of a binary space partition, so that the next new window splits its
+  /// region. New windows are usually focused by the time they are tiled, which is why the previous one is needed.
+  fn remember_focused_window(&mut self, foreground_window: Option<WindowHandle>) {
+    if let Some(window) = foreground_window.filter(|window| self.bsp_trees.values().any(|tree| tree.contains(*window))) {
+      self.last_focused_window = Some(window);
+    }
+  }
+
+  /// Turns the split of the BSP tiled workspace that contains `window` by 90 degrees and re-tiles the workspace. Does
+  /// nothing if the window is not tiled as part of a binary space partition.
+  pub(super) fn rotate_split<T: WindowsApi>(
+    &mut self,
+    api: &T,
+    workspace_manager: &impl WorkspaceBackend,
+    placement: &Placement,
+    window: WindowHandle,
+    margin: i32,
+  ) {
+    self.update_bsp_tree(api, workspace_manager, placement, window, margin, |tree| tree.rotate(window));
+  }
+
+  /// Swaps `window` with its sibling in the BSP tiled workspace that contains it, or with the master window (or, if it
+  /// is the master window, with the first window of the stack) in a master-stack tiled workspace, and re-tiles the
+  /// workspace.
+  pub(super) fn swap_window<T: WindowsApi>(
+    &mut self,
+    api: &T,
+    workspace_manager: &impl WorkspaceBackend,
+    placement: &Placement,
+    window: WindowHandle,
+    margin: i32,
+    master_width_in_percent: i32,
+  ) {
+    if self.bsp_trees.values().any(|tree| tree.contains(window)) {
+      self.update_bsp_tree(api, workspace_manager, placement, window, margin, |tree| tree.swap(window));
+      return;
+    }
+    let Some((workspace, tiled_windows)) = self
+      .tiled_windows
+      .iter_mut()
+      .find(|(_, tiled_windows)| tiled_windows.contains(&window))
+    else {
+      return;
+    };
+    let Some(monitor) = workspace_manager.monitor_for_workspace(*workspace) else {
+      return;
+    };
+    let index = tiled_windows.iter().position(|handle| *handle == window).unwrap_or_default();
+    let other_index = if index == 0 { 1 } else { 0 };
+    if other_index >= tiled_windows.len() {
+      return;
+    }
+    tiled_windows.swap(index, other_index);
+    let sizings = geometry::master_stack(monitor.work_area, tiled_windows.len(), master_width_in_percent, margin);
+    for (handle, sizing) in tiled_windows.iter().zip(sizings) {
+      placement.resize(api, *handle, sizing, margin);
+    }
+  }
+
+  fn update_bsp_tree<T: WindowsApi>(
+    &mut self,
+    api: &T,
+    workspace_manager: &impl WorkspaceBackend,
+    placement: &Placement,
+    window: WindowHandle,
+    margin: i32,
+    update: impl FnOnce(&mut BspTree) -> bool,
+  ) {
+    let Some((workspace, tree)) = self.bsp_trees.iter_mut().find(|(_, tree)| tree.contains(window)) else {
+      return;
+    };
+    let Some(monitor) = workspace_manager.monitor_for_workspace(*workspace) else {
+      return;
+    };
+    if !update(tree) {
+      return;
+    }
+    for (handle, sizing) in tree.layout(monitor.work_area, margin) {
+      placement.resize(api, handle, sizing, margin);
+    }
+  }
+}
@@ -1,28 +1,59 @@
+use super::monocle_mode::MonocleMode;
 use super::navigation;
 use super::scrolling_layout::ScrollingLayout;
 use super::spatial_layout::SpatialLayout;
+use super::tiling_layout::TilingLayout;
 use crate::api::WindowsApi;
 use crate::common::*;
 use crate::configuration_provider::{
   ADDITIONAL_WORKSPACE_COUNT, ALLOW_MOVING_CURSOR_AFTER_OPEN_CLOSE_OR_MINIMISE, ALLOW_SELECTING_SAME_CENTER_WINDOWS,
-  ConfigurationProvider, Layout, SCROLLING_ANIMATION_DURATION_IN_MS, WINDOW_MARGIN,
+  AUTO_SNAP_THRESHOLD_IN_PERCENT, CLOSE_CONFIRMATION_TIMEOUT_IN_MS, ConfigurationProvider, FocusFallbackPolicy,
+  KEEP_ALWAYS_ON_TOP_WINDOWS_VISIBLE, Layout, PRESERVE_RELATIVE_WINDOW_POSITION, SCROLLING_ANIMATION_DURATION_IN_MS,
+  SKIP_NEAR_MAXIMISE_ANIMATION, SLOW_WORKSPACE_SWITCH_THRESHOLD_IN_MS, SNAP_TO_THIRDS, WINDOW_MARGIN, WindowRule,
 };
-use crate::utils::{CONFIGURATION_PROVIDER_LOCK, MINIMUM_WINDOW_MARGIN};
+use crate::files::{IgnoredWindow, IgnoredWindowsFile, SessionFile, SessionWindow};
+use crate::utils::{CONFIGURATION_PROVIDER_LOCK, FREE_FLOATING_MONITORS_LOCK, MINIMUM_WINDOW_MARGIN};
+use crate::workspace_backend::WorkspaceBackend;
 use crate::workspace_manager::WorkspaceManager;
-use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant, UNIX_EPOCH};
 use windows::Win32::UI::Shell::IVirtualDesktopManager;
 
-/// Routes window commands to the configured layout and coordinates workspace changes.
-pub struct WindowManager<T: WindowsApi> {
+/// How recent the last user input must be for a change of the foreground window to count as intended by the user
+/// rather than as an application stealing the foreground.
+const FOREGROUND_CHANGE_INPUT_TOLERANCE_IN_MS: u32 = 1_000;
+const NO_FOREGROUND_WINDOW_REASON: &str = "there is no foreground window";
+const FREE_FLOATING_REASON: &str = "the foreground window is free-floating";
+const SCROLLING_LAYOUT_REASON: &str = "the foreground window uses the scrolling layout";
+const NO_PLACEMENT_REASON: &str = "the placement of the foreground window is unavailable";
+const NO_MONITOR_REASON: &str = "the monitor of the foreground window is unavailable";
+/// Replaces the title of each window in the state description unless window titles are included explicitly.
+const REDACTED_WINDOW_TITLE: &str = "<redacted>";
+const CLOSE_CONFIRMATION_REASON: &str = "the foreground window matches a window rule that requires confirming to close it";
+
+/// Routes window commands to the configured layout and coordinates workspace changes through a workspace backend.
+pub struct WindowManager<T: WindowsApi, B: WorkspaceBackend = WorkspaceManager<T>> {
   pub(super) configuration_provider: Arc<Mutex<ConfigurationProvider>>,
   pub(super) placement: Placement,
   pub(super) allow_moving_cursor_after_close_or_minimise: bool,
+  pub(super) monocle: MonocleMode,
   pub(super) scrolling: ScrollingLayout,
   pub(super) spatial: SpatialLayout,
-  pub(super) workspace_manager: WorkspaceManager<T>,
+  pub(super) tiling: TilingLayout,
+  pub(super) workspace_manager: B,
   pub(super) virtual_desktop_manager: Option<IVirtualDesktopManager>,
   pub(super) windows_api: T,
+  /// The window that is closed if closing it is requested again before the close confirmation timeout expires, and
+  /// when closing it was first requested.
+  pub(super) pending_close_confirmation: Option<(WindowHandle, Instant)>,
+  /// The workspace each window was on before a window rule moved it to another workspace, so that it can be moved back
+  /// once no rule requires it to be elsewhere.
+  pub(super) rule_placed_windows: HashMap<WindowHandle, PersistentWorkspaceId>,
+  /// The monitors whose active workspace is free-floating, shared with the mouse hook so that it does not snap windows
+  /// dragged or resized on them.
+  pub(super) free_floating_monitors: Arc<RwLock<HashSet<[u16; 32]>>>,
 }
 
 impl<T: WindowsApi + Clone> WindowManager<T> {
@@ -37,16 +68,43 @@ impl<T: WindowsApi + Clone> WindowManager<T> {
       )
     });
     let additional_workspace_count = guard.get_i32(ADDITIONAL_WORKSPACE_COUNT);
+    let monitor_workspace_counts = guard.get_monitor_workspace_counts();
     let window_margin = guard.get_i32(WINDOW_MARGIN);
     let allow_moving_cursor_after_close_or_minimise = guard.get_bool(ALLOW_MOVING_CURSOR_AFTER_OPEN_CLOSE_OR_MINIMISE);
+    let keep_always_on_top_windows_visible = guard.get_bool(KEEP_ALWAYS_ON_TOP_WINDOWS_VISIBLE);
+    let preserve_relative_window_position = guard.get_bool(PRESERVE_RELATIVE_WINDOW_POSITION);
+    let skip_near_maximise_animation = guard.get_bool(SKIP_NEAR_MAXIMISE_ANIMATION);
+    let workspace_fallbacks = guard.get_workspace_fallbacks();
+    let monitor_ordering = guard.get_monitor_ordering();
+    let slow_workspace_switch_threshold_in_ms = guard.get_i32(SLOW_WORKSPACE_SWITCH_THRESHOLD_IN_MS);
+    let data_file_format = guard.get_data_file_format();
     drop(guard);
-    let workspace_manager = WorkspaceManager::new(additional_workspace_count, window_margin, api.clone());
+    let workspace_manager = WorkspaceManager::new(
+      additional_workspace_count,
+      monitor_workspace_counts,
+      window_margin,
+      keep_always_on_top_windows_visible,
+      preserve_relative_window_position,
+      workspace_fallbacks,
+      monitor_ordering,
+      slow_workspace_switch_threshold_in_ms,
+      data_file_format,
+      api.clone(),
+    );
+
+    let mut spatial = SpatialLayout::default();
+    spatial.record_work_areas(&api);
 
     Self {
-      placement: Placement::default(),
+      placement: Placement {
+        skip_near_maximise_animation,
+        ..Placement::default()
+      },
       allow_moving_cursor_after_close_or_minimise,
+      monocle: MonocleMode::default(),
       scrolling: ScrollingLayout::default(),
-      spatial: SpatialLayout,
+      spatial,
+      tiling: TilingLayout::default(),
       virtual_desktop_manager: Some(
         api
           .get_virtual_desktop_manager()
@@ -55,64 +113,199 @@ impl<T: WindowsApi + Clone> WindowManager<T> {
       workspace_manager,
       configuration_provider,
       windows_api: api,
+      pending_close_confirmation: None,
+      rule_placed_windows: HashMap::new(),
+      free_floating_monitors: Arc::new(RwLock::new(HashSet::new())),
     }
   }
 
+  /// Returns the path to the backup of the workspaces file if it was corrupt at startup and had to be replaced.
+  pub fn take_corrupt_workspaces_file_backup(&mut self) -> Option<PathBuf> {
+    self.workspace_manager.take_corrupt_workspaces_file_backup()
+  }
+
+  /// Returns a single-line summary of the latency of workspace switches, which is written to the log with every
+  /// heartbeat to help identifying windows that stall switching workspace.
+  pub fn describe_workspace_switch_latency(&self) -> String {
+    self.workspace_manager.describe_switch_latency()
+  }
+}
+
+impl<T: WindowsApi + Clone, B: WorkspaceBackend> WindowManager<T, B> {
   /// Lists every permanent workspace in monitor and workspace order.
   pub fn get_ordered_permanent_workspace_ids(&mut self) -> Vec<PersistentWorkspaceId> {
     self.workspace_manager.get_ordered_permanent_workspace_ids()
   }
 
-  /// Closes the foreground window and lets its layout choose the next focus.
-  pub fn close_window(&mut self) {
+  /// Returns the current rect of the window, e.g. to report where it ended up after it was dragged with the mouse.
+  pub fn get_window_rect(&self, handle: WindowHandle) -> Option<Rect> {
+    self.windows_api.get_window_rect(handle)
+  }
+
+  /// Returns the foreground window if it is managed by this application, e.g. to draw the focus border around it.
+  pub fn get_managed_foreground_window(&self) -> Option<WindowHandle> {
+    self
+      .windows_api
+      .get_foreground_window()
+      .filter(|window| !self.windows_api.is_not_a_managed_window(window))
+  }
+
+  /// Returns `true` if the foreground window belongs to an elevated process, which Windows does not allow this
+  /// application to manage unless it is running with admin privileges too.
+  pub fn is_foreground_window_out_of_reach(&self) -> bool {
+    self
+      .windows_api
+      .get_foreground_window()
+      .is_some_and(|window| self.windows_api.is_window_elevated(window) && !self.windows_api.is_running_as_admin())
+  }
+
+  /// Closes the foreground window and lets its layout choose the next focus. If the window matches a window rule with
+  /// `confirm_before_close`, the window is only closed if this is called again before the close confirmation timeout
+  /// expires (see [`Self::get_window_awaiting_close_confirmation`]).
+  pub fn close_window(&mut self) -> CommandOutcome {
     let Some(window) = self.windows_api.get_foreground_window() else {
-      return;
+      return CommandOutcome::no_op(NO_FOREGROUND_WINDOW_REASON);
     };
+    let is_confirmed = self.get_window_awaiting_close_confirmation() == Some(window);
+    self.pending_close_confirmation = None;
+    if !is_confirmed && self.is_close_confirmation_required(window) {
+      self.pending_close_confirmation = Some((window, Instant::now()));
+      return CommandOutcome::no_op(CLOSE_CONFIRMATION_REASON);
+    }
     let layout = self.get_layout_for_window(window);
     self.windows_api.do_close_window(window);
     self.execute_post_close_or_minimise_layout_specific_logic(window, layout);
+
+    CommandOutcome::Success
+  }
+
+  /// Returns the window that is closed if [`Self::close_window`] is called again before the close confirmation timeout
+  /// expires, e.g. to prompt the user to confirm closing it.
+  pub fn get_window_awaiting_close_confirmation(&self) -> Option<WindowHandle> {
+    self
+      .pending_close_confirmation
+      .filter(|(_, requested_at)| requested_at.elapsed() < self.close_confirmation_timeout())
+      .map(|(window, _)| window)
   }
 
   /// Minimises the foreground window and lets its layout choose the next focus.
-  pub fn minimise_window(&mut self) {
+  pub fn minimise_window(&mut self) -> CommandOutcome {
     let Some(window) = self.windows_api.get_foreground_window() else {
-      return;
+      return CommandOutcome::no_op(NO_FOREGROUND_WINDOW_REASON);
     };
     let layout = self.get_layout_for_window(window);
     self.windows_api.do_minimise_window(window);
     self.execute_post_close_or_minimise_layout_specific_logic(window, layout);
+
+    CommandOutcome::Success
   }
 
-  /// Shows a workspace and refreshes its scrolling strip when needed.
-  pub fn switch_workspace(&mut self, id: PersistentWorkspaceId) {
+  /// Shows a workspace and refreshes its scrolling strip when needed. Leaves monocle mode on the workspace that is
+  /// switched away from first, so that its hidden windows are stored with it.
+  pub fn switch_workspace(&mut self, id: PersistentWorkspaceId) -> CommandOutcome {
+    if let Some(source) = self.get_active_workspace_on_monitor(id.monitor_id) {
+      self.monocle.exit(&self.windows_api, source);
+    }
     if self.get_layout_for_workspace(id) != Some(Layout::Scrolling) {
-      self.workspace_manager.switch_workspace(id);
-      return;
+      let outcome = self.workspace_manager.switch_workspace(id);
+      self.tile_workspaces(&self.margins());
+      return outcome;
     }
-    let source = self
-      .workspace_manager
-      .active_workspace_ids()
-      .into_iter()
-      .find(|workspace| workspace.monitor_id == id.monitor_id);
+    let source = self.get_active_workspace_on_monitor(id.monitor_id);
     let additional_windows = source.map_or_else(Vec::new, |workspace| self.scrolling.get_members(workspace));
-    self
+    let outcome = self
       .workspace_manager
       .switch_workspace_with_additional_windows(id, &additional_windows);
-    let margin = self.margin();
+    let margin = self.margin_for_workspace(id);
     self.scrolling.reflow(&self.windows_api, &self.workspace_manager, id, margin);
     self.scrolling.focus(&self.windows_api, &self.workspace_manager, id, margin);
+
+    outcome
+  }
+
+  /// Switches to the next or previous workspace on the monitor under the cursor, wrapping around at either end. Returns
+  /// the ID of the workspace that was switched to or the outcome if no workspace was switched to.
+  pub fn switch_to_adjacent_workspace(&mut self, is_next: bool) -> Result<PersistentWorkspaceId, CommandOutcome> {
+    let monitor_id = self
+      .get_monitor_id_under_cursor()
+      .ok_or_else(|| CommandOutcome::error("there is no monitor under the cursor"))?;
+    let active_workspace = self
+      .workspace_manager
+      .active_workspace_ids()
+      .into_iter()
+      .find(|workspace| workspace.monitor_id == monitor_id)
+      .ok_or_else(|| CommandOutcome::error("there is no active workspace under the cursor"))?;
+    let workspaces = self
+      .workspace_manager
+      .get_ordered_permanent_workspace_ids()
+      .into_iter()
+      .filter(|workspace| workspace.monitor_id == monitor_id)
+      .collect::<Vec<_>>();
+    if workspaces.len() < 2 {
+      return Err(CommandOutcome::no_op("the monitor under the cursor has only one workspace"));
+    }
+    let index = workspaces
+      .iter()
+      .position(|workspace| *workspace == active_workspace)
+      .ok_or_else(|| CommandOutcome::error(format!("workspace [{active_workspace}] is not in the workspace order")))?;
+    let target_index = if is_next {
+      (index + 1) % workspaces.len()
+    } else {
+      (index + workspaces.len() - 1) % workspaces.len()
+    };
+    let target = workspaces[target_index];
+    match self.switch_workspace(target) {
+      CommandOutcome::Success => Ok(target),
+      outcome => Err(outcome),
+    }
+  }
+
+  /// Adds a workspace to the monitor under the cursor. If a workspace was created, i.e. if the outcome is a success,
+  /// the workspace hotkeys need to be registered again.
+  pub fn create_workspace(&mut self) -> CommandOutcome {
+    let Some(monitor_id) = self.get_monitor_id_under_cursor() else {
+      return CommandOutcome::error("there is no monitor under the cursor");
+    };
+    let id = match self.workspace_manager.create_workspace(monitor_id) {
+      Ok(id) => id,
+      Err(reason) => return CommandOutcome::Error(reason),
+    };
+    self.persist_additional_workspace_count(id, id.workspace - 1);
+
+    CommandOutcome::Success
+  }
+
+  /// Removes the last workspace of the monitor under the cursor, provided it is inactive and empty. If a workspace was
+  /// deleted, i.e. if the outcome is a success, the workspace hotkeys need to be registered again.
+  pub fn delete_workspace(&mut self) -> CommandOutcome {
+    let Some(monitor_id) = self.get_monitor_id_under_cursor() else {
+      return CommandOutcome::error("there is no monitor under the cursor");
+    };
+    let id = match self.workspace_manager.delete_workspace(monitor_id) {
+      Ok(id) => id,
+      Err(reason) => return CommandOutcome::Error(reason),
+    };
+    self.persist_additional_workspace_count(id, id.workspace - 2);
+
+    CommandOutcome::Success
   }
 
   /// Moves the foreground window to a workspace and updates scrolling strip membership.
-  pub fn move_window_to_workspace(&mut self, target_id: PersistentWorkspaceId) {
+  pub fn move_window_to_workspace(&mut self, target_id: PersistentWorkspaceId) -> CommandOutcome {
     let foreground = self.windows_api.get_foreground_window();
     let source = foreground.and_then(|handle| self.get_workspace_for_window(handle));
-    if source == Some(target_id) || self.workspace_manager.monitor_for_workspace(target_id).is_none() {
-      return;
+    if source == Some(target_id) {
+      return CommandOutcome::no_op(format!("the foreground window is already on workspace [{target_id}]"));
+    }
+    if self.workspace_manager.monitor_for_workspace(target_id).is_none() {
+      return CommandOutcome::error(format!("workspace [{target_id}] does not exist"));
+    }
+    if let Some(source) = source {
+      self.monocle.exit(&self.windows_api, source);
     }
     let source_layout = source.and_then(|workspace| self.get_layout_for_workspace(workspace));
     let target_layout = self.get_layout_for_workspace(target_id);
-    self.workspace_manager.move_window_to_workspace(target_id);
+    let outcome = self.workspace_manager.move_window_to_workspace(target_id);
 
     if let (Some(handle), Some(source_id)) = (foreground, source) {
       let transferred_preset = if source_layout == Some(Layout::Scrolling) {
@@ -120,7 +313,7 @@ impl<T: WindowsApi + Clone> WindowManager<T> {
       } else {
         None
       };
-      let margin = self.margin();
+      let margins = self.margins();
       if target_layout == Some(Layout::Scrolling) {
         self.scrolling.insert(
           &self.windows_api,
@@ -128,11 +321,12 @@ impl<T: WindowsApi + Clone> WindowManager<T> {
           target_id,
           handle,
           transferred_preset,
-          margin,
+          margins.for_workspace(target_id),
         );
       }
       for (workspace, layout) in [(source_id, source_layout), (target_id, target_layout)] {
         if layout == Some(Layout::Scrolling) && self.workspace_manager.is_workspace_active(workspace) {
+          let margin = margins.for_workspace(workspace);
           self
             .scrolling
             .reflow(&self.windows_api, &self.workspace_manager, workspace, margin);
@@ -141,25 +335,131 @@ impl<T: WindowsApi + Clone> WindowManager<T> {
             .focus(&self.windows_api, &self.workspace_manager, workspace, margin);
         }
       }
+      self.tile_workspaces(&margins);
+    }
+
+    outcome
+  }
+
+  /// Moves the foreground window to the active workspace of the closest monitor in the direction, regardless of how the
+  /// window is placed. Refits the window to the target monitor in the same way as [`Self::move_window_to_workspace`].
+  pub fn move_window_to_monitor(&mut self, direction: Direction) -> CommandOutcome {
+    let Some(window) = self.windows_api.get_foreground_window() else {
+      return CommandOutcome::no_op(NO_FOREGROUND_WINDOW_REASON);
+    };
+    let source_monitor = self.windows_api.get_monitor_handle_for_window_handle(window);
+    let Some(target_monitor_id) = self
+      .windows_api
+      .get_all_monitors()
+      .get(direction, source_monitor)
+      .map(|monitor| monitor.id)
+    else {
+      return CommandOutcome::no_op(format!("there is no monitor in direction [{direction:?}]"));
+    };
+
+    self.move_window_to_active_workspace_on_monitor(target_monitor_id)
+  }
+
+  /// Moves the foreground window to the active workspace of the monitor with the number, counting from `1` in the
+  /// configured monitor order. Refits the window to the target monitor in the same way as
+  /// [`Self::move_window_to_workspace`].
+  pub fn move_window_to_monitor_number(&mut self, number: usize) -> CommandOutcome {
+    let mut monitor_ids = Vec::new();
+    for workspace in self.workspace_manager.get_ordered_permanent_workspace_ids() {
+      if !monitor_ids.contains(&workspace.monitor_id) {
+        monitor_ids.push(workspace.monitor_id);
+      }
+    }
+    let Some(target_monitor_id) = number.checked_sub(1).and_then(|index| monitor_ids.get(index)).copied() else {
+      return CommandOutcome::error(format!("there is no monitor with number [{number}]"));
+    };
+
+    self.move_window_to_active_workspace_on_monitor(target_monitor_id)
+  }
+
+  /// Moves a window that is hidden on an inactive workspace to the active workspace under the cursor and focuses it,
+  /// which is the inverse of [`Self::move_window_to_workspace`]. Updates scrolling strip membership in the same way.
+  pub fn bring_window_here(&mut self, handle: WindowHandle) -> CommandOutcome {
+    let source = self
+      .workspace_manager
+      .stored_windows()
+      .into_iter()
+      .find(|(_, window)| window.handle == handle)
+      .map(|(workspace, _)| workspace);
+    let target = self
+      .get_monitor_id_under_cursor()
+      .and_then(|monitor_id| self.get_active_workspace_on_monitor(monitor_id));
+    if let Some(target) = target {
+      self.monocle.exit(&self.windows_api, target);
+    }
+    let outcome = self.workspace_manager.bring_window_here(handle);
+    if !outcome.is_success() {
+      return outcome;
+    }
+
+    let transferred_preset = match source {
+      Some(source) if self.get_layout_for_workspace(source) == Some(Layout::Scrolling) => {
+        self.scrolling.remove(source, handle)
+      }
+      _ => None,
+    };
+    let margins = self.margins();
+    if let Some(target) = target
+      && self.get_layout_for_workspace(target) == Some(Layout::Scrolling)
+    {
+      let margin = margins.for_workspace(target);
+      self.scrolling.insert(
+        &self.windows_api,
+        &self.workspace_manager,
+        target,
+        handle,
+        transferred_preset,
+        margin,
+      );
+      self
+        .scrolling
+        .reflow(&self.windows_api, &self.workspace_manager, target, margin);
+      self
+        .scrolling
+        .focus(&self.windows_api, &self.workspace_manager, target, margin);
     }
+    self.tile_workspaces(&margins);
+
+    outcome
   }
 
   /// Moves the foreground window according to its layout and the requested direction.
-  pub fn move_window(&mut self, direction: Direction) {
+  pub fn move_window(&mut self, direction: Direction) -> CommandOutcome {
+    if self.is_foreground_window_free_floating() {
+      return CommandOutcome::no_op(FREE_FLOATING_REASON);
+    }
     if self.get_foreground_window_layout() == Some(Layout::Scrolling) {
       if matches!(direction, Direction::Left | Direction::Right) {
-        let margin = self.margin();
+        let margin = self.margin_for_foreground_window();
         self
           .scrolling
           .reorder(&self.windows_api, &self.workspace_manager, direction, margin);
       } else {
         self.move_scrolling_window_to_monitor(direction);
       }
-      return;
+      return CommandOutcome::Success;
     }
-    self
-      .spatial
-      .move_window(&self.windows_api, &self.placement, direction, self.margin());
+    let size_tolerance_in_px = self.size_tolerance_in_px_for_foreground_window();
+    let snap_to_thirds = self
+      .configuration_provider
+      .lock()
+      .expect(CONFIGURATION_PROVIDER_LOCK)
+      .get_bool(SNAP_TO_THIRDS);
+    self.spatial.move_window(
+      &self.windows_api,
+      &self.placement,
+      direction,
+      self.margin_for_foreground_window(),
+      size_tolerance_in_px,
+      snap_to_thirds,
+    );
+
+    CommandOutcome::Success
   }
 
   /// Transfers the active scrolling window vertically to an adjacent monitor. This method:
@@ -200,10 +500,11 @@ impl<T: WindowsApi + Clone> WindowManager<T> {
       return;
     };
 
-    let margin = self.margin();
+    let source_margin = self.margin_for_workspace(source_workspace_id);
     self
       .scrolling
-      .reflow(&self.windows_api, &self.workspace_manager, source_workspace_id, margin);
+      .reflow(&self.windows_api, &self.workspace_manager, source_workspace_id, source_margin);
+    let margin = self.margin_for_monitor(&target_monitor);
     match target_layout {
       Layout::Spatial => {
         self
@@ -231,23 +532,162 @@ impl<T: WindowsApi + Clone> WindowManager<T> {
   }
 
   /// Resizes a window on a monitor using the spatial layout. Scrolling windows remain unchanged.
-  pub fn resize_spatial_window(&mut self, direction: Direction) {
-    if self.get_foreground_window_layout() != Some(Layout::Scrolling) {
-      self
-        .spatial
-        .resize_window(&self.windows_api, &self.placement, direction, self.margin());
+  pub fn resize_spatial_window(&mut self, direction: Direction) -> CommandOutcome {
+    if self.get_foreground_window_layout() == Some(Layout::Scrolling) {
+      return CommandOutcome::no_op(SCROLLING_LAYOUT_REASON);
+    }
+    if self.is_foreground_window_free_floating() {
+      return CommandOutcome::no_op(FREE_FLOATING_REASON);
+    }
+    let size_tolerance_in_px = self.size_tolerance_in_px_for_foreground_window();
+    self.spatial.resize_window(
+      &self.windows_api,
+      &self.placement,
+      direction,
+      self.margin_for_foreground_window(),
+      size_tolerance_in_px,
+    );
+
+    CommandOutcome::Success
+  }
+
+  /// Moves the edge the foreground window shares with its neighbours, resizing all of them at once. No-ops in scrolling
+  /// layout.
+  pub fn move_shared_edge(&mut self, direction: Direction) -> CommandOutcome {
+    if self.get_foreground_window_layout() == Some(Layout::Scrolling) {
+      return CommandOutcome::no_op(SCROLLING_LAYOUT_REASON);
+    }
+    if self.is_foreground_window_free_floating() {
+      return CommandOutcome::no_op(FREE_FLOATING_REASON);
     }
+    self.spatial.move_shared_edge(
+      &self.windows_api,
+      &self.placement,
+      direction,
+      self.margin_for_foreground_window(),
+    );
+
+    CommandOutcome::Success
   }
 
   /// Narrows or widens a scrolling layout window. No-ops in spatial layout.
-  pub fn resize_scrolling_window(&mut self, direction: Direction) {
+  pub fn resize_scrolling_window(&mut self, direction: Direction) -> CommandOutcome {
     if self.get_foreground_window_layout() != Some(Layout::Scrolling) {
-      return;
+      return CommandOutcome::no_op("the foreground window does not use the scrolling layout");
+    }
+    if self.is_foreground_window_free_floating() {
+      return CommandOutcome::no_op(FREE_FLOATING_REASON);
     }
-    let margin = self.margin();
+    let margin = self.margin_for_foreground_window();
     self
       .scrolling
       .resize_window(&self.windows_api, &self.workspace_manager, direction, margin);
+
+    CommandOutcome::Success
+  }
+
+  /// Turns the split that contains the foreground window by 90 degrees, if the window belongs to a workspace that is
+  /// tiled as a binary space partition.
+  pub fn rotate_tiled_split(&mut self) -> CommandOutcome {
+    let Some(window) = self.windows_api.get_foreground_window() else {
+      return CommandOutcome::no_op(NO_FOREGROUND_WINDOW_REASON);
+    };
+    let margin = self.margin_for_window(window);
+    self
+      .tiling
+      .rotate_split(&self.windows_api, &self.workspace_manager, &self.placement, window, margin);
+
+    CommandOutcome::Success
+  }
+
+  /// Swaps the foreground window with its sibling in a workspace tiled as a binary space partition, or with the master
+  /// window in a workspace tiled in a master-stack layout.
+  pub fn swap_tiled_windows(&mut self) -> CommandOutcome {
+    let Some(window) = self.windows_api.get_foreground_window() else {
+      return CommandOutcome::no_op(NO_FOREGROUND_WINDOW_REASON);
+    };
+    let Some(master_width_in_percent) = self.get_workspace_for_window(window).and_then(|workspace| {
+      self
+        .configuration_provider
+        .lock()
+        .expect(CONFIGURATION_PROVIDER_LOCK)
+        .get_tiled_workspace(&workspace.id_to_string(), workspace.workspace)
+        .map(|tiled_workspace| tiled_workspace.master_width_in_percent)
+    }) else {
+      return CommandOutcome::no_op("the foreground window is not on a tiled workspace");
+    };
+    let margin = self.margin_for_window(window);
+    self.tiling.swap_window(
+      &self.windows_api,
+      &self.workspace_manager,
+      &self.placement,
+      window,
+      margin,
+      master_width_in_percent,
+    );
+
+    CommandOutcome::Success
+  }
+
+  /// Snaps a window that was dragged using the mouse into the snap zone it was released over or, if there is none,
+  /// into the half or quarter of its monitor that it mostly overlaps, if `auto_snap_threshold_in_percent` is set. Only
+  /// applies to windows that belong to the spatial layout. Expected to be called after the user has dragged a window
+  /// using the mouse-based window drag feature.
+  pub fn finish_mouse_drag(&mut self, window: WindowHandle, snap_zone: Option<Sizing>) {
+    if self.get_layout_for_window(window) == Some(Layout::Scrolling) || self.is_window_free_floating(window) {
+      return;
+    }
+    let margin = self.margin_for_window(window);
+    if let Some(snap_zone) = snap_zone {
+      debug!("Snapping {} into snap zone {:?} after dragging", window, snap_zone);
+      self.placement.resize(&self.windows_api, window, snap_zone, margin);
+      return;
+    }
+    let threshold_in_percent = self
+      .configuration_provider
+      .lock()
+      .expect(CONFIGURATION_PROVIDER_LOCK)
+      .get_i32(AUTO_SNAP_THRESHOLD_IN_PERCENT);
+    if threshold_in_percent == 0 {
+      return;
+    }
+    self
+      .spatial
+      .snap_after_drag(&self.windows_api, &self.placement, window, margin, threshold_in_percent);
+  }
+
+  /// Returns the foreground window together with the work area of its monitor and the snap zones of that work area
+  /// that it can be placed in using place mode (see [`Sizing::snap_zones`]), or the outcome if place mode does not
+  /// apply to the foreground window.
+  pub fn get_place_mode_zones(&self) -> Result<(WindowHandle, Rect, Vec<Sizing>), CommandOutcome> {
+    let Some(window) = self.get_managed_foreground_window() else {
+      return Err(CommandOutcome::no_op(NO_FOREGROUND_WINDOW_REASON));
+    };
+    if self.get_layout_for_window(window) == Some(Layout::Scrolling) {
+      return Err(CommandOutcome::no_op(SCROLLING_LAYOUT_REASON));
+    }
+    if self.is_window_free_floating(window) {
+      return Err(CommandOutcome::no_op(FREE_FLOATING_REASON));
+    }
+    let Some(monitor_info) = self.windows_api.get_monitor_info_for_window(window) else {
+      return Err(CommandOutcome::error(NO_MONITOR_REASON));
+    };
+    let zones = Sizing::snap_zones(monitor_info.work_area, self.margin_for_window(window));
+
+    Ok((window, monitor_info.work_area, zones))
+  }
+
+  /// Moves a window into the snap zone that was selected for it in place mode, as if it had been dragged into that snap
+  /// zone using the mouse.
+  pub fn place_window_in_zone(&mut self, window: WindowHandle, zone: Sizing) -> CommandOutcome {
+    if !self.windows_api.is_window_alive(window) {
+      return CommandOutcome::no_op("the window was closed in the meantime");
+    }
+    debug!("Placing {} in zone {:?}", window, zone);
+    let margin = self.margin_for_window(window);
+    self.placement.resize(&self.windows_api, window, zone, margin);
+
+    CommandOutcome::Success
   }
 
   /// Snaps a completed mouse resize when the window belongs to scrolling layout. Expected to be called after the user
@@ -256,16 +696,57 @@ impl<T: WindowsApi + Clone> WindowManager<T> {
     if self.get_layout_for_window(window) != Some(Layout::Scrolling) {
       return;
     }
-    let margin = self.margin();
+    let margin = self.margin_for_window(window);
     self
       .scrolling
       .finish_mouse_resize(&self.windows_api, &self.workspace_manager, window, margin);
   }
 
+  /// Takes the foreground back if `window` stole it from a window of an active do-not-disturb workspace, i.e. if
+  /// `window` belongs to another application and became the foreground window without any recent user input.
+  /// Otherwise, remembers `window` as the most recently focused window.
+  pub fn guard_foreground_window(&mut self, window: WindowHandle) {
+    let Some(disturbed_window) = self.find_window_disturbed_by(window) else {
+      self.spatial.focus_history.record(window);
+      return;
+    };
+    warn!(
+      "Prevented {} \"{}\" of [{}] from stealing the foreground from {} on a do-not-disturb workspace",
+      window,
+      self.windows_api.get_window_title(&window),
+      self.windows_api.get_window_process_name(window).unwrap_or_default(),
+      disturbed_window
+    );
+    self.windows_api.set_foreground_window(disturbed_window);
+  }
+
+  /// Forgets the position a window had before it was near-maximised, if the window has since been moved by another
+  /// application (e.g. Windows Snap or FancyZones). Restoring the window would otherwise make it jump to a position that
+  /// is no longer relevant.
+  pub fn forget_placement_if_moved_externally(&mut self, window: WindowHandle) {
+    if !self.placement.remembers(window) {
+      return;
+    }
+    let Some(window_placement) = self.windows_api.get_window_placement(window) else {
+      return;
+    };
+    let Some(monitor_info) = self.windows_api.get_monitor_info_for_window(window) else {
+      return;
+    };
+    let margin = self.margin_for_window(window);
+    if !self
+      .placement
+      .is_near_maximised(&self.windows_api, &window_placement, &window, &monitor_info, margin)
+    {
+      debug!("{} is no longer near-maximised, forgetting its previous placement", window);
+      self.placement.forget(window);
+    }
+  }
+
   /// Moves focus and the cursor using navigation rules for the current layout.
   pub fn move_cursor(&mut self, direction: Direction) {
     if matches!(direction, Direction::Left | Direction::Right) {
-      let margin = self.margin();
+      let margin = self.margin_for_foreground_window();
       let animation_duration = self.scrolling_animation_duration();
       if self.scrolling.move_focus(
         &self.windows_api,
@@ -288,74 +769,1003 @@ impl<T: WindowsApi + Clone> WindowManager<T> {
       .lock()
       .expect(CONFIGURATION_PROVIDER_LOCK)
       .get_bool(ALLOW_SELECTING_SAME_CENTER_WINDOWS);
-    navigation::move_cursor(
+    let focused_window = navigation::move_cursor(
       &self.windows_api,
       direction,
       &eligible,
       self.virtual_desktop_manager.as_ref(),
       allow_same_center,
+      &self.spatial.focus_history,
     );
+    if let Some(window) = focused_window {
+      self.spatial.focus_history.record(window);
+    }
+  }
+
+  /// Focuses the next or, if `is_next` is `false`, the previous window of the workspace on the monitor of the
+  /// foreground window (or, if there is none, under the cursor). Unlike [`Self::move_cursor`], this reaches every
+  /// window, including windows that perfectly overlap.
+  pub fn cycle_focus(&mut self, is_next: bool) -> CommandOutcome {
+    let monitor = match self.windows_api.get_foreground_window() {
+      Some(window) => self.windows_api.get_monitor_handle_for_window_handle(window),
+      None => self
+        .windows_api
+        .get_monitor_handle_for_point(&self.windows_api.get_cursor_position()),
+    };
+    let windows = self.windows_api.get_all_visible_windows();
+    let candidates = windows
+      .iter()
+      .filter(|window| self.scrolling.is_navigation_eligible(window.handle))
+      .filter(|window| self.windows_api.get_monitor_handle_for_window_handle(window.handle) == monitor)
+      .filter(|window| {
+        self
+          .virtual_desktop_manager
+          .as_ref()
+          .is_none_or(|vdm| self.windows_api.is_window_on_current_desktop(vdm, window))
+      })
+      .collect::<Vec<_>>();
+    let order = self
+      .configuration_provider
+      .lock()
+      .expect(CONFIGURATION_PROVIDER_LOCK)
+      .get_focus_cycle_order();
+    match self.spatial.cycle_focus(&self.windows_api, &candidates, order, is_next) {
+      Some(_) => CommandOutcome::Success,
+      None => CommandOutcome::no_op("there is no other window on the current workspace"),
+    }
+  }
+
+  /// Moves the cursor to the centre of the adjacent monitor in the given direction, skipping any windows in between.
+  pub fn throw_cursor(&mut self, direction: Direction) {
+    navigation::throw_cursor(&self.windows_api, direction);
   }
 
   /// Toggles the foreground window between near-maximised and its previous position.
-  pub fn near_maximise_or_restore(&mut self) {
+  pub fn near_maximise_or_restore(&mut self) -> CommandOutcome {
     let Some(window) = self.windows_api.get_foreground_window() else {
-      return;
+      return CommandOutcome::no_op(NO_FOREGROUND_WINDOW_REASON);
     };
+    if self.is_window_free_floating(window) {
+      return CommandOutcome::no_op(FREE_FLOATING_REASON);
+    }
     let Some(window_placement) = self.windows_api.get_window_placement(window) else {
-      return;
+      return CommandOutcome::error(NO_PLACEMENT_REASON);
     };
     let Some(monitor_info) = self.windows_api.get_monitor_info_for_window(window) else {
-      return;
+      return CommandOutcome::error(NO_MONITOR_REASON);
     };
-    let margin = self.margin();
+    let margin = self.margin_for_window(window);
     self
       .placement
       .near_maximise_or_restore(&self.windows_api, window, window_placement, monitor_info, margin);
+
+    CommandOutcome::Success
   }
 
-  /// Brings back windows hidden or moved off-screen by managed layouts.
-  pub fn restore_all_managed_windows(&mut self) {
-    self.workspace_manager.restore_all_managed_windows();
-    self.scrolling.restore_off_screen(&self.windows_api, self.margin());
+  /// Moves the foreground window to the placement remembered before its current one, cycling through the few most
+  /// recent placements on repeated use.
+  pub fn restore_older_placement(&mut self) -> CommandOutcome {
+    let Some(window) = self.windows_api.get_foreground_window() else {
+      return CommandOutcome::no_op(NO_FOREGROUND_WINDOW_REASON);
+    };
+    if self.is_window_free_floating(window) {
+      return CommandOutcome::no_op(FREE_FLOATING_REASON);
+    }
+    let Some(window_placement) = self.windows_api.get_window_placement(window) else {
+      return CommandOutcome::error(NO_PLACEMENT_REASON);
+    };
+    self.placement.restore_older(&self.windows_api, window, &window_placement);
+
+    CommandOutcome::Success
   }
 
-  /// Updates active layout state to match the visible managed windows.
-  pub fn reconcile_layouts(&mut self) {
-    let active_workspaces = self.workspace_manager.active_workspace_ids();
-    let scrolling_workspaces = active_workspaces
-      .iter()
-      .copied()
-      .filter(|workspace| self.get_layout_for_workspace(*workspace) == Some(Layout::Scrolling))
-      .collect::<Vec<_>>();
-    let spatial_workspaces = active_workspaces
-      .into_iter()
-      .filter(|workspace| self.get_layout_for_workspace(*workspace) == Some(Layout::Spatial))
-      .collect::<Vec<_>>();
-    let margin = self.margin();
+  /// Shrinks the foreground window to the configured size and pins it on top in the configured corner, keeping it
+  /// visible across workspace switches. Restores its previous placement if it is already pinned.
+  pub fn toggle_picture_in_picture(&mut self) -> CommandOutcome {
+    let Some(window) = self.windows_api.get_foreground_window() else {
+      return CommandOutcome::no_op(NO_FOREGROUND_WINDOW_REASON);
+    };
+    if self.placement.unpin_picture_in_picture(&self.windows_api, window) {
+      self.workspace_manager.set_window_sticky(window, false);
+      return CommandOutcome::Success;
+    }
+    let Some(window_placement) = self.windows_api.get_window_placement(window) else {
+      return CommandOutcome::error(NO_PLACEMENT_REASON);
+    };
+    let Some(monitor_info) = self.windows_api.get_monitor_info_for_window(window) else {
+      return CommandOutcome::error(NO_MONITOR_REASON);
+    };
+    let settings = self
+      .configuration_provider
+      .lock()
+      .expect(CONFIGURATION_PROVIDER_LOCK)
+      .get_picture_in_picture()
+      .clone();
+    let (horizontal, vertical) = settings.corner.to_directions();
+    let rect = geometry::in_corner(
+      monitor_info.work_area,
+      settings.width,
+      settings.height,
+      horizontal,
+      vertical,
+      self.margin_for_window(window),
+    );
     self
-      .scrolling
-      .deactivate(&self.windows_api, &self.workspace_manager, &spatial_workspaces, margin);
-    self.scrolling.reconcile(
+      .placement
+      .pin_picture_in_picture(&self.windows_api, window, window_placement, rect);
+    self.workspace_manager.set_window_sticky(window, true);
+
+    CommandOutcome::Success
+  }
+
+  /// Near-maximises the foreground window and hides all other windows of its workspace, or restores the hidden windows
+  /// and the previous placement of the window if the workspace of the foreground window (or, if there is none, the
+  /// workspace under the cursor) is already in monocle mode. No-ops in scrolling layout and on free-floating workspaces.
+  pub fn toggle_monocle(&mut self) -> CommandOutcome {
+    let window = self.windows_api.get_foreground_window();
+    let Some(workspace) = window
+      .and_then(|window| self.get_workspace_for_window(window))
+      .or_else(|| self.get_active_workspace_on_monitor(self.get_monitor_id_under_cursor()?))
+    else {
+      return CommandOutcome::error("there is no workspace for the foreground window or under the cursor");
+    };
+    if self.monocle.is_active(workspace) {
+      self.monocle.exit(&self.windows_api, workspace);
+      return CommandOutcome::Success;
+    }
+    let Some(window) = window else {
+      return CommandOutcome::no_op(NO_FOREGROUND_WINDOW_REASON);
+    };
+    if self.get_layout_for_workspace(workspace) == Some(Layout::Scrolling) {
+      return CommandOutcome::no_op(format!("workspace [{workspace}] uses the scrolling layout"));
+    }
+    if self.is_workspace_free_floating(workspace) {
+      return CommandOutcome::no_op(format!("workspace [{workspace}] is free-floating"));
+    }
+    let margin = self.margin_for_workspace(workspace);
+    self.monocle.enter(
       &self.windows_api,
       &self.workspace_manager,
-      &scrolling_workspaces,
-      self.virtual_desktop_manager.as_ref(),
+      &self.placement,
+      workspace,
+      window,
       margin,
     );
+
+    CommandOutcome::Success
   }
 
-  fn execute_post_close_or_minimise_layout_specific_logic(&mut self, window: WindowHandle, layout: Option<Layout>) {
-    match layout {
-      Some(Layout::Scrolling) => {
-        let margin = self.margin();
-        self
-          .scrolling
-          .remove_and_refocus(&self.windows_api, &self.workspace_manager, window, margin);
-      }
-      _ => self
-        .spatial
-        .after_close_or_minimise(&self.windows_api, window, self.allow_moving_cursor_after_close_or_minimise),
+  /// Stops managing the foreground window, or resumes managing it if it is ignored already, and reconciles the layouts
+  /// afterwards. Leaves monocle mode on the workspace of a window that is about to be ignored first. Ignored windows are skipped by every command and layout until Randolf exits, which allows exempting a
+  /// misbehaving application without changing the exclusion settings.
+  pub fn toggle_manage_window(&mut self) -> CommandOutcome {
+    if let Some(window) = self.windows_api.get_ignored_foreground_window() {
+      self.windows_api.set_window_ignored(window, false);
+      self.reconcile_layouts();
+      info!("Resumed managing {window}");
+      return CommandOutcome::Success;
+    }
+    let Some(window) = self.windows_api.get_foreground_window() else {
+      return CommandOutcome::no_op(NO_FOREGROUND_WINDOW_REASON);
+    };
+    if let Some(workspace) = self.get_workspace_for_window(window) {
+      self.monocle.exit(&self.windows_api, workspace);
+    }
+    self.windows_api.set_window_ignored(window, true);
+    self.reconcile_layouts();
+    info!("Stopped managing {window} until it is toggled again or Randolf exits");
+
+    CommandOutcome::Success
+  }
+
+  /// Describes every window that is currently ignored (see [`Self::toggle_manage_window`]) and still exists, so that
+  /// they can be ignored again after Randolf has restarted.
+  pub fn capture_ignored_windows(&self) -> IgnoredWindowsFile {
+    let windows = self
+      .windows_api
+      .get_ignored_windows()
+      .into_iter()
+      .filter(|handle| self.windows_api.is_window_alive(*handle))
+      .map(|handle| IgnoredWindow {
+        handle,
+        class_name: self.windows_api.get_window_class_name(&handle),
+      })
+      .collect();
+
+    IgnoredWindowsFile::new(windows)
+  }
+
+  /// Ignores every window of the file that still exists and belongs to the same application, e.g. after Randolf has
+  /// restarted, and reconciles the layouts afterwards.
+  pub fn restore_ignored_windows(&mut self, file: &IgnoredWindowsFile) {
+    let windows = file
+      .windows
+      .iter()
+      .filter(|window| self.windows_api.is_window_alive(window.handle))
+      .filter(|window| self.windows_api.get_window_class_name(&window.handle) == window.class_name)
+      .map(|window| window.handle)
+      .collect::<Vec<_>>();
+    if windows.is_empty() {
+      return;
+    }
+    for window in &windows {
+      self.windows_api.set_window_ignored(*window, true);
+    }
+    self.reconcile_layouts();
+    info!("Restored [{}] ignored window(s)", windows.len());
+  }
+
+  /// Returns the monitors whose active workspace is free-floating as a shared reference that is updated by
+  /// [`WindowManager::refresh_free_floating_monitors`], so that the mouse hook can skip snapping on them.
+  pub fn get_shared_free_floating_monitors(&self) -> Arc<RwLock<HashSet<[u16; 32]>>> {
+    self.free_floating_monitors.clone()
+  }
+
+  /// Updates the monitors whose active workspace is free-floating. Expected to be called whenever the active workspaces
+  /// or the configuration may have changed.
+  pub fn refresh_free_floating_monitors(&self) {
+    let monitors = self
+      .workspace_manager
+      .active_workspace_ids()
+      .into_iter()
+      .filter(|workspace| self.is_workspace_free_floating(*workspace))
+      .map(|workspace| workspace.monitor_id)
+      .collect::<HashSet<_>>();
+    *self.free_floating_monitors.write().expect(FREE_FLOATING_MONITORS_LOCK) = monitors;
+  }
+
+  /// Returns the active workspace of the monitor under the cursor together with the work area of that monitor.
+  pub fn get_active_workspace_under_cursor(&self) -> Option<(PersistentWorkspaceId, Rect)> {
+    let workspace = self.get_active_workspace_on_monitor(self.get_monitor_id_under_cursor()?)?;
+    let monitor = self.workspace_manager.monitor_for_workspace(workspace)?;
+
+    Some((workspace, monitor.work_area))
+  }
+
+  /// Brings back windows hidden or moved off-screen by managed layouts.
+  pub fn restore_all_managed_windows(&mut self) {
+    self.monocle.exit_all(&self.windows_api);
+    self.workspace_manager.restore_all_managed_windows();
+    self.scrolling.restore_off_screen(&self.windows_api, &self.margins());
+  }
+
+  /// Returns the monitors that were disconnected since the last call and whose inactive workspaces still store windows,
+  /// together with the number of such windows per monitor.
+  pub fn find_parked_windows_of_disconnected_monitors(&mut self) -> Vec<([u16; 32], usize)> {
+    self.workspace_manager.find_parked_windows_of_disconnected_monitors()
+  }
+
+  /// Makes the windows stored in the inactive workspaces of a disconnected monitor visible on the primary monitor.
+  pub fn move_parked_windows_to_primary_monitor(&mut self, monitor_id: [u16; 32]) {
+    self.workspace_manager.move_parked_windows_to_primary_monitor(monitor_id);
+  }
+
+  /// Returns `true` if the known monitor state no longer matches the connected monitors, e.g. because monitor handles
+  /// changed after a display driver reset.
+  pub fn has_stale_monitor_state(&self) -> bool {
+    self.workspace_manager.has_stale_monitors()
+  }
+
+  /// Creates a workspace for every monitor that was connected since the last call, so that it can be switched to.
+  pub fn add_workspaces_for_new_monitors(&mut self) {
+    let added_workspace_ids = self.workspace_manager.add_workspaces_for_new_monitors();
+    if !added_workspace_ids.is_empty() {
+      self.reconcile_layouts();
+    }
+  }
+
+  /// Rebuilds the monitor state of all workspaces and layouts without restarting the application, e.g. after a display
+  /// driver reset left monitor handles and work areas stale.
+  pub fn reinitialise(&mut self) {
+    self.workspace_manager.refresh_monitors();
+    self.spatial.record_work_areas(&self.windows_api);
+    self.reconcile_layouts();
+    info!("Reinitialised monitor and workspace state");
+  }
+
+  /// Re-fits the windows of every monitor whose active workspace uses the spatial layout and whose work area changed,
+  /// e.g. because the taskbar was moved. Monitors with a scrolling or tiling workspace or a free-floating workspace are
+  /// left alone.
+  pub fn refit_windows_after_work_area_change(&mut self) {
+    let monitor_ids = self
+      .workspace_manager
+      .active_workspace_ids()
+      .into_iter()
+      .filter(|workspace| {
+        !self.is_workspace_free_floating(*workspace) && self.get_layout_for_workspace(*workspace) == Some(Layout::Spatial)
+      })
+      .map(|workspace| workspace.monitor_id)
+      .collect::<Vec<_>>();
+    let margins = self.margins();
+    self
+      .spatial
+      .refit_windows_after_work_area_change(&self.windows_api, &self.placement, &margins, &monitor_ids);
+  }
+
+  /// Forgets windows stored in inactive workspaces and the previous placements of windows whose window or process no
+  /// longer exists.
+  pub fn remove_dead_windows(&mut self) {
+    let dead_windows = self.workspace_manager.remove_dead_windows();
+    if !dead_windows.is_empty() {
+      debug!("Cleaned up [{}] dead window(s) from workspaces", dead_windows.len());
+    }
+    self.placement.remove_dead_windows(&self.windows_api);
+  }
+
+  /// Updates active layout state to match the visible managed windows.
+  pub fn reconcile_layouts(&mut self) {
+    self.spatial.record_foreground_window(&self.windows_api);
+    let active_workspaces = self.workspace_manager.active_workspace_ids();
+    let (free_floating_workspaces, managed_workspaces): (Vec<_>, Vec<_>) = active_workspaces
+      .into_iter()
+      .partition(|workspace| self.is_workspace_free_floating(*workspace));
+    let scrolling_workspaces = managed_workspaces
+      .iter()
+      .copied()
+      .filter(|workspace| self.get_layout_for_workspace(*workspace) == Some(Layout::Scrolling))
+      .collect::<Vec<_>>();
+    // Windows of free-floating workspaces are released from their strips like those of spatial workspaces
+    let spatial_workspaces = managed_workspaces
+      .into_iter()
+      .filter(|workspace| self.get_layout_for_workspace(*workspace) == Some(Layout::Spatial))
+      .chain(free_floating_workspaces)
+      .collect::<Vec<_>>();
+    let margins = self.margins();
+    self.apply_rules_to_newly_shown_windows();
+    self.tile_workspaces(&margins);
+    self
+      .scrolling
+      .deactivate(&self.windows_api, &self.workspace_manager, &spatial_workspaces, &margins);
+    self.scrolling.reconcile(
+      &self.windows_api,
+      &self.workspace_manager,
+      &scrolling_workspaces,
+      self.virtual_desktop_manager.as_ref(),
+      &margins,
+    );
+  }
+
+  /// Applies the window rules to a window whose title changed if it has only now become a managed window. Some windows
+  /// are created with an empty (and therefore excluded) title and only set their real title later, which means window
+  /// rules matching their real title are applied right away rather than with the next periodic reconciliation. Windows
+  /// that were managed already are ignored, since browsers and terminals change their titles constantly.
+  pub fn reevaluate_window_after_title_change(&mut self, window: WindowHandle) {
+    let Some(window) = self.spatial.take_newly_shown_window(&self.windows_api, window) else {
+      return;
+    };
+    trace!("Re-evaluating window rules for {} after its title changed", window.handle);
+    let rules = self
+      .configuration_provider
+      .lock()
+      .expect(CONFIGURATION_PROVIDER_LOCK)
+      .get_window_rules()
+      .to_vec();
+    self.apply_rules_to_new_window(&rules, &window);
+  }
+
+  /// Describes the workspaces and visible windows as plain text, e.g. to include it in a support bundle. Window titles
+  /// often contain private information such as email subjects or document names, which is why they are redacted unless
+  /// `include_window_titles` is `true`.
+  pub fn describe_state(&mut self, include_window_titles: bool) -> String {
+    let mut lines = vec![format!(
+      "Foreground window: {}",
+      self
+        .windows_api
+        .get_foreground_window()
+        .map_or("none".to_string(), |window| window.to_string())
+    )];
+    lines.push("Workspaces:".to_string());
+    for id in self.workspace_manager.get_ordered_permanent_workspace_ids() {
+      let status = if self.workspace_manager.is_workspace_active(id) {
+        "active"
+      } else {
+        "inactive"
+      };
+      lines.push(format!("- {id} ({status})"));
+    }
+    lines.push("Visible windows:".to_string());
+    for window in self.windows_api.get_all_visible_windows() {
+      let title = match include_window_titles {
+        true => window.title_trunc(),
+        false => REDACTED_WINDOW_TITLE.to_string(),
+      };
+      lines.push(format!(
+        "- {} \"{}\" at {}, managed: {}, layout: {:?}, remembered placement: {}",
+        window.handle,
+        title,
+        window.rect,
+        !self.windows_api.is_not_a_managed_window(&window.handle),
+        self.get_layout_for_window(window.handle),
+        self.placement.remembers(window.handle)
+      ));
+    }
+
+    lines.join("\n")
+  }
+
+  /// Lists the managed windows that match the query, i.e. the visible windows on active workspaces followed by the
+  /// windows hidden in inactive workspaces, so that callers do not need to enumerate windows themselves.
+  pub fn list_windows(&self, query: &WindowQuery) -> Vec<WindowSummary> {
+    let visible_windows = self
+      .windows_api
+      .get_all_visible_windows()
+      .into_iter()
+      .filter(|window| !self.windows_api.is_not_a_managed_window(&window.handle))
+      .filter_map(|window| {
+        let workspace = self.get_workspace_for_window(window.handle)?;
+        Some(self.summarise_window(window, workspace, WindowVisibility::Visible))
+      })
+      .collect::<Vec<_>>();
+    let hidden_windows = self
+      .workspace_manager
+      .stored_windows()
+      .into_iter()
+      .map(|(workspace, window)| self.summarise_window(window, workspace, WindowVisibility::Hidden));
+
+    visible_windows
+      .into_iter()
+      .chain(hidden_windows)
+      .filter(|summary| query.matches(summary))
+      .collect()
+  }
+
+  /// Lists all managed windows for the window switcher, starting with the windows of the active workspace under the
+  /// cursor, followed by the windows of the active workspaces of other monitors and the windows hidden in inactive
+  /// workspaces.
+  pub fn list_windows_for_switcher(&self) -> Vec<WindowSummary> {
+    let mut windows = self.list_windows(&WindowQuery::default());
+    if let Some((current, _)) = self.get_active_workspace_under_cursor() {
+      let monitor_id = current.id_to_string();
+      windows.sort_by_key(|window| !(window.monitor_id == monitor_id && window.workspace == current.workspace));
+    }
+
+    windows
+  }
+
+  /// Focuses a managed window, switching to its workspace first if it is hidden in an inactive workspace. Returns the ID
+  /// of the workspace that was switched to, if any, or the outcome if the window cannot be focused.
+  pub fn switch_to_window(&mut self, handle: WindowHandle) -> Result<Option<PersistentWorkspaceId>, CommandOutcome> {
+    let hidden_in = self
+      .workspace_manager
+      .stored_windows()
+      .into_iter()
+      .find(|(_, window)| window.handle == handle)
+      .map(|(workspace, _)| workspace);
+    match hidden_in {
+      Some(workspace) => {
+        let outcome = self.switch_workspace(workspace);
+        if !outcome.is_success() {
+          return Err(outcome);
+        }
+      }
+      None if self.get_workspace_for_window(handle).is_none() => {
+        return Err(CommandOutcome::error(format!("window [{handle}] is not managed")));
+      }
+      None => {}
+    }
+    self.windows_api.set_foreground_window(handle);
+
+    Ok(hidden_in)
+  }
+
+  /// Describes every workspace, ordered by monitor position and then by workspace number, including whether it is
+  /// active, how many managed windows it contains, and when it was last used. Hotkey numbers and names are left empty
+  /// because they are not known to the window manager.
+  pub fn list_workspaces(&mut self) -> Vec<WorkspaceSummary> {
+    let windows = self.list_windows(&WindowQuery::default());
+    self
+      .workspace_manager
+      .get_ordered_permanent_workspace_ids()
+      .into_iter()
+      .map(|id| {
+        let monitor_id = id.id_to_string();
+        let window_count = windows
+          .iter()
+          .filter(|window| window.monitor_id == monitor_id && window.workspace == id.workspace)
+          .count();
+        let last_used_at = self
+          .workspace_manager
+          .workspace_last_used_at(id)
+          .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+          .map(|duration| duration.as_secs());
+        WorkspaceSummary {
+          monitor_id,
+          workspace: id.workspace,
+          number: None,
+          name: None,
+          is_active: self.workspace_manager.is_workspace_active(id),
+          window_count,
+          last_used_at,
+        }
+      })
+      .collect()
+  }
+
+  /// Returns a badge for each monitor that shows the number of each of its workspaces and which one is active. The
+  /// numbers are those of the `switch_to_workspace_<number>` hotkeys, so workspaces without a hotkey have none.
+  pub fn list_workspace_badges(&mut self, numbered_workspace_ids: &[(usize, PersistentWorkspaceId)]) -> Vec<WorkspaceBadge> {
+    let mut badges: Vec<WorkspaceBadge> = vec![];
+    for id in self.workspace_manager.get_ordered_permanent_workspace_ids() {
+      let Some(monitor) = self.workspace_manager.monitor_for_workspace(id) else {
+        continue;
+      };
+      let number = numbered_workspace_ids
+        .iter()
+        .find(|(_, numbered_id)| *numbered_id == id)
+        .map(|(number, _)| *number);
+      let workspace = (number, self.workspace_manager.is_workspace_active(id));
+      match badges.iter_mut().find(|badge| badge.work_area == monitor.work_area) {
+        Some(badge) => badge.workspaces.push(workspace),
+        None => badges.push(WorkspaceBadge {
+          work_area: monitor.work_area,
+          workspaces: vec![workspace],
+        }),
+      }
+    }
+
+    badges
+  }
+
+  /// Describes the placement, workspace, and monitor of every managed window, including the windows hidden on inactive
+  /// workspaces, so that they can be saved as a session.
+  pub fn capture_session(&self) -> SessionFile {
+    let windows = self
+      .locate_managed_windows()
+      .into_iter()
+      .map(|(workspace, window)| SessionWindow {
+        handle: window.handle,
+        class_name: self.windows_api.get_window_class_name(&window.handle),
+        process_name: self.windows_api.get_window_process_name(window.handle),
+        executable_path: self.windows_api.get_window_executable_path(window.handle),
+        title: window.title,
+        workspace,
+        rect: window.rect,
+      })
+      .collect();
+
+    SessionFile::new(windows)
+  }
+
+  /// Moves every window that matches a window of the session to the workspace and rect that window had when the session
+  /// was saved, hiding it if that workspace is inactive, and reconciles the layouts afterwards. Returns the windows of
+  /// the session that no window matches, e.g. because their application is not running.
+  pub fn restore_session<'a>(&mut self, session: &'a SessionFile) -> Vec<&'a SessionWindow> {
+    let windows = self.list_windows(&WindowQuery::default());
+    let (matched_windows, missing_windows) = session.match_windows(&windows);
+    let placements = matched_windows
+      .iter()
+      .map(|(handle, session_window)| (*handle, session_window.workspace, session_window.rect))
+      .collect::<Vec<_>>();
+    self.monocle.exit_all(&self.windows_api);
+    let placed_count = self.workspace_manager.place_windows(&placements);
+    self.reconcile_layouts();
+    info!(
+      "Restored [{}] of [{}] window(s) of the session, [{}] window(s) are missing",
+      placed_count,
+      session.windows.len(),
+      missing_windows.len()
+    );
+
+    missing_windows
+  }
+
+  /// Re-evaluates every managed window against the current window rules, moving windows to the workspace their first
+  /// matching rule requires and moving windows that a rule moved but that no rule requires to be elsewhere anymore, e.g.
+  /// because the rule was removed, back to the workspace they were on before. Windows on free-floating workspaces are
+  /// left alone.
+  pub fn reconcile_window_rules(&mut self) -> CommandOutcome {
+    let rules = self
+      .configuration_provider
+      .lock()
+      .expect(CONFIGURATION_PROVIDER_LOCK)
+      .get_window_rules()
+      .to_vec();
+    let windows_api = &self.windows_api;
+    self
+      .rule_placed_windows
+      .retain(|handle, _| windows_api.is_window_alive(*handle));
+    let workspace_ids = self.get_ordered_permanent_workspace_ids();
+    let mut placements = Vec::new();
+    for (current_workspace, window) in self.locate_managed_windows() {
+      if self.is_workspace_free_floating(current_workspace) {
+        continue;
+      }
+      let target_workspace = self
+        .find_rule_for_window(&rules, &window)
+        .and_then(|rule| self.find_rule_workspace(rule, &workspace_ids));
+      let target_workspace = match target_workspace {
+        Some(target_workspace) => {
+          self.rule_placed_windows.entry(window.handle).or_insert(current_workspace);
+          target_workspace
+        }
+        None => match self.rule_placed_windows.remove(&window.handle) {
+          Some(original_workspace) => original_workspace,
+          None => continue,
+        },
+      };
+      if target_workspace == current_workspace {
+        continue;
+      }
+      if let Some(rect) = self.fit_window_to_workspace(&window, current_workspace, target_workspace) {
+        placements.push((window.handle, target_workspace, rect));
+      }
+    }
+    if placements.is_empty() {
+      return CommandOutcome::no_op("every window is already on the workspace its window rules require");
+    }
+    self.monocle.exit_all(&self.windows_api);
+    let placed_count = self.workspace_manager.place_windows(&placements);
+    self.reconcile_layouts();
+    info!("Moved [{placed_count}] window(s) to the workspace required by the current window rules");
+
+    CommandOutcome::Success
+  }
+
+  /// Moves every visible, managed window that the user cannot reach with the mouse, e.g. because it was left behind
+  /// at the coordinates of a monitor that has since been disconnected, into the centre of the nearest work area.
+  pub fn rescue_offscreen_windows(&mut self) -> CommandOutcome {
+    let mut rescued_count = 0;
+    for window in self.windows_api.get_all_visible_windows() {
+      if self.windows_api.is_not_a_managed_window(&window.handle) || self.windows_api.is_window_minimised(window.handle) {
+        continue;
+      }
+      let rect = self.placement.keep_on_screen(&self.windows_api, window.handle, window.rect);
+      if rect != window.rect {
+        self.windows_api.set_window_position(window.handle, rect);
+        rescued_count += 1;
+      }
+    }
+    if rescued_count == 0 {
+      return CommandOutcome::no_op("no window is off-screen");
+    }
+    info!("Moved [{rescued_count}] off-screen window(s) back onto the nearest monitor");
+
+    CommandOutcome::Success
+  }
+
+  /// Describes the currently detected monitors as plain text, e.g. to include it in a support bundle.
+  /// Returns a single-line summary of the number of managed windows and of the windows stored per workspace, which is
+  /// written to the log with every heartbeat so that leaks or drift become visible in long-running sessions.
+  pub fn describe_window_metrics(&self) -> String {
+    let managed_window_count = self
+      .windows_api
+      .get_all_visible_windows()
+      .iter()
+      .filter(|window| !self.windows_api.is_not_a_managed_window(&window.handle))
+      .count();
+    let stored_window_counts = self
+      .workspace_manager
+      .stored_window_counts()
+      .iter()
+      .map(|(id, count)| format!("{id}: {count}"))
+      .collect::<Vec<_>>()
+      .join(", ");
+
+    format!("[{managed_window_count}] managed window(s) visible, stored window(s) per workspace: [{stored_window_counts}]")
+  }
+
+  pub fn describe_monitors(&self) -> String {
+    self
+      .windows_api
+      .get_all_monitors()
+      .get_all()
+      .iter()
+      .map(|monitor| format!("{monitor}, work area: {}", monitor.work_area))
+      .collect::<Vec<_>>()
+      .join("\n")
+  }
+
+  /// Applies the actions of the first matching window rule to each window that is shown for the first time. Border
+  /// colours are applied in any layout whereas near-maximising only applies to spatial layout windows that a rule did
+  /// not move to another workspace.
+  fn apply_rules_to_newly_shown_windows(&mut self) {
+    let new_windows = self.spatial.take_newly_shown_windows(&self.windows_api);
+    if new_windows.is_empty() {
+      return;
+    }
+    let rules = self
+      .configuration_provider
+      .lock()
+      .expect(CONFIGURATION_PROVIDER_LOCK)
+      .get_window_rules()
+      .to_vec();
+    if rules.is_empty() {
+      return;
+    }
+    for window in new_windows {
+      self.apply_rules_to_new_window(&rules, &window);
+    }
+  }
+
+  /// Applies the actions of the first matching window rule to a window that is shown for the first time.
+  fn apply_rules_to_new_window(&mut self, rules: &[WindowRule], window: &Window) {
+    if self.is_window_free_floating(window.handle) {
+      return;
+    }
+    let Some(rule) = self.find_rule_for_window(rules, window) else {
+      return;
+    };
+    debug!("Applying window rule {:?} to {}", rule, window.handle);
+    if let Some(colour) = rule.get_border_colour() {
+      self.windows_api.set_window_border_colour(window.handle, colour);
+    }
+    if rule.workspace.is_some() && self.move_window_to_rule_workspace(window, rule) {
+      return;
+    }
+    if rule.auto_near_maximise && self.get_layout_for_window(window.handle) == Some(Layout::Spatial) {
+      self.auto_near_maximise(window.handle, self.margin_for_window(window.handle));
+    }
+  }
+
+  fn find_rule_for_window<'a>(&self, rules: &'a [WindowRule], window: &Window) -> Option<&'a WindowRule> {
+    let class_name = self.windows_api.get_window_class_name(&window.handle);
+    let is_elevated = self.windows_api.is_window_elevated(window.handle);
+
+    rules
+      .iter()
+      .find(|rule| rule.matches(&window.title, &class_name, is_elevated))
+  }
+
+  /// Returns the permanent workspace that the rule moves matching windows to, if any.
+  fn find_rule_workspace(
+    &self,
+    rule: &WindowRule,
+    workspace_ids: &[PersistentWorkspaceId],
+  ) -> Option<PersistentWorkspaceId> {
+    workspace_ids
+      .iter()
+      .copied()
+      .find(|id| rule.moves_to_workspace(&id.id_to_string(), id.is_on_primary_monitor(), id.workspace))
+  }
+
+  /// Moves a newly shown window to the workspace the rule requires and remembers the workspace it was shown on. Returns
+  /// `true` if the window was moved.
+  fn move_window_to_rule_workspace(&mut self, window: &Window, rule: &WindowRule) -> bool {
+    let workspace_ids = self.get_ordered_permanent_workspace_ids();
+    let Some(target_workspace) = self.find_rule_workspace(rule, &workspace_ids) else {
+      warn!(
+        "Ignoring workspace [{:?}] of window rule because monitor [{}] has no such workspace",
+        rule.workspace,
+        rule.monitor.as_deref().unwrap_or("primary")
+      );
+      return false;
+    };
+    let Some(current_workspace) = self.get_workspace_for_window(window.handle) else {
+      return false;
+    };
+    if target_workspace == current_workspace {
+      return false;
+    }
+    let Some(rect) = self.fit_window_to_workspace(window, current_workspace, target_workspace) else {
+      return false;
+    };
+    if self
+      .workspace_manager
+      .place_windows(&[(window.handle, target_workspace, rect)])
+      == 0
+    {
+      return false;
+    }
+    self.rule_placed_windows.insert(window.handle, current_workspace);
+    info!(
+      "Moved {} \"{}\" to workspace [{}] as required by its window rule",
+      window.handle,
+      window.title_trunc(),
+      target_workspace
+    );
+
+    true
+  }
+
+  /// Returns every managed window together with its workspace, including the windows hidden on inactive workspaces.
+  fn locate_managed_windows(&self) -> Vec<(PersistentWorkspaceId, Window)> {
+    let visible_windows = self
+      .windows_api
+      .get_all_visible_windows()
+      .into_iter()
+      .filter(|window| !self.windows_api.is_not_a_managed_window(&window.handle))
+      .filter_map(|window| Some((self.get_workspace_for_window(window.handle)?, window)));
+
+    visible_windows.chain(self.workspace_manager.stored_windows()).collect()
+  }
+
+  /// Returns the rect the window should have on workspace `to`, keeping its rect if both workspaces are on the same
+  /// monitor and otherwise refitting or centring it within the work area of the other monitor.
+  fn fit_window_to_workspace(
+    &self,
+    window: &Window,
+    from: PersistentWorkspaceId,
+    to: PersistentWorkspaceId,
+  ) -> Option<Rect> {
+    if from.is_same_monitor(&to) {
+      return Some(window.rect);
+    }
+    let from_monitor = self.workspace_manager.monitor_for_workspace(from)?;
+    let to_monitor = self.workspace_manager.monitor_for_workspace(to)?;
+    let rect = Sizing::from(window.rect)
+      .refit(
+        from_monitor.work_area,
+        to_monitor.work_area,
+        self.margin_for_monitor(&to_monitor),
+      )
+      .map(Rect::from)
+      .unwrap_or_else(|| geometry::centre_within(&window.rect, &to_monitor.work_area));
+
+    Some(rect)
+  }
+
+  /// Re-tiles the active tiled workspaces of monitors using the spatial layout whose windows changed since they were
+  /// last tiled. Free-floating workspaces are never tiled.
+  fn tile_workspaces(&mut self, margins: &MonitorMargins) {
+    let tiled_workspaces = self
+      .workspace_manager
+      .active_workspace_ids()
+      .into_iter()
+      .filter(|workspace| self.get_layout_for_workspace(*workspace) == Some(Layout::Spatial))
+      .filter(|workspace| !self.is_workspace_free_floating(*workspace))
+      .filter_map(|workspace| {
+        self
+          .configuration_provider
+          .lock()
+          .expect(CONFIGURATION_PROVIDER_LOCK)
+          .get_tiled_workspace(&workspace.id_to_string(), workspace.workspace)
+          .map(|tiled_workspace| {
+            (
+              workspace,
+              tiled_workspace.arrangement,
+              tiled_workspace.master_width_in_percent,
+            )
+          })
+      })
+      .collect::<Vec<_>>();
+    self.tiling.reconcile(
+      &self.windows_api,
+      &self.workspace_manager,
+      &self.placement,
+      &tiled_workspaces,
+      margins,
+    );
+  }
+
+  /// Returns `true` if moving windows and the cursor is paused, e.g. because the screen is being shared or a
+  /// presentation is running. This is the case if the first window rule matching the foreground window pauses Randolf
+  /// or if a visible, full-screen window on an active screen sharing workspace matches one of the workspace's class
+  /// names.
+  pub fn is_paused_by_window(&self) -> bool {
+    self.is_paused_by_foreground_window() || self.is_paused_by_screen_sharing_window()
+  }
+
+  fn is_paused_by_foreground_window(&self) -> bool {
+    let rules = self
+      .configuration_provider
+      .lock()
+      .expect(CONFIGURATION_PROVIDER_LOCK)
+      .get_window_rules()
+      .to_vec();
+    if !rules.iter().any(|rule| rule.pause_while_foreground) {
+      return false;
+    }
+    let Some(window) = self.windows_api.get_foreground_window() else {
+      return false;
+    };
+    let title = self.windows_api.get_window_title(&window);
+    let class_name = self.windows_api.get_window_class_name(&window);
+    let is_elevated = self.windows_api.is_window_elevated(window);
+
+    rules
+      .iter()
+      .find(|rule| rule.matches(&title, &class_name, is_elevated))
+      .is_some_and(|rule| rule.pause_while_foreground)
+  }
+
+  fn is_paused_by_screen_sharing_window(&self) -> bool {
+    let screen_sharing_monitors = {
+      let configuration_provider = self.configuration_provider.lock().expect(CONFIGURATION_PROVIDER_LOCK);
+      self
+        .workspace_manager
+        .active_workspace_ids()
+        .into_iter()
+        .filter_map(|id| {
+          let screen_sharing_workspace =
+            configuration_provider.get_screen_sharing_workspace(&id.id_to_string(), id.workspace)?;
+          let monitor = self.workspace_manager.monitor_for_workspace(id)?;
+          Some((monitor.monitor_area, screen_sharing_workspace.class_names.clone()))
+        })
+        .collect::<Vec<_>>()
+    };
+    screen_sharing_monitors.iter().any(|(monitor_area, class_names)| {
+      self
+        .windows_api
+        .get_all_visible_windows_within_area(*monitor_area)
+        .iter()
+        .filter(|window| window.rect.covers(monitor_area))
+        .any(|window| class_names.contains(&self.windows_api.get_window_class_name(&window.handle)))
+    })
+  }
+
+  /// Returns the size tolerance of the first window rule matching the foreground window, or `0` if there is none.
+  fn size_tolerance_in_px_for_foreground_window(&self) -> i32 {
+    let Some(window) = self.windows_api.get_foreground_window() else {
+      return 0;
+    };
+    let title = self.windows_api.get_window_title(&window);
+    let class_name = self.windows_api.get_window_class_name(&window);
+    let is_elevated = self.windows_api.is_window_elevated(window);
+
+    self
+      .configuration_provider
+      .lock()
+      .expect(CONFIGURATION_PROVIDER_LOCK)
+      .get_window_rules()
+      .iter()
+      .find(|rule| rule.matches(&title, &class_name, is_elevated))
+      .and_then(WindowRule::get_size_tolerance_in_px)
+      .unwrap_or_default()
+  }
+
+  /// Returns `true` if the first window rule matching the window requires confirming to close it.
+  fn is_close_confirmation_required(&self, window: WindowHandle) -> bool {
+    let title = self.windows_api.get_window_title(&window);
+    let class_name = self.windows_api.get_window_class_name(&window);
+    let is_elevated = self.windows_api.is_window_elevated(window);
+
+    self
+      .configuration_provider
+      .lock()
+      .expect(CONFIGURATION_PROVIDER_LOCK)
+      .get_window_rules()
+      .iter()
+      .find(|rule| rule.matches(&title, &class_name, is_elevated))
+      .is_some_and(|rule| rule.confirm_before_close)
+  }
+
+  fn close_confirmation_timeout(&self) -> Duration {
+    let timeout = self
+      .configuration_provider
+      .lock()
+      .expect(CONFIGURATION_PROVIDER_LOCK)
+      .get_i32(CLOSE_CONFIRMATION_TIMEOUT_IN_MS);
+    Duration::from_millis(u64::try_from(timeout).unwrap_or_default())
+  }
+
+  /// Near-maximises a window unless it already is, remembering its original position so that it can be restored.
+  fn auto_near_maximise(&mut self, window: WindowHandle, margin: i32) {
+    let Some(window_placement) = self.windows_api.get_window_placement(window) else {
+      return;
+    };
+    let Some(monitor_info) = self.windows_api.get_monitor_info_for_window(window) else {
+      return;
+    };
+    if self
+      .placement
+      .is_near_maximised(&self.windows_api, &window_placement, &window, &monitor_info, margin)
+    {
+      return;
+    }
+    self.placement.remember(window, window_placement);
+    self.placement.near_maximise(&self.windows_api, window, monitor_info, margin);
+  }
+
+  fn execute_post_close_or_minimise_layout_specific_logic(&mut self, window: WindowHandle, layout: Option<Layout>) {
+    match layout {
+      Some(Layout::Scrolling) => {
+        let margin = self
+          .scrolling
+          .get_workspace_containing(window)
+          .map_or_else(|| self.default_margin(), |workspace| self.margin_for_workspace(workspace));
+        self
+          .scrolling
+          .remove_and_refocus(&self.windows_api, &self.workspace_manager, window, margin);
+      }
+      _ => {
+        let policy = self.focus_fallback_policy();
+        self.spatial.after_close_or_minimise(
+          &self.windows_api,
+          window,
+          self.allow_moving_cursor_after_close_or_minimise,
+          policy,
+        );
+      }
     }
   }
 
@@ -381,6 +1791,73 @@ impl<T: WindowsApi + Clone> WindowManager<T> {
       .or_else(|| self.workspace_manager.active_workspace_for_window(window))
   }
 
+  fn summarise_window(
+    &self,
+    window: Window,
+    workspace: PersistentWorkspaceId,
+    visibility: WindowVisibility,
+  ) -> WindowSummary {
+    WindowSummary {
+      handle: window.handle,
+      class_name: self.windows_api.get_window_class_name(&window.handle),
+      process_name: self.windows_api.get_window_process_name(window.handle),
+      title: window.title,
+      monitor_id: workspace.id_to_string(),
+      workspace: workspace.workspace,
+      visibility,
+    }
+  }
+
+  /// Returns `true` if the workspace is configured as free-floating, i.e. its windows must be left alone.
+  fn is_workspace_free_floating(&self, workspace: PersistentWorkspaceId) -> bool {
+    self
+      .configuration_provider
+      .lock()
+      .expect(CONFIGURATION_PROVIDER_LOCK)
+      .is_workspace_free_floating(&workspace.id_to_string(), workspace.workspace)
+  }
+
+  /// Returns the most recently focused window if it is visible, belongs to an active do-not-disturb workspace, and
+  /// `window` took the foreground from it without recent user input and without belonging to the same application.
+  fn find_window_disturbed_by(&self, window: WindowHandle) -> Option<WindowHandle> {
+    let previous_window = self.spatial.focus_history.most_recent()?;
+    if previous_window == window
+      || self.windows_api.get_milliseconds_since_last_input() < FOREGROUND_CHANGE_INPUT_TOLERANCE_IN_MS
+      || !self.windows_api.is_window_alive(previous_window)
+      || self.windows_api.is_window_hidden(&previous_window)
+      || self.windows_api.is_window_minimised(previous_window)
+    {
+      return None;
+    }
+    let workspace = self.workspace_manager.active_workspace_for_window(previous_window)?;
+    let is_do_not_disturb = self
+      .configuration_provider
+      .lock()
+      .expect(CONFIGURATION_PROVIDER_LOCK)
+      .is_workspace_do_not_disturb(&workspace.id_to_string(), workspace.workspace);
+    let process_name = self.windows_api.get_window_process_name(window);
+    if !is_do_not_disturb
+      || (process_name.is_some() && process_name == self.windows_api.get_window_process_name(previous_window))
+    {
+      return None;
+    }
+
+    Some(previous_window)
+  }
+
+  fn is_window_free_floating(&self, window: WindowHandle) -> bool {
+    self
+      .get_workspace_for_window(window)
+      .is_some_and(|workspace| self.is_workspace_free_floating(workspace))
+  }
+
+  fn is_foreground_window_free_floating(&self) -> bool {
+    self
+      .windows_api
+      .get_foreground_window()
+      .is_some_and(|window| self.is_window_free_floating(window))
+  }
+
   fn get_layout_for_window(&self, window: WindowHandle) -> Option<Layout> {
     self
       .get_workspace_for_window(window)
@@ -394,7 +1871,42 @@ impl<T: WindowsApi + Clone> WindowManager<T> {
       .and_then(|window| self.get_layout_for_window(window))
   }
 
-  fn margin(&self) -> i32 {
+  fn get_active_workspace_on_monitor(&self, monitor_id: [u16; 32]) -> Option<PersistentWorkspaceId> {
+    self
+      .workspace_manager
+      .active_workspace_ids()
+      .into_iter()
+      .find(|workspace| workspace.monitor_id == monitor_id)
+  }
+
+  fn move_window_to_active_workspace_on_monitor(&mut self, monitor_id: [u16; 32]) -> CommandOutcome {
+    let Some(target) = self.get_active_workspace_on_monitor(monitor_id) else {
+      return CommandOutcome::error("the target monitor has no active workspace");
+    };
+
+    self.move_window_to_workspace(target)
+  }
+
+  fn get_monitor_id_under_cursor(&self) -> Option<[u16; 32]> {
+    let cursor_position = self.windows_api.get_cursor_position();
+    let monitor_handle = self.windows_api.get_monitor_handle_for_point(&cursor_position);
+    self.windows_api.get_monitor_id_for_handle(monitor_handle)
+  }
+
+  /// Saves the workspace count of the monitor of the given workspace, so that its workspaces are recreated after a
+  /// restart. The count of the primary monitor is `additional_workspace_count`, those of other monitors are recorded
+  /// per monitor.
+  fn persist_additional_workspace_count(&self, id: PersistentWorkspaceId, additional_workspace_count: usize) {
+    let mut configuration_provider = self.configuration_provider.lock().expect(CONFIGURATION_PROVIDER_LOCK);
+    if id.is_on_primary_monitor() {
+      configuration_provider.set_i32(ADDITIONAL_WORKSPACE_COUNT, additional_workspace_count as i32);
+    } else {
+      configuration_provider.set_monitor_workspace_count(&id.id_to_string(), additional_workspace_count as i32);
+    }
+  }
+
+  /// Returns `window_margin`, which is used where the monitor is unknown.
+  fn default_margin(&self) -> i32 {
     let margin = self
       .configuration_provider
       .lock()
@@ -403,6 +1915,58 @@ impl<T: WindowsApi + Clone> WindowManager<T> {
     if margin >= MINIMUM_WINDOW_MARGIN { margin } else { 0 }
   }
 
+  /// Returns the window margin of a monitor, which differs from `window_margin` if margins are scaled to the resolution
+  /// of each monitor or if the margin of the monitor is overridden.
+  fn margin_for_monitor(&self, monitor: &Monitor) -> i32 {
+    let margin = self
+      .configuration_provider
+      .lock()
+      .expect(CONFIGURATION_PROVIDER_LOCK)
+      .window_margin_for_monitor(&monitor.id_to_string(), monitor.is_primary, monitor.monitor_area.width());
+    if margin >= MINIMUM_WINDOW_MARGIN { margin } else { 0 }
+  }
+
+  fn margin_for_workspace(&self, workspace: PersistentWorkspaceId) -> i32 {
+    self
+      .workspace_manager
+      .monitor_for_workspace(workspace)
+      .map_or_else(|| self.default_margin(), |monitor| self.margin_for_monitor(&monitor))
+  }
+
+  fn margin_for_window(&self, window: WindowHandle) -> i32 {
+    let monitor_handle = self.windows_api.get_monitor_handle_for_window_handle(window);
+    self
+      .windows_api
+      .get_all_monitors()
+      .get_by_handle(monitor_handle)
+      .map_or_else(|| self.default_margin(), |monitor| self.margin_for_monitor(monitor))
+  }
+
+  fn margin_for_foreground_window(&self) -> i32 {
+    self
+      .windows_api
+      .get_foreground_window()
+      .map_or_else(|| self.default_margin(), |window| self.margin_for_window(window))
+  }
+
+  /// Returns the window margin of every connected monitor, for operations that arrange windows on several monitors.
+  fn margins(&self) -> MonitorMargins {
+    let mut margins = MonitorMargins::new(self.default_margin());
+    for monitor in self.windows_api.get_all_monitors().get_all() {
+      margins.insert(monitor.id, self.margin_for_monitor(monitor));
+    }
+
+    margins
+  }
+
+  fn focus_fallback_policy(&self) -> FocusFallbackPolicy {
+    self
+      .configuration_provider
+      .lock()
+      .expect(CONFIGURATION_PROVIDER_LOCK)
+      .get_focus_fallback_policy()
+  }
+
   fn scrolling_animation_duration(&self) -> Duration {
     let duration = self
       .configuration_provider
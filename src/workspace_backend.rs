@@ -0,0 +1,65 @@
+use crate::common::{CommandOutcome, Monitor, PersistentWorkspaceId, Rect, Window, WindowHandle};
+use std::time::SystemTime;
+
+/// The operations the window manager needs from a workspace implementation. The default implementation is the
+/// [`crate::workspace_manager::WorkspaceManager`], which hides and shows windows to switch workspaces. Alternative
+/// implementations (e.g. one backed by Windows virtual desktops) can be developed against this trait without changing
+/// any of the window manager's call sites.
+pub trait WorkspaceBackend {
+  /// Returns the IDs of all workspaces, ordered by monitor position and then by workspace number.
+  fn get_ordered_permanent_workspace_ids(&mut self) -> Vec<PersistentWorkspaceId>;
+  /// Makes the workspace the active workspace on its monitor.
+  fn switch_workspace(&mut self, target_workspace_id: PersistentWorkspaceId) -> CommandOutcome;
+  /// Switches workspace while capturing supplied off-screen members.
+  fn switch_workspace_with_additional_windows(
+    &mut self,
+    target_workspace_id: PersistentWorkspaceId,
+    additional_windows: &[WindowHandle],
+  ) -> CommandOutcome;
+  /// Moves the foreground window to the workspace.
+  fn move_window_to_workspace(&mut self, target_workspace_id: PersistentWorkspaceId) -> CommandOutcome;
+  /// Moves a window hidden in an inactive workspace to the active workspace under the cursor and focuses it.
+  fn bring_window_here(&mut self, handle: WindowHandle) -> CommandOutcome;
+  /// Moves each window to its workspace and gives it its rect, hiding it if the workspace is inactive, e.g. to restore a
+  /// session. Returns the number of windows that were placed.
+  fn place_windows(&mut self, placements: &[(WindowHandle, PersistentWorkspaceId, Rect)]) -> usize;
+  /// Makes every window stored in an inactive workspace visible again.
+  fn restore_all_managed_windows(&mut self);
+  /// Marks a window as sticky (if `true`), so that it stays visible when switching workspaces, or unmarks it.
+  fn set_window_sticky(&mut self, handle: WindowHandle, is_sticky: bool);
+  /// Forgets all stored windows that no longer exist or whose process has exited. Returns the forgotten windows.
+  fn remove_dead_windows(&mut self) -> Vec<Window>;
+  /// Returns the monitors that were disconnected since the last call and whose workspaces store windows that can no
+  /// longer be reached, together with the number of such windows per monitor.
+  fn find_parked_windows_of_disconnected_monitors(&mut self) -> Vec<([u16; 32], usize)>;
+  /// Restores the windows stored in the workspaces of a disconnected monitor on the primary monitor.
+  fn move_parked_windows_to_primary_monitor(&mut self, monitor_id: [u16; 32]);
+  /// Returns `true` if the handle or work area of a connected monitor no longer matches what its workspaces know about
+  /// it, e.g. after a display driver reset.
+  fn has_stale_monitors(&self) -> bool;
+  /// Re-reads all monitors, refreshes the monitor of every workspace, and forgets stored windows that no longer exist.
+  fn refresh_monitors(&mut self);
+  /// Creates a workspace for every connected monitor that has none, e.g. because it was connected after Randolf
+  /// started. Returns the IDs of the created workspaces.
+  fn add_workspaces_for_new_monitors(&mut self) -> Vec<PersistentWorkspaceId>;
+  /// Returns the active workspace containing a window's monitor.
+  fn active_workspace_for_window(&self, handle: WindowHandle) -> Option<PersistentWorkspaceId>;
+  /// Returns the number of windows stored in each workspace, ordered by workspace ID.
+  fn stored_window_counts(&self) -> Vec<(PersistentWorkspaceId, usize)>;
+  /// Returns every window stored in an inactive workspace together with its workspace, ordered by workspace ID.
+  fn stored_windows(&self) -> Vec<(PersistentWorkspaceId, Window)>;
+  /// Returns all active workspace IDs.
+  fn active_workspace_ids(&self) -> Vec<PersistentWorkspaceId>;
+  /// Returns a workspace's current monitor.
+  fn monitor_for_workspace(&self, id: PersistentWorkspaceId) -> Option<Monitor>;
+  /// Returns whether a workspace is active.
+  fn is_workspace_active(&self, id: PersistentWorkspaceId) -> bool;
+  /// Returns when a workspace was last active, or `None` if it has not been active since Randolf started.
+  fn workspace_last_used_at(&self, id: PersistentWorkspaceId) -> Option<SystemTime>;
+  /// Adds an inactive workspace after the last workspace on the monitor. Returns the ID of the new workspace or the
+  /// reason why none was created.
+  fn create_workspace(&mut self, monitor_id: [u16; 32]) -> Result<PersistentWorkspaceId, String>;
+  /// Removes the last workspace on the monitor, if it is inactive and empty. Returns the ID of the removed workspace or
+  /// the reason why none was removed.
+  fn delete_workspace(&mut self, monitor_id: [u16; 32]) -> Result<PersistentWorkspaceId, String>;
+}
@@ -1,9 +1,17 @@
 use crate::api::WindowsApi;
 use crate::common::{
-  MonitorHandle, PersistentWorkspaceId, TransientWorkspaceId, Window, WindowHandle, Workspace, WorkspaceAction,
+  CommandOutcome, MonitorHandle, PersistentWorkspaceId, Rect, TransientWorkspaceId, Window, WindowHandle, Workspace,
+  WorkspaceAction, WorkspaceSwitchTiming,
 };
+use crate::configuration_provider::MonitorOrdering;
+use crate::files::StoredWindow;
 use crate::workspace_manager::WorkspaceManager;
 use std::collections::HashMap;
+use std::time::Instant;
+
+/// The minimum share of the smaller of two monitors that must overlap with the other monitor, across the direction of
+/// a row or column, for both monitors to be in the same row or column.
+const MINIMUM_LINE_OVERLAP_PERCENTAGE: i32 = 50;
 
 /// This struct is used by [`WorkspaceManager`], implementing the RAII pattern to ensure that the workspace manager
 /// has the correct [`MonitorHandle`]s for each [`Workspace`]. The handle of a monitor changes in the Windows API in
@@ -19,11 +27,7 @@ impl<'a, T: WindowsApi + Clone> WorkspaceGuard<'a, T> {
   pub fn new(manager: &'a mut WorkspaceManager<T>) -> Self {
     let monitors = manager.windows_api.get_all_monitors();
     monitors.log_detected_monitors();
-    for workspace in manager.workspaces.values_mut() {
-      if let Some(monitor) = monitors.get_by_id(&workspace.id.monitor_id) {
-        workspace.update_handle(monitor.handle);
-      }
-    }
+    manager.assign_workspaces_to_monitors(&monitors);
     let id_map = manager.create_workspace_id_map(monitors);
 
     Self { manager, id_map }
@@ -41,8 +45,19 @@ impl<'a, T: WindowsApi + Clone> WorkspaceGuard<'a, T> {
     }
   }
 
-  /// Returns the unique IDs for all workspaces across all monitors. Ordered by monitor position. Returned in ascending
-  /// order from top-left to bottom-right.
+  /// Runs several workspace operations against the monitor snapshot taken when this guard was created and persists
+  /// the workspaces file once, after all operations have completed. Intended for macros, command batches, and session
+  /// restore.
+  pub fn run_transaction<R>(&mut self, operations: impl FnOnce(&mut Self) -> R) -> R {
+    self.manager.workspace_file.defer_saving();
+    let result = operations(self);
+    self.manager.workspace_file.save_deferred_changes(&self.manager.file_manager);
+
+    result
+  }
+
+  /// Returns the unique IDs for all workspaces across all monitors. Ordered by monitor position using the configured
+  /// [`MonitorOrdering`] and, for each monitor, by workspace ID.
   pub fn get_ordered_workspace_ids(&self) -> Vec<PersistentWorkspaceId> {
     let mut workspaces_by_monitor: HashMap<i64, Vec<&Workspace>> = HashMap::new();
     for workspace in self.manager.workspaces.values() {
@@ -52,37 +67,11 @@ impl<'a, T: WindowsApi + Clone> WorkspaceGuard<'a, T> {
         .push(workspace);
     }
 
-    let mut monitor_handles: Vec<i64> = workspaces_by_monitor.keys().cloned().collect();
-    monitor_handles.sort_by(|a, b| {
-      let monitor_a = self
-        .manager
-        .workspaces
-        .values()
-        .find(|w| w.monitor_handle == *a)
-        .map(|w| &w.monitor)
-        .expect("Monitor not found");
-
-      let monitor_b = self
-        .manager
-        .workspaces
-        .values()
-        .find(|w| w.monitor_handle == *b)
-        .map(|w| &w.monitor)
-        .expect("Monitor not found");
-
-      // Left to right
-      let a_center_x = monitor_a.center.x();
-      let b_center_x = monitor_b.center.x();
-      if a_center_x != b_center_x {
-        return a_center_x.cmp(&b_center_x);
-      }
-
-      // Top to bottom
-      let a_center_y = monitor_a.center.y();
-      let b_center_y = monitor_b.center.y();
-
-      a_center_y.cmp(&b_center_y)
-    });
+    let monitor_areas = workspaces_by_monitor
+      .iter()
+      .map(|(handle, workspaces)| (*handle, workspaces[0].monitor.monitor_area))
+      .collect::<Vec<_>>();
+    let monitor_handles = order_by_position(monitor_areas, self.manager.monitor_ordering);
 
     let mut result = Vec::new();
     for monitor_handle in monitor_handles {
@@ -93,11 +82,6 @@ impl<'a, T: WindowsApi + Clone> WorkspaceGuard<'a, T> {
       }
     }
 
-    debug!(
-      "Ordered workspaces: [{}]",
-      result.iter().map(|id| format!("{}", id)).collect::<Vec<_>>().join(", ")
-    );
-
     result
   }
 
@@ -106,25 +90,25 @@ impl<'a, T: WindowsApi + Clone> WorkspaceGuard<'a, T> {
     &mut self,
     target_workspace_id: PersistentWorkspaceId,
     additional_windows: &[WindowHandle],
-  ) {
+  ) -> CommandOutcome {
     if self.resolve_to_transient(target_workspace_id).is_none() {
-      return;
+      return CommandOutcome::error(format!("workspace [{target_workspace_id}] does not exist"));
     }
     let current_workspace_id = match self.get_current_workspace_id_if_different_to(target_workspace_id) {
-      Some(id) => id,
-      None => return,
+      Ok(id) => id,
+      Err(outcome) => return outcome,
     };
 
     // Identify the active workspace on the target monitor
     let target_monitor_active_workspace_id = if let Some(workspace) = self.get_active_workspace(&target_workspace_id) {
       workspace
     } else {
-      if target_workspace_id.monitor_id != current_workspace_id.monitor_id {
+      if self.get_monitor_id(&target_workspace_id) != self.get_monitor_id(&current_workspace_id) {
         error!(
           "Failed to switch workspace because: The target workspace ({}) does not exist",
           target_workspace_id.clone()
         );
-        return;
+        return CommandOutcome::error(format!("workspace [{target_workspace_id}] does not exist"));
       }
       trace!(
         "Expecting target monitor workspace ({}) and current workspace ({}) to be on the same monitor",
@@ -134,42 +118,33 @@ impl<'a, T: WindowsApi + Clone> WorkspaceGuard<'a, T> {
     };
 
     // Hide and store all windows in the target workspace, if required
-    if !target_workspace_id.is_same_workspace(&target_monitor_active_workspace_id) {
-      let current_windows = if let Some(target_monitor_active_workspace) =
-        self.manager.workspaces.get_mut(&target_monitor_active_workspace_id)
-      {
-        let mut current_windows = self
-          .manager
-          .windows_api
-          .get_all_visible_windows_within_area(target_monitor_active_workspace.monitor.monitor_area);
-        let additional = self
-          .manager
-          .windows_api
-          .get_all_windows()
-          .into_iter()
-          .filter(|window| additional_windows.contains(&window.handle) && !current_windows.contains(window))
-          .collect::<Vec<_>>();
-        current_windows.extend(additional);
-        let current_monitor = MonitorHandle::from(target_monitor_active_workspace.monitor_handle);
-        target_monitor_active_workspace.store_and_hide_windows(
-          current_windows.clone(),
-          current_monitor,
-          &self.manager.windows_api,
-        );
-
-        current_windows
-      } else {
+    let mut timing = WorkspaceSwitchTiming::default();
+    let store_and_hide_started = Instant::now();
+    if target_workspace_id != target_monitor_active_workspace_id {
+      let Some((current_windows, slowest_window)) = self
+        .manager
+        .store_and_hide_visible_windows(target_monitor_active_workspace_id, additional_windows)
+      else {
         warn!(
           "Failed to switch workspace because: The workspace ({}) to store the window doesn't exist",
           target_monitor_active_workspace_id
         );
         self.log_initialised_workspaces();
-        return;
+        return CommandOutcome::error(format!(
+          "workspace [{target_monitor_active_workspace_id}] to store the windows in does not exist"
+        ));
       };
+      timing.record_window(slowest_window);
+      let stored_windows = self
+        .manager
+        .workspaces
+        .get(&target_monitor_active_workspace_id)
+        .map(StoredWindow::all_from)
+        .unwrap_or_default();
       self.manager.workspace_file.add_all(
         &self.manager.file_manager,
         &target_monitor_active_workspace_id,
-        &current_windows,
+        &stored_windows,
       );
 
       // Remove stored windows from all other workspaces
@@ -186,22 +161,32 @@ impl<'a, T: WindowsApi + Clone> WorkspaceGuard<'a, T> {
         &current_windows,
       );
     }
+    timing.store_and_hide = store_and_hide_started.elapsed();
 
-    // Restore windows for the new workspace and set the cursor position
-    let largest_window = self.find_largest_visible_window_in_workspace(&target_workspace_id);
+    // Restore windows for the new workspace and set the cursor position, preferring the window that last had focus
+    let window_to_focus = self
+      .manager
+      .workspaces
+      .get(&target_workspace_id)
+      .and_then(|workspace| workspace.get_last_focused_window())
+      .or_else(|| self.find_largest_visible_window_in_workspace(&target_workspace_id));
     if let Some(new_workspace) = self.manager.workspaces.get_mut(&target_workspace_id) {
-      new_workspace.restore_windows(&self.manager.windows_api);
-      if let Some(largest_window) = largest_window {
+      let restore_started = Instant::now();
+      timing.record_window(new_workspace.restore_windows(&self.manager.windows_api));
+      timing.restore = restore_started.elapsed();
+      let foreground_started = Instant::now();
+      if let Some(window_to_focus) = window_to_focus {
         trace!(
           "Setting foreground window to {} \"{}\"",
-          largest_window.handle,
-          largest_window.title_trunc()
+          window_to_focus.handle,
+          window_to_focus.title_trunc()
         );
-        self.manager.windows_api.set_foreground_window(largest_window.handle);
-        self.manager.windows_api.set_cursor_position(&largest_window.center);
+        self.manager.windows_api.set_foreground_window(window_to_focus.handle);
+        self.manager.windows_api.set_cursor_position(&window_to_focus.center);
       } else {
         self.manager.windows_api.set_cursor_position(&new_workspace.monitor.center);
       }
+      timing.foreground = foreground_started.elapsed();
     } else {
       // Restore the original workspace if the target workspace doesn't exist
       warn!(
@@ -228,7 +213,7 @@ impl<'a, T: WindowsApi + Clone> WorkspaceGuard<'a, T> {
           current_workspace_id
         );
       }
-      return;
+      return CommandOutcome::error(format!("workspace [{target_workspace_id}] does not exist"));
     };
 
     // Remove the workspace file entry for the current workspace
@@ -238,34 +223,44 @@ impl<'a, T: WindowsApi + Clone> WorkspaceGuard<'a, T> {
       .remove_workspace(&self.manager.file_manager, &target_workspace_id);
 
     // Update the active workspaces
-    if !target_workspace_id.is_same_workspace(&target_monitor_active_workspace_id) {
+    if target_workspace_id != target_monitor_active_workspace_id {
       self.set_active_workspace(&target_workspace_id, true);
       self.set_active_workspace(&target_monitor_active_workspace_id, false);
     }
 
     info!(
-      "Switched workspace from [{}] to [{}]",
-      current_workspace_id, target_workspace_id
+      "Switched workspace from [{}] to [{}] in [{}ms]",
+      current_workspace_id,
+      target_workspace_id,
+      timing.total().as_millis()
     );
+    if self.manager.switch_latency.record(target_workspace_id, &timing) {
+      warn!(
+        "Switching to workspace [{}] was slow and took {}",
+        target_workspace_id, timing
+      );
+    }
+
+    CommandOutcome::Success
   }
 
-  pub fn move_window_to_workspace(&mut self, target_workspace_id: PersistentWorkspaceId) {
+  pub fn move_window_to_workspace(&mut self, target_workspace_id: PersistentWorkspaceId) -> CommandOutcome {
     if self.resolve_to_transient(target_workspace_id).is_none() {
-      return;
+      return CommandOutcome::error(format!("workspace [{target_workspace_id}] does not exist"));
     }
 
     // Collect all relevant information
     let current_workspace_id = match self.get_current_workspace_id_if_different_to(target_workspace_id) {
-      Some(id) => id,
-      None => return,
+      Ok(id) => id,
+      Err(outcome) => return outcome,
     };
     let Some(foreground_window) = self.manager.windows_api.get_foreground_window() else {
       debug!("Ignored request to move window to workspace because there is no foreground window");
-      return;
+      return CommandOutcome::no_op("there is no foreground window");
     };
     let Some(window_placement) = self.manager.windows_api.get_window_placement(foreground_window) else {
       debug!("Ignored request to move window to workspace because the window is not visible");
-      return;
+      return CommandOutcome::no_op("the foreground window is not visible");
     };
     let window_title = self.manager.windows_api.get_window_title(&foreground_window);
     let window = Window::new(foreground_window.as_hwnd(), window_title, window_placement.normal_position);
@@ -276,17 +271,18 @@ impl<'a, T: WindowsApi + Clone> WorkspaceGuard<'a, T> {
       if let WorkspaceAction::Stored =
         target_workspace.move_or_store_and_hide_window(window.clone(), current_monitor, &self.manager.windows_api)
       {
-        self
-          .manager
-          .workspace_file
-          .add(&self.manager.file_manager, &target_workspace_id, &window.handle)
+        self.manager.workspace_file.add_all(
+          &self.manager.file_manager,
+          &target_workspace_id,
+          &StoredWindow::all_from(target_workspace),
+        )
       }
     } else {
       warn!(
         "Failed to move window to workspace because: The target workspace ({}) does not exist",
         target_workspace_id
       );
-      return;
+      return CommandOutcome::error(format!("workspace [{target_workspace_id}] does not exist"));
     }
 
     // Remove the window from all other workspaces (only necessary when other apps have changed the state of the window)
@@ -326,6 +322,92 @@ impl<'a, T: WindowsApi + Clone> WorkspaceGuard<'a, T> {
       window.title_trunc(),
       target_workspace_id
     );
+
+    CommandOutcome::Success
+  }
+
+  /// Moves a window that is hidden in an inactive workspace to the active workspace under the cursor and makes it the
+  /// foreground window, which is the inverse of moving the foreground window to another workspace.
+  pub fn bring_window_here(&mut self, handle: WindowHandle) -> CommandOutcome {
+    let Some((source_workspace_id, source_monitor)) = self
+      .manager
+      .workspaces
+      .iter()
+      .find(|(_, workspace)| !workspace.is_active() && workspace.stores(&handle))
+      .map(|(id, workspace)| (*id, MonitorHandle::from(workspace.monitor_handle)))
+    else {
+      debug!("Ignored request to bring {handle} here because no inactive workspace stores it");
+      return CommandOutcome::no_op(format!("{handle} is not hidden on an inactive workspace"));
+    };
+    let Some(target_workspace_id) = self.get_active_workspace_for_cursor_position() else {
+      warn!("Failed to bring {handle} here: Unable to find the active workspace");
+      return CommandOutcome::error("there is no active workspace under the cursor");
+    };
+
+    // Restore the window and move it to the active workspace, which only changes its position if the active workspace
+    // is on a different monitor
+    let Some(window) = self
+      .manager
+      .workspaces
+      .get_mut(&source_workspace_id)
+      .and_then(|workspace| workspace.restore_window(handle, &self.manager.windows_api))
+    else {
+      return CommandOutcome::error(format!("{handle} could not be restored"));
+    };
+    if let Some(target_workspace) = self.manager.workspaces.get_mut(&target_workspace_id) {
+      target_workspace.move_or_store_and_hide_window(window.clone(), source_monitor, &self.manager.windows_api);
+    }
+    self.manager.workspace_file.remove_all_excluding(
+      &self.manager.file_manager,
+      &target_workspace_id,
+      std::slice::from_ref(&window),
+    );
+    self.manager.windows_api.set_foreground_window(window.handle);
+
+    info!(
+      "Brought {} \"{}\" from workspace [{}] to workspace [{}]",
+      window.handle,
+      window.title_trunc(),
+      source_workspace_id,
+      target_workspace_id
+    );
+
+    CommandOutcome::Success
+  }
+
+  /// Moves a window to the workspace and gives it the rect, hiding it if the workspace is inactive, regardless of which
+  /// workspace it is currently on. Returns `false` if the workspace does not exist.
+  pub fn place_window(&mut self, handle: WindowHandle, target_workspace_id: PersistentWorkspaceId, rect: Rect) -> bool {
+    if self.resolve_to_transient(target_workspace_id).is_none() {
+      return false;
+    }
+    let manager = &mut *self.manager;
+    for (_, workspace) in manager
+      .workspaces
+      .iter_mut()
+      .filter(|(id, workspace)| **id != target_workspace_id && workspace.stores(&handle))
+    {
+      workspace.restore_window(handle, &manager.windows_api);
+    }
+    let Some(target_workspace) = manager.workspaces.get_mut(&target_workspace_id) else {
+      return false;
+    };
+    let title = manager.windows_api.get_window_title(&handle);
+    let window = Window::new(handle.as_hwnd(), title, rect);
+    if target_workspace.is_active() {
+      manager.windows_api.set_window_position(handle, rect);
+    } else {
+      target_workspace.store_hidden_window(window.clone(), false, &manager.windows_api);
+    }
+    debug!(
+      "Placed {} \"{}\" at {} on workspace [{}]",
+      window.handle,
+      window.title_trunc(),
+      rect,
+      target_workspace_id
+    );
+
+    true
   }
 
   pub fn restore_all_managed_windows(&mut self) {
@@ -333,15 +415,18 @@ impl<'a, T: WindowsApi + Clone> WorkspaceGuard<'a, T> {
       workspace.restore_windows(&self.manager.windows_api);
     }
     self.manager.workspace_file.clear(&self.manager.file_manager);
+    // Restoring all windows usually precedes exiting, so the cleared file must be written before this returns
+    self.manager.file_manager.flush();
   }
 
+  /// Returns the active workspace under the cursor, unless it is `other`, or the outcome of the request otherwise.
   pub(crate) fn get_current_workspace_id_if_different_to(
     &mut self,
     other: PersistentWorkspaceId,
-  ) -> Option<PersistentWorkspaceId> {
+  ) -> Result<PersistentWorkspaceId, CommandOutcome> {
     let Some(current_workspace_id) = self.get_active_workspace_for_cursor_position() else {
       warn!("Failed to complete request: Unable to find the active workspace");
-      return None;
+      return Err(CommandOutcome::error("there is no active workspace under the cursor"));
     };
 
     if other == current_workspace_id {
@@ -349,10 +434,12 @@ impl<'a, T: WindowsApi + Clone> WorkspaceGuard<'a, T> {
         "Ignored request because current and target workspaces are the same: {}",
         other
       );
-      return None;
+      return Err(CommandOutcome::no_op(format!(
+        "workspace [{other}] is already the current workspace"
+      )));
     }
 
-    Some(current_workspace_id)
+    Ok(current_workspace_id)
   }
 
   fn get_active_workspace_for_cursor_position(&mut self) -> Option<PersistentWorkspaceId> {
@@ -368,7 +455,7 @@ impl<'a, T: WindowsApi + Clone> WorkspaceGuard<'a, T> {
       .manager
       .workspaces
       .iter()
-      .filter(|(id, workspace)| workspace.is_active() && id.monitor_id == monitor_id)
+      .filter(|(_, workspace)| workspace.is_active() && workspace.monitor.id == monitor_id)
       .map(|(id, _)| id)
       .collect::<Vec<_>>();
 
@@ -386,13 +473,24 @@ impl<'a, T: WindowsApi + Clone> WorkspaceGuard<'a, T> {
   }
 
   fn get_active_workspace(&mut self, workspace_id: &PersistentWorkspaceId) -> Option<PersistentWorkspaceId> {
+    let monitor_id = self.get_monitor_id(workspace_id);
     self
       .manager
       .workspaces
       .iter()
-      .filter(|(_, workspace)| workspace.is_active())
+      .filter(|(_, workspace)| workspace.is_active() && workspace.monitor.id == monitor_id)
       .map(|(id, _)| *id)
-      .find(|id| id.monitor_id == workspace_id.monitor_id)
+      .next()
+  }
+
+  /// Returns the ID of the monitor the workspace is currently assigned to, which is the configured fallback monitor if
+  /// the workspace's own monitor is disconnected.
+  fn get_monitor_id(&self, workspace_id: &PersistentWorkspaceId) -> [u16; 32] {
+    self
+      .manager
+      .workspaces
+      .get(workspace_id)
+      .map_or(workspace_id.monitor_id, |workspace| workspace.monitor.id)
   }
 
   fn set_active_workspace(&mut self, workspace_id: &PersistentWorkspaceId, is_active: bool) {
@@ -449,6 +547,54 @@ impl<'a, T: WindowsApi + Clone> WorkspaceGuard<'a, T> {
   }
 }
 
+/// Orders the keys of the given monitor areas according to the [`MonitorOrdering`].
+fn order_by_position<K>(mut areas: Vec<(K, Rect)>, ordering: MonitorOrdering) -> Vec<K> {
+  match ordering {
+    MonitorOrdering::Rows => order_in_lines(areas, |rect| (rect.top, rect.bottom), |rect| rect.left),
+    MonitorOrdering::Columns => order_in_lines(areas, |rect| (rect.left, rect.right), |rect| rect.top),
+    MonitorOrdering::Centres => {
+      areas.sort_by_key(|(_, rect)| (rect.center().x(), rect.center().y()));
+      areas.into_iter().map(|(key, _)| key).collect()
+    }
+  }
+}
+
+/// Groups areas into lines, i.e. rows or columns, and returns the keys of all areas line by line. Each line is anchored
+/// by the first area that does not fit into any previous line and contains all areas whose extent across the line
+/// overlaps sufficiently with the anchor's extent. This means that slightly offset monitors or monitors of different
+/// sizes still form a line, whereas monitors that are stacked on top of (or next to) each other do not. Lines are
+/// ordered by the start of their anchor and the areas within a line by their position along the line.
+fn order_in_lines<K>(
+  mut areas: Vec<(K, Rect)>,
+  extent_across: impl Fn(&Rect) -> (i32, i32),
+  position_along: impl Fn(&Rect) -> i32,
+) -> Vec<K> {
+  areas.sort_by_key(|(_, rect)| (extent_across(rect).0, position_along(rect)));
+  let mut lines: Vec<((i32, i32), Vec<(K, Rect)>)> = Vec::new();
+  for (key, rect) in areas {
+    let extent = extent_across(&rect);
+    match lines.iter_mut().find(|(anchor, _)| overlaps_sufficiently(*anchor, extent)) {
+      Some((_, line)) => line.push((key, rect)),
+      None => lines.push((extent, vec![(key, rect)])),
+    }
+  }
+
+  lines
+    .into_iter()
+    .flat_map(|(_, mut line)| {
+      line.sort_by_key(|(_, rect)| (position_along(rect), extent_across(rect).0));
+      line.into_iter().map(|(key, _)| key)
+    })
+    .collect()
+}
+
+fn overlaps_sufficiently((a_start, a_end): (i32, i32), (b_start, b_end): (i32, i32)) -> bool {
+  let overlap = a_end.min(b_end) - a_start.max(b_start);
+  let smaller_extent = (a_end - a_start).min(b_end - b_start);
+
+  overlap > 0 && overlap * 100 >= smaller_extent * MINIMUM_LINE_OVERLAP_PERCENTAGE
+}
+
 #[cfg(test)]
 mod tests {
   use crate::api::MockWindowsApi;
@@ -1,35 +1,77 @@
 use crate::api::WindowsApi;
-use crate::common::{Monitor, Monitors, PersistentWorkspaceId, TransientWorkspaceId, Window, WindowHandle, Workspace};
-use crate::files::{FileManager, FileType, WorkspacesFile};
+use crate::common::{
+  CommandOutcome, Monitor, MonitorHandle, Monitors, PersistentWorkspaceId, Rect, TransientWorkspaceId, Window, WindowHandle,
+  Workspace, WorkspaceSwitchLatency,
+};
+use crate::configuration_provider::{MonitorOrdering, MonitorWorkspaceCount, WorkspaceFallback};
+use crate::files::{FileFormat, FileManager, FileType, StoredWindow, WorkspacesFile};
+use crate::utils::MAXIMUM_WORKSPACE_COUNT;
+use crate::workspace_backend::WorkspaceBackend;
 use crate::workspace_guard::WorkspaceGuard;
 use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
 
 const WORKSPACES_FILE_NAME: &str = "workspaces.toml";
 const WORKSPACE_FILE_PREFIX: &str = "# This file is automatically generated and updated by Randolf.\n\
   # Please do NOT modify this file manually.\n\
-  # It is used to store references (window handles) to hidden windows.\n\
-  # The file is used by Randolf to restore hidden windows after a crash.\n\n";
+  # It is used to store references (window handles) to hidden windows, together with their position and state.\n\
+  # The file is used by Randolf to hide these windows in their workspace again after a crash or restart.\n\n";
+/// Workspace operations can change the workspaces file many times in quick succession, e.g. when switching workspaces
+/// via hotkeys while commands arrive via IPC, which is why changes are written at most this often.
+const WORKSPACES_FILE_SAVE_DELAY: Duration = Duration::from_millis(250);
 
 pub struct WorkspaceManager<T: WindowsApi> {
   pub(crate) workspaces: HashMap<PersistentWorkspaceId, Workspace>,
   pub(crate) windows_api: T,
   window_margin: i32,
   additional_workspace_count: i32,
+  monitor_workspace_counts: Vec<MonitorWorkspaceCount>,
+  pub(crate) keep_always_on_top_windows_visible: bool,
+  preserve_relative_window_position: bool,
+  pub(crate) sticky_windows: HashSet<WindowHandle>,
+  workspace_fallbacks: Vec<WorkspaceFallback>,
+  pub(crate) monitor_ordering: MonitorOrdering,
+  connected_monitor_ids: HashSet<[u16; 32]>,
   pub(crate) file_manager: FileManager<WorkspacesFile>,
   pub(crate) workspace_file: WorkspacesFile,
+  corrupt_workspaces_file_backup: Option<PathBuf>,
+  pub(crate) switch_latency: WorkspaceSwitchLatency,
 }
 
 impl<T: WindowsApi + Clone> WorkspaceManager<T> {
-  pub fn new(additional_workspace_count: i32, window_margin: i32, api: T) -> Self {
+  pub fn new(
+    additional_workspace_count: i32,
+    monitor_workspace_counts: Vec<MonitorWorkspaceCount>,
+    window_margin: i32,
+    keep_always_on_top_windows_visible: bool,
+    preserve_relative_window_position: bool,
+    workspace_fallbacks: Vec<WorkspaceFallback>,
+    monitor_ordering: MonitorOrdering,
+    slow_workspace_switch_threshold_in_ms: i32,
+    data_file_format: FileFormat,
+    api: T,
+  ) -> Self {
     let mut file_manager = FileManager::new(WORKSPACES_FILE_NAME, FileType::Data);
     file_manager.set_content_prefix(WORKSPACE_FILE_PREFIX);
+    file_manager.set_format(data_file_format);
+    file_manager.set_save_delay(WORKSPACES_FILE_SAVE_DELAY);
     let mut workspace_manager = Self {
       workspaces: HashMap::new(),
       windows_api: api,
       window_margin,
       additional_workspace_count,
+      monitor_workspace_counts,
+      keep_always_on_top_windows_visible,
+      preserve_relative_window_position,
+      sticky_windows: HashSet::new(),
+      workspace_fallbacks,
+      monitor_ordering,
+      connected_monitor_ids: HashSet::new(),
       file_manager,
       workspace_file: WorkspacesFile::new(),
+      corrupt_workspaces_file_backup: None,
+      switch_latency: WorkspaceSwitchLatency::new(slow_workspace_switch_threshold_in_ms),
     };
     workspace_manager.initialise_workspaces();
     workspace_manager.restore_hidden_windows_from_file();
@@ -37,32 +79,230 @@ impl<T: WindowsApi + Clone> WorkspaceManager<T> {
     workspace_manager
   }
 
+  /// Returns the path to the backup of the workspaces file if it was corrupt at startup and had to be replaced, which
+  /// means that windows hidden by a previous session may not have been restored. Only returns the path once.
+  pub fn take_corrupt_workspaces_file_backup(&mut self) -> Option<PathBuf> {
+    self.corrupt_workspaces_file_backup.take()
+  }
+
+  /// Describes the number of workspace switches, how many of them were slow, and the slowest switch as a single line.
+  pub fn describe_switch_latency(&self) -> String {
+    self.switch_latency.to_string()
+  }
+
   fn initialise_workspaces(&mut self) {
     let mut workspaces = HashMap::new();
     let all_monitors = self.windows_api.get_all_monitors();
     for monitor in all_monitors.get_all() {
-      if monitor.is_primary {
-        for layer in 1..=self.additional_workspace_count + 1 {
-          let id = PersistentWorkspaceId::new(monitor.id, layer as usize, true);
-          let workspace = if layer == 1 {
-            Workspace::new_active(id, monitor, self.window_margin)
-          } else {
-            Workspace::new_inactive(id, monitor, self.window_margin)
-          };
-          workspaces.insert(id, workspace);
-        }
-      } else {
-        let id = PersistentWorkspaceId::new(monitor.id, 1, false);
-        workspaces.insert(id, Workspace::new_active(id, monitor, self.window_margin));
+      self.connected_monitor_ids.insert(monitor.id);
+      for layer in 1..=self.get_additional_workspace_count(monitor) + 1 {
+        let id = PersistentWorkspaceId::new(monitor.id, layer as usize, monitor.is_primary);
+        let workspace = if layer == 1 {
+          Workspace::new_active(id, monitor, self.window_margin)
+        } else {
+          Workspace::new_inactive(id, monitor, self.window_margin)
+        };
+        workspaces.insert(id, workspace);
       }
     }
+    for workspace in workspaces.values_mut() {
+      workspace.set_preserve_relative_window_position(self.preserve_relative_window_position);
+    }
     self.workspaces = workspaces;
   }
 
+  /// Returns the number of workspaces that the monitor has in addition to its first workspace.
+  fn get_additional_workspace_count(&self, monitor: &Monitor) -> i32 {
+    if monitor.is_primary {
+      return self.additional_workspace_count;
+    }
+    let monitor_id = monitor.id_to_string();
+    self
+      .monitor_workspace_counts
+      .iter()
+      .find(|monitor_workspace_count| monitor_workspace_count.monitor == monitor_id)
+      .map_or(0, |monitor_workspace_count| {
+        monitor_workspace_count.additional_workspace_count
+      })
+  }
+
+  /// Records the number of workspaces that the monitor of the given workspace has in addition to its first workspace.
+  fn set_additional_workspace_count(&mut self, id: PersistentWorkspaceId, additional_workspace_count: i32) {
+    if id.is_on_primary_monitor() {
+      self.additional_workspace_count = additional_workspace_count;
+      return;
+    }
+    let monitor_id = id.id_to_string();
+    self
+      .monitor_workspace_counts
+      .retain(|monitor_workspace_count| monitor_workspace_count.monitor != monitor_id);
+    self.monitor_workspace_counts.push(MonitorWorkspaceCount {
+      monitor: monitor_id,
+      additional_workspace_count,
+    });
+  }
+
+  /// Assigns every workspace to its own monitor or, if its own monitor is disconnected, to its configured fallback
+  /// monitor. A workspace whose monitor is disconnected and that has no fallback keeps its assignment and cannot be
+  /// resolved until its monitor is reconnected.
+  pub(crate) fn assign_workspaces_to_monitors(&mut self, monitors: &Monitors) {
+    let mut reassigned_monitor_ids = HashSet::new();
+    let mut previous_monitors = HashMap::new();
+    for workspace in self.workspaces.values_mut() {
+      let monitor = match monitors.get_by_id(&workspace.id.monitor_id) {
+        Some(monitor) => monitor,
+        None => match resolve_fallback_monitor(&self.workspace_fallbacks, &workspace.id, monitors) {
+          Some(monitor) => monitor,
+          None => continue,
+        },
+      };
+      if workspace.monitor.id == monitor.id {
+        workspace.update_handle(monitor.handle);
+        continue;
+      }
+      info!(
+        "Assigning workspace [{}] from monitor [{}] to monitor [{}]",
+        workspace.id,
+        workspace.monitor.id_to_string(),
+        monitor.id_to_string()
+      );
+      reassigned_monitor_ids.insert(workspace.monitor.id);
+      reassigned_monitor_ids.insert(monitor.id);
+      previous_monitors.insert(workspace.id, monitors.get_by_id(&workspace.monitor.id).cloned());
+      workspace.update_monitor(monitor);
+    }
+    // Windows of workspaces that are deactivated must be hidden before any workspace is activated, because activating a
+    // workspace restores its windows on the monitor that the deactivated workspace may have been active on until now
+    for monitor_id in &reassigned_monitor_ids {
+      self.deactivate_surplus_workspaces(*monitor_id, &previous_monitors);
+    }
+    for monitor_id in reassigned_monitor_ids {
+      self.ensure_single_active_workspace(monitor_id);
+    }
+  }
+
+  /// Ensures that exactly one of the workspaces assigned to the monitor is active after workspaces have been assigned
+  /// to or removed from it. Prefers the monitor's own workspaces and, among those, the one with the lowest number.
+  fn ensure_single_active_workspace(&mut self, monitor_id: [u16; 32]) {
+    self.deactivate_surplus_workspaces(monitor_id, &HashMap::new());
+    let workspace_ids = self.get_workspace_ids_by_preference(monitor_id);
+    if workspace_ids.iter().any(|id| self.workspaces[id].is_active()) {
+      return;
+    }
+    let Some(workspace) = workspace_ids.first().and_then(|id| self.workspaces.get_mut(id)) else {
+      return;
+    };
+    info!(
+      "Activating workspace [{}] because its monitor had no active workspace",
+      workspace.id
+    );
+    workspace.set_active(true);
+    workspace.restore_windows(&self.windows_api);
+    self.workspace_file.remove_workspace(&self.file_manager, &workspace.id);
+  }
+
+  /// Deactivates all but the preferred active workspace assigned to the monitor, storing and hiding the windows that
+  /// are visible on the monitor each of them was active on until now, just like switching away from a workspace does.
+  /// `previous_monitors` contains the monitor that each workspace that was just reassigned was assigned to before, or
+  /// `None` if that monitor was disconnected, in which case Windows has already moved its windows to the remaining
+  /// monitors, where they cannot be told apart from the windows of the workspaces that are active there.
+  fn deactivate_surplus_workspaces(
+    &mut self,
+    monitor_id: [u16; 32],
+    previous_monitors: &HashMap<PersistentWorkspaceId, Option<Monitor>>,
+  ) {
+    let surplus_ids = self
+      .get_workspace_ids_by_preference(monitor_id)
+      .into_iter()
+      .filter(|id| self.workspaces[id].is_active())
+      .skip(1)
+      .collect::<Vec<_>>();
+    for id in surplus_ids {
+      let Some(workspace) = self.workspaces.get_mut(&id) else {
+        continue;
+      };
+      let current_monitor = workspace.monitor.clone();
+      match previous_monitors.get(&id) {
+        Some(None) => {
+          info!(
+            "Deactivating workspace [{id}] because its monitor already has an active workspace, leaving the windows of its \
+            disconnected monitor on the monitors Windows moved them to"
+          );
+          workspace.set_active(false);
+          continue;
+        }
+        // Stores the windows as if the workspace had not been reassigned yet, so that they are refitted to its new
+        // monitor below, like any other windows stored in a reassigned workspace
+        Some(Some(previous_monitor)) => workspace.update_monitor(previous_monitor),
+        None => {}
+      }
+      info!("Storing and hiding the windows of workspace [{id}] because its monitor already has an active workspace");
+      self.store_and_hide_visible_windows(id, &[]);
+      let Some(workspace) = self.workspaces.get_mut(&id) else {
+        continue;
+      };
+      workspace.update_monitor(&current_monitor);
+      workspace.set_active(false);
+      let stored_windows = StoredWindow::all_from(workspace);
+      self.workspace_file.add_all(&self.file_manager, &id, &stored_windows);
+    }
+  }
+
+  /// Returns the IDs of the workspaces assigned to the monitor, starting with the monitor's own workspaces and ordered
+  /// by number.
+  fn get_workspace_ids_by_preference(&self, monitor_id: [u16; 32]) -> Vec<PersistentWorkspaceId> {
+    let mut ids = self
+      .workspaces
+      .values()
+      .filter(|workspace| workspace.monitor.id == monitor_id)
+      .map(|workspace| workspace.id)
+      .collect::<Vec<_>>();
+    ids.sort_by_key(|id| (id.monitor_id != monitor_id, id.workspace));
+    ids
+  }
+
+  /// Stores the windows that are visible on the monitor of the workspace, as well as the additional windows, in the
+  /// workspace and hides them. Sticky windows and, if configured, always-on-top windows remain visible. Returns the
+  /// stored windows and the window that took the longest to hide, or `None` if the workspace does not exist.
+  pub(crate) fn store_and_hide_visible_windows(
+    &mut self,
+    id: PersistentWorkspaceId,
+    additional_windows: &[WindowHandle],
+  ) -> Option<(Vec<Window>, Option<(Window, Duration)>)> {
+    let workspace = self.workspaces.get(&id)?;
+    let mut windows = self
+      .windows_api
+      .get_all_visible_windows_within_area(workspace.monitor.monitor_area);
+    let additional = self
+      .windows_api
+      .get_all_windows()
+      .into_iter()
+      .filter(|window| additional_windows.contains(&window.handle) && !windows.contains(window))
+      .collect::<Vec<_>>();
+    windows.extend(additional);
+    windows.retain(|window| {
+      let is_sticky = self.sticky_windows.contains(&window.handle)
+        || (self.keep_always_on_top_windows_visible && self.windows_api.is_window_always_on_top(window.handle));
+      if is_sticky {
+        debug!(
+          "Keeping {} \"{}\" visible because it is sticky or always on top",
+          window.handle,
+          window.title_trunc()
+        );
+      }
+      !is_sticky
+    });
+    let workspace = self.workspaces.get_mut(&id)?;
+    let current_monitor = MonitorHandle::from(workspace.monitor_handle);
+    let slowest_window = workspace.store_and_hide_windows(windows.clone(), current_monitor, &self.windows_api);
+
+    Some((windows, slowest_window))
+  }
+
   pub(crate) fn create_workspace_id_map(&self, monitors: Monitors) -> HashMap<PersistentWorkspaceId, TransientWorkspaceId> {
     let mut workspace_id_map = HashMap::new();
     for workspace in self.workspaces.values() {
-      if let Some(monitor) = monitors.get_by_id(&workspace.id.monitor_id) {
+      if let Some(monitor) = monitors.get_by_id(&workspace.monitor.id) {
         workspace_id_map.insert(workspace.id, TransientWorkspaceId::from(workspace.id, monitor.handle));
       }
     }
@@ -70,56 +310,272 @@ impl<T: WindowsApi + Clone> WorkspaceManager<T> {
     workspace_id_map
   }
 
-  /// This function will read the workspaces file and attempt to restore any window handles that are present in the
-  /// file. This allows Randolf to recover after any form of non-graceful exit. The workspaces file is continuously
-  /// updated by Randolf.
+  /// This function will read the workspaces file and hide every window that still exists in the inactive workspace it
+  /// was stored in again, using the rect and minimised state it was stored with, and remembers which of them had focus
+  /// in each workspace. Windows whose workspace no longer exists or is active are restored instead. This allows Randolf
+  /// to recover after any form of non-graceful exit without losing track of hidden windows. The workspaces file is
+  /// continuously updated by Randolf.
   fn restore_hidden_windows_from_file(&mut self) {
-    let (workspace_file, _) = self
+    let (workspace_file, backup_path) = self
       .file_manager
-      .load_or_create()
+      .load_or_replace_if_corrupt()
       .expect("Failed to load or create workspaces file");
     self.workspace_file = workspace_file;
+    self.corrupt_workspaces_file_backup = backup_path;
     info!("Loaded workspaces file: {}", self.workspace_file);
-    let windows = self
-      .windows_api
-      .get_all_windows()
-      .into_iter()
-      .filter(|window| {
-        self
-          .workspace_file
+    let (mut stored_count, mut restored_count) = (0, 0);
+    for (workspace_id, stored_windows) in std::mem::take(&mut self.workspace_file.workspaces) {
+      // Owners are stored first, so that the windows they own can be stored together with them
+      let (owned_windows, stored_windows): (Vec<_>, Vec<_>) = stored_windows
+        .into_iter()
+        .partition(|stored_window| stored_window.owner.is_some());
+      for stored_window in stored_windows.into_iter().chain(owned_windows) {
+        if !self.windows_api.is_window_alive(stored_window.handle) {
+          debug!(
+            "Ignoring {} from workspaces file because it no longer exists",
+            stored_window.handle
+          );
+          continue;
+        }
+        let Some(rect) = stored_window
+          .rect
+          .or_else(|| self.windows_api.get_window_rect(stored_window.handle))
+        else {
+          self.windows_api.do_unhide_window(stored_window.handle);
+          restored_count += 1;
+          continue;
+        };
+        let title = self.windows_api.get_window_title(&stored_window.handle);
+        let window = Window::new(stored_window.handle.as_hwnd(), title, rect);
+        if let Some(owner) = stored_window.owner {
+          let is_stored = self
+            .workspaces
+            .get_mut(&workspace_id)
+            .filter(|workspace| !workspace.is_active())
+            .is_some_and(|workspace| workspace.store_hidden_owned_window(owner, window.clone(), &self.windows_api));
+          if is_stored {
+            stored_count += 1;
+          } else {
+            self.windows_api.do_restore_window(&window, &false);
+            restored_count += 1;
+          }
+          continue;
+        }
+        match self
           .workspaces
-          .values()
-          .any(|handles| handles.contains(&window.handle))
-      })
-      .collect::<HashSet<Window>>();
-    info!("Restoring [{}] hidden windows: {:?}", windows.len(), windows);
-    for window in windows {
-      self.windows_api.do_unhide_window(window.handle);
+          .get_mut(&workspace_id)
+          .filter(|workspace| !workspace.is_active())
+        {
+          Some(workspace) => {
+            workspace.store_hidden_window(window, stored_window.is_minimised, &self.windows_api);
+            if stored_window.is_last_focused {
+              workspace.set_last_focused_window(stored_window.handle);
+            }
+            stored_count += 1;
+          }
+          None => {
+            self.windows_api.do_restore_window(&window, &stored_window.is_minimised);
+            restored_count += 1;
+          }
+        }
+      }
     }
+    info!(
+      "Stored [{}] hidden window(s) in their workspace again and restored [{}] window(s) from the workspaces file",
+      stored_count, restored_count
+    );
+    self.workspace_file.defer_saving();
+    self.write_stored_windows_to_file();
+    self.workspace_file.save_deferred_changes(&self.file_manager);
+  }
+
+  /// Replaces the content of the workspaces file with the windows that each workspace currently stores.
+  pub(crate) fn write_stored_windows_to_file(&mut self) {
     self.workspace_file.clear(&self.file_manager);
+    for workspace in self.workspaces.values().filter(|workspace| !workspace.is_empty()) {
+      self
+        .workspace_file
+        .add_all(&self.file_manager, &workspace.id, &StoredWindow::all_from(workspace));
+    }
+  }
+
+  fn get_last_workspace_on_monitor(&self, monitor_id: [u16; 32]) -> Option<&Workspace> {
+    self
+      .workspaces
+      .values()
+      .filter(|workspace| workspace.id.monitor_id == monitor_id)
+      .max_by_key(|workspace| workspace.id.workspace)
+  }
+
+  /// Runs several workspace operations using a single [`WorkspaceGuard`], see [`WorkspaceGuard::run_transaction`].
+  pub fn run_transaction<R>(&mut self, operations: impl FnOnce(&mut WorkspaceGuard<T>) -> R) -> R {
+    let mut guard = WorkspaceGuard::new(self);
+    guard.run_transaction(operations)
   }
+}
+
+/// Returns the monitor configured as fallback for the workspace, preferring a fallback for this specific workspace over
+/// one for all workspaces of its monitor.
+fn resolve_fallback_monitor<'a>(
+  fallbacks: &[WorkspaceFallback],
+  id: &PersistentWorkspaceId,
+  monitors: &'a Monitors,
+) -> Option<&'a Monitor> {
+  let monitor_id = id.id_to_string();
+  let fallback = fallbacks
+    .iter()
+    .filter(|fallback| fallback.selector.applies_to(&monitor_id, id.workspace))
+    .min_by_key(|fallback| fallback.selector.workspace.is_none())?;
+  let monitor = monitors.get_all().into_iter().find(|monitor| {
+    if fallback.fallback == "primary" {
+      monitor.is_primary
+    } else {
+      monitor.id_to_string() == fallback.fallback
+    }
+  });
+  if monitor.is_none() {
+    debug!(
+      "Unable to resolve fallback monitor [{}] for workspace [{}] because it is not connected",
+      fallback.fallback, id
+    );
+  }
+
+  monitor
+}
 
-  pub fn get_ordered_permanent_workspace_ids(&mut self) -> Vec<PersistentWorkspaceId> {
+impl<T: WindowsApi + Clone> WorkspaceBackend for WorkspaceManager<T> {
+  fn get_ordered_permanent_workspace_ids(&mut self) -> Vec<PersistentWorkspaceId> {
     let guard = WorkspaceGuard::new(self);
     guard.get_ordered_workspace_ids()
   }
 
-  pub fn switch_workspace(&mut self, target_workspace_id: PersistentWorkspaceId) {
-    self.switch_workspace_with_additional_windows(target_workspace_id, &[]);
+  fn switch_workspace(&mut self, target_workspace_id: PersistentWorkspaceId) -> CommandOutcome {
+    self.switch_workspace_with_additional_windows(target_workspace_id, &[])
   }
 
-  /// Switches workspace while capturing supplied off-screen members.
-  pub fn switch_workspace_with_additional_windows(
+  fn switch_workspace_with_additional_windows(
     &mut self,
     target_workspace_id: PersistentWorkspaceId,
     additional_windows: &[WindowHandle],
-  ) {
+  ) -> CommandOutcome {
     let mut guard = WorkspaceGuard::new(self);
-    guard.switch_workspace_with_additional_windows(target_workspace_id, additional_windows);
+    guard.switch_workspace_with_additional_windows(target_workspace_id, additional_windows)
+  }
+
+  fn find_parked_windows_of_disconnected_monitors(&mut self) -> Vec<([u16; 32], usize)> {
+    let monitors = self.windows_api.get_all_monitors();
+    self.assign_workspaces_to_monitors(&monitors);
+    let connected_monitor_ids = monitors
+      .get_all()
+      .into_iter()
+      .map(|monitor| monitor.id)
+      .collect::<HashSet<_>>();
+    let disconnected_monitor_ids = self
+      .connected_monitor_ids
+      .difference(&connected_monitor_ids)
+      .copied()
+      .collect::<Vec<_>>();
+    self.connected_monitor_ids = connected_monitor_ids;
+
+    disconnected_monitor_ids
+      .into_iter()
+      .filter_map(|monitor_id| {
+        let parked_window_count = self
+          .workspaces
+          .values()
+          .filter(|workspace| workspace.id.monitor_id == monitor_id && workspace.monitor.id == monitor_id)
+          .map(Workspace::stored_window_count)
+          .sum::<usize>();
+        (parked_window_count > 0).then_some((monitor_id, parked_window_count))
+      })
+      .collect()
+  }
+
+  fn move_parked_windows_to_primary_monitor(&mut self, monitor_id: [u16; 32]) {
+    let monitors = self.windows_api.get_all_monitors();
+    if monitors.get_by_id(&monitor_id).is_some() {
+      info!("Ignored request to move parked windows because their monitor has been reconnected");
+      return;
+    }
+    let Some(primary_monitor) = monitors.get_all().into_iter().find(|monitor| monitor.is_primary) else {
+      warn!("Failed to move parked windows because the primary monitor could not be found");
+      return;
+    };
+    for workspace in self
+      .workspaces
+      .values_mut()
+      .filter(|workspace| workspace.id.monitor_id == monitor_id && workspace.monitor.id == monitor_id)
+    {
+      if workspace.is_empty() {
+        continue;
+      }
+      info!(
+        "Moving [{}] parked window(s) from workspace [{}] to the primary monitor",
+        workspace.stored_window_count(),
+        workspace.id
+      );
+      let own_monitor = workspace.monitor.clone();
+      workspace.update_monitor(primary_monitor);
+      workspace.restore_windows(&self.windows_api);
+      workspace.update_monitor(&own_monitor);
+      self.workspace_file.remove_workspace(&self.file_manager, &workspace.id);
+    }
+  }
+
+  fn has_stale_monitors(&self) -> bool {
+    let monitors = self.windows_api.get_all_monitors();
+    self.workspaces.values().any(|workspace| {
+      monitors.get_by_id(&workspace.monitor.id).is_some_and(|monitor| {
+        workspace.monitor_handle != monitor.handle.as_i64() || workspace.monitor.work_area != monitor.work_area
+      })
+    })
+  }
+
+  fn refresh_monitors(&mut self) {
+    let monitors = self.windows_api.get_all_monitors();
+    for workspace in self.workspaces.values_mut() {
+      let Some(monitor) = monitors.get_by_id(&workspace.monitor.id) else {
+        continue;
+      };
+      if workspace.monitor.work_area != monitor.work_area {
+        info!(
+          "Refreshing monitor of workspace [{}] because its work area changed from {} to {}",
+          workspace.id, workspace.monitor.work_area, monitor.work_area
+        );
+        workspace.update_monitor(monitor);
+      }
+    }
+    self.assign_workspaces_to_monitors(&monitors);
+    self.add_workspaces_for_new_monitors();
+    self.remove_dead_windows();
   }
 
-  /// Returns the active workspace containing a window's monitor.
-  pub fn active_workspace_for_window(&self, handle: WindowHandle) -> Option<PersistentWorkspaceId> {
+  fn add_workspaces_for_new_monitors(&mut self) -> Vec<PersistentWorkspaceId> {
+    let monitors = self.windows_api.get_all_monitors();
+    let mut added_workspace_ids = Vec::new();
+    for monitor in monitors.get_all() {
+      if self.workspaces.keys().any(|id| id.monitor_id == monitor.id) {
+        continue;
+      }
+      for layer in 1..=self.get_additional_workspace_count(monitor) + 1 {
+        let id = PersistentWorkspaceId::new(monitor.id, layer as usize, monitor.is_primary);
+        let mut workspace = Workspace::new_inactive(id, monitor, self.window_margin);
+        workspace.set_preserve_relative_window_position(self.preserve_relative_window_position);
+        info!(
+          "Adding workspace [{}] for newly connected monitor [{}]",
+          id,
+          monitor.id_to_string()
+        );
+        self.workspaces.insert(id, workspace);
+        added_workspace_ids.push(id);
+      }
+      self.ensure_single_active_workspace(monitor.id);
+    }
+
+    added_workspace_ids
+  }
+
+  fn active_workspace_for_window(&self, handle: WindowHandle) -> Option<PersistentWorkspaceId> {
     if self.workspaces.is_empty() {
       return None;
     }
@@ -128,11 +584,30 @@ impl<T: WindowsApi + Clone> WorkspaceManager<T> {
     self
       .workspaces
       .iter()
-      .find_map(|(id, workspace)| (workspace.is_active() && id.monitor_id == monitor_id).then_some(*id))
+      .find_map(|(id, workspace)| (workspace.is_active() && workspace.monitor.id == monitor_id).then_some(*id))
+  }
+
+  fn stored_window_counts(&self) -> Vec<(PersistentWorkspaceId, usize)> {
+    let mut counts = self
+      .workspaces
+      .iter()
+      .map(|(id, workspace)| (*id, workspace.stored_window_count()))
+      .collect::<Vec<_>>();
+    counts.sort();
+    counts
+  }
+
+  fn stored_windows(&self) -> Vec<(PersistentWorkspaceId, Window)> {
+    let mut windows = self
+      .workspaces
+      .iter()
+      .flat_map(|(id, workspace)| workspace.stored_windows().iter().map(move |window| (*id, window.clone())))
+      .collect::<Vec<_>>();
+    windows.sort_by_key(|(id, _)| *id);
+    windows
   }
 
-  /// Returns all active workspace IDs.
-  pub fn active_workspace_ids(&self) -> Vec<PersistentWorkspaceId> {
+  fn active_workspace_ids(&self) -> Vec<PersistentWorkspaceId> {
     let mut ids = self
       .workspaces
       .iter()
@@ -142,25 +617,127 @@ impl<T: WindowsApi + Clone> WorkspaceManager<T> {
     ids
   }
 
-  /// Returns a workspace's current monitor.
-  pub fn monitor_for_workspace(&self, id: PersistentWorkspaceId) -> Option<Monitor> {
+  fn monitor_for_workspace(&self, id: PersistentWorkspaceId) -> Option<Monitor> {
     self.workspaces.get(&id).map(|workspace| workspace.monitor.clone())
   }
 
-  /// Returns whether a workspace is active.
-  pub fn is_workspace_active(&self, id: PersistentWorkspaceId) -> bool {
+  fn is_workspace_active(&self, id: PersistentWorkspaceId) -> bool {
     self.workspaces.get(&id).is_some_and(Workspace::is_active)
   }
 
-  pub fn move_window_to_workspace(&mut self, target_workspace_id: PersistentWorkspaceId) {
+  fn workspace_last_used_at(&self, id: PersistentWorkspaceId) -> Option<SystemTime> {
+    self.workspaces.get(&id).and_then(Workspace::last_used_at)
+  }
+
+  fn move_window_to_workspace(&mut self, target_workspace_id: PersistentWorkspaceId) -> CommandOutcome {
+    let mut guard = WorkspaceGuard::new(self);
+    guard.move_window_to_workspace(target_workspace_id)
+  }
+
+  fn bring_window_here(&mut self, handle: WindowHandle) -> CommandOutcome {
     let mut guard = WorkspaceGuard::new(self);
-    guard.move_window_to_workspace(target_workspace_id);
+    guard.bring_window_here(handle)
+  }
+
+  fn place_windows(&mut self, placements: &[(WindowHandle, PersistentWorkspaceId, Rect)]) -> usize {
+    self.run_transaction(|guard| {
+      let placed_count = placements
+        .iter()
+        .filter(|(handle, workspace_id, rect)| guard.place_window(*handle, *workspace_id, *rect))
+        .count();
+      guard.manager.write_stored_windows_to_file();
+
+      placed_count
+    })
   }
 
-  pub fn restore_all_managed_windows(&mut self) {
+  fn restore_all_managed_windows(&mut self) {
     let mut guard = WorkspaceGuard::new(self);
     guard.restore_all_managed_windows();
   }
+
+  fn set_window_sticky(&mut self, handle: WindowHandle, is_sticky: bool) {
+    if is_sticky {
+      self.sticky_windows.insert(handle);
+    } else {
+      self.sticky_windows.remove(&handle);
+    }
+  }
+
+  fn remove_dead_windows(&mut self) -> Vec<Window> {
+    let mut dead_windows = Vec::new();
+    for workspace in self.workspaces.values_mut() {
+      for window in workspace.remove_dead_windows(&self.windows_api) {
+        info!(
+          "Removed {} \"{}\" from workspace [{}] because it no longer exists",
+          window.handle,
+          window.title_trunc(),
+          workspace.id
+        );
+        dead_windows.push(window);
+      }
+    }
+    let windows_api = &self.windows_api;
+    let dead_handles = self
+      .workspace_file
+      .remove_dead_windows(&self.file_manager, |handle| !windows_api.is_window_alive(handle));
+    if !dead_handles.is_empty() {
+      info!(
+        "Removed [{}] window(s) that no longer exist from the workspaces file: {:?}",
+        dead_handles.len(),
+        dead_handles
+      );
+    }
+
+    dead_windows
+  }
+
+  fn create_workspace(&mut self, monitor_id: [u16; 32]) -> Result<PersistentWorkspaceId, String> {
+    let last_workspace = self
+      .get_last_workspace_on_monitor(monitor_id)
+      .ok_or_else(|| "the monitor has no workspaces".to_string())?;
+    if last_workspace.id.workspace >= MAXIMUM_WORKSPACE_COUNT {
+      return Err(format!(
+        "the monitor of [{}] already has the maximum of [{}] workspaces",
+        last_workspace.id, MAXIMUM_WORKSPACE_COUNT
+      ));
+    }
+    let id = PersistentWorkspaceId::new(
+      monitor_id,
+      last_workspace.id.workspace + 1,
+      last_workspace.id.is_on_primary_monitor(),
+    );
+    let mut workspace = Workspace::new_inactive(id, &last_workspace.monitor, self.window_margin);
+    workspace.update_handle(MonitorHandle::from(last_workspace.monitor_handle));
+    workspace.set_preserve_relative_window_position(self.preserve_relative_window_position);
+    self.workspaces.insert(id, workspace);
+    self.set_additional_workspace_count(id, id.workspace as i32 - 1);
+    info!("Created workspace [{}]", id);
+
+    Ok(id)
+  }
+
+  fn delete_workspace(&mut self, monitor_id: [u16; 32]) -> Result<PersistentWorkspaceId, String> {
+    let last_workspace = self
+      .get_last_workspace_on_monitor(monitor_id)
+      .ok_or_else(|| "the monitor has no workspaces".to_string())?;
+    let id = last_workspace.id;
+    if self.workspaces.keys().filter(|id| id.monitor_id == monitor_id).count() < 2 {
+      return Err(format!("workspace [{id}] is the only workspace on its monitor"));
+    }
+    if last_workspace.is_active() {
+      return Err(format!("workspace [{id}] is active, switch to another workspace first"));
+    }
+    if !last_workspace.is_empty() {
+      return Err(format!("workspace [{id}] is not empty"));
+    }
+    self.workspaces.remove(&id);
+    self.workspace_file.remove_workspace(&self.file_manager, &id);
+    self.set_additional_workspace_count(id, id.workspace as i32 - 2);
+    info!("Deleted workspace [{}]", id);
+
+    Ok(id)
+  }
 }
 
 #[cfg(test)]
@@ -168,10 +745,10 @@ pub mod tests {
   use super::*;
   use crate::api::MockWindowsApi;
   use crate::common::{Monitor, MonitorHandle, Point, Rect, Sizing, TransientWorkspaceId, Window, WindowHandle, Workspace};
+  use crate::configuration_provider::WorkspaceSelector;
   use crate::files::FileType;
   use crate::utils::create_temp_directory;
   use std::fs;
-  use std::path::PathBuf;
   use std::sync::OnceLock;
 
   static PRIMARY_MONITOR: OnceLock<Monitor> = OnceLock::new();
@@ -193,7 +770,7 @@ pub mod tests {
     PRIMARY_ACTIVE_WORKSPACE.get_or_init(|| TransientWorkspaceId::new(primary_monitor().id, primary_monitor().handle, 1))
   }
 
-  fn primary_inactive_ws_id() -> &'static TransientWorkspaceId {
+  pub fn primary_inactive_ws_id() -> &'static TransientWorkspaceId {
     PRIMARY_INACTIVE_WORKSPACE.get_or_init(|| TransientWorkspaceId::new(primary_monitor().id, primary_monitor().handle, 2))
   }
 
@@ -216,6 +793,13 @@ pub mod tests {
         windows_api: MockWindowsApi::new(),
         window_margin: 10,
         additional_workspace_count: 0,
+        monitor_workspace_counts: Vec::new(),
+        keep_always_on_top_windows_visible: true,
+        preserve_relative_window_position: false,
+        sticky_windows: HashSet::new(),
+        workspace_fallbacks: Vec::new(),
+        monitor_ordering: MonitorOrdering::default(),
+        connected_monitor_ids: HashSet::new(),
         file_manager: FileManager::new(
           create_temp_directory()
             .path()
@@ -225,6 +809,8 @@ pub mod tests {
           FileType::Data,
         ),
         workspace_file: WorkspacesFile::new(),
+        corrupt_workspaces_file_backup: None,
+        switch_latency: WorkspaceSwitchLatency::default(),
       }
     }
 
@@ -293,8 +879,17 @@ pub mod tests {
         windows_api: mock_api,
         window_margin,
         additional_workspace_count: 1,
+        monitor_workspace_counts: Vec::new(),
+        keep_always_on_top_windows_visible: true,
+        preserve_relative_window_position: false,
+        sticky_windows: HashSet::new(),
+        workspace_fallbacks: Vec::new(),
+        monitor_ordering: MonitorOrdering::default(),
+        connected_monitor_ids: HashSet::from([primary_monitor.id, secondary_monitor.id]),
         file_manager: FileManager::new(path.to_string_lossy().as_ref(), FileType::Data),
         workspace_file: WorkspacesFile::new(),
+        corrupt_workspaces_file_backup: None,
+        switch_latency: WorkspaceSwitchLatency::default(),
       }
     }
 
@@ -310,6 +905,13 @@ pub mod tests {
         windows_api: MockWindowsApi::new(),
         window_margin,
         additional_workspace_count: 1,
+        monitor_workspace_counts: Vec::new(),
+        keep_always_on_top_windows_visible: true,
+        preserve_relative_window_position: false,
+        sticky_windows: HashSet::new(),
+        workspace_fallbacks: Vec::new(),
+        monitor_ordering: MonitorOrdering::default(),
+        connected_monitor_ids: HashSet::new(),
         file_manager: FileManager::new(
           create_temp_directory()
             .path()
@@ -319,6 +921,8 @@ pub mod tests {
           FileType::Data,
         ),
         workspace_file: WorkspacesFile::new(),
+        corrupt_workspaces_file_backup: None,
+        switch_latency: WorkspaceSwitchLatency::default(),
       }
     }
 
@@ -362,6 +966,134 @@ pub mod tests {
     assert!(workspace_manager.workspace_file.workspaces.is_empty());
   }
 
+  #[test]
+  fn restore_hidden_windows_stores_windows_of_inactive_workspaces_again() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(WORKSPACES_FILE_NAME);
+    let mut workspace_manager = WorkspaceManager::new_test(true, path.clone());
+    let window = Window::new_test(2, Rect::new(0, 0, 100, 100));
+    MockWindowsApi::add_or_update_window(window.handle, window.title.clone(), window.rect.into(), false, true, false);
+    let workspace_file = r#"
+      [[workspaces."DISPLAY1|2|false"]]
+      hwnd = 2
+      rect = { left = 10, top = 20, right = 310, bottom = 220 }
+      is_minimised = false
+      "#;
+    fs::write(&path, workspace_file).expect("Failed to write config file");
+
+    workspace_manager.restore_hidden_windows_from_file();
+
+    let workspace_id = PersistentWorkspaceId::from(*primary_inactive_ws_id());
+    let workspace = workspace_manager
+      .workspaces
+      .get(&workspace_id)
+      .expect("Inactive workspace not found");
+    assert!(workspace_manager.windows_api.is_window_hidden(&window.handle));
+    assert_eq!(workspace.get_windows().len(), 1);
+    assert_eq!(workspace.get_windows()[0].rect, Rect::new(10, 20, 310, 220));
+    assert_eq!(
+      workspace_manager.workspace_file.workspaces[&workspace_id],
+      vec![StoredWindow::new(&workspace.get_windows()[0], false)]
+    );
+  }
+
+  #[test]
+  fn restore_hidden_windows_remembers_last_focused_window_of_inactive_workspaces() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(WORKSPACES_FILE_NAME);
+    let mut workspace_manager = WorkspaceManager::new_test(true, path.clone());
+    let small_window = Window::new_test(2, Rect::new(0, 0, 50, 50));
+    let large_window = Window::new_test(3, Rect::new(0, 0, 500, 500));
+    for window in [&small_window, &large_window] {
+      MockWindowsApi::add_or_update_window(window.handle, window.title.clone(), window.rect.into(), false, true, false);
+    }
+    let workspace_file = r#"
+      [[workspaces."DISPLAY1|2|false"]]
+      hwnd = 2
+      rect = { left = 0, top = 0, right = 50, bottom = 50 }
+      is_minimised = false
+      is_last_focused = true
+
+      [[workspaces."DISPLAY1|2|false"]]
+      hwnd = 3
+      rect = { left = 0, top = 0, right = 500, bottom = 500 }
+      is_minimised = false
+      "#;
+    fs::write(&path, workspace_file).expect("Failed to write config file");
+
+    workspace_manager.restore_hidden_windows_from_file();
+
+    let workspace_id = PersistentWorkspaceId::from(*primary_inactive_ws_id());
+    let workspace = workspace_manager
+      .workspaces
+      .get(&workspace_id)
+      .expect("Inactive workspace not found");
+    assert_eq!(
+      workspace.get_last_focused_window().map(|window| window.handle),
+      Some(small_window.handle)
+    );
+    assert!(workspace_manager.workspace_file.workspaces[&workspace_id][0].is_last_focused);
+  }
+
+  #[test]
+  fn restore_hidden_windows_stores_owned_windows_with_their_owner_again() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(WORKSPACES_FILE_NAME);
+    let mut workspace_manager = WorkspaceManager::new_test(true, path.clone());
+    let owner = Window::new_test(2, Rect::new(0, 0, 100, 100));
+    let owned_window = Window::new_test(3, Rect::new(10, 10, 50, 50));
+    let orphaned_window = Window::new_test(4, Rect::new(20, 20, 60, 60));
+    for window in [&owner, &owned_window, &orphaned_window] {
+      MockWindowsApi::add_or_update_window(window.handle, window.title.clone(), window.rect.into(), false, true, false);
+    }
+    let workspace_file = r#"
+      [[workspaces."DISPLAY1|2|false"]]
+      hwnd = 3
+      rect = { left = 10, top = 10, right = 50, bottom = 50 }
+      owner = { hwnd = 2 }
+      [[workspaces."DISPLAY1|2|false"]]
+      hwnd = 2
+      rect = { left = 0, top = 0, right = 100, bottom = 100 }
+      [[workspaces."DISPLAY1|2|false"]]
+      hwnd = 4
+      rect = { left = 20, top = 20, right = 60, bottom = 60 }
+      owner = { hwnd = 5 }
+      "#;
+    fs::write(&path, workspace_file).expect("Failed to write config file");
+
+    workspace_manager.restore_hidden_windows_from_file();
+
+    assert!(workspace_manager.windows_api.is_window_hidden(&owner.handle));
+    assert!(workspace_manager.windows_api.is_window_hidden(&owned_window.handle));
+    assert!(!workspace_manager.windows_api.is_window_hidden(&orphaned_window.handle));
+    let workspace_id = PersistentWorkspaceId::from(*primary_inactive_ws_id());
+    workspace_manager
+      .workspaces
+      .get_mut(&workspace_id)
+      .expect("Inactive workspace not found")
+      .restore_windows(&workspace_manager.windows_api);
+    assert!(!workspace_manager.windows_api.is_window_hidden(&owner.handle));
+    assert!(!workspace_manager.windows_api.is_window_hidden(&owned_window.handle));
+  }
+
+  #[test]
+  fn restore_hidden_windows_replaces_corrupt_workspaces_file_and_keeps_backup() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(WORKSPACES_FILE_NAME);
+    let mut workspace_manager = WorkspaceManager::new_test(false, path.clone());
+    fs::write(&path, "[[workspaces.\"P_DISPLAY|1|true\"]]\nhwnd = ").expect("Failed to write workspaces file");
+
+    workspace_manager.restore_hidden_windows_from_file();
+
+    assert!(workspace_manager.workspace_file.workspaces.is_empty());
+    assert_eq!(workspace_manager.windows_api.get_all_visible_windows().len(), 1);
+    let backup_path = workspace_manager
+      .take_corrupt_workspaces_file_backup()
+      .expect("Expected a backup of the corrupt workspaces file");
+    assert!(backup_path.exists());
+    assert!(workspace_manager.take_corrupt_workspaces_file_backup().is_none());
+  }
+
   #[test]
   fn restore_hidden_windows_does_nothing_if_no_matching_windows() {
     let directory = create_temp_directory();
@@ -446,6 +1178,54 @@ pub mod tests {
     assert_eq!(ordered_workspaces[3], bottom_workspace_2.id);
   }
 
+  #[test]
+  fn get_ordered_workspace_ids_orders_offset_and_mixed_monitors_in_rows() {
+    let top_left_monitor = Monitor::new_test(1, Rect::new(0, 0, 1920, 1080));
+    let top_right_monitor = Monitor::new_test(2, Rect::new(1920, 100, 3840, 1180));
+    let bottom_monitor = Monitor::new_test(3, Rect::new(400, 1080, 1600, 1800));
+    let top_left_workspace =
+      Workspace::new_test(PersistentWorkspaceId::new(top_left_monitor.id, 1, true), &top_left_monitor);
+    let top_right_workspace =
+      Workspace::new_test(PersistentWorkspaceId::new(top_right_monitor.id, 1, false), &top_right_monitor);
+    let bottom_workspace = Workspace::new_test(PersistentWorkspaceId::new(bottom_monitor.id, 1, false), &bottom_monitor);
+    let mut workspace_manager =
+      WorkspaceManager::from_workspaces(&[&bottom_workspace, &top_right_workspace, &top_left_workspace], 0);
+
+    let ordered_workspaces = workspace_manager.get_ordered_permanent_workspace_ids();
+
+    assert_eq!(
+      ordered_workspaces,
+      vec![top_left_workspace.id, top_right_workspace.id, bottom_workspace.id]
+    );
+  }
+
+  #[test]
+  fn get_ordered_workspace_ids_uses_configured_monitor_ordering() {
+    let top_monitor = Monitor::new_test(1, Rect::new(500, 0, 2420, 1080));
+    let bottom_monitor = Monitor::new_test(2, Rect::new(0, 1080, 1920, 2160));
+    let right_monitor = Monitor::new_test(3, Rect::new(2420, 0, 3500, 1920));
+    let top_workspace = Workspace::new_test(PersistentWorkspaceId::new(top_monitor.id, 1, true), &top_monitor);
+    let bottom_workspace = Workspace::new_test(PersistentWorkspaceId::new(bottom_monitor.id, 1, false), &bottom_monitor);
+    let right_workspace = Workspace::new_test(PersistentWorkspaceId::new(right_monitor.id, 1, false), &right_monitor);
+    let mut workspace_manager = WorkspaceManager::from_workspaces(&[&top_workspace, &bottom_workspace, &right_workspace], 0);
+
+    workspace_manager.monitor_ordering = MonitorOrdering::Rows;
+    assert_eq!(
+      workspace_manager.get_ordered_permanent_workspace_ids(),
+      vec![top_workspace.id, right_workspace.id, bottom_workspace.id]
+    );
+    workspace_manager.monitor_ordering = MonitorOrdering::Columns;
+    assert_eq!(
+      workspace_manager.get_ordered_permanent_workspace_ids(),
+      vec![top_workspace.id, bottom_workspace.id, right_workspace.id]
+    );
+    workspace_manager.monitor_ordering = MonitorOrdering::Centres;
+    assert_eq!(
+      workspace_manager.get_ordered_permanent_workspace_ids(),
+      vec![bottom_workspace.id, top_workspace.id, right_workspace.id]
+    );
+  }
+
   #[test]
   fn switch_workspace_when_target_workspace_has_no_windows() {
     // Given the current workspace has one window and target workspace is not active
@@ -490,18 +1270,302 @@ pub mod tests {
   }
 
   #[test]
-  fn switch_workspace_sets_largest_target_workspace_window_as_foreground_window() {
-    // Given the current workspace has one window and the target workspace, which has two windows, is not active
-    let small_window = Window::new_test(2, Rect::new(0, 0, 50, 50));
-    let large_window = Window::new_test(3, Rect::new(0, 0, 500, 500));
-    MockWindowsApi::add_or_update_window(
-      small_window.handle,
-      small_window.title.clone(),
-      small_window.rect.into(),
-      false,
-      false,
-      true,
-    );
+  fn switch_workspace_keeps_always_on_top_window_visible() {
+    // Given the current workspace has one window that is always on top
+    let directory = create_temp_directory();
+    let path = directory.path().join(WORKSPACES_FILE_NAME);
+    let mut workspace_manager = WorkspaceManager::new_test(true, path);
+    MockWindowsApi::mark_window_always_on_top(WindowHandle::new(1));
+
+    // When the user switches to another workspace on the same monitor
+    workspace_manager.switch_workspace(PersistentWorkspaceId::from(*primary_inactive_ws_id()));
+
+    // Then the window is neither stored nor hidden
+    let original_workspace = workspace_manager
+      .workspaces
+      .get(&(*primary_active_ws_id()).into())
+      .expect("Original workspace not found");
+    assert!(original_workspace.get_windows().is_empty());
+    assert!(!workspace_manager.windows_api.is_window_hidden(&WindowHandle::new(1)));
+  }
+
+  #[test]
+  fn switch_workspace_hides_always_on_top_window_if_keeping_it_visible_is_disabled() {
+    // Given the current workspace has one window that is always on top but such windows are not kept visible
+    let directory = create_temp_directory();
+    let path = directory.path().join(WORKSPACES_FILE_NAME);
+    let mut workspace_manager = WorkspaceManager::new_test(true, path);
+    workspace_manager.keep_always_on_top_windows_visible = false;
+    MockWindowsApi::mark_window_always_on_top(WindowHandle::new(1));
+
+    // When the user switches to another workspace on the same monitor
+    workspace_manager.switch_workspace(PersistentWorkspaceId::from(*primary_inactive_ws_id()));
+
+    // Then the window is stored and hidden like any other window
+    let original_workspace = workspace_manager
+      .workspaces
+      .get(&(*primary_active_ws_id()).into())
+      .expect("Original workspace not found");
+    assert_eq!(original_workspace.get_windows().len(), 1);
+    assert!(workspace_manager.windows_api.is_window_hidden(&WindowHandle::new(1)));
+  }
+
+  #[test]
+  fn switch_workspace_resolves_workspace_of_disconnected_monitor_through_fallback() {
+    // Given the secondary monitor is disconnected and its workspaces fall back to the primary monitor
+    let directory = create_temp_directory();
+    let path = directory.path().join(WORKSPACES_FILE_NAME);
+    let mut workspace_manager = WorkspaceManager::new_test(true, path);
+    workspace_manager.workspace_fallbacks = vec![WorkspaceFallback {
+      selector: WorkspaceSelector {
+        monitor: secondary_monitor().id_to_string(),
+        workspace: None,
+      },
+      fallback: "primary".to_string(),
+    }];
+    MockWindowsApi::remove_monitor(secondary_monitor().handle);
+    let target_workspace_id = PersistentWorkspaceId::from(*secondary_inactive_ws_id());
+
+    // When the user switches to a workspace of the disconnected monitor
+    workspace_manager.switch_workspace(target_workspace_id);
+
+    // Then the workspace is shown on the primary monitor and is the only active workspace there
+    let target_workspace = workspace_manager
+      .workspaces
+      .get(&target_workspace_id)
+      .expect("Workspace not found");
+    assert!(target_workspace.is_active());
+    assert_eq!(target_workspace.monitor.id, primary_monitor().id);
+    assert!(!workspace_manager.is_workspace_active((*primary_active_ws_id()).into()));
+    assert!(!workspace_manager.is_workspace_active((*secondary_active_ws_id()).into()));
+
+    // And the window on the primary monitor has been stored in its previously active workspace
+    let primary_workspace = workspace_manager
+      .workspaces
+      .get(&(*primary_active_ws_id()).into())
+      .expect("Workspace not found");
+    assert_eq!(primary_workspace.get_windows().len(), 1);
+  }
+
+  #[test]
+  fn reconnecting_monitor_moves_fallback_workspace_back_and_reactivates_fallback_monitor_workspace() {
+    // Given a workspace of the disconnected secondary monitor is active on the primary monitor
+    let directory = create_temp_directory();
+    let path = directory.path().join(WORKSPACES_FILE_NAME);
+    let mut workspace_manager = WorkspaceManager::new_test(true, path);
+    workspace_manager.workspace_fallbacks = vec![WorkspaceFallback {
+      selector: WorkspaceSelector {
+        monitor: secondary_monitor().id_to_string(),
+        workspace: Some(2),
+      },
+      fallback: "primary".to_string(),
+    }];
+    MockWindowsApi::remove_monitor(secondary_monitor().handle);
+    let target_workspace_id = PersistentWorkspaceId::from(*secondary_inactive_ws_id());
+    workspace_manager.switch_workspace(target_workspace_id);
+
+    // When the secondary monitor is reconnected
+    let secondary_monitor = secondary_monitor();
+    MockWindowsApi::add_monitor_with_full_details(
+      secondary_monitor.id,
+      secondary_monitor.handle,
+      secondary_monitor.monitor_area,
+      secondary_monitor.work_area,
+      false,
+    );
+    workspace_manager.get_ordered_permanent_workspace_ids();
+
+    // Then the workspace is moved back to its own monitor and each monitor has exactly one active workspace again
+    let target_workspace = workspace_manager
+      .workspaces
+      .get(&target_workspace_id)
+      .expect("Workspace not found");
+    assert_eq!(target_workspace.monitor.id, secondary_monitor.id);
+    assert!(!target_workspace.is_active());
+    assert!(workspace_manager.is_workspace_active((*secondary_active_ws_id()).into()));
+    assert!(workspace_manager.is_workspace_active((*primary_active_ws_id()).into()));
+    assert!(
+      workspace_manager
+        .windows_api
+        .get_all_visible_windows()
+        .iter()
+        .any(|window| window.handle == WindowHandle::new(1)),
+      "The window of the reactivated workspace should have been restored"
+    );
+  }
+
+  #[test]
+  fn reconnecting_monitor_stores_and_hides_windows_of_fallback_workspace_before_deactivating_it() {
+    // Given a workspace of the disconnected secondary monitor is active on the primary monitor and has a window there
+    let directory = create_temp_directory();
+    let path = directory.path().join(WORKSPACES_FILE_NAME);
+    let mut workspace_manager = WorkspaceManager::new_test(true, path);
+    workspace_manager.workspace_fallbacks = vec![WorkspaceFallback {
+      selector: WorkspaceSelector {
+        monitor: secondary_monitor().id_to_string(),
+        workspace: Some(2),
+      },
+      fallback: "primary".to_string(),
+    }];
+    MockWindowsApi::remove_monitor(secondary_monitor().handle);
+    let target_workspace_id = PersistentWorkspaceId::from(*secondary_inactive_ws_id());
+    workspace_manager.switch_workspace(target_workspace_id);
+    let window_handle = WindowHandle::new(2);
+    MockWindowsApi::add_or_update_window(
+      window_handle,
+      "Fallback Window".to_string(),
+      Sizing::new(60, 60, 50, 50),
+      false,
+      false,
+      false,
+    );
+    MockWindowsApi::place_window(window_handle, primary_monitor().handle);
+
+    // When the secondary monitor is reconnected
+    let secondary_monitor = secondary_monitor();
+    MockWindowsApi::add_monitor_with_full_details(
+      secondary_monitor.id,
+      secondary_monitor.handle,
+      secondary_monitor.monitor_area,
+      secondary_monitor.work_area,
+      false,
+    );
+    workspace_manager.get_ordered_permanent_workspace_ids();
+
+    // Then the window is stored in the deactivated workspace and hidden instead of remaining on the primary monitor
+    let target_workspace = workspace_manager
+      .workspaces
+      .get(&target_workspace_id)
+      .expect("Workspace not found");
+    assert!(!target_workspace.is_active());
+    assert!(
+      target_workspace
+        .get_windows()
+        .iter()
+        .any(|window| window.handle == window_handle)
+    );
+    assert!(workspace_manager.windows_api.is_window_hidden(&window_handle));
+    let primary_workspace = workspace_manager
+      .workspaces
+      .get(&(*primary_active_ws_id()).into())
+      .expect("Workspace not found");
+    assert!(primary_workspace.get_windows().is_empty());
+  }
+
+  #[test]
+  fn find_parked_windows_of_disconnected_monitors_reports_each_disconnected_monitor_once() {
+    // Given a window is stored in an inactive workspace of the secondary monitor
+    let directory = create_temp_directory();
+    let path = directory.path().join(WORKSPACES_FILE_NAME);
+    let mut workspace_manager = WorkspaceManager::new_test(true, path);
+    let window = Window::new_test(1, Rect::new(50, 50, 100, 100));
+    workspace_manager
+      .workspaces
+      .get_mut(&(*secondary_inactive_ws_id()).into())
+      .expect("Workspace not found")
+      .store_and_hide_windows(vec![window], primary_monitor().handle, &MockWindowsApi);
+    assert!(workspace_manager.find_parked_windows_of_disconnected_monitors().is_empty());
+
+    // When the secondary monitor is disconnected
+    MockWindowsApi::remove_monitor(secondary_monitor().handle);
+
+    // Then the parked window is reported once
+    let parked_windows = workspace_manager.find_parked_windows_of_disconnected_monitors();
+    assert_eq!(parked_windows, vec![(secondary_monitor().id, 1)]);
+    assert!(workspace_manager.find_parked_windows_of_disconnected_monitors().is_empty());
+  }
+
+  #[test]
+  fn add_workspaces_for_new_monitors_adds_active_workspace_for_connected_monitor_only_once() {
+    // Given a monitor is connected after the workspaces were initialised
+    let directory = create_temp_directory();
+    let path = directory.path().join(WORKSPACES_FILE_NAME);
+    let mut workspace_manager = WorkspaceManager::new_test(true, path);
+    let workspace_count = workspace_manager.workspaces.len();
+    MockWindowsApi::add_monitor(MonitorHandle::from(3), Rect::new(3840, 0, 5760, 1080), false);
+
+    // When workspaces are added for new monitors
+    let added_workspace_ids = workspace_manager.add_workspaces_for_new_monitors();
+
+    // Then the new monitor has an active workspace which is ordered last
+    let expected_id = PersistentWorkspaceId::new([3; 32], 1, false);
+    assert_eq!(added_workspace_ids, vec![expected_id]);
+    assert_eq!(workspace_manager.workspaces.len(), workspace_count + 1);
+    assert!(workspace_manager.is_workspace_active(expected_id));
+    assert_eq!(
+      workspace_manager.get_ordered_permanent_workspace_ids().last(),
+      Some(&expected_id)
+    );
+    assert!(workspace_manager.add_workspaces_for_new_monitors().is_empty());
+  }
+
+  #[test]
+  fn refresh_monitors_updates_stale_work_area_of_workspaces() {
+    // Given the work area of the primary monitor changed, e.g. after a display driver reset
+    let directory = create_temp_directory();
+    let path = directory.path().join(WORKSPACES_FILE_NAME);
+    let mut workspace_manager = WorkspaceManager::new_test(true, path);
+    let work_area = Rect::new(0, 0, 1920, 1000);
+    MockWindowsApi::set_monitor_work_area(primary_monitor().handle, work_area);
+    assert!(workspace_manager.has_stale_monitors());
+
+    // When the monitors are refreshed
+    workspace_manager.refresh_monitors();
+
+    // Then every workspace of the primary monitor knows the new work area
+    assert!(!workspace_manager.has_stale_monitors());
+    assert!(
+      workspace_manager
+        .workspaces
+        .values()
+        .filter(|workspace| workspace.monitor.id == primary_monitor().id)
+        .all(|workspace| workspace.monitor.work_area == work_area)
+    );
+  }
+
+  #[test]
+  fn move_parked_windows_to_primary_monitor_restores_windows_on_primary_monitor() {
+    // Given a window is parked in an inactive workspace of the disconnected secondary monitor
+    let directory = create_temp_directory();
+    let path = directory.path().join(WORKSPACES_FILE_NAME);
+    let mut workspace_manager = WorkspaceManager::new_test(true, path);
+    let window = Window::new_test(1, Rect::new(50, 50, 100, 100));
+    let workspace_id = PersistentWorkspaceId::from(*secondary_inactive_ws_id());
+    workspace_manager
+      .workspaces
+      .get_mut(&workspace_id)
+      .expect("Workspace not found")
+      .store_and_hide_windows(vec![window.clone()], primary_monitor().handle, &MockWindowsApi);
+    MockWindowsApi::remove_monitor(secondary_monitor().handle);
+
+    // When the user chooses to move the parked windows to the primary monitor
+    workspace_manager.move_parked_windows_to_primary_monitor(secondary_monitor().id);
+
+    // Then the window is visible on the primary monitor and the workspace no longer stores it
+    assert!(!workspace_manager.windows_api.is_window_hidden(&window.handle));
+    let rect = workspace_manager
+      .windows_api
+      .get_window_rect(window.handle)
+      .expect("Window not found");
+    assert!(primary_monitor().work_area.contains(&rect.center()));
+    let workspace = workspace_manager.workspaces.get(&workspace_id).expect("Workspace not found");
+    assert!(workspace.is_empty());
+    assert_eq!(workspace.monitor.id, secondary_monitor().id);
+  }
+
+  #[test]
+  fn switch_workspace_sets_largest_target_workspace_window_as_foreground_window() {
+    // Given the current workspace has one window and the target workspace, which has two windows, is not active
+    let small_window = Window::new_test(2, Rect::new(0, 0, 50, 50));
+    let large_window = Window::new_test(3, Rect::new(0, 0, 500, 500));
+    MockWindowsApi::add_or_update_window(
+      small_window.handle,
+      small_window.title.clone(),
+      small_window.rect.into(),
+      false,
+      false,
+      true,
+    );
     MockWindowsApi::add_or_update_window(
       large_window.handle,
       large_window.title.clone(),
@@ -541,6 +1605,165 @@ pub mod tests {
     );
   }
 
+  #[test]
+  fn switch_workspace_prefers_last_focused_window_over_largest_window() {
+    // Given the target workspace stores two windows, of which the smaller one had focus when they were stored
+    let small_window = Window::new_test(2, Rect::new(0, 0, 50, 50));
+    let large_window = Window::new_test(3, Rect::new(0, 0, 500, 500));
+    MockWindowsApi::add_or_update_window(
+      small_window.handle,
+      small_window.title.clone(),
+      small_window.rect.into(),
+      false,
+      false,
+      false,
+    );
+    MockWindowsApi::add_or_update_window(
+      large_window.handle,
+      large_window.title.clone(),
+      large_window.rect.into(),
+      false,
+      false,
+      false,
+    );
+    let directory = create_temp_directory();
+    let path = directory.path().join(WORKSPACES_FILE_NAME);
+    let mut workspace_manager = WorkspaceManager::new_test(true, path.clone());
+    let target_workspace_id = primary_inactive_ws_id();
+    MockWindowsApi::set_foreground_window(small_window.handle);
+    if let Some(target_workspace) = workspace_manager.workspaces.get_mut(&(*target_workspace_id).into()) {
+      target_workspace.store_and_hide_windows(vec![small_window, large_window], 1.into(), &workspace_manager.windows_api);
+    }
+    MockWindowsApi::set_foreground_window(WindowHandle::new(1));
+
+    // When the user switches to the target workspace
+    workspace_manager.switch_workspace(PersistentWorkspaceId::from(*target_workspace_id));
+
+    // Then the previously focused window is brought to the foreground instead of the largest window
+    assert_eq!(
+      workspace_manager.windows_api.get_foreground_window().unwrap(),
+      WindowHandle::new(2),
+      "The foreground window should change to the last focused window on the target workspace"
+    );
+    assert_eq!(
+      workspace_manager.windows_api.get_cursor_position(),
+      Point::new(25, 25),
+      "The cursor position should be set to the center of the last focused window"
+    );
+  }
+
+  #[test]
+  fn create_workspace_adds_inactive_workspace_after_last_workspace_on_monitor() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(WORKSPACES_FILE_NAME);
+    let mut workspace_manager = WorkspaceManager::new_test(true, path);
+    let workspace_count = workspace_manager.workspaces.len();
+
+    let id = workspace_manager.create_workspace(primary_monitor().id);
+
+    let expected_id =
+      PersistentWorkspaceId::from(TransientWorkspaceId::new(primary_monitor().id, primary_monitor().handle, 3));
+    assert_eq!(id, Ok(expected_id));
+    assert_eq!(workspace_manager.workspaces.len(), workspace_count + 1);
+    assert!(!workspace_manager.is_workspace_active(expected_id));
+    assert!(workspace_manager.get_ordered_permanent_workspace_ids().contains(&expected_id));
+  }
+
+  #[test]
+  fn create_workspace_records_workspace_count_of_secondary_monitor() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(WORKSPACES_FILE_NAME);
+    let mut workspace_manager = WorkspaceManager::new_test(true, path);
+
+    let id = workspace_manager.create_workspace(secondary_monitor().id);
+
+    assert!(id.is_ok());
+    assert_eq!(workspace_manager.get_additional_workspace_count(secondary_monitor()), 2);
+    assert_eq!(workspace_manager.get_additional_workspace_count(primary_monitor()), 1);
+  }
+
+  #[test]
+  fn initialise_workspaces_creates_recorded_number_of_workspaces_on_secondary_monitor() {
+    for (monitor, is_primary) in [(primary_monitor(), true), (secondary_monitor(), false)] {
+      MockWindowsApi::add_monitor_with_full_details(
+        monitor.id,
+        monitor.handle,
+        monitor.monitor_area,
+        monitor.work_area,
+        is_primary,
+      );
+    }
+    let mut workspace_manager = WorkspaceManager::default();
+    workspace_manager.monitor_workspace_counts = vec![MonitorWorkspaceCount {
+      monitor: secondary_monitor().id_to_string(),
+      additional_workspace_count: 2,
+    }];
+
+    workspace_manager.initialise_workspaces();
+
+    let mut secondary_workspaces = workspace_manager
+      .workspaces
+      .values()
+      .filter(|workspace| workspace.id.monitor_id == secondary_monitor().id)
+      .map(|workspace| (workspace.id.workspace, workspace.is_active()))
+      .collect::<Vec<_>>();
+    secondary_workspaces.sort();
+    assert_eq!(secondary_workspaces, vec![(1, true), (2, false), (3, false)]);
+    assert_eq!(
+      workspace_manager
+        .workspaces
+        .keys()
+        .filter(|id| id.monitor_id == primary_monitor().id)
+        .count(),
+      1
+    );
+  }
+
+  #[test]
+  fn delete_workspace_removes_last_workspace_on_monitor_when_inactive_and_empty() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(WORKSPACES_FILE_NAME);
+    let mut workspace_manager = WorkspaceManager::new_test(true, path);
+    let expected_id = PersistentWorkspaceId::from(*primary_inactive_ws_id());
+
+    let id = workspace_manager.delete_workspace(primary_monitor().id);
+
+    assert_eq!(id, Ok(expected_id));
+    assert!(!workspace_manager.workspaces.contains_key(&expected_id));
+  }
+
+  #[test]
+  fn delete_workspace_does_nothing_when_last_workspace_on_monitor_stores_windows() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(WORKSPACES_FILE_NAME);
+    let mut workspace_manager = WorkspaceManager::new_test(true, path);
+    let target_workspace_id = PersistentWorkspaceId::from(*primary_inactive_ws_id());
+    let window = Window::new_test(2, Rect::new(0, 0, 50, 50));
+    MockWindowsApi::add_or_update_window(window.handle, window.title.clone(), window.rect.into(), false, false, false);
+    if let Some(target_workspace) = workspace_manager.workspaces.get_mut(&target_workspace_id) {
+      target_workspace.store_and_hide_windows(vec![window], 1.into(), &workspace_manager.windows_api);
+    }
+
+    let id = workspace_manager.delete_workspace(primary_monitor().id);
+
+    assert_eq!(id, Err(format!("workspace [{target_workspace_id}] is not empty")));
+    assert!(workspace_manager.workspaces.contains_key(&target_workspace_id));
+  }
+
+  #[test]
+  fn delete_workspace_does_nothing_when_last_workspace_on_monitor_is_active() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(WORKSPACES_FILE_NAME);
+    let mut workspace_manager = WorkspaceManager::new_test(true, path);
+    let target_workspace_id = PersistentWorkspaceId::from(*primary_inactive_ws_id());
+    workspace_manager.switch_workspace(target_workspace_id);
+
+    let id = workspace_manager.delete_workspace(primary_monitor().id);
+
+    assert!(id.is_err());
+    assert!(workspace_manager.workspaces.contains_key(&target_workspace_id));
+  }
+
   #[test]
   fn move_window_to_different_workspace_on_same_monitor() {
     // Given the primary monitor has an active workspace with one, visible foreground window
@@ -694,6 +1917,95 @@ pub mod tests {
     assert!(workspace_manager.windows_api.get_foreground_window().is_none());
   }
 
+  #[test]
+  fn bring_window_here_moves_hidden_window_to_active_workspace_and_focuses_it() {
+    // Given a window was moved to the inactive workspace of the primary monitor
+    MockWindowsApi::place_window(WindowHandle::new(1), primary_monitor().handle);
+    let directory = create_temp_directory();
+    let path = directory.path().join(WORKSPACES_FILE_NAME);
+    let mut workspace_manager = WorkspaceManager::new_test(true, path.clone());
+    let source_workspace_id = PersistentWorkspaceId::from(*primary_inactive_ws_id());
+    workspace_manager.move_window_to_workspace(source_workspace_id);
+    assert!(workspace_manager.windows_api.is_window_hidden(&WindowHandle::new(1)));
+
+    // When the user brings the window to the active workspace
+    let outcome = workspace_manager.bring_window_here(WindowHandle::new(1));
+
+    // Then the window is visible, focused, and no longer stored in the inactive workspace
+    assert_eq!(outcome, CommandOutcome::Success);
+    assert!(!workspace_manager.windows_api.is_window_hidden(&WindowHandle::new(1)));
+    assert_eq!(
+      workspace_manager.windows_api.get_foreground_window(),
+      Some(WindowHandle::new(1))
+    );
+    let source_workspace = workspace_manager
+      .workspaces
+      .get(&source_workspace_id)
+      .expect("Source workspace not found");
+    assert!(source_workspace.get_windows().is_empty());
+
+    // And the active workspaces have not changed
+    let active_workspaces = workspace_manager.active_workspaces();
+    assert!(active_workspaces.contains(primary_active_ws_id()));
+    assert!(!active_workspaces.contains(primary_inactive_ws_id()));
+
+    // But bringing it here again does nothing
+    assert!(matches!(
+      workspace_manager.bring_window_here(WindowHandle::new(1)),
+      CommandOutcome::NoOp(_)
+    ));
+  }
+
+  #[test]
+  fn place_windows_hides_windows_on_inactive_workspaces_and_moves_windows_on_active_workspaces() {
+    // Given a visible window on the primary monitor
+    MockWindowsApi::place_window(WindowHandle::new(1), primary_monitor().handle);
+    let directory = create_temp_directory();
+    let path = directory.path().join(WORKSPACES_FILE_NAME);
+    let mut workspace_manager = WorkspaceManager::new_test(true, path.clone());
+    let inactive_workspace_id = PersistentWorkspaceId::from(*primary_inactive_ws_id());
+    let hidden_rect = Rect::new(100, 100, 500, 400);
+
+    // When the window is placed on the inactive workspace
+    let placed_count = workspace_manager.place_windows(&[(WindowHandle::new(1), inactive_workspace_id, hidden_rect)]);
+
+    // Then it is hidden and stored with its rect, also in the workspaces file
+    assert_eq!(placed_count, 1);
+    assert!(workspace_manager.windows_api.is_window_hidden(&WindowHandle::new(1)));
+    let inactive_workspace = workspace_manager
+      .workspaces
+      .get(&inactive_workspace_id)
+      .expect("Inactive workspace not found");
+    assert_eq!(inactive_workspace.get_windows()[0].rect, hidden_rect);
+    assert!(fs::read_to_string(&path).unwrap().contains("DISPLAY1|2|false"));
+
+    // When it is placed on the active workspace of the secondary monitor, together with a window on a workspace that
+    // does not exist
+    let active_workspace_id = PersistentWorkspaceId::from(*secondary_active_ws_id());
+    let visible_rect = Rect::new(-700, 100, -300, 400);
+    let placed_count = workspace_manager.place_windows(&[
+      (WindowHandle::new(1), active_workspace_id, visible_rect),
+      (WindowHandle::new(2), PersistentWorkspaceId::new_test(1), visible_rect),
+    ]);
+
+    // Then only the existing workspace is used, and the window is visible at its rect and no longer stored
+    assert_eq!(placed_count, 1);
+    assert!(!workspace_manager.windows_api.is_window_hidden(&WindowHandle::new(1)));
+    assert_eq!(
+      workspace_manager.windows_api.get_window_rect(WindowHandle::new(1)),
+      Some(visible_rect)
+    );
+    assert!(
+      workspace_manager
+        .workspaces
+        .get(&inactive_workspace_id)
+        .expect("Inactive workspace not found")
+        .get_windows()
+        .is_empty()
+    );
+    assert!(!fs::read_to_string(&path).unwrap().contains("DISPLAY1|2|false"));
+  }
+
   #[test]
   fn move_window_clamps_size_of_large_window_when_moving_to_another_active_workspace() {
     // Given the primary monitor has an active workspace with two, visible windows, one of which is the foreground
@@ -787,4 +2099,91 @@ pub mod tests {
 
     assert_eq!(workspace_manager.windows_api.get_all_visible_windows().len(), 1);
   }
+
+  #[test]
+  fn run_transaction_saves_workspaces_file_once_after_all_operations() {
+    // Given the primary monitor has an active workspace with two visible windows
+    let w_2 = Window::new_test(2, Rect::new(0, 0, 100, 100));
+    MockWindowsApi::add_or_update_window(w_2.handle, w_2.title.clone(), w_2.rect.into(), false, false, false);
+    MockWindowsApi::place_window(WindowHandle::new(1), primary_monitor().handle);
+    MockWindowsApi::place_window(w_2.handle, primary_monitor().handle);
+    let directory = create_temp_directory();
+    let path = directory.path().join(WORKSPACES_FILE_NAME);
+    let mut workspace_manager = WorkspaceManager::new_test(true, path.clone());
+    let target_workspace_id = PersistentWorkspaceId::from(*primary_inactive_ws_id());
+
+    // When both windows are moved to an inactive workspace in a single transaction
+    workspace_manager.run_transaction(|guard| {
+      guard.move_window_to_workspace(target_workspace_id);
+      MockWindowsApi::set_foreground_window(w_2.handle);
+      guard.move_window_to_workspace(target_workspace_id);
+
+      // Then nothing is written to disk while the transaction is running
+      assert!(!path.exists());
+    });
+
+    // And both windows are persisted once the transaction has completed
+    let target_workspace = workspace_manager
+      .workspaces
+      .get(&target_workspace_id)
+      .expect("Target workspace not found");
+    assert_eq!(target_workspace.get_windows().len(), 2);
+    let file = fs::read_to_string(&path).expect("Failed to read workspaces file");
+    assert!(file.contains("hwnd = 1"));
+    assert!(file.contains("hwnd = 2"));
+  }
+
+  #[test]
+  fn stored_window_counts_reports_windows_per_workspace() {
+    // Given a window is moved to an inactive workspace
+    MockWindowsApi::place_window(WindowHandle::new(1), primary_monitor().handle);
+    let directory = create_temp_directory();
+    let path = directory.path().join(WORKSPACES_FILE_NAME);
+    let mut workspace_manager = WorkspaceManager::new_test(true, path);
+    let target_workspace_id = PersistentWorkspaceId::from(*primary_inactive_ws_id());
+    workspace_manager.move_window_to_workspace(target_workspace_id);
+
+    // When the stored window counts are requested
+    let counts = workspace_manager.stored_window_counts();
+
+    // Then only the target workspace stores a window and every workspace is listed
+    assert_eq!(counts.len(), workspace_manager.workspaces.len());
+    for (id, count) in counts {
+      assert_eq!(count, usize::from(id == target_workspace_id));
+    }
+  }
+
+  #[test]
+  fn remove_dead_windows_forgets_closed_windows_and_removes_them_from_file() {
+    // Given two windows are stored in an inactive workspace
+    let w_2 = Window::new_test(2, Rect::new(0, 0, 100, 100));
+    MockWindowsApi::add_or_update_window(w_2.handle, w_2.title.clone(), w_2.rect.into(), false, false, false);
+    MockWindowsApi::place_window(WindowHandle::new(1), primary_monitor().handle);
+    MockWindowsApi::place_window(w_2.handle, primary_monitor().handle);
+    let directory = create_temp_directory();
+    let path = directory.path().join(WORKSPACES_FILE_NAME);
+    let mut workspace_manager = WorkspaceManager::new_test(true, path.clone());
+    let target_workspace_id = PersistentWorkspaceId::from(*primary_inactive_ws_id());
+    workspace_manager.move_window_to_workspace(target_workspace_id);
+    MockWindowsApi::set_foreground_window(w_2.handle);
+    workspace_manager.move_window_to_workspace(target_workspace_id);
+
+    // When one of them is closed and dead windows are removed
+    workspace_manager.windows_api.do_close_window(w_2.handle);
+    let dead_windows = workspace_manager.remove_dead_windows();
+
+    // Then only the closed window is forgotten, both in memory and on disk
+    assert_eq!(
+      dead_windows.iter().map(|window| window.handle).collect::<Vec<_>>(),
+      vec![w_2.handle]
+    );
+    let target_workspace = workspace_manager
+      .workspaces
+      .get(&target_workspace_id)
+      .expect("Target workspace not found");
+    assert_eq!(target_workspace.get_windows().len(), 1);
+    let file = fs::read_to_string(&path).expect("Failed to read workspaces file");
+    assert!(file.contains("hwnd = 1"));
+    assert!(!file.contains("hwnd = 2"));
+  }
 }
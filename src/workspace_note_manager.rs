@@ -0,0 +1,190 @@
+use crate::api::real_windows_api_for_workspace_notes::WindowsApiForWorkspaceNotes;
+use crate::common::{PersistentWorkspaceId, Rect, WorkspaceSummary};
+use crate::files::{FileManager, FileType, WorkspaceNotesFile};
+
+const WORKSPACE_NOTES_FILE_NAME: &str = "workspace_notes.toml";
+const WORKSPACE_NOTES_FILE_PREFIX: &str = "# This file contains the notes that Randolf shows for your workspaces when you toggle them.\n\
+  # Edit the text of a note and switch workspaces or toggle the note again to see your changes.\n\
+  # Toggling the note of a workspace that has none yet adds an empty note for it to this file.\n\n";
+const EMPTY_NOTE_TEXT: &str = "No note yet - add one to workspace_notes.toml in Randolf's data folder";
+
+/// Shows a user-editable note for the active workspace of the monitor under the cursor, e.g. to label what each
+/// workspace is used for. The notes are stored in the data folder and read again whenever a note is shown, so that
+/// edits made while Randolf is running are picked up.
+pub struct WorkspaceNoteManager {
+  file_manager: FileManager<WorkspaceNotesFile>,
+  is_visible: bool,
+}
+
+impl Default for WorkspaceNoteManager {
+  fn default() -> Self {
+    let mut file_manager = FileManager::new(WORKSPACE_NOTES_FILE_NAME, FileType::Data);
+    file_manager.set_content_prefix(WORKSPACE_NOTES_FILE_PREFIX);
+    Self::new_with(file_manager)
+  }
+}
+
+impl WorkspaceNoteManager {
+  fn new_with(file_manager: FileManager<WorkspaceNotesFile>) -> Self {
+    Self {
+      file_manager,
+      is_visible: false,
+    }
+  }
+
+  pub fn initialise(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+    WindowsApiForWorkspaceNotes::initialise()
+  }
+
+  /// Shows the note of the given workspace in the top-right corner of the work area of its monitor or, if a note is
+  /// already shown, hides it.
+  pub fn toggle(&mut self, workspace: Option<(PersistentWorkspaceId, Rect)>) {
+    self.is_visible = !self.is_visible;
+    if !self.is_visible {
+      WindowsApiForWorkspaceNotes::hide();
+      return;
+    }
+    self.refresh(workspace);
+  }
+
+  /// Replaces the note that is shown with the note of the given workspace, e.g. after switching workspaces. Does nothing
+  /// if notes are hidden.
+  pub fn refresh(&mut self, workspace: Option<(PersistentWorkspaceId, Rect)>) {
+    if !self.is_visible {
+      return;
+    }
+    let Some((id, work_area)) = workspace else {
+      WindowsApiForWorkspaceNotes::hide();
+      return;
+    };
+    WindowsApiForWorkspaceNotes::show(&self.load_note_text(id), work_area);
+  }
+
+  /// Names each workspace after the first line of its note, if the user has written one. Unlike showing a note, this
+  /// does not add empty notes to the file.
+  pub fn name_workspaces(&self, workspaces: &mut [WorkspaceSummary]) {
+    if !self.file_manager.get_path().exists() {
+      return;
+    }
+    let notes = match self.file_manager.load_or_create() {
+      Ok((notes, _)) => notes,
+      Err(err) => {
+        error!("Failed to load workspace notes: {err}");
+        return;
+      }
+    };
+    for workspace in workspaces.iter_mut() {
+      workspace.name = notes
+        .notes
+        .iter()
+        .find(|note| note.monitor == workspace.monitor_id && note.workspace == workspace.workspace)
+        .and_then(|note| note.text.lines().map(str::trim).find(|line| !line.is_empty()))
+        .map(str::to_string);
+    }
+  }
+
+  /// Loads the text of the note of the given workspace, adding an empty note to the file if the workspace has none yet,
+  /// so that the user knows where to write it.
+  fn load_note_text(&self, id: PersistentWorkspaceId) -> String {
+    let mut notes = match self.file_manager.load_or_create() {
+      Ok((notes, _)) => notes,
+      Err(err) => {
+        error!("Failed to load workspace notes: {err}");
+        return EMPTY_NOTE_TEXT.to_string();
+      }
+    };
+    if notes.add_empty(id) {
+      debug!("Adding empty note for workspace {id}");
+      if let Err(err) = self.file_manager.save(&notes) {
+        error!("Failed to save workspace notes: {err}");
+      }
+    }
+    match notes.get(id) {
+      Some(note) if !note.text.trim().is_empty() => note.text.clone(),
+      _ => EMPTY_NOTE_TEXT.to_string(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::files::WorkspaceNote;
+  use crate::utils::create_temp_directory;
+
+  #[test]
+  fn load_note_text_returns_text_of_note_of_workspace() {
+    let directory = create_temp_directory();
+    let file_manager = FileManager::new_test(directory.path().join(WORKSPACE_NOTES_FILE_NAME));
+    let notes = WorkspaceNotesFile {
+      notes: vec![
+        WorkspaceNote {
+          monitor: "P_DISPLAY".to_string(),
+          workspace: 1,
+          text: "Email and chat".to_string(),
+        },
+        WorkspaceNote {
+          monitor: "P_DISPLAY".to_string(),
+          workspace: 2,
+          text: "Deep work".to_string(),
+        },
+      ],
+    };
+    file_manager.save(&notes).expect("Failed to save workspace notes");
+    let manager = WorkspaceNoteManager::new_with(file_manager);
+
+    assert_eq!(manager.load_note_text(PersistentWorkspaceId::new_test(2)), "Deep work");
+  }
+
+  #[test]
+  fn load_note_text_adds_empty_note_for_workspace_without_note() {
+    let directory = create_temp_directory();
+    let path = directory.path().join(WORKSPACE_NOTES_FILE_NAME);
+    let manager = WorkspaceNoteManager::new_with(FileManager::new_test(path.clone()));
+
+    assert_eq!(manager.load_note_text(PersistentWorkspaceId::new_test(3)), EMPTY_NOTE_TEXT);
+    assert_eq!(manager.load_note_text(PersistentWorkspaceId::new_test(3)), EMPTY_NOTE_TEXT);
+
+    let (notes, _) = manager.file_manager.load_or_create().expect("Failed to load workspace notes");
+    assert_eq!(
+      notes.notes,
+      vec![WorkspaceNote {
+        monitor: "P_DISPLAY".to_string(),
+        workspace: 3,
+        text: String::new(),
+      }]
+    );
+  }
+
+  #[test]
+  fn name_workspaces_uses_first_line_of_note_without_adding_empty_notes() {
+    let directory = create_temp_directory();
+    let file_manager = FileManager::new_test(directory.path().join(WORKSPACE_NOTES_FILE_NAME));
+    let notes = WorkspaceNotesFile {
+      notes: vec![WorkspaceNote {
+        monitor: "P_DISPLAY".to_string(),
+        workspace: 1,
+        text: "\nEmail and chat\nKeep Slack here".to_string(),
+      }],
+    };
+    file_manager.save(&notes).expect("Failed to save workspace notes");
+    let manager = WorkspaceNoteManager::new_with(file_manager);
+    let summary = |workspace| WorkspaceSummary {
+      monitor_id: "P_DISPLAY".to_string(),
+      workspace,
+      number: None,
+      name: None,
+      is_active: false,
+      window_count: 0,
+      last_used_at: None,
+    };
+    let mut workspaces = vec![summary(1), summary(2)];
+
+    manager.name_workspaces(&mut workspaces);
+
+    assert_eq!(workspaces[0].name, Some("Email and chat".to_string()));
+    assert_eq!(workspaces[1].name, None);
+    let (notes, _) = manager.file_manager.load_or_create().expect("Failed to load workspace notes");
+    assert_eq!(notes.notes.len(), 1);
+  }
+}
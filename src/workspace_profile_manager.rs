@@ -0,0 +1,108 @@
+use crate::api;
+use crate::common::Command;
+use crate::configuration_provider::{ConfigurationProvider, WorkspaceProfile};
+use crate::files::{FileManager, FileType};
+use crate::localisation::Text;
+use crate::utils::CONFIGURATION_PROVIDER_LOCK;
+use crossbeam_channel::Sender;
+use std::error::Error;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+pub const WORKSPACE_PROFILE_FILE_NAME: &str = "randolf-workspace-profile.toml";
+const WORKSPACE_PROFILE_FILE_PREFIX: &str = "# This file was exported by Randolf and contains the workspace count, layout presets,\n\
+  # workspace fallback monitors, and window rules. Copy it to the data folder of another machine and select\n\
+  # \"Import workspace profile\" in the tray menu to apply it there.\n\n";
+
+/// Exports the parts of the configuration that define how workspaces are set up to a single file in the data folder,
+/// and imports such a file on another machine, asking the user how to map monitors that are not connected there.
+pub struct WorkspaceProfileManager {
+  configuration_provider: Arc<Mutex<ConfigurationProvider>>,
+  file_manager: FileManager<WorkspaceProfile>,
+}
+
+impl WorkspaceProfileManager {
+  pub fn new(configuration_provider: Arc<Mutex<ConfigurationProvider>>) -> Self {
+    let mut file_manager = FileManager::new(WORKSPACE_PROFILE_FILE_NAME, FileType::Data);
+    file_manager.set_content_prefix(WORKSPACE_PROFILE_FILE_PREFIX);
+
+    Self {
+      configuration_provider,
+      file_manager,
+    }
+  }
+
+  /// Writes the workspace profile to the data folder and returns its path.
+  pub fn export_to_data_folder(&self) -> Result<PathBuf, Box<dyn Error>> {
+    let profile = self
+      .configuration_provider
+      .lock()
+      .expect(CONFIGURATION_PROVIDER_LOCK)
+      .export_workspace_profile();
+    self.file_manager.save(&profile)?;
+
+    Ok(self.file_manager.get_path().to_path_buf())
+  }
+
+  /// Imports the workspace profile from the data folder on a separate thread, because mapping monitors that are not
+  /// connected requires asking the user. Offers to restart Randolf afterwards, which is required to apply the changed
+  /// workspace count.
+  pub fn import_from_data_folder(&self, command_sender: Sender<Command>) {
+    let path = self.file_manager.get_path().to_path_buf();
+    if !path.exists() {
+      warn!(
+        "Failed to import workspace profile because [{}] does not exist",
+        path.display()
+      );
+      std::thread::spawn(move || {
+        api::show_message(
+          "Randolf",
+          &Text::NoWorkspaceProfileToImportMessage
+            .localised()
+            .replace("{path}", &path.display().to_string()),
+        )
+      });
+      return;
+    }
+    let mut profile = match self.file_manager.load_or_create() {
+      Ok((profile, _)) => profile,
+      Err(err) => {
+        error!("Failed to import workspace profile from [{}]: {err}", path.display());
+        return;
+      }
+    };
+    let configuration_provider = self.configuration_provider.clone();
+    std::thread::spawn(move || {
+      let connected_monitor_ids = api::get_all_monitors()
+        .get_all()
+        .iter()
+        .map(|monitor| monitor.id_to_string())
+        .collect::<Vec<_>>();
+      profile.resolve_monitor_ids(&connected_monitor_ids, |monitor_id| {
+        ask_for_replacement_monitor(monitor_id, &connected_monitor_ids)
+      });
+      configuration_provider
+        .lock()
+        .expect(CONFIGURATION_PROVIDER_LOCK)
+        .import_workspace_profile(profile);
+      info!("Imported workspace profile from [{}]", path.display());
+      if api::ask_question("Randolf", Text::WorkspaceProfileImportedQuestion.localised()) {
+        command_sender
+          .send(Command::RestartRandolf(false))
+          .expect("Failed to send restart command");
+      }
+    });
+  }
+}
+
+/// Asks the user, one connected monitor at a time, which monitor to use for the settings of a monitor that is not
+/// connected. Returns `None` if the user declines all of them.
+fn ask_for_replacement_monitor(monitor_id: &str, connected_monitor_ids: &[String]) -> Option<String> {
+  connected_monitor_ids.iter().find_map(|connected_monitor_id| {
+    let message = Text::ReplacementMonitorQuestion
+      .localised()
+      .replace("{monitor}", monitor_id)
+      .replace("{replacement}", connected_monitor_id);
+    api::ask_question("Randolf", &message).then(|| connected_monitor_id.clone())
+  })
+}